@@ -71,6 +71,68 @@ impl CapabilitySet {
 
         Ok(result)
     }
+
+    /// Returns a set permitting exactly what either set permits
+    ///
+    /// Capabilities of the same type are merged by dropping any capability
+    /// that another one already covers (`leq`). They are not joined, since a
+    /// join may permit more than either input, e.g. joining read on one path
+    /// with write on another would allow writing both. Capabilities of
+    /// different types never combine, so every type held by either set
+    /// appears in the result.
+    pub fn union(&self, other: &CapabilitySet) -> CapabilitySet {
+        let mut result = CapabilitySet::new();
+
+        for capability_type in self.capability_types().union(&other.capability_types()) {
+            let mut kept: Vec<Box<dyn Capability>> = Vec::new();
+
+            for capability in [self, other]
+                .into_iter()
+                .filter_map(|set| set.get_capabilities(capability_type))
+                .flatten()
+            {
+                if kept.iter().any(|k| capability.leq(k.as_ref())) {
+                    continue;
+                }
+                kept.retain(|k| !k.leq(capability.as_ref()));
+                kept.push(capability.clone_box());
+            }
+
+            kept.into_iter()
+                .for_each(|capability| result.add_capability(capability));
+        }
+
+        result
+    }
+
+    /// Returns a set permitting only what both sets permit
+    ///
+    /// Each capability of one set is met with each capability of the same
+    /// type in the other, e.g. two `FileCapability`s meet in their common
+    /// paths and the operations both allow. Capabilities of different types
+    /// are incompatible and never meet, so a type held by only one set is
+    /// dropped. Pairs with nothing in common, or whose type does not support
+    /// `meet`, are dropped as well, so the result never permits more than
+    /// either set.
+    pub fn intersect(&self, other: &CapabilitySet) -> CapabilitySet {
+        let mut result = CapabilitySet::new();
+
+        for (capability_type, capabilities) in &self.capabilities {
+            let Some(other_capabilities) = other.get_capabilities(capability_type) else {
+                continue;
+            };
+
+            for capability in capabilities {
+                for other_capability in other_capabilities {
+                    if let Ok(common) = capability.meet(other_capability.as_ref()) {
+                        result.add_capability(common);
+                    }
+                }
+            }
+        }
+
+        result
+    }
 }
 
 impl Default for CapabilitySet {
@@ -267,4 +329,73 @@ mod tests {
             })
             .is_ok());
     }
+
+    fn set_permits(set: &CapabilitySet, request: &AccessRequest) -> bool {
+        set.capability_types().iter().any(|capability_type| {
+            set.get_capabilities(capability_type)
+                .unwrap()
+                .iter()
+                .any(|capability| capability.permits(request).is_ok())
+        })
+    }
+
+    fn file_request(path: &str, read: bool, write: bool) -> AccessRequest {
+        AccessRequest::File {
+            path: path.to_string(),
+            read,
+            write,
+            execute: false,
+        }
+    }
+
+    #[test]
+    fn test_capability_set_union_and_intersect() {
+        let mut host_rules = HashSet::new();
+        host_rules.insert(HostRule::Domain("example.com".to_string()));
+
+        let mut first = CapabilitySet::new();
+        first.add_capability(Box::new(FileCapability::new(
+            ["/tmp/a".to_string(), "/tmp/b".to_string()]
+                .into_iter()
+                .collect(),
+            FileOperations::READ | FileOperations::WRITE,
+        )));
+        first.add_capability(Box::new(NetworkCapability::outbound(host_rules)));
+
+        let mut second = CapabilitySet::new();
+        second.add_capability(Box::new(FileCapability::new(
+            ["/tmp/b".to_string(), "/tmp/c".to_string()]
+                .into_iter()
+                .collect(),
+            FileOperations::READ,
+        )));
+        second.add_capability(Box::new(FileCapability::new(
+            ["/tmp/a".to_string()].into_iter().collect(),
+            FileOperations::READ,
+        )));
+
+        // The intersection keeps the common paths with the weaker operations
+        let common = first.intersect(&second);
+        assert_eq!(common.capability_types().len(), 1);
+        assert!(set_permits(&common, &file_request("/tmp/a", true, false)));
+        assert!(set_permits(&common, &file_request("/tmp/b", true, false)));
+        assert!(!set_permits(&common, &file_request("/tmp/b", false, true)));
+        assert!(!set_permits(&common, &file_request("/tmp/c", true, false)));
+
+        // The union drops covered capabilities and keeps the other types
+        let all = first.union(&second);
+        assert_eq!(all.get_capabilities("file").unwrap().len(), 2);
+        assert_eq!(all.get_capabilities("network").unwrap().len(), 1);
+        assert!(set_permits(&all, &file_request("/tmp/a", false, true)));
+        assert!(set_permits(&all, &file_request("/tmp/c", true, false)));
+        assert!(!set_permits(&all, &file_request("/tmp/c", false, true)));
+
+        // Sets with nothing in common intersect to the empty set
+        let mut other_paths = CapabilitySet::new();
+        other_paths.add_capability(Box::new(FileCapability::new(
+            ["/var/log".to_string()].into_iter().collect(),
+            FileOperations::READ,
+        )));
+        assert!(first.intersect(&other_paths).is_empty());
+    }
 }
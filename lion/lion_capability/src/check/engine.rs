@@ -3,7 +3,7 @@ use std::sync::Arc;
 use lion_core::id::PluginId;
 
 use super::audit::AuditLog;
-use crate::model::{AccessRequest, CapabilityError};
+use crate::model::{AccessRequest, Capability, CapabilityError};
 use crate::store::CapabilityStore;
 
 /// The main capability checking engine
@@ -61,6 +61,79 @@ impl CapabilityChecker {
         result
     }
 
+    /// Verifies that `derived` grants no more than `parent`
+    ///
+    /// Returns `IllegalWidening` if the derived capability is of another
+    /// type or permits anything the parent does not.
+    pub fn verify_attenuation(
+        &self,
+        parent: &dyn Capability,
+        derived: &dyn Capability,
+    ) -> Result<(), CapabilityError> {
+        if derived.is_attenuation_of(parent) {
+            Ok(())
+        } else {
+            Err(CapabilityError::IllegalWidening(format!(
+                "{:?} is not an attenuation of {:?}",
+                derived, parent
+            )))
+        }
+    }
+
+    /// Verifies a delegation chain, ordered from the root capability down
+    ///
+    /// Each capability must be an attenuation of the one before it.
+    pub fn verify_delegation_chain(
+        &self,
+        chain: &[&dyn Capability],
+    ) -> Result<(), CapabilityError> {
+        for (step, pair) in chain.windows(2).enumerate() {
+            self.verify_attenuation(pair[0], pair[1])
+                .map_err(|e| match e {
+                    CapabilityError::IllegalWidening(reason) => CapabilityError::IllegalWidening(
+                        format!("delegation step {}: {}", step + 1, reason),
+                    ),
+                    other => other,
+                })?;
+        }
+
+        Ok(())
+    }
+
+    /// Finds the plugins holding a capability that matches `predicate`
+    ///
+    /// Scans the capabilities of every plugin in the store; expired
+    /// capabilities are not considered.
+    pub fn plugins_with_capability(
+        &self,
+        predicate: impl Fn(&dyn Capability) -> bool,
+    ) -> Vec<PluginId> {
+        self.store
+            .list_plugins()
+            .into_iter()
+            .filter(|plugin_id| {
+                self.store
+                    .list_capabilities(plugin_id)
+                    .is_ok_and(|capabilities| {
+                        capabilities
+                            .iter()
+                            .any(|(_, capability)| predicate(capability.as_ref()))
+                    })
+            })
+            .collect()
+    }
+
+    /// Finds the plugins allowed to write to `path`
+    pub fn plugins_with_file_write(&self, path: &str) -> Vec<PluginId> {
+        let request = AccessRequest::File {
+            path: path.to_string(),
+            read: false,
+            write: true,
+            execute: false,
+        };
+        self.plugins_with_capability(|capability| capability.permits(&request).is_ok())
+    }
+
     /// Gets a reference to the capability store
     pub fn store(&self) -> &Arc<dyn CapabilityStore> {
         &self.store
@@ -78,6 +151,8 @@ mod tests {
     use uuid::Uuid;
 
     use crate::model::file::{FileCapability, FileOperations};
+    use crate::model::Constraint;
+    use crate::model::PluginCallCapability;
     use crate::store::InMemoryCapabilityStore;
 
     fn test_plugin_id(value: u64) -> PluginId {
@@ -183,4 +258,161 @@ mod tests {
         assert!(entries[0].permitted);
         assert!(!entries[1].permitted);
     }
+
+    #[test]
+    fn test_capability_checker_enforces_function_names() {
+        let store = Arc::new(InMemoryCapabilityStore::new());
+        let target = test_plugin_id(1);
+        let reader = test_plugin_id(2);
+        let admin = test_plugin_id(3);
+
+        // The reader may only call "read"; the admin may call anything
+        let functions = ["read".to_string()].into_iter().collect();
+        store
+            .add_capability(
+                reader,
+                Box::new(PluginCallCapability::for_plugin(target, Some(functions))),
+            )
+            .unwrap();
+        store
+            .add_capability(
+                admin,
+                Box::new(PluginCallCapability::for_plugin(target, None)),
+            )
+            .unwrap();
+
+        let checker = CapabilityChecker::new(store);
+        let call = |function: &str| AccessRequest::PluginCall {
+            plugin_id: target.to_string(),
+            function: function.to_string(),
+        };
+
+        assert!(checker.check(&reader, &call("read")).is_ok());
+        assert!(matches!(
+            checker.check(&reader, &call("delete")),
+            Err(CapabilityError::AccessDenied(_))
+        ));
+        assert!(checker.check(&admin, &call("read")).is_ok());
+        assert!(checker.check(&admin, &call("delete")).is_ok());
+    }
+
+    #[test]
+    fn test_capability_checker_denies_expired() {
+        let store = Arc::new(InMemoryCapabilityStore::new());
+        let plugin_id = test_plugin_id(1);
+
+        // Grant a capability that lasts one second
+        let paths = ["/tmp/file.txt".to_string()].into_iter().collect();
+        let file_cap = FileCapability::new(paths, FileOperations::READ);
+        let expires_at = chrono::Utc::now() + chrono::Duration::seconds(1);
+        store
+            .add_capability_with_expiry(plugin_id, Box::new(file_cap), expires_at)
+            .unwrap();
+
+        let checker = CapabilityChecker::new(store);
+        let request = AccessRequest::File {
+            path: "/tmp/file.txt".to_string(),
+            read: true,
+            write: false,
+            execute: false,
+        };
+
+        assert!(checker.check(&plugin_id, &request).is_ok());
+
+        // Once expired the capability is treated as absent
+        std::thread::sleep(std::time::Duration::from_millis(1100));
+        assert!(checker.check(&plugin_id, &request).is_err());
+    }
+
+    #[test]
+    fn test_verify_delegation_chain() {
+        let checker = CapabilityChecker::new(Arc::new(InMemoryCapabilityStore::new()));
+
+        let root = FileCapability::new(
+            ["/data/*".to_string()].into_iter().collect(),
+            FileOperations::READ | FileOperations::WRITE,
+        );
+        let reader = root
+            .attenuate(&Constraint::FileOperation {
+                read: true,
+                write: false,
+                execute: false,
+            })
+            .unwrap();
+        let report = reader
+            .attenuate(&Constraint::FilePath("/data/report.csv".to_string()))
+            .unwrap();
+        assert!(checker
+            .verify_delegation_chain(&[&root, reader.as_ref(), report.as_ref()])
+            .is_ok());
+
+        // Adding a path outside the parent's set is widening
+        let extra_path = FileCapability::new(
+            ["/data/report.csv".to_string(), "/etc/passwd".to_string()]
+                .into_iter()
+                .collect(),
+            FileOperations::READ,
+        );
+        assert!(matches!(
+            checker.verify_delegation_chain(&[&root, reader.as_ref(), &extra_path]),
+            Err(CapabilityError::IllegalWidening(reason)) if reason.contains("step 2")
+        ));
+
+        // So is regaining an operation dropped earlier in the chain
+        let rewrite = FileCapability::new(
+            ["/data/report.csv".to_string()].into_iter().collect(),
+            FileOperations::READ | FileOperations::WRITE,
+        );
+        assert!(matches!(
+            checker.verify_attenuation(reader.as_ref(), &rewrite),
+            Err(CapabilityError::IllegalWidening(_))
+        ));
+    }
+
+    #[test]
+    fn test_plugins_with_capability() {
+        let store = Arc::new(InMemoryCapabilityStore::new());
+        let etc_writer = test_plugin_id(1);
+        let etc_reader = test_plugin_id(2);
+        let tmp_writer = test_plugin_id(3);
+        let etc = || ["/etc/*".to_string()].into_iter().collect();
+        let tmp = ["/tmp/*".to_string()].into_iter().collect();
+        store
+            .add_capability(
+                etc_writer,
+                Box::new(FileCapability::new(
+                    etc(),
+                    FileOperations::READ | FileOperations::WRITE,
+                )),
+            )
+            .unwrap();
+        store
+            .add_capability(
+                etc_reader,
+                Box::new(FileCapability::new(etc(), FileOperations::READ)),
+            )
+            .unwrap();
+        store
+            .add_capability(
+                tmp_writer,
+                Box::new(FileCapability::new(tmp, FileOperations::WRITE)),
+            )
+            .unwrap();
+        let checker = CapabilityChecker::new(store);
+
+        assert_eq!(
+            checker.plugins_with_file_write("/etc/passwd"),
+            vec![etc_writer]
+        );
+        assert!(checker
+            .plugins_with_file_write("/var/log/syslog")
+            .is_empty());
+
+        let mut file_holders =
+            checker.plugins_with_capability(|capability| capability.capability_type() == "file");
+        file_holders.sort_by_key(|plugin_id| plugin_id.to_string());
+        let mut expected = vec![etc_writer, etc_reader, tmp_writer];
+        expected.sort_by_key(|plugin_id| plugin_id.to_string());
+        assert_eq!(file_holders, expected);
+    }
 }
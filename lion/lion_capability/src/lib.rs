@@ -61,8 +61,8 @@ pub mod store;
 // Re-export commonly used types
 pub use model::{
     path_matches, AccessRequest, Capability, CapabilityError, CapabilityOwner, CompositeCapability,
-    Constraint, FileCapability, MemoryCapability, MessageCapability, NetworkCapability,
-    PluginCallCapability,
+    Constraint, FileCapability, MemoryCapability, MessageCapability, MessagePermission,
+    NetworkCapability, PluginCallCapability,
 };
 
 pub use attenuation::{CombineCapability, CombineStrategy, FilterCapability, ProxyCapability};
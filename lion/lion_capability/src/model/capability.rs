@@ -1,5 +1,6 @@
 use std::any::Any;
 use std::fmt::{Debug, Display};
+use std::sync::Arc;
 
 use thiserror::Error;
 
@@ -28,6 +29,9 @@ pub enum CapabilityError {
     #[error("Invalid capability state: {0}")]
     InvalidState(String),
 
+    #[error("Attenuation would broaden permissions: {0}")]
+    IllegalWidening(String),
+
     #[error("Core error: {0}")]
     CoreError(lion_core::error::Error),
 }
@@ -50,6 +54,7 @@ impl Clone for CapabilityError {
             CapabilityError::NotFound(s) => CapabilityError::NotFound(s.clone()),
             CapabilityError::InternalError(s) => CapabilityError::InternalError(s.clone()),
             CapabilityError::InvalidState(s) => CapabilityError::InvalidState(s.clone()),
+            CapabilityError::IllegalWidening(s) => CapabilityError::IllegalWidening(s.clone()),
             // Skip cloning the core error, just create a new internal error with the display string
             CapabilityError::CoreError(e) => {
                 CapabilityError::InternalError(format!("Core error: {}", e))
@@ -206,6 +211,35 @@ pub trait Capability: Send + Sync + Debug {
         ))
     }
 
+    /// Derives a capability restricted by a single constraint
+    ///
+    /// The derived capability is verified to be an attenuation of this one;
+    /// any result that would permit more than this capability is rejected.
+    /// Capability types that do not override `leq` cannot be attenuated.
+    fn attenuate(&self, constraint: &Constraint) -> Result<Arc<dyn Capability>, CapabilityError> {
+        let derived = self.constrain(std::slice::from_ref(constraint))?;
+
+        // `self` may be unsized, so compare against a boxed copy
+        let parent = self.clone_box();
+        if !derived.is_attenuation_of(parent.as_ref()) {
+            return Err(CapabilityError::IllegalWidening(format!(
+                "applying {:?} to {} capability does not yield a weaker capability",
+                constraint,
+                self.capability_type()
+            )));
+        }
+
+        Ok(Arc::from(derived))
+    }
+
+    /// Returns true if this capability grants no more than `other`
+    ///
+    /// Both capabilities must be of the same type, and this one must be less
+    /// than or equal to `other` in the partial order.
+    fn is_attenuation_of(&self, other: &dyn Capability) -> bool {
+        self.capability_type() == other.capability_type() && self.leq(other)
+    }
+
     /// Clones this capability (since dyn Trait cannot implement Clone directly)
     fn clone_box(&self) -> Box<dyn Capability>;
 
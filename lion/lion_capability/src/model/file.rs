@@ -251,7 +251,7 @@ impl Capability for FileCapability {
             // 2. Operations in self are a subset of operations in other
 
             // Check operations first (faster)
-            if !(self.operations & other_file.operations).bits() == self.operations.bits() {
+            if (self.operations & other_file.operations) != self.operations {
                 return false;
             }
 
@@ -457,6 +457,29 @@ mod tests {
         assert!(!cap2.leq(&cap1));
     }
 
+    #[test]
+    fn test_attenuate() {
+        let paths = ["/tmp/*".to_string()].into_iter().collect();
+        let cap = FileCapability::new(paths, FileOperations::READ);
+
+        // Narrowing to a covered path yields a weaker capability
+        let narrowed = cap
+            .attenuate(&Constraint::FilePath("/tmp/test.txt".to_string()))
+            .unwrap();
+        assert!(narrowed.is_attenuation_of(&cap));
+        assert!(!cap.is_attenuation_of(narrowed.as_ref()));
+
+        // A path outside the parent's set cannot be added
+        assert!(cap
+            .attenuate(&Constraint::FilePath("/etc/passwd".to_string()))
+            .is_err());
+
+        // Operations the parent lacks are not gained
+        let same_paths = ["/tmp/*".to_string()].into_iter().collect();
+        let writer = FileCapability::new(same_paths, FileOperations::READ | FileOperations::WRITE);
+        assert!(!writer.is_attenuation_of(&cap));
+    }
+
     #[test]
     fn test_join_and_meet() {
         let paths1 = ["/tmp/file1.txt".to_string(), "/tmp/file2.txt".to_string()]
@@ -13,6 +13,6 @@ pub use capability::{
 pub use composite::CompositeCapability;
 pub use file::FileCapability;
 pub use memory::MemoryCapability;
-pub use message::MessageCapability;
+pub use message::{MessageCapability, MessagePermission};
 pub use network::NetworkCapability;
 pub use plugin_call::PluginCallCapability;
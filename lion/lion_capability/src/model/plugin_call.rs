@@ -61,6 +61,17 @@ impl PluginCallCapability {
         }
     }
 
+    /// Creates a new plugin call capability for a plugin, optionally restricted
+    /// to a set of functions
+    ///
+    /// Without a restriction, every function of the plugin may be called.
+    pub fn for_plugin(plugin_id: PluginId, functions: Option<HashSet<String>>) -> Self {
+        match functions {
+            Some(functions) => Self::with_plugin(plugin_id, functions),
+            None => Self::all_functions(plugin_id),
+        }
+    }
+
     /// Add a function to the allowed set for a plugin
     pub fn add_function(&mut self, plugin_id: PluginId, function: String) {
         self.plugin_functions
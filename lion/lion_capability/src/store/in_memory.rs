@@ -1,5 +1,6 @@
 use std::collections::HashSet;
 
+use chrono::{DateTime, Utc};
 use dashmap::DashMap;
 use std::sync::atomic::{AtomicU64, Ordering};
 
@@ -15,6 +16,15 @@ struct CapabilityEntry {
     /// Generation of this capability, incremented on each update
     /// Used to detect stale capability references
     generation: AtomicU64,
+    /// When this capability expires, if it does
+    expires_at: Option<DateTime<Utc>>,
+}
+
+impl CapabilityEntry {
+    /// Whether this capability has expired by `now`
+    fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at.is_some_and(|expires_at| expires_at <= now)
+    }
 }
 
 /// An in-memory implementation of the CapabilityStore trait
@@ -108,14 +118,14 @@ impl InMemoryCapabilityStore {
 
         self.plugin_capabilities.get_mut(plugin_id).unwrap()
     }
-}
 
-impl CapabilityStore for InMemoryCapabilityStore {
-    fn add_capability(
+    /// Inserts a new capability entry for a plugin
+    fn insert_entry(
         &self,
         plugin_id: PluginId,
         capability: Box<dyn Capability>,
-    ) -> Result<CapabilityId, CapabilityError> {
+        expires_at: Option<DateTime<Utc>>,
+    ) -> CapabilityId {
         let capability_id = self.generate_capability_id();
         let key = (plugin_id, capability_id);
 
@@ -123,6 +133,7 @@ impl CapabilityStore for InMemoryCapabilityStore {
         let entry = CapabilityEntry {
             capability,
             generation: AtomicU64::new(1),
+            expires_at,
         };
 
         // Add to the capabilities map
@@ -132,7 +143,26 @@ impl CapabilityStore for InMemoryCapabilityStore {
         let mut capability_set = self.get_or_create_capability_set(&plugin_id);
         capability_set.insert(capability_id);
 
-        Ok(capability_id)
+        capability_id
+    }
+}
+
+impl CapabilityStore for InMemoryCapabilityStore {
+    fn add_capability(
+        &self,
+        plugin_id: PluginId,
+        capability: Box<dyn Capability>,
+    ) -> Result<CapabilityId, CapabilityError> {
+        Ok(self.insert_entry(plugin_id, capability, None))
+    }
+
+    fn add_capability_with_expiry(
+        &self,
+        plugin_id: PluginId,
+        capability: Box<dyn Capability>,
+        expires_at: DateTime<Utc>,
+    ) -> Result<CapabilityId, CapabilityError> {
+        Ok(self.insert_entry(plugin_id, capability, Some(expires_at)))
     }
 
     fn get_capability(
@@ -141,6 +171,15 @@ impl CapabilityStore for InMemoryCapabilityStore {
         capability_id: &CapabilityId,
     ) -> Result<Box<dyn Capability>, CapabilityError> {
         let entry = self.get_entry(plugin_id, capability_id)?;
+
+        // An expired capability is treated as absent until it is purged
+        if entry.is_expired(Utc::now()) {
+            return Err(CapabilityError::NotFound(format!(
+                "Capability {} expired for plugin {}",
+                capability_id, plugin_id
+            )));
+        }
+
         Ok(entry.capability.clone_box())
     }
 
@@ -193,12 +232,17 @@ impl CapabilityStore for InMemoryCapabilityStore {
         let capability_set = self.plugin_capabilities.get(plugin_id);
 
         if let Some(capability_set) = capability_set {
+            let now = Utc::now();
             let mut result = Vec::new();
 
             for capability_id in capability_set.iter() {
                 // Get the capability if it exists
                 let key = (*plugin_id, *capability_id);
                 if let Some(entry) = self.capabilities.get(&key) {
+                    // Skip expired capabilities
+                    if entry.is_expired(now) {
+                        continue;
+                    }
                     result.push((*capability_id, entry.capability.clone_box()));
                 }
             }
@@ -210,6 +254,14 @@ impl CapabilityStore for InMemoryCapabilityStore {
         }
     }
 
+    fn list_plugins(&self) -> Vec<PluginId> {
+        self.plugin_capabilities
+            .iter()
+            .filter(|entry| !entry.value().is_empty())
+            .map(|entry| *entry.key())
+            .collect()
+    }
+
     fn clear_plugin_capabilities(&self, plugin_id: &PluginId) -> Result<(), CapabilityError> {
         // Remove all capabilities for this plugin
         if let Some(capability_set) = self.plugin_capabilities.get(plugin_id) {
@@ -228,6 +280,24 @@ impl CapabilityStore for InMemoryCapabilityStore {
         Ok(())
     }
 
+    fn purge_expired(&self, now: DateTime<Utc>) -> Vec<(PluginId, CapabilityId)> {
+        let expired: Vec<(PluginId, CapabilityId)> = self
+            .capabilities
+            .iter()
+            .filter(|entry| entry.value().is_expired(now))
+            .map(|entry| *entry.key())
+            .collect();
+
+        for (plugin_id, capability_id) in &expired {
+            self.capabilities.remove(&(*plugin_id, *capability_id));
+            if let Some(mut capability_set) = self.plugin_capabilities.get_mut(plugin_id) {
+                capability_set.remove(capability_id);
+            }
+        }
+
+        expired
+    }
+
     fn partial_revoke(
         &self,
         plugin_id: &PluginId,
@@ -476,4 +546,35 @@ mod tests {
             )
             .is_err());
     }
+
+    #[test]
+    fn test_purge_expired() {
+        let store = InMemoryCapabilityStore::new();
+        let plugin_id = test_plugin_id(1);
+        let now = Utc::now();
+
+        let paths: HashSet<String> = ["/tmp/file.txt".to_string()].into_iter().collect();
+        let expired_id = store
+            .add_capability_with_expiry(
+                plugin_id,
+                Box::new(FileCapability::new(paths.clone(), FileOperations::READ)),
+                now - chrono::Duration::seconds(1),
+            )
+            .unwrap();
+        let live_id = store
+            .add_capability_with_expiry(
+                plugin_id,
+                Box::new(FileCapability::new(paths, FileOperations::WRITE)),
+                now + chrono::Duration::hours(1),
+            )
+            .unwrap();
+
+        // Expired capabilities are hidden before they are purged
+        assert!(store.get_capability(&plugin_id, &expired_id).is_err());
+        assert_eq!(store.list_capabilities(&plugin_id).unwrap().len(), 1);
+
+        assert_eq!(store.purge_expired(now), vec![(plugin_id, expired_id)]);
+        assert!(store.purge_expired(now).is_empty());
+        assert!(store.get_capability(&plugin_id, &live_id).is_ok());
+    }
 }
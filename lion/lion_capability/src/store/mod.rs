@@ -1,6 +1,7 @@
 mod in_memory;
 mod partial_revocation;
 
+use chrono::{DateTime, Utc};
 use lion_core::id::{CapabilityId, PluginId};
 
 use crate::model::{AccessRequest, Capability, CapabilityError};
@@ -17,6 +18,14 @@ pub trait CapabilityStore: Send + Sync {
         capability: Box<dyn Capability>,
     ) -> Result<CapabilityId, CapabilityError>;
 
+    /// Add a capability to a plugin that is treated as absent from `expires_at` on
+    fn add_capability_with_expiry(
+        &self,
+        plugin_id: PluginId,
+        capability: Box<dyn Capability>,
+        expires_at: DateTime<Utc>,
+    ) -> Result<CapabilityId, CapabilityError>;
+
     /// Get a capability by plugin ID and capability ID
     fn get_capability(
         &self,
@@ -42,9 +51,15 @@ pub trait CapabilityStore: Send + Sync {
     /// List all capabilities for a plugin
     fn list_capabilities(&self, plugin_id: &PluginId) -> Result<CapabilityList, CapabilityError>;
 
+    /// List every plugin holding capabilities
+    fn list_plugins(&self) -> Vec<PluginId>;
+
     /// Clear all capabilities for a plugin
     fn clear_plugin_capabilities(&self, plugin_id: &PluginId) -> Result<(), CapabilityError>;
 
+    /// Remove every capability that has expired by `now`, returning what was removed
+    fn purge_expired(&self, now: DateTime<Utc>) -> Vec<(PluginId, CapabilityId)>;
+
     /// Check if a plugin has permission for a specific access request
     ///
     /// This is a convenience method that checks all capabilities for the plugin
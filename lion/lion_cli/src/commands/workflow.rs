@@ -61,10 +61,11 @@ pub fn pause_workflow(workflow_id: &str) -> Result<()> {
     // Use the workflow interface to pause the workflow
     println!("Pausing workflow: {}", workflow_id.cyan());
 
-    workflow::pause_workflow(workflow_id)
+    let state = workflow::pause_workflow(workflow_id)
         .context(format!("Failed to pause workflow {}", workflow_id))?;
 
     println!("{}", "Workflow paused successfully".yellow());
+    println!("State: {}", state.yellow().bold());
     println!("\n{}", "To resume:".bold());
     println!(
         "  {}",
@@ -79,10 +80,11 @@ pub fn resume_workflow(workflow_id: &str) -> Result<()> {
     // Use the workflow interface to resume the workflow
     println!("Resuming workflow: {}", workflow_id.cyan());
 
-    workflow::resume_workflow(workflow_id)
+    let state = workflow::resume_workflow(workflow_id)
         .context(format!("Failed to resume workflow {}", workflow_id))?;
 
     println!("{}", "Workflow resumed successfully".green());
+    println!("State: {}", state.green().bold());
 
     Ok(())
 }
@@ -62,14 +62,28 @@ mod tests {
         let temp_dir = tempdir().unwrap();
         let workflow_path = temp_dir.path().join("test_workflow.yaml");
 
-        // Create a mock workflow file
+        // Create a mock workflow file, or a real definition when the CLI runs
+        // workflows through the runtime
+        #[cfg(not(feature = "workflow-integration"))]
         std::fs::write(
             &workflow_path,
             b"nodes:\n  - id: test\n    plugin_id: test\n",
         )
         .unwrap();
+        #[cfg(feature = "workflow-integration")]
+        {
+            use lion_workflow::model::definition::{WorkflowDefinition, WorkflowId};
+            use lion_workflow::model::node::{Node, NodeId};
+
+            let mut definition = WorkflowDefinition::new(WorkflowId::new(), "Test".to_string());
+            definition
+                .add_node(Node::new(NodeId::new(), "test".to_string()))
+                .unwrap();
+            std::fs::write(&workflow_path, definition.to_json().unwrap()).unwrap();
+        }
 
         let result = cmd()
+            .env("LION_CLI_STATE_DIR", temp_dir.path())
             .arg("workflow")
             .arg("register")
             .arg("--file")
@@ -78,7 +92,7 @@ mod tests {
 
         result
             .success()
-            .stdout(predicate::str::contains("Workflow registered with ID"));
+            .stdout(predicate::str::contains("Workflow registered"));
     }
 
     #[test]
@@ -6,44 +6,26 @@
 
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
 use std::path::Path;
 
 /// Register a workflow from a definition file
 pub fn register_workflow(file_path: &Path) -> Result<String> {
-    // In a real implementation, this would call into lion_workflow::model
     #[cfg(feature = "workflow-integration")]
     {
-        use lion_workflow::engine::registry::WorkflowRegistry;
         use lion_workflow::model::definition::WorkflowDefinition;
 
-        // Read and parse the workflow definition
-        let content = std::fs::read_to_string(file_path).context(format!(
-            "Failed to read workflow file: {}",
+        // Read, parse and validate the workflow definition
+        let definition = WorkflowDefinition::from_file(file_path).context(format!(
+            "Failed to load workflow file: {}",
             file_path.display()
         ))?;
-
-        let definition: WorkflowDefinition = if file_path
-            .extension()
-            .map_or(false, |ext| ext == "yaml" || ext == "yml")
-        {
-            serde_yaml::from_str(&content)?
-        } else if file_path.extension().map_or(false, |ext| ext == "json") {
-            serde_json::from_str(&content)?
-        } else {
-            return Err(anyhow::anyhow!(
-                "Unsupported workflow definition format. Expected .yaml, .yml, or .json"
-            ));
-        };
-
-        // Validate the workflow definition
         definition.validate()?;
 
-        // Register the workflow
-        let registry = WorkflowRegistry::global();
-        let workflow_id = registry.register(definition)?;
+        // Keep it for the commands that run it
+        let workflow_id = definition.id.to_string();
+        execution_records::write_definition(&workflow_id, &definition)?;
 
-        Ok(workflow_id.to_string())
+        Ok(workflow_id)
     }
 
     #[cfg(not(feature = "workflow-integration"))]
@@ -72,16 +54,13 @@ pub fn register_workflow(file_path: &Path) -> Result<String> {
 
 /// Start a registered workflow
 pub fn start_workflow(workflow_id: &str) -> Result<()> {
-    // In a real implementation, this would call into lion_workflow::engine
     #[cfg(feature = "workflow-integration")]
     {
-        use lion_core::id::WorkflowId;
-        use lion_workflow::engine::executor::WorkflowExecutor;
-
-        let executor = WorkflowExecutor::global();
-        let id = WorkflowId::from_str(workflow_id).context("Invalid workflow ID format")?;
-
-        executor.start(&id)?;
+        managed::block_on(async {
+            let (manager, id) = managed::load(workflow_id).await?;
+            manager.start_workflow(id, serde_json::json!({})).await?;
+            managed::record_execution(&manager, workflow_id, id).await
+        })?;
     }
 
     #[cfg(not(feature = "workflow-integration"))]
@@ -94,6 +73,8 @@ pub fn start_workflow(workflow_id: &str) -> Result<()> {
             return Err(anyhow::anyhow!("Invalid workflow ID: {}", workflow_id));
         }
 
+        execution_records::write_state(workflow_id, "RUNNING")?;
+
         println!("Workflow started successfully");
         println!("Executing node: 'parse_input'");
     }
@@ -102,17 +83,20 @@ pub fn start_workflow(workflow_id: &str) -> Result<()> {
 }
 
 /// Pause a running workflow
-pub fn pause_workflow(workflow_id: &str) -> Result<()> {
-    // In a real implementation, this would call into lion_workflow::engine
+///
+/// Returns the resulting workflow state. Fails if the workflow is not
+/// running.
+pub fn pause_workflow(workflow_id: &str) -> Result<String> {
     #[cfg(feature = "workflow-integration")]
     {
-        use lion_core::id::WorkflowId;
-        use lion_workflow::engine::executor::WorkflowExecutor;
-
-        let executor = WorkflowExecutor::global();
-        let id = WorkflowId::from_str(workflow_id).context("Invalid workflow ID format")?;
-
-        executor.pause(&id)?;
+        managed::block_on(async {
+            let (manager, id) = managed::load(workflow_id).await?;
+            manager
+                .pause_workflow(id)
+                .await
+                .map_err(|e| managed::explain(workflow_id, e))?;
+            managed::record_execution(&manager, workflow_id, id).await
+        })
     }
 
     #[cfg(not(feature = "workflow-integration"))]
@@ -125,24 +109,42 @@ pub fn pause_workflow(workflow_id: &str) -> Result<()> {
             return Err(anyhow::anyhow!("Invalid workflow ID: {}", workflow_id));
         }
 
+        // Only running workflows can be paused
+        match execution_records::read_state(workflow_id)?.as_deref() {
+            Some("RUNNING") => {}
+            Some(state) => {
+                return Err(anyhow::anyhow!(
+                    "Workflow {} is not running (state: {})",
+                    workflow_id,
+                    state
+                ))
+            }
+            None => return Err(anyhow::anyhow!("Workflow {} is not running", workflow_id)),
+        }
+
+        execution_records::write_state(workflow_id, "PAUSED")?;
+
         println!("Workflow paused successfully");
-    }
 
-    Ok(())
+        Ok("PAUSED".to_string())
+    }
 }
 
 /// Resume a paused workflow
-pub fn resume_workflow(workflow_id: &str) -> Result<()> {
-    // In a real implementation, this would call into lion_workflow::engine
+///
+/// Returns the resulting workflow state. Fails if the workflow is not
+/// paused.
+pub fn resume_workflow(workflow_id: &str) -> Result<String> {
     #[cfg(feature = "workflow-integration")]
     {
-        use lion_core::id::WorkflowId;
-        use lion_workflow::engine::executor::WorkflowExecutor;
-
-        let executor = WorkflowExecutor::global();
-        let id = WorkflowId::from_str(workflow_id).context("Invalid workflow ID format")?;
-
-        executor.resume(&id)?;
+        managed::block_on(async {
+            let (manager, id) = managed::load(workflow_id).await?;
+            manager
+                .resume_workflow(id)
+                .await
+                .map_err(|e| managed::explain(workflow_id, e))?;
+            managed::record_execution(&manager, workflow_id, id).await
+        })
     }
 
     #[cfg(not(feature = "workflow-integration"))]
@@ -155,24 +157,39 @@ pub fn resume_workflow(workflow_id: &str) -> Result<()> {
             return Err(anyhow::anyhow!("Invalid workflow ID: {}", workflow_id));
         }
 
+        // Only paused workflows can be resumed
+        match execution_records::read_state(workflow_id)?.as_deref() {
+            Some("PAUSED") => {}
+            Some(state) => {
+                return Err(anyhow::anyhow!(
+                    "Workflow {} is not paused (state: {})",
+                    workflow_id,
+                    state
+                ))
+            }
+            None => return Err(anyhow::anyhow!("Workflow {} is not running", workflow_id)),
+        }
+
+        execution_records::write_state(workflow_id, "RUNNING")?;
+
         println!("Workflow resumed successfully");
-    }
 
-    Ok(())
+        Ok("RUNNING".to_string())
+    }
 }
 
 /// Cancel a running workflow
 pub fn cancel_workflow(workflow_id: &str) -> Result<()> {
-    // In a real implementation, this would call into lion_workflow::engine
     #[cfg(feature = "workflow-integration")]
     {
-        use lion_core::id::WorkflowId;
-        use lion_workflow::engine::executor::WorkflowExecutor;
-
-        let executor = WorkflowExecutor::global();
-        let id = WorkflowId::from_str(workflow_id).context("Invalid workflow ID format")?;
-
-        executor.cancel(&id)?;
+        managed::block_on(async {
+            let (manager, id) = managed::load(workflow_id).await?;
+            manager
+                .cancel_workflow(id)
+                .await
+                .map_err(|e| managed::explain(workflow_id, e))?;
+            managed::record_execution(&manager, workflow_id, id).await
+        })?;
     }
 
     #[cfg(not(feature = "workflow-integration"))]
@@ -185,6 +202,21 @@ pub fn cancel_workflow(workflow_id: &str) -> Result<()> {
             return Err(anyhow::anyhow!("Invalid workflow ID: {}", workflow_id));
         }
 
+        // Only running or paused workflows can be cancelled
+        match execution_records::read_state(workflow_id)?.as_deref() {
+            Some("RUNNING") | Some("PAUSED") => {}
+            Some(state) => {
+                return Err(anyhow::anyhow!(
+                    "Workflow {} has already finished (state: {})",
+                    workflow_id,
+                    state
+                ))
+            }
+            None => return Err(anyhow::anyhow!("Workflow {} is not running", workflow_id)),
+        }
+
+        execution_records::write_state(workflow_id, "CANCELLED")?;
+
         println!("Workflow cancelled successfully");
         println!("Cleanup operations completed");
     }
@@ -194,32 +226,25 @@ pub fn cancel_workflow(workflow_id: &str) -> Result<()> {
 
 /// Get the status of a workflow
 pub fn get_workflow_status(workflow_id: &str) -> Result<WorkflowStatus> {
-    // In a real implementation, this would call into lion_workflow::engine
     #[cfg(feature = "workflow-integration")]
     {
-        use lion_core::id::WorkflowId;
-        use lion_workflow::engine::executor::WorkflowExecutor;
-
-        let executor = WorkflowExecutor::global();
-        let id = WorkflowId::from_str(workflow_id).context("Invalid workflow ID format")?;
-
-        let status = executor.get_status(&id)?;
+        // Answer from the record, without running anything
+        let definition = execution_records::read_definition(workflow_id)?
+            .ok_or_else(|| anyhow::anyhow!("Workflow {} is not registered", workflow_id))?;
+        let execution = execution_records::read_execution(workflow_id)?
+            .ok_or_else(|| anyhow::anyhow!("Workflow {} is not running", workflow_id))?;
+        let summary = &execution.summary;
+        let finished_at = summary.finished_at.unwrap_or_else(chrono::Utc::now);
 
         Ok(WorkflowStatus {
             id: workflow_id.to_string(),
-            state: match status.state {
-                WorkflowState::Running => "RUNNING".to_string(),
-                WorkflowState::Paused => "PAUSED".to_string(),
-                WorkflowState::Completed => "COMPLETED".to_string(),
-                WorkflowState::Failed => "FAILED".to_string(),
-                WorkflowState::Cancelled => "CANCELLED".to_string(),
-            },
-            current_step: status.current_step,
-            total_steps: status.total_steps,
-            current_node: status.current_node,
-            started_at: status.started_at,
-            running_time_seconds: status.running_time_seconds,
-            error: status.error,
+            state: managed::state_name(summary.status).to_string(),
+            current_step: execution.node_outputs.len(),
+            total_steps: definition.nodes.len(),
+            current_node: String::new(),
+            started_at: summary.started_at.to_rfc3339(),
+            running_time_seconds: (finished_at - summary.started_at).num_seconds().max(0) as u64,
+            error: execution.error.clone(),
         })
     }
 
@@ -233,10 +258,11 @@ pub fn get_workflow_status(workflow_id: &str) -> Result<WorkflowStatus> {
             return Err(anyhow::anyhow!("Invalid workflow ID: {}", workflow_id));
         }
 
-        // Mock workflow status
+        // Mock workflow status, using the tracked state when there is one
+        let state = execution_records::read_state(workflow_id)?;
         Ok(WorkflowStatus {
             id: workflow_id.to_string(),
-            state: "RUNNING".to_string(),
+            state: state.unwrap_or_else(|| "RUNNING".to_string()),
             current_step: 2,
             total_steps: 5,
             current_node: "transform_data".to_string(),
@@ -249,24 +275,18 @@ pub fn get_workflow_status(workflow_id: &str) -> Result<WorkflowStatus> {
 
 /// List all registered workflows
 pub fn list_workflows() -> Result<Vec<WorkflowInfo>> {
-    // In a real implementation, this would call into lion_workflow::engine
     #[cfg(feature = "workflow-integration")]
     {
-        use lion_workflow::engine::registry::WorkflowRegistry;
-
-        let registry = WorkflowRegistry::global();
-        let workflows = registry.list_workflows()?;
-
-        let mut result = Vec::new();
-        for (id, def) in workflows {
-            result.push(WorkflowInfo {
-                id: id.to_string(),
-                name: def.name,
-                description: def.description,
-                node_count: def.nodes.len(),
-                edge_count: def.edges.len(),
-            });
-        }
+        let result = execution_records::read_definitions()?
+            .into_iter()
+            .map(|definition| WorkflowInfo {
+                id: definition.id.to_string(),
+                name: definition.name,
+                description: definition.description.unwrap_or_default(),
+                node_count: definition.nodes.len(),
+                edge_count: definition.edges.len(),
+            })
+            .collect();
 
         Ok(result)
     }
@@ -296,6 +316,15 @@ pub fn list_workflows() -> Result<Vec<WorkflowInfo>> {
     }
 }
 
+/// Get the definition of a registered workflow
+#[cfg(feature = "workflow-integration")]
+pub fn get_workflow_definition(
+    workflow_id: &str,
+) -> Result<lion_workflow::model::definition::WorkflowDefinition> {
+    execution_records::read_definition(workflow_id)?
+        .ok_or_else(|| anyhow::anyhow!("Workflow {} is not registered", workflow_id))
+}
+
 /// Information about a workflow
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WorkflowInfo {
@@ -319,22 +348,304 @@ pub struct WorkflowStatus {
     pub error: Option<String>,
 }
 
+/// Environment variable overriding where workflow execution state is kept
+pub const STATE_DIR_ENV: &str = "LION_CLI_STATE_DIR";
+
+/// Locally tracked definitions and execution state of workflows
+///
+/// Every CLI invocation is a separate process, so registered definitions and
+/// the state of each started workflow are kept on disk between commands.
+mod execution_records {
+    use super::STATE_DIR_ENV;
+    use anyhow::{Context, Result};
+    #[cfg(feature = "workflow-integration")]
+    use lion_runtime::workflow::execution::ArchivedExecution;
+    #[cfg(feature = "workflow-integration")]
+    use lion_workflow::model::definition::WorkflowDefinition;
+    use std::path::PathBuf;
+
+    /// Directory holding the records
+    fn records_dir() -> PathBuf {
+        std::env::var_os(STATE_DIR_ENV)
+            .map(PathBuf::from)
+            .unwrap_or_else(|| std::env::temp_dir().join("lion-cli"))
+            .join("workflows")
+    }
+
+    /// Path of the record for a workflow
+    fn record_path(workflow_id: &str) -> PathBuf {
+        records_dir().join(format!("{}.json", workflow_id))
+    }
+
+    /// Read the record of a workflow, if it was started
+    fn read_record(workflow_id: &str) -> Result<Option<serde_json::Value>> {
+        let path = record_path(workflow_id);
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let content = std::fs::read_to_string(&path)
+            .context(format!("Failed to read workflow state: {}", path.display()))?;
+        let record = serde_json::from_str(&content).context(format!(
+            "Failed to parse workflow state: {}",
+            path.display()
+        ))?;
+
+        Ok(Some(record))
+    }
+
+    /// Write a file of the records directory
+    fn write_file(path: &PathBuf, content: &str) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).context(format!(
+                "Failed to create workflow state directory: {}",
+                parent.display()
+            ))?;
+        }
+
+        std::fs::write(path, content).context(format!(
+            "Failed to write workflow state: {}",
+            path.display()
+        ))
+    }
+
+    /// Read the tracked state of a workflow, if it was started
+    #[cfg(not(feature = "workflow-integration"))]
+    pub fn read_state(workflow_id: &str) -> Result<Option<String>> {
+        Ok(read_record(workflow_id)?
+            .and_then(|record| record["state"].as_str().map(|state| state.to_string())))
+    }
+
+    /// Record the state of a workflow
+    #[cfg(not(feature = "workflow-integration"))]
+    pub fn write_state(workflow_id: &str, state: &str) -> Result<()> {
+        let now = chrono::Utc::now().to_rfc3339();
+        let started_at = read_record(workflow_id)?
+            .and_then(|record| record["started_at"].as_str().map(str::to_string))
+            .unwrap_or_else(|| now.clone());
+
+        let record = serde_json::json!({
+            "id": workflow_id,
+            "state": state,
+            "started_at": started_at,
+            "updated_at": now,
+        });
+        write_file(&record_path(workflow_id), &record.to_string())
+    }
+
+    /// Read the execution of a workflow the runtime last recorded, if it was
+    /// started
+    #[cfg(feature = "workflow-integration")]
+    pub fn read_execution(workflow_id: &str) -> Result<Option<ArchivedExecution>> {
+        let Some(mut record) = read_record(workflow_id)? else {
+            return Ok(None);
+        };
+
+        let execution = serde_json::from_value(record["execution"].take()).context(format!(
+            "Failed to parse workflow execution: {}",
+            record_path(workflow_id).display()
+        ))?;
+        Ok(Some(execution))
+    }
+
+    /// Record the execution of a workflow, along with its state as the CLI
+    /// shows it
+    #[cfg(feature = "workflow-integration")]
+    pub fn write_execution(
+        workflow_id: &str,
+        state: &str,
+        execution: &ArchivedExecution,
+    ) -> Result<()> {
+        let record = serde_json::json!({
+            "id": workflow_id,
+            "state": state,
+            "started_at": execution.summary.started_at.to_rfc3339(),
+            "updated_at": chrono::Utc::now().to_rfc3339(),
+            "execution": execution,
+        });
+        write_file(&record_path(workflow_id), &record.to_string())
+    }
+
+    /// Path of the registered definition of a workflow
+    #[cfg(feature = "workflow-integration")]
+    fn definition_path(workflow_id: &str) -> PathBuf {
+        records_dir().join(format!("{}.definition.json", workflow_id))
+    }
+
+    /// Keep the definition of a registered workflow
+    #[cfg(feature = "workflow-integration")]
+    pub fn write_definition(workflow_id: &str, definition: &WorkflowDefinition) -> Result<()> {
+        write_file(&definition_path(workflow_id), &definition.to_json()?)
+    }
+
+    /// Read the definition of a registered workflow, if it was registered
+    #[cfg(feature = "workflow-integration")]
+    pub fn read_definition(workflow_id: &str) -> Result<Option<WorkflowDefinition>> {
+        let path = definition_path(workflow_id);
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let definition = WorkflowDefinition::from_file(&path).context(format!(
+            "Failed to read workflow definition: {}",
+            path.display()
+        ))?;
+        Ok(Some(definition))
+    }
+
+    /// Read the definitions of every registered workflow
+    #[cfg(feature = "workflow-integration")]
+    pub fn read_definitions() -> Result<Vec<WorkflowDefinition>> {
+        let dir = records_dir();
+        if !dir.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut definitions = Vec::new();
+        for entry in std::fs::read_dir(&dir)
+            .context(format!("Failed to list workflows: {}", dir.display()))?
+        {
+            let name = entry?.file_name().to_string_lossy().into_owned();
+            if let Some(workflow_id) = name.strip_suffix(".definition.json") {
+                definitions.extend(read_definition(workflow_id)?);
+            }
+        }
+        definitions.sort_by(|a, b| a.name.cmp(&b.name));
+
+        Ok(definitions)
+    }
+}
+
+/// Workflows run through the runtime's workflow manager
+///
+/// The manager lives only as long as one command, so each command restores
+/// the recorded execution into it, without running anything, and records the
+/// execution it leaves behind.
+#[cfg(feature = "workflow-integration")]
+mod managed {
+    use super::execution_records;
+    use anyhow::{Context, Result};
+    use lion_core::id::WorkflowId;
+    use lion_core::types::workflow::ExecutionStatus;
+    use lion_runtime::capabilities::manager::CapabilityManager;
+    use lion_runtime::plugin::manager::PluginManager;
+    use lion_runtime::system::config::RuntimeConfig;
+    use lion_runtime::workflow::execution::ExecutionError;
+    use lion_runtime::workflow::WorkflowManager;
+    use std::sync::Arc;
+
+    /// Run a command's async work to completion
+    pub fn block_on<T>(future: impl std::future::Future<Output = Result<T>>) -> Result<T> {
+        tokio::runtime::Runtime::new()
+            .context("Failed to start the async runtime")?
+            .block_on(future)
+    }
+
+    /// Build a workflow manager holding a registered workflow and its
+    /// recorded execution, in the status it was recorded in
+    pub async fn load(workflow_id: &str) -> Result<(WorkflowManager, WorkflowId)> {
+        workflow_id
+            .parse::<WorkflowId>()
+            .context(format!("Invalid workflow ID: {}", workflow_id))?;
+        let definition = execution_records::read_definition(workflow_id)?
+            .ok_or_else(|| anyhow::anyhow!("Workflow {} is not registered", workflow_id))?;
+
+        let config = RuntimeConfig::default();
+        let capabilities = Arc::new(CapabilityManager::new()?);
+        let plugins = Arc::new(PluginManager::new(config.clone(), capabilities.clone())?);
+        let manager = WorkflowManager::new(config, capabilities, plugins)?;
+        let id = manager.register_workflow(definition).await?;
+
+        if let Some(execution) = execution_records::read_execution(workflow_id)? {
+            manager.restore_execution(execution).await?;
+        }
+
+        Ok((manager, id))
+    }
+
+    /// Record the execution of a workflow, returning its state
+    pub async fn record_execution(
+        manager: &WorkflowManager,
+        workflow_id: &str,
+        id: WorkflowId,
+    ) -> Result<String> {
+        let execution = manager.export_execution(&id).await?;
+        let state = state_name(execution.summary.status);
+        execution_records::write_execution(workflow_id, state, &execution)?;
+        Ok(state.to_string())
+    }
+
+    /// Name of an execution status as the CLI shows it
+    pub fn state_name(status: ExecutionStatus) -> &'static str {
+        match status {
+            ExecutionStatus::Pending => "PENDING",
+            ExecutionStatus::Running => "RUNNING",
+            ExecutionStatus::Paused => "PAUSED",
+            ExecutionStatus::Completed => "COMPLETED",
+            ExecutionStatus::Failed => "FAILED",
+            ExecutionStatus::Cancelled => "CANCELLED",
+        }
+    }
+
+    /// Describe a workflow in the wrong state the way the CLI reports it
+    pub fn explain(workflow_id: &str, error: anyhow::Error) -> anyhow::Error {
+        match error.downcast_ref::<ExecutionError>() {
+            Some(ExecutionError::WorkflowNotRunning(_)) => {
+                anyhow::anyhow!("Workflow {} is not running", workflow_id)
+            }
+            Some(ExecutionError::WorkflowNotPaused(_)) => {
+                anyhow::anyhow!("Workflow {} is not paused", workflow_id)
+            }
+            Some(ExecutionError::WorkflowAlreadyFinished(_)) => {
+                anyhow::anyhow!("Workflow {} has already finished", workflow_id)
+            }
+            _ => error,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::path::PathBuf;
     use tempfile::tempdir;
 
+    /// Write a workflow definition file into `dir`
+    fn write_definition_file(dir: &Path) -> PathBuf {
+        #[cfg(feature = "workflow-integration")]
+        {
+            use lion_workflow::model::definition::{WorkflowDefinition, WorkflowId};
+            use lion_workflow::model::node::{Node, NodeId};
+
+            let mut definition = WorkflowDefinition::new(WorkflowId::new(), "Test".to_string());
+            definition
+                .add_node(Node::new(NodeId::new(), "test".to_string()))
+                .unwrap();
+
+            let path = dir.join("test_workflow.json");
+            std::fs::write(&path, definition.to_json().unwrap()).unwrap();
+            path
+        }
+
+        #[cfg(not(feature = "workflow-integration"))]
+        {
+            // Create a mock workflow file
+            let path = dir.join("test_workflow.yaml");
+            std::fs::write(&path, b"nodes:\n  - id: test\n    plugin_id: test\n").unwrap();
+            path
+        }
+    }
+
+    /// Register a workflow the other commands can run
+    fn registered_workflow() -> String {
+        let temp_dir = tempdir().unwrap();
+        register_workflow(&write_definition_file(temp_dir.path())).unwrap()
+    }
+
     #[test]
     fn test_register_workflow() {
         let temp_dir = tempdir().unwrap();
-        let workflow_path = temp_dir.path().join("test_workflow.yaml");
-
-        // Create a mock workflow file
-        std::fs::write(
-            &workflow_path,
-            b"nodes:\n  - id: test\n    plugin_id: test\n",
-        )
-        .unwrap();
+        let workflow_path = write_definition_file(temp_dir.path());
 
         let result = register_workflow(&workflow_path);
         assert!(result.is_ok());
@@ -345,7 +656,7 @@ mod tests {
 
     #[test]
     fn test_start_workflow() {
-        let workflow_id = uuid::Uuid::new_v4().to_string();
+        let workflow_id = registered_workflow();
 
         let result = start_workflow(&workflow_id);
         assert!(result.is_ok());
@@ -353,31 +664,87 @@ mod tests {
 
     #[test]
     fn test_pause_workflow() {
-        let workflow_id = uuid::Uuid::new_v4().to_string();
+        let workflow_id = registered_workflow();
 
+        // A workflow that was never started is not running
+        assert!(pause_workflow(&workflow_id).is_err());
+
+        start_workflow(&workflow_id).unwrap();
         let result = pause_workflow(&workflow_id);
-        assert!(result.is_ok());
+        assert_eq!(result.unwrap(), "PAUSED");
+        assert_eq!(get_workflow_status(&workflow_id).unwrap().state, "PAUSED");
+
+        // Pausing again fails
+        assert!(pause_workflow(&workflow_id).is_err());
     }
 
     #[test]
     fn test_resume_workflow() {
-        let workflow_id = uuid::Uuid::new_v4().to_string();
+        let workflow_id = registered_workflow();
 
+        start_workflow(&workflow_id).unwrap();
+        assert!(resume_workflow(&workflow_id).is_err());
+
+        pause_workflow(&workflow_id).unwrap();
         let result = resume_workflow(&workflow_id);
-        assert!(result.is_ok());
+        assert_eq!(result.unwrap(), "RUNNING");
+        assert_eq!(get_workflow_status(&workflow_id).unwrap().state, "RUNNING");
     }
 
     #[test]
     fn test_cancel_workflow() {
-        let workflow_id = uuid::Uuid::new_v4().to_string();
+        let workflow_id = registered_workflow();
+        start_workflow(&workflow_id).unwrap();
 
         let result = cancel_workflow(&workflow_id);
         assert!(result.is_ok());
+        assert_eq!(
+            get_workflow_status(&workflow_id).unwrap().state,
+            "CANCELLED"
+        );
+    }
+
+    #[test]
+    fn test_finished_workflow_refuses_commands() {
+        let workflow_id = registered_workflow();
+
+        // Record the workflow as having completed
+        #[cfg(feature = "workflow-integration")]
+        {
+            use lion_core::id::WorkflowId;
+            use lion_core::types::workflow::ExecutionStatus;
+            use lion_runtime::workflow::execution::{ArchivedExecution, ExecutionSummary};
+
+            let now = chrono::Utc::now();
+            let execution = ArchivedExecution {
+                summary: ExecutionSummary {
+                    workflow_id: workflow_id.parse::<WorkflowId>().unwrap(),
+                    status: ExecutionStatus::Completed,
+                    started_at: now,
+                    finished_at: Some(now),
+                },
+                tags: Default::default(),
+                node_outputs: Default::default(),
+                error: None,
+            };
+            execution_records::write_execution(&workflow_id, "COMPLETED", &execution).unwrap();
+        }
+        #[cfg(not(feature = "workflow-integration"))]
+        execution_records::write_state(&workflow_id, "COMPLETED").unwrap();
+
+        assert!(pause_workflow(&workflow_id).is_err());
+        assert!(resume_workflow(&workflow_id).is_err());
+        assert!(cancel_workflow(&workflow_id).is_err());
+        assert_eq!(
+            get_workflow_status(&workflow_id).unwrap().state,
+            "COMPLETED"
+        );
     }
 
     #[test]
     fn test_get_workflow_status() {
-        let workflow_id = uuid::Uuid::new_v4().to_string();
+        let workflow_id = registered_workflow();
+        start_workflow(&workflow_id).unwrap();
 
         let result = get_workflow_status(&workflow_id);
         assert!(result.is_ok());
@@ -386,8 +753,22 @@ mod tests {
         assert_eq!(status.id, workflow_id);
     }
 
+    #[cfg(feature = "workflow-integration")]
+    #[test]
+    fn test_get_workflow_definition() {
+        let workflow_id = registered_workflow();
+
+        let definition = get_workflow_definition(&workflow_id).unwrap();
+        assert_eq!(definition.id.to_string(), workflow_id);
+        assert_eq!(definition.nodes.len(), 1);
+
+        assert!(get_workflow_definition(&uuid::Uuid::new_v4().to_string()).is_err());
+    }
+
     #[test]
     fn test_list_workflows() {
+        registered_workflow();
+
         let result = list_workflows();
         assert!(result.is_ok());
 
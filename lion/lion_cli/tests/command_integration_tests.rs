@@ -1,6 +1,5 @@
 use assert_cmd::Command;
 use predicates::prelude::*;
-use std::env;
 use std::fs;
 use std::path::Path;
 use std::process::Command as StdCommand;
@@ -160,6 +159,10 @@ fn test_system_commands() {
 }
 
 #[test]
+#[cfg_attr(
+    feature = "workflow-integration",
+    ignore = "checks the output of the placeholder workflow interface"
+)]
 fn test_workflow_commands() {
     // Test workflow register
     let temp_dir = tempdir().unwrap();
@@ -217,10 +220,92 @@ fn test_workflow_commands() {
         .stdout(predicate::str::contains("Cancelling workflow"));
 }
 
-fn setup_test_script(script_name: &str, content: &str) -> String {
-    let tmp_dir = env::temp_dir();
-    let script_path = tmp_dir.join(script_name);
+#[test]
+fn test_workflow_pause_resume() {
+    let state_dir = tempdir().unwrap();
+    let workflow_id = "123e4567-e89b-12d3-a456-426614174099";
+    let workflow_cmd = |action: &str| {
+        let mut command = cmd();
+        command
+            .env("LION_CLI_STATE_DIR", state_dir.path())
+            .arg("workflow")
+            .arg(action)
+            .arg(workflow_id);
+        command
+    };
+
+    // The runtime only runs registered workflows
+    #[cfg(feature = "workflow-integration")]
+    {
+        use lion_workflow::model::definition::{WorkflowDefinition, WorkflowId};
+        use lion_workflow::model::node::{Node, NodeId};
+
+        let mut definition = WorkflowDefinition::new(
+            WorkflowId::from_uuid(uuid::Uuid::parse_str(workflow_id).unwrap()),
+            "Pausable".to_string(),
+        );
+        definition
+            .add_node(Node::new(NodeId::new(), "step".to_string()))
+            .unwrap();
+        let definition_path = state_dir.path().join("pausable.json");
+        fs::write(&definition_path, definition.to_json().unwrap()).unwrap();
+
+        cmd()
+            .env("LION_CLI_STATE_DIR", state_dir.path())
+            .arg("workflow")
+            .arg("register")
+            .arg("--file")
+            .arg(&definition_path)
+            .assert()
+            .success();
+    }
 
+    // A workflow that has not been started cannot be paused
+    workflow_cmd("pause")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("is not running"));
+
+    workflow_cmd("start").assert().success();
+
+    // Pause the running workflow
+    workflow_cmd("pause")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Workflow paused successfully"))
+        .stdout(predicate::str::contains("PAUSED"));
+
+    workflow_cmd("status")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("PAUSED"));
+
+    // It is no longer running, so pausing again fails
+    workflow_cmd("pause")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("is not running"));
+
+    // Resume it
+    workflow_cmd("resume")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Workflow resumed successfully"))
+        .stdout(predicate::str::contains("RUNNING"));
+
+    workflow_cmd("resume")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("is not paused"));
+}
+
+/// Write an executable script to `scripts/<script_name>` under `dir`, where
+/// the CLI looks for it when run from `dir`
+fn setup_test_script(dir: &Path, script_name: &str, content: &str) {
+    let scripts_dir = dir.join("scripts");
+    fs::create_dir_all(&scripts_dir).expect("Failed to create scripts directory");
+
+    let script_path = scripts_dir.join(script_name);
     fs::write(&script_path, content).expect("Failed to write test script");
 
     // Make the script executable
@@ -231,8 +316,6 @@ fn setup_test_script(script_name: &str, content: &str) -> String {
             .status()
             .expect("Failed to chmod the test script");
     }
-
-    script_path.to_string_lossy().to_string()
 }
 
 #[test]
@@ -242,42 +325,21 @@ fn test_ci_command_executes_script() {
         return;
     }
 
-    // Create a test script that simulates the CI script
+    // Create a test script that simulates the CI script, outside the source tree
     let script_content = r#"#!/bin/sh
 echo "CI script executed successfully"
 exit 0
 "#;
-
-    let script_path = setup_test_script("test_ci.sh", script_content);
-
-    // Create a symbolic link or copy the script to the expected location
-    let scripts_dir = Path::new("scripts");
-    if !scripts_dir.exists() {
-        fs::create_dir_all(scripts_dir).expect("Failed to create scripts directory");
-    }
-
-    let target_path = scripts_dir.join("ci.sh");
-    fs::copy(&script_path, &target_path).expect("Failed to copy test script");
-
-    // Make the script executable
-    #[cfg(unix)]
-    {
-        StdCommand::new("chmod")
-            .args(&["+x", target_path.to_str().unwrap()])
-            .status()
-            .expect("Failed to chmod the target script");
-    }
+    let temp_dir = tempdir().unwrap();
+    setup_test_script(temp_dir.path(), "ci.sh", script_content);
 
     // Run the command and verify it executes the script
     let mut cmd = Command::cargo_bin("lion_cli").unwrap();
-    let assert = cmd.arg("ci").assert();
+    let assert = cmd.current_dir(temp_dir.path()).arg("ci").assert();
 
     assert
         .success()
         .stdout(predicate::str::contains("Executing CI script"));
-
-    // Clean up
-    let _ = fs::remove_file(target_path);
 }
 
 #[test]
@@ -287,40 +349,20 @@ fn test_test_cli_command_executes_script() {
         return;
     }
 
-    // Create a test script that simulates the test-cli script
+    // Create a test script that simulates the test-cli script, outside the
+    // source tree
     let script_content = r#"#!/bin/sh
 echo "Test CLI script executed successfully"
 exit 0
 "#;
-
-    let script_path = setup_test_script("test_test_cli.sh", script_content);
-
-    // Create a symbolic link or copy the script to the expected location
-    let scripts_dir = Path::new("scripts");
-    if !scripts_dir.exists() {
-        fs::create_dir_all(scripts_dir).expect("Failed to create scripts directory");
-    }
-
-    let target_path = scripts_dir.join("test_cli.sh");
-    fs::copy(&script_path, &target_path).expect("Failed to copy test script");
-
-    // Make the script executable
-    #[cfg(unix)]
-    {
-        StdCommand::new("chmod")
-            .args(&["+x", target_path.to_str().unwrap()])
-            .status()
-            .expect("Failed to chmod the target script");
-    }
+    let temp_dir = tempdir().unwrap();
+    setup_test_script(temp_dir.path(), "test_cli.sh", script_content);
 
     // Run the command and verify it executes the script
     let mut cmd = Command::cargo_bin("lion_cli").unwrap();
-    let assert = cmd.arg("test-cli").assert();
+    let assert = cmd.current_dir(temp_dir.path()).arg("test-cli").assert();
 
     assert
         .success()
         .stdout(predicate::str::contains("Running CLI tests"));
-
-    // Clean up
-    let _ = fs::remove_file(target_path);
 }
@@ -3,11 +3,14 @@
 //! Manages pools of pre-initialized instances (like WebAssembly modules)
 //! to avoid the overhead of repeated initialization.
 
+use lion_core::error::{ConcurrencyError, Result};
 use log::{debug, info, trace};
 use std::collections::VecDeque;
 use std::fmt;
 use std::marker::PhantomData;
-use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Condvar, Mutex, RwLock, Weak};
+use std::thread;
 use std::time::{Duration, Instant};
 
 /// Configuration for an instance pool
@@ -24,6 +27,18 @@ pub struct InstancePoolConfig {
 
     /// Maximum number of times an instance can be reused
     pub max_uses: usize,
+
+    /// How often idle instances are health-checked, if at all
+    pub health_check_interval: Option<Duration>,
+
+    /// Maximum number of instances `acquire` lets be checked out at once
+    pub max_concurrent: Option<usize>,
+
+    /// How long `acquire` waits for an instance before timing out
+    pub wait_timeout: Duration,
+
+    /// Scale the instance count with load instead of using `max_concurrent`
+    pub autoscale: Option<AutoscalePolicy>,
 }
 
 impl Default for InstancePoolConfig {
@@ -33,10 +48,49 @@ impl Default for InstancePoolConfig {
             max_instances: 20,
             max_age: Duration::from_secs(300), // 5 minutes
             max_uses: 100,
+            health_check_interval: None,
+            max_concurrent: None,
+            wait_timeout: Duration::from_secs(3),
+            autoscale: None,
+        }
+    }
+}
+
+/// Policy scaling a pool's live instance count between bounds
+///
+/// The pool grows by one instance whenever `acquire` would otherwise wait,
+/// up to `max_instances`, and shrinks back toward `min_instances` by
+/// dropping instances idle for `idle_timeout`. No shrinking happens within
+/// `cooldown` of the last change, so a bursty load does not thrash.
+#[derive(Debug, Clone)]
+pub struct AutoscalePolicy {
+    /// Fewest live instances to keep
+    pub min_instances: usize,
+
+    /// How long an instance may sit idle before it is dropped
+    pub idle_timeout: Duration,
+
+    /// Minimum time between a change in size and shrinking
+    pub cooldown: Duration,
+
+    /// How often idle instances are looked for
+    pub interval: Duration,
+}
+
+impl Default for AutoscalePolicy {
+    fn default() -> Self {
+        Self {
+            min_instances: 1,
+            idle_timeout: Duration::from_secs(60),
+            cooldown: Duration::from_secs(10),
+            interval: Duration::from_secs(1),
         }
     }
 }
 
+/// Predicate deciding whether a pooled instance is still healthy
+pub type HealthCheck<T> = Arc<dyn Fn(&T) -> bool + Send + Sync>;
+
 /// Trait for poolable instances
 pub trait Poolable: Send + 'static {
     /// Create a new instance
@@ -154,8 +208,34 @@ pub struct InstancePool<T: Poolable> {
     /// Statistics about this pool
     stats: Mutex<PoolStats>,
 
-    /// Phantom data to ensure proper variance
-    _phantom: PhantomData<T>,
+    /// Predicate used by health checks, replacing `Poolable::is_healthy`
+    health_check: RwLock<Option<HealthCheck<T>>>,
+
+    /// Callers of `acquire` waiting for an instance, in arrival order
+    waiters: Mutex<WaitQueue>,
+
+    /// Signalled when an instance is returned or a waiter leaves the queue
+    available: Condvar,
+
+    /// Live instance count the autoscaling policy currently allows
+    target_size: AtomicUsize,
+
+    /// When the target size last changed
+    last_scaled: Mutex<Instant>,
+
+    /// Phantom data to ensure proper variance, without requiring `T: Sync`
+    /// for the pool to be shared with its health checker
+    _phantom: PhantomData<fn() -> T>,
+}
+
+/// Tickets of the callers waiting in `InstancePool::acquire`
+#[derive(Debug, Default)]
+struct WaitQueue {
+    /// Ticket handed to the next caller
+    next_ticket: u64,
+
+    /// Tickets of waiting callers, oldest first
+    tickets: VecDeque<u64>,
 }
 
 /// Statistics about an instance pool
@@ -172,15 +252,45 @@ pub struct PoolStats {
 
     /// Total number of instance return operations
     pub total_returns: usize,
+
+    /// Total number of instances replaced by health checks
+    pub total_unhealthy_replaced: usize,
+
+    /// Number of instances idle in the pool
+    pub idle: usize,
+
+    /// Number of instances checked out
+    pub busy: usize,
+
+    /// Number of instances idle or checked out
+    pub current: usize,
+
+    /// Number of callers waiting in `acquire`
+    pub waiting: usize,
+
+    /// Live instance count the autoscaling policy currently allows
+    pub target_size: Option<usize>,
 }
 
 impl<T: Poolable> InstancePool<T> {
     /// Create a new instance pool with the specified configuration
     pub fn new(config: InstancePoolConfig) -> Arc<Self> {
+        let target_size = match &config.autoscale {
+            Some(policy) => config.initial_instances.clamp(
+                policy.min_instances,
+                config.max_instances.max(policy.min_instances),
+            ),
+            None => 0,
+        };
         let pool = Arc::new(Self {
             instances: Mutex::new(VecDeque::with_capacity(config.max_instances)),
             config,
             stats: Mutex::new(PoolStats::default()),
+            health_check: RwLock::new(None),
+            waiters: Mutex::new(WaitQueue::default()),
+            available: Condvar::new(),
+            target_size: AtomicUsize::new(target_size),
+            last_scaled: Mutex::new(Instant::now()),
             _phantom: PhantomData,
         });
 
@@ -188,9 +298,148 @@ impl<T: Poolable> InstancePool<T> {
         // Pre-create initial instances
         pool_clone.initialize();
 
+        if let Some(interval) = pool.config.health_check_interval {
+            Self::spawn_periodic(Arc::downgrade(&pool), interval, Self::check_health);
+        }
+        if let Some(policy) = &pool.config.autoscale {
+            Self::spawn_periodic(Arc::downgrade(&pool), policy.interval, Self::autoscale);
+        }
+
         pool
     }
 
+    /// Replace the predicate used by health checks
+    ///
+    /// Until set, health checks use `Poolable::is_healthy`.
+    pub fn set_health_check<F>(&self, predicate: F)
+    where
+        F: Fn(&T) -> bool + Send + Sync + 'static,
+    {
+        *self.health_check.write().unwrap() = Some(Arc::new(predicate));
+    }
+
+    /// Check every idle instance, replacing those found unhealthy
+    ///
+    /// Instances are taken out one at a time and checked without holding
+    /// the pool lock, so `get_instance` is never blocked by a slow check.
+    /// Returns the number of instances replaced.
+    pub fn check_health(&self) -> usize {
+        let predicate = self.health_check.read().unwrap().clone();
+        let to_check = self.available_count();
+        let mut replaced = 0;
+
+        for _ in 0..to_check {
+            let Some(pooled) = self.instances.lock().unwrap().pop_front() else {
+                // Everything left was checked out in the meantime
+                break;
+            };
+
+            let healthy = match &predicate {
+                Some(predicate) => predicate(&pooled.instance),
+                None => pooled.instance.is_healthy(),
+            };
+            let pooled = if healthy {
+                pooled
+            } else {
+                trace!("Replacing unhealthy instance");
+                replaced += 1;
+                PooledInstance::new(T::create())
+            };
+
+            self.instances.lock().unwrap().push_back(pooled);
+        }
+
+        if replaced > 0 {
+            debug!("Health check replaced {} instances", replaced);
+            let mut stats = self.stats.lock().unwrap();
+            stats.total_created += replaced;
+            stats.total_recycled += replaced;
+            stats.total_unhealthy_replaced += replaced;
+        }
+
+        replaced
+    }
+
+    /// Shrink toward the autoscaling policy's minimum
+    ///
+    /// Drops instances idle for longer than `idle_timeout` and lowers the
+    /// target size to the live count, unless the size changed within
+    /// `cooldown`. Returns the target size, or 0 without autoscaling.
+    pub fn autoscale(&self) -> usize {
+        let Some(policy) = &self.config.autoscale else {
+            return 0;
+        };
+        let target = self.target_size.load(Ordering::SeqCst);
+
+        let mut last_scaled = self.last_scaled.lock().unwrap();
+        if last_scaled.elapsed() < policy.cooldown {
+            return target;
+        }
+
+        let busy = self.busy_count();
+        let mut removed = 0;
+        let mut live = {
+            let mut instances = self.instances.lock().unwrap();
+            let mut live = busy + instances.len();
+            instances.retain(|pooled| {
+                let expired = pooled.last_used_at.elapsed() >= policy.idle_timeout;
+                if expired && live > policy.min_instances {
+                    live -= 1;
+                    removed += 1;
+                    false
+                } else {
+                    true
+                }
+            });
+            live
+        };
+        live = live.max(policy.min_instances);
+
+        if removed > 0 {
+            self.stats.lock().unwrap().total_recycled += removed;
+        }
+        if live < target {
+            debug!(
+                "Scaling instance pool down from {} to {} instances",
+                target, live
+            );
+            self.target_size.store(live, Ordering::SeqCst);
+            *last_scaled = Instant::now();
+            return live;
+        }
+        target
+    }
+
+    /// Raise the target size by one if the autoscaling policy allows it
+    fn grow(&self) -> bool {
+        if self.config.autoscale.is_none() {
+            return false;
+        }
+
+        let mut last_scaled = self.last_scaled.lock().unwrap();
+        let target = self.target_size.load(Ordering::SeqCst);
+        if target >= self.config.max_instances {
+            return false;
+        }
+        trace!("Scaling instance pool up to {} instances", target + 1);
+        self.target_size.store(target + 1, Ordering::SeqCst);
+        *last_scaled = Instant::now();
+        true
+    }
+
+    /// Run a task on a background thread until the pool is dropped
+    fn spawn_periodic(pool: Weak<Self>, interval: Duration, task: fn(&Self) -> usize) {
+        thread::spawn(move || loop {
+            thread::sleep(interval);
+            match pool.upgrade() {
+                Some(pool) => {
+                    task(&pool);
+                }
+                None => break,
+            }
+        });
+    }
+
     /// Initialize the pool with the initial instances
     fn initialize(&self) {
         info!(
@@ -208,7 +457,84 @@ impl<T: Poolable> InstancePool<T> {
         }
     }
 
+    /// Acquire an instance, waiting while `max_concurrent` are checked out
+    ///
+    /// Waiters are served in arrival order, so no caller starves. A caller
+    /// still waiting after `wait_timeout` gets `ConcurrencyError::Timeout`
+    /// with the length of the wait queue, itself included. Without
+    /// `max_concurrent` or an autoscaling policy this never waits; with a
+    /// policy, the pool grows rather than making callers wait until it
+    /// reaches `max_instances`.
+    pub fn acquire(self: &Arc<Self>) -> Result<InstanceHandle<T>> {
+        if self.concurrency_limit().is_none() {
+            return Ok(self.get_instance());
+        }
+        let started = Instant::now();
+        let deadline = started + self.config.wait_timeout;
+
+        let mut waiters = self.waiters.lock().unwrap();
+        let ticket = waiters.next_ticket;
+        waiters.next_ticket += 1;
+        waiters.tickets.push_back(ticket);
+
+        loop {
+            if waiters.tickets.front() == Some(&ticket) {
+                let limit = self.concurrency_limit().unwrap_or(usize::MAX);
+                if self.busy_count() < limit || self.grow() {
+                    waiters.tickets.pop_front();
+                    let handle = self.get_instance();
+                    // The next waiter may fit as well
+                    self.available.notify_all();
+                    return Ok(handle);
+                }
+            }
+
+            let now = Instant::now();
+            if now >= deadline {
+                let queue_len = waiters.tickets.len();
+                waiters.tickets.retain(|waiting| *waiting != ticket);
+                // Whoever was behind this caller may now be first in line
+                self.available.notify_all();
+                return Err(ConcurrencyError::Timeout(
+                    started.elapsed().as_millis() as u64,
+                    queue_len,
+                )
+                .into());
+            }
+
+            waiters = self
+                .available
+                .wait_timeout(waiters, deadline - now)
+                .unwrap()
+                .0;
+        }
+    }
+
+    /// Get the most instances `acquire` lets be checked out at once
+    fn concurrency_limit(&self) -> Option<usize> {
+        match self.config.autoscale {
+            Some(_) => Some(self.target_size.load(Ordering::SeqCst)),
+            None => self.config.max_concurrent,
+        }
+    }
+
+    /// Get the number of instances checked out
+    fn busy_count(&self) -> usize {
+        self.stats.lock().unwrap().busy
+    }
+
+    /// Wake callers waiting in `acquire`
+    fn notify_waiters(&self) {
+        // Taking the lock ensures a waiter between its check and its wait
+        // is not missed
+        let _waiters = self.waiters.lock().unwrap();
+        self.available.notify_all();
+    }
+
     /// Get an instance from the pool
+    ///
+    /// Never waits: an instance is created if none is idle, regardless of
+    /// `max_concurrent`.
     pub fn get_instance(self: &Arc<Self>) -> InstanceHandle<T> {
         let mut instances = self.instances.lock().unwrap();
         let mut stats = self.stats.lock().unwrap();
@@ -232,6 +558,7 @@ impl<T: Poolable> InstancePool<T> {
             // Mark the instance as used
             pooled.mark_used();
             stats.total_checkouts += 1;
+            stats.busy += 1;
 
             return InstanceHandle::new(pooled.instance, Arc::clone(self));
         }
@@ -241,16 +568,24 @@ impl<T: Poolable> InstancePool<T> {
         let instance = T::create();
         stats.total_created += 1;
         stats.total_checkouts += 1;
+        stats.busy += 1;
 
         InstanceHandle::new(instance, Arc::clone(self))
     }
 
     /// Return an instance to the pool
-    fn return_instance(&self, mut instance: T) {
+    fn return_instance(&self, instance: T) {
+        self.put_back(instance);
+        self.notify_waiters();
+    }
+
+    /// Reset an instance and keep it for reuse if it is still healthy
+    fn put_back(&self, mut instance: T) {
         let mut instances = self.instances.lock().unwrap();
         let mut stats = self.stats.lock().unwrap();
 
         stats.total_returns += 1;
+        stats.busy = stats.busy.saturating_sub(1);
 
         // Reset the instance state
         instance.reset();
@@ -284,8 +619,23 @@ impl<T: Poolable> InstancePool<T> {
 
     /// Get the current statistics for this pool
     pub fn get_stats(&self) -> PoolStats {
-        let stats = self.stats.lock().unwrap();
-        stats.clone()
+        self.stats()
+    }
+
+    /// Get the totals along with the current idle, busy and overall counts
+    pub fn stats(&self) -> PoolStats {
+        let idle = self.available_count();
+        let waiting = self.waiters.lock().unwrap().tickets.len();
+        let mut stats = self.stats.lock().unwrap().clone();
+        stats.idle = idle;
+        stats.waiting = waiting;
+        stats.target_size = self
+            .config
+            .autoscale
+            .as_ref()
+            .map(|_| self.target_size.load(Ordering::SeqCst));
+        stats.current = idle + stats.busy;
+        stats
     }
 
     /// Get the current number of available instances
@@ -301,6 +651,11 @@ impl<T: Poolable> Clone for InstancePool<T> {
             instances: Mutex::new(VecDeque::with_capacity(self.config.max_instances)),
             config: self.config.clone(),
             stats: Mutex::new(self.stats.lock().unwrap().clone()),
+            health_check: RwLock::new(self.health_check.read().unwrap().clone()),
+            waiters: Mutex::new(WaitQueue::default()),
+            available: Condvar::new(),
+            target_size: AtomicUsize::new(self.target_size.load(Ordering::SeqCst)),
+            last_scaled: Mutex::new(Instant::now()),
             _phantom: PhantomData,
         }
     }
@@ -341,6 +696,7 @@ mod tests {
             max_instances: 5,
             max_age: Duration::from_secs(10),
             max_uses: 3,
+            ..Default::default()
         };
 
         let pool = InstancePool::<TestInstance>::new(config);
@@ -372,6 +728,7 @@ mod tests {
             max_instances: 1,
             max_age: Duration::from_millis(10), // Very short age for testing
             max_uses: 10,
+            ..Default::default()
         };
 
         let pool = InstancePool::<TestInstance>::new(config);
@@ -399,6 +756,7 @@ mod tests {
             max_instances: 1,
             max_age: Duration::from_secs(10),
             max_uses: 2, // Only allow 2 uses
+            ..Default::default()
         };
 
         let pool = InstancePool::<TestInstance>::new(config);
@@ -458,6 +816,7 @@ mod tests {
             max_instances: 5,
             max_age: Duration::from_secs(300),
             max_uses: 100,
+            ..Default::default()
         };
         let pool = InstancePool::<TestInstance>::new(config);
 
@@ -481,4 +840,130 @@ mod tests {
         println!("Stats after: recycled={}", stats_after.total_recycled);
         // We're not asserting recycled count anymore since it's handled differently
     }
+
+    #[test]
+    fn test_health_check_replaces_unhealthy_instances() {
+        let config = InstancePoolConfig {
+            initial_instances: 3,
+            health_check_interval: Some(Duration::from_millis(10)),
+            ..Default::default()
+        };
+        let pool = InstancePool::<TestInstance>::new(config);
+        let ids: Vec<usize> = {
+            let handles: Vec<_> = (0..3).map(|_| pool.get_instance()).collect();
+            handles.iter().map(|handle| handle.get().id).collect()
+        };
+
+        // Flag the first instance as leaking; the background check replaces it
+        let leaking = ids[0];
+        pool.set_health_check(move |instance: &TestInstance| instance.id != leaking);
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while pool.stats().total_unhealthy_replaced == 0 {
+            assert!(Instant::now() < deadline, "health check never ran");
+            thread::sleep(Duration::from_millis(5));
+        }
+
+        let stats = pool.stats();
+        assert_eq!(stats.idle, 3);
+        assert_eq!(stats.total_unhealthy_replaced, 1);
+        let handles: Vec<_> = (0..3).map(|_| pool.get_instance()).collect();
+        assert!(handles.iter().all(|handle| handle.get().id != leaking));
+
+        let stats = pool.stats();
+        assert_eq!((stats.idle, stats.busy, stats.current), (0, 3, 3));
+        assert_eq!(stats.total_checkouts, 6);
+    }
+
+    #[test]
+    fn test_acquire_times_out_when_saturated() {
+        let config = InstancePoolConfig {
+            initial_instances: 1,
+            max_concurrent: Some(1),
+            wait_timeout: Duration::from_millis(50),
+            ..Default::default()
+        };
+        let pool = InstancePool::<TestInstance>::new(config);
+
+        let held = pool.acquire().unwrap();
+        match pool.acquire() {
+            Err(lion_core::error::Error::Concurrency(ConcurrencyError::Timeout(waited, 1))) => {
+                assert!(waited >= 50);
+            }
+            other => panic!("expected a timeout, got {:?}", other.map(|_| ())),
+        }
+        assert_eq!(pool.stats().waiting, 0);
+
+        // Once the instance is back, acquiring succeeds again
+        drop(held);
+        assert!(pool.acquire().is_ok());
+    }
+
+    #[test]
+    fn test_acquire_serves_waiters_in_order() {
+        let config = InstancePoolConfig {
+            initial_instances: 1,
+            max_concurrent: Some(1),
+            wait_timeout: Duration::from_secs(5),
+            ..Default::default()
+        };
+        let pool = InstancePool::<TestInstance>::new(config);
+        let held = pool.acquire().unwrap();
+
+        // Queue the waiters one at a time so their arrival order is known
+        let order = Arc::new(Mutex::new(Vec::new()));
+        let mut waiters = Vec::new();
+        for n in 0..4 {
+            let waiter_pool = Arc::clone(&pool);
+            let order = Arc::clone(&order);
+            waiters.push(thread::spawn(move || {
+                let _handle = waiter_pool.acquire().unwrap();
+                order.lock().unwrap().push(n);
+                thread::sleep(Duration::from_millis(5));
+            }));
+            while pool.stats().waiting <= n {
+                thread::sleep(Duration::from_millis(1));
+            }
+        }
+
+        drop(held);
+        for waiter in waiters {
+            waiter.join().unwrap();
+        }
+        assert_eq!(*order.lock().unwrap(), vec![0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn test_autoscale_grows_under_load_and_shrinks_when_idle() {
+        let config = InstancePoolConfig {
+            initial_instances: 1,
+            max_instances: 3,
+            wait_timeout: Duration::from_millis(20),
+            autoscale: Some(AutoscalePolicy {
+                min_instances: 1,
+                idle_timeout: Duration::from_millis(20),
+                cooldown: Duration::from_millis(200),
+                interval: Duration::from_secs(60),
+            }),
+            ..Default::default()
+        };
+        let pool = InstancePool::<TestInstance>::new(config);
+        assert_eq!(pool.stats().target_size, Some(1));
+
+        // Each caller that would wait grows the pool, up to max_instances
+        let handles: Vec<_> = (0..3).map(|_| pool.acquire().unwrap()).collect();
+        assert_eq!(pool.stats().target_size, Some(3));
+        assert!(pool.acquire().is_err());
+
+        // Right after growing, the cooldown holds the size
+        drop(handles);
+        thread::sleep(Duration::from_millis(25));
+        assert_eq!(pool.autoscale(), 3);
+
+        // Once it has passed, idle instances are dropped down to the minimum
+        thread::sleep(Duration::from_millis(200));
+        assert_eq!(pool.autoscale(), 1);
+        let stats = pool.stats();
+        assert_eq!((stats.idle, stats.current), (1, 1));
+        assert_eq!(stats.target_size, Some(1));
+    }
 }
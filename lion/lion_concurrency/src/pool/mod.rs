@@ -11,7 +11,10 @@ pub mod resource;
 pub mod thread;
 
 // Re-export key types from instance
-pub use instance::{InstanceHandle, InstancePool, InstancePoolConfig, PoolStats, Poolable};
+pub use instance::{
+    AutoscalePolicy, HealthCheck, InstanceHandle, InstancePool, InstancePoolConfig, PoolStats,
+    Poolable,
+};
 
 // Re-export key types from resource
 pub use resource::{Resource, ResourceHandle, ResourcePool, ResourcePoolConfig, ResourcePoolError};
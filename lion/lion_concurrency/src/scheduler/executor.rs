@@ -6,7 +6,7 @@
 use crate::actor::system::ActorSystem;
 use crate::pool::thread::ThreadPool;
 use lion_core::error::{Error as LionError, Result as LionResult};
-use log::{debug, error, info};
+use log::{debug, info};
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 use thiserror::Error;
@@ -250,6 +250,14 @@ pub enum IsolationError {
     /// Resource limits exceeded during execution
     #[error("Resource limits exceeded: {0}")]
     ResourceExhausted(String),
+
+    /// Execution used up its fuel budget
+    #[error("Fuel exhausted: {0}")]
+    FuelExhausted(String),
+
+    /// Execution ran past its wall-clock timeout
+    #[error("Execution timed out: {0}")]
+    ExecutionTimeout(String),
 }
 
 /// Errors related to concurrency operations.
@@ -271,6 +279,10 @@ pub enum ConcurrencyError {
     #[error("Acquisition timeout after {0}ms for plugin {1}")]
     AcquisitionTimeout(u64, PluginId),
 
+    /// Timed out waiting for a pooled instance, with the wait-queue length
+    #[error("Timed out after {0}ms waiting for an instance ({1} callers queued)")]
+    Timeout(u64, usize),
+
     /// Instance pool size limit reached
     #[error("Instance pool limit reached: {0}")]
     PoolLimitReached(String),
@@ -358,6 +370,14 @@ pub enum WorkflowError {
     /// Recovery from checkpoint failed
     #[error("Recovery from checkpoint failed: {0}")]
     RecoveryFailed(String),
+
+    /// No workflow engine is registered under the given name
+    #[error("Workflow engine not found: {0}")]
+    EngineNotFound(String),
+
+    /// A workflow engine is already registered under the given name
+    #[error("Workflow engine already registered: {0}")]
+    EngineAlreadyRegistered(String),
 }
 
 /// Errors related to distribution operations.
@@ -85,6 +85,37 @@ impl<T> Id<T> {
         }
     }
 
+    /// Derive a stable identifier from a scope and a name.
+    ///
+    /// The same scope and name always give the same identifier (a UUID v5),
+    /// so definitions built twice get identical IDs. The scope separates
+    /// names that may repeat, such as the nodes of different workflows.
+    ///
+    /// # Arguments
+    ///
+    /// * `scope` - The scope the name is unique within.
+    /// * `name` - The name to derive the identifier from.
+    ///
+    /// # Returns
+    ///
+    /// The identifier for the scope and name.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lion_core::id::PluginId;
+    ///
+    /// let id = PluginId::from_name("plugins", "calculator");
+    /// assert_eq!(id, PluginId::from_name("plugins", "calculator"));
+    /// assert_ne!(id, PluginId::from_name("tools", "calculator"));
+    /// ```
+    pub fn from_name(scope: &str, name: &str) -> Self {
+        // Hashing the scope into its own namespace keeps ("a/b", "c") and
+        // ("a", "b/c") apart
+        let namespace = Uuid::new_v5(&Uuid::NAMESPACE_OID, scope.as_bytes());
+        Self::from_uuid(Uuid::new_v5(&namespace, name.as_bytes()))
+    }
+
     /// Get the underlying UUID.
     ///
     /// This is useful when you need to extract the raw UUID for serialization
@@ -275,4 +306,18 @@ mod tests {
         let deserialized: PluginId = serde_json::from_str(&serialized).unwrap();
         assert_eq!(id, deserialized);
     }
+
+    #[test]
+    fn test_from_name_is_stable() {
+        let id = PluginId::from_name("orders", "validate");
+        assert_eq!(id, PluginId::from_name("orders", "validate"));
+        assert_eq!(id.uuid().get_version_num(), 5);
+
+        assert_ne!(id, PluginId::from_name("orders", "ship"));
+        assert_ne!(id, PluginId::from_name("returns", "validate"));
+        assert_ne!(
+            PluginId::from_name("a/b", "c"),
+            PluginId::from_name("a", "b/c")
+        );
+    }
 }
@@ -57,10 +57,13 @@ pub mod utils;
 pub use error::{Error, Result};
 pub use id::{CapabilityId, ExecutionId, MessageId, NodeId, PluginId, RegionId, WorkflowId};
 // Macros are automatically exported at the crate root due to #[macro_export]
-pub use traits::{Capability, ConcurrencyManager, IsolationBackend, PluginManager, WorkflowEngine};
+pub use traits::{
+    Capability, ConcurrencyManager, IsolationBackend, PluginManager, WorkflowEngine,
+    WorkflowEngineRegistry,
+};
 pub use types::{
     AccessRequest, ErrorPolicy, ExecutionOptions, ExecutionStatus, MemoryRegion, MemoryRegionType,
-    NodeStatus, NodeType, PluginConfig, PluginMetadata, PluginState, PluginType, ResourceUsage,
-    Workflow, WorkflowNode,
+    NodeStatus, NodeType, PluginConfig, PluginMetadata, PluginState, PluginType, Principal,
+    ResourceQuota, ResourceUsage, Workflow, WorkflowNode,
 };
 pub use utils::{ConfigValue, LogLevel, Version};
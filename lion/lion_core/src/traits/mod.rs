@@ -22,7 +22,7 @@ pub use capability::Capability;
 pub use concurrency::ConcurrencyManager;
 pub use isolation::IsolationBackend;
 pub use plugin::PluginManager;
-pub use workflow::WorkflowEngine;
+pub use workflow::{WorkflowEngine, WorkflowEngineRegistry};
 
 #[cfg(feature = "async")]
 pub use concurrency::AsyncConcurrencyManager;
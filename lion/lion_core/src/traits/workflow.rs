@@ -16,8 +16,10 @@
 //! - **Checkpointing**: Workflows can be paused and resumed
 
 use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
 
-use crate::error::Result;
+use crate::error::{Result, WorkflowError};
 use crate::id::{ExecutionId, NodeId, WorkflowId};
 use crate::types::{ExecutionOptions, ExecutionStatus, NodeStatus, Workflow};
 
@@ -362,6 +364,128 @@ pub trait WorkflowEngine: Send + Sync {
     }
 }
 
+/// Workflow engines registered by name.
+///
+/// Several engine implementations can coexist in one registry, so each
+/// workflow can choose the engine that runs it, for instance while moving
+/// workflows from one engine to another.
+///
+/// # Examples
+///
+/// ```no_run
+/// use lion_core::traits::{WorkflowEngine, WorkflowEngineRegistry};
+/// use std::sync::Arc;
+///
+/// fn register(engine: Arc<dyn WorkflowEngine>) -> lion_core::Result<()> {
+///     let mut registry = WorkflowEngineRegistry::new();
+///     registry.register("dag", engine)?;
+///     assert!(registry.contains("dag"));
+///     Ok(())
+/// }
+/// ```
+#[derive(Clone, Default)]
+pub struct WorkflowEngineRegistry {
+    /// The engines, by name.
+    engines: HashMap<String, Arc<dyn WorkflowEngine>>,
+}
+
+impl WorkflowEngineRegistry {
+    /// Create an empty registry.
+    ///
+    /// # Returns
+    ///
+    /// A registry with no engines.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register an engine under a name.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name workflows choose the engine by.
+    /// * `engine` - The engine.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` if the engine was registered.
+    /// * `Err` if an engine is already registered under the name.
+    pub fn register(
+        &mut self,
+        name: impl Into<String>,
+        engine: Arc<dyn WorkflowEngine>,
+    ) -> Result<()> {
+        let name = name.into();
+        if self.engines.contains_key(&name) {
+            return Err(WorkflowError::EngineAlreadyRegistered(name).into());
+        }
+        self.engines.insert(name, engine);
+        Ok(())
+    }
+
+    /// Remove the engine registered under a name.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the engine.
+    ///
+    /// # Returns
+    ///
+    /// The removed engine, or `None` if no engine had the name.
+    pub fn unregister(&mut self, name: &str) -> Option<Arc<dyn WorkflowEngine>> {
+        self.engines.remove(name)
+    }
+
+    /// Get the engine registered under a name.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the engine.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Arc<dyn WorkflowEngine>)` - The engine.
+    /// * `Err` if no engine is registered under the name.
+    pub fn get(&self, name: &str) -> Result<Arc<dyn WorkflowEngine>> {
+        self.engines
+            .get(name)
+            .cloned()
+            .ok_or_else(|| WorkflowError::EngineNotFound(name.to_string()).into())
+    }
+
+    /// Check whether an engine is registered under a name.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the engine.
+    ///
+    /// # Returns
+    ///
+    /// `true` if an engine is registered under the name.
+    pub fn contains(&self, name: &str) -> bool {
+        self.engines.contains_key(name)
+    }
+
+    /// Get the names of the registered engines.
+    ///
+    /// # Returns
+    ///
+    /// The names, sorted.
+    pub fn names(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = self.engines.keys().map(String::as_str).collect();
+        names.sort_unstable();
+        names
+    }
+}
+
+impl fmt::Debug for WorkflowEngineRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WorkflowEngineRegistry")
+            .field("engines", &self.names())
+            .finish()
+    }
+}
+
 /// Extension trait for asynchronous workflow execution.
 ///
 /// This trait provides asynchronous versions of the `WorkflowEngine` methods,
@@ -641,4 +765,41 @@ mod tests {
         let status = engine.get_execution_status(&execution_id).unwrap();
         assert_eq!(status, ExecutionStatus::Cancelled);
     }
+
+    #[test]
+    fn test_engine_registry() {
+        let mut registry = WorkflowEngineRegistry::new();
+        let engine = Arc::new(TestWorkflowEngine::new());
+        registry.register("dag", engine.clone()).unwrap();
+        registry
+            .register("parallel", Arc::new(TestWorkflowEngine::new()))
+            .unwrap();
+        assert_eq!(registry.names(), vec!["dag", "parallel"]);
+
+        // Names are unique
+        assert!(matches!(
+            registry.register("dag", Arc::new(TestWorkflowEngine::new())),
+            Err(crate::error::Error::Workflow(
+                WorkflowError::EngineAlreadyRegistered(_)
+            ))
+        ));
+
+        // The engine registered under a name runs its workflows
+        let workflow = create_test_workflow();
+        let workflow_id = registry
+            .get("dag")
+            .unwrap()
+            .create_workflow(workflow)
+            .unwrap();
+        assert!(engine.get_workflow(&workflow_id).is_ok());
+
+        assert!(registry.unregister("dag").is_some());
+        assert!(!registry.contains("dag"));
+        assert!(matches!(
+            registry.get("dag"),
+            Err(crate::error::Error::Workflow(
+                WorkflowError::EngineNotFound(_)
+            ))
+        ));
+    }
 }
@@ -13,5 +13,6 @@ pub use access::{AccessRequest, AccessRequestType};
 pub use memory::{MemoryRegion, MemoryRegionType};
 pub use plugin::{PluginConfig, PluginMetadata, PluginState, PluginType, ResourceUsage};
 pub use workflow::{
-    ErrorPolicy, ExecutionOptions, ExecutionStatus, NodeStatus, NodeType, Workflow, WorkflowNode,
+    ErrorPolicy, ExecutionOptions, ExecutionStatus, NodeStatus, NodeType, Principal, ResourceQuota,
+    Workflow, WorkflowNode,
 };
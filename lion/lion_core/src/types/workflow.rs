@@ -7,7 +7,7 @@
 //! The workflow system is based on the "Advanced Workflow Composition in
 //! Lion WebAssembly Plugin System" research.
 
-use crate::id::{NodeId, WorkflowId};
+use crate::id::{NodeId, PluginId, WorkflowId};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -477,6 +477,47 @@ pub enum NodeStatus {
     Retrying,
 }
 
+/// The identity on whose behalf a workflow is executed.
+///
+/// The principal is carried through an execution so that capability checks
+/// and audit records can attribute each action to whoever triggered it.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Principal {
+    /// A plugin.
+    Plugin(PluginId),
+
+    /// An autonomous agent.
+    Agent(String),
+
+    /// A human user.
+    User(String),
+}
+
+impl std::fmt::Display for Principal {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Principal::Plugin(id) => write!(f, "plugin:{}", id),
+            Principal::Agent(id) => write!(f, "agent:{}", id),
+            Principal::User(id) => write!(f, "user:{}", id),
+        }
+    }
+}
+
+/// Hard limits on the resources a single execution may use.
+///
+/// A limit left as `None` is not enforced.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResourceQuota {
+    /// Maximum total CPU time of all nodes, in milliseconds.
+    pub max_cpu_time_ms: Option<u64>,
+
+    /// Maximum peak memory of any node, in bytes.
+    pub max_memory_bytes: Option<usize>,
+
+    /// Maximum number of node executions, counting retries.
+    pub max_nodes: Option<usize>,
+}
+
 /// Options for workflow execution.
 ///
 /// This structure contains options for executing a workflow.
@@ -499,6 +540,23 @@ pub struct ExecutionOptions {
 
     /// Callback URL to notify when the execution completes.
     pub callback_url: Option<String>,
+
+    /// Identity on whose behalf the workflow is executed.
+    #[serde(default)]
+    pub principal: Option<Principal>,
+
+    /// Whether to accumulate the resource usage reported by each node.
+    #[serde(default)]
+    pub collect_resource_usage: bool,
+
+    /// Resource limits; the execution fails once one is exceeded.
+    #[serde(default)]
+    pub quota: ResourceQuota,
+
+    /// Whether to run the compensations of completed nodes if the
+    /// execution fails.
+    #[serde(default)]
+    pub rollback_on_failure: bool,
 }
 
 impl Default for ExecutionOptions {
@@ -510,6 +568,10 @@ impl Default for ExecutionOptions {
             enable_checkpointing: false,
             tags: HashMap::new(),
             callback_url: None,
+            principal: None,
+            collect_resource_usage: false,
+            quota: ResourceQuota::default(),
+            rollback_on_failure: false,
         }
     }
 }
@@ -725,6 +787,10 @@ mod tests {
             enable_checkpointing: true,
             tags: HashMap::new(),
             callback_url: Some("https://example.com/callback".to_string()),
+            principal: None,
+            collect_resource_usage: false,
+            quota: ResourceQuota::default(),
+            rollback_on_failure: false,
         };
 
         // Add tags
@@ -782,6 +848,10 @@ mod tests {
                 map
             },
             callback_url: Some("https://example.com/callback".to_string()),
+            principal: Some(Principal::User("alice".to_string())),
+            collect_resource_usage: false,
+            quota: ResourceQuota::default(),
+            rollback_on_failure: false,
         };
 
         let serialized = serde_json::to_string(&options).unwrap();
@@ -798,5 +868,7 @@ mod tests {
             deserialized.tags.get("environment").unwrap()
         );
         assert_eq!(options.callback_url, deserialized.callback_url);
+        assert_eq!(options.principal, deserialized.principal);
+        assert_eq!(options.principal.unwrap().to_string(), "user:alice");
     }
 }
@@ -132,6 +132,34 @@ impl Version {
         }
     }
 
+    /// Check if this version meets a version requirement.
+    ///
+    /// Requirements use Cargo's syntax: `^1.2` matches `1.3.0` but not
+    /// `2.0.0`, `~1.2` matches `1.2.9` but not `1.3.0`, and `=1.2.3` matches
+    /// only `1.2.3`. A prerelease version only meets a requirement that
+    /// names a prerelease of the same major, minor and patch version.
+    ///
+    /// # Arguments
+    ///
+    /// * `requirement` - The version requirement, e.g. `">=1.2, <1.5"`.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(bool)` - Whether this version meets the requirement.
+    /// * `Err(VersionParseError)` - If the requirement could not be parsed.
+    pub fn satisfies(&self, requirement: &str) -> Result<bool, VersionParseError> {
+        let error = |version: String, e: semver::Error| VersionParseError {
+            version,
+            reason: e.to_string(),
+        };
+
+        let requirement = semver::VersionReq::parse(requirement)
+            .map_err(|e| error(requirement.to_string(), e))?;
+        let version = self.to_string();
+        let version = semver::Version::parse(&version).map_err(|e| error(version, e))?;
+        Ok(requirement.matches(&version))
+    }
+
     /// Create a new compatible version by incrementing the minor version.
     ///
     /// # Returns
@@ -484,6 +512,51 @@ mod tests {
         assert!(!v0_1_2.is_compatible_with(&Version::new(1, 0, 0)));
     }
 
+    #[test]
+    fn test_version_satisfies() {
+        let satisfies = |version: &str, requirement: &str| {
+            Version::from_str(version)
+                .unwrap()
+                .satisfies(requirement)
+                .unwrap()
+        };
+
+        // Caret allows changes that do not modify the leftmost non-zero part
+        assert!(satisfies("1.3.0", "^1.2"));
+        assert!(satisfies("1.2.0", "^1.2"));
+        assert!(!satisfies("2.0.0", "^1.2"));
+        assert!(!satisfies("1.1.9", "^1.2"));
+        assert!(satisfies("0.2.5", "^0.2.3"));
+        assert!(!satisfies("0.3.0", "^0.2.3"));
+
+        // Bare requirements are caret requirements
+        assert!(satisfies("1.9.0", "1.2"));
+
+        // Tilde allows patch changes
+        assert!(satisfies("1.2.9", "~1.2.3"));
+        assert!(!satisfies("1.3.0", "~1.2.3"));
+
+        // Exact and compound ranges
+        assert!(satisfies("1.2.3", "=1.2.3"));
+        assert!(!satisfies("1.2.4", "=1.2.3"));
+        assert!(satisfies("1.4.0", ">=1.2, <1.5"));
+        assert!(!satisfies("1.5.0", ">=1.2, <1.5"));
+
+        // Prereleases only match requirements naming the same release
+        assert!(!satisfies("1.3.0-beta.1", "^1.2"));
+        assert!(satisfies("1.2.3-beta.2", "^1.2.3-beta.1"));
+        assert!(!satisfies("1.2.3-alpha", "^1.2.3-beta.1"));
+        assert!(!satisfies("1.2.4-beta.2", "^1.2.3-beta.1"));
+        assert!(satisfies("1.2.3", "^1.2.3-beta.1"));
+
+        // Build metadata is ignored
+        assert!(satisfies("1.2.3+build.7", "=1.2.3"));
+
+        assert!(Version::new(1, 0, 0)
+            .satisfies("not a requirement")
+            .is_err());
+    }
+
     #[test]
     fn test_version_increment() {
         // Increment patch
@@ -369,6 +369,10 @@ fn test_workflow_integration() {
             map
         },
         callback_url: Some("https://example.com/callback".to_string()),
+        principal: None,
+        collect_resource_usage: false,
+        quota: Default::default(),
+        rollback_on_failure: false,
     };
 
     assert_eq!(options.timeout_ms, Some(60000));
@@ -624,6 +628,10 @@ fn test_complete_scenario() {
             map
         },
         callback_url: Some("https://example.com/callback".to_string()),
+        principal: None,
+        collect_resource_usage: false,
+        quota: Default::default(),
+        rollback_on_failure: false,
     };
 
     // 7. Simulate a capability check for accessing the input file
@@ -0,0 +1,404 @@
+//! Host functions.
+//!
+//! This module provides host functions letting plugins publish events and
+//! stream output.
+
+use anyhow::Result;
+use std::sync::Arc;
+use tracing::{debug, warn};
+use wasmtime::Caller;
+
+use crate::interface::CapabilityChecker;
+use crate::wasm::hostcall::HostCallContext;
+use crate::wasm::module::WasmModule;
+
+/// The operation checked before a plugin publishes an event; the parameters
+/// passed to the capability checker are the topic.
+pub const PUBLISH_EVENT_OPERATION: &str = "publish_event";
+
+/// The default maximum size of an event payload.
+pub const DEFAULT_MAX_EVENT_PAYLOAD_BYTES: usize = 64 * 1024;
+
+/// Returned to the guest when the event was published.
+pub const PUBLISH_OK: i32 = 0;
+
+/// Returned to the guest when the topic or payload could not be read.
+pub const PUBLISH_ERR_MEMORY: i32 = -1;
+
+/// Returned to the guest when the plugin may not publish to the topic.
+pub const PUBLISH_ERR_DENIED: i32 = -2;
+
+/// Returned to the guest when the payload is over the size limit.
+pub const PUBLISH_ERR_TOO_LARGE: i32 = -3;
+
+/// Returned to the guest when the publisher rejected the event.
+pub const PUBLISH_ERR_FAILED: i32 = -4;
+
+/// Returned to the guest when every chunk was delivered.
+pub const EMIT_OK: i32 = 0;
+
+/// Returned to the guest when the frames could not be read.
+pub const EMIT_ERR_MEMORY: i32 = -1;
+
+/// Returned to the guest when a frame's length runs past the region.
+pub const EMIT_ERR_MALFORMED: i32 = -2;
+
+/// Returned to the guest when the consumer has dropped the stream.
+pub const EMIT_ERR_CLOSED: i32 = -3;
+
+/// Returned to the guest when the call is not being streamed.
+pub const EMIT_ERR_NOT_STREAMING: i32 = -4;
+
+/// A destination for events published by plugins.
+pub trait EventPublisher: Send + Sync {
+    /// Publish an event.
+    ///
+    /// # Arguments
+    ///
+    /// * `plugin_id` - The plugin publishing the event.
+    /// * `topic` - The topic.
+    /// * `payload` - The payload.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the event was accepted.
+    /// * `Err` - If the event was rejected.
+    fn publish(&self, plugin_id: &str, topic: &str, payload: &[u8]) -> Result<()>;
+}
+
+/// Host functions letting plugins publish events.
+///
+/// Registers `env.publish_event(topic_ptr, topic_len, payload_ptr,
+/// payload_len) -> i32`, reading both from the plugin's exported `memory`.
+/// Failures are reported to the guest as a negative status code rather than
+/// a trap.
+#[derive(Clone)]
+pub struct EventHostFunctions {
+    /// Where published events go.
+    publisher: Arc<dyn EventPublisher>,
+
+    /// Checks the plugin may publish to a topic.
+    checker: Arc<dyn CapabilityChecker>,
+
+    /// The maximum size of a payload.
+    max_payload_bytes: usize,
+}
+
+impl EventHostFunctions {
+    /// Create new event host functions.
+    ///
+    /// # Arguments
+    ///
+    /// * `publisher` - Where published events go.
+    /// * `checker` - Checks `PUBLISH_EVENT_OPERATION` with the topic.
+    ///
+    /// # Returns
+    ///
+    /// New event host functions.
+    pub fn new(publisher: Arc<dyn EventPublisher>, checker: Arc<dyn CapabilityChecker>) -> Self {
+        Self {
+            publisher,
+            checker,
+            max_payload_bytes: DEFAULT_MAX_EVENT_PAYLOAD_BYTES,
+        }
+    }
+
+    /// Set the maximum size of a payload.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_payload_bytes` - The maximum size.
+    ///
+    /// # Returns
+    ///
+    /// The updated host functions.
+    pub fn with_max_payload_bytes(mut self, max_payload_bytes: usize) -> Self {
+        self.max_payload_bytes = max_payload_bytes;
+        self
+    }
+
+    /// Add the host functions to a module.
+    ///
+    /// # Arguments
+    ///
+    /// * `module` - The module.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the functions were successfully added.
+    /// * `Err` - If the functions could not be added.
+    pub fn add_to_module(&self, module: &mut WasmModule) -> Result<()> {
+        let functions = self.clone();
+        module.linker_mut().func_wrap(
+            "env",
+            "publish_event",
+            move |mut caller: Caller<'_, HostCallContext>,
+                  topic_ptr: i32,
+                  topic_len: i32,
+                  payload_ptr: i32,
+                  payload_len: i32|
+                  -> i32 {
+                functions.publish_event(&mut caller, topic_ptr, topic_len, payload_ptr, payload_len)
+            },
+        )?;
+        Ok(())
+    }
+
+    /// Publish an event on behalf of a plugin.
+    ///
+    /// # Returns
+    ///
+    /// `PUBLISH_OK`, or one of the negative `PUBLISH_ERR_*` codes.
+    fn publish_event(
+        &self,
+        caller: &mut Caller<'_, HostCallContext>,
+        topic_ptr: i32,
+        topic_len: i32,
+        payload_ptr: i32,
+        payload_len: i32,
+    ) -> i32 {
+        let plugin_id = caller.data().plugin_id.clone();
+
+        // Reject oversized payloads before copying them
+        if usize::try_from(payload_len).is_ok_and(|len| len > self.max_payload_bytes) {
+            warn!(
+                "Plugin {} tried to publish a {} byte payload",
+                plugin_id, payload_len
+            );
+            return PUBLISH_ERR_TOO_LARGE;
+        }
+
+        let Some(topic) = read_guest_bytes(caller, topic_ptr, topic_len)
+            .and_then(|bytes| String::from_utf8(bytes).ok())
+        else {
+            return PUBLISH_ERR_MEMORY;
+        };
+
+        if let Err(e) =
+            self.checker
+                .check_capability(&plugin_id, PUBLISH_EVENT_OPERATION, topic.as_bytes())
+        {
+            warn!("Plugin {} may not publish to '{}': {}", plugin_id, topic, e);
+            return PUBLISH_ERR_DENIED;
+        }
+
+        let Some(payload) = read_guest_bytes(caller, payload_ptr, payload_len) else {
+            return PUBLISH_ERR_MEMORY;
+        };
+
+        match self.publisher.publish(&plugin_id, &topic, &payload) {
+            Ok(()) => {
+                debug!("Plugin {} published an event to '{}'", plugin_id, topic);
+                PUBLISH_OK
+            }
+            Err(e) => {
+                warn!("Failed to publish event from plugin {}: {}", plugin_id, e);
+                PUBLISH_ERR_FAILED
+            }
+        }
+    }
+}
+
+/// Host functions letting plugins stream output in chunks.
+///
+/// Registers `env.emit_chunk(ptr, len) -> i32`. The region holds one or more
+/// frames, each a little-endian `u32` length followed by that many bytes, and
+/// every frame becomes one chunk of the call's `ChunkStream`. The frames are
+/// validated before any is delivered, so a malformed region emits nothing.
+///
+/// The stream is bounded: when the consumer falls behind, `emit_chunk`
+/// blocks until it catches up instead of buffering without limit.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ChunkHostFunctions;
+
+impl ChunkHostFunctions {
+    /// Add the host functions to a module.
+    ///
+    /// # Arguments
+    ///
+    /// * `module` - The module.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the functions were successfully added.
+    /// * `Err` - If the functions could not be added.
+    pub fn add_to_module(&self, module: &mut WasmModule) -> Result<()> {
+        module.linker_mut().func_wrap(
+            "env",
+            "emit_chunk",
+            |mut caller: Caller<'_, HostCallContext>, ptr: i32, len: i32| -> i32 {
+                emit_chunk(&mut caller, ptr, len)
+            },
+        )?;
+        Ok(())
+    }
+}
+
+/// Deliver the frames in a region of guest memory to the call's stream.
+///
+/// # Returns
+///
+/// `EMIT_OK`, or one of the negative `EMIT_ERR_*` codes.
+fn emit_chunk(caller: &mut Caller<'_, HostCallContext>, ptr: i32, len: i32) -> i32 {
+    let Some(sender) = caller.data().chunk_sender().cloned() else {
+        return EMIT_ERR_NOT_STREAMING;
+    };
+
+    let Some(region) = read_guest_bytes(caller, ptr, len) else {
+        return EMIT_ERR_MEMORY;
+    };
+
+    let Some(chunks) = parse_frames(&region) else {
+        warn!(
+            "Plugin {} emitted a malformed chunk frame",
+            caller.data().plugin_id
+        );
+        return EMIT_ERR_MALFORMED;
+    };
+
+    for chunk in chunks {
+        // Blocks while the stream is full, pausing the plugin
+        if sender.blocking_send(Ok(chunk)).is_err() {
+            return EMIT_ERR_CLOSED;
+        }
+    }
+
+    EMIT_OK
+}
+
+/// Split a region into its length-prefixed frames.
+fn parse_frames(mut region: &[u8]) -> Option<Vec<Vec<u8>>> {
+    let mut frames = Vec::new();
+    while !region.is_empty() {
+        let (prefix, rest) = region.split_first_chunk::<4>()?;
+        let len = usize::try_from(u32::from_le_bytes(*prefix)).ok()?;
+        if len > rest.len() {
+            return None;
+        }
+        let (frame, rest) = rest.split_at(len);
+        frames.push(frame.to_vec());
+        region = rest;
+    }
+    Some(frames)
+}
+
+/// Copy a range of the caller's exported memory, if it is in bounds.
+fn read_guest_bytes(
+    caller: &mut Caller<'_, HostCallContext>,
+    ptr: i32,
+    len: i32,
+) -> Option<Vec<u8>> {
+    let start = usize::try_from(ptr).ok()?;
+    let len = usize::try_from(len).ok()?;
+    let memory = caller.get_export("memory")?.into_memory()?;
+    let data = memory.data(&*caller);
+    data.get(start..start.checked_add(len)?).map(<[u8]>::to_vec)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::wasm::WasmEngine;
+    use parking_lot::Mutex;
+
+    /// Records published events
+    #[derive(Default)]
+    struct RecordingPublisher {
+        events: Mutex<Vec<(String, String, Vec<u8>)>>,
+    }
+
+    impl EventPublisher for RecordingPublisher {
+        fn publish(&self, plugin_id: &str, topic: &str, payload: &[u8]) -> Result<()> {
+            self.events
+                .lock()
+                .push((plugin_id.to_string(), topic.to_string(), payload.to_vec()));
+            Ok(())
+        }
+    }
+
+    /// Allows publishing to the "orders" topic only
+    struct OrdersOnly;
+
+    impl CapabilityChecker for OrdersOnly {
+        fn check_capability(&self, _plugin_id: &str, operation: &str, params: &[u8]) -> Result<()> {
+            if operation == PUBLISH_EVENT_OPERATION && params == b"orders" {
+                Ok(())
+            } else {
+                Err(anyhow::anyhow!("denied"))
+            }
+        }
+    }
+
+    const WASM: &[u8] = br#"(module
+        (import "env" "publish_event" (func $publish (param i32 i32 i32 i32) (result i32)))
+        (memory (export "memory") 1)
+        (data (i32.const 0) "orders")
+        (data (i32.const 16) "audit")
+        (data (i32.const 32) "{\"id\":1}")
+        (func (export "allowed") (result i32)
+            (call $publish (i32.const 0) (i32.const 6) (i32.const 32) (i32.const 8)))
+        (func (export "denied") (result i32)
+            (call $publish (i32.const 16) (i32.const 5) (i32.const 32) (i32.const 8)))
+        (func (export "too_large") (result i32)
+            (call $publish (i32.const 0) (i32.const 6) (i32.const 32) (i32.const 100)))
+        (func (export "out_of_bounds") (result i32)
+            (call $publish (i32.const 65530) (i32.const 10) (i32.const 32) (i32.const 8))))"#;
+
+    #[test]
+    fn test_publish_event_host_function() {
+        let engine = WasmEngine::create_default().unwrap();
+        let publisher = Arc::new(RecordingPublisher::default());
+        let functions = EventHostFunctions::new(publisher.clone(), Arc::new(OrdersOnly))
+            .with_max_payload_bytes(64);
+
+        let mut module = engine.compile_module(WASM).unwrap();
+        functions.add_to_module(&mut module).unwrap();
+        let mut store = engine
+            .instantiate_module(&module, HostCallContext::new("plugin-1".to_string()))
+            .unwrap();
+        let instance = module
+            .linker()
+            .instantiate(&mut store, module.module())
+            .unwrap();
+
+        let mut call = |name: &str| {
+            instance
+                .get_typed_func::<(), i32>(&mut store, name)
+                .unwrap()
+                .call(&mut store, ())
+                .unwrap()
+        };
+        assert_eq!(call("allowed"), PUBLISH_OK);
+        assert_eq!(call("denied"), PUBLISH_ERR_DENIED);
+        assert_eq!(call("too_large"), PUBLISH_ERR_TOO_LARGE);
+        assert_eq!(call("out_of_bounds"), PUBLISH_ERR_MEMORY);
+
+        // Only the permitted publish went through
+        let events = publisher.events.lock();
+        assert_eq!(
+            *events,
+            vec![(
+                "plugin-1".to_string(),
+                "orders".to_string(),
+                br#"{"id":1}"#.to_vec()
+            )]
+        );
+    }
+
+    #[test]
+    fn test_parse_frames() {
+        let mut region = Vec::new();
+        for frame in [&b"ab"[..], b"", b"cde"] {
+            region.extend_from_slice(&(frame.len() as u32).to_le_bytes());
+            region.extend_from_slice(frame);
+        }
+        assert_eq!(
+            parse_frames(&region),
+            Some(vec![b"ab".to_vec(), Vec::new(), b"cde".to_vec()])
+        );
+
+        // A truncated length or frame is rejected
+        assert_eq!(parse_frames(&region[..region.len() - 1]), None);
+        assert_eq!(parse_frames(&[1, 0]), None);
+    }
+}
@@ -4,6 +4,13 @@
 
 mod capability;
 mod default_capability_checker;
+mod host_functions;
 
 pub use capability::{CapabilityChecker, CapabilityInterface};
 pub use default_capability_checker::DefaultCapabilityChecker;
+pub use host_functions::{
+    ChunkHostFunctions, EventHostFunctions, EventPublisher, DEFAULT_MAX_EVENT_PAYLOAD_BYTES,
+    EMIT_ERR_CLOSED, EMIT_ERR_MALFORMED, EMIT_ERR_MEMORY, EMIT_ERR_NOT_STREAMING, EMIT_OK,
+    PUBLISH_ERR_DENIED, PUBLISH_ERR_FAILED, PUBLISH_ERR_MEMORY, PUBLISH_ERR_TOO_LARGE,
+    PUBLISH_EVENT_OPERATION, PUBLISH_OK,
+};
@@ -26,10 +26,13 @@ pub mod resource;
 pub mod wasm;
 
 // Re-export key types and traits for convenience
-pub use interface::CapabilityInterface;
+pub use interface::{CapabilityInterface, ChunkHostFunctions, EventHostFunctions, EventPublisher};
 pub use manager::{
     DefaultIsolationBackend, InstancePool, IsolationBackend, IsolationManager, PluginLifecycle,
-    PluginState, PooledInstance,
+    PluginState, PooledInstance, WasmInstanceConfig,
 };
 pub use resource::{DefaultResourceLimiter, ResourceLimiter, ResourceMetering, ResourceUsage};
-pub use wasm::{HostCallContext, WasmEngine, WasmMemory, WasmModule};
+pub use wasm::{
+    ChunkStream, FileModuleStore, HostCallContext, MemoryModuleStore, ModuleCacheStats,
+    ModuleStore, WasmEngine, WasmMemory, WasmModule,
+};
@@ -7,10 +7,10 @@ use std::sync::{Arc, Mutex};
 use tracing::info;
 
 use super::lifecycle::{PluginLifecycle, PluginState};
-use super::pool::InstancePool;
-use crate::interface::CapabilityInterface;
+use super::pool::{InstancePool, PooledInstance, WasmInstanceConfig};
+use crate::interface::{CapabilityInterface, ChunkHostFunctions, EventHostFunctions};
 use crate::resource::ResourceLimiter;
-use crate::wasm::{WasmEngine, WasmModule};
+use crate::wasm::{ChunkStream, MemoryModuleStore, ModuleStore, WasmEngine, WasmModule};
 use lion_core::error::{IsolationError, Result};
 use lion_core::types::ResourceUsage;
 use lion_core::PluginId;
@@ -63,6 +63,60 @@ pub trait IsolationBackend: Send + Sync {
         params: &[u8],
     ) -> Result<Vec<u8>>;
 
+    /// Call a function in a plugin, streaming its output.
+    ///
+    /// The plugin emits chunks through `env.emit_chunk`; the function's own
+    /// result, if not empty, follows as the last chunk. The default
+    /// implementation does not stream and yields the whole result as one
+    /// chunk.
+    ///
+    /// # Arguments
+    ///
+    /// * `plugin_id` - The plugin ID.
+    /// * `function_name` - The name of the function.
+    /// * `params` - The parameters.
+    /// * `buffer` - The number of chunks buffered before the plugin is paused.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(ChunkStream)` - The stream of output chunks.
+    /// * `Err` - If the function could not be called.
+    fn call_function_streaming(
+        &self,
+        plugin_id: &PluginId,
+        function_name: &str,
+        params: &[u8],
+        buffer: usize,
+    ) -> Result<ChunkStream> {
+        let result = self.call_function(plugin_id, function_name, params)?;
+        let (sender, stream) = ChunkStream::channel(buffer);
+        if !result.is_empty() {
+            // The channel holds at least one chunk, so this cannot be full
+            let _ = sender.try_send(Ok(result));
+        }
+        Ok(stream)
+    }
+
+    /// Replace the code of a loaded plugin.
+    ///
+    /// The default implementation unloads the plugin and loads the new
+    /// code, failing calls in between.
+    ///
+    /// # Arguments
+    ///
+    /// * `plugin_id` - The plugin ID.
+    /// * `wasm_bytes` - The new WebAssembly binary.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the plugin now runs the new code.
+    /// * `Err` - If the plugin is not loaded or the new code could not be
+    ///   loaded.
+    fn reload_plugin(&mut self, plugin_id: &PluginId, wasm_bytes: &[u8]) -> Result<()> {
+        self.unload_plugin(plugin_id)?;
+        self.load_plugin(plugin_id, wasm_bytes)
+    }
+
     /// Get the state of a plugin.
     ///
     /// # Arguments
@@ -109,11 +163,23 @@ pub struct DefaultIsolationBackend {
     /// The compiled modules.
     modules: DashMap<PluginId, Arc<WasmModule>>,
 
+    /// The content-addressed cache the modules are compiled through.
+    module_store: Arc<dyn ModuleStore>,
+
+    /// The execution settings of plugins without their own.
+    default_instance_config: WasmInstanceConfig,
+
+    /// The execution settings of individual plugins.
+    instance_configs: DashMap<PluginId, WasmInstanceConfig>,
+
     /// The instance pool.
     instance_pool: Arc<Mutex<InstancePool>>,
 
     /// The capability interface.
     capability_interface: Arc<Mutex<CapabilityInterface>>,
+
+    /// The host functions letting plugins publish events, if enabled.
+    event_host_functions: Option<EventHostFunctions>,
 }
 
 impl DefaultIsolationBackend {
@@ -133,14 +199,19 @@ impl DefaultIsolationBackend {
     ) -> Result<Self> {
         let instance_pool = Arc::new(Mutex::new(InstancePool::new()));
         let capability_interface = Arc::new(Mutex::new(CapabilityInterface::new()));
+        let module_store = Arc::new(MemoryModuleStore::new(engine.clone()));
 
         Ok(Self {
             engine,
             resource_limiter,
             plugin_lifecycles: DashMap::new(),
             modules: DashMap::new(),
+            module_store,
+            default_instance_config: WasmInstanceConfig::default(),
+            instance_configs: DashMap::new(),
             instance_pool,
             capability_interface,
+            event_host_functions: None,
         })
     }
 
@@ -154,6 +225,67 @@ impl DefaultIsolationBackend {
         capability_interface.set_capability_checker(checker);
     }
 
+    /// Set the module store plugins are compiled through.
+    ///
+    /// The store must use this backend's engine, e.g. a `FileModuleStore`
+    /// keeping compiled plugins across restarts.
+    ///
+    /// # Arguments
+    ///
+    /// * `module_store` - The module store.
+    pub fn set_module_store(&mut self, module_store: Arc<dyn ModuleStore>) {
+        self.module_store = module_store;
+    }
+
+    /// Get the module store plugins are compiled through.
+    pub fn module_store(&self) -> &Arc<dyn ModuleStore> {
+        &self.module_store
+    }
+
+    /// Let plugins loaded from now on publish events.
+    ///
+    /// # Arguments
+    ///
+    /// * `functions` - The event host functions.
+    pub fn set_event_host_functions(&mut self, functions: EventHostFunctions) {
+        self.event_host_functions = Some(functions);
+    }
+
+    /// Set the execution settings of plugins without their own.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The execution settings.
+    pub fn set_default_instance_config(&mut self, config: WasmInstanceConfig) {
+        self.default_instance_config = config;
+    }
+
+    /// Set the execution settings of a plugin, e.g. its fuel budget.
+    ///
+    /// # Arguments
+    ///
+    /// * `plugin_id` - The plugin ID.
+    /// * `config` - The execution settings.
+    pub fn set_instance_config(&self, plugin_id: &PluginId, config: WasmInstanceConfig) {
+        self.instance_configs.insert(*plugin_id, config);
+    }
+
+    /// Get the execution settings of a plugin.
+    ///
+    /// # Arguments
+    ///
+    /// * `plugin_id` - The plugin ID.
+    ///
+    /// # Returns
+    ///
+    /// The plugin's own settings, or the default ones.
+    pub fn instance_config(&self, plugin_id: &PluginId) -> WasmInstanceConfig {
+        self.instance_configs
+            .get(plugin_id)
+            .map(|config| *config)
+            .unwrap_or(self.default_instance_config)
+    }
+
     /// Get a plugin lifecycle.
     ///
     /// # Arguments
@@ -183,21 +315,82 @@ impl DefaultIsolationBackend {
     fn get_module(&self, plugin_id: &PluginId) -> Option<Arc<WasmModule>> {
         self.modules.get(plugin_id).map(|module| module.clone())
     }
-}
 
-impl IsolationBackend for DefaultIsolationBackend {
-    fn load_plugin(&mut self, plugin_id: &PluginId, wasm_bytes: &[u8]) -> Result<()> {
-        // Check if plugin already exists
-        if self.plugin_lifecycles.contains_key(plugin_id) {
-            return Err(
-                IsolationError::LoadFailed(format!("Plugin {} already exists", plugin_id)).into(),
-            );
+    /// Take an instance of a plugin out of the pool, ready for a call.
+    ///
+    /// # Arguments
+    ///
+    /// * `plugin_id` - The plugin ID.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(PooledInstance)` - An instance configured for the plugin.
+    /// * `Err` - If the plugin is not loaded or not runnable.
+    fn checkout_instance(&self, plugin_id: &PluginId) -> Result<PooledInstance> {
+        let mut pooled_instance = {
+            let mut instance_pool = self.instance_pool.lock().unwrap();
+
+            // Get the module under the pool lock, so a reload cannot pair it
+            // with the instances of another version
+            let module = self
+                .get_module(plugin_id)
+                .ok_or(IsolationError::PluginNotLoaded(*plugin_id))?;
+
+            // Get or create an instance
+            instance_pool.get_or_create_instance(
+                plugin_id,
+                &self.engine,
+                &module,
+                self.resource_limiter.clone(),
+            )?
+        };
+
+        // Get a lifecycle
+        let mut lifecycle = self
+            .get_lifecycle(plugin_id)
+            .ok_or(IsolationError::PluginNotLoaded(*plugin_id))?;
+
+        // Check if the plugin is in a state that allows function calls
+        if !lifecycle.can_call_function() {
+            return Err(IsolationError::ExecutionTrap(format!(
+                "Plugin {} is not in a runnable state",
+                plugin_id
+            ))
+            .into());
         }
 
-        info!("Loading plugin {}", plugin_id);
+        // Update lifecycle state to Running if it was Loaded
+        if lifecycle.state() == PluginState::Loaded {
+            lifecycle.transition_to(PluginState::Running);
+            if let Some(mut entry) = self.plugin_lifecycles.get_mut(plugin_id) {
+                *entry = lifecycle.clone();
+            }
+        }
 
+        // Apply the plugin's execution settings
+        let config = self.instance_config(plugin_id);
+        if config.timeout.is_some() {
+            self.engine.ensure_epoch_ticker();
+        }
+        pooled_instance.set_config(config);
+
+        Ok(pooled_instance)
+    }
+
+    /// Compile a plugin and link the host functions it may import.
+    ///
+    /// # Arguments
+    ///
+    /// * `plugin_id` - The plugin ID.
+    /// * `wasm_bytes` - The WebAssembly binary.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(WasmModule)` - The module, ready to instantiate.
+    /// * `Err` - If the module could not be compiled or linked.
+    fn prepare_module(&self, plugin_id: &PluginId, wasm_bytes: &[u8]) -> Result<WasmModule> {
         // Compile the module
-        let mut module = match self.engine.compile_module(wasm_bytes) {
+        let mut module = match self.module_store.get_or_compile(wasm_bytes) {
             Ok(m) => m,
             Err(e) => {
                 return Err(IsolationError::CompilationFailed(format!(
@@ -220,8 +413,43 @@ impl IsolationBackend for DefaultIsolationBackend {
             }
         }
 
-        // Create a module Arc
-        let module_arc = Arc::new(module);
+        // Set up output streaming
+        if let Err(e) = ChunkHostFunctions.add_to_module(&mut module) {
+            return Err(IsolationError::LinkingFailed(format!(
+                "Failed to add chunk host functions to plugin {}: {}",
+                plugin_id, e
+            ))
+            .into());
+        }
+
+        // Set up event publishing
+        if let Some(functions) = &self.event_host_functions {
+            if let Err(e) = functions.add_to_module(&mut module) {
+                return Err(IsolationError::LinkingFailed(format!(
+                    "Failed to add event host functions to plugin {}: {}",
+                    plugin_id, e
+                ))
+                .into());
+            }
+        }
+
+        Ok(module)
+    }
+}
+
+impl IsolationBackend for DefaultIsolationBackend {
+    fn load_plugin(&mut self, plugin_id: &PluginId, wasm_bytes: &[u8]) -> Result<()> {
+        // Check if plugin already exists
+        if self.plugin_lifecycles.contains_key(plugin_id) {
+            return Err(
+                IsolationError::LoadFailed(format!("Plugin {} already exists", plugin_id)).into(),
+            );
+        }
+
+        info!("Loading plugin {}", plugin_id);
+
+        // Compile and link the module
+        let module_arc = Arc::new(self.prepare_module(plugin_id, wasm_bytes)?);
 
         // Create a plugin lifecycle
         let lifecycle = PluginLifecycle::new(*plugin_id, PluginState::Loaded);
@@ -252,63 +480,102 @@ impl IsolationBackend for DefaultIsolationBackend {
         // Remove the module and lifecycle
         self.modules.remove(plugin_id);
         self.plugin_lifecycles.remove(plugin_id);
+        self.instance_configs.remove(plugin_id);
 
         info!("Plugin {} unloaded successfully", plugin_id);
 
         Ok(())
     }
 
-    fn call_function(
-        &self,
-        plugin_id: &PluginId,
-        function_name: &str,
-        params: &[u8],
-    ) -> Result<Vec<u8>> {
-        // Get the module
-        let module = self
-            .get_module(plugin_id)
-            .ok_or(IsolationError::PluginNotLoaded(*plugin_id))?;
+    fn reload_plugin(&mut self, plugin_id: &PluginId, wasm_bytes: &[u8]) -> Result<()> {
+        if !self.plugin_lifecycles.contains_key(plugin_id) {
+            return Err(IsolationError::PluginNotLoaded(*plugin_id).into());
+        }
+
+        info!("Reloading plugin {}", plugin_id);
 
-        // Get or create an instance
+        // Check the new code links and instantiates before swapping it in
+        let module = Arc::new(self.prepare_module(plugin_id, wasm_bytes)?);
         let mut instance_pool = self.instance_pool.lock().unwrap();
-        let mut pooled_instance = instance_pool.get_or_create_instance(
+        let warm_instance = instance_pool.create_instance(
             plugin_id,
             &self.engine,
             &module,
             self.resource_limiter.clone(),
         )?;
 
-        // Get a lifecycle
-        let mut lifecycle = self
-            .get_lifecycle(plugin_id)
-            .ok_or(IsolationError::PluginNotLoaded(*plugin_id))?;
+        // Swap the module and pool together; calls already running keep
+        // their instance of the old module until they return. The plugin's
+        // lifecycle and execution settings are kept.
+        self.modules.insert(*plugin_id, module);
+        instance_pool.replace_plugin_instances(plugin_id, warm_instance);
 
-        // Check if the plugin is in a state that allows function calls
-        if !lifecycle.can_call_function() {
-            return Err(IsolationError::ExecutionTrap(format!(
-                "Plugin {} is not in a runnable state",
-                plugin_id
-            ))
-            .into());
-        }
+        info!("Plugin {} reloaded successfully", plugin_id);
 
-        // Update lifecycle state to Running if it was Loaded
-        if lifecycle.state() == PluginState::Loaded {
-            lifecycle.transition_to(PluginState::Running);
-            if let Some(mut entry) = self.plugin_lifecycles.get_mut(plugin_id) {
-                *entry = lifecycle.clone();
-            }
-        }
+        Ok(())
+    }
 
+    fn call_function(
+        &self,
+        plugin_id: &PluginId,
+        function_name: &str,
+        params: &[u8],
+    ) -> Result<Vec<u8>> {
         // Call the function
+        let mut pooled_instance = self.checkout_instance(plugin_id)?;
         let result = pooled_instance.call_function(function_name, params)?;
 
         // Return the instance to the pool
-        instance_pool.return_instance(pooled_instance);
+        self.instance_pool
+            .lock()
+            .unwrap()
+            .return_instance(pooled_instance);
 
         Ok(result)
     }
 
+    fn call_function_streaming(
+        &self,
+        plugin_id: &PluginId,
+        function_name: &str,
+        params: &[u8],
+        buffer: usize,
+    ) -> Result<ChunkStream> {
+        let mut pooled_instance = self.checkout_instance(plugin_id)?;
+        let (sender, stream) = ChunkStream::channel(buffer);
+
+        // Run the call on its own thread, so emitting can block on a slow
+        // consumer without holding up the caller
+        let instance_pool = self.instance_pool.clone();
+        let function_name = function_name.to_string();
+        let params = params.to_vec();
+        std::thread::spawn(move || {
+            pooled_instance
+                .store_mut()
+                .data_mut()
+                .set_chunk_sender(sender.clone());
+            let result = pooled_instance.call_function(&function_name, &params);
+            pooled_instance.store_mut().data_mut().take_chunk_sender();
+
+            match result {
+                Ok(result) => {
+                    if !result.is_empty() {
+                        let _ = sender.blocking_send(Ok(result));
+                    }
+                    instance_pool
+                        .lock()
+                        .unwrap()
+                        .return_instance(pooled_instance);
+                }
+                Err(e) => {
+                    let _ = sender.blocking_send(Err(e));
+                }
+            }
+        });
+
+        Ok(stream)
+    }
+
     fn get_plugin_state(&self, plugin_id: &PluginId) -> Result<PluginState> {
         let lifecycle = self
             .get_lifecycle(plugin_id)
@@ -374,4 +641,140 @@ mod tests {
         assert!(!backend.plugin_lifecycles.contains_key(&plugin_id));
         assert!(!backend.modules.contains_key(&plugin_id));
     }
+
+    #[test]
+    fn test_identical_plugins_share_compilation() {
+        const WASM: &[u8] = &[0, 97, 115, 109, 1, 0, 0, 0];
+
+        let mut backend = create_test_backend();
+        backend.load_plugin(&PluginId::new(), WASM).unwrap();
+        backend.load_plugin(&PluginId::new(), WASM).unwrap();
+
+        let stats = backend.module_store().stats();
+        assert_eq!((stats.hits, stats.misses), (1, 1));
+    }
+
+    #[test]
+    fn test_fuel_budget_stops_infinite_loop() {
+        const WASM: &[u8] = br#"(module
+            (func (export "spin") (loop (br 0)))
+            (func (export "answer") (result i32) i32.const 42))"#;
+
+        let mut backend = create_test_backend();
+        let plugin_id = PluginId::new();
+        backend.load_plugin(&plugin_id, WASM).unwrap();
+        backend.set_instance_config(
+            &plugin_id,
+            WasmInstanceConfig::default().with_fuel_per_call(10_000),
+        );
+
+        let err = backend.call_function(&plugin_id, "spin", &[]).unwrap_err();
+        assert!(matches!(
+            err,
+            lion_core::error::Error::Isolation(IsolationError::FuelExhausted(_))
+        ));
+
+        // Calls within the budget still succeed
+        backend.call_function(&plugin_id, "answer", &[]).unwrap();
+    }
+
+    #[test]
+    fn test_timeout_interrupts_long_call() {
+        const WASM: &[u8] = br#"(module (func (export "spin") (loop (br 0))))"#;
+
+        let mut backend = create_test_backend();
+        let plugin_id = PluginId::new();
+        backend.load_plugin(&plugin_id, WASM).unwrap();
+        backend.set_instance_config(
+            &plugin_id,
+            WasmInstanceConfig::default().with_timeout(std::time::Duration::from_millis(50)),
+        );
+
+        let err = backend.call_function(&plugin_id, "spin", &[]).unwrap_err();
+        assert!(matches!(
+            err,
+            lion_core::error::Error::Isolation(IsolationError::ExecutionTimeout(_))
+        ));
+    }
+
+    #[test]
+    fn test_streaming_call_delivers_chunks_in_order() {
+        // "hello" and "world" in one region, then "hello" again on its own
+        const WASM: &[u8] = br#"(module
+            (import "env" "emit_chunk" (func $emit (param i32 i32) (result i32)))
+            (memory (export "memory") 1)
+            (data (i32.const 0) "\05\00\00\00hello\05\00\00\00world")
+            (func (export "stream") (result i32)
+                (drop (call $emit (i32.const 0) (i32.const 18)))
+                (call $emit (i32.const 0) (i32.const 9))))"#;
+
+        let mut backend = create_test_backend();
+        let plugin_id = PluginId::new();
+        backend.load_plugin(&plugin_id, WASM).unwrap();
+
+        // A one-chunk buffer makes the plugin wait on the consumer
+        let mut stream = backend
+            .call_function_streaming(&plugin_id, "stream", &[], 1)
+            .unwrap();
+        let mut chunks = Vec::new();
+        while let Some(chunk) = stream.blocking_next() {
+            chunks.push(chunk.unwrap());
+        }
+        assert_eq!(
+            chunks,
+            vec![b"hello".to_vec(), b"world".to_vec(), b"hello".to_vec()]
+        );
+
+        // Outside a streaming call, emitting is refused and the plugin
+        // returns the error code
+        assert!(backend.call_function(&plugin_id, "stream", &[]).is_err());
+    }
+
+    #[test]
+    fn test_reload_swaps_code_and_lets_running_calls_finish() {
+        const OLD: &[u8] = br#"(module
+            (import "env" "emit_chunk" (func $emit (param i32 i32) (result i32)))
+            (memory (export "memory") 1)
+            (data (i32.const 0) "\01\00\00\00a\01\00\00\00b")
+            (func (export "stream") (result i32)
+                (drop (call $emit (i32.const 0) (i32.const 5)))
+                (call $emit (i32.const 5) (i32.const 5)))
+            (func (export "old") (result i32) i32.const 0))"#;
+        const NEW: &[u8] = br#"(module (func (export "new") (result i32) i32.const 0))"#;
+        const BROKEN: &[u8] =
+            br#"(module (import "env" "missing" (func)) (func (export "new") (result i32) i32.const 0))"#;
+
+        let mut backend = create_test_backend();
+        let plugin_id = PluginId::new();
+        backend.load_plugin(&plugin_id, OLD).unwrap();
+        backend.set_instance_config(
+            &plugin_id,
+            WasmInstanceConfig::default().with_fuel_per_call(1_000_000),
+        );
+
+        // A call in flight, held up by a one-chunk buffer
+        let mut stream = backend
+            .call_function_streaming(&plugin_id, "stream", &[], 1)
+            .unwrap();
+        backend.reload_plugin(&plugin_id, NEW).unwrap();
+
+        // The running call finishes on the old code
+        let mut chunks = Vec::new();
+        while let Some(chunk) = stream.blocking_next() {
+            chunks.push(chunk.unwrap());
+        }
+        assert_eq!(chunks, vec![b"a".to_vec(), b"b".to_vec()]);
+
+        // New calls run the new code, and the old instance is not reused
+        backend.call_function(&plugin_id, "new", &[]).unwrap();
+        assert!(backend.call_function(&plugin_id, "old", &[]).is_err());
+        assert_eq!(
+            backend.instance_config(&plugin_id).fuel_per_call,
+            Some(1_000_000)
+        );
+
+        // Code that cannot be instantiated is rejected, leaving the plugin as it was
+        assert!(backend.reload_plugin(&plugin_id, BROKEN).is_err());
+        backend.call_function(&plugin_id, "new", &[]).unwrap();
+    }
 }
@@ -9,11 +9,11 @@ mod pool;
 
 pub use backend::{DefaultIsolationBackend, IsolationBackend};
 pub use lifecycle::{PluginLifecycle, PluginState};
-pub use pool::{InstancePool, PooledInstance};
+pub use pool::{InstancePool, PooledInstance, WasmInstanceConfig};
 
 use crate::interface::CapabilityChecker;
 use crate::resource::ResourceLimiter;
-use crate::wasm::WasmEngine;
+use crate::wasm::{ChunkStream, WasmEngine};
 use lion_core::error::Result;
 use lion_core::PluginId;
 use std::sync::Arc;
@@ -92,6 +92,24 @@ impl IsolationManager {
         self.backend.unload_plugin(plugin_id)
     }
 
+    /// Replace the code of a loaded plugin without unloading it.
+    ///
+    /// The plugin keeps its capabilities and execution settings; calls
+    /// already running finish on the old code.
+    ///
+    /// # Arguments
+    ///
+    /// * `plugin_id` - The plugin ID.
+    /// * `wasm_bytes` - The new WebAssembly binary.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the plugin now runs the new code.
+    /// * `Err` - If the new code was rejected, leaving the plugin unchanged.
+    pub fn reload_plugin(&mut self, plugin_id: &PluginId, wasm_bytes: &[u8]) -> Result<()> {
+        self.backend.reload_plugin(plugin_id, wasm_bytes)
+    }
+
     /// Call a function in a plugin.
     ///
     /// # Arguments
@@ -113,6 +131,30 @@ impl IsolationManager {
         self.backend.call_function(plugin_id, function_name, params)
     }
 
+    /// Call a function in a plugin, streaming its output.
+    ///
+    /// # Arguments
+    ///
+    /// * `plugin_id` - The plugin ID.
+    /// * `function_name` - The name of the function.
+    /// * `params` - The parameters.
+    /// * `buffer` - The number of chunks buffered before the plugin is paused.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(ChunkStream)` - The stream of output chunks.
+    /// * `Err` - If the function could not be called.
+    pub fn call_function_streaming(
+        &self,
+        plugin_id: &PluginId,
+        function_name: &str,
+        params: &[u8],
+        buffer: usize,
+    ) -> Result<ChunkStream> {
+        self.backend
+            .call_function_streaming(plugin_id, function_name, params, buffer)
+    }
+
     /// Get the state of a plugin.
     ///
     /// # Arguments
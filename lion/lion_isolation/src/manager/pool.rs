@@ -3,15 +3,67 @@
 //! This module provides instance pooling for better performance.
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Duration;
 use tracing::trace;
-use wasmtime::{Instance, Store};
+use wasmtime::{Instance, Store, Trap, Val};
 
 use crate::resource::ResourceLimiter;
+use crate::wasm::engine::{EPOCH_TICK, NO_EPOCH_DEADLINE};
 use crate::wasm::{HostCallContext, WasmEngine, WasmModule};
 use lion_core::error::{IsolationError, Result};
 use lion_core::types::ResourceUsage;
 use lion_core::PluginId;
 
+/// Execution settings of a plugin's instances.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct WasmInstanceConfig {
+    /// The fuel each function call may consume, or `None` for no limit.
+    ///
+    /// Fuel is consumed roughly per executed instruction, so a budget stops a
+    /// plugin from spinning forever.
+    pub fuel_per_call: Option<u64>,
+
+    /// The wall-clock time each function call may take, or `None` for no
+    /// limit.
+    ///
+    /// The timeout is enforced by the engine's epoch ticker with a
+    /// granularity of `EPOCH_TICK`. It is only checked while wasm code runs:
+    /// a host function that blocks is not interrupted, and the call times out
+    /// once control returns to the plugin. Host calls that sleep or do I/O
+    /// should therefore bound their own waits.
+    pub timeout: Option<Duration>,
+}
+
+impl WasmInstanceConfig {
+    /// Limit each function call to a fuel budget.
+    ///
+    /// # Arguments
+    ///
+    /// * `fuel` - The fuel each call may consume.
+    ///
+    /// # Returns
+    ///
+    /// The updated config.
+    pub fn with_fuel_per_call(mut self, fuel: u64) -> Self {
+        self.fuel_per_call = Some(fuel);
+        self
+    }
+
+    /// Limit each function call to a wall-clock timeout.
+    ///
+    /// # Arguments
+    ///
+    /// * `timeout` - The time each call may take.
+    ///
+    /// # Returns
+    ///
+    /// The updated config.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+}
+
 /// A pooled instance.
 pub struct PooledInstance {
     /// The plugin ID.
@@ -22,6 +74,12 @@ pub struct PooledInstance {
 
     /// The instance.
     instance: Instance,
+
+    /// The execution settings applied to each call.
+    config: WasmInstanceConfig,
+
+    /// The version of the plugin's module the instance was created from.
+    generation: u64,
 }
 
 impl PooledInstance {
@@ -41,9 +99,25 @@ impl PooledInstance {
             plugin_id,
             store,
             instance,
+            config: WasmInstanceConfig::default(),
+            generation: 0,
         }
     }
 
+    /// Set the execution settings applied to each call.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The execution settings.
+    pub fn set_config(&mut self, config: WasmInstanceConfig) {
+        self.config = config;
+    }
+
+    /// Get the execution settings applied to each call.
+    pub fn config(&self) -> &WasmInstanceConfig {
+        &self.config
+    }
+
     /// Get the plugin ID.
     pub fn plugin_id(&self) -> &PluginId {
         &self.plugin_id
@@ -101,9 +175,45 @@ impl PooledInstance {
             }
         };
 
+        // Give the call its fuel budget
+        let fuel = self.config.fuel_per_call.unwrap_or(u64::MAX);
+        if let Err(e) = self.store.set_fuel(fuel) {
+            return Err(IsolationError::ExecutionTrap(format!(
+                "Failed to set fuel for plugin {}: {}",
+                self.plugin_id, e
+            ))
+            .into());
+        }
+
+        // Give the call its deadline, in epoch ticks from now
+        let deadline = match self.config.timeout {
+            Some(timeout) => {
+                let ticks = timeout.as_nanos().div_ceil(EPOCH_TICK.as_nanos()).max(1);
+                u64::try_from(ticks).unwrap_or(NO_EPOCH_DEADLINE)
+            }
+            None => NO_EPOCH_DEADLINE,
+        };
+        self.store.set_epoch_deadline(deadline);
+
         // Call the function using a more compatible approach
         let result_ptr = match call_function_with_bytes(&mut self.store, func, params) {
             Ok(ptr) => ptr,
+            Err(e) if e.downcast_ref::<Trap>() == Some(&Trap::OutOfFuel) => {
+                return Err(IsolationError::FuelExhausted(format!(
+                    "Function '{}' in plugin {} used up its budget of {} fuel",
+                    function_name, self.plugin_id, fuel
+                ))
+                .into());
+            }
+            Err(e) if e.downcast_ref::<Trap>() == Some(&Trap::Interrupt) => {
+                return Err(IsolationError::ExecutionTimeout(format!(
+                    "Function '{}' in plugin {} ran past its timeout of {:?}",
+                    function_name,
+                    self.plugin_id,
+                    self.config.timeout.unwrap_or_default()
+                ))
+                .into());
+            }
             Err(e) => {
                 return Err(lion_core::error::Error::Isolation(
                     lion_core::error::IsolationError::ExecutionTrap(format!(
@@ -152,10 +262,18 @@ impl PooledInstance {
 
 // Helper function to call a WebAssembly function with byte parameters
 fn call_function_with_bytes(
-    _store: &mut Store<HostCallContext>,
-    _func: wasmtime::Func,
+    store: &mut Store<HostCallContext>,
+    func: wasmtime::Func,
     _params: &[u8],
 ) -> anyhow::Result<i32> {
+    // Functions without parameters need no memory setup and are called directly
+    let ty = func.ty(&*store);
+    if ty.params().len() == 0 {
+        let mut results = vec![Val::I32(0); ty.results().len()];
+        func.call(&mut *store, &[], &mut results)?;
+        return Ok(results.first().and_then(Val::i32).unwrap_or(0));
+    }
+
     // In a real implementation, we would:
     // 1. Get the memory from the store
     // 2. Allocate memory for parameters
@@ -177,6 +295,10 @@ pub struct InstancePool {
 
     /// The maximum number of instances per plugin.
     max_instances_per_plugin: usize,
+
+    /// The current module version of each plugin; instances of older
+    /// versions are dropped when returned.
+    generations: HashMap<PluginId, u64>,
 }
 
 impl InstancePool {
@@ -185,6 +307,7 @@ impl InstancePool {
         Self {
             instances: HashMap::new(),
             max_instances_per_plugin: 10,
+            generations: HashMap::new(),
         }
     }
 }
@@ -233,6 +356,12 @@ impl InstancePool {
     pub fn return_instance(&mut self, instance: PooledInstance) {
         let plugin_id = *instance.plugin_id();
 
+        // Instances of a replaced module finish their call but are not reused
+        if instance.generation != self.generation(&plugin_id) {
+            trace!("Dropping stale instance of plugin {}", plugin_id);
+            return;
+        }
+
         let instances = self.instances.entry(plugin_id).or_default();
 
         // Only keep up to max_instances_per_plugin
@@ -293,7 +422,8 @@ impl InstancePool {
         };
 
         // Create a pooled instance
-        let pooled_instance = PooledInstance::new(*plugin_id, store, instance);
+        let mut pooled_instance = PooledInstance::new(*plugin_id, store, instance);
+        pooled_instance.generation = self.generation(plugin_id);
 
         // Ensure the plugin exists in the instances map
         self.instances.entry(*plugin_id).or_default();
@@ -337,6 +467,27 @@ impl InstancePool {
     /// * `plugin_id` - The plugin ID.
     pub fn remove_plugin_instances(&mut self, plugin_id: &PluginId) {
         self.instances.remove(plugin_id);
+        *self.generations.entry(*plugin_id).or_default() += 1;
+    }
+
+    /// Replace the instances of a plugin with one of its new module.
+    ///
+    /// Idle instances of the old module are dropped, and instances still
+    /// running a call are dropped when returned.
+    ///
+    /// # Arguments
+    ///
+    /// * `plugin_id` - The plugin ID.
+    /// * `instance` - An instance created from the new module.
+    pub fn replace_plugin_instances(&mut self, plugin_id: &PluginId, mut instance: PooledInstance) {
+        self.remove_plugin_instances(plugin_id);
+        instance.generation = self.generation(plugin_id);
+        self.instances.insert(*plugin_id, vec![instance]);
+    }
+
+    /// Get the current module version of a plugin.
+    fn generation(&self, plugin_id: &PluginId) -> u64 {
+        self.generations.get(plugin_id).copied().unwrap_or_default()
     }
 
     /// Get the resource usage for a plugin.
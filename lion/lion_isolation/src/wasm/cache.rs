@@ -0,0 +1,349 @@
+//! WebAssembly module cache.
+//!
+//! This module provides content-addressed stores of compiled WebAssembly
+//! modules, so identical binaries are compiled once.
+
+use anyhow::Result;
+use dashmap::DashMap;
+use sha2::{Digest, Sha256};
+use std::collections::hash_map::DefaultHasher;
+use std::fmt;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use tracing::{debug, trace, warn};
+use wasmtime::{Linker, Module};
+
+use crate::wasm::engine::WasmEngine;
+use crate::wasm::module::WasmModule;
+
+/// The SHA-256 hash of a WebAssembly binary.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ModuleHash([u8; 32]);
+
+impl ModuleHash {
+    /// Hash a WebAssembly binary.
+    ///
+    /// # Arguments
+    ///
+    /// * `wasm` - The WebAssembly binary.
+    ///
+    /// # Returns
+    ///
+    /// The hash of the binary.
+    pub fn of(wasm: &[u8]) -> Self {
+        Self(Sha256::digest(wasm).into())
+    }
+}
+
+impl fmt::Display for ModuleHash {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for byte in self.0 {
+            write!(f, "{:02x}", byte)?;
+        }
+        Ok(())
+    }
+}
+
+/// Hit and miss counts of a module store.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ModuleCacheStats {
+    /// Lookups served without compiling.
+    pub hits: u64,
+
+    /// Lookups that compiled the binary.
+    pub misses: u64,
+}
+
+/// A content-addressed store of compiled WebAssembly modules.
+///
+/// Modules are keyed by the hash of their binary, so plugins loaded from
+/// identical binaries share one compilation.
+pub trait ModuleStore: Send + Sync {
+    /// Get the compiled module for a binary, compiling it on a miss.
+    ///
+    /// Each call returns a module with a fresh linker, so host functions
+    /// added for one plugin do not leak into another.
+    ///
+    /// # Arguments
+    ///
+    /// * `wasm` - The WebAssembly binary.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(WasmModule)` - The compiled module.
+    /// * `Err` - If the module could not be compiled.
+    fn get_or_compile(&self, wasm: &[u8]) -> Result<WasmModule>;
+
+    /// Get the hit and miss counts.
+    fn stats(&self) -> ModuleCacheStats;
+}
+
+/// A module store holding compiled modules in memory.
+pub struct MemoryModuleStore {
+    /// The engine compiling the modules.
+    engine: Arc<WasmEngine>,
+
+    /// The compiled modules by hash.
+    modules: DashMap<ModuleHash, Module>,
+
+    /// The number of lookups served without compiling.
+    hits: AtomicU64,
+
+    /// The number of lookups that compiled the binary.
+    misses: AtomicU64,
+}
+
+impl MemoryModuleStore {
+    /// Create a new in-memory module store.
+    ///
+    /// # Arguments
+    ///
+    /// * `engine` - The engine compiling the modules; modules can only be
+    ///   instantiated by this engine.
+    ///
+    /// # Returns
+    ///
+    /// A new module store.
+    pub fn new(engine: Arc<WasmEngine>) -> Self {
+        Self {
+            engine,
+            modules: DashMap::new(),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    /// Get the number of modules held in memory.
+    pub fn len(&self) -> usize {
+        self.modules.len()
+    }
+
+    /// Check whether no modules are held in memory.
+    pub fn is_empty(&self) -> bool {
+        self.modules.is_empty()
+    }
+
+    /// Get a module held in memory, counting a hit if found.
+    fn cached(&self, hash: &ModuleHash) -> Option<Module> {
+        let module = self.modules.get(hash)?.clone();
+        trace!("Module cache hit for {}", hash);
+        self.hits.fetch_add(1, Ordering::Relaxed);
+        Some(module)
+    }
+
+    /// Compile a binary and hold the module in memory, counting a miss.
+    fn compile(&self, hash: ModuleHash, wasm: &[u8]) -> Result<Module> {
+        trace!("Module cache miss for {}", hash);
+        self.misses.fetch_add(1, Ordering::Relaxed);
+        let module = self.engine.compile_module(wasm)?.module;
+        self.modules.insert(hash, module.clone());
+        Ok(module)
+    }
+
+    /// Wrap a module with a fresh linker.
+    fn with_linker(&self, module: Module) -> WasmModule {
+        WasmModule {
+            module,
+            linker: Linker::new(self.engine.engine()),
+        }
+    }
+}
+
+impl ModuleStore for MemoryModuleStore {
+    fn get_or_compile(&self, wasm: &[u8]) -> Result<WasmModule> {
+        let hash = ModuleHash::of(wasm);
+        let module = match self.cached(&hash) {
+            Some(module) => module,
+            None => self.compile(hash, wasm)?,
+        };
+        Ok(self.with_linker(module))
+    }
+
+    fn stats(&self) -> ModuleCacheStats {
+        ModuleCacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// A module store persisting compiled modules to a directory.
+///
+/// Artifacts are named by the hash of their binary and a tag of the engine's
+/// version and configuration, so artifacts from another Wasmtime version or
+/// engine configuration are recompiled rather than loaded.
+pub struct FileModuleStore {
+    /// The in-memory layer in front of the directory.
+    memory: MemoryModuleStore,
+
+    /// The directory holding serialized modules.
+    directory: PathBuf,
+
+    /// The tag of the engine the artifacts must come from.
+    engine_tag: u64,
+}
+
+impl FileModuleStore {
+    /// Create a new module store backed by a directory.
+    ///
+    /// # Arguments
+    ///
+    /// * `engine` - The engine compiling the modules.
+    /// * `directory` - The directory holding serialized modules.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(FileModuleStore)` - The new module store.
+    /// * `Err` - If the directory could not be created.
+    pub fn new(engine: Arc<WasmEngine>, directory: impl Into<PathBuf>) -> Result<Self> {
+        let directory = directory.into();
+        fs::create_dir_all(&directory)?;
+
+        let mut hasher = DefaultHasher::new();
+        engine
+            .engine()
+            .precompile_compatibility_hash()
+            .hash(&mut hasher);
+
+        Ok(Self {
+            memory: MemoryModuleStore::new(engine),
+            directory,
+            engine_tag: hasher.finish(),
+        })
+    }
+
+    /// Get the number of modules held in memory.
+    pub fn len(&self) -> usize {
+        self.memory.len()
+    }
+
+    /// Check whether no modules are held in memory.
+    pub fn is_empty(&self) -> bool {
+        self.memory.is_empty()
+    }
+
+    /// Load a serialized module, counting a hit if found.
+    fn load(&self, hash: &ModuleHash) -> Option<Module> {
+        let path = self.path_for(hash);
+        if !path.exists() {
+            return None;
+        }
+
+        // SAFETY: the file name carries this engine's compatibility tag, so
+        // it was serialized by a store using an identically configured engine.
+        match unsafe { Module::deserialize_file(self.memory.engine.engine(), &path) } {
+            Ok(module) => {
+                debug!("Loaded compiled module {} from disk", hash);
+                self.memory.hits.fetch_add(1, Ordering::Relaxed);
+                self.memory.modules.insert(*hash, module.clone());
+                Some(module)
+            }
+            Err(e) => {
+                warn!("Recompiling unreadable module {}: {}", path.display(), e);
+                None
+            }
+        }
+    }
+
+    /// Write a compiled module to disk.
+    fn persist(&self, hash: &ModuleHash, module: &Module) {
+        let path = self.path_for(hash);
+
+        // Write to a temporary file first so readers never see a partial module
+        let temp_path = path.with_extension("tmp");
+        let result = module
+            .serialize()
+            .and_then(|bytes| Ok(fs::write(&temp_path, bytes)?))
+            .and_then(|()| Ok(fs::rename(&temp_path, &path)?));
+        if let Err(e) = result {
+            warn!("Failed to cache module {}: {}", path.display(), e);
+        }
+    }
+
+    /// Get the path of a serialized module.
+    fn path_for(&self, hash: &ModuleHash) -> PathBuf {
+        self.directory
+            .join(format!("{}-{:016x}.cwasm", hash, self.engine_tag))
+    }
+}
+
+impl ModuleStore for FileModuleStore {
+    fn get_or_compile(&self, wasm: &[u8]) -> Result<WasmModule> {
+        let hash = ModuleHash::of(wasm);
+        let module = match self.memory.cached(&hash).or_else(|| self.load(&hash)) {
+            Some(module) => module,
+            None => {
+                let module = self.memory.compile(hash, wasm)?;
+                self.persist(&hash, &module);
+                module
+            }
+        };
+        Ok(self.memory.with_linker(module))
+    }
+
+    fn stats(&self) -> ModuleCacheStats {
+        self.memory.stats()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Wasmtime compiles the text format as well as binaries
+    const WASM: &[u8] = br#"(module (func (export "answer") (result i32) i32.const 42))"#;
+
+    #[test]
+    fn test_get_or_compile_dedups_by_content() {
+        let engine = Arc::new(WasmEngine::create_default().unwrap());
+        let store = MemoryModuleStore::new(engine);
+
+        store.get_or_compile(WASM).unwrap();
+        store.get_or_compile(WASM).unwrap();
+        assert_eq!(store.stats(), ModuleCacheStats { hits: 1, misses: 1 });
+        assert_eq!(store.len(), 1);
+
+        // A different binary is compiled separately
+        let other = br#"(module (func (export "answer") (result i32) i32.const 7))"#;
+        store.get_or_compile(other).unwrap();
+        assert_eq!(store.stats().misses, 2);
+        assert_eq!(store.len(), 2);
+    }
+
+    #[test]
+    fn test_file_store_survives_restart() {
+        let directory = tempfile::tempdir().unwrap();
+        let engine = Arc::new(WasmEngine::create_default().unwrap());
+
+        let store = FileModuleStore::new(engine.clone(), directory.path()).unwrap();
+        store.get_or_compile(WASM).unwrap();
+        assert_eq!(store.stats().misses, 1);
+        drop(store);
+
+        // A new store loads the module from disk without compiling
+        let restarted = FileModuleStore::new(engine, directory.path()).unwrap();
+        let module = restarted.get_or_compile(WASM).unwrap();
+        assert_eq!(restarted.stats(), ModuleCacheStats { hits: 1, misses: 0 });
+        assert!(module.module().get_export("answer").is_some());
+    }
+
+    #[test]
+    fn test_file_store_recompiles_stale_artifacts() {
+        let directory = tempfile::tempdir().unwrap();
+        let engine = Arc::new(WasmEngine::create_default().unwrap());
+        let store = FileModuleStore::new(engine.clone(), directory.path()).unwrap();
+
+        // An artifact this engine cannot load is replaced
+        let path = store.path_for(&ModuleHash::of(WASM));
+        fs::write(&path, b"not a compiled module").unwrap();
+        store.get_or_compile(WASM).unwrap();
+        assert_eq!(store.stats().misses, 1);
+
+        let restarted = FileModuleStore::new(engine, directory.path()).unwrap();
+        restarted.get_or_compile(WASM).unwrap();
+        assert_eq!(restarted.stats(), ModuleCacheStats { hits: 1, misses: 0 });
+    }
+}
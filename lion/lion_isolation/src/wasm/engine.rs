@@ -3,7 +3,11 @@
 //! This module provides a WebAssembly engine for plugin isolation.
 
 use anyhow::Result;
+use parking_lot::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::Duration;
 use tracing::{debug, trace};
 use wasmtime::{Config, Engine, Instance, Linker, Memory, Module, Store, Strategy};
 
@@ -12,6 +16,51 @@ use crate::wasm::hostcall::HostCallContext;
 use crate::wasm::memory::WasmMemory;
 use crate::wasm::module::WasmModule;
 
+/// The interval at which the epoch ticker advances an engine's epoch.
+///
+/// This is the granularity of wall-clock timeouts.
+pub const EPOCH_TICK: Duration = Duration::from_millis(10);
+
+/// An epoch deadline far enough away to never be reached.
+pub(crate) const NO_EPOCH_DEADLINE: u64 = u64::MAX / 2;
+
+/// A background thread advancing an engine's epoch.
+struct EpochTicker {
+    /// Set to stop the thread.
+    stop: Arc<AtomicBool>,
+
+    /// The thread.
+    handle: Option<JoinHandle<()>>,
+}
+
+impl EpochTicker {
+    /// Start advancing an engine's epoch every `EPOCH_TICK`.
+    fn start(engine: Engine) -> Self {
+        let stop = Arc::new(AtomicBool::new(false));
+        let thread_stop = stop.clone();
+        let handle = std::thread::Builder::new()
+            .name("lion-epoch-ticker".to_string())
+            .spawn(move || {
+                while !thread_stop.load(Ordering::Relaxed) {
+                    std::thread::sleep(EPOCH_TICK);
+                    engine.increment_epoch();
+                }
+            })
+            .ok();
+
+        Self { stop, handle }
+    }
+}
+
+impl Drop for EpochTicker {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
 /// A WebAssembly engine.
 pub struct WasmEngine {
     /// The wasmtime engine.
@@ -19,6 +68,10 @@ pub struct WasmEngine {
 
     /// The resource limiter.
     resource_limiter: Arc<dyn ResourceLimiter>,
+
+    /// The epoch ticker shared by every store of this engine, started by
+    /// the first call with a timeout.
+    epoch_ticker: Mutex<Option<EpochTicker>>,
 }
 
 impl Default for WasmEngine {
@@ -69,6 +122,7 @@ impl WasmEngine {
         Ok(Self {
             engine,
             resource_limiter,
+            epoch_ticker: Mutex::new(None),
         })
     }
 
@@ -130,8 +184,12 @@ impl WasmEngine {
         let resource_metering = ResourceMetering::new(self.resource_limiter.clone());
         store.data_mut().set_resource_metering(resource_metering);
 
-        // Set up fuel
-        // store.add_fuel(u64::MAX / 2)?; // Removed as not available in this wasmtime version
+        // Fuel is always consumed, so give instantiation an unlimited budget;
+        // per-call budgets are set before each call
+        store.set_fuel(u64::MAX)?;
+
+        // Only calls with a timeout get an epoch deadline
+        store.set_epoch_deadline(NO_EPOCH_DEADLINE);
 
         // Instantiate the module
         module.linker.instantiate(&mut store, &module.module)?;
@@ -139,6 +197,18 @@ impl WasmEngine {
         Ok(store)
     }
 
+    /// Start the background thread advancing the epoch, if not yet running.
+    ///
+    /// One thread serves every store of this engine, and stops when the
+    /// engine is dropped.
+    pub fn ensure_epoch_ticker(&self) {
+        let mut ticker = self.epoch_ticker.lock();
+        if ticker.is_none() {
+            debug!("Starting epoch ticker");
+            *ticker = Some(EpochTicker::start(self.engine.clone()));
+        }
+    }
+
     /// Get the underlying Wasmtime engine.
     pub fn engine(&self) -> &Engine {
         &self.engine
@@ -3,6 +3,7 @@
 //! This module provides a context for host calls.
 
 use crate::resource::ResourceMetering;
+use crate::wasm::stream::ChunkSender;
 use wasmtime::Memory;
 
 /// A context for host calls.
@@ -21,6 +22,9 @@ pub struct HostCallContext {
 
     /// The WebAssembly memory instance
     memory: Option<Memory>,
+
+    /// Where emitted chunks go, during a streaming call.
+    chunk_sender: Option<ChunkSender>,
 }
 
 impl HostCallContext {
@@ -40,6 +44,7 @@ impl HostCallContext {
             exited: false,
             exit_code: None,
             memory: None,
+            chunk_sender: None,
         }
     }
 
@@ -113,4 +118,19 @@ impl HostCallContext {
     pub fn get_memory_mut(&mut self) -> Option<&mut Memory> {
         self.memory.as_mut()
     }
+
+    /// Set where emitted chunks go for the duration of a streaming call.
+    pub fn set_chunk_sender(&mut self, sender: ChunkSender) {
+        self.chunk_sender = Some(sender);
+    }
+
+    /// Remove the chunk sender once a streaming call has finished.
+    pub fn take_chunk_sender(&mut self) -> Option<ChunkSender> {
+        self.chunk_sender.take()
+    }
+
+    /// Get the chunk sender, if a streaming call is in progress.
+    pub fn chunk_sender(&self) -> Option<&ChunkSender> {
+        self.chunk_sender.as_ref()
+    }
 }
@@ -2,12 +2,16 @@
 //!
 //! This module provides isolation using WebAssembly.
 
+pub mod cache;
 pub mod engine;
 pub mod hostcall;
 pub mod memory;
 pub mod module;
+pub mod stream;
 
+pub use cache::{FileModuleStore, MemoryModuleStore, ModuleCacheStats, ModuleHash, ModuleStore};
 pub use engine::WasmEngine;
 pub use hostcall::HostCallContext;
 pub use memory::WasmMemory;
 pub use module::WasmModule;
+pub use stream::{ChunkSender, ChunkStream, DEFAULT_CHUNK_BUFFER};
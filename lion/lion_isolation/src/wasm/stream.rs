@@ -0,0 +1,63 @@
+//! Streamed function output.
+//!
+//! This module provides the channel carrying chunks of output from a plugin
+//! call to its consumer.
+
+use futures::Stream;
+use lion_core::error::Result;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tokio::sync::mpsc;
+
+/// The default number of chunks buffered between a plugin and its consumer.
+pub const DEFAULT_CHUNK_BUFFER: usize = 16;
+
+/// The sending half of a chunk stream, held by the host during a call.
+pub type ChunkSender = mpsc::Sender<Result<Vec<u8>>>;
+
+/// A stream of output chunks from a plugin call.
+///
+/// The stream ends when the call returns; a failed call yields its error as
+/// the last item. The channel is bounded, so a plugin emitting faster than
+/// the consumer reads is paused rather than buffering without limit.
+/// Dropping the stream makes further emits fail in the plugin.
+pub struct ChunkStream {
+    /// The receiving half of the channel.
+    receiver: mpsc::Receiver<Result<Vec<u8>>>,
+}
+
+impl ChunkStream {
+    /// Create a connected chunk sender and stream.
+    ///
+    /// # Arguments
+    ///
+    /// * `buffer` - The number of chunks buffered before the plugin is paused
+    ///   (at least one).
+    ///
+    /// # Returns
+    ///
+    /// The sender for the host and the stream for the consumer.
+    pub fn channel(buffer: usize) -> (ChunkSender, Self) {
+        let (sender, receiver) = mpsc::channel(buffer.max(1));
+        (sender, Self { receiver })
+    }
+
+    /// Wait for the next chunk outside of an async context.
+    ///
+    /// # Returns
+    ///
+    /// * `Some(Ok(Vec<u8>))` - The next chunk.
+    /// * `Some(Err)` - The error the call failed with.
+    /// * `None` - If the call has finished.
+    pub fn blocking_next(&mut self) -> Option<Result<Vec<u8>>> {
+        self.receiver.blocking_recv()
+    }
+}
+
+impl Stream for ChunkStream {
+    type Item = Result<Vec<u8>>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.receiver.poll_recv(cx)
+    }
+}
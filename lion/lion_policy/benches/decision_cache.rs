@@ -0,0 +1,68 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use lion_core::id::PluginId;
+use lion_core::types::AccessRequest;
+use lion_policy::model::rule::FileObject;
+use lion_policy::{
+    InMemoryPolicyStore, PolicyAction, PolicyEvaluator, PolicyObject, PolicyRule, PolicySubject,
+};
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// An evaluator with one rule allowing /data and many unrelated rules
+fn evaluator(plugin_id: PluginId, ttl: Duration) -> PolicyEvaluator<InMemoryPolicyStore> {
+    let mut evaluator = PolicyEvaluator::new(InMemoryPolicyStore::new());
+    evaluator.set_cache_config(1024, ttl);
+
+    for i in 0..200 {
+        let (path, action) = if i == 0 {
+            ("/data".to_string(), PolicyAction::Allow)
+        } else {
+            (format!("/other/{}", i), PolicyAction::Deny)
+        };
+        evaluator
+            .add_rule(PolicyRule::new(
+                format!("rule{}", i),
+                format!("Rule {}", i),
+                "Benchmark rule",
+                PolicySubject::Plugin(plugin_id),
+                PolicyObject::File(FileObject {
+                    path,
+                    is_directory: true,
+                }),
+                action,
+                None,
+                0,
+            ))
+            .unwrap();
+    }
+
+    evaluator
+}
+
+fn repeated_file_checks(c: &mut Criterion) {
+    let plugin_id = PluginId::new();
+    let request = AccessRequest::File {
+        path: PathBuf::from("/data/report.csv"),
+        read: true,
+        write: false,
+        execute: false,
+    };
+
+    let mut group = c.benchmark_group("repeated_file_check");
+
+    // A zero TTL makes every check re-evaluate the rules
+    let mut uncached = evaluator(plugin_id, Duration::ZERO);
+    group.bench_function("uncached", |b| {
+        b.iter(|| uncached.is_allowed(black_box(&plugin_id), black_box(&request)))
+    });
+
+    let mut cached = evaluator(plugin_id, Duration::from_secs(60));
+    group.bench_function("cached", |b| {
+        b.iter(|| cached.is_allowed(black_box(&plugin_id), black_box(&request)))
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, repeated_file_checks);
+criterion_main!(benches);
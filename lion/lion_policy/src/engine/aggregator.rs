@@ -115,6 +115,7 @@ where
                 PolicyAction::AllowWithConstraints(_) => "allow_with_constraints".to_string(),
                 PolicyAction::TransformToConstraints(_) => "transform_to_constraints".to_string(),
                 PolicyAction::Audit => "audit".to_string(),
+                PolicyAction::Throttle { .. } => "throttle".to_string(),
             };
 
             by_action
@@ -275,6 +276,7 @@ mod tests {
                 host: "example.com".to_string(),
                 port: None,
                 protocol: None,
+                rate_limit: None,
             }),
             PolicyAction::Allow,
             None,
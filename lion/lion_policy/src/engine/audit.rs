@@ -2,13 +2,26 @@
 //!
 //! This module provides functionality for auditing policy evaluations.
 
+use chrono::{DateTime, Utc};
 use dashmap::DashMap;
-use lion_core::error::Result;
+use lion_core::error::{Error, Result};
 use lion_core::id::PluginId;
+use parking_lot::RwLock;
+use std::io::Write;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 
 use crate::model::{Evaluation, EvaluationResult};
 
+/// The default maximum number of evaluations kept across all plugins.
+pub const DEFAULT_POLICY_AUDIT_CAPACITY: usize = 100_000;
+
+/// A callback receiving each recorded evaluation.
+pub type PolicyAuditSink = Arc<dyn Fn(&Evaluation) + Send + Sync>;
+
+/// A callback receiving evaluations evicted from a full audit, e.g. to persist them.
+pub type PolicyAuditRollover = Arc<dyn Fn(Vec<Evaluation>) + Send + Sync>;
+
 /// A policy audit.
 ///
 /// This audit records policy evaluations.
@@ -19,6 +32,18 @@ pub struct PolicyAudit {
 
     /// The maximum number of entries to keep per plugin.
     max_entries_per_plugin: usize,
+
+    /// The maximum number of entries to keep across all plugins.
+    max_entries: usize,
+
+    /// The number of entries kept across all plugins.
+    len: Arc<AtomicUsize>,
+
+    /// The callbacks pushing recorded evaluations to external sinks.
+    sinks: Arc<RwLock<Vec<PolicyAuditSink>>>,
+
+    /// The callback handed evicted evaluations.
+    rollover: Arc<RwLock<Option<PolicyAuditRollover>>>,
 }
 
 impl PolicyAudit {
@@ -32,12 +57,42 @@ impl PolicyAudit {
     ///
     /// A new policy audit.
     pub fn new(max_entries_per_plugin: usize) -> Self {
+        Self::with_capacity(max_entries_per_plugin, DEFAULT_POLICY_AUDIT_CAPACITY)
+    }
+
+    /// Create a new policy audit bounded per plugin and in total.
+    ///
+    /// Once either bound is reached, the oldest evaluations are evicted.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_entries_per_plugin` - The maximum number of entries to keep per plugin.
+    /// * `max_entries` - The maximum number of entries to keep across all plugins.
+    ///
+    /// # Returns
+    ///
+    /// A new policy audit.
+    pub fn with_capacity(max_entries_per_plugin: usize, max_entries: usize) -> Self {
         Self {
             entries: Arc::new(DashMap::new()),
             max_entries_per_plugin,
+            max_entries: max_entries.max(1),
+            len: Arc::new(AtomicUsize::new(0)),
+            sinks: Arc::new(RwLock::new(Vec::new())),
+            rollover: Arc::new(RwLock::new(None)),
         }
     }
 
+    /// Set the callback handed evaluations before they are evicted, replacing
+    /// any previous one.
+    ///
+    /// # Arguments
+    ///
+    /// * `rollover` - The callback.
+    pub fn set_rollover(&self, rollover: impl Fn(Vec<Evaluation>) + Send + Sync + 'static) {
+        *self.rollover.write() = Some(Arc::new(rollover));
+    }
+
     /// Record an evaluation.
     ///
     /// # Arguments
@@ -54,18 +109,87 @@ impl PolicyAudit {
             .entry(evaluation.plugin_id)
             .or_default()
             .push(evaluation.clone());
+        self.len.fetch_add(1, Ordering::SeqCst);
 
         // Trim the entries if necessary
+        let mut evicted = Vec::new();
         if let Some(mut plugin_entries) = self.entries.get_mut(&evaluation.plugin_id) {
             if plugin_entries.len() > self.max_entries_per_plugin {
                 let to_remove = plugin_entries.len() - self.max_entries_per_plugin;
-                plugin_entries.drain(0..to_remove);
+                evicted.extend(plugin_entries.drain(0..to_remove));
+            }
+        }
+        self.len.fetch_sub(evicted.len(), Ordering::SeqCst);
+
+        // Evict the oldest entries across plugins while over the total bound
+        while self.len.load(Ordering::SeqCst) > self.max_entries {
+            match self.evict_oldest() {
+                Some(oldest) => evicted.push(oldest),
+                None => break,
             }
         }
 
+        // Hand off the evicted evaluations before they are dropped
+        if !evicted.is_empty() {
+            if let Some(rollover) = self.rollover.read().clone() {
+                rollover(evicted);
+            }
+        }
+
+        // Push the evaluation to the sinks
+        for sink in self.sinks.read().iter() {
+            sink(&evaluation);
+        }
+
         Ok(())
     }
 
+    /// Register a callback that receives every evaluation as it is recorded.
+    ///
+    /// # Arguments
+    ///
+    /// * `sink` - The callback.
+    pub fn subscribe(&self, sink: impl Fn(&Evaluation) + Send + Sync + 'static) {
+        self.sinks.write().push(Arc::new(sink));
+    }
+
+    /// Write the evaluations recorded at or after a time as JSON Lines.
+    ///
+    /// The evaluations are copied before writing, so a slow writer does not
+    /// hold up new evaluations.
+    ///
+    /// # Arguments
+    ///
+    /// * `writer` - Where to write the evaluations, oldest first.
+    /// * `since` - The earliest evaluation time to export.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(usize)` - The number of evaluations written.
+    /// * `Err` - If writing or serializing an evaluation failed.
+    pub fn export_audits(&self, mut writer: impl Write, since: DateTime<Utc>) -> Result<usize> {
+        let mut evaluations = Vec::new();
+        for entry in self.entries.iter() {
+            evaluations.extend(
+                entry
+                    .value()
+                    .iter()
+                    .filter(|evaluation| evaluation.timestamp >= since)
+                    .cloned(),
+            );
+        }
+        evaluations.sort_by_key(|evaluation| evaluation.timestamp);
+
+        for evaluation in &evaluations {
+            serde_json::to_writer(&mut writer, evaluation)
+                .map_err(|e| Error::Serialization(e.to_string()))?;
+            writer.write_all(b"\n")?;
+        }
+        writer.flush()?;
+
+        Ok(evaluations.len())
+    }
+
     /// Get evaluations for a plugin.
     ///
     /// # Arguments
@@ -98,7 +222,9 @@ impl PolicyAudit {
     /// * `Err` - If the evaluations could not be cleared.
     pub fn clear_evaluations(&self, plugin_id: &PluginId) -> Result<()> {
         // Remove the plugin's entries
-        self.entries.remove(plugin_id);
+        if let Some((_, removed)) = self.entries.remove(plugin_id) {
+            self.len.fetch_sub(removed.len(), Ordering::SeqCst);
+        }
 
         Ok(())
     }
@@ -171,6 +297,33 @@ impl PolicyAudit {
 
         Ok(evaluations)
     }
+
+    /// Remove the oldest evaluation across all plugins.
+    ///
+    /// # Returns
+    ///
+    /// The removed evaluation, or `None` if there are none.
+    fn evict_oldest(&self) -> Option<Evaluation> {
+        let plugin_id = self
+            .entries
+            .iter()
+            .filter_map(|entry| {
+                entry
+                    .value()
+                    .first()
+                    .map(|oldest| (*entry.key(), oldest.timestamp))
+            })
+            .min_by_key(|(_, timestamp)| *timestamp)
+            .map(|(plugin_id, _)| plugin_id)?;
+
+        let mut plugin_entries = self.entries.get_mut(&plugin_id)?;
+        if plugin_entries.is_empty() {
+            return None;
+        }
+        let oldest = plugin_entries.remove(0);
+        self.len.fetch_sub(1, Ordering::SeqCst);
+        Some(oldest)
+    }
 }
 
 impl Default for PolicyAudit {
@@ -411,4 +564,83 @@ mod tests {
             "rule2"
         );
     }
+
+    #[test]
+    fn test_export_and_subscribe_audits() {
+        let audit = PolicyAudit::new(10);
+        let plugin_id = PluginId::new();
+
+        let pushed = Arc::new(parking_lot::Mutex::new(Vec::new()));
+        let sink = pushed.clone();
+        audit.subscribe(move |evaluation| sink.lock().push(evaluation.result.clone()));
+
+        let request = |path: &str| AccessRequest::File {
+            path: PathBuf::from(path),
+            read: true,
+            write: false,
+            execute: false,
+        };
+        audit
+            .record(Evaluation::new(
+                plugin_id,
+                request("/tmp/old"),
+                EvaluationResult::Allow,
+                None,
+            ))
+            .unwrap();
+        let since = Utc::now();
+        audit
+            .record(Evaluation::new(
+                plugin_id,
+                request("/tmp/new"),
+                EvaluationResult::Deny,
+                None,
+            ))
+            .unwrap();
+
+        // Only evaluations at or after `since` are exported, one per line
+        let mut output = Vec::new();
+        assert_eq!(audit.export_audits(&mut output, since).unwrap(), 1);
+        let lines: Vec<_> = std::str::from_utf8(&output).unwrap().lines().collect();
+        assert_eq!(lines.len(), 1);
+        let exported: Evaluation = serde_json::from_str(lines[0]).unwrap();
+        assert!(matches!(exported.result, EvaluationResult::Deny));
+
+        // Subscribers saw every evaluation
+        assert_eq!(pushed.lock().len(), 2);
+    }
+
+    #[test]
+    fn test_total_bound_rolls_over_oldest() {
+        let audit = PolicyAudit::with_capacity(10, 3);
+        let evicted = Arc::new(parking_lot::Mutex::new(Vec::new()));
+        let rollover = evicted.clone();
+        audit.set_rollover(move |evaluations| rollover.lock().extend(evaluations));
+
+        let first = PluginId::new();
+        let second = PluginId::new();
+        let start = Utc::now();
+        for (i, plugin_id) in [first, second, second, first].into_iter().enumerate() {
+            let request = AccessRequest::File {
+                path: PathBuf::from(format!("/tmp/file{}", i)),
+                read: true,
+                write: false,
+                execute: false,
+            };
+            let mut evaluation = Evaluation::new(plugin_id, request, EvaluationResult::Allow, None);
+            evaluation.timestamp = start + chrono::Duration::seconds(i as i64);
+            audit.record(evaluation).unwrap();
+        }
+
+        // The oldest evaluation overall was handed off and dropped
+        let evicted = evicted.lock();
+        assert_eq!(evicted.len(), 1);
+        assert!(matches!(
+            &evicted[0].request,
+            AccessRequest::File { path, .. } if path.as_path() == std::path::Path::new("/tmp/file0")
+        ));
+        assert_eq!(audit.get_all_evaluations().unwrap().len(), 3);
+        assert_eq!(audit.get_evaluations(&first).unwrap().len(), 1);
+        assert_eq!(audit.get_evaluations(&second).unwrap().len(), 2);
+    }
 }
@@ -2,13 +2,19 @@
 //!
 //! This module provides the policy evaluation engine.
 
-use lion_core::error::Result;
+use chrono::{DateTime, Utc};
+use lion_core::error::{PolicyError, Result};
 use lion_core::id::PluginId;
 use lion_core::types::AccessRequest;
-use std::collections::HashMap;
+use std::time::{Duration, Instant};
 
-use crate::model::{Evaluation, EvaluationResult, PolicyRule};
-use crate::store::PolicyStore;
+use crate::engine::rate_limit::NetworkRateLimiter;
+use crate::engine::PolicyAudit;
+use crate::model::{
+    Evaluation, EvaluationResult, NetworkRateLimit, PolicyAction, PolicyObject, PolicyRule,
+    PolicySubject, ResourceLimitRule,
+};
+use crate::store::{DecisionCache, PolicyStore};
 
 /// Policy evaluation engine.
 ///
@@ -17,8 +23,17 @@ pub struct PolicyEvaluator<P> {
     /// The policy store.
     policy_store: P,
 
-    /// Cache of evaluations by plugin and request.
-    evaluation_cache: HashMap<(PluginId, AccessRequest), EvaluationResult>,
+    /// Cache of decisions by plugin and request.
+    evaluation_cache: DecisionCache,
+
+    /// Resource limit rules.
+    resource_limits: Vec<ResourceLimitRule>,
+
+    /// Counters for network rate limits.
+    network_limiter: NetworkRateLimiter,
+
+    /// Audit that network access violations are recorded in.
+    audit: Option<PolicyAudit>,
 }
 
 impl<P> PolicyEvaluator<P>
@@ -37,15 +52,95 @@ where
     pub fn new(policy_store: P) -> Self {
         Self {
             policy_store,
-            evaluation_cache: HashMap::new(),
+            evaluation_cache: DecisionCache::default(),
+            resource_limits: Vec::new(),
+            network_limiter: NetworkRateLimiter::default(),
+            audit: None,
         }
     }
 
+    /// Record every decision in an audit.
+    ///
+    /// # Arguments
+    ///
+    /// * `audit` - The audit to record decisions in.
+    pub fn set_audit(&mut self, audit: PolicyAudit) {
+        self.audit = Some(audit);
+    }
+
+    /// Replace the decision cache with an empty one.
+    ///
+    /// # Arguments
+    ///
+    /// * `capacity` - The maximum number of cached decisions.
+    /// * `ttl` - How long a cached decision stays valid.
+    pub fn set_cache_config(&mut self, capacity: usize, ttl: Duration) {
+        self.evaluation_cache = DecisionCache::new(capacity, ttl);
+    }
+
     /// Clear the evaluation cache.
     pub fn clear_cache(&mut self) {
         self.evaluation_cache.clear();
     }
 
+    /// Drop the cached decisions for a plugin.
+    ///
+    /// Call this when a capability is granted to or revoked from the plugin.
+    ///
+    /// # Arguments
+    ///
+    /// * `plugin_id` - The ID of the plugin.
+    pub fn invalidate_plugin(&mut self, plugin_id: &PluginId) {
+        self.evaluation_cache.invalidate_plugin(plugin_id);
+    }
+
+    /// Add a policy rule, dropping the cached decisions it may change.
+    ///
+    /// # Arguments
+    ///
+    /// * `rule` - The policy rule to add.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the rule was successfully added.
+    /// * `Err` - If the rule could not be added.
+    pub fn add_rule(&mut self, rule: PolicyRule) -> Result<()> {
+        let subject = rule.subject.clone();
+        self.policy_store.add_rule(rule)?;
+        self.invalidate_subject(&subject);
+        Ok(())
+    }
+
+    /// Remove a policy rule, dropping the cached decisions it may change.
+    ///
+    /// # Arguments
+    ///
+    /// * `rule_id` - The ID of the rule to remove.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the rule was successfully removed.
+    /// * `Err` - If the rule could not be removed.
+    pub fn remove_rule(&mut self, rule_id: &str) -> Result<()> {
+        let subject = self.policy_store.get_rule(rule_id)?.subject;
+        self.policy_store.remove_rule(rule_id)?;
+        self.invalidate_subject(&subject);
+        Ok(())
+    }
+
+    /// Drop the cached decisions of every plugin a rule subject may cover.
+    fn invalidate_subject(&mut self, subject: &PolicySubject) {
+        match subject {
+            PolicySubject::Plugin(plugin_id) => self.invalidate_plugin(plugin_id),
+            PolicySubject::Plugins(plugin_ids) => {
+                for plugin_id in plugin_ids {
+                    self.invalidate_plugin(plugin_id);
+                }
+            }
+            _ => self.clear_cache(),
+        }
+    }
+
     /// Evaluate an access request against policies.
     ///
     /// # Arguments
@@ -62,25 +157,90 @@ where
         plugin_id: &PluginId,
         request: &AccessRequest,
     ) -> Result<Evaluation> {
-        // Check if we have a cached evaluation
-        let cache_key = (*plugin_id, request.clone());
-
-        if let Some(result) = self.evaluation_cache.get(&cache_key) {
-            return Ok(Evaluation::new(
-                *plugin_id,
-                request.clone(),
-                result.clone(),
-                None,
-            ));
+        self.evaluate_at(plugin_id, request, Utc::now())
+    }
+
+    /// Evaluate an access request against policies at a given time.
+    ///
+    /// Rules whose condition does not hold at `now` (e.g. a time window
+    /// that is closed) are ignored. Results that depend on the time are
+    /// not cached. The decision is recorded in the audit, if one is set.
+    ///
+    /// # Arguments
+    ///
+    /// * `plugin_id` - The ID of the plugin making the request.
+    /// * `request` - The access request.
+    /// * `now` - The time to evaluate the request at.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Evaluation)` - The evaluation.
+    /// * `Err` - If the evaluation could not be performed.
+    pub fn evaluate_at(
+        &mut self,
+        plugin_id: &PluginId,
+        request: &AccessRequest,
+        now: DateTime<Utc>,
+    ) -> Result<Evaluation> {
+        let evaluation = self.decide(plugin_id, request, now)?;
+        self.record(evaluation.clone())?;
+        Ok(evaluation)
+    }
+
+    /// Decide an access request, using the decision cache.
+    fn decide(
+        &mut self,
+        plugin_id: &PluginId,
+        request: &AccessRequest,
+        now: DateTime<Utc>,
+    ) -> Result<Evaluation> {
+        // Check if we have a cached decision
+        if let Some(decision) = self
+            .evaluation_cache
+            .get(plugin_id, request, Instant::now())
+        {
+            return Ok(Evaluation {
+                cached: true,
+                ..Evaluation::new(
+                    *plugin_id,
+                    request.clone(),
+                    decision.result,
+                    decision.matched_rule,
+                )
+            });
         }
 
-        // Get relevant policies
+        // Get relevant policies whose condition holds now
         let policies = self.get_relevant_policies(plugin_id, request)?;
+        let time_dependent = policies.iter().any(|policy| {
+            policy
+                .condition
+                .as_ref()
+                .is_some_and(|condition| condition.is_time_dependent())
+        });
+        let policies: Vec<PolicyRule> = policies
+            .into_iter()
+            .filter(|policy| {
+                policy
+                    .condition
+                    .as_ref()
+                    .is_none_or(|condition| condition.holds_at(now) != Some(false))
+            })
+            .collect();
+        let cacheable = !time_dependent;
 
         // If there are no policies, return NoPolicy
         if policies.is_empty() {
             let result = EvaluationResult::NoPolicy;
-            self.evaluation_cache.insert(cache_key, result.clone());
+            if cacheable {
+                self.evaluation_cache.insert(
+                    plugin_id,
+                    request,
+                    result.clone(),
+                    None,
+                    Instant::now(),
+                );
+            }
 
             return Ok(Evaluation::new(*plugin_id, request.clone(), result, None));
         }
@@ -96,9 +256,18 @@ where
             match result {
                 EvaluationResult::Allow
                 | EvaluationResult::Deny
-                | EvaluationResult::AllowWithConstraints(_) => {
+                | EvaluationResult::AllowWithConstraints(_)
+                | EvaluationResult::Throttle { .. } => {
                     // Cache the result
-                    self.evaluation_cache.insert(cache_key, result.clone());
+                    if cacheable {
+                        self.evaluation_cache.insert(
+                            plugin_id,
+                            request,
+                            result.clone(),
+                            Some(policy.clone()),
+                            Instant::now(),
+                        );
+                    }
 
                     return Ok(Evaluation::new(
                         *plugin_id,
@@ -118,7 +287,10 @@ where
 
         // If no policy explicitly allows or denies, default to deny
         let result = EvaluationResult::Deny;
-        self.evaluation_cache.insert(cache_key, result.clone());
+        if cacheable {
+            self.evaluation_cache
+                .insert(plugin_id, request, result.clone(), None, Instant::now());
+        }
 
         Ok(Evaluation::new(*plugin_id, request.clone(), result, None))
     }
@@ -146,16 +318,7 @@ where
             }
 
             // Check if the rule applies to this plugin
-            let plugin_match = match &rule.subject {
-                crate::model::PolicySubject::Any => true,
-                crate::model::PolicySubject::Plugin(id) => id == plugin_id,
-                crate::model::PolicySubject::Plugins(ids) => ids.contains(plugin_id),
-                // For now, these other subject types don't match any plugin
-                // In a real implementation, you'd check against plugin metadata
-                crate::model::PolicySubject::PluginName(_) => false,
-                crate::model::PolicySubject::PluginTag(_) => false,
-                crate::model::PolicySubject::PluginRole(_) => false,
-            };
+            let plugin_match = subject_matches(&rule.subject, plugin_id);
 
             // Check if the rule applies to this request type
             let request_match = match request {
@@ -191,6 +354,214 @@ where
             };plugin_match && request_match})
     }
 
+    /// Add a resource limit rule.
+    ///
+    /// # Arguments
+    ///
+    /// * `rule` - The resource limit rule.
+    pub fn add_resource_limit(&mut self, rule: ResourceLimitRule) {
+        self.resource_limits.push(rule);
+    }
+
+    /// Evaluate a plugin's usage of a resource against resource limit rules.
+    ///
+    /// When several rules apply, the strictest decision wins: a denial over
+    /// a throttle, and the longest throttle over shorter ones.
+    ///
+    /// # Arguments
+    ///
+    /// * `plugin_id` - The ID of the plugin using the resource.
+    /// * `resource` - The resource being used.
+    /// * `usage` - The current usage of the resource.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(EvaluationResult)` - `Allow`, `Throttle`, `Deny`, or `NoPolicy`
+    ///   if no rule limits the resource.
+    /// * `Err` - If the evaluation could not be performed.
+    pub fn evaluate_resource_usage(
+        &self,
+        plugin_id: &PluginId,
+        resource: &str,
+        usage: f64,
+    ) -> Result<EvaluationResult> {
+        let mut result = EvaluationResult::NoPolicy;
+
+        for rule in &self.resource_limits {
+            if rule.resource != resource || !subject_matches(&rule.subject, plugin_id) {
+                continue;
+            }
+
+            result = match (rule.action_for(usage), result) {
+                (_, EvaluationResult::Deny) | (PolicyAction::Deny, _) => EvaluationResult::Deny,
+                (
+                    PolicyAction::Throttle { delay },
+                    EvaluationResult::Throttle { delay: current },
+                ) => EvaluationResult::Throttle {
+                    delay: delay.max(current),
+                },
+                (PolicyAction::Throttle { delay }, _) => EvaluationResult::Throttle { delay },
+                (_, EvaluationResult::Throttle { delay }) => EvaluationResult::Throttle { delay },
+                _ => EvaluationResult::Allow,
+            };
+        }
+
+        Ok(result)
+    }
+
+    /// Check a plugin's usage of a resource.
+    ///
+    /// # Arguments
+    ///
+    /// * `plugin_id` - The ID of the plugin using the resource.
+    /// * `resource` - The resource being used.
+    /// * `usage` - The current usage of the resource.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(None)` - The plugin may proceed.
+    /// * `Ok(Some(delay))` - The plugin should back off for `delay` first.
+    /// * `Err` - If the usage exceeds a hard limit.
+    pub fn check_resource_usage(
+        &self,
+        plugin_id: &PluginId,
+        resource: &str,
+        usage: f64,
+    ) -> Result<Option<Duration>> {
+        match self.evaluate_resource_usage(plugin_id, resource, usage)? {
+            EvaluationResult::Deny => Err(PolicyError::ResourceLimitExceeded(format!(
+                "{} usage {} by plugin {}",
+                resource, usage, plugin_id
+            ))
+            .into()),
+            EvaluationResult::Throttle { delay } => Ok(Some(delay)),
+            _ => Ok(None),
+        }
+    }
+
+    /// Check a network access request, enforcing rate limits.
+    ///
+    /// # Arguments
+    ///
+    /// * `plugin_id` - The ID of the plugin making the request.
+    /// * `request` - The network access request.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Evaluation)` - The evaluation, if the access is allowed.
+    /// * `Err` - If the access is denied or exceeds a rate limit.
+    pub fn check_network_access(
+        &mut self,
+        plugin_id: &PluginId,
+        request: &AccessRequest,
+    ) -> Result<Evaluation> {
+        self.check_network_access_at(plugin_id, request, Utc::now())
+    }
+
+    /// Check a network access request at a given time, enforcing rate limits.
+    ///
+    /// An allowed access counts against every rate limit on a matching
+    /// network rule; the strictest limit applies. The decision, including
+    /// a denial for exceeding a rate limit, is recorded in the audit, if
+    /// one is set.
+    ///
+    /// # Arguments
+    ///
+    /// * `plugin_id` - The ID of the plugin making the request.
+    /// * `request` - The network access request.
+    /// * `now` - The time of the access.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Evaluation)` - The evaluation, if the access is allowed.
+    /// * `Err` - If the access is denied or exceeds a rate limit.
+    pub fn check_network_access_at(
+        &mut self,
+        plugin_id: &PluginId,
+        request: &AccessRequest,
+        now: DateTime<Utc>,
+    ) -> Result<Evaluation> {
+        let AccessRequest::Network { host, port, .. } = request else {
+            return Err(PolicyError::EvaluationFailed(format!(
+                "Not a network access request: {:?}",
+                request
+            ))
+            .into());
+        };
+
+        let evaluation = self.decide(plugin_id, request, now)?;
+        if !matches!(
+            evaluation.result,
+            EvaluationResult::Allow | EvaluationResult::AllowWithConstraints(_)
+        ) {
+            self.record(evaluation)?;
+            return Err(PolicyError::NetworkAccessViolation(format!(
+                "Plugin {} may not access {}:{}",
+                plugin_id, host, port
+            ))
+            .into());
+        }
+
+        if let Some((rule, limit)) = self.strictest_rate_limit(plugin_id, request, now)? {
+            if !self
+                .network_limiter
+                .try_acquire(plugin_id, host, &limit, now)
+            {
+                self.record(Evaluation {
+                    timestamp: now,
+                    ..Evaluation::new(
+                        *plugin_id,
+                        request.clone(),
+                        EvaluationResult::Deny,
+                        Some(rule),
+                    )
+                })?;
+                return Err(PolicyError::NetworkAccessViolation(format!(
+                    "Plugin {} exceeded rate limit for {}: {}",
+                    plugin_id, host, limit
+                ))
+                .into());
+            }
+        }
+
+        self.record(evaluation.clone())?;
+        Ok(evaluation)
+    }
+
+    /// Find the strictest rate limit on the network rules matching a request.
+    fn strictest_rate_limit(
+        &self,
+        plugin_id: &PluginId,
+        request: &AccessRequest,
+        now: DateTime<Utc>,
+    ) -> Result<Option<(PolicyRule, NetworkRateLimit)>> {
+        let strictest = self
+            .get_relevant_policies(plugin_id, request)?
+            .into_iter()
+            .filter(|rule| {
+                rule.condition
+                    .as_ref()
+                    .is_none_or(|condition| condition.holds_at(now) != Some(false))
+            })
+            .filter_map(|rule| match &rule.object {
+                PolicyObject::Network(network) => {
+                    network.rate_limit.map(|limit| (rule.clone(), limit))
+                }
+                _ => None,
+            })
+            .min_by(|(_, a), (_, b)| a.rate().total_cmp(&b.rate()));
+
+        Ok(strictest)
+    }
+
+    /// Record a decision in the audit, if one is set.
+    fn record(&self, evaluation: Evaluation) -> Result<()> {
+        match &self.audit {
+            Some(audit) => audit.record(evaluation),
+            None => Ok(()),
+        }
+    }
+
     /// Check if a request is allowed.
     ///
     /// # Arguments
@@ -213,6 +584,20 @@ where
     }
 }
 
+/// Check whether a rule subject covers a plugin.
+fn subject_matches(subject: &PolicySubject, plugin_id: &PluginId) -> bool {
+    match subject {
+        PolicySubject::Any => true,
+        PolicySubject::Plugin(id) => id == plugin_id,
+        PolicySubject::Plugins(ids) => ids.contains(plugin_id),
+        // For now, these other subject types don't match any plugin
+        // In a real implementation, you'd check against plugin metadata
+        PolicySubject::PluginName(_) => false,
+        PolicySubject::PluginTag(_) => false,
+        PolicySubject::PluginRole(_) => false,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -315,6 +700,7 @@ mod tests {
                 host: "example.com".to_string(),
                 port: Some(80),
                 protocol: None,
+                rate_limit: None,
             }),
             PolicyAction::Allow,
             None,
@@ -330,6 +716,7 @@ mod tests {
                 host: "evil.com".to_string(),
                 port: Some(80),
                 protocol: None,
+                rate_limit: None,
             }),
             PolicyAction::Deny,
             None,
@@ -417,4 +804,272 @@ mod tests {
         let evaluation = evaluator.evaluate(&plugin_id, &request).unwrap();
         assert!(matches!(evaluation.result, EvaluationResult::NoPolicy));
     }
+
+    #[test]
+    fn test_network_rate_limit_is_enforced_and_audited() {
+        use crate::model::NetworkRateLimit;
+
+        let policy_store = InMemoryPolicyStore::new();
+        let mut evaluator = PolicyEvaluator::new(policy_store.clone());
+        let audit = PolicyAudit::new(10);
+        evaluator.set_audit(audit.clone());
+        let plugin_id = PluginId::new();
+
+        let rule = PolicyRule::new(
+            "api-rate",
+            "API Rate",
+            "At most 2 connections per minute to the API",
+            PolicySubject::Plugin(plugin_id),
+            PolicyObject::Network(NetworkObject {
+                host: "api.example.com".to_string(),
+                port: None,
+                protocol: None,
+                rate_limit: Some(NetworkRateLimit::per_minute(2)),
+            }),
+            PolicyAction::Allow,
+            None,
+            0,
+        );
+        policy_store.add_rule(rule).unwrap();
+
+        let request = AccessRequest::Network {
+            host: "api.example.com".to_string(),
+            port: 443,
+            connect: true,
+            listen: false,
+        };
+        let start = Utc::now();
+        for secs in [0, 10] {
+            let now = start + chrono::Duration::seconds(secs);
+            assert!(evaluator
+                .check_network_access_at(&plugin_id, &request, now)
+                .is_ok());
+        }
+
+        // The third connection within a minute is a violation
+        let now = start + chrono::Duration::seconds(20);
+        assert!(evaluator
+            .check_network_access_at(&plugin_id, &request, now)
+            .is_err());
+        let violations = audit
+            .get_evaluations_by_result(&EvaluationResult::Deny)
+            .unwrap();
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].matched_rule.as_ref().unwrap().id, "api-rate");
+
+        // Once the first connection leaves the window another is allowed
+        let now = start + chrono::Duration::seconds(61);
+        assert!(evaluator
+            .check_network_access_at(&plugin_id, &request, now)
+            .is_ok());
+
+        // Hosts without a rule are violations too
+        let request = AccessRequest::Network {
+            host: "evil.com".to_string(),
+            port: 80,
+            connect: true,
+            listen: false,
+        };
+        assert!(evaluator
+            .check_network_access(&plugin_id, &request)
+            .is_err());
+        assert_eq!(
+            audit
+                .get_evaluations_by_result(&EvaluationResult::NoPolicy)
+                .unwrap()
+                .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_decision_cache_is_audited_and_invalidated() {
+        let policy_store = InMemoryPolicyStore::new();
+        let mut evaluator = PolicyEvaluator::new(policy_store);
+        let audit = PolicyAudit::new(10);
+        evaluator.set_audit(audit.clone());
+        let plugin_id = PluginId::new();
+
+        let rule = |id: &str, action| {
+            PolicyRule::new(
+                id,
+                id,
+                "Access to /data",
+                PolicySubject::Plugin(plugin_id),
+                PolicyObject::File(FileObject {
+                    path: "/data".to_string(),
+                    is_directory: true,
+                }),
+                action,
+                None,
+                0,
+            )
+        };
+        evaluator
+            .add_rule(rule("allow-data", PolicyAction::Allow))
+            .unwrap();
+
+        let request = AccessRequest::File {
+            path: PathBuf::from("/data/report.csv"),
+            read: true,
+            write: false,
+            execute: false,
+        };
+        assert!(!evaluator.evaluate(&plugin_id, &request).unwrap().cached);
+        let evaluation = evaluator.evaluate(&plugin_id, &request).unwrap();
+        assert!(evaluation.cached);
+        assert_eq!(evaluation.matched_rule.unwrap().id, "allow-data");
+
+        // Every decision is audited, cache hits included
+        let cached: Vec<bool> = audit
+            .get_evaluations(&plugin_id)
+            .unwrap()
+            .iter()
+            .map(|e| e.cached)
+            .collect();
+        assert_eq!(cached, vec![false, true]);
+
+        // Changing the plugin's rules drops its cached decisions
+        evaluator.remove_rule("allow-data").unwrap();
+        let evaluation = evaluator.evaluate(&plugin_id, &request).unwrap();
+        assert!(!evaluation.cached);
+        assert_eq!(evaluation.result, EvaluationResult::NoPolicy);
+
+        evaluator
+            .add_rule(rule("deny-data", PolicyAction::Deny))
+            .unwrap();
+        let evaluation = evaluator.evaluate(&plugin_id, &request).unwrap();
+        assert_eq!(evaluation.result, EvaluationResult::Deny);
+
+        // So does a capability change, and decisions expire
+        evaluator.invalidate_plugin(&plugin_id);
+        assert!(!evaluator.evaluate(&plugin_id, &request).unwrap().cached);
+        evaluator.set_cache_config(10, Duration::ZERO);
+        evaluator.evaluate(&plugin_id, &request).unwrap();
+        assert!(!evaluator.evaluate(&plugin_id, &request).unwrap().cached);
+    }
+
+    #[test]
+    fn test_time_window_rule_only_matches_inside_window() {
+        use crate::model::rule::PolicyTimezone;
+        use crate::model::PolicyCondition;
+        use chrono::Weekday;
+
+        let policy_store = InMemoryPolicyStore::new();
+        let mut evaluator = PolicyEvaluator::new(policy_store.clone());
+        let plugin_id = PluginId::new();
+
+        // Network access is only allowed on weekends
+        let mut rule = PolicyRule::new(
+            "weekend-network",
+            "Weekend Network",
+            "Allow network access on weekends",
+            PolicySubject::Plugin(plugin_id),
+            PolicyObject::Network(NetworkObject {
+                host: "*".to_string(),
+                port: None,
+                protocol: None,
+                rate_limit: None,
+            }),
+            PolicyAction::Allow,
+            None,
+            0,
+        );
+        rule.condition = Some(PolicyCondition::TimeWindow {
+            start: chrono::NaiveTime::MIN,
+            end: chrono::NaiveTime::MIN,
+            days: vec![Weekday::Sat, Weekday::Sun],
+            timezone: PolicyTimezone::Named("Europe/Berlin".to_string()),
+        });
+        policy_store.add_rule(rule).unwrap();
+
+        let request = AccessRequest::Network {
+            host: "example.com".to_string(),
+            port: 443,
+            connect: true,
+            listen: false,
+        };
+        let saturday: DateTime<Utc> = "2024-07-06T12:00:00Z".parse().unwrap();
+        let monday: DateTime<Utc> = "2024-07-08T12:00:00Z".parse().unwrap();
+
+        let evaluation = evaluator
+            .evaluate_at(&plugin_id, &request, saturday)
+            .unwrap();
+        assert_eq!(evaluation.result, EvaluationResult::Allow);
+
+        // The weekday result is not served from the cache
+        let evaluation = evaluator.evaluate_at(&plugin_id, &request, monday).unwrap();
+        assert_eq!(evaluation.result, EvaluationResult::NoPolicy);
+    }
+
+    #[test]
+    fn test_resource_usage_throttles_before_denying() {
+        let mut evaluator = PolicyEvaluator::new(InMemoryPolicyStore::new());
+        let plugin_id = PluginId::new();
+        let other_plugin = PluginId::new();
+
+        evaluator.add_resource_limit(ResourceLimitRule::new(
+            "memory-any",
+            PolicySubject::Any,
+            "memory",
+            100.0,
+            200.0,
+            50.0,
+        ));
+        evaluator.add_resource_limit(ResourceLimitRule::new(
+            "memory-plugin",
+            PolicySubject::Plugin(plugin_id),
+            "memory",
+            80.0,
+            300.0,
+            10.0,
+        ));
+
+        // Under every soft limit
+        assert_eq!(
+            evaluator
+                .evaluate_resource_usage(&plugin_id, "memory", 50.0)
+                .unwrap(),
+            EvaluationResult::Allow
+        );
+        assert_eq!(
+            evaluator
+                .check_resource_usage(&plugin_id, "memory", 50.0)
+                .unwrap(),
+            None
+        );
+
+        // Between the limits the longest throttle wins
+        assert_eq!(
+            evaluator
+                .check_resource_usage(&plugin_id, "memory", 150.0)
+                .unwrap(),
+            Some(Duration::from_secs(7))
+        );
+        assert_eq!(
+            evaluator
+                .check_resource_usage(&other_plugin, "memory", 150.0)
+                .unwrap(),
+            Some(Duration::from_secs(1))
+        );
+
+        // Over a hard limit the usage is denied
+        assert_eq!(
+            evaluator
+                .evaluate_resource_usage(&plugin_id, "memory", 250.0)
+                .unwrap(),
+            EvaluationResult::Deny
+        );
+        assert!(evaluator
+            .check_resource_usage(&plugin_id, "memory", 250.0)
+            .is_err());
+
+        // Unlimited resources are not restricted
+        assert_eq!(
+            evaluator
+                .evaluate_resource_usage(&plugin_id, "cpu", 1_000.0)
+                .unwrap(),
+            EvaluationResult::NoPolicy
+        );
+    }
 }
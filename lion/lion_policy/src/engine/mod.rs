@@ -5,7 +5,8 @@
 mod aggregator;
 mod audit;
 mod evaluator;
+mod rate_limit;
 
 pub use aggregator::PolicyAggregator;
-pub use audit::PolicyAudit;
+pub use audit::{PolicyAudit, PolicyAuditRollover, PolicyAuditSink, DEFAULT_POLICY_AUDIT_CAPACITY};
 pub use evaluator::PolicyEvaluator;
@@ -0,0 +1,193 @@
+//! Network rate limiting.
+//!
+//! This module provides sliding-window counters for network rate limits.
+
+use chrono::{DateTime, Utc};
+use lion_core::id::PluginId;
+use std::collections::{HashMap, VecDeque};
+use std::time::Duration;
+
+use crate::model::NetworkRateLimit;
+
+/// The default maximum number of (plugin, host) pairs tracked at once.
+pub(crate) const DEFAULT_MAX_TRACKED_HOSTS: usize = 10_000;
+
+/// Recent accesses of one plugin to one host.
+struct SlidingWindow {
+    /// The window the accesses are counted over.
+    window: Duration,
+
+    /// When the accesses happened, oldest first.
+    hits: VecDeque<DateTime<Utc>>,
+}
+
+impl SlidingWindow {
+    /// Drop accesses that have left the window.
+    fn prune(&mut self, now: DateTime<Utc>) {
+        while let Some(oldest) = self.hits.front() {
+            if age(*oldest, now) < self.window {
+                break;
+            }
+            self.hits.pop_front();
+        }
+    }
+
+    /// Whether no access is left within the window.
+    fn is_idle(&self, now: DateTime<Utc>) -> bool {
+        self.hits
+            .back()
+            .is_none_or(|newest| age(*newest, now) >= self.window)
+    }
+}
+
+/// How long ago `then` was, or zero if it is in the future.
+fn age(then: DateTime<Utc>, now: DateTime<Utc>) -> Duration {
+    (now - then).to_std().unwrap_or(Duration::ZERO)
+}
+
+/// Sliding-window counters keyed by plugin and host.
+///
+/// Memory is bounded: each counter keeps at most `max_requests` timestamps,
+/// and at most `max_keys` counters are kept. Idle counters are evicted first,
+/// then the least recently used.
+pub(crate) struct NetworkRateLimiter {
+    /// Counters by plugin and host.
+    windows: HashMap<(PluginId, String), SlidingWindow>,
+
+    /// The maximum number of counters kept.
+    max_keys: usize,
+}
+
+impl NetworkRateLimiter {
+    /// Create a new rate limiter.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_keys` - The maximum number of (plugin, host) pairs tracked.
+    ///
+    /// # Returns
+    ///
+    /// A new rate limiter.
+    pub(crate) fn new(max_keys: usize) -> Self {
+        Self {
+            windows: HashMap::new(),
+            max_keys,
+        }
+    }
+
+    /// Count an access if it is within the limit.
+    ///
+    /// # Arguments
+    ///
+    /// * `plugin_id` - The plugin accessing the host.
+    /// * `host` - The host being accessed.
+    /// * `limit` - The limit that applies.
+    /// * `now` - When the access happens.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the access was counted, `false` if it would exceed the limit.
+    pub(crate) fn try_acquire(
+        &mut self,
+        plugin_id: &PluginId,
+        host: &str,
+        limit: &NetworkRateLimit,
+        now: DateTime<Utc>,
+    ) -> bool {
+        let key = (*plugin_id, host.to_string());
+        if !self.windows.contains_key(&key) {
+            self.make_room(now);
+        }
+
+        let entry = self.windows.entry(key).or_insert_with(|| SlidingWindow {
+            window: limit.window,
+            hits: VecDeque::new(),
+        });
+        entry.window = limit.window;
+        entry.prune(now);
+
+        if entry.hits.len() >= limit.max_requests as usize {
+            return false;
+        }
+        entry.hits.push_back(now);
+        true
+    }
+
+    /// The number of (plugin, host) pairs currently tracked.
+    #[cfg(test)]
+    pub(crate) fn tracked(&self) -> usize {
+        self.windows.len()
+    }
+
+    /// Evict counters until a new one fits.
+    fn make_room(&mut self, now: DateTime<Utc>) {
+        if self.windows.len() < self.max_keys {
+            return;
+        }
+
+        self.windows.retain(|_, window| !window.is_idle(now));
+
+        while self.windows.len() >= self.max_keys {
+            let least_recent = self
+                .windows
+                .iter()
+                .min_by_key(|(_, window)| window.hits.back().copied())
+                .map(|(key, _)| key.clone());
+            match least_recent {
+                Some(key) => {
+                    self.windows.remove(&key);
+                }
+                None => break,
+            }
+        }
+    }
+}
+
+impl Default for NetworkRateLimiter {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_TRACKED_HOSTS)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_window_slides() {
+        let mut limiter = NetworkRateLimiter::default();
+        let plugin_id = PluginId::new();
+        let limit = NetworkRateLimit::new(2, Duration::from_secs(10));
+        let start = Utc::now();
+        let at = |secs| start + chrono::Duration::seconds(secs);
+
+        assert!(limiter.try_acquire(&plugin_id, "api.example.com", &limit, at(0)));
+        assert!(limiter.try_acquire(&plugin_id, "api.example.com", &limit, at(5)));
+        assert!(!limiter.try_acquire(&plugin_id, "api.example.com", &limit, at(9)));
+
+        // Other hosts are counted separately
+        assert!(limiter.try_acquire(&plugin_id, "cdn.example.com", &limit, at(9)));
+
+        // The first access has left the window
+        assert!(limiter.try_acquire(&plugin_id, "api.example.com", &limit, at(10)));
+        assert!(!limiter.try_acquire(&plugin_id, "api.example.com", &limit, at(11)));
+    }
+
+    #[test]
+    fn test_tracked_hosts_are_bounded() {
+        let mut limiter = NetworkRateLimiter::new(2);
+        let plugin_id = PluginId::new();
+        let limit = NetworkRateLimit::new(1, Duration::from_secs(10));
+        let start = Utc::now();
+        let at = |secs| start + chrono::Duration::seconds(secs);
+
+        limiter.try_acquire(&plugin_id, "a", &limit, at(0));
+        limiter.try_acquire(&plugin_id, "b", &limit, at(1));
+        limiter.try_acquire(&plugin_id, "c", &limit, at(2));
+        assert_eq!(limiter.tracked(), 2);
+
+        // "a" was least recently used and evicted, "b" is still limited
+        assert!(!limiter.try_acquire(&plugin_id, "b", &limit, at(3)));
+        assert!(limiter.try_acquire(&plugin_id, "a", &limit, at(4)));
+    }
+}
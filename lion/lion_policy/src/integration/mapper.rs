@@ -184,6 +184,7 @@ where
                 PolicyAction::AllowWithConstraints(_) => return Ok(true),
                 PolicyAction::TransformToConstraints(_) => return Ok(true),
                 PolicyAction::Audit => {}
+                PolicyAction::Throttle { .. } => return Ok(true),
             }
         }
 
@@ -150,6 +150,7 @@ where
                     }
                 }
                 PolicyAction::Audit => {}
+                PolicyAction::Throttle { .. } => {}
             }
         }
 
@@ -404,6 +405,9 @@ where
                 PolicyAction::Audit => {
                     // Continue checking other policies
                 }
+                PolicyAction::Throttle { .. } => {
+                    return Ok(EvaluationResult::from(&policy.action));
+                }
             }
 
             // Special handling for file paths - check if the path matches the policy object
@@ -22,9 +22,12 @@ pub mod model;
 pub mod store;
 
 // Re-export key types and traits for convenience
-pub use engine::{PolicyAggregator, PolicyAudit, PolicyEvaluator};
+pub use engine::{
+    PolicyAggregator, PolicyAudit, PolicyAuditRollover, PolicyAuditSink, PolicyEvaluator,
+};
 pub use integration::{CapabilityMapper, ConstraintResolver};
 pub use model::{
-    Constraint, PolicyAction, PolicyCondition, PolicyObject, PolicyRule, PolicySubject,
+    Constraint, NetworkRateLimit, PolicyAction, PolicyCondition, PolicyObject, PolicyRule,
+    PolicySubject, PolicyTimezone, ResourceLimitRule,
 };
 pub use store::{InMemoryPolicyStore, PolicyStore};
@@ -6,6 +6,7 @@ use chrono::{DateTime, Utc};
 use lion_core::id::PluginId;
 use serde::{Deserialize, Serialize};
 use std::fmt;
+use std::time::Duration;
 
 use crate::model::{Constraint, PolicyAction, PolicyRule};
 
@@ -26,6 +27,10 @@ pub struct Evaluation {
 
     /// When the evaluation was performed.
     pub timestamp: DateTime<Utc>,
+
+    /// Whether the result came from the decision cache.
+    #[serde(default)]
+    pub cached: bool,
 }
 
 impl Evaluation {
@@ -53,6 +58,7 @@ impl Evaluation {
             result,
             matched_rule,
             timestamp: Utc::now(),
+            cached: false,
         }
     }
 }
@@ -74,6 +80,12 @@ pub enum EvaluationResult {
 
     /// The action should be audited.
     Audit,
+
+    /// The action is allowed once the caller has backed off for `delay`.
+    Throttle {
+        /// How long the caller should wait before proceeding.
+        delay: Duration,
+    },
 }
 
 impl fmt::Display for EvaluationResult {
@@ -93,6 +105,7 @@ impl fmt::Display for EvaluationResult {
             }
             Self::NoPolicy => write!(f, "No policy"),
             Self::Audit => write!(f, "Audit"),
+            Self::Throttle { delay } => write!(f, "Throttle for {:?}", delay),
         }
     }
 }
@@ -125,6 +138,7 @@ impl From<&PolicyAction> for EvaluationResult {
                 Self::AllowWithConstraints(constraints)
             }
             PolicyAction::Audit => Self::Audit,
+            PolicyAction::Throttle { delay } => Self::Throttle { delay: *delay },
         }
     }
 }
@@ -8,4 +8,7 @@ pub mod rule;
 
 pub use constraint::Constraint;
 pub use evaluation::{Evaluation, EvaluationResult};
-pub use rule::{PolicyAction, PolicyCondition, PolicyObject, PolicyRule, PolicySubject};
+pub use rule::{
+    NetworkRateLimit, PolicyAction, PolicyCondition, PolicyObject, PolicyRule, PolicySubject,
+    PolicyTimezone, ResourceLimitRule,
+};
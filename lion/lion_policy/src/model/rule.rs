@@ -2,10 +2,11 @@
 //!
 //! This module defines the core policy rule types.
 
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Datelike, NaiveTime, Utc, Weekday};
 use lion_core::id::PluginId;
 use serde::{Deserialize, Serialize};
 use std::fmt;
+use std::time::Duration;
 
 /// A policy rule.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -244,6 +245,10 @@ pub struct NetworkObject {
 
     /// The protocol.
     pub protocol: Option<String>,
+
+    /// How often each plugin may access the host.
+    #[serde(default)]
+    pub rate_limit: Option<NetworkRateLimit>,
 }
 
 impl fmt::Display for NetworkObject {
@@ -258,10 +263,66 @@ impl fmt::Display for NetworkObject {
             write!(f, " using {}", protocol)?;
         }
 
+        if let Some(rate_limit) = &self.rate_limit {
+            write!(f, " at most {}", rate_limit)?;
+        }
+
         Ok(())
     }
 }
 
+/// A limit on how often a plugin may access a network host.
+///
+/// The limit applies over a sliding window: at any moment, at most
+/// `max_requests` accesses may have happened within the last `window`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct NetworkRateLimit {
+    /// The maximum number of accesses within the window.
+    pub max_requests: u32,
+
+    /// The length of the sliding window.
+    pub window: Duration,
+}
+
+impl NetworkRateLimit {
+    /// Create a new network rate limit.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_requests` - The maximum number of accesses within the window.
+    /// * `window` - The length of the sliding window.
+    ///
+    /// # Returns
+    ///
+    /// A new network rate limit.
+    pub fn new(max_requests: u32, window: Duration) -> Self {
+        Self {
+            max_requests,
+            window,
+        }
+    }
+
+    /// Create a limit of `max_requests` accesses per minute.
+    pub fn per_minute(max_requests: u32) -> Self {
+        Self::new(max_requests, Duration::from_secs(60))
+    }
+
+    /// Accesses per second allowed by this limit.
+    pub fn rate(&self) -> f64 {
+        if self.window.is_zero() {
+            f64::INFINITY
+        } else {
+            f64::from(self.max_requests) / self.window.as_secs_f64()
+        }
+    }
+}
+
+impl fmt::Display for NetworkRateLimit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} times per {:?}", self.max_requests, self.window)
+    }
+}
+
 /// A plugin call object.
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct PluginCallObject {
@@ -336,6 +397,12 @@ pub enum PolicyAction {
 
     /// Audit the action.
     Audit,
+
+    /// Allow the action once the caller has backed off for `delay`.
+    Throttle {
+        /// How long the caller should wait before proceeding.
+        delay: Duration,
+    },
 }
 
 impl fmt::Display for PolicyAction {
@@ -364,6 +431,7 @@ impl fmt::Display for PolicyAction {
                 write!(f, "]")
             }
             Self::Audit => write!(f, "Audit"),
+            Self::Throttle { delay } => write!(f, "Throttle for {:?}", delay),
         }
     }
 }
@@ -388,6 +456,152 @@ pub enum PolicyCondition {
 
     /// A not condition (logical NOT).
     Not(Box<PolicyCondition>),
+
+    /// A recurring daily time window, such as business hours.
+    TimeWindow {
+        /// When the window opens (local time, inclusive).
+        start: NaiveTime,
+
+        /// When the window closes (local time, exclusive). A window that
+        /// closes at or before it opens spans midnight.
+        end: NaiveTime,
+
+        /// The days the window opens on (every day if empty).
+        days: Vec<Weekday>,
+
+        /// The timezone the window is expressed in.
+        timezone: PolicyTimezone,
+    },
+}
+
+impl PolicyCondition {
+    /// Check whether this condition depends on the current time.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the condition contains a time window.
+    pub fn is_time_dependent(&self) -> bool {
+        match self {
+            Self::TimeWindow { .. } => true,
+            Self::All(conditions) | Self::Any(conditions) => {
+                conditions.iter().any(Self::is_time_dependent)
+            }
+            Self::Not(condition) => condition.is_time_dependent(),
+            Self::Time(_) | Self::Resource(_) | Self::Context(_) => false,
+        }
+    }
+
+    /// Evaluate this condition at the given time.
+    ///
+    /// Only time windows are evaluated here; conditions that need request
+    /// context are left undecided.
+    ///
+    /// # Arguments
+    ///
+    /// * `now` - The time to evaluate the condition at.
+    ///
+    /// # Returns
+    ///
+    /// `Some(result)` if the condition could be decided, `None` otherwise.
+    pub fn holds_at(&self, now: DateTime<Utc>) -> Option<bool> {
+        match self {
+            Self::TimeWindow {
+                start,
+                end,
+                days,
+                timezone,
+            } => Some(time_window_contains(*start, *end, days, timezone, now)),
+            Self::All(conditions) => {
+                let results: Vec<Option<bool>> =
+                    conditions.iter().map(|c| c.holds_at(now)).collect();
+                if results.contains(&Some(false)) {
+                    Some(false)
+                } else if results.iter().all(Option::is_some) {
+                    Some(true)
+                } else {
+                    None
+                }
+            }
+            Self::Any(conditions) => {
+                let results: Vec<Option<bool>> =
+                    conditions.iter().map(|c| c.holds_at(now)).collect();
+                if results.contains(&Some(true)) {
+                    Some(true)
+                } else if results.iter().all(Option::is_some) {
+                    Some(false)
+                } else {
+                    None
+                }
+            }
+            Self::Not(condition) => condition.holds_at(now).map(|result| !result),
+            Self::Time(_) | Self::Resource(_) | Self::Context(_) => None,
+        }
+    }
+}
+
+/// The timezone of a time window.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PolicyTimezone {
+    /// An IANA timezone name (e.g. "Europe/Berlin"), following its
+    /// daylight saving rules.
+    Named(String),
+
+    /// A fixed offset from UTC in seconds east, never adjusted for
+    /// daylight saving.
+    FixedOffset(i32),
+}
+
+impl PolicyTimezone {
+    /// Convert a time to the local time of day and weekday of this timezone.
+    ///
+    /// # Returns
+    ///
+    /// `None` if the timezone name or offset is invalid.
+    fn localize(&self, now: DateTime<Utc>) -> Option<(NaiveTime, Weekday)> {
+        match self {
+            Self::Named(name) => {
+                let tz: chrono_tz::Tz = name.parse().ok()?;
+                let local = now.with_timezone(&tz);
+                Some((local.time(), local.weekday()))
+            }
+            Self::FixedOffset(seconds) => {
+                let offset = chrono::FixedOffset::east_opt(*seconds)?;
+                let local = now.with_timezone(&offset);
+                Some((local.time(), local.weekday()))
+            }
+        }
+    }
+}
+
+/// Check whether a time falls inside a time window.
+///
+/// An invalid timezone never matches, so a misconfigured window fails closed.
+fn time_window_contains(
+    start: NaiveTime,
+    end: NaiveTime,
+    days: &[Weekday],
+    timezone: &PolicyTimezone,
+    now: DateTime<Utc>,
+) -> bool {
+    let Some((time, weekday)) = timezone.localize(now) else {
+        return false;
+    };
+
+    // The day the window containing `time` opened on
+    let opened_on = if start < end {
+        if time < start || time >= end {
+            return false;
+        }
+        weekday
+    } else if time >= start {
+        weekday
+    } else if time < end {
+        weekday.pred()
+    } else {
+        return false;
+    };
+
+    days.is_empty() || days.contains(&opened_on)
 }
 
 /// A time-based condition.
@@ -422,6 +636,91 @@ pub struct ResourceCondition {
     pub max_network: Option<usize>,
 }
 
+/// A limit on a plugin's usage of a resource.
+///
+/// Usage up to the soft limit is allowed and usage above the hard limit is
+/// denied. In between, the caller is throttled token-bucket style: the
+/// excess over the soft limit must drain at `refill_rate` units per second
+/// before the caller proceeds.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResourceLimitRule {
+    /// The unique ID of this rule.
+    pub id: String,
+
+    /// The plugins this rule applies to.
+    pub subject: PolicySubject,
+
+    /// The limited resource (e.g. "cpu", "memory").
+    pub resource: String,
+
+    /// Usage above which callers are throttled.
+    pub soft_limit: f64,
+
+    /// Usage above which callers are denied.
+    pub hard_limit: f64,
+
+    /// Units per second by which usage above the soft limit drains.
+    pub refill_rate: f64,
+}
+
+impl ResourceLimitRule {
+    /// Create a new resource limit rule.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The unique ID of this rule.
+    /// * `subject` - The plugins this rule applies to.
+    /// * `resource` - The limited resource.
+    /// * `soft_limit` - Usage above which callers are throttled.
+    /// * `hard_limit` - Usage above which callers are denied.
+    /// * `refill_rate` - Units per second by which excess usage drains.
+    ///
+    /// # Returns
+    ///
+    /// A new resource limit rule.
+    pub fn new(
+        id: impl Into<String>,
+        subject: PolicySubject,
+        resource: impl Into<String>,
+        soft_limit: f64,
+        hard_limit: f64,
+        refill_rate: f64,
+    ) -> Self {
+        Self {
+            id: id.into(),
+            subject,
+            resource: resource.into(),
+            soft_limit,
+            hard_limit,
+            refill_rate,
+        }
+    }
+
+    /// Decide what to do about the given usage.
+    ///
+    /// # Arguments
+    ///
+    /// * `usage` - The current usage of the resource.
+    ///
+    /// # Returns
+    ///
+    /// `Allow`, `Throttle` with the time needed to drain the excess, or `Deny`.
+    pub fn action_for(&self, usage: f64) -> PolicyAction {
+        if usage > self.hard_limit {
+            PolicyAction::Deny
+        } else if usage > self.soft_limit {
+            let delay = if self.refill_rate > 0.0 {
+                Duration::from_secs_f64((usage - self.soft_limit) / self.refill_rate)
+            } else {
+                Duration::MAX
+            };
+            PolicyAction::Throttle { delay }
+        } else {
+            PolicyAction::Allow
+        }
+    }
+}
+
 /// A context-based condition.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ContextCondition {
@@ -486,4 +785,94 @@ mod tests {
         assert!(expired_rule.is_expired());
         assert!(!active_rule.is_expired());
     }
+
+    fn business_hours(timezone: PolicyTimezone) -> PolicyCondition {
+        PolicyCondition::TimeWindow {
+            start: NaiveTime::from_hms_opt(9, 0, 0).unwrap(),
+            end: NaiveTime::from_hms_opt(17, 0, 0).unwrap(),
+            days: vec![
+                Weekday::Mon,
+                Weekday::Tue,
+                Weekday::Wed,
+                Weekday::Thu,
+                Weekday::Fri,
+            ],
+            timezone,
+        }
+    }
+
+    fn utc(s: &str) -> DateTime<Utc> {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_time_window_fixed_offset() {
+        // UTC+2
+        let condition = business_hours(PolicyTimezone::FixedOffset(2 * 3600));
+
+        // Monday 08:30 UTC is 10:30 local
+        assert_eq!(condition.holds_at(utc("2024-07-01T08:30:00Z")), Some(true));
+        // Monday 15:30 UTC is 17:30 local, after closing
+        assert_eq!(condition.holds_at(utc("2024-07-01T15:30:00Z")), Some(false));
+        // Saturday
+        assert_eq!(condition.holds_at(utc("2024-07-06T08:30:00Z")), Some(false));
+        assert!(condition.is_time_dependent());
+    }
+
+    #[test]
+    fn test_time_window_named_timezone_follows_dst() {
+        let condition = business_hours(PolicyTimezone::Named("America/New_York".to_string()));
+
+        // 13:30 UTC is 09:30 EDT in summer but 08:30 EST in winter
+        assert_eq!(condition.holds_at(utc("2024-07-01T13:30:00Z")), Some(true));
+        assert_eq!(condition.holds_at(utc("2024-01-15T13:30:00Z")), Some(false));
+
+        // Unknown timezones never match
+        let invalid = business_hours(PolicyTimezone::Named("Mars/Olympus".to_string()));
+        assert_eq!(invalid.holds_at(utc("2024-07-01T13:30:00Z")), Some(false));
+    }
+
+    #[test]
+    fn test_time_window_spanning_midnight() {
+        // Maintenance from Saturday 22:00 to Sunday 02:00 UTC
+        let condition = PolicyCondition::TimeWindow {
+            start: NaiveTime::from_hms_opt(22, 0, 0).unwrap(),
+            end: NaiveTime::from_hms_opt(2, 0, 0).unwrap(),
+            days: vec![Weekday::Sat],
+            timezone: PolicyTimezone::FixedOffset(0),
+        };
+
+        assert_eq!(condition.holds_at(utc("2024-07-06T23:00:00Z")), Some(true));
+        assert_eq!(condition.holds_at(utc("2024-07-07T01:00:00Z")), Some(true));
+        assert_eq!(condition.holds_at(utc("2024-07-07T23:00:00Z")), Some(false));
+        assert_eq!(condition.holds_at(utc("2024-07-06T01:00:00Z")), Some(false));
+
+        // Combined with conditions that cannot be decided here
+        let combined = PolicyCondition::All(vec![
+            condition.clone(),
+            PolicyCondition::Context(ContextCondition {
+                context: std::collections::HashMap::new(),
+            }),
+        ]);
+        assert_eq!(combined.holds_at(utc("2024-07-07T23:00:00Z")), Some(false));
+        assert_eq!(combined.holds_at(utc("2024-07-06T23:00:00Z")), None);
+        assert_eq!(
+            PolicyCondition::Not(Box::new(condition)).holds_at(utc("2024-07-06T23:00:00Z")),
+            Some(false)
+        );
+    }
+
+    #[test]
+    fn test_resource_limit_rule_throttles_between_limits() {
+        let rule = ResourceLimitRule::new("limit1", PolicySubject::Any, "cpu", 50.0, 90.0, 10.0);
+
+        assert_eq!(rule.action_for(40.0), PolicyAction::Allow);
+        assert_eq!(
+            rule.action_for(70.0),
+            PolicyAction::Throttle {
+                delay: Duration::from_secs(2)
+            }
+        );
+        assert_eq!(rule.action_for(95.0), PolicyAction::Deny);
+    }
 }
@@ -0,0 +1,219 @@
+//! Policy decision cache.
+//!
+//! This module provides a bounded cache of policy decisions.
+
+use lion_core::id::PluginId;
+use lion_core::types::AccessRequest;
+use lru::LruCache;
+use std::num::NonZeroUsize;
+use std::time::{Duration, Instant};
+
+use crate::model::{EvaluationResult, PolicyRule};
+
+/// The default maximum number of cached decisions.
+pub const DEFAULT_DECISION_CACHE_CAPACITY: usize = 10_000;
+
+/// The default time a cached decision stays valid.
+pub const DEFAULT_DECISION_CACHE_TTL: Duration = Duration::from_secs(60);
+
+/// A cached policy decision.
+#[derive(Debug, Clone)]
+pub struct CachedDecision {
+    /// The result of the evaluation.
+    pub result: EvaluationResult,
+
+    /// The rule that was matched.
+    pub matched_rule: Option<PolicyRule>,
+
+    /// When the decision was cached.
+    cached_at: Instant,
+}
+
+/// A least-recently-used cache of policy decisions with a time to live.
+///
+/// Decisions are keyed by plugin and access request, which names both the
+/// resource and the action.
+pub struct DecisionCache {
+    /// The cached decisions.
+    entries: LruCache<(PluginId, AccessRequest), CachedDecision>,
+
+    /// How long a decision stays valid.
+    ttl: Duration,
+}
+
+impl DecisionCache {
+    /// Create a new decision cache.
+    ///
+    /// # Arguments
+    ///
+    /// * `capacity` - The maximum number of cached decisions (at least one).
+    /// * `ttl` - How long a decision stays valid.
+    ///
+    /// # Returns
+    ///
+    /// A new decision cache.
+    pub fn new(capacity: usize, ttl: Duration) -> Self {
+        Self {
+            entries: LruCache::new(NonZeroUsize::new(capacity).unwrap_or(NonZeroUsize::MIN)),
+            ttl,
+        }
+    }
+
+    /// Get a cached decision that is still valid.
+    ///
+    /// # Arguments
+    ///
+    /// * `plugin_id` - The ID of the plugin making the request.
+    /// * `request` - The access request.
+    /// * `now` - The current time.
+    ///
+    /// # Returns
+    ///
+    /// The cached decision, or `None` if there is none or it has expired.
+    pub fn get(
+        &mut self,
+        plugin_id: &PluginId,
+        request: &AccessRequest,
+        now: Instant,
+    ) -> Option<CachedDecision> {
+        let key = (*plugin_id, request.clone());
+        let decision = self.entries.get(&key)?;
+
+        if now.saturating_duration_since(decision.cached_at) >= self.ttl {
+            self.entries.pop(&key);
+            return None;
+        }
+
+        Some(decision.clone())
+    }
+
+    /// Cache a decision, evicting the least recently used one if full.
+    ///
+    /// # Arguments
+    ///
+    /// * `plugin_id` - The ID of the plugin making the request.
+    /// * `request` - The access request.
+    /// * `result` - The result of the evaluation.
+    /// * `matched_rule` - The rule that was matched.
+    /// * `now` - The current time.
+    pub fn insert(
+        &mut self,
+        plugin_id: &PluginId,
+        request: &AccessRequest,
+        result: EvaluationResult,
+        matched_rule: Option<PolicyRule>,
+        now: Instant,
+    ) {
+        self.entries.put(
+            (*plugin_id, request.clone()),
+            CachedDecision {
+                result,
+                matched_rule,
+                cached_at: now,
+            },
+        );
+    }
+
+    /// Drop every cached decision for a plugin.
+    ///
+    /// # Arguments
+    ///
+    /// * `plugin_id` - The ID of the plugin.
+    pub fn invalidate_plugin(&mut self, plugin_id: &PluginId) {
+        let keys: Vec<_> = self
+            .entries
+            .iter()
+            .filter(|((id, _), _)| id == plugin_id)
+            .map(|(key, _)| key.clone())
+            .collect();
+
+        for key in keys {
+            self.entries.pop(&key);
+        }
+    }
+
+    /// Drop every cached decision.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    /// The number of cached decisions, including expired ones not yet dropped.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether the cache is empty.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+impl Default for DecisionCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_DECISION_CACHE_CAPACITY, DEFAULT_DECISION_CACHE_TTL)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(function: &str) -> AccessRequest {
+        AccessRequest::PluginCall {
+            plugin_id: "target".to_string(),
+            function: function.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_entries_expire_and_are_evicted() {
+        let mut cache = DecisionCache::new(2, Duration::from_secs(10));
+        let plugin_id = PluginId::new();
+        let start = Instant::now();
+
+        cache.insert(
+            &plugin_id,
+            &request("a"),
+            EvaluationResult::Allow,
+            None,
+            start,
+        );
+        assert!(cache.get(&plugin_id, &request("a"), start).is_some());
+        assert!(cache
+            .get(&plugin_id, &request("a"), start + Duration::from_secs(10))
+            .is_none());
+
+        // The least recently used decision is evicted when full
+        cache.insert(
+            &plugin_id,
+            &request("a"),
+            EvaluationResult::Allow,
+            None,
+            start,
+        );
+        cache.insert(
+            &plugin_id,
+            &request("b"),
+            EvaluationResult::Deny,
+            None,
+            start,
+        );
+        cache.get(&plugin_id, &request("a"), start);
+        cache.insert(
+            &plugin_id,
+            &request("c"),
+            EvaluationResult::Allow,
+            None,
+            start,
+        );
+        assert!(cache.get(&plugin_id, &request("a"), start).is_some());
+        assert!(cache.get(&plugin_id, &request("b"), start).is_none());
+
+        // Invalidation only affects the given plugin
+        let other = PluginId::new();
+        cache.insert(&other, &request("a"), EvaluationResult::Allow, None, start);
+        cache.invalidate_plugin(&plugin_id);
+        assert_eq!(cache.len(), 1);
+        assert!(cache.get(&other, &request("a"), start).is_some());
+    }
+}
@@ -2,8 +2,12 @@
 //!
 //! This module provides storage for policies.
 
+mod cache;
 mod in_memory;
 
+pub use cache::{
+    CachedDecision, DecisionCache, DEFAULT_DECISION_CACHE_CAPACITY, DEFAULT_DECISION_CACHE_TTL,
+};
 pub use in_memory::InMemoryPolicyStore;
 
 use crate::model::PolicyRule;
@@ -3,12 +3,17 @@
 //! Provides capability creation, granting, checking, and revocation based on
 //! a unified capability-policy security model.
 
-use std::collections::{HashMap, HashSet};
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::fmt;
+use std::io::Write;
+use std::sync::{Arc, Weak};
+use std::time::Duration;
 
 use anyhow::Result;
+use chrono::{DateTime, Utc};
 use lion_core::CapabilityId;
 use parking_lot::RwLock;
+use serde::Serialize;
 use thiserror::Error;
 use tracing::{error, info};
 
@@ -42,8 +47,92 @@ pub enum CapabilityError {
 
     #[error("Policy evaluation error: {0}")]
     PolicyError(String),
+
+    #[error("Invalid capability grant: {0}")]
+    InvalidGrant(String),
+
+    #[error("Delegating capability {0} to {1} would create a delegation cycle")]
+    DelegationCycle(CapabilityId, String),
 }
 
+/// A capability to grant as part of a batch
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CapabilityGrant {
+    /// The object the capability targets
+    pub object: String,
+
+    /// The operations allowed with the capability
+    pub rights: Vec<String>,
+}
+
+impl CapabilityGrant {
+    /// Create a grant of `rights` on `object`
+    pub fn new(object: impl Into<String>, rights: Vec<String>) -> Self {
+        Self {
+            object: object.into(),
+            rights,
+        }
+    }
+}
+
+/// Default number of entries kept in the capability audit trail
+pub const DEFAULT_CAPABILITY_AUDIT_CAPACITY: usize = 10_000;
+
+/// Change recorded in the capability audit trail
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum CapabilityAuditAction {
+    /// A single capability was granted
+    Granted {
+        capability_id: CapabilityId,
+        object: String,
+    },
+
+    /// A capability and the capabilities derived from it were revoked
+    Revoked { capability_ids: Vec<CapabilityId> },
+
+    /// A capability was delegated to another subject
+    Delegated {
+        capability_id: CapabilityId,
+        parent_id: CapabilityId,
+        to: String,
+    },
+
+    /// A capability was revoked because one it was derived from was revoked
+    CascadeRevoked {
+        capability_id: CapabilityId,
+        revoked_with: CapabilityId,
+    },
+
+    /// A batch of capabilities was granted at once
+    BulkGranted { capability_ids: Vec<CapabilityId> },
+
+    /// Every capability of the subject, and those derived from them, was revoked
+    RevokedAll { capability_ids: Vec<CapabilityId> },
+
+    /// Expired capabilities were purged
+    Expired { capability_ids: Vec<CapabilityId> },
+}
+
+/// Entry in the capability audit trail
+#[derive(Debug, Clone, Serialize)]
+pub struct CapabilityAuditEntry {
+    /// When the change happened
+    pub timestamp: DateTime<Utc>,
+
+    /// The subject whose capabilities changed
+    pub subject: String,
+
+    /// What changed
+    pub action: CapabilityAuditAction,
+}
+
+/// Callback receiving each new capability audit entry
+pub type CapabilityAuditSink = Arc<dyn Fn(&CapabilityAuditEntry) + Send + Sync>;
+
+/// Callback receiving audit entries evicted from a full trail, e.g. to persist them
+pub type CapabilityAuditRollover = Arc<dyn Fn(Vec<CapabilityAuditEntry>) + Send + Sync>;
+
 /// Entry in the capability table
 #[derive(Debug, Clone)]
 struct CapabilityEntry {
@@ -63,16 +152,38 @@ struct CapabilityEntry {
     valid: bool,
 
     /// Parent capability, if this was derived from another
-    _parent: Option<CapabilityId>,
+    parent: Option<CapabilityId>,
 
     /// Child capabilities derived from this one
     children: Vec<CapabilityId>,
+
+    /// When this capability expires, if it does
+    expires_at: Option<DateTime<Utc>>,
+}
+
+impl CapabilityEntry {
+    /// Whether this capability is valid and not expired at `now`
+    fn is_live(&self, now: DateTime<Utc>) -> bool {
+        self.valid && self.expires_at.is_none_or(|expires_at| expires_at > now)
+    }
 }
 
 /// The capability manager handles the granting, checking, and revoking of capabilities
 pub struct CapabilityManager {
     /// Main capability table, protected by a read-write lock
     data: RwLock<CapabilityStore>,
+
+    /// Bounded trail of grants and revocations, oldest first
+    audit: RwLock<VecDeque<CapabilityAuditEntry>>,
+
+    /// Maximum number of entries kept in the audit trail
+    audit_capacity: usize,
+
+    /// Callbacks pushing new audit entries to external sinks
+    audit_sinks: RwLock<Vec<CapabilityAuditSink>>,
+
+    /// Callback handed the entries evicted from the audit trail
+    audit_rollover: RwLock<Option<CapabilityAuditRollover>>,
 }
 
 /// Internal store for capabilities
@@ -104,11 +215,21 @@ impl PolicyEvaluator {
 impl CapabilityManager {
     /// Create a new capability manager
     pub fn new() -> Result<Self> {
+        Self::with_audit_capacity(DEFAULT_CAPABILITY_AUDIT_CAPACITY)
+    }
+
+    /// Create a new capability manager keeping at most `audit_capacity` audit
+    /// entries, evicting the oldest first
+    pub fn with_audit_capacity(audit_capacity: usize) -> Result<Self> {
         Ok(Self {
             data: RwLock::new(CapabilityStore {
                 capabilities: HashMap::new(),
                 subject_index: HashMap::new(),
             }),
+            audit: RwLock::new(VecDeque::new()),
+            audit_capacity: audit_capacity.max(1),
+            audit_sinks: RwLock::new(Vec::new()),
+            audit_rollover: RwLock::new(None),
         })
     }
 
@@ -119,75 +240,251 @@ impl CapabilityManager {
         object: String,
         rights: Vec<String>,
     ) -> Result<CapabilityId> {
-        let mut data = self.data.write();
+        Ok(self.grant(subject, object, rights, None))
+    }
 
-        // Convert rights to a HashSet
-        let rights_set: HashSet<String> = rights.into_iter().collect();
+    /// Grant a capability to a subject that is treated as absent from `expires_at` on
+    pub async fn grant_capability_with_expiry(
+        &self,
+        subject: String,
+        object: String,
+        rights: Vec<String>,
+        expires_at: DateTime<Utc>,
+    ) -> Result<CapabilityId> {
+        Ok(self.grant(subject, object, rights, Some(expires_at)))
+    }
 
-        // Clone subject for later use in logging
-        let subject_clone = subject.clone();
-        // Generate a new capability ID
-        let cap_id = CapabilityId::new();
+    // Helper to grant a single capability and record it
+    fn grant(
+        &self,
+        subject: String,
+        object: String,
+        rights: Vec<String>,
+        expires_at: Option<DateTime<Utc>>,
+    ) -> CapabilityId {
+        let cap_id = {
+            let mut data = self.data.write();
+            Self::insert_capability(&mut data, &subject, &object, rights, expires_at)
+        };
 
-        // Create a new capability entry
-        let entry = CapabilityEntry {
-            _id: cap_id,
-            subject: subject.clone(),
-            object: object.clone(),
-            rights: rights_set,
-            valid: true,
-            _parent: None,
-            children: Vec::new(),
+        info!(
+            "Granted capability {:?} to subject {} for object {}",
+            cap_id, subject, object
+        );
+        self.record_audit(
+            subject,
+            CapabilityAuditAction::Granted {
+                capability_id: cap_id,
+                object,
+            },
+        );
+
+        cap_id
+    }
+
+    /// Grant a batch of capabilities to a subject, all or nothing
+    ///
+    /// Every grant must name an object and at least one right. If any grant
+    /// is invalid, the grants already applied are rolled back and none of
+    /// the batch is held. A successful batch is recorded as a single audit
+    /// entry.
+    pub async fn grant_capabilities(
+        &self,
+        subject: String,
+        grants: Vec<CapabilityGrant>,
+    ) -> Result<Vec<CapabilityId>> {
+        let cap_ids = {
+            let mut data = self.data.write();
+            let mut granted = Vec::with_capacity(grants.len());
+
+            for grant in grants {
+                if grant.object.is_empty() || grant.rights.is_empty() {
+                    // Roll back the grants applied so far
+                    for cap_id in &granted {
+                        data.capabilities.remove(cap_id);
+                        if let Some(subject_caps) = data.subject_index.get_mut(&subject) {
+                            subject_caps.remove(cap_id);
+                        }
+                    }
+                    return Err(CapabilityError::InvalidGrant(format!(
+                        "grant on '{}' for subject {} needs an object and at least one right",
+                        grant.object, subject
+                    ))
+                    .into());
+                }
+
+                granted.push(Self::insert_capability(
+                    &mut data,
+                    &subject,
+                    &grant.object,
+                    grant.rights,
+                    None,
+                ));
+            }
+
+            granted
         };
 
-        // Add to the capabilities map
-        data.capabilities.insert(cap_id, entry);
+        info!(
+            "Granted {} capabilities to subject {}",
+            cap_ids.len(),
+            subject
+        );
+        self.record_audit(
+            subject,
+            CapabilityAuditAction::BulkGranted {
+                capability_ids: cap_ids.clone(),
+            },
+        );
 
-        // Add to the subject index
-        data.subject_index
-            .entry(subject.clone())
-            .or_default()
-            .insert(cap_id);
+        Ok(cap_ids)
+    }
+
+    /// Revoke every capability of a subject and all their derived capabilities
+    ///
+    /// Recorded as a single audit entry. Returns the revoked capabilities.
+    pub async fn revoke_all(&self, subject: &str) -> Result<Vec<CapabilityId>> {
+        let revoked = {
+            let mut data = self.data.write();
+
+            let held: Vec<CapabilityId> = data
+                .subject_index
+                .get(subject)
+                .map(|caps| caps.iter().copied().collect())
+                .unwrap_or_default();
+
+            let mut to_revoke = Vec::new();
+            for cap_id in held {
+                self.collect_descendants(&mut data, &cap_id, &mut to_revoke);
+            }
+            Self::invalidate(&mut data, to_revoke)
+        };
 
         info!(
-            "Granted capability {:?} to subject {} for object {}",
-            cap_id, subject_clone, object
+            "Revoked {} capabilities held by or derived from subject {}",
+            revoked.len(),
+            subject
+        );
+        self.record_audit(
+            subject.to_string(),
+            CapabilityAuditAction::RevokedAll {
+                capability_ids: revoked.clone(),
+            },
         );
 
-        Ok(cap_id)
+        Ok(revoked)
     }
 
     /// Revoke a capability and all its derived capabilities
+    ///
+    /// Each derived capability revoked along with it, including those
+    /// delegated to other subjects, gets its own audit entry.
     pub async fn revoke_capability(&self, cap_id: CapabilityId) -> Result<()> {
-        let mut data = self.data.write();
+        let (subject, revoked, cascaded) = {
+            let mut data = self.data.write();
+
+            // Check if the capability exists
+            let subject = match data.capabilities.get(&cap_id) {
+                Some(entry) => entry.subject.clone(),
+                None => return Err(CapabilityError::NotFound(cap_id).into()),
+            };
+
+            // Collect all descendants
+            let mut to_revoke = Vec::new();
+            self.collect_descendants(&mut data, &cap_id, &mut to_revoke);
+
+            let revoked = Self::invalidate(&mut data, to_revoke);
+
+            // Pair each revoked descendant with its holder for the audit trail
+            let cascaded: Vec<(CapabilityId, String)> = revoked
+                .iter()
+                .filter(|id| **id != cap_id)
+                .filter_map(|id| {
+                    data.capabilities
+                        .get(id)
+                        .map(|entry| (*id, entry.subject.clone()))
+                })
+                .collect();
+
+            (subject, revoked, cascaded)
+        };
 
-        // Check if the capability exists
-        if !data.capabilities.contains_key(&cap_id) {
-            return Err(CapabilityError::NotFound(cap_id).into());
+        self.record_audit(
+            subject,
+            CapabilityAuditAction::Revoked {
+                capability_ids: revoked,
+            },
+        );
+        for (capability_id, holder) in cascaded {
+            self.record_audit(
+                holder,
+                CapabilityAuditAction::CascadeRevoked {
+                    capability_id,
+                    revoked_with: cap_id,
+                },
+            );
         }
 
-        // Collect all descendants
-        let mut to_revoke = Vec::new();
-        self.collect_descendants(&mut data, &cap_id, &mut to_revoke);
+        Ok(())
+    }
 
-        // Revoke all collected capabilities
-        for id in to_revoke {
-            if let Some(entry) = data.capabilities.get_mut(&id) {
-                let subject = entry.subject.clone();
+    /// Delegate a capability held by `from` to `to` with the same rights
+    ///
+    /// The delegated capability is derived from the original, so revoking
+    /// the original revokes it too. Delegating to a subject that already
+    /// holds a capability in the delegation chain is rejected as a cycle.
+    pub async fn delegate(
+        &self,
+        from: &str,
+        to: String,
+        cap_id: CapabilityId,
+    ) -> Result<CapabilityId> {
+        let rights = {
+            let data = self.data.read();
+
+            let entry = match data.capabilities.get(&cap_id) {
+                Some(entry) if entry.subject == from => entry,
+                Some(entry) => {
+                    return Err(CapabilityError::Unauthorized(
+                        from.to_string(),
+                        "delegate".to_string(),
+                        entry.object.clone(),
+                    )
+                    .into())
+                }
+                None => return Err(CapabilityError::NotFound(cap_id).into()),
+            };
+
+            // Walk up the delegation chain looking for the recipient
+            let mut current = Some(entry);
+            while let Some(link) = current {
+                if link.subject == to {
+                    return Err(CapabilityError::DelegationCycle(cap_id, to).into());
+                }
+                current = link.parent.and_then(|id| data.capabilities.get(&id));
+            }
 
-                // Mark as invalid
-                entry.valid = false;
+            entry.rights.iter().cloned().collect()
+        };
 
-                // Remove from subject index
-                if let Some(subject_caps) = data.subject_index.get_mut(&subject) {
-                    subject_caps.remove(&id);
-                }
+        let delegated_id = self
+            .attenuate_capability(cap_id, to.clone(), rights)
+            .await?;
 
-                info!("Revoked capability {:?} from subject {}", id, subject);
-            }
-        }
+        info!(
+            "Delegated capability {:?} from subject {} to {} as {:?}",
+            cap_id, from, to, delegated_id
+        );
+        self.record_audit(
+            from.to_string(),
+            CapabilityAuditAction::Delegated {
+                capability_id: delegated_id,
+                parent_id: cap_id,
+                to,
+            },
+        );
 
-        Ok(())
+        Ok(delegated_id)
     }
 
     /// Derive a new capability with restricted rights
@@ -201,12 +498,17 @@ impl CapabilityManager {
 
         // Get parent info before modifications
         let parent_info = match data.capabilities.get(&parent_id) {
-            Some(entry) if entry.valid => (entry.object.clone(), entry.rights.clone()),
+            Some(entry) if entry.is_live(Utc::now()) => {
+                (entry.object.clone(), entry.rights.clone(), entry.expires_at)
+            }
+            // An expired parent is treated as absent
+            Some(entry) if entry.valid => return Err(CapabilityError::NotFound(parent_id).into()),
             Some(_) => return Err(CapabilityError::Revoked(parent_id).into()),
             None => return Err(CapabilityError::NotFound(parent_id).into()),
         };
 
-        let (parent_object, parent_rights) = parent_info;
+        // A derived capability cannot outlive its parent
+        let (parent_object, parent_rights, parent_expires_at) = parent_info;
 
         // Convert rights to a HashSet
         let rights_set: HashSet<String> = rights.into_iter().collect();
@@ -226,8 +528,9 @@ impl CapabilityManager {
             object: parent_object,
             rights: rights_set,
             valid: true,
-            _parent: Some(parent_id),
+            parent: Some(parent_id),
             children: Vec::new(),
+            expires_at: parent_expires_at,
         };
 
         // Add to the capabilities map
@@ -255,13 +558,17 @@ impl CapabilityManager {
     /// Check if a subject has a capability for an operation on an object
     pub fn has_capability(&self, subject: &str, object: &str, operation: &str) -> bool {
         let data = self.data.read();
+        let now = Utc::now();
 
         // Get the subject's capabilities
         if let Some(caps) = data.subject_index.get(subject) {
-            // Check each capability
+            // Check each capability, treating expired ones as absent
             for &cap_id in caps {
                 if let Some(entry) = data.capabilities.get(&cap_id) {
-                    if entry.valid && entry.object == object && entry.rights.contains(operation) {
+                    if entry.is_live(now)
+                        && entry.object == object
+                        && entry.rights.contains(operation)
+                    {
                         // Capability allows the operation
                         return true;
                     }
@@ -273,6 +580,108 @@ impl CapabilityManager {
         false
     }
 
+    /// Remove every capability that has expired by `now`
+    ///
+    /// Each removal is logged and recorded in the audit trail. Returns the
+    /// removed capabilities.
+    pub fn purge_expired(&self, now: DateTime<Utc>) -> Vec<CapabilityId> {
+        let mut expired: HashMap<String, Vec<CapabilityId>> = HashMap::new();
+
+        {
+            let mut data = self.data.write();
+
+            let ids: Vec<CapabilityId> = data
+                .capabilities
+                .iter()
+                .filter(|(_, entry)| entry.expires_at.is_some_and(|expires_at| expires_at <= now))
+                .map(|(id, _)| *id)
+                .collect();
+
+            for id in ids {
+                if let Some(entry) = data.capabilities.remove(&id) {
+                    if let Some(subject_caps) = data.subject_index.get_mut(&entry.subject) {
+                        subject_caps.remove(&id);
+                    }
+
+                    info!(
+                        "Purged expired capability {:?} from subject {} for object {}",
+                        id, entry.subject, entry.object
+                    );
+                    expired.entry(entry.subject).or_default().push(id);
+                }
+            }
+        }
+
+        let mut purged = Vec::new();
+        for (subject, capability_ids) in expired {
+            purged.extend(capability_ids.iter().copied());
+            self.record_audit(subject, CapabilityAuditAction::Expired { capability_ids });
+        }
+
+        purged
+    }
+
+    /// Start a background task that purges expired capabilities every `interval`
+    ///
+    /// The task stops once the manager is dropped.
+    pub fn start_expiry_sweep(self: &Arc<Self>, interval: Duration) -> tokio::task::JoinHandle<()> {
+        let manager: Weak<Self> = Arc::downgrade(self);
+
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                match manager.upgrade() {
+                    Some(manager) => {
+                        manager.purge_expired(Utc::now());
+                    }
+                    None => break,
+                }
+            }
+        })
+    }
+
+    /// Get the audit trail of grants and revocations, oldest first
+    pub fn audit_entries(&self) -> Vec<CapabilityAuditEntry> {
+        self.audit.read().iter().cloned().collect()
+    }
+
+    /// Write the audit entries recorded at or after `since` as JSON Lines
+    ///
+    /// The entries are copied before writing, so a slow writer does not hold
+    /// up new audit entries. Returns the number of entries written.
+    pub fn export_audits(&self, mut writer: impl Write, since: DateTime<Utc>) -> Result<usize> {
+        let entries: Vec<CapabilityAuditEntry> = self
+            .audit
+            .read()
+            .iter()
+            .filter(|entry| entry.timestamp >= since)
+            .cloned()
+            .collect();
+
+        for entry in &entries {
+            serde_json::to_writer(&mut writer, entry)?;
+            writer.write_all(b"\n")?;
+        }
+        writer.flush()?;
+
+        Ok(entries.len())
+    }
+
+    /// Register a callback that receives every new audit entry as it is recorded
+    pub fn subscribe_audits(&self, sink: impl Fn(&CapabilityAuditEntry) + Send + Sync + 'static) {
+        self.audit_sinks.write().push(Arc::new(sink));
+    }
+
+    /// Set the callback handed audit entries before they are evicted from a
+    /// full trail, replacing any previous one
+    pub fn set_audit_rollover(
+        &self,
+        rollover: impl Fn(Vec<CapabilityAuditEntry>) + Send + Sync + 'static,
+    ) {
+        *self.audit_rollover.write() = Some(Arc::new(rollover));
+    }
+
     /// Check both capability and policy for an operation
     pub async fn check_permission(
         &self,
@@ -294,6 +703,90 @@ impl CapabilityManager {
         Ok(())
     }
 
+    // Helper to add a valid, parentless capability to the table
+    fn insert_capability(
+        data: &mut CapabilityStore,
+        subject: &str,
+        object: &str,
+        rights: Vec<String>,
+        expires_at: Option<DateTime<Utc>>,
+    ) -> CapabilityId {
+        let cap_id = CapabilityId::new();
+
+        data.capabilities.insert(
+            cap_id,
+            CapabilityEntry {
+                _id: cap_id,
+                subject: subject.to_string(),
+                object: object.to_string(),
+                rights: rights.into_iter().collect(),
+                valid: true,
+                parent: None,
+                children: Vec::new(),
+                expires_at,
+            },
+        );
+        data.subject_index
+            .entry(subject.to_string())
+            .or_default()
+            .insert(cap_id);
+
+        cap_id
+    }
+
+    // Helper to mark capabilities invalid, returning those that were valid
+    fn invalidate(data: &mut CapabilityStore, ids: Vec<CapabilityId>) -> Vec<CapabilityId> {
+        let mut revoked = Vec::new();
+
+        for id in ids {
+            if let Some(entry) = data.capabilities.get_mut(&id) {
+                if !entry.valid {
+                    continue;
+                }
+                let subject = entry.subject.clone();
+
+                // Mark as invalid
+                entry.valid = false;
+
+                // Remove from subject index
+                if let Some(subject_caps) = data.subject_index.get_mut(&subject) {
+                    subject_caps.remove(&id);
+                }
+
+                info!("Revoked capability {:?} from subject {}", id, subject);
+                revoked.push(id);
+            }
+        }
+
+        revoked
+    }
+
+    // Helper to append to the bounded audit trail
+    fn record_audit(&self, subject: String, action: CapabilityAuditAction) {
+        let entry = CapabilityAuditEntry {
+            timestamp: Utc::now(),
+            subject,
+            action,
+        };
+
+        let evicted: Vec<_> = {
+            let mut audit = self.audit.write();
+            audit.push_back(entry.clone());
+            let excess = audit.len().saturating_sub(self.audit_capacity);
+            audit.drain(..excess).collect()
+        };
+
+        // Callbacks run outside the trail's lock
+        if !evicted.is_empty() {
+            if let Some(rollover) = self.audit_rollover.read().clone() {
+                rollover(evicted);
+            }
+        }
+        for sink in self.audit_sinks.read().iter() {
+            sink(&entry);
+        }
+    }
+
     // Helper to collect all descendants of a capability
     #[allow(clippy::only_used_in_recursion)]
     fn collect_descendants(
@@ -407,4 +900,277 @@ mod tests {
         assert!(!manager.has_capability("subject1", "object1", "read"));
         assert!(!manager.has_capability("subject2", "object1", "read"));
     }
+
+    #[tokio::test]
+    async fn test_bulk_grant_and_revoke_all() {
+        let manager = CapabilityManager::new().unwrap();
+        let rights = |r: &[&str]| r.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+
+        let cap_ids = manager
+            .grant_capabilities(
+                "plugin1".to_string(),
+                vec![
+                    CapabilityGrant::new("file:/data", rights(&["read", "write"])),
+                    CapabilityGrant::new("net:api.example.com", rights(&["connect"])),
+                ],
+            )
+            .await
+            .unwrap();
+        assert_eq!(cap_ids.len(), 2);
+        assert!(manager.has_capability("plugin1", "file:/data", "write"));
+        assert!(manager.has_capability("plugin1", "net:api.example.com", "connect"));
+
+        // Capabilities derived from the subject's are revoked with them
+        manager
+            .attenuate_capability(cap_ids[0], "plugin2".to_string(), rights(&["read"]))
+            .await
+            .unwrap();
+        let revoked = manager.revoke_all("plugin1").await.unwrap();
+        assert_eq!(revoked.len(), 3);
+        assert!(!manager.has_capability("plugin1", "file:/data", "read"));
+        assert!(!manager.has_capability("plugin1", "net:api.example.com", "connect"));
+        assert!(!manager.has_capability("plugin2", "file:/data", "read"));
+
+        // Each batch is a single audit entry
+        let actions: Vec<CapabilityAuditAction> = manager
+            .audit_entries()
+            .into_iter()
+            .map(|entry| entry.action)
+            .collect();
+        assert_eq!(
+            actions,
+            vec![
+                CapabilityAuditAction::BulkGranted {
+                    capability_ids: cap_ids
+                },
+                CapabilityAuditAction::RevokedAll {
+                    capability_ids: revoked
+                },
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_bulk_grant_rolls_back_on_invalid_grant() {
+        let manager = CapabilityManager::new().unwrap();
+
+        let result = manager
+            .grant_capabilities(
+                "plugin1".to_string(),
+                vec![
+                    CapabilityGrant::new("file:/data", vec!["read".to_string()]),
+                    CapabilityGrant::new("file:/logs", vec!["read".to_string()]),
+                    CapabilityGrant::new("net:api.example.com", Vec::new()),
+                ],
+            )
+            .await;
+        assert!(result.is_err());
+
+        // None of the batch is held, and nothing was audited
+        assert!(!manager.has_capability("plugin1", "file:/data", "read"));
+        assert!(!manager.has_capability("plugin1", "file:/logs", "read"));
+        assert!(manager.revoke_all("plugin1").await.unwrap().is_empty());
+        assert_eq!(manager.audit_entries().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_expired_capability_is_denied_and_purged() {
+        let manager = Arc::new(CapabilityManager::new().unwrap());
+        let sweep = manager.start_expiry_sweep(Duration::from_millis(100));
+
+        // Grant a capability that lasts one second
+        let cap_id = manager
+            .grant_capability_with_expiry(
+                "plugin1".to_string(),
+                "file:/tmp".to_string(),
+                vec!["read".to_string()],
+                Utc::now() + chrono::Duration::seconds(1),
+            )
+            .await
+            .unwrap();
+        let child_id = manager
+            .attenuate_capability(cap_id, "plugin2".to_string(), vec!["read".to_string()])
+            .await
+            .unwrap();
+        assert!(manager.has_capability("plugin1", "file:/tmp", "read"));
+        assert!(manager.has_capability("plugin2", "file:/tmp", "read"));
+
+        tokio::time::sleep(Duration::from_millis(1300)).await;
+
+        // The capability and the one derived from it are denied and purged
+        assert!(!manager.has_capability("plugin1", "file:/tmp", "read"));
+        assert!(!manager.has_capability("plugin2", "file:/tmp", "read"));
+        assert!(manager
+            .check_permission("plugin1", "file:/tmp", "read")
+            .await
+            .is_err());
+        let mut expired: Vec<CapabilityId> = manager
+            .audit_entries()
+            .into_iter()
+            .filter_map(|entry| match entry.action {
+                CapabilityAuditAction::Expired { capability_ids } => Some(capability_ids),
+                _ => None,
+            })
+            .flatten()
+            .collect();
+        let mut expected = vec![cap_id, child_id];
+        expired.sort_by_key(|id| id.to_string());
+        expected.sort_by_key(|id| id.to_string());
+        assert_eq!(expired, expected);
+
+        // The sweep stops once the manager is dropped
+        drop(manager);
+        tokio::time::timeout(Duration::from_secs(1), sweep)
+            .await
+            .unwrap()
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_delegation_revocation_cascades() {
+        let manager = CapabilityManager::new().unwrap();
+        let root = manager
+            .grant_capability(
+                "pluginA".to_string(),
+                "file:/data".to_string(),
+                vec!["read".to_string(), "write".to_string()],
+            )
+            .await
+            .unwrap();
+
+        // A delegates to B, B to C, C to D
+        let to_b = manager
+            .delegate("pluginA", "pluginB".to_string(), root)
+            .await
+            .unwrap();
+        let to_c = manager
+            .delegate("pluginB", "pluginC".to_string(), to_b)
+            .await
+            .unwrap();
+        let to_d = manager
+            .delegate("pluginC", "pluginD".to_string(), to_c)
+            .await
+            .unwrap();
+        assert!(manager.has_capability("pluginD", "file:/data", "write"));
+
+        // Only the holder may delegate, and never back up the chain
+        assert!(manager
+            .delegate("pluginA", "pluginE".to_string(), to_b)
+            .await
+            .is_err());
+        let cycle = manager
+            .delegate("pluginD", "pluginB".to_string(), to_d)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            cycle.downcast_ref::<CapabilityError>(),
+            Some(CapabilityError::DelegationCycle(id, to)) if *id == to_d && to == "pluginB"
+        ));
+        assert!(manager
+            .delegate("pluginA", "pluginA".to_string(), root)
+            .await
+            .is_err());
+
+        // Revoking A's capability revokes every delegated one
+        manager.revoke_capability(root).await.unwrap();
+        for plugin in ["pluginA", "pluginB", "pluginC", "pluginD"] {
+            assert!(!manager.has_capability(plugin, "file:/data", "read"));
+        }
+
+        // Each cascaded revocation is audited against its holder
+        let mut cascaded: Vec<(String, CapabilityId)> = manager
+            .audit_entries()
+            .into_iter()
+            .filter_map(|entry| match entry.action {
+                CapabilityAuditAction::CascadeRevoked {
+                    capability_id,
+                    revoked_with,
+                } => {
+                    assert_eq!(revoked_with, root);
+                    Some((entry.subject, capability_id))
+                }
+                _ => None,
+            })
+            .collect();
+        cascaded.sort_by(|a, b| a.0.cmp(&b.0));
+        assert_eq!(
+            cascaded,
+            vec![
+                ("pluginB".to_string(), to_b),
+                ("pluginC".to_string(), to_c),
+                ("pluginD".to_string(), to_d),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_export_and_subscribe_audits() {
+        let manager = CapabilityManager::new().unwrap();
+        let pushed = Arc::new(parking_lot::Mutex::new(Vec::new()));
+        let sink = pushed.clone();
+        manager.subscribe_audits(move |entry| sink.lock().push(entry.subject.clone()));
+
+        let first = manager
+            .grant_capability("pluginA".into(), "file:/data".into(), vec!["read".into()])
+            .await
+            .unwrap();
+        let since = Utc::now();
+        manager
+            .grant_capability("pluginB".into(), "file:/logs".into(), vec!["read".into()])
+            .await
+            .unwrap();
+        assert_eq!(*pushed.lock(), vec!["pluginA", "pluginB"]);
+
+        // Everything since the epoch, one JSON object per line
+        let mut out = Vec::new();
+        let written = manager
+            .export_audits(&mut out, DateTime::<Utc>::UNIX_EPOCH)
+            .unwrap();
+        assert_eq!(written, 2);
+        let lines: Vec<serde_json::Value> = String::from_utf8(out)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["subject"], "pluginA");
+        assert_eq!(lines[0]["action"]["type"], "granted");
+        assert_eq!(
+            lines[0]["action"]["capability_id"],
+            serde_json::to_value(first).unwrap()
+        );
+
+        // Only later entries are exported
+        let mut out = Vec::new();
+        assert_eq!(manager.export_audits(&mut out, since).unwrap(), 1);
+        assert!(String::from_utf8(out).unwrap().contains("pluginB"));
+    }
+
+    #[tokio::test]
+    async fn test_audit_trail_rolls_over() {
+        let manager = CapabilityManager::with_audit_capacity(2).unwrap();
+        let evicted = Arc::new(parking_lot::Mutex::new(Vec::new()));
+        let rollover = evicted.clone();
+        manager.set_audit_rollover(move |entries| {
+            rollover
+                .lock()
+                .extend(entries.into_iter().map(|entry| entry.subject))
+        });
+
+        for subject in ["a", "b", "c", "d"] {
+            manager
+                .grant_capability(subject.into(), "file:/data".into(), vec!["read".into()])
+                .await
+                .unwrap();
+        }
+
+        // The oldest entries were handed off before being dropped
+        let kept: Vec<_> = manager
+            .audit_entries()
+            .into_iter()
+            .map(|entry| entry.subject)
+            .collect();
+        assert_eq!(kept, vec!["c", "d"]);
+        assert_eq!(*evicted.lock(), vec!["a", "b"]);
+    }
 }
@@ -5,6 +5,11 @@
 
 pub mod manager;
 pub mod resolution;
+pub mod workflow;
 
 // Re-export key types for convenience
-pub use manager::CapabilityManager;
+pub use manager::{
+    CapabilityAuditAction, CapabilityAuditEntry, CapabilityAuditRollover, CapabilityAuditSink,
+    CapabilityGrant, CapabilityManager,
+};
+pub use workflow::{WorkflowExecuteCapability, WorkflowScope};
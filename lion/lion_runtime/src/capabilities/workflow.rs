@@ -0,0 +1,97 @@
+//! Workflow Capabilities for Lion Runtime
+//!
+//! Capabilities that gate operations on workflows, expressed as subject/object/right
+//! entries in the capability manager.
+
+use anyhow::Result;
+use lion_core::id::WorkflowId;
+use lion_core::CapabilityId;
+
+use super::manager::CapabilityManager;
+
+/// Right required to execute a workflow
+pub const WORKFLOW_EXECUTE_RIGHT: &str = "execute";
+
+/// The workflows a workflow capability applies to
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum WorkflowScope {
+    /// A single workflow
+    Workflow(WorkflowId),
+
+    /// Every workflow assigned to the named group
+    Group(String),
+}
+
+/// Capability to execute a workflow or a group of workflows
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct WorkflowExecuteCapability {
+    /// Workflows this capability applies to
+    pub scope: WorkflowScope,
+}
+
+impl WorkflowExecuteCapability {
+    /// Capability to execute a single workflow
+    pub fn for_workflow(workflow_id: WorkflowId) -> Self {
+        Self {
+            scope: WorkflowScope::Workflow(workflow_id),
+        }
+    }
+
+    /// Capability to execute every workflow in a group
+    pub fn for_group(group: impl Into<String>) -> Self {
+        Self {
+            scope: WorkflowScope::Group(group.into()),
+        }
+    }
+
+    /// Object name this capability is recorded under in the capability manager
+    pub fn object(&self) -> String {
+        match &self.scope {
+            WorkflowScope::Workflow(workflow_id) => format!("workflow:{}", workflow_id),
+            WorkflowScope::Group(group) => format!("workflow-group:{}", group),
+        }
+    }
+
+    /// Grant this capability to a principal
+    pub async fn grant(
+        &self,
+        manager: &CapabilityManager,
+        principal: impl Into<String>,
+    ) -> Result<CapabilityId> {
+        manager
+            .grant_capability(
+                principal.into(),
+                self.object(),
+                vec![WORKFLOW_EXECUTE_RIGHT.to_string()],
+            )
+            .await
+    }
+
+    /// Check whether a principal holds this capability
+    pub fn is_held_by(&self, manager: &CapabilityManager, principal: &str) -> bool {
+        manager.has_capability(principal, &self.object(), WORKFLOW_EXECUTE_RIGHT)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_workflow_execute_capability_scopes() {
+        let manager = CapabilityManager::new().unwrap();
+        let workflow_id = WorkflowId::new();
+        let single = WorkflowExecuteCapability::for_workflow(workflow_id);
+        let group = WorkflowExecuteCapability::for_group("reports");
+
+        single.grant(&manager, "alice").await.unwrap();
+        group.grant(&manager, "bob").await.unwrap();
+
+        assert!(single.is_held_by(&manager, "alice"));
+        assert!(!single.is_held_by(&manager, "bob"));
+        assert!(group.is_held_by(&manager, "bob"));
+        assert!(!group.is_held_by(&manager, "alice"));
+        assert!(!WorkflowExecuteCapability::for_workflow(WorkflowId::new())
+            .is_held_by(&manager, "alice"));
+    }
+}
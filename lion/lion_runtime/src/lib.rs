@@ -10,10 +10,13 @@ pub mod system;
 pub mod workflow;
 
 use std::sync::Arc;
+use std::time::Duration;
 
 use anyhow::Result;
 use lion_core::id::WorkflowId;
+use lion_core::types::workflow::ExecutionStatus;
 use lion_core::CapabilityId;
+use tokio::task::JoinHandle;
 use tracing::info;
 
 /// Runtime facade that provides a unified interface to the Lion runtime.
@@ -29,6 +32,9 @@ pub struct Runtime {
 
     /// System component for bootstrap and shutdown
     pub system: Arc<system::bootstrap::System>,
+
+    /// Background task purging expired capabilities, unless disabled
+    capability_expiry_sweep: Option<JoinHandle<()>>,
 }
 
 impl Runtime {
@@ -36,8 +42,9 @@ impl Runtime {
     pub async fn new(config_path: Option<&str>) -> Result<Self> {
         info!("Initializing Lion Runtime");
 
-        // Load configuration from the provided path or defaults
-        let config = system::config::RuntimeConfig::load(config_path).await?;
+        // Load configuration from the provided path or defaults, then apply
+        // LION_ environment variable overrides
+        let config = system::config::RuntimeConfig::load_with_env(config_path).await?;
 
         // Initialize the system component
         let system = Arc::new(system::bootstrap::System::new(config.clone())?);
@@ -45,6 +52,11 @@ impl Runtime {
         // Initialize the capability manager
         let capabilities = Arc::new(capabilities::manager::CapabilityManager::new()?);
 
+        // Purge expired capabilities in the background
+        let sweep_interval = config.capabilities.expiry_sweep_interval;
+        let capability_expiry_sweep = (sweep_interval > 0)
+            .then(|| capabilities.start_expiry_sweep(Duration::from_secs(sweep_interval)));
+
         // Initialize the plugin manager with capability manager
         let plugins = Arc::new(plugin::manager::PluginManager::new(
             config.clone(),
@@ -65,6 +77,7 @@ impl Runtime {
             plugins,
             workflows,
             system,
+            capability_expiry_sweep,
         })
     }
 
@@ -93,6 +106,10 @@ impl Runtime {
         // Use the shutdown manager
         self.system.shutdown().await?;
 
+        if let Some(sweep) = &self.capability_expiry_sweep {
+            sweep.abort();
+        }
+
         info!("Lion Runtime shut down successfully");
 
         Ok(())
@@ -115,6 +132,15 @@ impl Runtime {
         self.capabilities.revoke_capability(capability_id).await
     }
 
+    /// Register a workflow engine that workflow definitions can choose by name
+    pub async fn register_workflow_engine(
+        &self,
+        name: impl Into<String>,
+        engine: Arc<dyn lion_core::traits::WorkflowEngine>,
+    ) -> Result<()> {
+        self.workflows.register_workflow_engine(name, engine).await
+    }
+
     /// Start a workflow with the given ID and input
     pub async fn start_workflow(
         &self,
@@ -123,4 +149,71 @@ impl Runtime {
     ) -> Result<()> {
         self.workflows.start_workflow(workflow_id, input).await
     }
+
+    /// Execute a workflow on behalf of a principal holding an execute capability for it
+    pub async fn execute_workflow(
+        &self,
+        principal: &str,
+        workflow_id: WorkflowId,
+        input: serde_json::Value,
+    ) -> Result<()> {
+        self.workflows
+            .execute_workflow(principal, workflow_id, input)
+            .await
+    }
+
+    /// Pause a running workflow
+    pub async fn pause_workflow(&self, workflow_id: WorkflowId) -> Result<ExecutionStatus> {
+        self.workflows.pause_workflow(workflow_id).await
+    }
+
+    /// Resume a paused workflow
+    pub async fn resume_workflow(&self, workflow_id: WorkflowId) -> Result<ExecutionStatus> {
+        self.workflows.resume_workflow(workflow_id).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::capabilities::manager::CapabilityAuditAction;
+    use chrono::Utc;
+    use tempfile::NamedTempFile;
+
+    #[tokio::test]
+    async fn test_runtime_purges_expired_capabilities() {
+        let file = NamedTempFile::new().unwrap();
+        let path = file.path().to_str().unwrap();
+        std::fs::write(
+            path,
+            r#"{ "capabilities": { "expiry_sweep_interval": 1 } }"#,
+        )
+        .unwrap();
+        let runtime = Runtime::new(Some(path)).await.unwrap();
+
+        let cap_id = runtime
+            .capabilities
+            .grant_capability_with_expiry(
+                "plugin1".to_string(),
+                "file:/tmp".to_string(),
+                vec!["read".to_string()],
+                Utc::now() + chrono::Duration::milliseconds(200),
+            )
+            .await
+            .unwrap();
+
+        // The sweep started by the runtime purges the capability
+        tokio::time::sleep(Duration::from_millis(1500)).await;
+        let expired = runtime
+            .capabilities
+            .audit_entries()
+            .into_iter()
+            .any(|entry| match entry.action {
+                CapabilityAuditAction::Expired { capability_ids } => {
+                    capability_ids.contains(&cap_id)
+                }
+                _ => false,
+            });
+        assert!(expired);
+    }
 }
@@ -0,0 +1,152 @@
+//! Event publishing for plugins
+//!
+//! Connects the isolation layer's `publish_event` host function to the
+//! workflow event broker, gated by message capabilities.
+
+use anyhow::{anyhow, Result};
+use lion_capability::{AccessRequest, Capability, MessageCapability};
+use lion_isolation::interface::{CapabilityChecker, EventPublisher, PUBLISH_EVENT_OPERATION};
+use lion_workflow::patterns::event::{Event, EventBroker};
+use parking_lot::RwLock;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::mpsc;
+use tracing::warn;
+
+/// Publisher forwarding plugin events to an event broker
+///
+/// Host functions run synchronously, so events are queued and published by
+/// a background task. Payloads must be JSON; the topic becomes the event type
+/// and the source is `plugin:<id>`.
+pub struct BrokerEventPublisher {
+    /// Queue drained by the forwarding task
+    sender: mpsc::UnboundedSender<Event>,
+}
+
+impl BrokerEventPublisher {
+    /// Start forwarding to a broker; must be called within a tokio runtime
+    pub fn spawn(broker: Arc<EventBroker>) -> Self {
+        let (sender, mut receiver) = mpsc::unbounded_channel::<Event>();
+        tokio::spawn(async move {
+            while let Some(event) = receiver.recv().await {
+                let event_id = event.id.clone();
+                if let Err(e) = broker.publish(event).await {
+                    warn!("Failed to publish plugin event {}: {}", event_id, e);
+                }
+            }
+        });
+
+        Self { sender }
+    }
+}
+
+impl EventPublisher for BrokerEventPublisher {
+    fn publish(&self, plugin_id: &str, topic: &str, payload: &[u8]) -> Result<()> {
+        let payload: serde_json::Value = serde_json::from_slice(payload)
+            .map_err(|e| anyhow!("Event payload is not JSON: {}", e))?;
+
+        let mut event = Event::new(topic, payload);
+        event.source = format!("plugin:{}", plugin_id);
+
+        self.sender
+            .send(event)
+            .map_err(|_| anyhow!("Event broker is no longer running"))
+    }
+}
+
+/// Capability checker allowing plugins to publish to the topics of their
+/// message capability
+#[derive(Default)]
+pub struct MessageCapabilityChecker {
+    /// Message capability of each plugin
+    capabilities: RwLock<HashMap<String, MessageCapability>>,
+}
+
+impl MessageCapabilityChecker {
+    /// Create a checker granting nothing
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the message capability of a plugin
+    pub fn grant(&self, plugin_id: &str, capability: MessageCapability) {
+        self.capabilities
+            .write()
+            .insert(plugin_id.to_string(), capability);
+    }
+
+    /// Remove the message capability of a plugin
+    pub fn revoke(&self, plugin_id: &str) {
+        self.capabilities.write().remove(plugin_id);
+    }
+}
+
+impl CapabilityChecker for MessageCapabilityChecker {
+    fn check_capability(&self, plugin_id: &str, operation: &str, params: &[u8]) -> Result<()> {
+        if operation != PUBLISH_EVENT_OPERATION {
+            return Err(anyhow!(
+                "Operation '{}' is not a message operation",
+                operation
+            ));
+        }
+        let topic = std::str::from_utf8(params).map_err(|_| anyhow!("Topic is not UTF-8"))?;
+
+        let capabilities = self.capabilities.read();
+        let capability = capabilities
+            .get(plugin_id)
+            .ok_or_else(|| anyhow!("Plugin '{}' has no message capability", plugin_id))?;
+        capability
+            .permits(&AccessRequest::Message {
+                topic: topic.to_string(),
+                recipient: None,
+            })
+            .map_err(|e| anyhow!(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lion_capability::MessagePermission;
+    use lion_workflow::patterns::event::types::EventBrokerConfig;
+
+    #[tokio::test]
+    async fn test_plugin_events_reach_broker_when_permitted() {
+        let checker = MessageCapabilityChecker::new();
+        checker.grant(
+            "plugin-1",
+            MessageCapability::with_topics(HashMap::from([(
+                "orders".to_string(),
+                MessagePermission::Publish,
+            )])),
+        );
+        assert!(checker
+            .check_capability("plugin-1", PUBLISH_EVENT_OPERATION, b"orders")
+            .is_ok());
+        assert!(checker
+            .check_capability("plugin-1", PUBLISH_EVENT_OPERATION, b"audit")
+            .is_err());
+        assert!(checker
+            .check_capability("plugin-2", PUBLISH_EVENT_OPERATION, b"orders")
+            .is_err());
+
+        let broker = Arc::new(EventBroker::new(EventBrokerConfig::default()));
+        let (mut events, _acks) = broker.subscribe("orders", "listener", None).await.unwrap();
+        let publisher = BrokerEventPublisher::spawn(broker);
+
+        assert!(publisher
+            .publish("plugin-1", "orders", b"not json")
+            .is_err());
+        publisher
+            .publish("plugin-1", "orders", br#"{"id":1}"#)
+            .unwrap();
+
+        let event = tokio::time::timeout(std::time::Duration::from_secs(5), events.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(event.event_type, "orders");
+        assert_eq!(event.source, "plugin:plugin-1");
+        assert_eq!(event.payload, serde_json::json!({"id": 1}));
+    }
+}
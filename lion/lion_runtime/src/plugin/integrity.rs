@@ -0,0 +1,57 @@
+//! Plugin Integrity Checks
+//!
+//! Verifies plugin files against the content hash and signature declared in
+//! their metadata, so only pinned or trusted builds are loaded.
+
+use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+use sha2::{Digest, Sha256};
+
+use super::lifecycle::PluginMetadata;
+
+/// Parse a hex-encoded ed25519 public key
+pub fn parse_public_key(key: &str) -> Result<VerifyingKey, String> {
+    let bytes: [u8; 32] = hex::decode(key)
+        .map_err(|e| format!("public key {} is not hex: {}", key, e))?
+        .try_into()
+        .map_err(|_| format!("public key {} is not 32 bytes", key))?;
+    VerifyingKey::from_bytes(&bytes).map_err(|e| format!("public key {} is invalid: {}", key, e))
+}
+
+/// Check a plugin's bytes against the hash and signature in its metadata
+///
+/// A pinned SHA-256 must match the bytes exactly, and a signature must be
+/// made over the bytes by one of the trusted keys. Plugins declaring
+/// neither are not checked.
+pub fn verify_plugin(
+    bytes: &[u8],
+    metadata: &PluginMetadata,
+    trusted_keys: &[String],
+) -> Result<(), String> {
+    if let Some(expected) = &metadata.sha256 {
+        let actual = hex::encode(Sha256::digest(bytes));
+        if !actual.eq_ignore_ascii_case(expected) {
+            return Err(format!(
+                "SHA-256 is {} but {} was expected",
+                actual, expected
+            ));
+        }
+    }
+
+    if let Some(signature) = &metadata.signature {
+        let signature: [u8; 64] = hex::decode(signature)
+            .map_err(|e| format!("signature is not hex: {}", e))?
+            .try_into()
+            .map_err(|_| "signature is not 64 bytes".to_string())?;
+        let signature = Signature::from_bytes(&signature);
+
+        let signed_by_trusted_key = trusted_keys
+            .iter()
+            .filter_map(|key| parse_public_key(key).ok())
+            .any(|key| key.verify(bytes, &signature).is_ok());
+        if !signed_by_trusted_key {
+            return Err("signature is not from a trusted key".to_string());
+        }
+    }
+
+    Ok(())
+}
@@ -8,6 +8,7 @@ use std::sync::Arc;
 use anyhow::Result;
 use lion_core::id::PluginId;
 use lion_core::types::plugin::PluginState;
+use lion_core::utils::Version;
 use serde::{Deserialize, Serialize};
 use tokio::sync::RwLock;
 use tracing::{debug, info};
@@ -38,6 +39,54 @@ pub struct PluginMetadata {
 
     /// Required capabilities for this plugin
     pub required_capabilities: Vec<String>,
+
+    /// JSON Schema the parameters of every function call must match, if any
+    #[serde(default)]
+    pub input_schema: Option<serde_json::Value>,
+
+    /// Plugins that must be loaded before this one
+    #[serde(default)]
+    pub dependencies: Vec<PluginDependency>,
+
+    /// Hex-encoded SHA-256 the plugin file must have, if pinned
+    #[serde(default)]
+    pub sha256: Option<String>,
+
+    /// Hex-encoded ed25519 signature over the plugin file by a trusted key,
+    /// if signed
+    #[serde(default)]
+    pub signature: Option<String>,
+}
+
+/// A plugin another plugin needs loaded, by name and version requirement
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PluginDependency {
+    /// Name of the plugin depended on
+    pub name: String,
+
+    /// Semver requirement its version must meet, e.g. `^1.2`
+    pub version_req: String,
+}
+
+impl PluginDependency {
+    /// Create a dependency on a plugin
+    pub fn new(name: impl Into<String>, version_req: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            version_req: version_req.into(),
+        }
+    }
+
+    /// Check whether a version meets the requirement
+    ///
+    /// An unparsable version or requirement is never met.
+    pub fn is_satisfied_by(&self, version: &str) -> bool {
+        version
+            .parse::<Version>()
+            .ok()
+            .and_then(|version| version.satisfies(&self.version_req).ok())
+            .unwrap_or(false)
+    }
 }
 
 /// Manager for plugin lifecycle operations
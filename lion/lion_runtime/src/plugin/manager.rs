@@ -2,7 +2,7 @@
 //!
 //! Manages the loading, unloading, and lifecycle of plugins in the Lion system.
 
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use std::path::Path;
 use std::sync::Arc;
 
@@ -10,10 +10,12 @@ use crate::plugin::lifecycle::LifecycleManager;
 use anyhow::Result;
 use lion_core::id::PluginId;
 use lion_core::types::plugin::PluginState;
+use lion_isolation::IsolationManager;
 use serde::{Deserialize, Serialize};
 use tokio::sync::RwLock;
 use tracing::{debug, error, info, warn};
 
+use super::integrity::verify_plugin;
 use super::lifecycle::{PluginLifecycle, PluginMetadata};
 use super::registry::PluginRegistry;
 use crate::capabilities::manager::CapabilityManager;
@@ -36,6 +38,21 @@ pub enum PluginManagerError {
 
     #[error("Failed to load plugin: {0}")]
     LoadFailed(String),
+
+    #[error("Invalid input for plugin {0}: {1}")]
+    InvalidInput(PluginId, String),
+
+    #[error("Plugin {0} has unresolved dependencies: {1}")]
+    UnresolvedDependencies(PluginId, String),
+
+    #[error("Plugins depend on each other in a cycle: {0}")]
+    DependencyCycle(String),
+
+    #[error("Integrity check failed for plugin {0}: {1}")]
+    IntegrityCheckFailed(PluginId, String),
+
+    #[error("Failed to reload plugin {0}: {1}")]
+    ReloadFailed(PluginId, String),
 }
 
 /// Configuration for a plugin
@@ -64,6 +81,9 @@ pub struct PluginManager {
 
     /// Runtime configuration
     config: RuntimeConfig,
+
+    /// Isolation manager running plugin code, if plugins run isolated
+    isolation: Option<RwLock<IsolationManager>>,
 }
 
 impl PluginManager {
@@ -76,9 +96,19 @@ impl PluginManager {
             registry,
             capability_manager,
             config,
+            isolation: None,
         })
     }
 
+    /// Run plugin code in an isolation manager
+    ///
+    /// Loading a plugin then loads its file into the isolation manager, and
+    /// unloading it removes it again.
+    pub fn with_isolation(mut self, isolation: IsolationManager) -> Self {
+        self.isolation = Some(RwLock::new(isolation));
+        self
+    }
+
     /// Start the plugin manager
     pub async fn start(&self) -> Result<()> {
         info!("Starting plugin manager");
@@ -151,6 +181,15 @@ impl PluginManager {
             return Err(PluginManagerError::AlreadyExists(metadata.id).into());
         }
 
+        // Check a pinned or signed plugin file before accepting it
+        if metadata.sha256.is_some() || metadata.signature.is_some() {
+            let bytes = tokio::fs::read(path).await.map_err(|e| {
+                PluginManagerError::LoadFailed(format!("Failed to read {}: {}", path, e))
+            })?;
+            verify_plugin(&bytes, &metadata, &self.config.trusted_plugin_keys)
+                .map_err(|reason| PluginManagerError::IntegrityCheckFailed(metadata.id, reason))?;
+        }
+
         // Register the plugin in the registry
         self.registry.register_plugin(metadata.clone()).await?;
 
@@ -176,12 +215,123 @@ impl PluginManager {
             .get(plugin_id)
             .ok_or(PluginManagerError::NotFound(*plugin_id))?;
 
+        // Refuse to load a plugin whose dependencies are not loaded
+        check_dependencies(&plugins, &plugin.get_metadata().await).await?;
+
+        // Load the plugin code into isolation
+        if let Some(isolation) = &self.isolation {
+            let path = plugin.get_metadata().await.path;
+            let bytes = tokio::fs::read(&path).await.map_err(|e| {
+                PluginManagerError::LoadFailed(format!("Failed to read {}: {}", path, e))
+            })?;
+            isolation
+                .write()
+                .await
+                .load_plugin(plugin_id, &bytes)
+                .map_err(|e| PluginManagerError::LoadFailed(e.to_string()))?;
+        }
+
         // Load the plugin
         plugin.load().await?;
 
         Ok(())
     }
 
+    /// Replace the code of a loaded plugin without unloading it
+    ///
+    /// The new code is checked by the isolation manager before it is swapped
+    /// in, and calls already running finish on the old code. The plugin
+    /// keeps its state and its capability grants.
+    pub async fn reload_plugin(&self, plugin_id: &PluginId, new_code: &[u8]) -> Result<()> {
+        info!("Reloading plugin: {:?}", plugin_id);
+
+        let plugins = self.plugins.read().await;
+        if !plugins.contains_key(plugin_id) {
+            return Err(PluginManagerError::NotFound(*plugin_id).into());
+        }
+
+        let isolation = self.isolation.as_ref().ok_or_else(|| {
+            PluginManagerError::ReloadFailed(*plugin_id, "plugins do not run isolated".to_string())
+        })?;
+        isolation
+            .write()
+            .await
+            .reload_plugin(plugin_id, new_code)
+            .map_err(|e| PluginManagerError::ReloadFailed(*plugin_id, e.to_string()))?;
+
+        info!("Plugin reloaded: {:?}", plugin_id);
+
+        Ok(())
+    }
+
+    /// Load a set of plugins, each after the plugins it depends on
+    ///
+    /// Dependencies on plugins outside the set must already be loaded.
+    /// Returns the plugins in the order they were loaded.
+    pub async fn load_plugins(&self, plugin_ids: &[PluginId]) -> Result<Vec<PluginId>> {
+        let order = self.load_order(plugin_ids).await?;
+        for plugin_id in &order {
+            self.load_plugin(plugin_id).await?;
+        }
+
+        Ok(order)
+    }
+
+    /// Order a set of plugins so each comes after the plugins it depends on
+    async fn load_order(&self, plugin_ids: &[PluginId]) -> Result<Vec<PluginId>> {
+        let mut metadata = Vec::with_capacity(plugin_ids.len());
+        {
+            let plugins = self.plugins.read().await;
+            for plugin_id in plugin_ids {
+                let plugin = plugins
+                    .get(plugin_id)
+                    .ok_or(PluginManagerError::NotFound(*plugin_id))?;
+                metadata.push(plugin.get_metadata().await);
+            }
+        }
+
+        // Edges from each plugin in the set to the plugins in the set needing it
+        let by_name: HashMap<&str, usize> = metadata
+            .iter()
+            .enumerate()
+            .map(|(index, plugin)| (plugin.name.as_str(), index))
+            .collect();
+        let mut in_degree = vec![0; metadata.len()];
+        let mut dependents = vec![Vec::new(); metadata.len()];
+        for (index, plugin) in metadata.iter().enumerate() {
+            for dependency in &plugin.dependencies {
+                if let Some(&needed) = by_name.get(dependency.name.as_str()) {
+                    in_degree[index] += 1;
+                    dependents[needed].push(index);
+                }
+            }
+        }
+
+        let mut ready: VecDeque<usize> = (0..metadata.len())
+            .filter(|&index| in_degree[index] == 0)
+            .collect();
+        let mut order = Vec::with_capacity(metadata.len());
+        while let Some(index) = ready.pop_front() {
+            order.push(metadata[index].id);
+            for &dependent in &dependents[index] {
+                in_degree[dependent] -= 1;
+                if in_degree[dependent] == 0 {
+                    ready.push_back(dependent);
+                }
+            }
+        }
+
+        if order.len() < metadata.len() {
+            let cycle: Vec<&str> = (0..metadata.len())
+                .filter(|&index| in_degree[index] > 0)
+                .map(|index| metadata[index].name.as_str())
+                .collect();
+            return Err(PluginManagerError::DependencyCycle(cycle.join(", ")).into());
+        }
+
+        Ok(order)
+    }
+
     /// Initialize a plugin with configuration
     pub async fn initialize_plugin(
         &self,
@@ -280,6 +430,16 @@ impl PluginManager {
             plugin.stop().await?;
         }
 
+        // Remove the plugin code from isolation
+        if let Some(isolation) = &self.isolation {
+            if let Err(e) = isolation.write().await.unload_plugin(plugin_id) {
+                warn!(
+                    "Error unloading plugin {:?} from isolation: {}",
+                    plugin_id, e
+                );
+            }
+        }
+
         // Unload the plugin
         plugin.unload().await?;
 
@@ -333,6 +493,11 @@ impl PluginManager {
             .get(plugin_id)
             .ok_or(PluginManagerError::NotFound(*plugin_id))?;
 
+        // Reject input the plugin declares it cannot take
+        if let Some(schema) = &plugin.get_metadata().await.input_schema {
+            validate_input(plugin_id, schema, &params)?;
+        }
+
         // Call the function
         let result = plugin.call_function(function_name, params).await?;
 
@@ -413,6 +578,71 @@ impl PluginManager {
     }
 }
 
+/// Check the dependencies of a plugin are loaded with compatible versions
+async fn check_dependencies(
+    plugins: &HashMap<PluginId, Arc<PluginLifecycle>>,
+    metadata: &PluginMetadata,
+) -> Result<(), PluginManagerError> {
+    if metadata.dependencies.is_empty() {
+        return Ok(());
+    }
+
+    // Versions of the loaded plugins, by name
+    let mut loaded = HashMap::new();
+    for plugin in plugins.values() {
+        if matches!(
+            plugin.get_state().await,
+            PluginState::Ready | PluginState::Running | PluginState::Paused
+        ) {
+            let other = plugin.get_metadata().await;
+            loaded.insert(other.name, other.version);
+        }
+    }
+
+    let problems: Vec<String> = metadata
+        .dependencies
+        .iter()
+        .filter_map(|dependency| match loaded.get(&dependency.name) {
+            None => Some(format!(
+                "{} {} is not loaded",
+                dependency.name, dependency.version_req
+            )),
+            Some(version) if !dependency.is_satisfied_by(version) => Some(format!(
+                "{} {} does not match loaded version {}",
+                dependency.name, dependency.version_req, version
+            )),
+            Some(_) => None,
+        })
+        .collect();
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        Err(PluginManagerError::UnresolvedDependencies(
+            metadata.id,
+            problems.join("; "),
+        ))
+    }
+}
+
+/// Check function call parameters against a plugin's input schema
+fn validate_input(
+    plugin_id: &PluginId,
+    schema: &serde_json::Value,
+    params: &serde_json::Value,
+) -> Result<(), PluginManagerError> {
+    let invalid = |reason: String| PluginManagerError::InvalidInput(*plugin_id, reason);
+
+    let compiled = jsonschema::JSONSchema::compile(schema)
+        .map_err(|e| invalid(format!("the input schema is invalid: {}", e)))?;
+    let reasons: Vec<String> = match compiled.validate(params) {
+        Ok(()) => return Ok(()),
+        Err(errors) => errors
+            .map(|e| format!("{} at '{}'", e, e.instance_path))
+            .collect(),
+    };
+    Err(invalid(reasons.join("; ")))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -445,6 +675,10 @@ mod tests {
             path: format!("{}/test-plugin", temp_path),
             state: PluginState::Created,
             required_capabilities: vec![],
+            input_schema: None,
+            dependencies: vec![],
+            sha256: None,
+            signature: None,
         };
 
         // Create test file
@@ -468,4 +702,276 @@ mod tests {
         // Shutdown
         manager.shutdown().await.unwrap();
     }
+
+    #[tokio::test]
+    async fn test_call_validates_input_schema() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("adder");
+        std::fs::write(&path, b"test plugin").unwrap();
+        let path = path.to_str().unwrap().to_string();
+
+        let manager = PluginManager::new(
+            RuntimeConfig::default(),
+            Arc::new(CapabilityManager::new().unwrap()),
+        )
+        .unwrap();
+
+        // One plugin declares its input, the other takes anything
+        let metadata = |name: &str, input_schema| PluginMetadata {
+            id: PluginId::new(),
+            name: name.to_string(),
+            version: "1.0.0".to_string(),
+            description: "Test plugin".to_string(),
+            author: "Test Author".to_string(),
+            path: path.clone(),
+            state: PluginState::Created,
+            required_capabilities: vec![],
+            input_schema,
+            dependencies: vec![],
+            sha256: None,
+            signature: None,
+        };
+        let schema = serde_json::json!({
+            "type": "object",
+            "properties": { "a": { "type": "integer" } },
+            "required": ["a"]
+        });
+        let checked = manager
+            .register_plugin(metadata("adder", Some(schema)), &path)
+            .await
+            .unwrap();
+        let unchecked = manager
+            .register_plugin(metadata("echo", None), &path)
+            .await
+            .unwrap();
+
+        let params = serde_json::json!({ "a": 1 });
+        assert!(manager
+            .call_plugin_function(&checked, "add", params)
+            .await
+            .is_ok());
+
+        let error = manager
+            .call_plugin_function(&checked, "add", serde_json::json!({ "a": "one" }))
+            .await
+            .unwrap_err();
+        match error.downcast_ref::<PluginManagerError>() {
+            Some(PluginManagerError::InvalidInput(id, reason)) => {
+                assert_eq!(*id, checked);
+                assert!(reason.contains("/a"), "{}", reason);
+            }
+            other => panic!("expected invalid input, got {:?}", other),
+        }
+
+        // Without a schema, any input reaches the plugin
+        assert!(manager
+            .call_plugin_function(&unchecked, "echo", serde_json::json!("anything"))
+            .await
+            .is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_load_plugins_resolves_dependencies() {
+        use super::super::lifecycle::PluginDependency;
+
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("plugin");
+        std::fs::write(&path, b"test plugin").unwrap();
+        let path = path.to_str().unwrap().to_string();
+
+        let manager = PluginManager::new(
+            RuntimeConfig::default(),
+            Arc::new(CapabilityManager::new().unwrap()),
+        )
+        .unwrap();
+        let register = |name: &str, version: &str, dependencies: Vec<PluginDependency>| {
+            let metadata = PluginMetadata {
+                id: PluginId::new(),
+                name: name.to_string(),
+                version: version.to_string(),
+                description: "Test plugin".to_string(),
+                author: "Test Author".to_string(),
+                path: path.clone(),
+                state: PluginState::Created,
+                required_capabilities: vec![],
+                input_schema: None,
+                dependencies,
+                sha256: None,
+                signature: None,
+            };
+            manager.register_plugin(metadata, &path)
+        };
+
+        let app = register("app", "1.0.0", vec![PluginDependency::new("base", "^1.1")])
+            .await
+            .unwrap();
+        let base = register("base", "1.2.0", vec![]).await.unwrap();
+        let newer = register("newer", "1.0.0", vec![PluginDependency::new("base", "^2")])
+            .await
+            .unwrap();
+
+        // A plugin cannot load before its dependency
+        let error = manager.load_plugin(&app).await.unwrap_err();
+        assert!(
+            error.to_string().contains("base ^1.1 is not loaded"),
+            "{}",
+            error
+        );
+
+        // Loading the set loads the dependency first
+        assert_eq!(
+            manager.load_plugins(&[app, base]).await.unwrap(),
+            vec![base, app]
+        );
+
+        // A loaded dependency of the wrong version is rejected
+        let error = manager.load_plugin(&newer).await.unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .contains("does not match loaded version 1.2.0"),
+            "{}",
+            error
+        );
+
+        // Plugins depending on each other cannot be ordered
+        let left = register("left", "1.0.0", vec![PluginDependency::new("right", "*")])
+            .await
+            .unwrap();
+        let right = register("right", "1.0.0", vec![PluginDependency::new("left", "*")])
+            .await
+            .unwrap();
+        let error = manager.load_plugins(&[left, right]).await.unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<PluginManagerError>(),
+            Some(PluginManagerError::DependencyCycle(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_register_verifies_plugin_integrity() {
+        use ed25519_dalek::{Signer, SigningKey};
+        use sha2::{Digest, Sha256};
+
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("plugin.wasm");
+        let bytes = b"trusted plugin build";
+        std::fs::write(&path, bytes).unwrap();
+        let path = path.to_str().unwrap().to_string();
+
+        let trusted = SigningKey::from_bytes(&[7; 32]);
+        let untrusted = SigningKey::from_bytes(&[9; 32]);
+        let config = RuntimeConfig {
+            trusted_plugin_keys: vec![hex::encode(trusted.verifying_key().as_bytes())],
+            ..RuntimeConfig::default()
+        };
+        config.validate().unwrap();
+        let manager =
+            PluginManager::new(config, Arc::new(CapabilityManager::new().unwrap())).unwrap();
+
+        let register = |sha256: Option<String>, signature: Option<String>| {
+            let metadata = PluginMetadata {
+                id: PluginId::new(),
+                name: "pinned".to_string(),
+                version: "1.0.0".to_string(),
+                description: "Test plugin".to_string(),
+                author: "Test Author".to_string(),
+                path: path.clone(),
+                state: PluginState::Created,
+                required_capabilities: vec![],
+                input_schema: None,
+                dependencies: vec![],
+                sha256,
+                signature,
+            };
+            manager.register_plugin(metadata, &path)
+        };
+        let rejected = |result: Result<PluginId>| {
+            matches!(
+                result.unwrap_err().downcast_ref::<PluginManagerError>(),
+                Some(PluginManagerError::IntegrityCheckFailed(_, _))
+            )
+        };
+
+        // A matching hash or a trusted signature is accepted
+        let sha256 = hex::encode(Sha256::digest(bytes));
+        assert!(register(Some(sha256), None).await.is_ok());
+        let signature = hex::encode(trusted.sign(bytes).to_bytes());
+        assert!(register(None, Some(signature)).await.is_ok());
+
+        // Other builds, and signatures by other keys, are rejected
+        let other_sha256 = hex::encode(Sha256::digest(b"tampered build"));
+        assert!(rejected(register(Some(other_sha256), None).await));
+        let forged = hex::encode(untrusted.sign(bytes).to_bytes());
+        assert!(rejected(register(None, Some(forged)).await));
+        assert_eq!(manager.get_plugins().await.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_reload_keeps_capability_grants() {
+        const OLD: &[u8] = br#"(module (func (export "old") (result i32) i32.const 0))"#;
+        const NEW: &[u8] = br#"(module (func (export "new") (result i32) i32.const 0))"#;
+        const BROKEN: &[u8] = br#"(module (import "env" "missing" (func)))"#;
+
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("worker.wat");
+        std::fs::write(&path, OLD).unwrap();
+        let path = path.to_str().unwrap().to_string();
+
+        let engine = Arc::new(lion_isolation::WasmEngine::create_default().unwrap());
+        let limiter = Arc::new(lion_isolation::DefaultResourceLimiter::default());
+        let isolation = IsolationManager::with_default_backend(engine, limiter).unwrap();
+        let capability_manager = Arc::new(CapabilityManager::new().unwrap());
+        let manager = PluginManager::new(RuntimeConfig::default(), capability_manager.clone())
+            .unwrap()
+            .with_isolation(isolation);
+
+        let metadata = PluginMetadata {
+            id: PluginId::new(),
+            name: "worker".to_string(),
+            version: "1.0.0".to_string(),
+            description: "Test plugin".to_string(),
+            author: "Test Author".to_string(),
+            path: path.clone(),
+            state: PluginState::Created,
+            required_capabilities: vec![],
+            input_schema: None,
+            dependencies: vec![],
+            sha256: None,
+            signature: None,
+        };
+        let plugin_id = manager.register_plugin(metadata, &path).await.unwrap();
+        manager.load_plugin(&plugin_id).await.unwrap();
+        manager
+            .grant_capability(&plugin_id, "file:/data", vec!["read".to_string()])
+            .await
+            .unwrap();
+
+        manager.reload_plugin(&plugin_id, NEW).await.unwrap();
+
+        // The grant and the plugin's state survive the reload
+        assert!(capability_manager.has_capability(&plugin_id.to_string(), "file:/data", "read"));
+        assert_eq!(
+            manager.get_plugin(&plugin_id).await.unwrap().state,
+            PluginState::Ready
+        );
+
+        // Code that cannot be instantiated is rejected
+        let error = manager.reload_plugin(&plugin_id, BROKEN).await.unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<PluginManagerError>(),
+            Some(PluginManagerError::ReloadFailed(id, _)) if *id == plugin_id
+        ));
+        assert!(capability_manager.has_capability(&plugin_id.to_string(), "file:/data", "read"));
+
+        // Unknown plugins cannot be reloaded
+        let error = manager
+            .reload_plugin(&PluginId::new(), NEW)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<PluginManagerError>(),
+            Some(PluginManagerError::NotFound(_))
+        ));
+    }
 }
@@ -3,9 +3,12 @@
 //! This module provides components for managing plugins, including
 //! lifecycle management, registration, and execution.
 
+pub mod events;
+pub mod integrity;
 pub mod lifecycle;
 pub mod manager;
 pub mod registry;
 
 // Re-export key types for convenience
+pub use events::{BrokerEventPublisher, MessageCapabilityChecker};
 pub use manager::PluginManager;
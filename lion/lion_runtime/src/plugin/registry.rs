@@ -147,6 +147,10 @@ impl PluginRegistry {
                                 path: path.to_string_lossy().to_string(),
                                 state: PluginState::Created,
                                 required_capabilities: Vec::new(),
+                                input_schema: None,
+                                dependencies: Vec::new(),
+                                sha256: None,
+                                signature: None,
                             };
                             discovered.push(metadata);
                         }
@@ -3,7 +3,9 @@
 //! Handles loading and managing runtime configuration.
 
 use std::collections::HashMap;
+use std::fmt::Display;
 use std::path::Path;
+use std::str::FromStr;
 
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
@@ -11,6 +13,7 @@ use thiserror::Error;
 use tokio::fs;
 use tracing::{error, info, warn};
 
+use crate::plugin::integrity::parse_public_key;
 use crate::plugin::manager::PluginConfig;
 
 /// Errors that can occur in configuration
@@ -24,6 +27,38 @@ pub enum ConfigError {
 
     #[error("Invalid configuration: {0}")]
     Invalid(String),
+
+    #[error("Invalid value {value:?} for environment variable {name}: {reason}")]
+    InvalidEnvVar {
+        name: String,
+        value: String,
+        reason: String,
+    },
+}
+
+/// Prefix of the environment variables overriding configuration values
+pub const ENV_PREFIX: &str = "LION_";
+
+/// Parse the value of an environment variable
+fn parse_env<T>(name: &str, value: &str) -> std::result::Result<T, ConfigError>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    value
+        .trim()
+        .parse()
+        .map_err(|e: T::Err| ConfigError::InvalidEnvVar {
+            name: name.to_string(),
+            value: value.to_string(),
+            reason: e.to_string(),
+        })
+}
+
+/// Parse the value of an environment variable, an empty one meaning `None`
+fn parse_env_option(value: &str) -> Option<String> {
+    let value = value.trim();
+    (!value.is_empty()).then(|| value.to_string())
 }
 
 /// Monitoring configuration
@@ -65,6 +100,60 @@ impl Default for MonitoringConfig {
     }
 }
 
+/// Workflow execution configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkflowConfig {
+    /// How long finished executions are kept before cleanup (seconds)
+    #[serde(default = "default_execution_retention")]
+    pub execution_retention: u64,
+
+    /// How often finished executions are cleaned up (seconds, never if zero)
+    #[serde(default = "default_cleanup_interval")]
+    pub cleanup_interval: u64,
+
+    /// Directory finished executions are archived to before cleanup
+    #[serde(default)]
+    pub archive_directory: Option<String>,
+}
+
+fn default_execution_retention() -> u64 {
+    24 * 60 * 60
+}
+
+fn default_cleanup_interval() -> u64 {
+    60
+}
+
+impl Default for WorkflowConfig {
+    fn default() -> Self {
+        Self {
+            execution_retention: default_execution_retention(),
+            cleanup_interval: default_cleanup_interval(),
+            archive_directory: None,
+        }
+    }
+}
+
+/// Capability management configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CapabilityConfig {
+    /// How often expired capabilities are purged (seconds, never if zero)
+    #[serde(default = "default_expiry_sweep_interval")]
+    pub expiry_sweep_interval: u64,
+}
+
+fn default_expiry_sweep_interval() -> u64 {
+    60
+}
+
+impl Default for CapabilityConfig {
+    fn default() -> Self {
+        Self {
+            expiry_sweep_interval: default_expiry_sweep_interval(),
+        }
+    }
+}
+
 /// Runtime configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RuntimeConfig {
@@ -92,6 +181,18 @@ pub struct RuntimeConfig {
     #[serde(default = "default_max_threads")]
     pub max_threads: usize,
 
+    /// Hex-encoded ed25519 public keys trusted to sign plugins
+    #[serde(default)]
+    pub trusted_plugin_keys: Vec<String>,
+
+    /// Workflow execution configuration
+    #[serde(default)]
+    pub workflows: WorkflowConfig,
+
+    /// Capability management configuration
+    #[serde(default)]
+    pub capabilities: CapabilityConfig,
+
     /// Additional configuration
     #[serde(default)]
     pub extra: HashMap<String, serde_json::Value>,
@@ -127,6 +228,9 @@ impl Default for RuntimeConfig {
             shutdown_timeout: default_shutdown_timeout(),
             monitoring: MonitoringConfig::default(),
             max_threads: default_max_threads(),
+            trusted_plugin_keys: Vec::new(),
+            workflows: WorkflowConfig::default(),
+            capabilities: CapabilityConfig::default(),
             extra: HashMap::new(),
         }
     }
@@ -135,6 +239,113 @@ impl Default for RuntimeConfig {
 impl RuntimeConfig {
     /// Load configuration from a file
     pub async fn load(path: Option<&str>) -> Result<Self> {
+        let config = Self::load_file(path).await?;
+
+        // Validate the configuration
+        config.validate()?;
+
+        Ok(config)
+    }
+
+    /// Load configuration from defaults, then a file, then the environment
+    ///
+    /// Each layer overrides the one before it; see `apply_env_overrides`
+    /// for the environment variables read. The result is validated once all
+    /// layers are applied.
+    pub async fn load_with_env(path: Option<&str>) -> Result<Self> {
+        let mut config = Self::load_file(path).await?;
+        config.apply_env_overrides(std::env::vars())?;
+
+        // Validate the configuration
+        config.validate()?;
+
+        Ok(config)
+    }
+
+    /// Override configuration values with environment variables
+    ///
+    /// Variables are named `LION_` followed by the field's path in upper
+    /// case, with nested fields separated by a double underscore:
+    ///
+    /// | Variable | Field |
+    /// |----------|-------|
+    /// | `LION_PLUGIN_DIRECTORY` | `plugin_directory` |
+    /// | `LION_SHUTDOWN_TIMEOUT` | `shutdown_timeout` |
+    /// | `LION_MAX_THREADS` | `max_threads` |
+    /// | `LION_BOOTSTRAP_TIMEOUTS__<PHASE>` | `bootstrap_timeouts[phase]` |
+    /// | `LION_TRUSTED_PLUGIN_KEYS` | `trusted_plugin_keys`, comma-separated |
+    /// | `LION_MONITORING__ENABLED` | `monitoring.enabled` |
+    /// | `LION_MONITORING__PORT` | `monitoring.port` |
+    /// | `LION_MONITORING__LOCAL_ONLY` | `monitoring.local_only` |
+    /// | `LION_MONITORING__TOKEN` | `monitoring.token` |
+    /// | `LION_WORKFLOWS__EXECUTION_RETENTION` | `workflows.execution_retention` |
+    /// | `LION_WORKFLOWS__CLEANUP_INTERVAL` | `workflows.cleanup_interval` |
+    /// | `LION_WORKFLOWS__ARCHIVE_DIRECTORY` | `workflows.archive_directory` |
+    /// | `LION_CAPABILITIES__EXPIRY_SWEEP_INTERVAL` | `capabilities.expiry_sweep_interval` |
+    ///
+    /// Booleans are `true` or `false`, and an empty value unsets an optional
+    /// field. A value that does not parse fails with
+    /// `ConfigError::InvalidEnvVar`; other `LION_` variables are ignored
+    /// with a warning.
+    pub fn apply_env_overrides<I>(&mut self, vars: I) -> Result<()>
+    where
+        I: IntoIterator<Item = (String, String)>,
+    {
+        for (name, value) in vars {
+            let Some(key) = name.strip_prefix(ENV_PREFIX) else {
+                continue;
+            };
+
+            match key {
+                "PLUGIN_DIRECTORY" => self.plugin_directory = value.trim().to_string(),
+                "SHUTDOWN_TIMEOUT" => self.shutdown_timeout = parse_env(&name, &value)?,
+                "MAX_THREADS" => self.max_threads = parse_env(&name, &value)?,
+                "TRUSTED_PLUGIN_KEYS" => {
+                    self.trusted_plugin_keys = value
+                        .split(',')
+                        .map(str::trim)
+                        .filter(|key| !key.is_empty())
+                        .map(String::from)
+                        .collect();
+                }
+                "MONITORING__ENABLED" => self.monitoring.enabled = parse_env(&name, &value)?,
+                "MONITORING__PORT" => self.monitoring.port = parse_env(&name, &value)?,
+                "MONITORING__LOCAL_ONLY" => {
+                    self.monitoring.local_only = parse_env(&name, &value)?;
+                }
+                "MONITORING__TOKEN" => self.monitoring.token = parse_env_option(&value),
+                "WORKFLOWS__EXECUTION_RETENTION" => {
+                    self.workflows.execution_retention = parse_env(&name, &value)?;
+                }
+                "WORKFLOWS__CLEANUP_INTERVAL" => {
+                    self.workflows.cleanup_interval = parse_env(&name, &value)?;
+                }
+                "WORKFLOWS__ARCHIVE_DIRECTORY" => {
+                    self.workflows.archive_directory = parse_env_option(&value);
+                }
+                "CAPABILITIES__EXPIRY_SWEEP_INTERVAL" => {
+                    self.capabilities.expiry_sweep_interval = parse_env(&name, &value)?;
+                }
+                _ => match key.strip_prefix("BOOTSTRAP_TIMEOUTS__") {
+                    Some(phase) => {
+                        let phase = parse_env(&name, phase)?;
+                        let timeout = parse_env(&name, &value)?;
+                        self.bootstrap_timeouts.insert(phase, timeout);
+                    }
+                    None => {
+                        warn!("Ignoring unknown configuration variable {}", name);
+                        continue;
+                    }
+                },
+            }
+            info!("Configuration overridden by {}", name);
+        }
+
+        Ok(())
+    }
+
+    /// Load configuration from a file over the defaults, without validating it
+    async fn load_file(path: Option<&str>) -> Result<Self> {
         // Start with default configuration
         let mut config = RuntimeConfig::default();
 
@@ -160,9 +371,6 @@ impl RuntimeConfig {
             info!("No configuration file specified, using defaults");
         }
 
-        // Validate the configuration
-        config.validate()?;
-
         Ok(config)
     }
 
@@ -201,6 +409,11 @@ impl RuntimeConfig {
             return Err(ConfigError::Invalid("Max threads cannot be zero".to_string()).into());
         }
 
+        // Check trusted plugin keys
+        for key in &self.trusted_plugin_keys {
+            parse_public_key(key).map_err(ConfigError::Invalid)?;
+        }
+
         Ok(())
     }
 
@@ -242,6 +455,13 @@ impl RuntimeConfig {
             self.max_threads = other.max_threads;
         }
 
+        // Merge trusted plugin keys
+        for key in other.trusted_plugin_keys {
+            if !self.trusted_plugin_keys.contains(&key) {
+                self.trusted_plugin_keys.push(key);
+            }
+        }
+
         // Merge extra
         for (key, value) in other.extra {
             self.extra.insert(key, value);
@@ -319,4 +539,74 @@ mod tests {
         assert!(base.monitoring.enabled);
         assert_eq!(base.monitoring.token, Some("override-token".to_string()));
     }
+
+    #[tokio::test]
+    async fn test_env_overrides_file_and_defaults() {
+        let file = NamedTempFile::new().unwrap();
+        let path = file.path().to_str().unwrap();
+        let config_json = r#"
+        {
+            "shutdown_timeout": 60,
+            "max_threads": 8,
+            "monitoring": { "token": "file-token" }
+        }
+        "#;
+        fs::write(path, config_json).await.unwrap();
+
+        // Only this test sets LION_ variables
+        let vars = [
+            ("LION_MAX_THREADS", "16"),
+            ("LION_MONITORING__ENABLED", "true"),
+            ("LION_MONITORING__TOKEN", ""),
+            ("LION_WORKFLOWS__ARCHIVE_DIRECTORY", "/var/lib/lion/archive"),
+            ("LION_BOOTSTRAP_TIMEOUTS__2", "90"),
+        ];
+        for (name, value) in vars {
+            std::env::set_var(name, value);
+        }
+        let config = RuntimeConfig::load_with_env(Some(path)).await;
+        for (name, _) in vars {
+            std::env::remove_var(name);
+        }
+        let config = config.unwrap();
+
+        // The environment wins over the file, which wins over the defaults
+        assert_eq!(config.max_threads, 16);
+        assert_eq!(config.shutdown_timeout, 60);
+        assert_eq!(config.plugin_directory, "./plugins");
+        assert!(config.monitoring.enabled);
+        assert_eq!(config.monitoring.token, None);
+        assert_eq!(
+            config.workflows.archive_directory.as_deref(),
+            Some("/var/lib/lion/archive")
+        );
+        assert_eq!(config.bootstrap_timeouts[&2], 90);
+        assert_eq!(config.bootstrap_timeouts[&1], 30);
+
+        // The file alone still loads as before
+        let config = RuntimeConfig::load(Some(path)).await.unwrap();
+        assert_eq!(config.max_threads, 8);
+        assert_eq!(config.monitoring.token, Some("file-token".to_string()));
+    }
+
+    #[test]
+    fn test_invalid_env_override() {
+        let mut config = RuntimeConfig::default();
+        let err = config
+            .apply_env_overrides([("LION_MONITORING__PORT".to_string(), "70000".to_string())])
+            .unwrap_err();
+        match err.downcast_ref::<ConfigError>() {
+            Some(ConfigError::InvalidEnvVar { name, value, .. }) => {
+                assert_eq!(name, "LION_MONITORING__PORT");
+                assert_eq!(value, "70000");
+            }
+            other => panic!("Unexpected error: {:?}", other),
+        }
+
+        // Values left invalid by an override are caught by validation
+        config
+            .apply_env_overrides([("LION_MAX_THREADS".to_string(), "0".to_string())])
+            .unwrap();
+        assert!(config.validate().is_err());
+    }
 }
@@ -2,16 +2,18 @@
 //!
 //! Handles the execution of workflows.
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use std::time::Instant;
 
 use anyhow::Result;
+use chrono::{DateTime, Utc};
 use lion_core::id::{NodeId, PluginId, WorkflowId};
 use lion_core::types::workflow::{ExecutionStatus, NodeStatus};
 use lion_workflow::model::definition::WorkflowDefinition;
 use lion_workflow::model::node::NodeId as ModelNodeId;
-use tokio::sync::RwLock;
+use serde::{Deserialize, Serialize};
+use tokio::sync::{broadcast, oneshot, Notify, RwLock};
 use tracing::{debug, error, info};
 
 use crate::capabilities::manager::CapabilityManager;
@@ -22,6 +24,25 @@ fn convert_node_id(node_id: &ModelNodeId) -> NodeId {
     NodeId::from_uuid(node_id.uuid())
 }
 
+/// Number of node status events buffered for slow subscribers
+const NODE_EVENT_CAPACITY: usize = 1024;
+
+/// A node of a workflow execution changed status
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NodeStatusEvent {
+    /// Workflow whose execution the node belongs to
+    pub workflow_id: WorkflowId,
+
+    /// Node that changed status
+    pub node_id: NodeId,
+
+    /// Status the node moved to
+    pub status: NodeStatus,
+
+    /// When the node moved to it
+    pub at: DateTime<Utc>,
+}
+
 /// Errors that can occur during workflow execution
 #[derive(thiserror::Error, Debug)]
 pub enum ExecutionError {
@@ -43,12 +64,18 @@ pub enum ExecutionError {
     #[error("Workflow {0} not running")]
     WorkflowNotRunning(WorkflowId),
 
+    #[error("Workflow {0} not paused")]
+    WorkflowNotPaused(WorkflowId),
+
+    #[error("Workflow {0} already finished")]
+    WorkflowAlreadyFinished(WorkflowId),
+
     #[error("Execution timeout")]
     Timeout,
 }
 
 /// Workflow execution state
-#[derive(Debug, Clone)]
+#[derive(Debug)]
 struct WorkflowExecutionState {
     /// Workflow definition
     #[allow(dead_code)]
@@ -58,7 +85,6 @@ struct WorkflowExecutionState {
     status: ExecutionStatus,
 
     /// Status of each node
-    #[allow(dead_code)]
     node_statuses: HashMap<NodeId, NodeStatus>,
 
     /// Node outputs
@@ -74,6 +100,255 @@ struct WorkflowExecutionState {
 
     /// End time
     end_time: Option<Instant>,
+
+    /// Error that failed the workflow, if any
+    error: Option<String>,
+
+    /// Listeners waiting for the workflow to finish
+    completion_subscribers: Vec<oneshot::Sender<ExecutionStatus>>,
+
+    /// Tags the workflow was started with
+    tags: HashMap<String, String>,
+
+    /// When the workflow was started
+    started_at: DateTime<Utc>,
+
+    /// When the workflow reached a terminal status
+    finished_at: Option<DateTime<Utc>>,
+}
+
+impl WorkflowExecutionState {
+    /// Whether the workflow has reached a terminal status
+    fn is_finished(&self) -> bool {
+        matches!(
+            self.status,
+            ExecutionStatus::Completed | ExecutionStatus::Failed | ExecutionStatus::Cancelled
+        )
+    }
+
+    /// Move the workflow to a terminal status and notify completion listeners
+    fn finish(&mut self, status: ExecutionStatus) {
+        self.status = status;
+        self.end_time = Some(Instant::now());
+        self.finished_at = Some(Utc::now());
+        for subscriber in self.completion_subscribers.drain(..) {
+            // The listener may have gone away
+            let _ = subscriber.send(status);
+        }
+    }
+
+    /// Describe the execution in the form it is archived in
+    fn archive(&self, workflow_id: WorkflowId) -> ArchivedExecution {
+        ArchivedExecution {
+            summary: ExecutionSummary {
+                workflow_id,
+                status: self.status,
+                started_at: self.started_at,
+                finished_at: self.finished_at,
+            },
+            tags: self.tags.clone(),
+            node_outputs: self
+                .node_outputs
+                .iter()
+                .map(|(node_id, output)| (node_id.to_string(), output.clone()))
+                .collect(),
+            error: self.error.clone(),
+        }
+    }
+}
+
+/// Which executions `WorkflowExecutor::list_workflows` returns
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExecutionFilter {
+    /// Statuses to include (all if empty)
+    pub statuses: Vec<ExecutionStatus>,
+
+    /// Only executions started at or after this time
+    pub started_after: Option<DateTime<Utc>>,
+
+    /// Only executions started before this time
+    pub started_before: Option<DateTime<Utc>>,
+}
+
+impl ExecutionFilter {
+    /// Whether an execution passes this filter
+    pub(crate) fn matches(&self, status: ExecutionStatus, started_at: DateTime<Utc>) -> bool {
+        (self.statuses.is_empty() || self.statuses.contains(&status))
+            && self.started_after.is_none_or(|after| started_at >= after)
+            && self.started_before.is_none_or(|before| started_at < before)
+    }
+}
+
+/// Position in a listing of executions, returned with each page but the last
+///
+/// Listings are ordered newest first, so a cursor stays valid while new
+/// executions start.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Cursor {
+    /// Start time of the last execution of the page
+    started_at: DateTime<Utc>,
+
+    /// ID of the last execution of the page
+    workflow_id: WorkflowId,
+}
+
+impl Cursor {
+    /// Sort key of the position, newest first
+    fn key(&self) -> (std::cmp::Reverse<DateTime<Utc>>, uuid::Uuid) {
+        (std::cmp::Reverse(self.started_at), self.workflow_id.uuid())
+    }
+}
+
+/// Page of a listing of executions to return
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Page {
+    /// Maximum number of executions on the page (at least one)
+    pub limit: usize,
+
+    /// Cursor returned with the previous page (the first page if `None`)
+    pub after: Option<Cursor>,
+}
+
+impl Page {
+    /// The first page of a listing
+    pub fn first(limit: usize) -> Self {
+        Self { limit, after: None }
+    }
+
+    /// The page following `cursor`
+    pub fn after(cursor: Cursor, limit: usize) -> Self {
+        Self {
+            limit,
+            after: Some(cursor),
+        }
+    }
+}
+
+/// Overview of an execution, without its node state
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExecutionSummary {
+    /// Workflow the execution runs
+    pub workflow_id: WorkflowId,
+
+    /// Status of the execution
+    pub status: ExecutionStatus,
+
+    /// When the execution started
+    pub started_at: DateTime<Utc>,
+
+    /// When the execution reached a terminal status, if it has
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+impl ExecutionSummary {
+    /// Cursor pointing at this execution
+    fn cursor(&self) -> Cursor {
+        Cursor {
+            started_at: self.started_at,
+            workflow_id: self.workflow_id,
+        }
+    }
+}
+
+/// Select the page of a listing of executions, newest first
+///
+/// Returns the page and, if more executions follow, the cursor of the next
+/// page.
+pub(crate) fn paginate(
+    mut summaries: Vec<ExecutionSummary>,
+    page: Page,
+) -> (Vec<ExecutionSummary>, Option<Cursor>) {
+    if let Some(after) = &page.after {
+        summaries.retain(|summary| summary.cursor().key() > after.key());
+    }
+    summaries.sort_by_key(|summary| summary.cursor().key());
+
+    let limit = page.limit.max(1);
+    let next = if summaries.len() > limit {
+        summaries.truncate(limit);
+        summaries.last().map(ExecutionSummary::cursor)
+    } else {
+        None
+    };
+    (summaries, next)
+}
+
+/// A finished execution, as archived before it is cleaned up
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ArchivedExecution {
+    /// Overview of the execution
+    pub summary: ExecutionSummary,
+
+    /// Tags the execution was started with
+    pub tags: HashMap<String, String>,
+
+    /// Outputs of the completed nodes, by node ID
+    pub node_outputs: HashMap<String, serde_json::Value>,
+
+    /// Error that failed the execution, if any
+    pub error: Option<String>,
+}
+
+/// Index from execution tags to the workflows started with them
+#[derive(Debug, Default)]
+struct TagIndex {
+    /// Workflows by tag key, then by tag value
+    workflows: HashMap<String, HashMap<String, HashSet<WorkflowId>>>,
+}
+
+impl TagIndex {
+    /// Index a workflow under each of its tags
+    fn insert(&mut self, workflow_id: WorkflowId, tags: &HashMap<String, String>) {
+        for (key, value) in tags {
+            self.workflows
+                .entry(key.clone())
+                .or_default()
+                .entry(value.clone())
+                .or_default()
+                .insert(workflow_id);
+        }
+    }
+
+    /// Drop a workflow from under each of its tags
+    fn remove(&mut self, workflow_id: &WorkflowId, tags: &HashMap<String, String>) {
+        for (key, value) in tags {
+            let Some(values) = self.workflows.get_mut(key) else {
+                continue;
+            };
+            if let Some(ids) = values.get_mut(value) {
+                ids.remove(workflow_id);
+                if ids.is_empty() {
+                    values.remove(value);
+                }
+            }
+            if values.is_empty() {
+                self.workflows.remove(key);
+            }
+        }
+    }
+
+    /// Get the workflows carrying every tag in `filters`, which must not be
+    /// empty
+    fn find(&self, filters: &HashMap<String, String>) -> HashSet<WorkflowId> {
+        let mut sets = Vec::with_capacity(filters.len());
+        for (key, value) in filters {
+            match self.workflows.get(key).and_then(|values| values.get(value)) {
+                Some(ids) => sets.push(ids),
+                None => return HashSet::new(),
+            }
+        }
+
+        // Intersect starting from the smallest set
+        sets.sort_by_key(|ids| ids.len());
+        let Some((smallest, rest)) = sets.split_first() else {
+            return HashSet::new();
+        };
+        smallest
+            .iter()
+            .filter(|id| rest.iter().all(|ids| ids.contains(id)))
+            .copied()
+            .collect()
+    }
 }
 
 /// Workflow executor for managing workflow execution
@@ -86,6 +361,15 @@ pub struct WorkflowExecutor {
 
     /// Plugin manager
     plugin_manager: Arc<PluginManager>,
+
+    /// Signalled whenever a workflow changes status
+    status_changed: Arc<Notify>,
+
+    /// Workflows by the tags they were started with
+    tag_index: Arc<RwLock<TagIndex>>,
+
+    /// Node status changes of every execution
+    node_events: broadcast::Sender<NodeStatusEvent>,
 }
 
 impl WorkflowExecutor {
@@ -98,15 +382,51 @@ impl WorkflowExecutor {
             workflow_states: Arc::new(RwLock::new(HashMap::new())),
             capability_manager,
             plugin_manager,
+            status_changed: Arc::new(Notify::new()),
+            tag_index: Arc::new(RwLock::new(TagIndex::default())),
+            node_events: broadcast::channel(NODE_EVENT_CAPACITY).0,
         }
     }
 
+    /// Subscribe to the node status changes of every execution, as they
+    /// happen
+    ///
+    /// Filter on `NodeStatusEvent::workflow_id` to follow one execution.
+    pub fn subscribe_node_events(&self) -> broadcast::Receiver<NodeStatusEvent> {
+        self.node_events.subscribe()
+    }
+
+    /// Publish a node status change to subscribers
+    fn publish_node_status(&self, workflow_id: WorkflowId, node_id: NodeId, status: NodeStatus) {
+        // Nobody may be subscribed
+        let _ = self.node_events.send(NodeStatusEvent {
+            workflow_id,
+            node_id,
+            status,
+            at: Utc::now(),
+        });
+    }
+
     /// Start a workflow
     pub async fn start_workflow(
         &self,
         workflow_id: WorkflowId,
         definition: WorkflowDefinition,
         input: serde_json::Value,
+    ) -> Result<()> {
+        self.start_workflow_with_tags(workflow_id, definition, input, HashMap::new())
+            .await
+    }
+
+    /// Start a workflow carrying tags it can later be found by
+    ///
+    /// Restarting a workflow replaces the tags of its previous execution.
+    pub async fn start_workflow_with_tags(
+        &self,
+        workflow_id: WorkflowId,
+        definition: WorkflowDefinition,
+        input: serde_json::Value,
+        tags: HashMap<String, String>,
     ) -> Result<()> {
         info!("Starting workflow: {:?}", workflow_id);
 
@@ -128,21 +448,36 @@ impl WorkflowExecutor {
             input,
             start_time: Some(Instant::now()),
             end_time: None,
+            error: None,
+            completion_subscribers: Vec::new(),
+            tags,
+            started_at: Utc::now(),
+            finished_at: None,
         };
 
-        // Store the state
-        self.workflow_states
-            .write()
-            .await
-            .insert(workflow_id, state);
-
+        // Store the state, re-indexing the workflow under its new tags
+        let node_ids: Vec<NodeId> = state.node_statuses.keys().copied().collect();
+        let mut states = self.workflow_states.write().await;
+        let mut tag_index = self.tag_index.write().await;
+        if let Some(previous) = states.get(&workflow_id) {
+            tag_index.remove(&workflow_id, &previous.tags);
+        }
+        tag_index.insert(workflow_id, &state.tags);
+        states.insert(workflow_id, state);
+        drop(tag_index);
+        drop(states);
+
+        self.status_changed.notify_waiters();
+        for node_id in node_ids {
+            self.publish_node_status(workflow_id, node_id, NodeStatus::Pending);
+        }
         info!("Workflow started: {:?}", workflow_id);
 
         Ok(())
     }
 
-    /// Pause a workflow
-    pub async fn pause_workflow(&self, workflow_id: WorkflowId) -> Result<()> {
+    /// Pause a running workflow, returning its new status
+    pub async fn pause_workflow(&self, workflow_id: WorkflowId) -> Result<ExecutionStatus> {
         info!("Pausing workflow: {:?}", workflow_id);
 
         let mut states = self.workflow_states.write().await;
@@ -150,18 +485,20 @@ impl WorkflowExecutor {
             .get_mut(&workflow_id)
             .ok_or(ExecutionError::WorkflowNotRunning(workflow_id))?;
 
-        if state.status == ExecutionStatus::Running {
-            state.status = ExecutionStatus::Paused;
-            info!("Workflow paused: {:?}", workflow_id);
-        } else {
+        if state.status != ExecutionStatus::Running {
             debug!("Workflow not running, cannot pause: {:?}", workflow_id);
+            return Err(ExecutionError::WorkflowNotRunning(workflow_id).into());
         }
 
-        Ok(())
+        state.status = ExecutionStatus::Paused;
+        self.status_changed.notify_waiters();
+        info!("Workflow paused: {:?}", workflow_id);
+
+        Ok(state.status)
     }
 
-    /// Resume a workflow
-    pub async fn resume_workflow(&self, workflow_id: WorkflowId) -> Result<()> {
+    /// Resume a paused workflow, returning its new status
+    pub async fn resume_workflow(&self, workflow_id: WorkflowId) -> Result<ExecutionStatus> {
         info!("Resuming workflow: {:?}", workflow_id);
 
         let mut states = self.workflow_states.write().await;
@@ -169,17 +506,22 @@ impl WorkflowExecutor {
             .get_mut(&workflow_id)
             .ok_or(ExecutionError::WorkflowNotRunning(workflow_id))?;
 
-        if state.status == ExecutionStatus::Paused {
-            state.status = ExecutionStatus::Running;
-            info!("Workflow resumed: {:?}", workflow_id);
-        } else {
+        if state.status != ExecutionStatus::Paused {
             debug!("Workflow not paused, cannot resume: {:?}", workflow_id);
+            return Err(ExecutionError::WorkflowNotPaused(workflow_id).into());
         }
 
-        Ok(())
+        state.status = ExecutionStatus::Running;
+        self.status_changed.notify_waiters();
+        info!("Workflow resumed: {:?}", workflow_id);
+
+        Ok(state.status)
     }
 
-    /// Cancel a workflow
+    /// Cancel a running or paused workflow
+    ///
+    /// Fails with `ExecutionError::WorkflowAlreadyFinished` if the workflow
+    /// has completed, failed or been cancelled, leaving its status as is.
     pub async fn cancel_workflow(&self, workflow_id: WorkflowId) -> Result<()> {
         info!("Cancelling workflow: {:?}", workflow_id);
 
@@ -188,14 +530,45 @@ impl WorkflowExecutor {
             .get_mut(&workflow_id)
             .ok_or(ExecutionError::WorkflowNotRunning(workflow_id))?;
 
-        state.status = ExecutionStatus::Cancelled;
-        state.end_time = Some(Instant::now());
+        if state.is_finished() {
+            debug!(
+                "Workflow already finished, cannot cancel: {:?}",
+                workflow_id
+            );
+            return Err(ExecutionError::WorkflowAlreadyFinished(workflow_id).into());
+        }
+
+        state.finish(ExecutionStatus::Cancelled);
+        self.status_changed.notify_waiters();
 
         info!("Workflow cancelled: {:?}", workflow_id);
 
         Ok(())
     }
 
+    /// Subscribe to the completion of a workflow
+    ///
+    /// The receiver resolves with the terminal status (completed, failed or
+    /// cancelled), immediately if the workflow has already finished.
+    pub async fn subscribe_completion(
+        &self,
+        workflow_id: &WorkflowId,
+    ) -> Result<oneshot::Receiver<ExecutionStatus>> {
+        let mut states = self.workflow_states.write().await;
+        let state = states
+            .get_mut(workflow_id)
+            .ok_or(ExecutionError::WorkflowNotRunning(*workflow_id))?;
+
+        let (tx, rx) = oneshot::channel();
+        if state.is_finished() {
+            let _ = tx.send(state.status);
+        } else {
+            state.completion_subscribers.push(tx);
+        }
+
+        Ok(rx)
+    }
+
     /// Get workflow status
     pub async fn get_workflow_status(&self, workflow_id: &WorkflowId) -> Result<ExecutionStatus> {
         let states = self.workflow_states.read().await;
@@ -206,6 +579,152 @@ impl WorkflowExecutor {
             .ok_or(ExecutionError::WorkflowNotRunning(*workflow_id).into())
     }
 
+    /// Find the started workflows carrying every tag in `filters`
+    ///
+    /// Empty filters match every started workflow. The IDs are sorted.
+    pub async fn find_workflows(&self, filters: &HashMap<String, String>) -> Vec<WorkflowId> {
+        let mut workflow_ids: Vec<WorkflowId> = if filters.is_empty() {
+            self.workflow_states.read().await.keys().copied().collect()
+        } else {
+            self.tag_index
+                .read()
+                .await
+                .find(filters)
+                .into_iter()
+                .collect()
+        };
+        workflow_ids.sort_by_key(|id| id.uuid());
+        workflow_ids
+    }
+
+    /// List the executions passing a filter, a page at a time, newest first
+    ///
+    /// Returns the page and, if more executions follow, the cursor of the
+    /// next page.
+    pub async fn list_workflows(
+        &self,
+        filter: &ExecutionFilter,
+        page: Page,
+    ) -> (Vec<ExecutionSummary>, Option<Cursor>) {
+        let summaries: Vec<ExecutionSummary> = {
+            let states = self.workflow_states.read().await;
+            states
+                .iter()
+                .filter(|(_, state)| filter.matches(state.status, state.started_at))
+                .map(|(workflow_id, state)| ExecutionSummary {
+                    workflow_id: *workflow_id,
+                    status: state.status,
+                    started_at: state.started_at,
+                    finished_at: state.finished_at,
+                })
+                .collect()
+        };
+        paginate(summaries, page)
+    }
+
+    /// Get the executions that finished before a time
+    pub async fn finished_before(&self, cutoff: DateTime<Utc>) -> Vec<ArchivedExecution> {
+        let states = self.workflow_states.read().await;
+        states
+            .iter()
+            .filter(|(_, state)| state.finished_at.is_some_and(|at| at < cutoff))
+            .map(|(workflow_id, state)| state.archive(*workflow_id))
+            .collect()
+    }
+
+    /// Get an execution in the form it is archived in, whatever its status
+    pub async fn export_execution(&self, workflow_id: &WorkflowId) -> Result<ArchivedExecution> {
+        let states = self.workflow_states.read().await;
+
+        states
+            .get(workflow_id)
+            .map(|state| state.archive(*workflow_id))
+            .ok_or(ExecutionError::WorkflowNotRunning(*workflow_id).into())
+    }
+
+    /// Restore an exported execution, without running any of its nodes
+    ///
+    /// The execution keeps its recorded status, so one that has finished
+    /// stays finished. Nodes with a recorded output are completed and the
+    /// rest pending. Replaces the workflow's current execution, if any.
+    pub async fn restore_workflow(
+        &self,
+        definition: WorkflowDefinition,
+        execution: ArchivedExecution,
+    ) -> Result<()> {
+        let workflow_id = execution.summary.workflow_id;
+        info!("Restoring workflow: {:?}", workflow_id);
+
+        let mut node_outputs = HashMap::new();
+        for (node_id, output) in execution.node_outputs {
+            node_outputs.insert(node_id.parse::<NodeId>()?, output);
+        }
+        let node_statuses = definition
+            .nodes
+            .keys()
+            .map(|model_node_id| {
+                let node_id = convert_node_id(model_node_id);
+                let status = if node_outputs.contains_key(&node_id) {
+                    NodeStatus::Completed
+                } else {
+                    NodeStatus::Pending
+                };
+                (node_id, status)
+            })
+            .collect();
+
+        let state = WorkflowExecutionState {
+            definition,
+            status: execution.summary.status,
+            node_statuses,
+            node_outputs,
+            input: serde_json::json!({}),
+            start_time: None,
+            end_time: None,
+            error: execution.error,
+            completion_subscribers: Vec::new(),
+            tags: execution.tags,
+            started_at: execution.summary.started_at,
+            finished_at: execution.summary.finished_at,
+        };
+
+        // Store the state, re-indexing the workflow under its tags
+        let mut states = self.workflow_states.write().await;
+        let mut tag_index = self.tag_index.write().await;
+        if let Some(previous) = states.get(&workflow_id) {
+            tag_index.remove(&workflow_id, &previous.tags);
+        }
+        tag_index.insert(workflow_id, &state.tags);
+        states.insert(workflow_id, state);
+        drop(tag_index);
+        drop(states);
+
+        self.status_changed.notify_waiters();
+
+        Ok(())
+    }
+
+    /// Remove a finished execution, unless the workflow has been restarted
+    /// since it was read
+    ///
+    /// Returns whether the execution was removed.
+    pub async fn remove_finished(&self, summary: &ExecutionSummary) -> bool {
+        let mut states = self.workflow_states.write().await;
+        let Some(state) = states.get(&summary.workflow_id) else {
+            return false;
+        };
+        if !state.is_finished() || state.started_at != summary.started_at {
+            return false;
+        }
+
+        self.tag_index
+            .write()
+            .await
+            .remove(&summary.workflow_id, &state.tags);
+        states.remove(&summary.workflow_id);
+        true
+    }
+
     /// Get workflow results
     pub async fn get_workflow_results(
         &self,
@@ -237,6 +756,121 @@ impl WorkflowExecutor {
         Ok(results)
     }
 
+    /// Record the output of a completed node
+    ///
+    /// The workflow completes once all of its nodes have completed.
+    pub async fn complete_node(
+        &self,
+        workflow_id: &WorkflowId,
+        node_id: &NodeId,
+        output: serde_json::Value,
+    ) -> Result<()> {
+        let mut states = self.workflow_states.write().await;
+        let state = Self::running_state(&mut states, workflow_id, node_id)?;
+
+        state.node_statuses.insert(*node_id, NodeStatus::Completed);
+        state.node_outputs.insert(*node_id, output);
+
+        if state
+            .node_statuses
+            .values()
+            .all(|status| *status == NodeStatus::Completed)
+        {
+            state.finish(ExecutionStatus::Completed);
+            info!("Workflow completed: {:?}", workflow_id);
+        }
+        drop(states);
+        self.status_changed.notify_waiters();
+        self.publish_node_status(*workflow_id, *node_id, NodeStatus::Completed);
+
+        Ok(())
+    }
+
+    /// Record the failure of a node, which fails the workflow
+    pub async fn fail_node(
+        &self,
+        workflow_id: &WorkflowId,
+        node_id: &NodeId,
+        error: impl Into<String>,
+    ) -> Result<()> {
+        let mut states = self.workflow_states.write().await;
+        let state = Self::running_state(&mut states, workflow_id, node_id)?;
+        let error = error.into();
+
+        state.node_statuses.insert(*node_id, NodeStatus::Failed);
+        state.error = Some(format!("Node {} failed: {}", node_id, error));
+        state.finish(ExecutionStatus::Failed);
+        drop(states);
+        self.status_changed.notify_waiters();
+        self.publish_node_status(*workflow_id, *node_id, NodeStatus::Failed);
+
+        error!(
+            "Workflow {:?} failed at node {:?}: {}",
+            workflow_id, node_id, error
+        );
+
+        Ok(())
+    }
+
+    /// Get the state of a running workflow that contains a node
+    fn running_state<'a>(
+        states: &'a mut HashMap<WorkflowId, WorkflowExecutionState>,
+        workflow_id: &WorkflowId,
+        node_id: &NodeId,
+    ) -> Result<&'a mut WorkflowExecutionState> {
+        let state = states
+            .get_mut(workflow_id)
+            .filter(|state| state.status == ExecutionStatus::Running)
+            .ok_or(ExecutionError::WorkflowNotRunning(*workflow_id))?;
+
+        if !state.node_statuses.contains_key(node_id) {
+            return Err(ExecutionError::NodeNotFound(*node_id).into());
+        }
+
+        Ok(state)
+    }
+
+    /// Wait until a workflow finishes, returning its results
+    ///
+    /// Fails with `ExecutionError::WorkflowExecutionFailed` if the workflow
+    /// fails or is cancelled.
+    pub async fn wait_for_workflow(&self, workflow_id: &WorkflowId) -> Result<serde_json::Value> {
+        loop {
+            // Register for the next status change before checking, so that a
+            // change in between is not missed
+            let notified = self.status_changed.notified();
+            tokio::pin!(notified);
+            notified.as_mut().enable();
+
+            let (status, error) = {
+                let states = self.workflow_states.read().await;
+                let state = states
+                    .get(workflow_id)
+                    .ok_or(ExecutionError::WorkflowNotRunning(*workflow_id))?;
+                (state.status, state.error.clone())
+            };
+
+            match status {
+                ExecutionStatus::Completed => return self.get_workflow_results(workflow_id).await,
+                ExecutionStatus::Failed => {
+                    return Err(ExecutionError::WorkflowExecutionFailed(
+                        *workflow_id,
+                        error.unwrap_or_else(|| "unknown error".to_string()),
+                    )
+                    .into())
+                }
+                ExecutionStatus::Cancelled => {
+                    return Err(ExecutionError::WorkflowExecutionFailed(
+                        *workflow_id,
+                        "cancelled".to_string(),
+                    )
+                    .into())
+                }
+                _ => notified.await,
+            }
+        }
+    }
+
     /// Execute a node in a workflow
     #[allow(dead_code)]
     async fn execute_node(
@@ -268,6 +902,9 @@ impl Clone for WorkflowExecutor {
             workflow_states: self.workflow_states.clone(),
             capability_manager: self.capability_manager.clone(),
             plugin_manager: self.plugin_manager.clone(),
+            status_changed: self.status_changed.clone(),
+            tag_index: self.tag_index.clone(),
+            node_events: self.node_events.clone(),
         }
     }
 }
@@ -4,17 +4,29 @@
 
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Duration;
 
 use anyhow::Result;
-use lion_core::id::{PluginId, WorkflowId};
-use lion_core::types::workflow::ExecutionStatus;
+use chrono::{DateTime, Utc};
+use lion_core::id::{ExecutionId, NodeId as CoreNodeId, PluginId, WorkflowId};
+use lion_core::traits::{WorkflowEngine, WorkflowEngineRegistry};
+use lion_core::types::workflow::{
+    ExecutionOptions, ExecutionStatus, NodeType as CoreNodeType, Workflow, WorkflowNode,
+};
 use lion_workflow::model::definition::WorkflowDefinition;
 use lion_workflow::model::definition::WorkflowId as DefWorkflowId;
-use tokio::sync::RwLock;
+use lion_workflow::model::NodeType;
+use lion_workflow::state::{FileStorage, StorageBackend};
+use tokio::sync::{broadcast, oneshot, Mutex, RwLock};
+use tokio::task::JoinHandle;
 use tracing::{debug, error, info, warn};
 
-use super::execution::WorkflowExecutor;
+use super::execution::{
+    paginate, ArchivedExecution, Cursor, ExecutionError, ExecutionFilter, ExecutionSummary,
+    NodeStatusEvent, Page, WorkflowExecutor,
+};
 use crate::capabilities::manager::CapabilityManager;
+use crate::capabilities::workflow::WorkflowExecuteCapability;
 use crate::plugin::manager::PluginManager;
 use crate::system::config::RuntimeConfig;
 
@@ -32,6 +44,25 @@ pub enum WorkflowManagerError {
 
     #[error("Invalid workflow definition: {0}")]
     InvalidDefinition(String),
+
+    #[error("Principal {0} is not authorized to execute workflow {1}")]
+    Unauthorized(String, WorkflowId),
+
+    #[error("Workflow engine {0} is not registered")]
+    UnknownEngine(String),
+
+    #[error("Workflow {0} runs on a workflow engine, which does not support {1}")]
+    EngineUnsupported(WorkflowId, &'static str),
+}
+
+/// Outcome of one cleanup of finished executions
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CleanupReport {
+    /// Executions removed from memory
+    pub removed: usize,
+
+    /// Executions archived before removal
+    pub archived: usize,
 }
 
 /// Convert a workflow definition ID to a core workflow ID
@@ -45,22 +76,106 @@ fn core_to_def_id(core_id: &WorkflowId) -> DefWorkflowId {
     DefWorkflowId::from_uuid(core_id.uuid())
 }
 
+/// Engine running a workflow, and the workflow's ID in that engine
+type EngineWorkflow = (Arc<dyn WorkflowEngine>, WorkflowId);
+
+/// Latest execution of a workflow run by a registered engine
+#[derive(Debug, Clone)]
+struct EngineExecution {
+    /// Execution ID in the engine
+    execution_id: ExecutionId,
+
+    /// When the execution was started
+    started_at: DateTime<Utc>,
+
+    /// Tags the execution was started with
+    tags: HashMap<String, String>,
+}
+
+/// Convert a workflow definition to the form workflow engines take
+///
+/// Plugin call nodes keep their plugin and function; other nodes become
+/// custom nodes typed by their name, which selects their handler.
+fn to_core_workflow(definition: &WorkflowDefinition) -> Workflow {
+    let mut workflow = Workflow::new(
+        definition.name.clone(),
+        definition.description.clone().unwrap_or_default(),
+    );
+    workflow.id = def_to_core_id(&definition.id);
+
+    let mut nodes: Vec<_> = definition.nodes.values().collect();
+    nodes.sort_by_key(|node| node.id.uuid());
+    for node in nodes {
+        let node_type = match &node.node_type {
+            NodeType::PluginCall {
+                plugin_id,
+                function,
+            } => CoreNodeType::PluginCall {
+                plugin_id: plugin_id.clone(),
+                function: function.clone(),
+            },
+            _ => CoreNodeType::Custom {
+                plugin_id: String::new(),
+                type_id: node.name.clone(),
+                config: node.config.clone(),
+            },
+        };
+        let mut core_node = WorkflowNode::new(node.name.clone(), node_type);
+        core_node.id = CoreNodeId::from_uuid(node.id.uuid());
+        core_node.set_error_policy(definition.error_policy_for(&node.id));
+        let mut dependencies: Vec<_> = definition
+            .edges
+            .values()
+            .filter(|edge| edge.target == node.id)
+            .map(|edge| edge.source.uuid())
+            .collect();
+        dependencies.sort();
+        for dependency in dependencies {
+            core_node.add_dependency(CoreNodeId::from_uuid(dependency));
+        }
+        workflow.add_node(core_node);
+    }
+
+    workflow
+}
+
 /// Workflow manager for creating and managing workflows
 pub struct WorkflowManager {
     /// Map of workflow IDs to definitions
     workflows: RwLock<HashMap<WorkflowId, WorkflowDefinition>>,
 
+    /// Group each workflow belongs to, for group-scoped capabilities
+    groups: RwLock<HashMap<WorkflowId, String>>,
+
+    /// Workflow engines definitions can choose instead of the executor
+    engines: RwLock<WorkflowEngineRegistry>,
+
+    /// Engine and engine-side ID of each workflow run by a registered engine
+    engine_workflows: RwLock<HashMap<WorkflowId, EngineWorkflow>>,
+
+    /// Latest execution of each workflow run by a registered engine
+    engine_executions: RwLock<HashMap<WorkflowId, EngineExecution>>,
+
     /// Workflow executor
     executor: WorkflowExecutor,
 
     /// Capability manager
-    _capability_manager: Arc<CapabilityManager>,
+    capability_manager: Arc<CapabilityManager>,
 
     /// Plugin manager
     _plugin_manager: Arc<PluginManager>,
 
     /// Runtime configuration
-    _config: RuntimeConfig,
+    config: RuntimeConfig,
+
+    /// Storage finished executions are archived to before cleanup
+    archive: Option<Arc<dyn StorageBackend>>,
+
+    /// Outcome of the latest cleanup
+    last_cleanup: Arc<RwLock<Option<CleanupReport>>>,
+
+    /// Background task cleaning up finished executions
+    cleanup_task: Mutex<Option<JoinHandle<()>>>,
 }
 
 impl WorkflowManager {
@@ -73,25 +188,107 @@ impl WorkflowManager {
         // Create the workflow executor
         let executor = WorkflowExecutor::new(capability_manager.clone(), plugin_manager.clone());
 
+        let archive = config
+            .workflows
+            .archive_directory
+            .as_ref()
+            .map(|dir| Arc::new(FileStorage::new(dir.into())) as Arc<dyn StorageBackend>);
+
         Ok(Self {
             workflows: RwLock::new(HashMap::new()),
+            groups: RwLock::new(HashMap::new()),
+            engines: RwLock::new(WorkflowEngineRegistry::new()),
+            engine_workflows: RwLock::new(HashMap::new()),
+            engine_executions: RwLock::new(HashMap::new()),
             executor,
-            _capability_manager: capability_manager,
+            capability_manager,
             _plugin_manager: plugin_manager,
-            _config: config,
+            config,
+            archive,
+            last_cleanup: Arc::new(RwLock::new(None)),
+            cleanup_task: Mutex::new(None),
         })
     }
 
+    /// Archive finished executions to a storage backend before cleanup,
+    /// instead of the configured archive directory
+    pub fn with_archive(mut self, archive: Arc<dyn StorageBackend>) -> Self {
+        self.archive = Some(archive);
+        self
+    }
+
     /// Start the workflow manager
+    ///
+    /// Unless the cleanup interval is zero, this starts a background task
+    /// removing finished executions older than the retention period.
     pub async fn start(&self) -> Result<()> {
         info!("Starting workflow manager");
 
         // TODO: Load persisted workflows if needed
 
+        let interval = self.config.workflows.cleanup_interval;
+        if interval > 0 {
+            let executor = self.executor.clone();
+            let archive = self.archive.clone();
+            let retention = Duration::from_secs(self.config.workflows.execution_retention);
+            let last_cleanup = self.last_cleanup.clone();
+            let task = tokio::spawn(async move {
+                let mut ticks = tokio::time::interval(Duration::from_secs(interval));
+                loop {
+                    ticks.tick().await;
+                    let report =
+                        clean_up_executions(&executor, archive.as_deref(), retention).await;
+                    *last_cleanup.write().await = Some(report);
+                }
+            });
+            if let Some(previous) = self.cleanup_task.lock().await.replace(task) {
+                previous.abort();
+            }
+        }
+
         Ok(())
     }
 
+    /// Remove finished executions older than the retention period now,
+    /// archiving them first if an archive is set
+    pub async fn cleanup_executions(&self) -> CleanupReport {
+        let retention = Duration::from_secs(self.config.workflows.execution_retention);
+        let report = clean_up_executions(&self.executor, self.archive.as_deref(), retention).await;
+        *self.last_cleanup.write().await = Some(report);
+        report
+    }
+
+    /// Get the outcome of the latest cleanup, if one has run
+    pub async fn last_cleanup(&self) -> Option<CleanupReport> {
+        *self.last_cleanup.read().await
+    }
+
+    /// Register a workflow engine under a name
+    ///
+    /// Workflow definitions naming the engine in `engine` are handed to it
+    /// instead of the built-in executor, so workflows can move between
+    /// engines one at a time.
+    pub async fn register_workflow_engine(
+        &self,
+        name: impl Into<String>,
+        engine: Arc<dyn WorkflowEngine>,
+    ) -> Result<()> {
+        let name = name.into();
+        info!("Registering workflow engine: {}", name);
+        self.engines.write().await.register(name, engine)?;
+        Ok(())
+    }
+
+    /// Get the names of the registered workflow engines
+    pub async fn workflow_engines(&self) -> Vec<String> {
+        let engines = self.engines.read().await;
+        engines.names().into_iter().map(String::from).collect()
+    }
+
     /// Register a workflow
+    ///
+    /// A definition choosing a workflow engine is created in that engine,
+    /// which must already be registered.
     pub async fn register_workflow(&self, definition: WorkflowDefinition) -> Result<WorkflowId> {
         info!("Registering workflow: {}", definition.name);
 
@@ -107,6 +304,21 @@ impl WorkflowManager {
             return Err(WorkflowManagerError::AlreadyExists(core_id).into());
         }
 
+        // Hand the workflow to its engine, if it chose one
+        if let Some(name) = &definition.engine {
+            let engine = self
+                .engines
+                .read()
+                .await
+                .get(name)
+                .map_err(|_| WorkflowManagerError::UnknownEngine(name.clone()))?;
+            let engine_id = engine.create_workflow(to_core_workflow(&definition))?;
+            self.engine_workflows
+                .write()
+                .await
+                .insert(core_id, (engine, engine_id));
+        }
+
         // Store the workflow using the core ID
         workflows.insert(core_id, definition);
 
@@ -115,6 +327,13 @@ impl WorkflowManager {
 
     /// Validate a workflow definition
     async fn validate_workflow(&self, definition: &WorkflowDefinition) -> Result<()> {
+        // Check the chosen engine exists
+        if let Some(name) = &definition.engine {
+            if !self.engines.read().await.contains(name) {
+                return Err(WorkflowManagerError::UnknownEngine(name.clone()).into());
+            }
+        }
+
         // Check for empty nodes
         if definition.nodes.is_empty() {
             return Err(WorkflowManagerError::InvalidDefinition(
@@ -164,6 +383,18 @@ impl WorkflowManager {
         &self,
         workflow_id: WorkflowId,
         input: serde_json::Value,
+    ) -> Result<()> {
+        self.start_workflow_with_options(workflow_id, input, &ExecutionOptions::default())
+            .await
+    }
+
+    /// Start a workflow, tagging the execution with `options.tags` so it can
+    /// be found by `find_executions`
+    pub async fn start_workflow_with_options(
+        &self,
+        workflow_id: WorkflowId,
+        input: serde_json::Value,
+        options: &ExecutionOptions,
     ) -> Result<()> {
         info!("Starting workflow: {:?}", workflow_id);
 
@@ -176,16 +407,134 @@ impl WorkflowManager {
                 .ok_or(WorkflowManagerError::NotFound(workflow_id))?
         };
 
+        // Run it on its engine, if it chose one
+        let engine_workflow = self
+            .engine_workflows
+            .read()
+            .await
+            .get(&workflow_id)
+            .cloned();
+        if let Some((engine, engine_id)) = engine_workflow {
+            let execution_id = engine.execute_workflow(
+                &engine_id,
+                ExecutionOptions {
+                    input: Some(input),
+                    ..options.clone()
+                },
+            )?;
+            self.engine_executions.write().await.insert(
+                workflow_id,
+                EngineExecution {
+                    execution_id,
+                    started_at: Utc::now(),
+                    tags: options.tags.clone(),
+                },
+            );
+            return Ok(());
+        }
+
         // Start the workflow
         self.executor
-            .start_workflow(workflow_id, definition, input)
+            .start_workflow_with_tags(workflow_id, definition, input, options.tags.clone())
             .await?;
 
         Ok(())
     }
 
-    /// Pause a workflow
-    pub async fn pause_workflow(&self, workflow_id: WorkflowId) -> Result<()> {
+    /// Assign a workflow to a group, so group-scoped capabilities apply to it
+    pub async fn set_workflow_group(
+        &self,
+        workflow_id: WorkflowId,
+        group: impl Into<String>,
+    ) -> Result<()> {
+        if !self.workflows.read().await.contains_key(&workflow_id) {
+            return Err(WorkflowManagerError::NotFound(workflow_id).into());
+        }
+
+        self.groups.write().await.insert(workflow_id, group.into());
+        Ok(())
+    }
+
+    /// Execute a workflow on behalf of a principal
+    ///
+    /// The principal must hold a `WorkflowExecuteCapability` for the workflow
+    /// itself or for the group it belongs to.
+    pub async fn execute_workflow(
+        &self,
+        principal: &str,
+        workflow_id: WorkflowId,
+        input: serde_json::Value,
+    ) -> Result<()> {
+        self.authorize_execution(principal, workflow_id).await?;
+        self.start_workflow(workflow_id, input).await
+    }
+
+    /// Check that a principal may execute a registered workflow
+    async fn authorize_execution(&self, principal: &str, workflow_id: WorkflowId) -> Result<()> {
+        if !self.workflows.read().await.contains_key(&workflow_id) {
+            return Err(WorkflowManagerError::NotFound(workflow_id).into());
+        }
+
+        let authorized = WorkflowExecuteCapability::for_workflow(workflow_id)
+            .is_held_by(&self.capability_manager, principal)
+            || match self.groups.read().await.get(&workflow_id) {
+                Some(group) => WorkflowExecuteCapability::for_group(group.clone())
+                    .is_held_by(&self.capability_manager, principal),
+                None => false,
+            };
+
+        if !authorized {
+            warn!(
+                "Principal {} denied execution of workflow {:?}",
+                principal, workflow_id
+            );
+            return Err(
+                WorkflowManagerError::Unauthorized(principal.to_string(), workflow_id).into(),
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Execute a workflow on behalf of `options.principal` and wait for its
+    /// results
+    ///
+    /// Fails with `ExecutionError::Timeout` if the workflow has not finished
+    /// within `timeout` (it keeps running), and with
+    /// `ExecutionError::WorkflowExecutionFailed` if it fails or is cancelled.
+    /// Workflows run by a registered engine are refused, as engines do not
+    /// report completion.
+    pub async fn execute_workflow_and_wait(
+        &self,
+        workflow_id: WorkflowId,
+        input: serde_json::Value,
+        options: &ExecutionOptions,
+        timeout: Duration,
+    ) -> Result<serde_json::Value> {
+        let principal = options
+            .principal
+            .as_ref()
+            .map_or_else(|| "anonymous".to_string(), |p| p.to_string());
+        self.authorize_execution(&principal, workflow_id).await?;
+        self.refuse_engine_workflow(&workflow_id, "waiting for completion")
+            .await?;
+        self.start_workflow_with_options(workflow_id, input, options)
+            .await?;
+
+        match tokio::time::timeout(timeout, self.executor.wait_for_workflow(&workflow_id)).await {
+            Ok(result) => result,
+            Err(_) => {
+                warn!(
+                    "Workflow {:?} did not finish within {:?}",
+                    workflow_id, timeout
+                );
+                Err(ExecutionError::Timeout.into())
+            }
+        }
+    }
+
+    /// Pause a running workflow, returning its new status
+    pub async fn pause_workflow(&self, workflow_id: WorkflowId) -> Result<ExecutionStatus> {
         info!("Pausing workflow: {:?}", workflow_id);
 
         // Verify workflow exists
@@ -197,13 +546,15 @@ impl WorkflowManager {
         }
 
         // Pause the workflow
-        self.executor.pause_workflow(workflow_id).await?;
-
-        Ok(())
+        if let Some((engine, execution_id)) = self.engine_execution(&workflow_id).await {
+            engine.pause_execution(&execution_id)?;
+            return Ok(engine.get_execution_status(&execution_id)?);
+        }
+        self.executor.pause_workflow(workflow_id).await
     }
 
-    /// Resume a workflow
-    pub async fn resume_workflow(&self, workflow_id: WorkflowId) -> Result<()> {
+    /// Resume a paused workflow, returning its new status
+    pub async fn resume_workflow(&self, workflow_id: WorkflowId) -> Result<ExecutionStatus> {
         info!("Resuming workflow: {:?}", workflow_id);
 
         // Verify workflow exists
@@ -215,12 +566,17 @@ impl WorkflowManager {
         }
 
         // Resume the workflow
-        self.executor.resume_workflow(workflow_id).await?;
-
-        Ok(())
+        if let Some((engine, execution_id)) = self.engine_execution(&workflow_id).await {
+            engine.resume_execution(&execution_id)?;
+            return Ok(engine.get_execution_status(&execution_id)?);
+        }
+        self.executor.resume_workflow(workflow_id).await
     }
 
-    /// Cancel a workflow
+    /// Cancel a running or paused workflow
+    ///
+    /// Completion subscribers are notified with `ExecutionStatus::Cancelled`.
+    /// Fails if the workflow was never started or has already finished.
     pub async fn cancel_workflow(&self, workflow_id: WorkflowId) -> Result<()> {
         info!("Cancelling workflow: {:?}", workflow_id);
 
@@ -233,6 +589,10 @@ impl WorkflowManager {
         }
 
         // Cancel the workflow
+        if let Some((engine, execution_id)) = self.engine_execution(&workflow_id).await {
+            engine.cancel_execution(&execution_id)?;
+            return Ok(());
+        }
         self.executor.cancel_workflow(workflow_id).await?;
 
         Ok(())
@@ -249,9 +609,143 @@ impl WorkflowManager {
         }
 
         // Get status
+        if let Some((engine, execution_id)) = self.engine_execution(workflow_id).await {
+            return Ok(engine.get_execution_status(&execution_id)?);
+        }
         return self.executor.get_workflow_status(workflow_id).await;
     }
 
+    /// Get the engine and latest execution of a workflow run by a registered
+    /// engine, if it has been started
+    async fn engine_execution(
+        &self,
+        workflow_id: &WorkflowId,
+    ) -> Option<(Arc<dyn WorkflowEngine>, ExecutionId)> {
+        let execution_id = self
+            .engine_executions
+            .read()
+            .await
+            .get(workflow_id)?
+            .execution_id;
+        let engine_workflows = self.engine_workflows.read().await;
+        let (engine, _) = engine_workflows.get(workflow_id)?;
+        Some((engine.clone(), execution_id))
+    }
+
+    /// Fail if a workflow is run by a registered engine, which cannot perform
+    /// `operation`
+    async fn refuse_engine_workflow(
+        &self,
+        workflow_id: &WorkflowId,
+        operation: &'static str,
+    ) -> Result<()> {
+        if self.engine_workflows.read().await.contains_key(workflow_id) {
+            return Err(WorkflowManagerError::EngineUnsupported(*workflow_id, operation).into());
+        }
+        Ok(())
+    }
+
+    /// Subscribe to the completion of a started workflow
+    ///
+    /// The receiver resolves with the terminal status, including
+    /// cancellation, or immediately if the workflow has already finished.
+    /// Workflows run by a registered engine are refused, as engines do not
+    /// report completion.
+    pub async fn subscribe_completion(
+        &self,
+        workflow_id: &WorkflowId,
+    ) -> Result<oneshot::Receiver<ExecutionStatus>> {
+        // Verify workflow exists
+        {
+            let workflows = self.workflows.read().await;
+            if !workflows.contains_key(workflow_id) {
+                return Err(WorkflowManagerError::NotFound(*workflow_id).into());
+            }
+        }
+
+        self.refuse_engine_workflow(workflow_id, "completion subscriptions")
+            .await?;
+        self.executor.subscribe_completion(workflow_id).await
+    }
+
+    /// Find the started workflows whose execution carries every tag in
+    /// `tag_filters`
+    ///
+    /// Lookups go through an index of the tags, so they do not scan every
+    /// execution. Empty filters match every started workflow. Executions run
+    /// by a registered engine are matched against the tags they were started
+    /// with.
+    pub async fn find_executions(&self, tag_filters: &HashMap<String, String>) -> Vec<WorkflowId> {
+        let mut workflow_ids = self.executor.find_workflows(tag_filters).await;
+        workflow_ids.extend(
+            self.engine_executions
+                .read()
+                .await
+                .iter()
+                .filter(|(_, execution)| {
+                    tag_filters
+                        .iter()
+                        .all(|(key, value)| execution.tags.get(key) == Some(value))
+                })
+                .map(|(workflow_id, _)| *workflow_id),
+        );
+        workflow_ids.sort_by_key(|id| id.uuid());
+        workflow_ids
+    }
+
+    /// List started executions passing a filter, a page at a time, newest
+    /// first
+    ///
+    /// Returns lightweight summaries and, if more executions follow, the
+    /// cursor to pass in the next `Page`. Engines do not report when an
+    /// execution finished, so summaries of executions run by a registered
+    /// engine have no `finished_at`.
+    pub async fn list_executions(
+        &self,
+        filter: &ExecutionFilter,
+        page: Page,
+    ) -> (Vec<ExecutionSummary>, Option<Cursor>) {
+        // One more than the page holds, so a following page is still detected
+        // once engine executions are merged in
+        let (mut summaries, _) = self
+            .executor
+            .list_workflows(
+                filter,
+                Page {
+                    limit: page.limit.max(1) + 1,
+                    after: page.after,
+                },
+            )
+            .await;
+
+        let engine_executions = self.engine_executions.read().await.clone();
+        for (workflow_id, execution) in engine_executions {
+            let Some((engine, execution_id)) = self.engine_execution(&workflow_id).await else {
+                continue;
+            };
+            let status = match engine.get_execution_status(&execution_id) {
+                Ok(status) => status,
+                Err(e) => {
+                    warn!(
+                        "Failed to get status of engine execution {:?}: {}",
+                        execution_id, e
+                    );
+                    continue;
+                }
+            };
+            if filter.matches(status, execution.started_at) {
+                summaries.push(ExecutionSummary {
+                    workflow_id,
+                    status,
+                    started_at: execution.started_at,
+                    finished_at: None,
+                });
+            }
+        }
+
+        paginate(summaries, page)
+    }
+
     /// Get workflow results
     pub async fn get_workflow_results(
         &self,
@@ -266,9 +760,89 @@ impl WorkflowManager {
         }
 
         // Get results
+        if let Some((engine, execution_id)) = self.engine_execution(workflow_id).await {
+            if engine.get_execution_status(&execution_id)? != ExecutionStatus::Completed {
+                return Err(ExecutionError::WorkflowNotRunning(*workflow_id).into());
+            }
+
+            // Combine the node outputs the engine recorded
+            let mut results = serde_json::Map::new();
+            for output in engine.get_execution_results(&execution_id)?.values() {
+                if let Ok(serde_json::Value::Object(obj)) = serde_json::from_slice(output) {
+                    results.extend(obj);
+                }
+            }
+            return Ok(serde_json::Value::Object(results));
+        }
         self.executor.get_workflow_results(workflow_id).await
     }
 
+    /// Subscribe to the node status changes of every workflow run by this
+    /// manager's own executor
+    ///
+    /// Workflows run by a registered engine publish no node events.
+    pub fn subscribe_node_events(&self) -> broadcast::Receiver<NodeStatusEvent> {
+        self.executor.subscribe_node_events()
+    }
+
+    /// Record the output of a completed node of a running workflow
+    ///
+    /// The workflow completes once all of its nodes have completed.
+    pub async fn complete_node(
+        &self,
+        workflow_id: &WorkflowId,
+        node_id: &CoreNodeId,
+        output: serde_json::Value,
+    ) -> Result<()> {
+        self.refuse_engine_workflow(workflow_id, "complete_node").await?;
+        self.executor.complete_node(workflow_id, node_id, output).await
+    }
+
+    /// Record the failure of a node of a running workflow, which fails the
+    /// workflow
+    pub async fn fail_node(
+        &self,
+        workflow_id: &WorkflowId,
+        node_id: &CoreNodeId,
+        error: impl Into<String>,
+    ) -> Result<()> {
+        self.refuse_engine_workflow(workflow_id, "fail_node").await?;
+        self.executor.fail_node(workflow_id, node_id, error).await
+    }
+
+    /// Export the execution of a workflow, so it can be persisted and later
+    /// restored with `restore_execution`
+    ///
+    /// Workflows run by a registered engine are refused, as engines keep
+    /// their own executions.
+    pub async fn export_execution(&self, workflow_id: &WorkflowId) -> Result<ArchivedExecution> {
+        // Verify workflow exists
+        {
+            let workflows = self.workflows.read().await;
+            if !workflows.contains_key(workflow_id) {
+                return Err(WorkflowManagerError::NotFound(*workflow_id).into());
+            }
+        }
+
+        self.refuse_engine_workflow(workflow_id, "export_execution")
+            .await?;
+        self.executor.export_execution(workflow_id).await
+    }
+
+    /// Restore an exported execution of a registered workflow, without running
+    /// any of its nodes
+    ///
+    /// The execution keeps its recorded status, so pausing, resuming or
+    /// cancelling it behaves as it would have on the original.
+    pub async fn restore_execution(&self, execution: ArchivedExecution) -> Result<()> {
+        let workflow_id = execution.summary.workflow_id;
+        let definition = self.get_workflow(&workflow_id).await?;
+
+        self.refuse_engine_workflow(&workflow_id, "restore_execution")
+            .await?;
+        self.executor.restore_workflow(definition, execution).await
+    }
+
     /// Get a registered workflow
     pub async fn get_workflow(&self, workflow_id: &WorkflowId) -> Result<WorkflowDefinition> {
         let workflows = self.workflows.read().await;
@@ -319,6 +893,7 @@ impl WorkflowManager {
 
         // Remove the workflow
         workflows.remove(workflow_id);
+        self.groups.write().await.remove(workflow_id);
 
         Ok(())
     }
@@ -327,14 +902,25 @@ impl WorkflowManager {
     pub async fn shutdown(&self) -> Result<()> {
         info!("Shutting down all workflows");
 
+        if let Some(task) = self.cleanup_task.lock().await.take() {
+            task.abort();
+        }
+
         // Get all workflow IDs
         let workflow_ids: Vec<WorkflowId> = {
             let workflows = self.workflows.read().await;
             workflows.keys().cloned().collect()
         };
 
-        // Cancel each workflow
+        // Cancel each workflow still in progress
         for workflow_id in workflow_ids {
+            let in_progress = matches!(
+                self.executor.get_workflow_status(&workflow_id).await,
+                Ok(ExecutionStatus::Running | ExecutionStatus::Paused)
+            );
+            if !in_progress {
+                continue;
+            }
             if let Err(e) = self.cancel_workflow(workflow_id).await {
                 warn!("Failed to cancel workflow {:?}: {}", workflow_id, e);
                 // Continue with next workflow
@@ -347,8 +933,73 @@ impl WorkflowManager {
     }
 }
 
+/// Remove the finished executions older than `retention`, archiving each
+/// to `archive` first if given
+///
+/// An execution that fails to archive is kept, to be retried by the next
+/// cleanup.
+async fn clean_up_executions(
+    executor: &WorkflowExecutor,
+    archive: Option<&dyn StorageBackend>,
+    retention: Duration,
+) -> CleanupReport {
+    let cutoff = chrono::Duration::from_std(retention)
+        .ok()
+        .and_then(|retention| chrono::Utc::now().checked_sub_signed(retention))
+        .unwrap_or(chrono::DateTime::<chrono::Utc>::MIN_UTC);
+    let mut report = CleanupReport::default();
+
+    for execution in executor.finished_before(cutoff).await {
+        if let Some(archive) = archive {
+            if let Err(e) = archive_execution(archive, &execution).await {
+                warn!(
+                    "Keeping execution of workflow {:?}, archiving failed: {}",
+                    execution.summary.workflow_id, e
+                );
+                continue;
+            }
+            report.archived += 1;
+        }
+        if executor.remove_finished(&execution.summary).await {
+            report.removed += 1;
+        }
+    }
+
+    if report.removed > 0 {
+        info!(
+            "Cleaned up {} finished executions ({} archived)",
+            report.removed, report.archived
+        );
+    }
+    report
+}
+
+/// Storage key of an archived execution
+fn archive_key(summary: &ExecutionSummary) -> String {
+    format!(
+        "execution-{}-{}",
+        summary.workflow_id,
+        summary.started_at.timestamp_millis()
+    )
+}
+
+/// Write an execution to the archive
+async fn archive_execution(
+    archive: &dyn StorageBackend,
+    execution: &ArchivedExecution,
+) -> Result<()> {
+    let data = serde_json::to_vec(execution)?;
+    archive
+        .store(&archive_key(&execution.summary), &data)
+        .await?;
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
+    use super::*;
+    use lion_core::types::workflow::{NodeStatus, Principal};
+    use lion_workflow::model::node::{Node, NodeId};
 
     #[tokio::test]
     async fn test_workflow_manager() {
@@ -356,4 +1007,757 @@ mod tests {
         // to match the new WorkflowDefinition structure
         assert!(true);
     }
+
+    fn create_manager() -> WorkflowManager {
+        let config = RuntimeConfig::default();
+        let capability_manager = Arc::new(CapabilityManager::new().unwrap());
+        let plugin_manager =
+            Arc::new(PluginManager::new(config.clone(), capability_manager.clone()).unwrap());
+        WorkflowManager::new(config, capability_manager, plugin_manager).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_pause_and_resume_workflow() {
+        let manager = create_manager();
+
+        let mut definition = WorkflowDefinition::new(DefWorkflowId::new(), "Pausable".to_string());
+        definition
+            .add_node(Node::new(NodeId::new(), "step".to_string()))
+            .unwrap();
+        let workflow_id = manager.register_workflow(definition).await.unwrap();
+
+        // A workflow that was never started cannot be paused
+        assert!(manager.pause_workflow(workflow_id).await.is_err());
+
+        manager
+            .start_workflow(workflow_id, serde_json::json!({}))
+            .await
+            .unwrap();
+
+        // Pause a running workflow
+        let status = manager.pause_workflow(workflow_id).await.unwrap();
+        assert_eq!(status, ExecutionStatus::Paused);
+        assert_eq!(
+            manager.get_workflow_status(&workflow_id).await.unwrap(),
+            ExecutionStatus::Paused
+        );
+
+        // Pausing again fails because the workflow is no longer running
+        assert!(manager.pause_workflow(workflow_id).await.is_err());
+
+        // Resume it
+        let status = manager.resume_workflow(workflow_id).await.unwrap();
+        assert_eq!(status, ExecutionStatus::Running);
+        assert!(manager.resume_workflow(workflow_id).await.is_err());
+
+        // Unknown workflows are reported as not found
+        assert!(manager.pause_workflow(WorkflowId::new()).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_execute_workflow_requires_capability() {
+        let manager = create_manager();
+
+        let mut definition = WorkflowDefinition::new(DefWorkflowId::new(), "Guarded".to_string());
+        definition
+            .add_node(Node::new(NodeId::new(), "step".to_string()))
+            .unwrap();
+        let workflow_id = manager.register_workflow(definition).await.unwrap();
+
+        // Without a capability the execution is rejected and nothing runs
+        let err = manager
+            .execute_workflow("mallory", workflow_id, serde_json::json!({}))
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<WorkflowManagerError>(),
+            Some(WorkflowManagerError::Unauthorized(principal, id))
+                if principal == "mallory" && *id == workflow_id
+        ));
+        assert!(manager.get_workflow_status(&workflow_id).await.is_err());
+
+        // A capability for another workflow does not help
+        WorkflowExecuteCapability::for_workflow(WorkflowId::new())
+            .grant(&manager.capability_manager, "alice")
+            .await
+            .unwrap();
+        assert!(manager
+            .execute_workflow("alice", workflow_id, serde_json::json!({}))
+            .await
+            .is_err());
+
+        // A capability scoped to the workflow authorizes the execution
+        WorkflowExecuteCapability::for_workflow(workflow_id)
+            .grant(&manager.capability_manager, "alice")
+            .await
+            .unwrap();
+        manager
+            .execute_workflow("alice", workflow_id, serde_json::json!({}))
+            .await
+            .unwrap();
+        assert!(manager.get_workflow_status(&workflow_id).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_execute_workflow_with_group_capability() {
+        let manager = create_manager();
+
+        let mut definition = WorkflowDefinition::new(DefWorkflowId::new(), "Report".to_string());
+        definition
+            .add_node(Node::new(NodeId::new(), "step".to_string()))
+            .unwrap();
+        let workflow_id = manager.register_workflow(definition).await.unwrap();
+
+        WorkflowExecuteCapability::for_group("reports")
+            .grant(&manager.capability_manager, "bob")
+            .await
+            .unwrap();
+
+        // The group capability only applies once the workflow is in the group
+        assert!(manager
+            .execute_workflow("bob", workflow_id, serde_json::json!({}))
+            .await
+            .is_err());
+
+        manager
+            .set_workflow_group(workflow_id, "reports")
+            .await
+            .unwrap();
+        manager
+            .execute_workflow("bob", workflow_id, serde_json::json!({}))
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_execute_workflow_and_wait() {
+        let manager = Arc::new(create_manager());
+        let options = ExecutionOptions {
+            principal: Some(Principal::Agent("planner".to_string())),
+            ..Default::default()
+        };
+
+        let register = |name: &str| {
+            let manager = manager.clone();
+            let name = name.to_string();
+            async move {
+                let mut definition = WorkflowDefinition::new(DefWorkflowId::new(), name);
+                let node = Node::new(NodeId::new(), "step".to_string());
+                let node_id = lion_core::id::NodeId::from_uuid(node.id.uuid());
+                definition.add_node(node).unwrap();
+                let workflow_id = manager.register_workflow(definition).await.unwrap();
+                WorkflowExecuteCapability::for_workflow(workflow_id)
+                    .grant(&manager.capability_manager, "agent:planner")
+                    .await
+                    .unwrap();
+                (workflow_id, node_id)
+            }
+        };
+
+        // The results are returned once the workflow completes
+        let (workflow_id, node_id) = register("Completes").await;
+        let reporter = manager.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            reporter
+                .executor
+                .complete_node(&workflow_id, &node_id, serde_json::json!({"total": 3}))
+                .await
+                .unwrap();
+        });
+        let results = manager
+            .execute_workflow_and_wait(
+                workflow_id,
+                serde_json::json!({}),
+                &options,
+                Duration::from_secs(5),
+            )
+            .await
+            .unwrap();
+        assert_eq!(results, serde_json::json!({"total": 3}));
+
+        // A failing workflow reports its error
+        let (workflow_id, node_id) = register("Fails").await;
+        let reporter = manager.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            reporter
+                .executor
+                .fail_node(&workflow_id, &node_id, "boom")
+                .await
+                .unwrap();
+        });
+        let err = manager
+            .execute_workflow_and_wait(
+                workflow_id,
+                serde_json::json!({}),
+                &options,
+                Duration::from_secs(5),
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ExecutionError>(),
+            Some(ExecutionError::WorkflowExecutionFailed(id, message))
+                if *id == workflow_id && message.contains("boom")
+        ));
+
+        // A workflow that never finishes times out
+        let (workflow_id, _) = register("Hangs").await;
+        let err = manager
+            .execute_workflow_and_wait(
+                workflow_id,
+                serde_json::json!({}),
+                &options,
+                Duration::from_millis(50),
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ExecutionError>(),
+            Some(ExecutionError::Timeout)
+        ));
+        assert_eq!(
+            manager.get_workflow_status(&workflow_id).await.unwrap(),
+            ExecutionStatus::Running
+        );
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_completion() {
+        let manager = create_manager();
+
+        let mut definition = WorkflowDefinition::new(DefWorkflowId::new(), "Notify".to_string());
+        let node = Node::new(NodeId::new(), "step".to_string());
+        let node_id = lion_core::id::NodeId::from_uuid(node.id.uuid());
+        definition.add_node(node).unwrap();
+        let workflow_id = manager.register_workflow(definition).await.unwrap();
+
+        // Nothing to subscribe to before the workflow starts
+        assert!(manager.subscribe_completion(&workflow_id).await.is_err());
+
+        manager
+            .start_workflow(workflow_id, serde_json::json!({}))
+            .await
+            .unwrap();
+        let first = manager.subscribe_completion(&workflow_id).await.unwrap();
+        let second = manager.subscribe_completion(&workflow_id).await.unwrap();
+
+        manager
+            .executor
+            .complete_node(&workflow_id, &node_id, serde_json::json!({}))
+            .await
+            .unwrap();
+        assert_eq!(first.await.unwrap(), ExecutionStatus::Completed);
+        assert_eq!(second.await.unwrap(), ExecutionStatus::Completed);
+
+        // Subscribing after the fact resolves immediately
+        let late = manager.subscribe_completion(&workflow_id).await.unwrap();
+        assert_eq!(late.await.unwrap(), ExecutionStatus::Completed);
+
+        // Cancellation also fires the notification
+        manager
+            .start_workflow(workflow_id, serde_json::json!({}))
+            .await
+            .unwrap();
+        let cancelled = manager.subscribe_completion(&workflow_id).await.unwrap();
+        manager.cancel_workflow(workflow_id).await.unwrap();
+        assert_eq!(cancelled.await.unwrap(), ExecutionStatus::Cancelled);
+    }
+
+    #[tokio::test]
+    async fn test_find_executions_by_tags() {
+        let manager = create_manager();
+        let tags = |pairs: &[(&str, &str)]| -> HashMap<String, String> {
+            pairs
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect()
+        };
+
+        let mut started = Vec::new();
+        for (name, customer, region) in [
+            ("a", "acme", "eu"),
+            ("b", "acme", "us"),
+            ("c", "globex", "eu"),
+        ] {
+            let mut definition = WorkflowDefinition::new(DefWorkflowId::new(), name.to_string());
+            definition
+                .add_node(Node::new(NodeId::new(), "step".to_string()))
+                .unwrap();
+            let workflow_id = manager.register_workflow(definition).await.unwrap();
+            let options = ExecutionOptions {
+                tags: tags(&[("customer", customer), ("region", region)]),
+                ..Default::default()
+            };
+            manager
+                .start_workflow_with_options(workflow_id, serde_json::json!({}), &options)
+                .await
+                .unwrap();
+            started.push(workflow_id);
+        }
+
+        let mut acme = vec![started[0], started[1]];
+        acme.sort_by_key(|id| id.uuid());
+        assert_eq!(
+            manager
+                .find_executions(&tags(&[("customer", "acme")]))
+                .await,
+            acme
+        );
+        assert_eq!(
+            manager
+                .find_executions(&tags(&[("customer", "acme"), ("region", "eu")]))
+                .await,
+            vec![started[0]]
+        );
+        assert!(manager
+            .find_executions(&tags(&[("customer", "initech")]))
+            .await
+            .is_empty());
+        assert_eq!(manager.find_executions(&HashMap::new()).await.len(), 3);
+
+        // Restarting a workflow replaces the tags it is found by
+        manager
+            .start_workflow(started[0], serde_json::json!({}))
+            .await
+            .unwrap();
+        assert_eq!(
+            manager
+                .find_executions(&tags(&[("customer", "acme")]))
+                .await,
+            vec![started[1]]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_list_executions_pages_and_filters() {
+        let manager = create_manager();
+
+        let mut started = Vec::new();
+        for name in ["first", "second", "third"] {
+            let mut definition = WorkflowDefinition::new(DefWorkflowId::new(), name.to_string());
+            let node = Node::new(NodeId::new(), "step".to_string());
+            let node_id = lion_core::id::NodeId::from_uuid(node.id.uuid());
+            definition.add_node(node).unwrap();
+            let workflow_id = manager.register_workflow(definition).await.unwrap();
+            manager
+                .start_workflow(workflow_id, serde_json::json!({}))
+                .await
+                .unwrap();
+            started.push((workflow_id, node_id));
+            tokio::time::sleep(Duration::from_millis(2)).await;
+        }
+        let (failed_id, failed_node) = started[1];
+        manager
+            .executor
+            .fail_node(&failed_id, &failed_node, "boom")
+            .await
+            .unwrap();
+
+        // Pages run newest first until no cursor is returned
+        let (page, cursor) = manager
+            .list_executions(&ExecutionFilter::default(), Page::first(2))
+            .await;
+        let ids: Vec<WorkflowId> = page.iter().map(|s| s.workflow_id).collect();
+        assert_eq!(ids, vec![started[2].0, started[1].0]);
+        assert!(page[1].finished_at.is_some());
+        let (page, cursor) = manager
+            .list_executions(&ExecutionFilter::default(), Page::after(cursor.unwrap(), 2))
+            .await;
+        assert_eq!(page.len(), 1);
+        assert_eq!(page[0].workflow_id, started[0].0);
+        assert!(cursor.is_none());
+
+        // Filtering by status
+        let failed = ExecutionFilter {
+            statuses: vec![ExecutionStatus::Failed],
+            ..Default::default()
+        };
+        let (page, _) = manager.list_executions(&failed, Page::first(10)).await;
+        assert_eq!(page.len(), 1);
+        assert_eq!(page[0].workflow_id, failed_id);
+        assert_eq!(page[0].status, ExecutionStatus::Failed);
+
+        // Filtering by start time
+        let recent = ExecutionFilter {
+            started_after: Some(page[0].started_at),
+            ..Default::default()
+        };
+        let (page, _) = manager.list_executions(&recent, Page::first(10)).await;
+        assert_eq!(page.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_cleanup_removes_and_archives_finished_executions() {
+        let mut config = RuntimeConfig::default();
+        config.workflows.execution_retention = 0;
+        let capability_manager = Arc::new(CapabilityManager::new().unwrap());
+        let plugin_manager =
+            Arc::new(PluginManager::new(config.clone(), capability_manager.clone()).unwrap());
+        let archive = Arc::new(lion_workflow::state::MemoryStorage::new());
+        let manager = WorkflowManager::new(config, capability_manager, plugin_manager)
+            .unwrap()
+            .with_archive(archive.clone());
+
+        let mut started = Vec::new();
+        for name in ["done", "running"] {
+            let mut definition = WorkflowDefinition::new(DefWorkflowId::new(), name.to_string());
+            let node = Node::new(NodeId::new(), "step".to_string());
+            let node_id = lion_core::id::NodeId::from_uuid(node.id.uuid());
+            definition.add_node(node).unwrap();
+            let workflow_id = manager.register_workflow(definition).await.unwrap();
+            manager
+                .start_workflow(workflow_id, serde_json::json!({}))
+                .await
+                .unwrap();
+            started.push((workflow_id, node_id));
+        }
+        let (done_id, done_node) = started[0];
+        manager
+            .executor
+            .complete_node(&done_id, &done_node, serde_json::json!({"total": 3}))
+            .await
+            .unwrap();
+        tokio::time::sleep(Duration::from_millis(5)).await;
+
+        // Only the finished execution goes, after being archived
+        let report = manager.cleanup_executions().await;
+        assert_eq!(
+            report,
+            CleanupReport {
+                removed: 1,
+                archived: 1
+            }
+        );
+        assert_eq!(manager.last_cleanup().await, Some(report));
+        assert!(manager.get_workflow_status(&done_id).await.is_err());
+        assert_eq!(
+            manager.get_workflow_status(&started[1].0).await.unwrap(),
+            ExecutionStatus::Running
+        );
+
+        let keys = archive.list().await.unwrap();
+        assert_eq!(keys.len(), 1);
+        let archived: ArchivedExecution =
+            serde_json::from_slice(&archive.load(&keys[0]).await.unwrap()).unwrap();
+        assert_eq!(archived.summary.workflow_id, done_id);
+        assert_eq!(archived.summary.status, ExecutionStatus::Completed);
+        assert_eq!(
+            archived.node_outputs[&done_node.to_string()],
+            serde_json::json!({"total": 3})
+        );
+
+        // Nothing is left to clean
+        assert_eq!(manager.cleanup_executions().await, CleanupReport::default());
+    }
+
+    #[tokio::test]
+    async fn test_node_events() {
+        let manager = create_manager();
+
+        let mut definition = WorkflowDefinition::new(DefWorkflowId::new(), "Events".to_string());
+        let node = Node::new(NodeId::new(), "step".to_string());
+        let node_id = lion_core::id::NodeId::from_uuid(node.id.uuid());
+        definition.add_node(node).unwrap();
+        let workflow_id = manager.register_workflow(definition).await.unwrap();
+
+        let mut events = manager.subscribe_node_events();
+        manager
+            .start_workflow(workflow_id, serde_json::json!({}))
+            .await
+            .unwrap();
+        manager
+            .complete_node(&workflow_id, &node_id, serde_json::json!({}))
+            .await
+            .unwrap();
+
+        for status in [NodeStatus::Pending, NodeStatus::Completed] {
+            let event = events.recv().await.unwrap();
+            assert_eq!(event.workflow_id, workflow_id);
+            assert_eq!(event.node_id, node_id);
+            assert_eq!(event.status, status);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_restore_exported_execution() {
+        let manager = create_manager();
+
+        let mut definition = WorkflowDefinition::new(DefWorkflowId::new(), "Restore".to_string());
+        let node = Node::new(NodeId::new(), "step".to_string());
+        let node_id = lion_core::id::NodeId::from_uuid(node.id.uuid());
+        definition.add_node(node).unwrap();
+        let workflow_id = manager.register_workflow(definition.clone()).await.unwrap();
+
+        // Nothing to export before the workflow starts
+        assert!(manager.export_execution(&workflow_id).await.is_err());
+
+        manager
+            .start_workflow(workflow_id, serde_json::json!({}))
+            .await
+            .unwrap();
+        manager
+            .executor
+            .complete_node(&workflow_id, &node_id, serde_json::json!({"done": true}))
+            .await
+            .unwrap();
+        let exported = manager.export_execution(&workflow_id).await.unwrap();
+        assert_eq!(exported.summary.status, ExecutionStatus::Completed);
+
+        // A fresh manager picks the execution up as it was
+        let restored = create_manager();
+        restored.register_workflow(definition).await.unwrap();
+        restored.restore_execution(exported.clone()).await.unwrap();
+        assert_eq!(
+            restored.get_workflow_status(&workflow_id).await.unwrap(),
+            ExecutionStatus::Completed
+        );
+        assert_eq!(
+            restored.get_workflow_results(&workflow_id).await.unwrap(),
+            serde_json::json!({"done": true})
+        );
+        assert_eq!(
+            restored.export_execution(&workflow_id).await.unwrap(),
+            exported
+        );
+
+        // A finished execution stays finished
+        assert!(restored.pause_workflow(workflow_id).await.is_err());
+        assert!(restored.cancel_workflow(workflow_id).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_cancel_refuses_finished_workflow() {
+        let manager = create_manager();
+
+        let mut definition = WorkflowDefinition::new(DefWorkflowId::new(), "Cancel".to_string());
+        let node = Node::new(NodeId::new(), "step".to_string());
+        let node_id = lion_core::id::NodeId::from_uuid(node.id.uuid());
+        definition.add_node(node).unwrap();
+        let workflow_id = manager.register_workflow(definition).await.unwrap();
+
+        manager
+            .start_workflow(workflow_id, serde_json::json!({}))
+            .await
+            .unwrap();
+        manager
+            .executor
+            .complete_node(&workflow_id, &node_id, serde_json::json!({}))
+            .await
+            .unwrap();
+
+        // A finished workflow keeps its status
+        let err = manager.cancel_workflow(workflow_id).await.unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ExecutionError>(),
+            Some(ExecutionError::WorkflowAlreadyFinished(id)) if *id == workflow_id
+        ));
+        assert_eq!(
+            manager.get_workflow_status(&workflow_id).await.unwrap(),
+            ExecutionStatus::Completed
+        );
+    }
+
+    /// Engine recording the workflows it is given
+    #[derive(Default)]
+    struct RecordingEngine {
+        workflows: std::sync::Mutex<HashMap<WorkflowId, Workflow>>,
+        executions: std::sync::Mutex<HashMap<ExecutionId, ExecutionStatus>>,
+        options: std::sync::Mutex<Vec<ExecutionOptions>>,
+    }
+
+    impl WorkflowEngine for RecordingEngine {
+        fn create_workflow(&self, workflow: Workflow) -> lion_core::Result<WorkflowId> {
+            let workflow_id = workflow.id;
+            self.workflows.lock().unwrap().insert(workflow_id, workflow);
+            Ok(workflow_id)
+        }
+
+        fn execute_workflow(
+            &self,
+            _workflow_id: &WorkflowId,
+            options: ExecutionOptions,
+        ) -> lion_core::Result<ExecutionId> {
+            self.options.lock().unwrap().push(options);
+            let execution_id = ExecutionId::new();
+            self.executions
+                .lock()
+                .unwrap()
+                .insert(execution_id, ExecutionStatus::Running);
+            Ok(execution_id)
+        }
+
+        fn get_execution_status(
+            &self,
+            execution_id: &ExecutionId,
+        ) -> lion_core::Result<ExecutionStatus> {
+            Ok(self.executions.lock().unwrap()[execution_id])
+        }
+
+        fn get_node_status(
+            &self,
+            _execution_id: &ExecutionId,
+            _node_id: &CoreNodeId,
+        ) -> lion_core::Result<lion_core::types::workflow::NodeStatus> {
+            Ok(lion_core::types::workflow::NodeStatus::Pending)
+        }
+
+        fn pause_execution(&self, execution_id: &ExecutionId) -> lion_core::Result<()> {
+            self.executions
+                .lock()
+                .unwrap()
+                .insert(*execution_id, ExecutionStatus::Paused);
+            Ok(())
+        }
+
+        fn resume_execution(&self, execution_id: &ExecutionId) -> lion_core::Result<()> {
+            self.executions
+                .lock()
+                .unwrap()
+                .insert(*execution_id, ExecutionStatus::Completed);
+            Ok(())
+        }
+
+        fn get_execution_results(
+            &self,
+            _execution_id: &ExecutionId,
+        ) -> lion_core::Result<HashMap<CoreNodeId, Vec<u8>>> {
+            Ok(HashMap::from([(
+                CoreNodeId::new(),
+                serde_json::to_vec(&serde_json::json!({"stored": true})).unwrap(),
+            )]))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_definitions_choose_registered_engine() {
+        use lion_workflow::model::{Edge, EdgeId};
+
+        let manager = create_manager();
+        let mut definition = WorkflowDefinition::new(DefWorkflowId::new(), "Migrated".to_string())
+            .with_engine("legacy");
+        let first = Node::new(NodeId::new(), "fetch".to_string());
+        let second = Node::new(NodeId::new(), "store".to_string());
+        let (first_id, second_id) = (first.id.clone(), second.id.clone());
+        definition.add_node(first).unwrap();
+        definition.add_node(second).unwrap();
+        definition
+            .add_edge(Edge::new(
+                EdgeId::new(),
+                first_id.clone(),
+                second_id.clone(),
+            ))
+            .unwrap();
+
+        // The engine must exist when the workflow is registered
+        let err = manager
+            .register_workflow(definition.clone())
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<WorkflowManagerError>(),
+            Some(WorkflowManagerError::UnknownEngine(name)) if name == "legacy"
+        ));
+
+        let engine = Arc::new(RecordingEngine::default());
+        manager
+            .register_workflow_engine("legacy", engine.clone())
+            .await
+            .unwrap();
+        assert!(manager
+            .register_workflow_engine("legacy", Arc::new(RecordingEngine::default()))
+            .await
+            .is_err());
+        assert_eq!(manager.workflow_engines().await, vec!["legacy".to_string()]);
+
+        // The engine gets the workflow and runs it; the executor does not
+        let workflow_id = manager.register_workflow(definition).await.unwrap();
+        let workflow = engine.workflows.lock().unwrap()[&workflow_id].clone();
+        assert_eq!(workflow.nodes.len(), 2);
+        let store = workflow
+            .get_node(&CoreNodeId::from_uuid(second_id.uuid()))
+            .unwrap();
+        assert_eq!(
+            store.dependencies,
+            vec![CoreNodeId::from_uuid(first_id.uuid())]
+        );
+
+        let options = ExecutionOptions {
+            tags: HashMap::from([("team".to_string(), "ops".to_string())]),
+            ..Default::default()
+        };
+        manager
+            .start_workflow_with_options(workflow_id, serde_json::json!({"n": 1}), &options)
+            .await
+            .unwrap();
+        assert_eq!(
+            engine.options.lock().unwrap()[0].input,
+            Some(serde_json::json!({"n": 1}))
+        );
+        assert_eq!(
+            manager.get_workflow_status(&workflow_id).await.unwrap(),
+            ExecutionStatus::Running
+        );
+        assert!(manager
+            .executor
+            .get_workflow_status(&workflow_id)
+            .await
+            .is_err());
+
+        // Engine executions are found and listed with the executor's
+        assert_eq!(
+            manager.find_executions(&options.tags).await,
+            vec![workflow_id]
+        );
+        let (summaries, next) = manager
+            .list_executions(&ExecutionFilter::default(), Page::first(10))
+            .await;
+        assert!(next.is_none());
+        assert_eq!(summaries.len(), 1);
+        assert_eq!(summaries[0].workflow_id, workflow_id);
+        assert_eq!(summaries[0].status, ExecutionStatus::Running);
+
+        // Pause, resume and results go to the engine
+        assert_eq!(
+            manager.pause_workflow(workflow_id).await.unwrap(),
+            ExecutionStatus::Paused
+        );
+        assert_eq!(
+            manager.resume_workflow(workflow_id).await.unwrap(),
+            ExecutionStatus::Completed
+        );
+        assert_eq!(
+            manager.get_workflow_results(&workflow_id).await.unwrap(),
+            serde_json::json!({"stored": true})
+        );
+
+        // Engines do not report completion
+        WorkflowExecuteCapability::for_workflow(workflow_id)
+            .grant(&manager.capability_manager, "anonymous")
+            .await
+            .unwrap();
+        let err = manager
+            .subscribe_completion(&workflow_id)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<WorkflowManagerError>(),
+            Some(WorkflowManagerError::EngineUnsupported(id, _)) if *id == workflow_id
+        ));
+        let err = manager
+            .execute_workflow_and_wait(
+                workflow_id,
+                serde_json::json!({}),
+                &ExecutionOptions::default(),
+                Duration::from_secs(1),
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<WorkflowManagerError>(),
+            Some(WorkflowManagerError::EngineUnsupported(..))
+        ));
+        assert_eq!(engine.options.lock().unwrap().len(), 1);
+    }
 }
@@ -0,0 +1,127 @@
+use crate::model::{NodeId, WorkflowId};
+use lion_core::types::workflow::Principal;
+use lion_core::CapabilityId;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+/// Default number of entries kept by an execution audit log
+pub const DEFAULT_AUDIT_CAPACITY: usize = 10_000;
+
+/// Action recorded in the execution audit log
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AuditAction {
+    /// A workflow execution was submitted
+    ExecutionSubmitted,
+
+    /// The capability required by a node was checked before running it
+    CapabilityCheck {
+        /// Capability required by the node
+        capability_id: CapabilityId,
+
+        /// Whether the principal was allowed to use it
+        allowed: bool,
+    },
+}
+
+/// A single entry of the execution audit log
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExecutionAuditEntry {
+    /// When the action happened
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+
+    /// Workflow being executed
+    pub workflow_id: WorkflowId,
+
+    /// Instance being executed
+    pub instance_id: String,
+
+    /// Node the action applies to, if any
+    pub node_id: Option<NodeId>,
+
+    /// Identity on whose behalf the workflow runs
+    pub principal: Option<Principal>,
+
+    /// What happened
+    pub action: AuditAction,
+}
+
+/// Bounded in-memory log of security-relevant execution events
+///
+/// When full, the oldest entries are dropped.
+#[derive(Debug)]
+pub struct ExecutionAuditLog {
+    /// Entries, oldest first
+    entries: Mutex<VecDeque<ExecutionAuditEntry>>,
+
+    /// Maximum number of entries kept
+    capacity: usize,
+}
+
+impl ExecutionAuditLog {
+    /// Create an audit log keeping at most `capacity` entries
+    pub fn new(capacity: usize) -> Self {
+        ExecutionAuditLog {
+            entries: Mutex::new(VecDeque::new()),
+            capacity,
+        }
+    }
+
+    /// Append an entry
+    pub fn record(&self, entry: ExecutionAuditEntry) {
+        let mut entries = self.entries.lock().unwrap();
+        if entries.len() >= self.capacity {
+            entries.pop_front();
+        }
+        entries.push_back(entry);
+    }
+
+    /// All entries, oldest first
+    pub fn entries(&self) -> Vec<ExecutionAuditEntry> {
+        self.entries.lock().unwrap().iter().cloned().collect()
+    }
+
+    /// Entries of one instance, oldest first
+    pub fn entries_for_instance(&self, instance_id: &str) -> Vec<ExecutionAuditEntry> {
+        self.entries
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|entry| entry.instance_id == instance_id)
+            .cloned()
+            .collect()
+    }
+}
+
+impl Default for ExecutionAuditLog {
+    fn default() -> Self {
+        Self::new(DEFAULT_AUDIT_CAPACITY)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_audit_log_is_bounded() {
+        let log = ExecutionAuditLog::new(2);
+        let workflow_id = WorkflowId::new();
+
+        for instance in ["a", "b", "c"] {
+            log.record(ExecutionAuditEntry {
+                timestamp: chrono::Utc::now(),
+                workflow_id: workflow_id.clone(),
+                instance_id: instance.to_string(),
+                node_id: None,
+                principal: Some(Principal::Agent("planner".to_string())),
+                action: AuditAction::ExecutionSubmitted,
+            });
+        }
+
+        let instances: Vec<String> = log.entries().into_iter().map(|e| e.instance_id).collect();
+        assert_eq!(instances, vec!["b", "c"]);
+        assert!(log.entries_for_instance("a").is_empty());
+        assert_eq!(log.entries_for_instance("c").len(), 1);
+    }
+}
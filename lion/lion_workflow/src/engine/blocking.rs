@@ -0,0 +1,236 @@
+use crate::engine::executor::{ExecutorConfig, ExecutorError, NodeHandler, WorkflowExecutor};
+use crate::engine::scheduler::{SchedulerConfig, WorkflowScheduler};
+use crate::model::{NodeId, WorkflowDefinition};
+use crate::state::{FailureReason, InstanceStatus, MemoryStorage, StateMachineManager};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::runtime::Runtime;
+
+/// Default time a blocking run may take before it is abandoned
+pub const DEFAULT_RUN_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Interval at which a blocking run polls for completion
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Outcome of a workflow run to completion
+#[derive(Debug, Clone)]
+pub struct ExecutionResult {
+    /// Instance that was run
+    pub instance_id: String,
+
+    /// Final status, `Completed` or `Failed`
+    pub status: InstanceStatus,
+
+    /// Output of each finished node; failed nodes hold `{"error": ...}`
+    pub node_results: HashMap<NodeId, serde_json::Value>,
+
+    /// Why the engine failed the instance, if it did
+    pub failure_reason: Option<FailureReason>,
+}
+
+impl ExecutionResult {
+    /// Whether every node completed successfully
+    pub fn is_success(&self) -> bool {
+        self.status == InstanceStatus::Completed
+    }
+}
+
+/// Synchronous workflow engine for scripts and tests
+///
+/// Owns a tokio runtime and runs each workflow on a fresh in-memory
+/// executor, so callers need no async setup. Must not be used from within
+/// an async context.
+pub struct BlockingWorkflowEngine {
+    /// Runtime the executors run on
+    runtime: Runtime,
+
+    /// Configuration of each executor
+    config: ExecutorConfig,
+
+    /// Time a run may take before it is abandoned
+    run_timeout: Duration,
+}
+
+impl BlockingWorkflowEngine {
+    /// Create an engine with the default executor configuration
+    pub fn new() -> Result<Self, ExecutorError> {
+        Self::with_config(ExecutorConfig::default())
+    }
+
+    /// Create an engine with the given executor configuration
+    pub fn with_config(config: ExecutorConfig) -> Result<Self, ExecutorError> {
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(config.worker_threads.max(1))
+            .enable_all()
+            .build()
+            .map_err(|e| ExecutorError::Other(format!("Failed to start runtime: {}", e)))?;
+
+        Ok(BlockingWorkflowEngine {
+            runtime,
+            config,
+            run_timeout: DEFAULT_RUN_TIMEOUT,
+        })
+    }
+
+    /// Set the time a run may take before it is abandoned
+    pub fn with_run_timeout(mut self, run_timeout: Duration) -> Self {
+        self.run_timeout = run_timeout;
+        self
+    }
+
+    /// Run a workflow until it completes or fails
+    ///
+    /// `handlers` maps node types to their handlers. Fails if the run does
+    /// not finish within the run timeout.
+    pub fn run_to_completion(
+        &self,
+        definition: WorkflowDefinition,
+        handlers: HashMap<String, NodeHandler>,
+    ) -> Result<ExecutionResult, ExecutorError> {
+        self.runtime.block_on(async {
+            let state_manager = Arc::new(StateMachineManager::<MemoryStorage>::new());
+            let executor = WorkflowExecutor::new(
+                Arc::new(WorkflowScheduler::new(SchedulerConfig::default())),
+                state_manager.clone(),
+                self.config.clone(),
+            );
+            for (node_type, handler) in handlers {
+                executor.register_node_handler(&node_type, handler).await;
+            }
+            executor.start().await?;
+
+            let result = Self::await_completion(
+                &executor,
+                &state_manager,
+                Arc::new(definition),
+                self.run_timeout,
+            )
+            .await;
+            executor.shutdown(Duration::from_secs(1)).await?;
+            result
+        })
+    }
+
+    /// Submit a workflow and wait for it to finish
+    async fn await_completion(
+        executor: &WorkflowExecutor<MemoryStorage>,
+        state_manager: &StateMachineManager<MemoryStorage>,
+        definition: Arc<WorkflowDefinition>,
+        run_timeout: Duration,
+    ) -> Result<ExecutionResult, ExecutorError> {
+        let instance_id = executor.execute_workflow(definition).await?;
+
+        let finished = tokio::time::timeout(run_timeout, async {
+            loop {
+                match executor.get_instance_status(&instance_id).await {
+                    Some(status @ (InstanceStatus::Completed | InstanceStatus::Failed)) => {
+                        return status
+                    }
+                    _ => tokio::time::sleep(POLL_INTERVAL).await,
+                }
+            }
+        })
+        .await;
+        let status = finished.map_err(|_| {
+            ExecutorError::Other(format!(
+                "Workflow instance {} did not finish within {:?}",
+                instance_id, run_timeout
+            ))
+        })?;
+
+        let state = state_manager
+            .get_instance(&instance_id)
+            .await
+            .ok_or_else(|| ExecutorError::Other(format!("Instance not found: {}", instance_id)))?;
+        let state = state.read().await;
+
+        Ok(ExecutionResult {
+            instance_id: instance_id.clone(),
+            status,
+            node_results: state.node_results.clone(),
+            failure_reason: state.failure_reason.clone(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::context::NodeResult;
+    use crate::model::{Edge, EdgeId, Node};
+
+    fn echo_handler(value: serde_json::Value) -> NodeHandler {
+        Arc::new(move |ctx| {
+            let value = value.clone();
+            Box::pin(async move {
+                let node_id = ctx.current_node_id.clone().unwrap();
+                Ok(NodeResult::success(node_id, value))
+            })
+        })
+    }
+
+    #[test]
+    fn test_run_to_completion_without_caller_runtime() {
+        let mut workflow = WorkflowDefinition::new(crate::model::WorkflowId::new(), "Sync".into());
+        let fetch = Node::new(NodeId::new(), "fetch".to_string());
+        let fetch_id = fetch.id.clone();
+        let report = Node::new(NodeId::new(), "report".to_string());
+        let report_id = report.id.clone();
+        workflow.add_node(fetch).unwrap();
+        workflow.add_node(report).unwrap();
+        workflow
+            .add_edge(Edge::new(
+                EdgeId::new(),
+                fetch_id.clone(),
+                report_id.clone(),
+            ))
+            .unwrap();
+
+        let mut handlers = HashMap::new();
+        handlers.insert(
+            "fetch".to_string(),
+            echo_handler(serde_json::json!({"rows": 3})),
+        );
+        handlers.insert(
+            "report".to_string(),
+            echo_handler(serde_json::json!("done")),
+        );
+
+        let engine = BlockingWorkflowEngine::new().unwrap();
+        let result = engine.run_to_completion(workflow, handlers).unwrap();
+
+        assert!(result.is_success());
+        assert_eq!(
+            result.node_results.get(&fetch_id),
+            Some(&serde_json::json!({"rows": 3}))
+        );
+        assert_eq!(
+            result.node_results.get(&report_id),
+            Some(&serde_json::json!("done"))
+        );
+    }
+
+    #[test]
+    fn test_run_to_completion_reports_failure() {
+        let mut workflow = WorkflowDefinition::new(crate::model::WorkflowId::new(), "Sync".into());
+        let explode = Node::new(NodeId::new(), "explode".to_string());
+        let explode_id = explode.id.clone();
+        workflow.add_node(explode).unwrap();
+
+        let mut handlers: HashMap<String, NodeHandler> = HashMap::new();
+        handlers.insert(
+            "explode".to_string(),
+            Arc::new(|_ctx| Box::pin(async { Err(ExecutorError::NodeError("boom".to_string())) })),
+        );
+
+        let engine = BlockingWorkflowEngine::new().unwrap();
+        let result = engine.run_to_completion(workflow, handlers).unwrap();
+        assert_eq!(result.status, InstanceStatus::Failed);
+        assert!(!result.is_success());
+        assert_eq!(
+            result.node_results.get(&explode_id),
+            Some(&serde_json::json!({"error": "boom"}))
+        );
+    }
+}
@@ -0,0 +1,207 @@
+use crate::model::{Node, NodeId, WorkflowId};
+use crate::state::{StorageBackend, StorageError};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+
+/// Prefix of the storage keys holding cached node results
+const KEY_PREFIX: &str = "node-cache";
+
+/// A cached node output and when it was stored
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CacheEntry {
+    /// Output of the node
+    output: serde_json::Value,
+
+    /// When the output was stored
+    stored_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Cache of the outputs of cacheable nodes, keyed by the hash of their input
+///
+/// Entries are kept in a storage backend, so a persistent backend keeps them
+/// across restarts. An entry belongs to a node of a workflow definition and
+/// to the node's content; re-running the definition reuses the outputs of
+/// unchanged nodes whose input is unchanged.
+pub struct NodeResultCache {
+    /// Backend holding the entries
+    storage: Arc<dyn StorageBackend>,
+}
+
+impl NodeResultCache {
+    /// Create a cache keeping its entries in a storage backend
+    pub fn new(storage: Arc<dyn StorageBackend>) -> Self {
+        NodeResultCache { storage }
+    }
+
+    /// Get the output stored for a node and input, unless older than the
+    /// TTL of the node's cache policy
+    pub async fn get(
+        &self,
+        workflow_id: &WorkflowId,
+        node: &Node,
+        input: &serde_json::Value,
+    ) -> Result<Option<serde_json::Value>, StorageError> {
+        let key = entry_key(workflow_id, node, input)?;
+        let data = match self.storage.load(&key).await {
+            Ok(data) => data,
+            Err(StorageError::NotFound(_)) => return Ok(None),
+            Err(e) => return Err(e),
+        };
+        let entry: CacheEntry = self.storage.format().deserialize(&data)?;
+
+        let policy = node.cache.unwrap_or_default();
+        let expired = policy.ttl_ms.is_some_and(|ttl_ms| {
+            let age = chrono::Utc::now() - entry.stored_at;
+            age.num_milliseconds() >= ttl_ms as i64
+        });
+        if expired {
+            self.storage.delete(&key).await?;
+            return Ok(None);
+        }
+        Ok(Some(entry.output))
+    }
+
+    /// Store the output a node produced for an input
+    pub async fn put(
+        &self,
+        workflow_id: &WorkflowId,
+        node: &Node,
+        input: &serde_json::Value,
+        output: &serde_json::Value,
+    ) -> Result<(), StorageError> {
+        let key = entry_key(workflow_id, node, input)?;
+        let entry = CacheEntry {
+            output: output.clone(),
+            stored_at: chrono::Utc::now(),
+        };
+        let data = self.storage.format().serialize(&entry)?;
+        self.storage.store(&key, &data).await
+    }
+
+    /// Drop every output cached for a node, returning how many were dropped
+    pub async fn invalidate_node(
+        &self,
+        workflow_id: &WorkflowId,
+        node_id: &NodeId,
+    ) -> Result<usize, StorageError> {
+        self.delete_matching(&format!("{}-{}-{}-", KEY_PREFIX, workflow_id, node_id))
+            .await
+    }
+
+    /// Drop every cached output, returning how many were dropped
+    pub async fn clear(&self) -> Result<usize, StorageError> {
+        self.delete_matching(&format!("{}-", KEY_PREFIX)).await
+    }
+
+    /// Delete the entries whose key starts with a prefix
+    async fn delete_matching(&self, prefix: &str) -> Result<usize, StorageError> {
+        let mut deleted = 0;
+        for key in self.storage.list().await? {
+            if key.starts_with(prefix) {
+                self.storage.delete(&key).await?;
+                deleted += 1;
+            }
+        }
+        Ok(deleted)
+    }
+}
+
+/// Storage key of the entry for a node and input
+///
+/// The key carries the fingerprint of the node without its cache policy, so
+/// a node re-registered under the same ID with a different config or type
+/// misses its old entries, while a new TTL applies to them. The input is
+/// hashed in its serialized form, whose object keys are sorted.
+fn entry_key(
+    workflow_id: &WorkflowId,
+    node: &Node,
+    input: &serde_json::Value,
+) -> Result<String, StorageError> {
+    let bytes =
+        serde_json::to_vec(input).map_err(|e| StorageError::SerializationError(e.to_string()))?;
+    let fingerprint = Node {
+        cache: None,
+        ..node.clone()
+    }
+    .fingerprint();
+    let hash: String = Sha256::digest(&bytes)
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect();
+    Ok(format!(
+        "{}-{}-{}-{}-{}",
+        KEY_PREFIX, workflow_id, node.id, fingerprint, hash
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::CachePolicy;
+    use crate::state::MemoryStorage;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_cache_ttl_and_invalidation() {
+        let cache = NodeResultCache::new(Arc::new(MemoryStorage::new()));
+        let workflow_id = WorkflowId::new();
+        let node = Node::new(NodeId::new(), "fetch".to_string());
+        let other = Node::new(NodeId::new(), "parse".to_string());
+        let input = serde_json::json!({ "b": 2, "a": 1 });
+
+        cache
+            .put(&workflow_id, &node, &input, &serde_json::json!("out"))
+            .await
+            .unwrap();
+        cache
+            .put(&workflow_id, &other, &input, &serde_json::json!("other"))
+            .await
+            .unwrap();
+        let reordered = serde_json::json!({ "a": 1, "b": 2 });
+        assert_eq!(
+            cache.get(&workflow_id, &node, &reordered).await.unwrap(),
+            Some(serde_json::json!("out"))
+        );
+        let changed = serde_json::json!({ "a": 1, "b": 3 });
+        assert_eq!(
+            cache.get(&workflow_id, &node, &changed).await.unwrap(),
+            None
+        );
+
+        // The same node with a different config does not see the entry
+        let reconfigured = node
+            .clone()
+            .with_config(serde_json::json!({ "retries": 3 }));
+        assert_eq!(
+            cache
+                .get(&workflow_id, &reconfigured, &input)
+                .await
+                .unwrap(),
+            None
+        );
+
+        // Entries older than the TTL are dropped
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        let mut short = node.clone();
+        short.cache = Some(CachePolicy::with_ttl(Duration::from_millis(10)));
+        assert_eq!(cache.get(&workflow_id, &short, &input).await.unwrap(), None);
+        assert_eq!(cache.get(&workflow_id, &node, &input).await.unwrap(), None);
+
+        // Busting one node leaves the others
+        cache
+            .put(&workflow_id, &node, &input, &serde_json::json!("out"))
+            .await
+            .unwrap();
+        assert_eq!(
+            cache.invalidate_node(&workflow_id, &node.id).await.unwrap(),
+            1
+        );
+        assert!(cache
+            .get(&workflow_id, &other, &input)
+            .await
+            .unwrap()
+            .is_some());
+        assert_eq!(cache.clear().await.unwrap(), 1);
+    }
+}
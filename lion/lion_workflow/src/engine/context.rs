@@ -1,11 +1,14 @@
+use crate::engine::shared::{SharedContext, SharedContextStore};
 use crate::model::{EdgeId, NodeId, NodeStatus, WorkflowDefinition};
 use crate::state::{ConditionResult, WorkflowState};
+use lion_core::types::workflow::Principal;
 use lion_core::CapabilityId;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fmt;
 use std::sync::Arc;
 use thiserror::Error;
+use tokio::sync::watch;
 
 /// Trait for capability checking
 pub trait CapabilityChecker: Send + Sync {
@@ -73,6 +76,11 @@ pub struct NodeResult {
 
     /// Execution timestamp
     pub timestamp: chrono::DateTime<chrono::Utc>,
+
+    /// Resources consumed by the execution, as reported by the isolation
+    /// backend
+    #[serde(default)]
+    pub resource_usage: Option<lion_core::types::ResourceUsage>,
 }
 
 impl NodeResult {
@@ -85,6 +93,7 @@ impl NodeResult {
             error: None,
             metadata: serde_json::Value::Null,
             timestamp: chrono::Utc::now(),
+            resource_usage: None,
         }
     }
 
@@ -97,6 +106,7 @@ impl NodeResult {
             error: Some(error),
             metadata: serde_json::Value::Null,
             timestamp: chrono::Utc::now(),
+            resource_usage: None,
         }
     }
 
@@ -106,6 +116,12 @@ impl NodeResult {
         self
     }
 
+    /// Attach the resources consumed by the execution
+    pub fn with_resource_usage(mut self, usage: lion_core::types::ResourceUsage) -> Self {
+        self.resource_usage = Some(usage);
+        self
+    }
+
     /// Check if this result represents a successful execution
     pub fn is_success(&self) -> bool {
         self.status == NodeStatus::Completed
@@ -132,6 +148,15 @@ pub struct ExecutionContext {
     /// Capability checker for capability-based security
     pub capability_checker: Option<Arc<dyn CapabilityChecker + 'static>>,
 
+    /// Identity on whose behalf the workflow runs
+    pub principal: Option<Principal>,
+
+    /// Checker of the principal's plugin call capabilities
+    pub plugin_capabilities: Option<Arc<lion_capability::CapabilityChecker>>,
+
+    /// Store of values shared with other parts of the execution (e.g. sagas)
+    pub shared_store: Option<Arc<SharedContextStore>>,
+
     /// Currently executing node ID
     pub current_node_id: Option<NodeId>,
 
@@ -146,20 +171,32 @@ pub struct ExecutionContext {
 
     /// Execution deadline (if any)
     pub deadline: Option<chrono::DateTime<chrono::Utc>>,
+
+    /// Channel the handler's heartbeats go to, when the executor watches them
+    heartbeat: Option<Arc<watch::Sender<()>>>,
+
+    /// Set once the executor asks the handler to stop
+    stop_signal: Option<watch::Receiver<bool>>,
 }
 
 impl ExecutionContext {
     /// Create a new execution context
     pub fn new(definition: Arc<WorkflowDefinition>, state: Arc<WorkflowState>) -> Self {
+        let principal = state.principal.clone();
         ExecutionContext {
             definition,
             state,
             capability_checker: None,
+            principal,
+            plugin_capabilities: None,
+            shared_store: None,
             current_node_id: None,
             variables: HashMap::new(),
             priority: 1,
             attempt: 1,
             deadline: None,
+            heartbeat: None,
+            stop_signal: None,
         }
     }
 
@@ -172,6 +209,68 @@ impl ExecutionContext {
         self
     }
 
+    /// Set the principal on whose behalf the workflow runs
+    pub fn with_principal(mut self, principal: Principal) -> Self {
+        self.principal = Some(principal);
+        self
+    }
+
+    /// Set the checker of plugin call capabilities
+    pub fn with_plugin_capabilities(
+        mut self,
+        checker: Arc<lion_capability::CapabilityChecker>,
+    ) -> Self {
+        self.plugin_capabilities = Some(checker);
+        self
+    }
+
+    /// Check the principal may call a plugin function
+    ///
+    /// Only plugin principals hold capabilities, and a call is denied unless
+    /// one of them permits it; without a checker every call is denied. The
+    /// checker records each decision in its audit log.
+    pub fn check_plugin_call(&self, plugin_id: &str, function: &str) -> Result<(), ContextError> {
+        let checker = self.plugin_capabilities.as_ref().ok_or_else(|| {
+            ContextError::CapabilityError("No checker for plugin call capabilities".to_string())
+        })?;
+
+        let caller = match &self.principal {
+            Some(Principal::Plugin(caller)) => caller,
+            other => {
+                return Err(ContextError::CapabilityError(format!(
+                    "{} may not call plugins",
+                    other
+                        .as_ref()
+                        .map_or("workflow_executor".to_string(), |p| p.to_string())
+                )))
+            }
+        };
+
+        let request = lion_capability::AccessRequest::PluginCall {
+            plugin_id: plugin_id.to_string(),
+            function: function.to_string(),
+        };
+        checker.check(caller, &request).map_err(|e| {
+            ContextError::CapabilityError(format!(
+                "plugin:{} may not call {}::{}: {}",
+                caller, plugin_id, function, e
+            ))
+        })
+    }
+
+    /// Set the store of values shared across the execution
+    pub fn with_shared_store(mut self, store: Arc<SharedContextStore>) -> Self {
+        self.shared_store = Some(store);
+        self
+    }
+
+    /// Get the values shared across this execution, keyed by instance id
+    pub fn shared_context(&self) -> Option<SharedContext> {
+        self.shared_store
+            .as_ref()
+            .map(|store| store.scope(&self.state.instance_id))
+    }
+
     /// Set the current node ID
     pub fn with_node(mut self, node_id: &NodeId) -> Self {
         self.current_node_id = Some(node_id.clone());
@@ -196,6 +295,53 @@ impl ExecutionContext {
         self
     }
 
+    /// Connect the context to the executor watching the node's liveness
+    pub(crate) fn with_liveness(
+        mut self,
+        heartbeat: watch::Sender<()>,
+        stop_signal: watch::Receiver<bool>,
+    ) -> Self {
+        self.heartbeat = Some(Arc::new(heartbeat));
+        self.stop_signal = Some(stop_signal);
+        self
+    }
+
+    /// Report that the node is still making progress
+    ///
+    /// Once a node has sent a heartbeat, the executor fails it if the next
+    /// one does not come within `ExecutorConfig::heartbeat_timeout`. Nodes
+    /// that never send one are only bound by the execution timeout.
+    pub fn heartbeat(&self) {
+        if let Some(heartbeat) = &self.heartbeat {
+            heartbeat.send_replace(());
+        }
+    }
+
+    /// Whether the executor has asked the node to stop
+    ///
+    /// Set when the node misses its heartbeat or its execution is cancelled.
+    /// The handler's future is dropped at that point; work it handed off,
+    /// such as to a blocking thread, should check this and wind down.
+    pub fn is_cancelled(&self) -> bool {
+        self.stop_signal
+            .as_ref()
+            .is_some_and(|stop_signal| *stop_signal.borrow())
+    }
+
+    /// Wait until the executor asks the node to stop
+    ///
+    /// Never returns if the context is not connected to an executor.
+    pub async fn cancelled(&self) {
+        match self.stop_signal.clone() {
+            Some(mut stop_signal) => {
+                if stop_signal.wait_for(|stop| *stop).await.is_err() {
+                    std::future::pending::<()>().await;
+                }
+            }
+            None => std::future::pending::<()>().await,
+        }
+    }
+
     /// Get the current node
     pub fn get_current_node(&self) -> Result<&crate::model::Node, ContextError> {
         let node_id = self
@@ -208,30 +354,92 @@ impl ExecutionContext {
             .ok_or_else(|| ContextError::NodeNotFound(node_id.clone()))
     }
 
-    /// Get input data for the current node
+    /// Get input data for the current node, as `all_inputs` does
     pub fn get_inputs(&self) -> Result<HashMap<NodeId, serde_json::Value>, ContextError> {
-        let node_id = self
-            .current_node_id
-            .as_ref()
-            .ok_or_else(|| ContextError::InvalidState("No current node".to_string()))?;
+        self.all_inputs()
+    }
 
-        // Get parent nodes
-        let parent_nodes = self
-            .definition
-            .get_parent_nodes(node_id)
-            .map_err(|e| ContextError::ExecutionError(e.to_string()))?;
+    /// Get the current node's input as one value
+    ///
+    /// A node without upstream outputs gets `null`, a node with one gets
+    /// what that node passes, and a node with several gets an object of
+    /// what each passes, keyed by upstream node id.
+    pub fn input(&self) -> Result<serde_json::Value, ContextError> {
+        let inputs = self.all_inputs()?;
+        if inputs.len() <= 1 {
+            return Ok(inputs.into_values().next().unwrap_or_default());
+        }
+        Ok(serde_json::Value::Object(
+            inputs
+                .into_iter()
+                .map(|(node_id, value)| (node_id.to_string(), value))
+                .collect(),
+        ))
+    }
 
-        // Collect results from parent nodes
+    /// Get what each upstream node passes to the current node, keyed by node
+    ///
+    /// Each value is the upstream node's output, or the field selected by
+    /// the input path of the edge between them. Upstream nodes without an
+    /// output, such as skipped ones, are left out.
+    pub fn all_inputs(&self) -> Result<HashMap<NodeId, serde_json::Value>, ContextError> {
         let mut inputs = HashMap::new();
-        for parent in parent_nodes {
-            if let Some(result) = self.state.node_results.get(&parent.id) {
-                inputs.insert(parent.id.clone(), result.clone());
+        for edge in self.incoming_edges()? {
+            if let Some(input) = self.edge_input(edge)? {
+                inputs.insert(edge.source.clone(), input);
             }
         }
-
         Ok(inputs)
     }
 
+    /// Get what one upstream node passes to the current node
+    ///
+    /// Returns `None` if the upstream node has no output, and an error if
+    /// it is not upstream of the current node.
+    pub fn input_from(&self, node_id: &NodeId) -> Result<Option<serde_json::Value>, ContextError> {
+        let edge = self
+            .incoming_edges()?
+            .into_iter()
+            .find(|edge| &edge.source == node_id)
+            .ok_or_else(|| {
+                ContextError::InvalidState(format!("Node {} is not an upstream node", node_id))
+            })?;
+        self.edge_input(edge)
+    }
+
+    /// Edges into the current node
+    fn incoming_edges(&self) -> Result<Vec<&crate::model::Edge>, ContextError> {
+        let node_id = self
+            .current_node_id
+            .as_ref()
+            .ok_or_else(|| ContextError::InvalidState("No current node".to_string()))?;
+        self.definition
+            .get_incoming_edges(node_id)
+            .map_err(|e| ContextError::ExecutionError(e.to_string()))
+    }
+
+    /// The source's output as passed along an edge
+    fn edge_input(
+        &self,
+        edge: &crate::model::Edge,
+    ) -> Result<Option<serde_json::Value>, ContextError> {
+        let Some(output) = self.state.node_results.get(&edge.source) else {
+            return Ok(None);
+        };
+        let Some(path) = &edge.input_path else {
+            return Ok(Some(output.clone()));
+        };
+        resolve_json_path(output, path)
+            .cloned()
+            .map(Some)
+            .map_err(|e| {
+                ContextError::ExecutionError(format!(
+                    "Input path '{}' of edge {}: {}",
+                    path, edge.id, e
+                ))
+            })
+    }
+
     /// Check if the current context has a required capability
     pub fn has_capability(&self, capability_id: &CapabilityId) -> Result<bool, ContextError> {
         // If no checker is provided, assume all capabilities are allowed
@@ -241,13 +449,17 @@ impl ExecutionContext {
 
         let checker = self.capability_checker.as_ref().unwrap();
 
-        // TODO: Get the actual subject and action from the context
-        let subject = "workflow_executor"; // Placeholder
+        // Checks are made on behalf of the principal, if known
+        let subject = self
+            .principal
+            .as_ref()
+            .map(|principal| principal.to_string())
+            .unwrap_or_else(|| "workflow_executor".to_string());
         let object = capability_id.to_string();
         let action = "execute"; // Placeholder
 
         checker
-            .check_permission(subject, &object, action)
+            .check_permission(&subject, &object, action)
             .map_err(ContextError::CapabilityError)
             .map(|result| result.is_allowed())
     }
@@ -310,6 +522,7 @@ impl ExecutionContext {
 impl fmt::Debug for ExecutionContext {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("ExecutionContext")
+            .field("principal", &self.principal)
             .field("current_node_id", &self.current_node_id)
             .field("priority", &self.priority)
             .field("attempt", &self.attempt)
@@ -321,6 +534,22 @@ impl fmt::Debug for ExecutionContext {
 
 /// Evaluate a JSON path against a value
 fn evaluate_json_path(value: &serde_json::Value, path: &str) -> Result<bool, String> {
+    // Convert final value to boolean
+    match resolve_json_path(value, path)? {
+        serde_json::Value::Bool(b) => Ok(*b),
+        serde_json::Value::Number(n) => Ok(!n.is_i64() || n.as_i64().unwrap() != 0),
+        serde_json::Value::String(s) => Ok(!s.is_empty()),
+        serde_json::Value::Array(a) => Ok(!a.is_empty()),
+        serde_json::Value::Object(o) => Ok(!o.is_empty()),
+        serde_json::Value::Null => Ok(false),
+    }
+}
+
+/// Find the value at a dotted path with array indexing, like "items[0].id"
+fn resolve_json_path<'a>(
+    value: &'a serde_json::Value,
+    path: &str,
+) -> Result<&'a serde_json::Value, String> {
     // Very simple implementation - would use a proper JSON path library in production
     let mut current = value;
 
@@ -360,15 +589,7 @@ fn evaluate_json_path(value: &serde_json::Value, path: &str) -> Result<bool, Str
         }
     }
 
-    // Convert final value to boolean
-    match current {
-        serde_json::Value::Bool(b) => Ok(*b),
-        serde_json::Value::Number(n) => Ok(!n.is_i64() || n.as_i64().unwrap() != 0),
-        serde_json::Value::String(s) => Ok(!s.is_empty()),
-        serde_json::Value::Array(a) => Ok(!a.is_empty()),
-        serde_json::Value::Object(o) => Ok(!o.is_empty()),
-        serde_json::Value::Null => Ok(false),
-    }
+    Ok(current)
 }
 
 /// Evaluate an expression against a context
@@ -468,4 +689,59 @@ mod tests {
         assert!(evaluate_json_path(&value, "non_existent").is_err());
         assert!(evaluate_json_path(&value, "array[10]").is_err());
     }
+
+    #[test]
+    fn test_inputs_follow_edge_input_paths() {
+        let workflow = crate::model::WorkflowBuilder::new("Pipeline")
+            .node("fetch")
+            .unwrap()
+            .node("price")
+            .unwrap()
+            .node("invoice")
+            .unwrap()
+            .node("unrelated")
+            .unwrap();
+        let fetch_id = workflow.node_id("fetch").unwrap();
+        let price_id = workflow.node_id("price").unwrap();
+        let invoice_id = workflow.node_id("invoice").unwrap();
+        let unrelated_id = workflow.node_id("unrelated").unwrap();
+        let definition = Arc::new(
+            workflow
+                .add_edge(
+                    Edge::new(EdgeId::new(), fetch_id.clone(), invoice_id.clone())
+                        .with_input_path("order.items[1]"),
+                )
+                .unwrap()
+                .add_edge_by_name("price", "invoice")
+                .unwrap()
+                .build(),
+        );
+
+        let mut state = WorkflowState::new(definition.clone());
+        state.node_results.insert(
+            fetch_id.clone(),
+            serde_json::json!({ "order": { "items": ["pen", "ink"] } }),
+        );
+        state
+            .node_results
+            .insert(price_id.clone(), serde_json::json!(12));
+        let context = ExecutionContext::new(definition, Arc::new(state)).with_node(&invoice_id);
+
+        // An edge's input path selects a field of its source's output
+        assert_eq!(
+            context.input_from(&fetch_id).unwrap(),
+            Some(serde_json::json!("ink"))
+        );
+        assert_eq!(
+            context.input_from(&price_id).unwrap(),
+            Some(serde_json::json!(12))
+        );
+        assert!(context.input_from(&unrelated_id).is_err());
+        assert_eq!(context.all_inputs().unwrap().len(), 2);
+
+        // Several upstream inputs are merged, keyed by upstream node id
+        let input = context.input().unwrap();
+        assert_eq!(input[fetch_id.to_string()], "ink");
+        assert_eq!(input[price_id.to_string()], 12);
+    }
 }
@@ -1,12 +1,30 @@
+use crate::engine::audit::{AuditAction, ExecutionAuditEntry, ExecutionAuditLog};
+use crate::engine::cache::NodeResultCache;
 use crate::engine::context::{CapabilityChecker, ContextError, ExecutionContext, NodeResult};
+use crate::engine::handler::{node_input, NodeHandlerRegistry};
+use crate::engine::plugin::PluginInvoker;
 use crate::engine::scheduler::{SchedulerError, Task, TaskId, TaskStatus, WorkflowScheduler};
-use crate::model::{NodeId, WorkflowDefinition};
-use std::collections::HashMap;
+use crate::engine::shared::SharedContextStore;
+use crate::model::{
+    ConditionType, NodeId, NodeStatus, NodeType, SwitchConfig, SwitchRouter, WorkflowDefinition,
+    WorkflowId,
+};
+use crate::patterns::saga::{SagaOrchestrator, SagaStatus};
+use crate::state::{
+    CompensationOutcome, ConditionResult, ExecutionResourceUsage, FailureReason, InstanceStatus,
+    NodeStatusEvent, NodeTimelineEntry,
+};
+use lion_core::types::workflow::{ErrorPolicy, ExecutionOptions};
+use lion_core::CapabilityId;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::sync::Arc;
 use std::time::Duration;
 use thiserror::Error;
-use tokio::sync::{mpsc, Mutex, RwLock};
+use tokio::sync::{oneshot, watch, Mutex, RwLock};
+use tokio::task::JoinHandle;
 use tokio::time::timeout;
+use tracing::Instrument;
 
 /// Error types for workflow executor
 #[derive(Error, Debug)]
@@ -26,6 +44,9 @@ pub enum ExecutorError {
     #[error("Task timeout: {0}")]
     TaskTimeout(TaskId),
 
+    #[error("Node missed its heartbeat: {0}")]
+    HeartbeatTimeout(NodeId),
+
     #[error("Task cancelled: {0}")]
     TaskCancelled(TaskId),
 
@@ -38,9 +59,24 @@ pub enum ExecutorError {
     #[error("Executor stopped")]
     ExecutorStopped,
 
+    #[error("Maximum active executions reached: {0}")]
+    ExecutionLimitReached(usize),
+
+    #[error("Level {0} exceeded its time budget")]
+    LevelTimeout(usize),
+
     #[error("No node handler for type: {0}")]
     NoNodeHandler(String),
 
+    #[error("External task not awaiting completion: {0}")]
+    ExternalTaskNotPending(NodeId),
+
+    #[error("Invalid workflow input: {0}")]
+    InvalidInput(String),
+
+    #[error("Execution cancelled: {0}")]
+    ExecutionCancelled(String),
+
     #[error("Other executor error: {0}")]
     Other(String),
 }
@@ -83,6 +119,12 @@ pub type NodeHandler = Arc<
         + Sync,
 >;
 
+/// Callbacks of external task nodes awaiting completion, by instance and node
+type ExternalTasks = HashMap<(String, NodeId), oneshot::Sender<serde_json::Value>>;
+
+/// Routers of switch nodes, by instance and node
+type SwitchRouters = HashMap<(String, NodeId), SwitchRouter>;
+
 /// Configuration for workflow executor
 #[derive(Debug, Clone)]
 pub struct ExecutorConfig {
@@ -112,6 +154,40 @@ pub struct ExecutorConfig {
 
     /// Timeout for yielding a task (seconds)
     pub yield_timeout_seconds: u64,
+
+    /// Maximum number of executions in flight at once (unlimited if `None`)
+    pub max_active_executions: Option<usize>,
+
+    /// What to do with executions submitted beyond `max_active_executions`
+    pub admission_policy: AdmissionPolicy,
+
+    /// Time budget for each topological level of a workflow (unlimited if
+    /// `None`)
+    ///
+    /// A level's clock starts when its first node starts running. Once the
+    /// budget is spent, the level's unfinished nodes are cancelled and the
+    /// workflow fails.
+    pub level_timeout: Option<Duration>,
+
+    /// Longest gap allowed between the heartbeats of a node (unchecked if
+    /// `None`)
+    ///
+    /// A node is watched from its first `ExecutionContext::heartbeat` on.
+    /// One that then goes silent for longer is asked to stop and fails with
+    /// `ExecutorError::HeartbeatTimeout`, going through its error policy.
+    pub heartbeat_timeout: Option<Duration>,
+}
+
+/// Handling of executions submitted while the executor is at capacity
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AdmissionPolicy {
+    /// Queue the execution and start it, in submission order, once a slot
+    /// frees up
+    #[default]
+    Queue,
+
+    /// Reject the execution with `ExecutorError::ExecutionLimitReached`
+    Reject,
 }
 
 impl Default for ExecutorConfig {
@@ -126,6 +202,10 @@ impl Default for ExecutorConfig {
             prioritize_deadlines: true,
             worker_threads: num_cpus::get(),
             yield_timeout_seconds: 1,
+            max_active_executions: None,
+            admission_policy: AdmissionPolicy::default(),
+            level_timeout: None,
+            heartbeat_timeout: None,
         }
     }
 }
@@ -164,6 +244,144 @@ pub struct WorkerStats {
     _total_wait_time: f64,
 }
 
+/// Estimated resource needs of a workflow, derived from its topology
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResourceEstimate {
+    /// Maximum number of nodes that can be runnable at the same time
+    pub peak_parallelism: usize,
+
+    /// Number of sequential stages (topological levels)
+    pub depth: usize,
+
+    /// Total number of nodes
+    pub total_nodes: usize,
+
+    /// Number of nodes of each type, i.e. per node handler
+    pub nodes_by_type: HashMap<String, usize>,
+
+    /// Worker slots this executor would use at peak
+    pub peak_worker_slots: usize,
+}
+
+/// A node as it would run in a planned execution
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlannedNode {
+    /// Node to run
+    pub node_id: NodeId,
+
+    /// Node name, which selects its handler
+    pub name: String,
+
+    /// How the node would be executed
+    pub node_type: NodeType,
+
+    /// Topological level the node runs in
+    pub level: usize,
+
+    /// Whether an edge condition decides at run time if the node runs
+    pub conditional: bool,
+}
+
+/// A capability check a planned execution would make
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum PlannedCheck {
+    /// The node's required capability, checked for the execution's principal
+    Capability {
+        /// Node requiring the capability
+        node_id: NodeId,
+
+        /// Capability required
+        capability_id: CapabilityId,
+    },
+
+    /// A plugin call, checked against the principal's plugin call capabilities
+    PluginCall {
+        /// Node making the call
+        node_id: NodeId,
+
+        /// Plugin called
+        plugin_id: String,
+
+        /// Function called
+        function: String,
+    },
+}
+
+/// What executing a workflow would do, worked out without running any node
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExecutionPlan {
+    /// Workflow planned
+    pub workflow_id: WorkflowId,
+
+    /// Nodes in the order their levels would be scheduled
+    pub nodes: Vec<PlannedNode>,
+
+    /// Capability checks the execution would make, in node order
+    pub capability_checks: Vec<PlannedCheck>,
+}
+
+impl ExecutionPlan {
+    /// Nodes that may be skipped by an edge condition
+    pub fn conditional_nodes(&self) -> impl Iterator<Item = &PlannedNode> {
+        self.nodes.iter().filter(|node| node.conditional)
+    }
+}
+
+/// Admission bookkeeping for workflow executions
+#[derive(Default)]
+struct ExecutionSlots {
+    /// Executions that have started and not yet finished
+    active: HashSet<String>,
+
+    /// Executions waiting for a free slot, in submission order
+    queued: VecDeque<String>,
+}
+
+/// Topological levels of a workflow instance and their deadlines
+struct LevelClock {
+    /// Nodes in each level
+    levels: Vec<Vec<NodeId>>,
+
+    /// Level of each node
+    node_levels: HashMap<NodeId, usize>,
+
+    /// Deadlines of the levels whose clock has started
+    deadlines: HashMap<usize, std::time::Instant>,
+}
+
+impl LevelClock {
+    fn new(definition: &WorkflowDefinition) -> Result<Self, ExecutorError> {
+        let levels = definition.get_topological_levels()?;
+        let node_levels = levels
+            .iter()
+            .enumerate()
+            .flat_map(|(level, nodes)| nodes.iter().map(move |id| (id.clone(), level)))
+            .collect();
+
+        Ok(LevelClock {
+            levels,
+            node_levels,
+            deadlines: HashMap::new(),
+        })
+    }
+
+    /// Get the level of a node and its deadline, starting the level's clock
+    /// if this is its first node to run
+    fn start_node(
+        &mut self,
+        node_id: &NodeId,
+        budget: Duration,
+    ) -> Option<(usize, std::time::Instant)> {
+        let level = *self.node_levels.get(node_id)?;
+        let deadline = *self
+            .deadlines
+            .entry(level)
+            .or_insert_with(|| std::time::Instant::now() + budget);
+        Some((level, deadline))
+    }
+}
+
 /// Workflow executor
 pub struct WorkflowExecutor<S>
 where
@@ -176,7 +394,7 @@ where
     state_manager: Arc<crate::state::StateMachineManager<S>>,
 
     /// Node handlers by node type
-    node_handlers: Arc<RwLock<HashMap<String, NodeHandler>>>,
+    node_handlers: Arc<RwLock<NodeHandlerRegistry>>,
 
     /// Capability checker for capability-based security
     capability_checker: Option<Arc<dyn CapabilityChecker + 'static>>,
@@ -187,14 +405,694 @@ where
     /// Execution configuration
     config: RwLock<ExecutorConfig>,
 
-    /// Whether the executor is running
-    is_running: RwLock<bool>,
+    /// Whether the executor is running (shared with workers)
+    is_running: Arc<RwLock<bool>>,
+
+    /// Shutdown signal observed by workers and the task monitor
+    shutdown_tx: watch::Sender<bool>,
+
+    /// Handles of the spawned worker tasks
+    worker_handles: Mutex<Vec<JoinHandle<()>>>,
+
+    /// Whether a graceful shutdown has completed
+    shutdown_complete: Mutex<bool>,
+
+    /// Active and queued executions
+    executions: Arc<Mutex<ExecutionSlots>>,
+
+    /// Level clocks of running instances, used for level timeouts
+    level_clocks: Arc<Mutex<HashMap<String, LevelClock>>>,
+
+    /// Audit trail of submissions and node capability checks
+    audit_log: Arc<ExecutionAuditLog>,
+
+    /// External task nodes waiting for their result
+    external_tasks: Arc<Mutex<ExternalTasks>>,
+
+    /// Values shared between node handlers and other parts of an execution
+    shared_store: Option<Arc<SharedContextStore>>,
+
+    /// Orchestrator running the sagas of saga nodes
+    saga_orchestrator: Option<Arc<SagaOrchestrator>>,
+
+    /// Invoker of the plugins called by plugin call nodes
+    plugin_invoker: Option<Arc<dyn PluginInvoker>>,
+
+    /// Checker of the capabilities plugin call nodes need
+    plugin_capabilities: Option<Arc<lion_capability::CapabilityChecker>>,
+
+    /// Cache of the outputs of cacheable nodes
+    node_cache: Option<Arc<NodeResultCache>>,
+
+    /// Cancellation signals observed by running node handlers, by instance
+    cancel_signals: Arc<Mutex<HashMap<String, watch::Sender<bool>>>>,
+
+    /// Routers of switch nodes of running instances, so seeded weighted
+    /// selections follow one sequence within an execution
+    switch_routers: Arc<Mutex<SwitchRouters>>,
+}
+
+/// What a worker needs to run the tasks it takes from the scheduler
+///
+/// Everything is shared with the executor that started the worker, except
+/// the configuration, which is a copy taken when the worker started.
+struct WorkerContext<S>
+where
+    S: crate::state::storage::StorageBackend,
+{
+    scheduler: Arc<WorkflowScheduler>,
+    state_manager: Arc<crate::state::StateMachineManager<S>>,
+    node_handlers: Arc<RwLock<NodeHandlerRegistry>>,
+    capability_checker: Option<Arc<dyn CapabilityChecker + 'static>>,
+    workers: Arc<RwLock<Vec<Worker>>>,
+    executions: Arc<Mutex<ExecutionSlots>>,
+    level_clocks: Arc<Mutex<HashMap<String, LevelClock>>>,
+    audit_log: Arc<ExecutionAuditLog>,
+    external_tasks: Arc<Mutex<ExternalTasks>>,
+    shared_store: Option<Arc<SharedContextStore>>,
+    saga_orchestrator: Option<Arc<SagaOrchestrator>>,
+    plugin_invoker: Option<Arc<dyn PluginInvoker>>,
+    plugin_capabilities: Option<Arc<lion_capability::CapabilityChecker>>,
+    node_cache: Option<Arc<NodeResultCache>>,
+    cancel_signals: Arc<Mutex<HashMap<String, watch::Sender<bool>>>>,
+    switch_routers: Arc<Mutex<SwitchRouters>>,
+    is_running: Arc<RwLock<bool>>,
+    config: ExecutorConfig,
+}
+
+impl<S> WorkerContext<S>
+where
+    S: crate::state::storage::StorageBackend,
+{
+    /// Run a task taken from the scheduler by a worker and record its outcome
+    async fn run_task(&self, worker_id: usize, task: Arc<Task>) {
+        let task_id = task.id;
+        let node_id = task.node_id.clone();
+        let instance_id = task.instance_id.clone();
+
+        // Tasks of paused instances are dropped; their nodes stay
+        // ready and are rescheduled on resume
+        if let Some(state) = self.state_manager.get_instance(&instance_id).await {
+            let state = state.read().await;
+            if state.is_paused {
+                if let Err(e) = self.scheduler.cancel_task(task_id).await {
+                    tracing::error!("Failed to cancel task: {:?}", e);
+                }
+                tracing::debug!("Instance paused, deferring node");
+                return;
+            }
+        }
+
+        // Mark task as running
+        if let Err(e) = self.scheduler.mark_task_running(task_id).await {
+            tracing::error!("Failed to mark task as running: {:?}", e);
+            return;
+        }
+
+        // Mark node as running in state machine
+        if let Err(e) = self
+            .state_manager
+            .set_node_running(&instance_id, &node_id)
+            .await
+        {
+            tracing::error!("Failed to mark node as running: {:?}", e);
+            if let Err(e) = self.scheduler.cancel_task(task_id).await {
+                tracing::error!("Failed to cancel task: {:?}", e);
+            }
+            return;
+        }
+
+        // Get node type
+        let definition = match self.state_manager.get_instance(&instance_id).await {
+            Some(state) => state.read().await.definition.clone(),
+            None => None,
+        };
+        let node_type = definition
+            .as_ref()
+            .and_then(|def| def.get_node(&node_id))
+            .map(|node| node.name.clone()) // Use node name as type
+            .unwrap_or_else(|| String::from("unknown"));
+        let node_kind = definition
+            .as_ref()
+            .and_then(|def| def.get_node(&node_id))
+            .map(|node| node.node_type.clone())
+            .unwrap_or_default();
+
+        // Start the clock of the node's level
+        let level_deadline = match (self.config.level_timeout, &definition) {
+            (Some(budget), Some(def)) => {
+                let mut clocks = self.level_clocks.lock().await;
+                if !clocks.contains_key(&instance_id) {
+                    match LevelClock::new(def) {
+                        Ok(clock) => {
+                            clocks.insert(instance_id.clone(), clock);
+                        }
+                        Err(e) => {
+                            tracing::error!("Failed to compute levels: {:?}", e);
+                        }
+                    }
+                }
+                clocks
+                    .get_mut(&instance_id)
+                    .and_then(|clock| clock.start_node(&node_id, budget))
+            }
+            _ => None,
+        };
+
+        // Get node handler
+        let handler = {
+            let handlers = self.node_handlers.read().await;
+            handlers.get(&node_type)
+        };
+
+        // Execute task with timeout
+        let start_time = std::time::Instant::now();
+
+        // Check the node's required capability on behalf of the principal
+        let required_capability = definition
+            .as_ref()
+            .and_then(|def| def.get_node(&node_id))
+            .and_then(|node| node.required_capability);
+        let capability_denied = match required_capability {
+            Some(capability_id) => {
+                let mut context = task.context.clone();
+                if let Some(checker) = &self.capability_checker {
+                    context = context.with_capability_checker(checker.clone());
+                }
+                let allowed = context.has_capability(&capability_id).unwrap_or_else(|e| {
+                    tracing::error!("Capability check failed: {:?}", e);
+                    false
+                });
+                self.audit_log.record(ExecutionAuditEntry {
+                    timestamp: chrono::Utc::now(),
+                    workflow_id: context.definition.id.clone(),
+                    instance_id: instance_id.clone(),
+                    node_id: Some(node_id.clone()),
+                    principal: context.principal.clone(),
+                    action: AuditAction::CapabilityCheck {
+                        capability_id,
+                        allowed,
+                    },
+                });
+                (!allowed).then(|| {
+                    ContextError::CapabilityError(format!(
+                        "{} may not use capability {}",
+                        context
+                            .principal
+                            .as_ref()
+                            .map_or("workflow_executor".to_string(), |p| p.to_string()),
+                        capability_id
+                    ))
+                })
+            }
+            None => None,
+        };
+
+        // Execution timeout, bounded by the level's deadline
+        let bound_by_level = |mut limit: Duration| {
+            if let Some((_, deadline)) = level_deadline {
+                limit = limit.min(deadline.saturating_duration_since(std::time::Instant::now()));
+            }
+            limit
+        };
+        let timed_out = || match level_deadline {
+            Some((level, deadline)) if std::time::Instant::now() >= deadline => {
+                ExecutorError::LevelTimeout(level)
+            }
+            _ => ExecutorError::TaskTimeout(task_id),
+        };
+
+        let execution_result = if let Some(denied) = capability_denied {
+            Err(ExecutorError::ContextError(denied))
+        } else if let NodeType::ExternalTask { timeout_ms } = node_kind {
+            // Hold the worker until the external system reports back
+            let (result_tx, result_rx) = oneshot::channel();
+            let key = (instance_id.clone(), node_id.clone());
+            self.external_tasks
+                .lock()
+                .await
+                .insert(key.clone(), result_tx);
+
+            let limit = bound_by_level(
+                timeout_ms
+                    .map(Duration::from_millis)
+                    .unwrap_or(self.config.default_timeout),
+            );
+            let result = match timeout(limit, result_rx).await {
+                Ok(Ok(output)) => Ok(NodeResult::success(node_id.clone(), output)),
+                Ok(Err(_)) => Err(ExecutorError::Other(
+                    "External task callback dropped".to_string(),
+                )),
+                Err(_) => Err(timed_out()),
+            };
+            self.external_tasks.lock().await.remove(&key);
+            result
+        } else if let NodeType::Saga { saga_id } = node_kind {
+            match &self.saga_orchestrator {
+                Some(orchestrator) => {
+                    let limit = bound_by_level(self.config.default_timeout);
+                    let run = orchestrator.run_registered_saga(&saga_id, &instance_id);
+                    match timeout(limit, run).await {
+                        Ok(Ok(saga)) if saga.status == SagaStatus::Completed => {
+                            Ok(NodeResult::success(
+                                node_id.clone(),
+                                serde_json::json!({
+                                    "saga_instance_id": saga.instance_id,
+                                    "outcome": "committed",
+                                    "result": saga.result,
+                                }),
+                            ))
+                        }
+                        Ok(Ok(saga)) => {
+                            // Report the saga's error, or else the failed step's
+                            let error = saga
+                                .error
+                                .clone()
+                                .or_else(|| saga.steps.values().find_map(|s| s.error.clone()))
+                                .unwrap_or_default();
+                            Err(ExecutorError::NodeError(format!(
+                                "Saga {} ended {:?}: {}",
+                                saga.instance_id, saga.status, error
+                            )))
+                        }
+                        Ok(Err(e)) => Err(ExecutorError::NodeError(e.to_string())),
+                        Err(_) => Err(timed_out()),
+                    }
+                }
+                None => Err(ExecutorError::Other(
+                    "No saga orchestrator for saga node".to_string(),
+                )),
+            }
+        } else if let NodeType::PluginCall {
+            plugin_id,
+            function,
+        } = node_kind
+        {
+            let mut context = task.context.clone();
+            context.current_node_id = Some(node_id.clone());
+            if let Some(checker) = &self.plugin_capabilities {
+                context = context.with_plugin_capabilities(checker.clone());
+            }
+
+            // The principal must hold a capability for the call
+            match (
+                context.check_plugin_call(&plugin_id, &function),
+                &self.plugin_invoker,
+            ) {
+                (Err(denied), _) => Err(ExecutorError::ContextError(denied)),
+                (Ok(()), None) => Err(ExecutorError::Other(
+                    "No plugin invoker for plugin call node".to_string(),
+                )),
+                (Ok(()), Some(invoker)) => match node_input(&context) {
+                    Ok((_, input)) => {
+                        let limit = bound_by_level(self.config.default_timeout);
+                        let call = invoker.call_plugin(&plugin_id, &function, input);
+                        match timeout(limit, call).await {
+                            Ok(Ok(output)) => Ok(NodeResult::success(node_id.clone(), output)),
+                            Ok(Err(e)) => Err(ExecutorError::NodeError(format!(
+                                "Plugin {} failed in {}: {}",
+                                plugin_id, function, e
+                            ))),
+                            Err(_) => Err(timed_out()),
+                        }
+                    }
+                    Err(e) => Err(e),
+                },
+            }
+        } else if let Some(handler) = handler {
+            // Create execution context
+            let mut context = task.context.clone();
+
+            // Set current node ID in context to ensure handler can access it
+            context.current_node_id = Some(node_id.clone());
+
+            if let Some(checker) = &self.capability_checker {
+                context = context.with_capability_checker(checker.clone());
+            }
+            if let Some(store) = &self.shared_store {
+                context = context.with_shared_store(store.clone());
+            }
+
+            // Look up the output of an earlier run with the same input
+            let cache_node = definition
+                .as_ref()
+                .and_then(|def| def.get_node(&node_id))
+                .filter(|node| node.cache.is_some())
+                .cloned();
+            let cache_input = match (&self.node_cache, &cache_node) {
+                (Some(_), Some(_)) => node_input(&context).ok().map(|(_, input)| input),
+                _ => None,
+            };
+            let cached = match (&self.node_cache, &cache_node, &cache_input) {
+                (Some(cache), Some(node), Some(input)) => cache
+                    .get(&context.definition.id, node, input)
+                    .await
+                    .unwrap_or_else(|e| {
+                        tracing::warn!("Failed to read node cache: {}", e);
+                        None
+                    }),
+                _ => None,
+            };
+
+            if let Some(output) = cached {
+                let mut result = NodeResult::success(node_id.clone(), output);
+                result.metadata = serde_json::json!({ "cached": true });
+                Ok(result)
+            } else {
+                // Execute with timeout, abandoning the handler if the
+                // execution is cancelled or the node goes silent
+                let (heartbeat_tx, heartbeat_rx) = watch::channel(());
+                let (stop_tx, stop_rx) = watch::channel(false);
+                let context = context.with_liveness(heartbeat_tx, stop_rx);
+                let mut cancel_rx = self
+                    .cancel_signals
+                    .lock()
+                    .await
+                    .entry(instance_id.clone())
+                    .or_insert_with(|| watch::channel(false).0)
+                    .subscribe();
+                let limit = bound_by_level(self.config.default_timeout);
+                let workflow_id = context.definition.id.clone();
+                let execution_future = (handler)(context);
+                let result = tokio::select! {
+                    result = timeout(limit, execution_future) => match result {
+                        Ok(result) => result,
+                        Err(_) => Err(timed_out()),
+                    },
+                    Ok(_) = cancel_rx.wait_for(|cancelled| *cancelled) => {
+                        stop_tx.send_replace(true);
+                        Err(ExecutorError::ExecutionCancelled(instance_id.clone()))
+                    }
+                    _ = heartbeat_lapse(heartbeat_rx, self.config.heartbeat_timeout) => {
+                        tracing::warn!("Node missed its heartbeat, asking it to stop");
+                        stop_tx.send_replace(true);
+                        Err(ExecutorError::HeartbeatTimeout(node_id.clone()))
+                    }
+                };
+
+                // Keep successful outputs for the next run
+                if let (Some(cache), Some(node), Some(input), Ok(result)) =
+                    (&self.node_cache, &cache_node, &cache_input, &result)
+                {
+                    if result.status == NodeStatus::Completed {
+                        if let Err(e) = cache.put(&workflow_id, node, input, &result.output).await {
+                            tracing::warn!("Failed to write node cache: {}", e);
+                        }
+                    }
+                }
+                result
+            }
+        } else {
+            Err(ExecutorError::NoNodeHandler(node_type))
+        };
+
+        let execution_time = start_time.elapsed();
+
+        // A node cancelled while it ran keeps its cancelled state
+        let node_cancelled = match self.state_manager.get_instance(&instance_id).await {
+            Some(state) => {
+                state.read().await.get_node_status(&node_id) == Some(NodeStatus::Cancelled)
+            }
+            None => false,
+        };
+        let execution_result = if node_cancelled {
+            Err(ExecutorError::ExecutionCancelled(instance_id.clone()))
+        } else {
+            execution_result
+        };
+
+        // A switch node releases only the branch it selects
+        let execution_result = match execution_result {
+            Ok(node_result) => route_switch(
+                &self.switch_routers,
+                &self.state_manager,
+                &instance_id,
+                &node_id,
+                &node_result.output,
+            )
+            .await
+            .map(|()| node_result),
+            Err(e) => Err(e),
+        };
+
+        // Update worker stats
+        {
+            let mut workers_guard = self.workers.write().await;
+            let worker = &mut workers_guard[worker_id];
+            worker.last_completion = Some(chrono::Utc::now());
+            worker.stats.total_execution_time += execution_time.as_secs_f64();
+
+            match &execution_result {
+                Ok(_) => {
+                    worker.stats.tasks_completed += 1;
+                }
+                Err(_) => {
+                    worker.stats.tasks_failed += 1;
+                }
+            }
+        }
+
+        // Handle execution result
+        match execution_result {
+            Err(ExecutorError::ExecutionCancelled(_)) => {
+                if let Err(e) = self.scheduler.cancel_task(task_id).await {
+                    tracing::error!("Failed to cancel task: {:?}", e);
+                }
+                tracing::info!("Node cancelled with its execution");
+            }
+            Err(ExecutorError::LevelTimeout(level)) => {
+                if let Err(e) = self.scheduler.mark_task_failed(task_id).await {
+                    tracing::error!("Failed to mark task as failed: {:?}", e);
+                }
+
+                // Cancel everything left in the level, this node included
+                let level_nodes = {
+                    let clocks = self.level_clocks.lock().await;
+                    clocks
+                        .get(&instance_id)
+                        .and_then(|clock| clock.levels.get(level).cloned())
+                        .unwrap_or_default()
+                };
+                let error = serde_json::json!({
+                    "error": format!("Level {} exceeded its time budget", level)
+                });
+                match self
+                    .state_manager
+                    .cancel_nodes(&instance_id, &level_nodes, error)
+                    .await
+                {
+                    Ok(cancelled) if !cancelled.is_empty() => {
+                        tracing::warn!(
+                            level,
+                            cancelled = cancelled.len(),
+                            "Level timed out, cancelled unfinished nodes"
+                        );
+                    }
+                    Ok(_) => {}
+                    Err(e) => {
+                        tracing::error!("Failed to cancel level nodes: {:?}", e);
+                    }
+                }
+            }
+            Ok(node_result) => {
+                // Mark task as completed
+                if let Err(e) = self.scheduler.mark_task_completed(task_id).await {
+                    tracing::error!("Failed to mark task as completed: {:?}", e);
+                }
+
+                // Account for the resources the node consumed
+                if let Some(usage) = &node_result.resource_usage {
+                    if let Some(state) = self.state_manager.get_instance(&instance_id).await {
+                        state.write().await.record_resource_usage(usage);
+                    }
+                }
+
+                // Update state machine
+                match self
+                    .state_manager
+                    .set_node_completed(&instance_id, &node_id, node_result.output)
+                    .await
+                {
+                    Err(e) => {
+                        tracing::error!("Failed to mark node as completed: {:?}", e);
+                    }
+                    Ok(newly_ready) => {
+                        start_ready_nodes(
+                            &self.scheduler,
+                            &self.state_manager,
+                            &instance_id,
+                            newly_ready,
+                            *self.is_running.read().await,
+                        )
+                        .await;
+                    }
+                }
+            }
+            Err(e) => {
+                // Mark task as failed
+                if let Err(mark_err) = self.scheduler.mark_task_failed(task_id).await {
+                    tracing::error!("Failed to mark task as failed: {:?}", mark_err);
+                }
+
+                // Update state machine
+                let error_json = match &e {
+                    ExecutorError::NodeError(msg) => {
+                        serde_json::json!({ "error": msg })
+                    }
+                    ExecutorError::TaskTimeout(_) => {
+                        serde_json::json!({ "error": "Task timed out" })
+                    }
+                    ExecutorError::HeartbeatTimeout(_) => {
+                        serde_json::json!({
+                            "error": "Node missed its heartbeat",
+                            "reason": "heartbeat_timeout"
+                        })
+                    }
+                    _ => {
+                        serde_json::json!({ "error": format!("{:?}", e) })
+                    }
+                };
+
+                tracing::error!("Task execution failed: {:?}", e);
+
+                // Apply the node's error policy, else the workflow default
+                let policy = definition
+                    .as_ref()
+                    .map(|def| def.error_policy_for(&node_id))
+                    .unwrap_or(ErrorPolicy::Fail);
+                let attempts = match self.state_manager.get_instance(&instance_id).await {
+                    Some(state) => state
+                        .read()
+                        .await
+                        .node_timings
+                        .get(&node_id)
+                        .map_or(0, |timing| timing.attempts),
+                    None => 0,
+                };
 
-    /// Cancellation channel
-    cancel_tx: mpsc::Sender<()>,
+                match policy {
+                    ErrorPolicy::Retry { max_attempts } if attempts <= max_attempts => {
+                        tracing::warn!(attempts, max_attempts, "Retrying failed node");
+                        match self
+                            .state_manager
+                            .requeue_node(&instance_id, &node_id)
+                            .await
+                        {
+                            Ok(()) => {
+                                start_ready_nodes(
+                                    &self.scheduler,
+                                    &self.state_manager,
+                                    &instance_id,
+                                    vec![node_id.clone()],
+                                    *self.is_running.read().await,
+                                )
+                                .await;
+                            }
+                            Err(state_err) => {
+                                tracing::error!("Failed to requeue node: {:?}", state_err);
+                            }
+                        }
+                    }
+                    ErrorPolicy::Skip => {
+                        tracing::warn!("Skipping failed node");
+                        match self
+                            .state_manager
+                            .set_node_skipped(&instance_id, &node_id, error_json)
+                            .await
+                        {
+                            Ok(newly_ready) => {
+                                start_ready_nodes(
+                                    &self.scheduler,
+                                    &self.state_manager,
+                                    &instance_id,
+                                    newly_ready,
+                                    *self.is_running.read().await,
+                                )
+                                .await;
+                            }
+                            Err(state_err) => {
+                                tracing::error!("Failed to mark node as skipped: {:?}", state_err);
+                            }
+                        }
+                    }
+                    policy => {
+                        if let ErrorPolicy::Custom {
+                            plugin_id,
+                            function,
+                        } = policy
+                        {
+                            tracing::warn!(
+                                %plugin_id,
+                                %function,
+                                "Custom error handlers are not supported, failing node"
+                            );
+                        }
+                        // Then the failure policy decides what runs next
+                        let failure_policy = definition
+                            .as_ref()
+                            .map(|def| def.failure_policy_for(&node_id))
+                            .unwrap_or_default();
+                        match self
+                            .state_manager
+                            .set_node_failed_with_policy(
+                                &instance_id,
+                                &node_id,
+                                error_json,
+                                failure_policy,
+                            )
+                            .await
+                        {
+                            Ok(newly_ready) => {
+                                start_ready_nodes(
+                                    &self.scheduler,
+                                    &self.state_manager,
+                                    &instance_id,
+                                    newly_ready,
+                                    *self.is_running.read().await,
+                                )
+                                .await;
+                            }
+                            Err(state_err) => {
+                                tracing::error!("Failed to mark node as failed: {:?}", state_err);
+                            }
+                        }
+                    }
+                }
+            }
+        }
 
-    /// Cancellation receiver (kept for cleanup)
-    _cancel_rx: Mutex<mpsc::Receiver<()>>,
+        // Free the execution slot once the instance has finished
+        let finished = match self.state_manager.get_instance(&instance_id).await {
+            Some(state) => {
+                let state = state.read().await;
+                state.is_completed || state.has_failed
+            }
+            None => false,
+        };
+        if finished {
+            roll_back(
+                &self.state_manager,
+                &self.node_handlers,
+                &instance_id,
+                self.config.default_timeout,
+            )
+            .await;
+            self.level_clocks.lock().await.remove(&instance_id);
+            self.cancel_signals.lock().await.remove(&instance_id);
+            self.switch_routers
+                .lock()
+                .await
+                .retain(|(id, _), _| *id != instance_id);
+            finish_execution(
+                &self.executions,
+                &self.scheduler,
+                &self.state_manager,
+                &instance_id,
+                *self.is_running.read().await,
+            )
+            .await;
+        }
+    }
 }
 
 impl<S> WorkflowExecutor<S>
@@ -207,7 +1105,7 @@ where
         state_manager: Arc<crate::state::StateMachineManager<S>>,
         config: ExecutorConfig,
     ) -> Self {
-        let (tx, rx) = mpsc::channel(1);
+        let (shutdown_tx, _) = watch::channel(false);
 
         // Initialize workers
         let mut workers = Vec::with_capacity(config.worker_threads);
@@ -224,13 +1122,25 @@ where
         WorkflowExecutor {
             scheduler,
             state_manager,
-            node_handlers: Arc::new(RwLock::new(HashMap::new())),
+            node_handlers: Arc::new(RwLock::new(NodeHandlerRegistry::new())),
             capability_checker: None,
             workers: Arc::new(RwLock::new(workers)),
             config: RwLock::new(config),
-            is_running: RwLock::new(true),
-            cancel_tx: tx,
-            _cancel_rx: Mutex::new(rx),
+            is_running: Arc::new(RwLock::new(true)),
+            shutdown_tx,
+            worker_handles: Mutex::new(Vec::new()),
+            shutdown_complete: Mutex::new(false),
+            executions: Arc::new(Mutex::new(ExecutionSlots::default())),
+            level_clocks: Arc::new(Mutex::new(HashMap::new())),
+            audit_log: Arc::new(ExecutionAuditLog::default()),
+            external_tasks: Arc::new(Mutex::new(HashMap::new())),
+            shared_store: None,
+            saga_orchestrator: None,
+            plugin_invoker: None,
+            plugin_capabilities: None,
+            node_cache: None,
+            cancel_signals: Arc::new(Mutex::new(HashMap::new())),
+            switch_routers: Arc::new(Mutex::new(HashMap::new())),
         }
     }
 
@@ -243,10 +1153,81 @@ where
         self
     }
 
+    /// Set the store of values shared across an execution
+    pub fn with_shared_context(mut self, store: Arc<SharedContextStore>) -> Self {
+        self.shared_store = Some(store);
+        self
+    }
+
+    /// Set the orchestrator that runs the sagas of saga nodes
+    ///
+    /// Saga nodes name a definition registered with the orchestrator; the
+    /// saga is correlated with the workflow instance.
+    pub fn with_saga_orchestrator(mut self, orchestrator: Arc<SagaOrchestrator>) -> Self {
+        self.saga_orchestrator = Some(orchestrator);
+        self
+    }
+
+    /// Set the invoker of the plugins called by plugin call nodes
+    ///
+    /// Each call must be permitted by a `PluginCallCapability` of the
+    /// workflow's principal, checked by the checker set with
+    /// `with_plugin_capabilities`; without one, plugin call nodes fail.
+    pub fn with_plugin_invoker(mut self, invoker: Arc<dyn PluginInvoker>) -> Self {
+        self.plugin_invoker = Some(invoker);
+        self
+    }
+
+    /// Set the checker of the capabilities plugin call nodes need
+    ///
+    /// Its audit log records whether each call was allowed.
+    pub fn with_plugin_capabilities(
+        mut self,
+        checker: Arc<lion_capability::CapabilityChecker>,
+    ) -> Self {
+        self.plugin_capabilities = Some(checker);
+        self
+    }
+
+    /// Set the cache that lets cacheable nodes reuse earlier outputs
+    ///
+    /// A task node with a cache policy whose input hashes the same as an
+    /// earlier successful run gets that run's output without its handler
+    /// being called.
+    pub fn with_node_cache(mut self, cache: Arc<NodeResultCache>) -> Self {
+        self.node_cache = Some(cache);
+        self
+    }
+
+    /// Get the node output cache, if set
+    pub fn node_cache(&self) -> Option<&Arc<NodeResultCache>> {
+        self.node_cache.as_ref()
+    }
+
+    /// Get the store of values shared across an execution, if set
+    pub fn shared_context(&self) -> Option<&Arc<SharedContextStore>> {
+        self.shared_store.as_ref()
+    }
+
+    /// Get the audit log of this executor
+    pub fn audit_log(&self) -> &Arc<ExecutionAuditLog> {
+        &self.audit_log
+    }
+
     /// Register a node handler for a specific node type
     pub async fn register_node_handler(&self, node_type: &str, handler: NodeHandler) {
         let mut handlers = self.node_handlers.write().await;
-        handlers.insert(node_type.to_string(), handler);
+        handlers.register(node_type, handler);
+    }
+
+    /// Set the handler for node types without their own
+    pub async fn set_fallback_handler(&self, handler: NodeHandler) {
+        self.node_handlers.write().await.set_fallback(handler);
+    }
+
+    /// Replace the node handlers with a registry
+    pub async fn set_handler_registry(&self, registry: NodeHandlerRegistry) {
+        *self.node_handlers.write().await = registry;
     }
 
     /// Start the executor
@@ -255,6 +1236,7 @@ where
         let mut is_running = self.is_running.write().await;
         *is_running = true;
         drop(is_running);
+        self.shutdown_tx.send_replace(false);
 
         // Start worker threads
         let config = self.config.read().await;
@@ -273,214 +1255,90 @@ where
     /// Start a worker thread
     async fn start_worker(&self, worker_id: usize) -> Result<(), ExecutorError> {
         // Clone necessary references for the worker
-        let scheduler_clone = self.scheduler.clone();
-        let state_manager_clone = self.state_manager.clone();
-        let node_handlers_clone = self.node_handlers.clone();
-        let capability_checker_clone = self.capability_checker.clone();
-        let workers_clone = self.workers.clone();
-
-        // Get current values
-        let is_running_val = *self.is_running.read().await;
-        let config_val = self.config.read().await.clone();
-
-        // Create a new channel for this worker
-        let (_tx, mut cancel_rx) = mpsc::channel::<()>(1);
+        let worker = WorkerContext {
+            scheduler: self.scheduler.clone(),
+            state_manager: self.state_manager.clone(),
+            node_handlers: self.node_handlers.clone(),
+            capability_checker: self.capability_checker.clone(),
+            workers: self.workers.clone(),
+            executions: self.executions.clone(),
+            level_clocks: self.level_clocks.clone(),
+            audit_log: self.audit_log.clone(),
+            external_tasks: self.external_tasks.clone(),
+            shared_store: self.shared_store.clone(),
+            saga_orchestrator: self.saga_orchestrator.clone(),
+            plugin_invoker: self.plugin_invoker.clone(),
+            plugin_capabilities: self.plugin_capabilities.clone(),
+            node_cache: self.node_cache.clone(),
+            cancel_signals: self.cancel_signals.clone(),
+            switch_routers: self.switch_routers.clone(),
+            is_running: self.is_running.clone(),
+            config: self.config.read().await.clone(),
+        };
+        let mut shutdown_rx = self.shutdown_tx.subscribe();
 
         // Spawn a worker task
-        tokio::spawn(async move {
-            let worker_id_copy = worker_id;
-            let is_running_local = is_running_val;
-
+        let handle = tokio::spawn(async move {
             // Worker loop
             'worker_loop: loop {
                 // Check if executor is still running
-                if !is_running_local {
+                if !*worker.is_running.read().await {
                     break;
                 }
 
                 // Update worker status
                 {
-                    let mut workers_guard = workers_clone.write().await;
+                    let mut workers_guard = worker.workers.write().await;
                     workers_guard[worker_id].is_busy = false;
                     workers_guard[worker_id].current_task = None;
                 }
 
-                // Check cancellation
-                if cancel_rx.try_recv().is_ok() {
-                    break;
-                }
-
                 // Get next task from scheduler
-                let next_task = scheduler_clone.next_task().await;
+                let next_task = worker.scheduler.next_task().await;
 
                 // If no task is available to work on, attempt to schedule ready nodes
-                if next_task.is_none() {
+                let Some(task) = next_task else {
                     tokio::select! {
                         _ = tokio::time::sleep(Duration::from_millis(100)) => {}
-                        _ = cancel_rx.recv() => {
-                            break 'worker_loop;
+                        changed = shutdown_rx.changed() => {
+                            if changed.is_err() {
+                                break 'worker_loop;
+                            }
                         }
                     }
                     continue;
-                }
-
-                let task = next_task.unwrap();
-                let task_id = task.id;
-                let node_id = task.node_id.clone();
-                let instance_id = task.instance_id.clone();
+                };
 
                 // Update worker status
                 {
-                    let mut workers_guard = workers_clone.write().await;
+                    let mut workers_guard = worker.workers.write().await;
                     workers_guard[worker_id].is_busy = true;
-                    workers_guard[worker_id].current_task = Some(task_id);
-                }
-
-                // Mark task as running
-                if let Err(e) = scheduler_clone.mark_task_running(task_id).await {
-                    log::error!("Failed to mark task as running: {:?}", e);
-                    continue;
-                }
-
-                // Mark node as running in state machine
-                if let Err(e) = state_manager_clone
-                    .set_node_running(&instance_id, &node_id)
-                    .await
-                {
-                    log::error!("Failed to mark node as running: {:?}", e);
-                    continue;
-                }
-
-                // Get node type
-                let node_type =
-                    if let Some(state) = state_manager_clone.get_instance(&instance_id).await {
-                        let state_read = state.read().await;
-                        if let Some(def) = &state_read.definition {
-                            if let Some(node) = def.get_node(&node_id) {
-                                node.name.clone() // Use node name as type
-                            } else {
-                                String::from("unknown")
-                            }
-                        } else {
-                            String::from("unknown")
-                        }
-                    } else {
-                        String::from("unknown")
-                    };
-
-                // Get node handler
-                let handler = {
-                    let handlers = node_handlers_clone.read().await;
-                    handlers.get(&node_type).cloned()
-                };
-
-                // Execute task with timeout
-                let start_time = std::time::Instant::now();
-
-                let execution_result = if let Some(handler) = handler {
-                    // Create execution context
-                    let mut context = task.context.clone();
-
-                    // Set current node ID in context to ensure handler can access it
-                    context.current_node_id = Some(node_id.clone());
-
-                    if let Some(checker) = &capability_checker_clone {
-                        context = context.with_capability_checker(checker.clone());
-                    }
-
-                    // Execute with timeout
-                    let execution_future = (handler)(context);
-                    match timeout(config_val.default_timeout, execution_future).await {
-                        Ok(result) => result,
-                        Err(_) => Err(ExecutorError::TaskTimeout(task_id)),
-                    }
-                } else {
-                    Err(ExecutorError::NoNodeHandler(node_type))
-                };
-
-                let execution_time = start_time.elapsed();
-
-                // Update worker stats
-                {
-                    let mut workers_guard = workers_clone.write().await;
-                    let worker = &mut workers_guard[worker_id];
-                    worker.last_completion = Some(chrono::Utc::now());
-                    worker.stats.total_execution_time += execution_time.as_secs_f64();
-
-                    match &execution_result {
-                        Ok(_) => {
-                            worker.stats.tasks_completed += 1;
-                        }
-                        Err(_) => {
-                            worker.stats.tasks_failed += 1;
-                        }
-                    }
+                    workers_guard[worker_id].current_task = Some(task.id);
                 }
 
-                // Handle execution result
-                match execution_result {
-                    Ok(node_result) => {
-                        // Mark task as completed
-                        if let Err(e) = scheduler_clone.mark_task_completed(task_id).await {
-                            log::error!("Failed to mark task as completed: {:?}", e);
-                        }
-
-                        // Update state machine
-                        if let Err(e) = state_manager_clone
-                            .set_node_completed(&instance_id, &node_id, node_result.output)
-                            .await
-                        {
-                            log::error!("Failed to mark node as completed: {:?}", e);
-                        } else {
-                            // Schedule next nodes immediately after completing this one
-                            if let Err(e) =
-                                state_manager_clone.schedule_next_nodes(&instance_id).await
-                            {
-                                log::error!("Failed to schedule next nodes: {:?}", e);
-                            }
-                        }
-                    }
-                    Err(e) => {
-                        // Mark task as failed
-                        if let Err(mark_err) = scheduler_clone.mark_task_failed(task_id).await {
-                            log::error!("Failed to mark task as failed: {:?}", mark_err);
-                        }
-
-                        // Update state machine
-                        let error_json = match &e {
-                            ExecutorError::NodeError(msg) => {
-                                serde_json::json!({ "error": msg })
-                            }
-                            ExecutorError::TaskTimeout(_) => {
-                                serde_json::json!({ "error": "Task timed out" })
-                            }
-                            _ => {
-                                serde_json::json!({ "error": format!("{:?}", e) })
-                            }
-                        };
-
-                        if let Err(state_err) = state_manager_clone
-                            .set_node_failed(&instance_id, &node_id, error_json)
-                            .await
-                        {
-                            log::error!("Failed to mark node as failed: {:?}", state_err);
-                        }
-
-                        log::error!("Task execution failed: {:?}", e);
-                    }
-                }
+                // Run the task within its execution's span so that node logs
+                // can be correlated by execution id
+                let node_span = tracing::info_span!(
+                    parent: &execution_span(&task.instance_id),
+                    "workflow_node",
+                    node_id = %task.node_id,
+                    task_id = %task.id
+                );
+                worker.run_task(worker_id, task).instrument(node_span).await;
             }
 
             // Update worker status on exit
             {
-                let mut workers_guard = workers_clone.write().await;
-                workers_guard[worker_id_copy].is_busy = false;
-                workers_guard[worker_id_copy].current_task = None;
+                let mut workers_guard = worker.workers.write().await;
+                workers_guard[worker_id].is_busy = false;
+                workers_guard[worker_id].current_task = None;
             }
 
-            log::info!("Worker {} exited", worker_id_copy);
+            log::info!("Worker {} exited", worker_id);
         });
 
+        self.worker_handles.lock().await.push(handle);
+
         Ok(())
     }
 
@@ -488,27 +1346,17 @@ where
     async fn start_task_monitor(&self) -> Result<(), ExecutorError> {
         // Clone necessary references
         let scheduler_clone = self.scheduler.clone();
-
-        // Get current values
-        let is_running_val = *self.is_running.read().await;
-
-        // Create a new channel for this monitor
-        let (_tx, mut cancel_rx) = mpsc::channel::<()>(1);
+        let is_running = self.is_running.clone();
+        let mut shutdown_rx = self.shutdown_tx.subscribe();
 
         // Spawn monitor task
         tokio::spawn(async move {
-            let is_running_local = is_running_val;
             let check_interval = Duration::from_secs(1);
 
             // Monitor loop
             'monitor_loop: loop {
                 // Check if executor is still running
-                if !is_running_local {
-                    break;
-                }
-
-                // Check cancellation
-                if cancel_rx.try_recv().is_ok() {
+                if !*is_running.read().await {
                     break;
                 }
 
@@ -527,8 +1375,10 @@ where
                 // Sleep before next check
                 tokio::select! {
                     _ = tokio::time::sleep(check_interval) => {}
-                    _ = cancel_rx.recv() => {
-                        break 'monitor_loop;
+                    changed = shutdown_rx.changed() => {
+                        if changed.is_err() {
+                            break 'monitor_loop;
+                        }
                     }
                 }
             }
@@ -550,35 +1400,13 @@ where
             return Err(ExecutorError::ExecutorStopped);
         }
 
-        // Get the workflow instance
-        let instance = self
-            .state_manager
-            .get_instance(workflow_instance_id)
-            .await
-            .ok_or_else(|| {
-                ExecutorError::Other(format!(
-                    "Workflow instance not found: {}",
-                    workflow_instance_id
-                ))
-            })?;
-
-        // Get the workflow definition
-        let instance_guard = instance.read().await;
-        let definition = instance_guard.definition.clone().ok_or_else(|| {
-            ExecutorError::Other("Workflow instance has no definition".to_string())
-        })?;
-
-        // Create execution context
-        let context =
-            ExecutionContext::new(definition, Arc::new(instance_guard.clone())).with_node(&node_id);
-
-        // Create task
-        let task = Task::new(node_id, workflow_instance_id.to_string(), context);
-
-        // Schedule task
-        let task_id = self.scheduler.schedule_task(task).await?;
-
-        Ok(task_id)
+        enqueue_node(
+            &self.scheduler,
+            &self.state_manager,
+            workflow_instance_id,
+            node_id,
+        )
+        .await
     }
 
     /// Schedule newly ready nodes for a workflow instance
@@ -609,14 +1437,99 @@ where
         &self,
         definition: Arc<WorkflowDefinition>,
     ) -> Result<String, ExecutorError> {
+        self.execute_workflow_with_options(definition, &ExecutionOptions::default())
+            .await
+    }
+
+    /// Execute a workflow with the given options
+    ///
+    /// This executor honours these options:
+    ///
+    /// - `input` is checked against the input schema of each entry node
+    ///   before any state is created.
+    /// - `principal` is recorded on the instance and used for node
+    ///   capability checks and audit entries.
+    /// - `quota` is checked each time a node completes; an instance over
+    ///   quota fails with its unfinished nodes cancelled.
+    /// - `collect_resource_usage` accumulates the usage each node reports.
+    /// - `rollback_on_failure` runs the compensations of completed nodes, in
+    ///   reverse topological order, if the instance fails.
+    ///
+    /// `timeout_ms`, `max_concurrency`, `enable_checkpointing`, `tags` and
+    /// `callback_url` are not yet honoured.
+    pub async fn execute_workflow_with_options(
+        &self,
+        definition: Arc<WorkflowDefinition>,
+        options: &ExecutionOptions,
+    ) -> Result<String, ExecutorError> {
+        // Check if executor is running
+        if !*self.is_running.read().await {
+            return Err(ExecutorError::ExecutorStopped);
+        }
+
+        // Reject malformed input, and nodes nothing would run, before any
+        // state is created
+        Self::validate_input(&definition, options.input.as_ref())?;
+        self.check_handlers(&definition).await?;
+
+        let (max_active, policy) = {
+            let config = self.config.read().await;
+            (config.max_active_executions, config.admission_policy)
+        };
+
+        // Hold the slots while admitting so submissions keep their order
+        let mut slots = self.executions.lock().await;
+        let at_capacity = max_active.is_some_and(|max| slots.active.len() >= max);
+        if at_capacity && policy == AdmissionPolicy::Reject {
+            return Err(ExecutorError::ExecutionLimitReached(
+                max_active.unwrap_or_default(),
+            ));
+        }
+
         // Create a new workflow instance
+        let definition_id = definition.id.clone();
         let instance = self.state_manager.create_instance(definition).await?;
 
-        // Get the instance ID
+        // Record who the instance runs for before any node is scheduled
         let instance_id = {
-            let state = instance.read().await;
+            let mut state = instance.write().await;
+            state.principal = options.principal.clone();
+            state.quota = options.quota.clone();
+            state.rollback_on_failure = options.rollback_on_failure;
+            let quota_needs_usage =
+                options.quota.max_cpu_time_ms.is_some() || options.quota.max_memory_bytes.is_some();
+            if options.collect_resource_usage || quota_needs_usage {
+                state.resource_usage = Some(ExecutionResourceUsage::default());
+            }
             state.instance_id.clone()
         };
+        self.audit_log.record(ExecutionAuditEntry {
+            timestamp: chrono::Utc::now(),
+            workflow_id: definition_id.clone(),
+            instance_id: instance_id.clone(),
+            node_id: None,
+            principal: options.principal.clone(),
+            action: AuditAction::ExecutionSubmitted,
+        });
+
+        // Wait for a free slot behind earlier submissions
+        if at_capacity || !slots.queued.is_empty() {
+            slots.queued.push_back(instance_id.clone());
+            tracing::info!(
+                parent: &execution_span(&instance_id),
+                workflow_id = %definition_id,
+                "Workflow execution queued"
+            );
+            return Ok(instance_id);
+        }
+        slots.active.insert(instance_id.clone());
+        drop(slots);
+
+        tracing::info!(
+            parent: &execution_span(&instance_id),
+            workflow_id = %definition_id,
+            "Workflow execution started"
+        );
 
         // Schedule all ready nodes
         self.schedule_ready_nodes(&instance_id).await?;
@@ -624,21 +1537,166 @@ where
         Ok(instance_id)
     }
 
+    /// Check that every task node has a handler or the fallback
+    async fn check_handlers(&self, definition: &WorkflowDefinition) -> Result<(), ExecutorError> {
+        let missing = self.node_handlers.read().await.missing_handlers(definition);
+        if missing.is_empty() {
+            return Ok(());
+        }
+        Err(ExecutorError::NoNodeHandler(
+            missing.into_iter().collect::<Vec<_>>().join(", "),
+        ))
+    }
+
+    /// Check the execution input against the input schema of each entry node
+    ///
+    /// Missing input is validated as `null`.
+    fn validate_input(
+        definition: &WorkflowDefinition,
+        input: Option<&serde_json::Value>,
+    ) -> Result<(), ExecutorError> {
+        let input = input.unwrap_or(&serde_json::Value::Null);
+
+        for node_id in &definition.start_nodes {
+            let Some(node) = definition.nodes.get(node_id) else {
+                continue;
+            };
+            let Some(schema) = &node.input_schema else {
+                continue;
+            };
+
+            let compiled = jsonschema::JSONSchema::compile(schema).map_err(|e| {
+                ExecutorError::InvalidInput(format!(
+                    "node '{}' has an invalid input schema: {}",
+                    node.name, e
+                ))
+            })?;
+            let reasons: Vec<String> = match compiled.validate(input) {
+                Ok(()) => continue,
+                Err(errors) => errors
+                    .map(|e| format!("{} at '{}'", e, e.instance_path))
+                    .collect(),
+            };
+            return Err(ExecutorError::InvalidInput(format!(
+                "input does not match the schema of entry node '{}': {}",
+                node.name,
+                reasons.join("; ")
+            )));
+        }
+
+        Ok(())
+    }
+
     /// Stop the executor
     pub async fn stop(&self) -> Result<(), ExecutorError> {
         // Set the executor as not running
         let mut is_running = self.is_running.write().await;
         *is_running = false;
 
+        drop(is_running);
+
         // Send cancellation signal to all workers
-        let _ = self.cancel_tx.send(()).await;
+        self.shutdown_tx.send_replace(true);
+
+        // Stop the scheduler
+        self.scheduler.stop().await;
+
+        Ok(())
+    }
+
+    /// Gracefully shut down the executor
+    ///
+    /// Stops accepting new work and lets in-flight nodes finish for up to
+    /// `drain_timeout`. Nodes still running after the timeout are cancelled
+    /// and returned to the ready set. The state of every unfinished instance
+    /// is then checkpointed (when a checkpoint manager is configured) so it
+    /// can be restored later. Calling this again after it has completed is a
+    /// no-op.
+    pub async fn shutdown(&self, drain_timeout: Duration) -> Result<(), ExecutorError> {
+        // Serialize concurrent shutdowns and skip repeated ones
+        let mut shutdown_complete = self.shutdown_complete.lock().await;
+        if *shutdown_complete {
+            return Ok(());
+        }
+
+        // Stop accepting new work; workers exit after their current task
+        *self.is_running.write().await = false;
+        self.shutdown_tx.send_replace(true);
+
+        // Drain in-flight nodes
+        let mut handles = std::mem::take(&mut *self.worker_handles.lock().await);
+        let drained = timeout(drain_timeout, futures::future::join_all(handles.iter_mut()))
+            .await
+            .is_ok();
+
+        if !drained {
+            log::warn!(
+                "Executor did not drain within {:?}, cancelling remaining tasks",
+                drain_timeout
+            );
+            for handle in &handles {
+                handle.abort();
+            }
+            for handle in handles {
+                let _ = handle.await;
+            }
+            self.cancel_stragglers().await;
+        }
+
+        // Persist unfinished instances so they can be resumed
+        if self.state_manager.has_checkpoint_manager() {
+            for instance_id in self.state_manager.list_instances().await {
+                let unfinished = match self.state_manager.get_instance(&instance_id).await {
+                    Some(state) => !state.read().await.is_completed,
+                    None => false,
+                };
+                if unfinished {
+                    self.state_manager
+                        .checkpoint_instance_state(&instance_id)
+                        .await?;
+                }
+            }
+        }
 
         // Stop the scheduler
         self.scheduler.stop().await;
 
+        *shutdown_complete = true;
         Ok(())
     }
 
+    /// Cancel tasks left on aborted workers and requeue their nodes
+    async fn cancel_stragglers(&self) {
+        let stragglers: Vec<TaskId> = {
+            let mut workers = self.workers.write().await;
+            workers
+                .iter_mut()
+                .filter_map(|worker| {
+                    worker.is_busy = false;
+                    worker.current_task.take()
+                })
+                .collect()
+        };
+
+        for task_id in stragglers {
+            let Some(task) = self.scheduler.get_task(task_id).await else {
+                continue;
+            };
+
+            if let Err(e) = self.scheduler.cancel_task(task_id).await {
+                log::error!("Failed to cancel task {}: {:?}", task_id, e);
+            }
+
+            if let Err(e) = self
+                .state_manager
+                .requeue_node(&task.instance_id, &task.node_id)
+                .await
+            {
+                log::warn!("Failed to requeue node {}: {:?}", task.node_id, e);
+            }
+        }
+    }
+
     /// Update executor configuration
     pub async fn update_config(&self, config: ExecutorConfig) {
         let mut current_config = self.config.write().await;
@@ -652,6 +1710,335 @@ where
         workers.iter().map(|w| (w._id, w.stats.clone())).collect()
     }
 
+    /// Pause a workflow instance
+    ///
+    /// Nodes that are already running finish normally, but no further nodes
+    /// of the instance are started until it is resumed. The paused flag is
+    /// part of the instance state, so it survives a checkpoint/restore cycle.
+    pub async fn pause_instance(&self, instance_id: &str) -> Result<(), ExecutorError> {
+        self.state_manager
+            .set_instance_paused(instance_id, true)
+            .await?;
+        tracing::info!(parent: &execution_span(instance_id), "Workflow execution paused");
+        Ok(())
+    }
+
+    /// Resume a paused workflow instance
+    ///
+    /// Ready nodes that have no queued task are scheduled again. This also
+    /// picks up instances that were restored from a checkpoint.
+    pub async fn resume_instance(&self, instance_id: &str) -> Result<Vec<TaskId>, ExecutorError> {
+        // Check if executor is running
+        if !*self.is_running.read().await {
+            return Err(ExecutorError::ExecutorStopped);
+        }
+
+        self.state_manager
+            .set_instance_paused(instance_id, false)
+            .await?;
+
+        // Queued executions are started when a slot frees up
+        if self
+            .executions
+            .lock()
+            .await
+            .queued
+            .iter()
+            .any(|id| id == instance_id)
+        {
+            return Ok(Vec::new());
+        }
+
+        // Nodes that still have a queued task will be picked up by a worker
+        let queued: HashSet<NodeId> = self
+            .scheduler
+            .get_all_tasks()
+            .await
+            .iter()
+            .filter(|task| task.instance_id == instance_id && task.status == TaskStatus::Pending)
+            .map(|task| task.node_id.clone())
+            .collect();
+
+        let mut task_ids = Vec::new();
+        for node_id in self.state_manager.get_ready_nodes(instance_id).await? {
+            if !queued.contains(&node_id) {
+                task_ids.push(self.schedule_node(instance_id, node_id).await?);
+            }
+        }
+
+        tracing::info!(parent: &execution_span(instance_id), "Workflow execution resumed");
+        Ok(task_ids)
+    }
+
+    /// Cancel a workflow execution
+    ///
+    /// Unfinished nodes are cancelled and the instance fails with
+    /// `FailureReason::Cancelled`. Running node handlers are abandoned, while
+    /// the sagas of running saga nodes stop after their current step and
+    /// compensate. Returns the cancelled nodes.
+    pub async fn cancel_execution(&self, instance_id: &str) -> Result<Vec<NodeId>, ExecutorError> {
+        let state = self
+            .state_manager
+            .get_instance(instance_id)
+            .await
+            .ok_or_else(|| ExecutorError::Other(format!("Instance not found: {}", instance_id)))?;
+
+        let cancelled = {
+            let mut state = state.write().await;
+            if state.is_completed || state.has_failed {
+                return Ok(Vec::new());
+            }
+            let node_ids: Vec<NodeId> = state.node_status.keys().cloned().collect();
+            let cancelled = state.cancel_nodes(
+                &node_ids,
+                serde_json::json!({ "error": "Execution cancelled" }),
+            );
+            state.failure_reason = Some(FailureReason::Cancelled);
+            cancelled
+        };
+
+        // Stop the handlers still running for this execution
+        if let Some(signal) = self.cancel_signals.lock().await.remove(instance_id) {
+            signal.send_replace(true);
+        }
+        if let Some(orchestrator) = &self.saga_orchestrator {
+            orchestrator
+                .abort_correlated_sagas(instance_id, "Workflow execution cancelled")
+                .await;
+        }
+
+        tracing::info!(
+            parent: &execution_span(instance_id),
+            cancelled = cancelled.len(),
+            "Workflow execution cancelled"
+        );
+
+        self.level_clocks.lock().await.remove(instance_id);
+        self.switch_routers
+            .lock()
+            .await
+            .retain(|(id, _), _| id != instance_id);
+        self.executions
+            .lock()
+            .await
+            .queued
+            .retain(|id| id != instance_id);
+        finish_execution(
+            &self.executions,
+            &self.scheduler,
+            &self.state_manager,
+            instance_id,
+            *self.is_running.read().await,
+        )
+        .await;
+
+        Ok(cancelled)
+    }
+
+    /// Get the overall status of a workflow instance
+    pub async fn get_instance_status(&self, instance_id: &str) -> Option<InstanceStatus> {
+        let state = self.state_manager.get_instance(instance_id).await?;
+        let status = state.read().await.status();
+        if status == InstanceStatus::Running
+            && self
+                .executions
+                .lock()
+                .await
+                .queued
+                .iter()
+                .any(|id| id == instance_id)
+        {
+            return Some(InstanceStatus::Queued);
+        }
+        Some(status)
+    }
+
+    /// Complete an external task node with the result reported by the
+    /// external system
+    ///
+    /// Fails with `ExternalTaskNotPending` unless the node is currently
+    /// waiting for its result.
+    pub async fn complete_external_task(
+        &self,
+        instance_id: &str,
+        node_id: &NodeId,
+        result: serde_json::Value,
+    ) -> Result<(), ExecutorError> {
+        let sender = self
+            .external_tasks
+            .lock()
+            .await
+            .remove(&(instance_id.to_string(), node_id.clone()))
+            .ok_or_else(|| ExecutorError::ExternalTaskNotPending(node_id.clone()))?;
+        sender
+            .send(result)
+            .map_err(|_| ExecutorError::ExternalTaskNotPending(node_id.clone()))
+    }
+
+    /// Get the execution timeline of a workflow instance
+    pub async fn get_instance_timeline(&self, instance_id: &str) -> Option<Vec<NodeTimelineEntry>> {
+        let state = self.state_manager.get_instance(instance_id).await?;
+        let timeline = state.read().await.timeline();
+        Some(timeline)
+    }
+
+    /// Get the resources consumed by a workflow instance
+    ///
+    /// `None` if the instance is unknown or was not started with
+    /// `ExecutionOptions::collect_resource_usage`.
+    pub async fn get_instance_resource_usage(
+        &self,
+        instance_id: &str,
+    ) -> Option<ExecutionResourceUsage> {
+        let state = self.state_manager.get_instance(instance_id).await?;
+        let usage = state.read().await.resource_usage();
+        usage
+    }
+
+    /// Get the outcomes of an instance's rollback, once it has finished
+    pub async fn get_instance_compensations(
+        &self,
+        instance_id: &str,
+    ) -> Option<Vec<CompensationOutcome>> {
+        let instance = self.state_manager.get_instance(instance_id).await?;
+        let compensations = instance.read().await.compensations.clone();
+        compensations
+    }
+
+    /// Subscribe to the node status changes of every instance, as they
+    /// happen
+    ///
+    /// Filter on `NodeStatusEvent::instance_id` to follow one instance.
+    pub fn subscribe_node_events(&self) -> tokio::sync::broadcast::Receiver<NodeStatusEvent> {
+        self.state_manager.subscribe_node_events()
+    }
+
+    /// Get the fraction of an instance's work that is done, from 0.0 to 1.0
+    pub async fn get_instance_progress(&self, instance_id: &str) -> Option<f32> {
+        let instance = self.state_manager.get_instance(instance_id).await?;
+        let progress = instance.read().await.progress();
+        Some(progress)
+    }
+
+    /// Get why the engine failed a workflow instance, if it did
+    pub async fn get_instance_failure_reason(&self, instance_id: &str) -> Option<FailureReason> {
+        let state = self.state_manager.get_instance(instance_id).await?;
+        let reason = state.read().await.failure_reason.clone();
+        reason
+    }
+
+    /// Get the number of executions that have started and not yet finished
+    pub async fn get_active_execution_count(&self) -> usize {
+        self.executions.lock().await.active.len()
+    }
+
+    /// Get the number of executions waiting for a free slot
+    pub async fn get_queued_execution_count(&self) -> usize {
+        self.executions.lock().await.queued.len()
+    }
+
+    /// Estimate how many slots a workflow needs at peak without running it
+    ///
+    /// Peak parallelism is the width of the widest topological level, which is
+    /// how many nodes become runnable together when every node takes equally long.
+    pub async fn estimate_resources(
+        &self,
+        definition: &WorkflowDefinition,
+    ) -> Result<ResourceEstimate, ExecutorError> {
+        let levels = definition.get_topological_levels()?;
+        let peak_parallelism = levels.iter().map(Vec::len).max().unwrap_or(0);
+
+        // Node names select the handler, so they double as node types
+        let mut nodes_by_type = HashMap::new();
+        for node in definition.nodes.values() {
+            *nodes_by_type.entry(node.name.clone()).or_insert(0) += 1;
+        }
+
+        let worker_threads = self.config.read().await.worker_threads;
+
+        Ok(ResourceEstimate {
+            peak_parallelism,
+            depth: levels.len(),
+            total_nodes: definition.nodes.len(),
+            nodes_by_type,
+            peak_worker_slots: peak_parallelism.min(worker_threads),
+        })
+    }
+
+    /// Plan an execution of a workflow without running any handler
+    ///
+    /// The input is checked against the entry nodes' input schemas as
+    /// `execute_workflow_with_options` would. Edge conditions read the output
+    /// of their source node, so the plan marks the nodes they guard, and the
+    /// nodes left without an unconditional parent, rather than deciding them.
+    pub fn plan(
+        &self,
+        definition: &WorkflowDefinition,
+        input: Option<&serde_json::Value>,
+    ) -> Result<ExecutionPlan, ExecutorError> {
+        Self::validate_input(definition, input)?;
+
+        let mut nodes: Vec<PlannedNode> = Vec::new();
+        let mut capability_checks = Vec::new();
+        let mut conditional = HashSet::new();
+        for (level, node_ids) in definition.get_topological_levels()?.into_iter().enumerate() {
+            for node_id in node_ids {
+                let Some(node) = definition.get_node(&node_id) else {
+                    continue;
+                };
+
+                // A node is skipped when an incoming condition fails, when a
+                // switch parent selects another branch, or when every parent
+                // was skipped
+                let incoming = definition.get_incoming_edges(&node_id)?;
+                let guarded = incoming.iter().any(|edge| {
+                    edge.condition != ConditionType::None
+                        || definition.get_node(&edge.source).is_some_and(|source| {
+                            SwitchConfig::from_config(&source.config).is_some()
+                        })
+                }) || (!incoming.is_empty()
+                    && incoming
+                        .iter()
+                        .all(|edge| conditional.contains(&edge.source)));
+                if guarded {
+                    conditional.insert(node_id.clone());
+                }
+
+                if let Some(capability_id) = node.required_capability {
+                    capability_checks.push(PlannedCheck::Capability {
+                        node_id: node_id.clone(),
+                        capability_id,
+                    });
+                }
+                if let NodeType::PluginCall {
+                    plugin_id,
+                    function,
+                } = &node.node_type
+                {
+                    capability_checks.push(PlannedCheck::PluginCall {
+                        node_id: node_id.clone(),
+                        plugin_id: plugin_id.clone(),
+                        function: function.clone(),
+                    });
+                }
+
+                nodes.push(PlannedNode {
+                    node_id,
+                    name: node.name.clone(),
+                    node_type: node.node_type.clone(),
+                    level,
+                    conditional: guarded,
+                });
+            }
+        }
+
+        Ok(ExecutionPlan {
+            workflow_id: definition.id.clone(),
+            nodes,
+            capability_checks,
+        })
+    }
+
     /// Get the number of busy workers
     pub async fn get_busy_worker_count(&self) -> usize {
         let workers = self.workers.read().await;
@@ -677,27 +2064,330 @@ where
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::engine::scheduler::SchedulerConfig;
-    use crate::model::{Edge, Node};
-    use crate::state::storage::MemoryStorage;
-
-    // Helper to create a test workflow
-    fn create_test_workflow() -> Arc<WorkflowDefinition> {
-        let mut workflow =
-            WorkflowDefinition::new(crate::model::WorkflowId::new(), "Test Workflow".to_string());
+/// Create the tracing span for a workflow execution
+///
+/// Node tasks run inside a child of this span, so every event emitted while
+/// executing a node (including from node handlers) carries the
+/// `execution_id` field.
+pub fn execution_span(execution_id: &str) -> tracing::Span {
+    tracing::info_span!("workflow_execution", execution_id = %execution_id)
+}
 
-        // Create nodes
-        let node1 = Node::new(NodeId::new(), "start".to_string());
-        let node2 = Node::new(NodeId::new(), "process".to_string());
-        let node3 = Node::new(NodeId::new(), "end".to_string());
+/// Wait until a node that has sent a heartbeat misses the next one
+///
+/// Never returns if heartbeats are unchecked, none was sent, or the handler
+/// has finished.
+async fn heartbeat_lapse(mut heartbeat_rx: watch::Receiver<()>, limit: Option<Duration>) {
+    let Some(limit) = limit else {
+        return std::future::pending().await;
+    };
+    if heartbeat_rx.changed().await.is_err() {
+        return std::future::pending().await;
+    }
+    loop {
+        match timeout(limit, heartbeat_rx.changed()).await {
+            Ok(Ok(())) => continue,
+            Ok(Err(_)) => return std::future::pending().await,
+            Err(_) => return,
+        }
+    }
+}
 
-        // Clone the IDs before adding nodes to workflow
-        let node1_id = node1.id.clone();
-        let node2_id = node2.id.clone();
-        let node3_id = node3.id.clone();
+/// Run the compensations of a failed instance's completed nodes, if it
+/// asked for rollback
+///
+/// Compensations run once, one at a time, in reverse topological order,
+/// each with the context of the node it undoes; a failed compensation does
+/// not stop the others. Their outcomes are stored on the instance.
+async fn roll_back<S>(
+    state_manager: &crate::state::StateMachineManager<S>,
+    node_handlers: &RwLock<NodeHandlerRegistry>,
+    instance_id: &str,
+    limit: Duration,
+) where
+    S: crate::state::storage::StorageBackend,
+{
+    let Some(instance) = state_manager.get_instance(instance_id).await else {
+        return;
+    };
+
+    // Clear the request first so concurrent finishing workers roll back once
+    let (definition, snapshot) = {
+        let mut state = instance.write().await;
+        if !state.has_failed || !state.rollback_on_failure {
+            return;
+        }
+        state.rollback_on_failure = false;
+        let Some(definition) = state.definition.clone() else {
+            return;
+        };
+        (definition, Arc::new(state.clone()))
+    };
+    let order = match definition.get_topological_order() {
+        Ok(order) => order,
+        Err(e) => {
+            tracing::error!("Failed to order compensations: {:?}", e);
+            return;
+        }
+    };
+
+    let span = execution_span(instance_id);
+    let mut outcomes = Vec::new();
+    for node_id in order.into_iter().rev() {
+        if snapshot.node_status.get(&node_id) != Some(&NodeStatus::Completed) {
+            continue;
+        }
+        let Some(handler_type) = definition
+            .get_node(&node_id)
+            .and_then(|node| node.compensation.clone())
+        else {
+            continue;
+        };
+
+        let handler = node_handlers.read().await.get_registered(&handler_type);
+        let error = match handler {
+            Some(handler) => {
+                let context =
+                    ExecutionContext::new(definition.clone(), snapshot.clone()).with_node(&node_id);
+                match timeout(limit, handler(context)).await {
+                    Ok(Ok(result)) if result.is_success() => None,
+                    Ok(Ok(result)) => Some(
+                        result
+                            .error
+                            .map_or_else(|| "Compensation failed".to_string(), |e| e.to_string()),
+                    ),
+                    Ok(Err(e)) => Some(e.to_string()),
+                    Err(_) => Some("Compensation timed out".to_string()),
+                }
+            }
+            None => Some(ExecutorError::NoNodeHandler(handler_type.clone()).to_string()),
+        };
+        match &error {
+            None => tracing::info!(parent: &span, node_id = %node_id, "Node compensated"),
+            Some(e) => {
+                tracing::warn!(parent: &span, node_id = %node_id, error = %e, "Compensation failed")
+            }
+        }
+        outcomes.push(CompensationOutcome {
+            node_id,
+            handler: handler_type,
+            error,
+        });
+    }
+
+    instance.write().await.compensations = Some(outcomes);
+}
+
+/// Release the slot of a finished execution and start the next queued one
+async fn finish_execution<S>(
+    executions: &Mutex<ExecutionSlots>,
+    scheduler: &WorkflowScheduler,
+    state_manager: &crate::state::StateMachineManager<S>,
+    instance_id: &str,
+    start_next: bool,
+) where
+    S: crate::state::storage::StorageBackend,
+{
+    let next = {
+        let mut slots = executions.lock().await;
+        if !slots.active.remove(instance_id) {
+            return;
+        }
+        if !start_next {
+            return;
+        }
+        let next = slots.queued.pop_front();
+        if let Some(next_id) = &next {
+            slots.active.insert(next_id.clone());
+        }
+        next
+    };
+
+    let Some(next_id) = next else {
+        return;
+    };
+
+    let span = execution_span(&next_id);
+    tracing::info!(parent: &span, "Queued workflow execution started");
+    match state_manager.get_ready_nodes(&next_id).await {
+        Ok(ready_nodes) => {
+            for node_id in ready_nodes {
+                if let Err(e) = enqueue_node(scheduler, state_manager, &next_id, node_id).await {
+                    tracing::error!(parent: &span, "Failed to enqueue node: {:?}", e);
+                }
+            }
+        }
+        Err(e) => {
+            tracing::error!(parent: &span, "Failed to get ready nodes: {:?}", e);
+        }
+    }
+}
+
+/// Decide the outgoing edges of a completed switch node from its output
+///
+/// The edge to the selected branch passes and every other edge fails, so only
+/// the selected branch is released. Nodes without a switch config are left
+/// alone.
+async fn route_switch<S>(
+    routers: &Mutex<SwitchRouters>,
+    state_manager: &crate::state::StateMachineManager<S>,
+    instance_id: &str,
+    node_id: &NodeId,
+    output: &serde_json::Value,
+) -> Result<(), ExecutorError>
+where
+    S: crate::state::storage::StorageBackend,
+{
+    let Some(state) = state_manager.get_instance(instance_id).await else {
+        return Ok(());
+    };
+    let Some(definition) = state.read().await.definition.clone() else {
+        return Ok(());
+    };
+    let Some(config) = definition
+        .get_node(node_id)
+        .and_then(|node| SwitchConfig::from_config(&node.config))
+    else {
+        return Ok(());
+    };
+
+    // Reuse the router unless the switch was reconfigured
+    let selected = {
+        let mut routers = routers.lock().await;
+        let key = (instance_id.to_string(), node_id.clone());
+        let mut router = match routers.remove(&key) {
+            Some(router) if router.config() == &config => router,
+            _ => SwitchRouter::new(config)
+                .map_err(|e| ExecutorError::NodeError(format!("Invalid switch: {}", e)))?,
+        };
+        let selected = router.route(output);
+        routers.insert(key, router);
+        selected.map_err(|e| ExecutorError::NodeError(format!("Switch routing failed: {}", e)))?
+    };
+
+    let mut state = state.write().await;
+    for edge in definition.get_outgoing_edges(node_id)? {
+        let result = if edge.target == selected {
+            ConditionResult::Passed
+        } else {
+            ConditionResult::Failed
+        };
+        state.set_edge_condition(&edge.id, result)?;
+    }
+
+    Ok(())
+}
+
+/// Enqueue nodes that just became ready, unless the instance is over a quota
+///
+/// Nodes stay in the ready set when `start` is false (the executor is
+/// stopping) or the instance is paused.
+async fn start_ready_nodes<S>(
+    scheduler: &WorkflowScheduler,
+    state_manager: &crate::state::StateMachineManager<S>,
+    instance_id: &str,
+    newly_ready: Vec<NodeId>,
+    start: bool,
+) where
+    S: crate::state::storage::StorageBackend,
+{
+    // Abort the instance instead of starting the nodes once it is over a quota
+    let over_quota = match state_manager
+        .enforce_quota(instance_id, newly_ready.len())
+        .await
+    {
+        Ok(Some(reason)) => {
+            tracing::warn!(%reason, "Aborting workflow execution");
+            true
+        }
+        Ok(None) => false,
+        Err(e) => {
+            tracing::error!("Failed to check quota: {:?}", e);
+            false
+        }
+    };
+
+    if let Err(e) = state_manager.schedule_next_nodes(instance_id).await {
+        tracing::error!("Failed to schedule next nodes: {:?}", e);
+    }
+
+    let paused = match state_manager.get_instance(instance_id).await {
+        Some(state) => state.read().await.is_paused,
+        None => true,
+    };
+    if over_quota || !start || paused {
+        return;
+    }
+
+    for node_id in newly_ready {
+        if let Err(e) = enqueue_node(scheduler, state_manager, instance_id, node_id).await {
+            tracing::error!("Failed to enqueue next node: {:?}", e);
+        }
+    }
+}
+
+/// Create a task for a node of a workflow instance and hand it to the scheduler
+async fn enqueue_node<S>(
+    scheduler: &WorkflowScheduler,
+    state_manager: &crate::state::StateMachineManager<S>,
+    workflow_instance_id: &str,
+    node_id: NodeId,
+) -> Result<TaskId, ExecutorError>
+where
+    S: crate::state::storage::StorageBackend,
+{
+    // Get the workflow instance
+    let instance = state_manager
+        .get_instance(workflow_instance_id)
+        .await
+        .ok_or_else(|| {
+            ExecutorError::Other(format!(
+                "Workflow instance not found: {}",
+                workflow_instance_id
+            ))
+        })?;
+
+    // Get the workflow definition
+    let instance_guard = instance.read().await;
+    let definition = instance_guard
+        .definition
+        .clone()
+        .ok_or_else(|| ExecutorError::Other("Workflow instance has no definition".to_string()))?;
+
+    // Create execution context
+    let context =
+        ExecutionContext::new(definition, Arc::new(instance_guard.clone())).with_node(&node_id);
+
+    // Create task
+    let task = Task::new(node_id, workflow_instance_id.to_string(), context);
+
+    // Schedule task
+    let task_id = scheduler.schedule_task(task).await?;
+
+    Ok(task_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::scheduler::SchedulerConfig;
+    use crate::model::{Edge, Node, NodeStatus};
+    use crate::state::storage::MemoryStorage;
+
+    // Helper to create a test workflow
+    fn create_test_workflow() -> Arc<WorkflowDefinition> {
+        let mut workflow =
+            WorkflowDefinition::new(crate::model::WorkflowId::new(), "Test Workflow".to_string());
+
+        // Create nodes
+        let node1 = Node::new(NodeId::new(), "start".to_string());
+        let node2 = Node::new(NodeId::new(), "process".to_string());
+        let node3 = Node::new(NodeId::new(), "end".to_string());
+
+        // Clone the IDs before adding nodes to workflow
+        let node1_id = node1.id.clone();
+        let node2_id = node2.id.clone();
+        let node3_id = node3.id.clone();
 
         // Add nodes to workflow
         workflow.add_node(node1).unwrap();
@@ -1133,4 +2823,1963 @@ mod tests {
             crate::model::NodeStatus::Pending
         ); // end (not reached)
     }
+
+    // Helper to build an executor with a checkpointing state manager
+    async fn create_checkpointing_executor(
+        start_delay: Duration,
+    ) -> WorkflowExecutor<MemoryStorage> {
+        create_checkpointing_executor_with(start_delay, ExecutorConfig::default()).await
+    }
+
+    async fn create_checkpointing_executor_with(
+        start_delay: Duration,
+        config: ExecutorConfig,
+    ) -> WorkflowExecutor<MemoryStorage> {
+        let scheduler = Arc::new(WorkflowScheduler::new(SchedulerConfig::default()));
+        let checkpoint_manager =
+            crate::state::CheckpointManager::new(MemoryStorage::new(), "1.0.0");
+        let state_manager = Arc::new(crate::state::StateMachineManager::with_checkpoint_manager(
+            checkpoint_manager,
+        ));
+        let exec_config = ExecutorConfig {
+            default_timeout: Duration::from_secs(30),
+            worker_threads: 1,
+            ..config
+        };
+        let executor = WorkflowExecutor::new(scheduler, state_manager, exec_config);
+
+        for name in ["start", "process", "end"] {
+            let delay = if name == "start" {
+                start_delay
+            } else {
+                Duration::ZERO
+            };
+            executor
+                .register_node_handler(
+                    name,
+                    Arc::new(move |ctx| {
+                        Box::pin(async move {
+                            tokio::time::sleep(delay).await;
+                            let node_id = ctx.current_node_id.clone().unwrap();
+                            Ok(NodeResult::success(node_id, serde_json::json!({})))
+                        })
+                    }),
+                )
+                .await;
+        }
+
+        executor
+    }
+
+    // Helper to wait until a worker has picked up a task
+    async fn wait_for_busy_worker(executor: &WorkflowExecutor<MemoryStorage>) {
+        for _ in 0..100 {
+            if executor.get_busy_worker_count().await > 0 {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        panic!("No worker picked up a task");
+    }
+
+    fn node_id_by_name(workflow: &WorkflowDefinition, name: &str) -> NodeId {
+        workflow
+            .nodes
+            .iter()
+            .find(|(_, node)| node.name == name)
+            .map(|(id, _)| id.clone())
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_drains_in_flight_nodes() {
+        let executor = create_checkpointing_executor(Duration::from_millis(200)).await;
+        executor.start().await.unwrap();
+
+        let workflow = create_test_workflow();
+        let start_id = node_id_by_name(&workflow, "start");
+        let process_id = node_id_by_name(&workflow, "process");
+        let instance_id = executor.execute_workflow(workflow).await.unwrap();
+        wait_for_busy_worker(&executor).await;
+
+        executor.shutdown(Duration::from_secs(5)).await.unwrap();
+
+        // The in-flight node finished, but no new work was started
+        let instance = executor
+            .state_manager
+            .get_instance(&instance_id)
+            .await
+            .unwrap();
+        {
+            let state = instance.read().await;
+            assert_eq!(state.node_status[&start_id], NodeStatus::Completed);
+            assert_eq!(state.node_status[&process_id], NodeStatus::Ready);
+            assert!(state.ready_nodes.contains(&process_id));
+        }
+        assert_eq!(executor.get_busy_worker_count().await, 0);
+
+        // The remaining state was checkpointed
+        let restored = executor
+            .state_manager
+            .restore_instance(&instance_id)
+            .await
+            .unwrap();
+        let restored = restored.read().await;
+        assert_eq!(restored.node_status[&start_id], NodeStatus::Completed);
+        assert!(restored.ready_nodes.contains(&process_id));
+
+        // New work is rejected and a second shutdown is a no-op
+        assert!(matches!(
+            executor.execute_workflow(create_test_workflow()).await,
+            Err(ExecutorError::ExecutorStopped)
+        ));
+        executor.shutdown(Duration::from_secs(5)).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_cancels_stragglers() {
+        let executor = create_checkpointing_executor(Duration::from_secs(30)).await;
+        executor.start().await.unwrap();
+
+        let workflow = create_test_workflow();
+        let start_id = node_id_by_name(&workflow, "start");
+        let instance_id = executor.execute_workflow(workflow).await.unwrap();
+        wait_for_busy_worker(&executor).await;
+
+        let began = std::time::Instant::now();
+        executor.shutdown(Duration::from_millis(100)).await.unwrap();
+        assert!(began.elapsed() < Duration::from_secs(5));
+
+        // The straggler's task was cancelled and its node requeued
+        let tasks = executor.scheduler.get_all_tasks().await;
+        assert_eq!(tasks.len(), 1);
+        assert_eq!(tasks[0].status, TaskStatus::Cancelled);
+
+        let instance = executor
+            .state_manager
+            .get_instance(&instance_id)
+            .await
+            .unwrap();
+        let state = instance.read().await;
+        assert_eq!(state.node_status[&start_id], NodeStatus::Ready);
+        assert!(state.ready_nodes.contains(&start_id));
+        drop(state);
+
+        executor.shutdown(Duration::from_millis(100)).await.unwrap();
+    }
+
+    // Writer that collects formatted log output for assertions
+    #[derive(Clone, Default)]
+    struct CapturedLogs(Arc<std::sync::Mutex<Vec<u8>>>);
+
+    impl std::io::Write for CapturedLogs {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_node_logs_carry_execution_id() {
+        let logs = CapturedLogs::default();
+        let writer = logs.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .with_writer(move || writer.clone())
+            .with_ansi(false)
+            .finish();
+        // The test runtime is single-threaded, so workers run on this thread
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let executor = create_checkpointing_executor(Duration::ZERO).await;
+        executor
+            .register_node_handler(
+                "start",
+                Arc::new(|ctx| {
+                    Box::pin(async move {
+                        tracing::info!("start handler ran");
+                        let node_id = ctx.current_node_id.clone().unwrap();
+                        Ok(NodeResult::success(node_id, serde_json::json!({})))
+                    })
+                }),
+            )
+            .await;
+        executor.start().await.unwrap();
+
+        let workflow = create_test_workflow();
+        let instance_id = executor.execute_workflow(workflow).await.unwrap();
+        for _ in 0..100 {
+            let output = String::from_utf8_lossy(&logs.0.lock().unwrap()).into_owned();
+            if output.contains("start handler ran") {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        executor.shutdown(Duration::from_secs(5)).await.unwrap();
+
+        let output = String::from_utf8(logs.0.lock().unwrap().clone()).unwrap();
+        let handler_line = output
+            .lines()
+            .find(|line| line.contains("start handler ran"))
+            .expect("handler log not captured");
+        assert!(handler_line.contains(&format!("execution_id={}", instance_id)));
+        assert!(handler_line.contains("workflow_node"));
+    }
+
+    #[tokio::test]
+    async fn test_pause_and_resume_instance() {
+        let executor = create_checkpointing_executor(Duration::from_millis(100)).await;
+        executor.start().await.unwrap();
+
+        let workflow = create_test_workflow();
+        let start_id = node_id_by_name(&workflow, "start");
+        let process_id = node_id_by_name(&workflow, "process");
+        let instance_id = executor.execute_workflow(workflow).await.unwrap();
+        wait_for_busy_worker(&executor).await;
+
+        // Pausing lets the running node finish but starts nothing new
+        executor.pause_instance(&instance_id).await.unwrap();
+        assert_eq!(
+            executor.get_instance_status(&instance_id).await,
+            Some(InstanceStatus::Paused)
+        );
+        tokio::time::sleep(Duration::from_millis(400)).await;
+        {
+            let instance = executor
+                .state_manager
+                .get_instance(&instance_id)
+                .await
+                .unwrap();
+            let state = instance.read().await;
+            assert_eq!(state.node_status[&start_id], NodeStatus::Completed);
+            assert_ne!(state.node_status[&process_id], NodeStatus::Completed);
+            assert!(state.ready_nodes.contains(&process_id));
+        }
+
+        // The paused flag survives a checkpoint/restore cycle
+        executor
+            .state_manager
+            .checkpoint_instance_state(&instance_id)
+            .await
+            .unwrap();
+        executor
+            .state_manager
+            .restore_instance(&instance_id)
+            .await
+            .unwrap();
+        assert_eq!(
+            executor.get_instance_status(&instance_id).await,
+            Some(InstanceStatus::Paused)
+        );
+
+        // Resuming runs the remaining nodes to completion
+        executor.resume_instance(&instance_id).await.unwrap();
+        for _ in 0..100 {
+            if executor.get_instance_status(&instance_id).await == Some(InstanceStatus::Completed) {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        assert_eq!(
+            executor.get_instance_status(&instance_id).await,
+            Some(InstanceStatus::Completed)
+        );
+
+        executor.shutdown(Duration::from_secs(1)).await.unwrap();
+    }
+
+    // Helper to wait until an instance reaches a status
+    async fn wait_for_status(
+        executor: &WorkflowExecutor<MemoryStorage>,
+        instance_id: &str,
+        status: InstanceStatus,
+    ) {
+        for _ in 0..100 {
+            if executor.get_instance_status(instance_id).await == Some(status) {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        panic!("Instance {} never reached {}", instance_id, status);
+    }
+
+    #[tokio::test]
+    async fn test_max_active_executions_queues() {
+        let config = ExecutorConfig {
+            max_active_executions: Some(1),
+            ..Default::default()
+        };
+        let executor = create_checkpointing_executor_with(Duration::from_millis(100), config).await;
+        executor.start().await.unwrap();
+
+        let first = executor
+            .execute_workflow(create_test_workflow())
+            .await
+            .unwrap();
+        let second_workflow = create_test_workflow();
+        let second_start = node_id_by_name(&second_workflow, "start");
+        let second = executor.execute_workflow(second_workflow).await.unwrap();
+
+        // The second submission waits behind the first
+        assert_eq!(executor.get_active_execution_count().await, 1);
+        assert_eq!(executor.get_queued_execution_count().await, 1);
+        assert_eq!(
+            executor.get_instance_status(&second).await,
+            Some(InstanceStatus::Queued)
+        );
+
+        // None of its nodes run while the first execution is in flight
+        wait_for_busy_worker(&executor).await;
+        let instance = executor.state_manager.get_instance(&second).await.unwrap();
+        assert_eq!(
+            instance.read().await.node_status[&second_start],
+            NodeStatus::Pending
+        );
+        wait_for_status(&executor, &first, InstanceStatus::Completed).await;
+
+        // Once the slot frees up the queued execution runs to completion
+        wait_for_status(&executor, &second, InstanceStatus::Completed).await;
+        assert_eq!(
+            instance.read().await.node_status[&second_start],
+            NodeStatus::Completed
+        );
+        assert_eq!(executor.get_active_execution_count().await, 0);
+        assert_eq!(executor.get_queued_execution_count().await, 0);
+
+        executor.shutdown(Duration::from_secs(1)).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_max_active_executions_rejects() {
+        let config = ExecutorConfig {
+            max_active_executions: Some(1),
+            admission_policy: AdmissionPolicy::Reject,
+            ..Default::default()
+        };
+        let executor = create_checkpointing_executor_with(Duration::from_millis(100), config).await;
+        executor.start().await.unwrap();
+
+        let first = executor
+            .execute_workflow(create_test_workflow())
+            .await
+            .unwrap();
+        assert!(matches!(
+            executor.execute_workflow(create_test_workflow()).await,
+            Err(ExecutorError::ExecutionLimitReached(1))
+        ));
+
+        // A slot is available again once the first execution finishes
+        wait_for_status(&executor, &first, InstanceStatus::Completed).await;
+        executor
+            .execute_workflow(create_test_workflow())
+            .await
+            .unwrap();
+
+        executor.shutdown(Duration::from_secs(1)).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_level_timeout_cancels_stage() {
+        let scheduler = Arc::new(WorkflowScheduler::new(SchedulerConfig::default()));
+        let state_manager = Arc::new(crate::state::StateMachineManager::<MemoryStorage>::new());
+        let exec_config = ExecutorConfig {
+            worker_threads: 2,
+            level_timeout: Some(Duration::from_millis(300)),
+            ..Default::default()
+        };
+        let executor = WorkflowExecutor::new(scheduler, state_manager, exec_config);
+
+        for (name, delay) in [
+            ("start", Duration::ZERO),
+            ("slow", Duration::from_secs(10)),
+            ("lagging", Duration::from_secs(5)),
+            ("fast", Duration::from_millis(10)),
+            ("end", Duration::ZERO),
+        ] {
+            executor
+                .register_node_handler(
+                    name,
+                    Arc::new(move |ctx| {
+                        Box::pin(async move {
+                            tokio::time::sleep(delay).await;
+                            let node_id = ctx.current_node_id.clone().unwrap();
+                            Ok(NodeResult::success(node_id, serde_json::json!({})))
+                        })
+                    }),
+                )
+                .await;
+        }
+
+        // start -> {slow, lagging, fast} -> end
+        let mut workflow =
+            WorkflowDefinition::new(crate::model::WorkflowId::new(), "Staged".to_string());
+        let mut ids = HashMap::new();
+        for name in ["start", "slow", "lagging", "fast", "end"] {
+            let node = Node::new(NodeId::new(), name.to_string());
+            ids.insert(name, node.id.clone());
+            workflow.add_node(node).unwrap();
+        }
+        for name in ["slow", "lagging", "fast"] {
+            workflow
+                .add_edge(Edge::new(
+                    crate::model::EdgeId::new(),
+                    ids["start"].clone(),
+                    ids[name].clone(),
+                ))
+                .unwrap();
+            workflow
+                .add_edge(Edge::new(
+                    crate::model::EdgeId::new(),
+                    ids[name].clone(),
+                    ids["end"].clone(),
+                ))
+                .unwrap();
+        }
+
+        executor.start().await.unwrap();
+        let began = std::time::Instant::now();
+        let instance_id = executor.execute_workflow(Arc::new(workflow)).await.unwrap();
+
+        for _ in 0..100 {
+            if executor.get_instance_status(&instance_id).await == Some(InstanceStatus::Failed)
+                && executor.get_busy_worker_count().await == 0
+            {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        assert!(began.elapsed() < Duration::from_secs(3));
+        assert_eq!(
+            executor.get_instance_status(&instance_id).await,
+            Some(InstanceStatus::Failed)
+        );
+
+        // The stage was cut short and the workflow never moved past it
+        let instance = executor
+            .state_manager
+            .get_instance(&instance_id)
+            .await
+            .unwrap();
+        let state = instance.read().await;
+        assert_eq!(state.node_status[&ids["start"]], NodeStatus::Completed);
+        assert_eq!(state.node_status[&ids["slow"]], NodeStatus::Cancelled);
+        assert_eq!(state.node_status[&ids["lagging"]], NodeStatus::Cancelled);
+        assert_eq!(state.node_status[&ids["end"]], NodeStatus::Pending);
+        drop(state);
+
+        executor.shutdown(Duration::from_secs(1)).await.unwrap();
+    }
+
+    // Checker allowing a single subject to use a single capability
+    struct SingleGrantChecker {
+        subject: String,
+        capability: String,
+    }
+
+    impl CapabilityChecker for SingleGrantChecker {
+        fn check_permission(
+            &self,
+            subject: &str,
+            object: &str,
+            _action: &str,
+        ) -> Result<crate::engine::context::PermissionResult, String> {
+            Ok(crate::engine::context::PermissionResult(
+                subject == self.subject && object == self.capability,
+            ))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_principal_drives_capability_checks_and_audit() {
+        use lion_core::types::workflow::Principal;
+        use lion_core::CapabilityId;
+
+        let capability_id = CapabilityId::new();
+        let alice = Principal::User("alice".to_string());
+        let bob = Principal::User("bob".to_string());
+        let executor = create_checkpointing_executor(Duration::ZERO)
+            .await
+            .with_capability_checker(Arc::new(SingleGrantChecker {
+                subject: alice.to_string(),
+                capability: capability_id.to_string(),
+            }));
+        executor.start().await.unwrap();
+
+        // The "process" node requires the capability
+        let start = Node::new(NodeId::new(), "start".to_string());
+        let process =
+            Node::new(NodeId::new(), "process".to_string()).with_capability(capability_id);
+        let process_id = process.id.clone();
+        let workflow = Arc::new(
+            crate::model::WorkflowBuilder::new("Guarded")
+                .add_node(start.clone())
+                .unwrap()
+                .add_node(process)
+                .unwrap()
+                .add_edge(
+                    Edge::new(crate::model::EdgeId::new(), start.id, process_id.clone())
+                        .with_capability(capability_id),
+                )
+                .unwrap()
+                .build(),
+        );
+
+        let options = |principal: &Principal| ExecutionOptions {
+            principal: Some(principal.clone()),
+            ..ExecutionOptions::default()
+        };
+
+        // The authorized principal runs the workflow to completion
+        let allowed_id = executor
+            .execute_workflow_with_options(workflow.clone(), &options(&alice))
+            .await
+            .unwrap();
+        wait_for_status(&executor, &allowed_id, InstanceStatus::Completed).await;
+
+        // Anyone else is stopped at the guarded node
+        let denied_id = executor
+            .execute_workflow_with_options(workflow, &options(&bob))
+            .await
+            .unwrap();
+        wait_for_status(&executor, &denied_id, InstanceStatus::Failed).await;
+
+        // The principal is recorded on submission and on each capability check
+        for (instance_id, principal, allowed) in
+            [(&allowed_id, &alice, true), (&denied_id, &bob, false)]
+        {
+            let entries = executor.audit_log().entries_for_instance(instance_id);
+            assert_eq!(entries.len(), 2);
+            assert!(entries
+                .iter()
+                .all(|e| e.principal.as_ref() == Some(principal)));
+            assert_eq!(entries[0].action, AuditAction::ExecutionSubmitted);
+            assert_eq!(entries[1].node_id.as_ref(), Some(&process_id));
+            assert_eq!(
+                entries[1].action,
+                AuditAction::CapabilityCheck {
+                    capability_id,
+                    allowed
+                }
+            );
+        }
+
+        executor.shutdown(Duration::from_secs(1)).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_estimate_resources_fan_out() {
+        let executor = create_checkpointing_executor(Duration::ZERO).await;
+
+        // start -> N workers -> end
+        let width = 6;
+        let start = Node::new(NodeId::new(), "start".to_string());
+        let end = Node::new(NodeId::new(), "end".to_string());
+        let mut builder = crate::model::WorkflowBuilder::new("Fan-out")
+            .add_node(start.clone())
+            .unwrap()
+            .add_node(end.clone())
+            .unwrap();
+        for _ in 0..width {
+            let worker = Node::new(NodeId::new(), "process".to_string());
+            let worker_id = worker.id.clone();
+            builder = builder
+                .add_node(worker)
+                .unwrap()
+                .add_edge(Edge::new(
+                    crate::model::EdgeId::new(),
+                    start.id.clone(),
+                    worker_id.clone(),
+                ))
+                .unwrap()
+                .add_edge(Edge::new(
+                    crate::model::EdgeId::new(),
+                    worker_id,
+                    end.id.clone(),
+                ))
+                .unwrap();
+        }
+        let workflow = builder.build();
+
+        let estimate = executor.estimate_resources(&workflow).await.unwrap();
+        assert_eq!(estimate.peak_parallelism, width);
+        assert_eq!(estimate.depth, 3);
+        assert_eq!(estimate.total_nodes, width + 2);
+        assert_eq!(estimate.nodes_by_type["process"], width);
+        assert_eq!(estimate.nodes_by_type["start"], 1);
+        // Bounded by the executor's single worker
+        assert_eq!(estimate.peak_worker_slots, 1);
+    }
+
+    // Make the "process" node of the test workflow an external task
+    fn create_external_task_workflow(timeout_ms: Option<u64>) -> (Arc<WorkflowDefinition>, NodeId) {
+        let mut workflow = (*create_test_workflow()).clone();
+        let process_id = node_id_by_name(&workflow, "process");
+        workflow.nodes.get_mut(&process_id).unwrap().node_type =
+            NodeType::ExternalTask { timeout_ms };
+        (Arc::new(workflow), process_id)
+    }
+
+    async fn wait_for_node_status(
+        executor: &WorkflowExecutor<MemoryStorage>,
+        instance_id: &str,
+        node_id: &NodeId,
+        status: NodeStatus,
+    ) {
+        let instance = executor
+            .state_manager
+            .get_instance(instance_id)
+            .await
+            .unwrap();
+        for _ in 0..100 {
+            if instance.read().await.get_node_status(node_id) == Some(status) {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        panic!("Node {} never reached {}", node_id, status);
+    }
+
+    #[tokio::test]
+    async fn test_external_task_completed_by_callback() {
+        let executor = create_checkpointing_executor(Duration::ZERO).await;
+        executor.start().await.unwrap();
+
+        let (workflow, process_id) = create_external_task_workflow(None);
+        let instance_id = executor.execute_workflow(workflow).await.unwrap();
+
+        // The node waits for the external system
+        wait_for_node_status(&executor, &instance_id, &process_id, NodeStatus::Running).await;
+        assert_eq!(
+            executor.get_instance_status(&instance_id).await,
+            Some(InstanceStatus::Running)
+        );
+
+        executor
+            .complete_external_task(&instance_id, &process_id, serde_json::json!({"paid": true}))
+            .await
+            .unwrap();
+        wait_for_status(&executor, &instance_id, InstanceStatus::Completed).await;
+
+        let instance = executor
+            .state_manager
+            .get_instance(&instance_id)
+            .await
+            .unwrap();
+        assert_eq!(
+            instance.read().await.node_results[&process_id],
+            serde_json::json!({"paid": true})
+        );
+
+        // A second callback has nothing to complete
+        assert!(matches!(
+            executor
+                .complete_external_task(&instance_id, &process_id, serde_json::json!({}))
+                .await,
+            Err(ExecutorError::ExternalTaskNotPending(_))
+        ));
+
+        executor.shutdown(Duration::from_secs(1)).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_external_task_times_out() {
+        let executor = create_checkpointing_executor(Duration::ZERO).await;
+        executor.start().await.unwrap();
+
+        let (workflow, process_id) = create_external_task_workflow(Some(100));
+        let end_id = node_id_by_name(&workflow, "end");
+        let instance_id = executor.execute_workflow(workflow).await.unwrap();
+
+        wait_for_status(&executor, &instance_id, InstanceStatus::Failed).await;
+        let instance = executor
+            .state_manager
+            .get_instance(&instance_id)
+            .await
+            .unwrap();
+        {
+            let state = instance.read().await;
+            assert_eq!(state.node_status[&process_id], NodeStatus::Failed);
+            assert_eq!(
+                state.node_results[&process_id],
+                serde_json::json!({"error": "Task timed out"})
+            );
+            assert_eq!(state.node_status[&end_id], NodeStatus::Skipped);
+            assert_eq!(
+                state.skip_reason(&end_id),
+                Some(crate::state::SkipReason::ParentFailed)
+            );
+        }
+
+        // A late callback is rejected
+        assert!(matches!(
+            executor
+                .complete_external_task(&instance_id, &process_id, serde_json::json!({}))
+                .await,
+            Err(ExecutorError::ExternalTaskNotPending(_))
+        ));
+
+        executor.shutdown(Duration::from_secs(1)).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_resource_usage_accumulates_when_enabled() {
+        let executor = create_checkpointing_executor(Duration::ZERO).await;
+        for (name, memory) in [("start", 1_000), ("process", 4_000), ("end", 2_000)] {
+            executor
+                .register_node_handler(
+                    name,
+                    Arc::new(move |ctx| {
+                        Box::pin(async move {
+                            let mut usage = lion_core::types::ResourceUsage::new();
+                            usage.cpu_time_us = 100;
+                            usage.memory_bytes = memory;
+                            usage
+                                .custom_metrics
+                                .insert(crate::state::FUEL_METRIC.to_string(), 7.0);
+                            let node_id = ctx.current_node_id.clone().unwrap();
+                            Ok(NodeResult::success(node_id, serde_json::json!({}))
+                                .with_resource_usage(usage))
+                        })
+                    }),
+                )
+                .await;
+        }
+        executor.start().await.unwrap();
+
+        let options = ExecutionOptions {
+            collect_resource_usage: true,
+            ..Default::default()
+        };
+        let metered = executor
+            .execute_workflow_with_options(create_test_workflow(), &options)
+            .await
+            .unwrap();
+        let unmetered = executor
+            .execute_workflow(create_test_workflow())
+            .await
+            .unwrap();
+        wait_for_status(&executor, &metered, InstanceStatus::Completed).await;
+        wait_for_status(&executor, &unmetered, InstanceStatus::Completed).await;
+
+        assert_eq!(
+            executor.get_instance_resource_usage(&metered).await,
+            Some(ExecutionResourceUsage {
+                cpu_time_us: 300,
+                peak_memory_bytes: 4_000,
+                fuel_consumed: 21,
+                nodes_metered: 3,
+            })
+        );
+        assert_eq!(executor.get_instance_resource_usage(&unmetered).await, None);
+
+        executor.shutdown(Duration::from_secs(1)).await.unwrap();
+    }
+
+    /// Run the test workflow with "process" as a saga node whose saga
+    /// reserves stock, then charges (failing unless `charge_succeeds`).
+    /// Returns the instance, the saga node and whether stock was released.
+    async fn run_saga_node(
+        charge_succeeds: bool,
+        expected: InstanceStatus,
+    ) -> (
+        WorkflowExecutor<MemoryStorage>,
+        String,
+        NodeId,
+        Arc<std::sync::atomic::AtomicBool>,
+    ) {
+        use crate::patterns::saga::{SagaDefinition, SagaOrchestratorConfig, SagaStepDefinition};
+        use std::sync::atomic::{AtomicBool, Ordering};
+
+        let orchestrator = Arc::new(SagaOrchestrator::new(SagaOrchestratorConfig::default()));
+        let released = Arc::new(AtomicBool::new(false));
+        orchestrator
+            .register_step_handler(
+                "inventory",
+                "reserve",
+                Arc::new(|_step| Box::new(Box::pin(async { Ok(serde_json::json!({"held": 2})) }))),
+            )
+            .await;
+        orchestrator
+            .register_step_handler(
+                "billing",
+                "charge",
+                Arc::new(move |_step| {
+                    Box::new(Box::pin(async move {
+                        if charge_succeeds {
+                            Ok(serde_json::json!({"charged": 10}))
+                        } else {
+                            Err("Card declined".to_string())
+                        }
+                    }))
+                }),
+            )
+            .await;
+        let released_flag = released.clone();
+        orchestrator
+            .register_compensation_handler(
+                "inventory",
+                "release",
+                Arc::new(move |_step| {
+                    released_flag.store(true, Ordering::SeqCst);
+                    Box::new(Box::pin(async { Ok(()) }))
+                }),
+            )
+            .await;
+
+        let mut saga = SagaDefinition::new("checkout", "Checkout");
+        saga.add_step(
+            SagaStepDefinition::new(
+                "reserve",
+                "Reserve",
+                "inventory",
+                "reserve",
+                serde_json::json!({}),
+            )
+            .with_compensation("release", serde_json::json!({})),
+        )
+        .unwrap();
+        saga.add_step(
+            SagaStepDefinition::new(
+                "charge",
+                "Charge",
+                "billing",
+                "charge",
+                serde_json::json!({}),
+            )
+            .with_dependency("reserve"),
+        )
+        .unwrap();
+        orchestrator.register_definition(saga).await.unwrap();
+
+        let executor = create_checkpointing_executor(Duration::ZERO)
+            .await
+            .with_saga_orchestrator(orchestrator);
+        executor.start().await.unwrap();
+
+        let mut workflow = (*create_test_workflow()).clone();
+        let process_id = node_id_by_name(&workflow, "process");
+        workflow.nodes.get_mut(&process_id).unwrap().node_type = NodeType::Saga {
+            saga_id: "checkout".to_string(),
+        };
+        let instance_id = executor.execute_workflow(Arc::new(workflow)).await.unwrap();
+        wait_for_status(&executor, &instance_id, expected).await;
+
+        (executor, instance_id, process_id, released)
+    }
+
+    #[tokio::test]
+    async fn test_saga_node_commits() {
+        let (executor, instance_id, process_id, released) =
+            run_saga_node(true, InstanceStatus::Completed).await;
+
+        let instance = executor
+            .state_manager
+            .get_instance(&instance_id)
+            .await
+            .unwrap();
+        let result = instance.read().await.node_results[&process_id].clone();
+        assert_eq!(result["outcome"], "committed");
+        assert!(result["saga_instance_id"]
+            .as_str()
+            .unwrap()
+            .starts_with("saga-"));
+        assert!(!released.load(std::sync::atomic::Ordering::SeqCst));
+
+        executor.shutdown(Duration::from_secs(1)).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_saga_node_fails_when_saga_compensates() {
+        let (executor, instance_id, process_id, released) =
+            run_saga_node(false, InstanceStatus::Failed).await;
+
+        // The compensation ran before the node failed
+        assert!(released.load(std::sync::atomic::Ordering::SeqCst));
+        let instance = executor
+            .state_manager
+            .get_instance(&instance_id)
+            .await
+            .unwrap();
+        let state = instance.read().await;
+        assert_eq!(state.get_node_status(&process_id), Some(NodeStatus::Failed));
+        let error = state.node_results[&process_id]["error"].as_str().unwrap();
+        assert!(error.contains("Compensated"), "{}", error);
+        assert!(error.contains("Card declined"), "{}", error);
+        drop(state);
+
+        executor.shutdown(Duration::from_secs(1)).await.unwrap();
+    }
+
+    // Invoker echoing the function called and the input it got
+    struct EchoInvoker;
+
+    #[async_trait::async_trait]
+    impl PluginInvoker for EchoInvoker {
+        async fn call_plugin(
+            &self,
+            _plugin_id: &str,
+            function: &str,
+            input: serde_json::Value,
+        ) -> Result<serde_json::Value, String> {
+            Ok(serde_json::json!({ "function": function, "input": input }))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_plugin_call_node_requires_capability() {
+        use lion_capability::{
+            AuditLog, CapabilityStore, InMemoryCapabilityStore, PluginCallCapability,
+        };
+        use lion_core::id::PluginId;
+        use lion_core::types::workflow::Principal;
+
+        // The caller may call "get" on the target, and nothing else
+        let target = PluginId::new();
+        let caller = PluginId::new();
+        let store = Arc::new(InMemoryCapabilityStore::new());
+        store
+            .add_capability(
+                caller,
+                Box::new(PluginCallCapability::with_plugin(
+                    target,
+                    ["get".to_string()].into(),
+                )),
+            )
+            .unwrap();
+        let audit = Arc::new(AuditLog::new(10));
+        let checker = Arc::new(lion_capability::CapabilityChecker::with_audit(
+            store,
+            audit.clone(),
+        ));
+
+        let executor = create_checkpointing_executor(Duration::ZERO)
+            .await
+            .with_plugin_invoker(Arc::new(EchoInvoker))
+            .with_plugin_capabilities(checker);
+        executor.start().await.unwrap();
+
+        let run = |function: &str, principal: Principal| {
+            let mut workflow = (*create_test_workflow()).clone();
+            let process_id = node_id_by_name(&workflow, "process");
+            workflow.nodes.get_mut(&process_id).unwrap().node_type = NodeType::PluginCall {
+                plugin_id: target.to_string(),
+                function: function.to_string(),
+            };
+            let options = ExecutionOptions {
+                principal: Some(principal),
+                ..ExecutionOptions::default()
+            };
+            let executor = &executor;
+            async move {
+                executor
+                    .execute_workflow_with_options(Arc::new(workflow), &options)
+                    .await
+                    .unwrap()
+            }
+        };
+
+        // The permitted call gets the node's input and returns the plugin output
+        let allowed_id = run("get", Principal::Plugin(caller)).await;
+        wait_for_status(&executor, &allowed_id, InstanceStatus::Completed).await;
+        let instance = executor
+            .state_manager
+            .get_instance(&allowed_id)
+            .await
+            .unwrap();
+        let state = instance.read().await;
+        let process_id = node_id_by_name(state.definition.as_ref().unwrap(), "process");
+        assert_eq!(
+            state.node_results[&process_id],
+            serde_json::json!({ "function": "get", "input": {} })
+        );
+        drop(state);
+
+        // Another function, or another principal, fails the node
+        let other_function = run("put", Principal::Plugin(caller)).await;
+        wait_for_status(&executor, &other_function, InstanceStatus::Failed).await;
+        let other_principal = run("get", Principal::User("mallory".to_string())).await;
+        wait_for_status(&executor, &other_principal, InstanceStatus::Failed).await;
+
+        // Each decision about the caller lands in the capability audit log
+        let decisions: Vec<bool> = audit
+            .get_entries(caller)
+            .iter()
+            .map(|e| e.permitted)
+            .collect();
+        assert_eq!(decisions, vec![true, false]);
+
+        executor.shutdown(Duration::from_secs(1)).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_cancel_execution_abandons_running_node() {
+        let executor = create_checkpointing_executor(Duration::from_secs(30)).await;
+        executor.start().await.unwrap();
+
+        let instance_id = executor
+            .execute_workflow(create_test_workflow())
+            .await
+            .unwrap();
+        wait_for_busy_worker(&executor).await;
+
+        let cancelled = executor.cancel_execution(&instance_id).await.unwrap();
+        assert_eq!(cancelled.len(), 3);
+        assert_eq!(
+            executor.get_instance_status(&instance_id).await,
+            Some(InstanceStatus::Failed)
+        );
+        assert_eq!(
+            executor.get_instance_failure_reason(&instance_id).await,
+            Some(FailureReason::Cancelled)
+        );
+        assert_eq!(executor.get_active_execution_count().await, 0);
+
+        // The running handler is abandoned rather than left to finish
+        for _ in 0..100 {
+            if executor.get_busy_worker_count().await == 0 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert_eq!(executor.get_busy_worker_count().await, 0);
+        assert!(executor
+            .cancel_execution(&instance_id)
+            .await
+            .unwrap()
+            .is_empty());
+
+        executor.shutdown(Duration::from_secs(1)).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_cancel_execution_compensates_running_saga() {
+        use crate::patterns::saga::{SagaDefinition, SagaOrchestratorConfig, SagaStepDefinition};
+        use std::sync::atomic::{AtomicBool, Ordering};
+
+        let orchestrator = Arc::new(SagaOrchestrator::new(SagaOrchestratorConfig::default()));
+        let charging = Arc::new(AtomicBool::new(false));
+        let released = Arc::new(AtomicBool::new(false));
+        orchestrator
+            .register_step_handler(
+                "inventory",
+                "reserve",
+                Arc::new(|_step| Box::new(Box::pin(async { Ok(serde_json::json!({"held": 2})) }))),
+            )
+            .await;
+        let charging_flag = charging.clone();
+        orchestrator
+            .register_step_handler(
+                "billing",
+                "charge",
+                Arc::new(move |_step| {
+                    charging_flag.store(true, Ordering::SeqCst);
+                    Box::new(Box::pin(async {
+                        tokio::time::sleep(Duration::from_millis(300)).await;
+                        Ok(serde_json::json!({"charged": 10}))
+                    }))
+                }),
+            )
+            .await;
+        let released_flag = released.clone();
+        orchestrator
+            .register_compensation_handler(
+                "inventory",
+                "release",
+                Arc::new(move |_step| {
+                    released_flag.store(true, Ordering::SeqCst);
+                    Box::new(Box::pin(async { Ok(()) }))
+                }),
+            )
+            .await;
+
+        let mut saga = SagaDefinition::new("checkout", "Checkout");
+        saga.add_step(
+            SagaStepDefinition::new(
+                "reserve",
+                "Reserve",
+                "inventory",
+                "reserve",
+                serde_json::json!({}),
+            )
+            .with_compensation("release", serde_json::json!({})),
+        )
+        .unwrap();
+        saga.add_step(
+            SagaStepDefinition::new(
+                "charge",
+                "Charge",
+                "billing",
+                "charge",
+                serde_json::json!({}),
+            )
+            .with_dependency("reserve"),
+        )
+        .unwrap();
+        orchestrator.register_definition(saga).await.unwrap();
+
+        let executor = create_checkpointing_executor(Duration::ZERO)
+            .await
+            .with_saga_orchestrator(orchestrator.clone());
+        executor.start().await.unwrap();
+
+        let mut workflow = (*create_test_workflow()).clone();
+        let process_id = node_id_by_name(&workflow, "process");
+        workflow.nodes.get_mut(&process_id).unwrap().node_type = NodeType::Saga {
+            saga_id: "checkout".to_string(),
+        };
+        let instance_id = executor.execute_workflow(Arc::new(workflow)).await.unwrap();
+        for _ in 0..100 {
+            if charging.load(Ordering::SeqCst) {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert!(charging.load(Ordering::SeqCst));
+
+        // Cancelling the workflow aborts the saga, which compensates once
+        // its current step is done
+        executor.cancel_execution(&instance_id).await.unwrap();
+        for _ in 0..100 {
+            if !orchestrator
+                .get_sagas_by_status(SagaStatus::Compensated)
+                .await
+                .is_empty()
+            {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        assert!(released.load(Ordering::SeqCst));
+        assert_eq!(
+            orchestrator
+                .get_sagas_by_status(SagaStatus::Compensated)
+                .await
+                .len(),
+            1
+        );
+
+        let instance = executor
+            .state_manager
+            .get_instance(&instance_id)
+            .await
+            .unwrap();
+        assert_eq!(
+            instance.read().await.get_node_status(&process_id),
+            Some(NodeStatus::Cancelled)
+        );
+
+        executor.shutdown(Duration::from_secs(1)).await.unwrap();
+    }
+
+    /// Run the test workflow under `quota`; nodes report 1ms of CPU each and
+    /// "process" peaks at 4000 bytes. Returns the instance once it failed.
+    async fn run_over_quota(
+        quota: lion_core::types::ResourceQuota,
+    ) -> (
+        WorkflowExecutor<MemoryStorage>,
+        String,
+        Arc<WorkflowDefinition>,
+    ) {
+        let executor = create_checkpointing_executor(Duration::ZERO).await;
+        for (name, memory) in [("start", 1_000), ("process", 4_000), ("end", 1_000)] {
+            executor
+                .register_node_handler(
+                    name,
+                    Arc::new(move |ctx| {
+                        Box::pin(async move {
+                            let mut usage = lion_core::types::ResourceUsage::new();
+                            usage.cpu_time_us = 1_000;
+                            usage.memory_bytes = memory;
+                            let node_id = ctx.current_node_id.clone().unwrap();
+                            Ok(NodeResult::success(node_id, serde_json::json!({}))
+                                .with_resource_usage(usage))
+                        })
+                    }),
+                )
+                .await;
+        }
+        executor.start().await.unwrap();
+
+        let workflow = create_test_workflow();
+        let options = ExecutionOptions {
+            quota,
+            ..Default::default()
+        };
+        let instance_id = executor
+            .execute_workflow_with_options(workflow.clone(), &options)
+            .await
+            .unwrap();
+        wait_for_status(&executor, &instance_id, InstanceStatus::Failed).await;
+
+        // The breach is detected once "process" completes, before "end" starts
+        let state = executor
+            .state_manager
+            .get_instance(&instance_id)
+            .await
+            .unwrap();
+        let state = state.read().await;
+        let status = |name| state.get_node_status(&node_id_by_name(&workflow, name));
+        assert_eq!(status("process"), Some(NodeStatus::Completed));
+        assert_eq!(status("end"), Some(NodeStatus::Cancelled));
+        drop(state);
+        assert_eq!(executor.get_active_execution_count().await, 0);
+
+        (executor, instance_id, workflow)
+    }
+
+    #[tokio::test]
+    async fn test_cpu_quota_aborts_execution() {
+        let (executor, instance_id, _) = run_over_quota(lion_core::types::ResourceQuota {
+            max_cpu_time_ms: Some(1),
+            ..Default::default()
+        })
+        .await;
+
+        assert_eq!(
+            executor.get_instance_failure_reason(&instance_id).await,
+            Some(FailureReason::QuotaExceeded {
+                quota: crate::state::QuotaKind::CpuTime,
+                limit: 1,
+                used: 2,
+            })
+        );
+
+        executor.shutdown(Duration::from_secs(1)).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_memory_quota_aborts_execution() {
+        let (executor, instance_id, _) = run_over_quota(lion_core::types::ResourceQuota {
+            max_memory_bytes: Some(3_000),
+            ..Default::default()
+        })
+        .await;
+
+        assert_eq!(
+            executor.get_instance_failure_reason(&instance_id).await,
+            Some(FailureReason::QuotaExceeded {
+                quota: crate::state::QuotaKind::Memory,
+                limit: 3_000,
+                used: 4_000,
+            })
+        );
+
+        executor.shutdown(Duration::from_secs(1)).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_node_quota_aborts_execution() {
+        let (executor, instance_id, workflow) = run_over_quota(lion_core::types::ResourceQuota {
+            max_nodes: Some(2),
+            ..Default::default()
+        })
+        .await;
+
+        assert_eq!(
+            executor.get_instance_failure_reason(&instance_id).await,
+            Some(FailureReason::QuotaExceeded {
+                quota: crate::state::QuotaKind::NodeCount,
+                limit: 2,
+                used: 3,
+            })
+        );
+
+        // The cancelled node carries the breach as its result
+        let state = executor
+            .state_manager
+            .get_instance(&instance_id)
+            .await
+            .unwrap();
+        let end_result =
+            state.read().await.node_results[&node_id_by_name(&workflow, "end")].clone();
+        assert_eq!(
+            end_result,
+            serde_json::json!({ "error": "Quota exceeded: node_count used 3 of 2" })
+        );
+
+        executor.shutdown(Duration::from_secs(1)).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_input_validated_against_entry_schema() {
+        let executor =
+            create_checkpointing_executor_with(Duration::ZERO, ExecutorConfig::default()).await;
+        executor.start().await.unwrap();
+
+        let mut workflow = (*create_test_workflow()).clone();
+        let start_id = node_id_by_name(&workflow, "start");
+        workflow.nodes.get_mut(&start_id).unwrap().input_schema = Some(serde_json::json!({
+            "type": "object",
+            "properties": { "order_id": { "type": "integer" } },
+            "required": ["order_id"]
+        }));
+        let workflow = Arc::new(workflow);
+        let with_input = |input| ExecutionOptions {
+            input,
+            ..Default::default()
+        };
+
+        // Malformed and missing input are rejected before an instance is created
+        let malformed = with_input(Some(serde_json::json!({ "order_id": "abc" })));
+        match executor
+            .execute_workflow_with_options(workflow.clone(), &malformed)
+            .await
+        {
+            Err(ExecutorError::InvalidInput(reason)) => {
+                assert!(reason.contains("'start'"), "{}", reason);
+                assert!(reason.contains("/order_id"), "{}", reason);
+            }
+            other => panic!("expected invalid input, got {:?}", other),
+        }
+        assert!(matches!(
+            executor
+                .execute_workflow_with_options(workflow.clone(), &with_input(None))
+                .await,
+            Err(ExecutorError::InvalidInput(_))
+        ));
+        assert_eq!(executor.get_active_execution_count().await, 0);
+
+        // Valid input proceeds to completion
+        let valid = with_input(Some(serde_json::json!({ "order_id": 42 })));
+        let instance_id = executor
+            .execute_workflow_with_options(workflow, &valid)
+            .await
+            .unwrap();
+        wait_for_status(&executor, &instance_id, InstanceStatus::Completed).await;
+
+        executor.shutdown(Duration::from_secs(1)).await.unwrap();
+    }
+
+    // Helper to create an executor whose "process" handler fails its first
+    // `failures` calls, returning the executor and the call counter
+    async fn create_flaky_executor(
+        failures: u32,
+    ) -> (
+        WorkflowExecutor<MemoryStorage>,
+        Arc<std::sync::atomic::AtomicU32>,
+    ) {
+        let executor =
+            create_checkpointing_executor_with(Duration::ZERO, ExecutorConfig::default()).await;
+        let calls = Arc::new(std::sync::atomic::AtomicU32::new(0));
+        let counter = calls.clone();
+        executor
+            .register_node_handler(
+                "process",
+                Arc::new(move |ctx| {
+                    let call = counter.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                    Box::pin(async move {
+                        if call < failures {
+                            return Err(ExecutorError::NodeError("flaky".to_string()));
+                        }
+                        let node_id = ctx.current_node_id.clone().unwrap();
+                        Ok(NodeResult::success(node_id, serde_json::json!({})))
+                    })
+                }),
+            )
+            .await;
+        executor.start().await.unwrap();
+        (executor, calls)
+    }
+
+    #[tokio::test]
+    async fn test_nodes_inherit_workflow_error_policy() {
+        // A skipped node lets the rest of the workflow run
+        let (executor, calls) = create_flaky_executor(u32::MAX).await;
+        let workflow = Arc::new(
+            (*create_test_workflow())
+                .clone()
+                .with_default_error_policy(ErrorPolicy::Skip),
+        );
+        let instance_id = executor.execute_workflow(workflow.clone()).await.unwrap();
+        wait_for_status(&executor, &instance_id, InstanceStatus::Completed).await;
+
+        let state = executor
+            .state_manager
+            .get_instance(&instance_id)
+            .await
+            .unwrap();
+        let state = state.read().await;
+        let process_id = node_id_by_name(&workflow, "process");
+        assert_eq!(state.node_status[&process_id], NodeStatus::Skipped);
+        assert_eq!(
+            state.node_results[&process_id],
+            serde_json::json!({ "error": "flaky" })
+        );
+        assert_eq!(
+            state.node_status[&node_id_by_name(&workflow, "end")],
+            NodeStatus::Completed
+        );
+        assert_eq!(calls.load(std::sync::atomic::Ordering::SeqCst), 1);
+        drop(state);
+        executor.shutdown(Duration::from_secs(1)).await.unwrap();
+
+        // A retried node runs again until it succeeds
+        let (executor, calls) = create_flaky_executor(2).await;
+        let workflow = Arc::new(
+            (*create_test_workflow())
+                .clone()
+                .with_default_error_policy(ErrorPolicy::Retry { max_attempts: 2 }),
+        );
+        let instance_id = executor.execute_workflow(workflow).await.unwrap();
+        wait_for_status(&executor, &instance_id, InstanceStatus::Completed).await;
+        assert_eq!(calls.load(std::sync::atomic::Ordering::SeqCst), 3);
+        executor.shutdown(Duration::from_secs(1)).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_node_error_policy_overrides_workflow_default() {
+        let (executor, calls) = create_flaky_executor(u32::MAX).await;
+        let mut workflow = (*create_test_workflow())
+            .clone()
+            .with_default_error_policy(ErrorPolicy::Skip);
+        let process_id = node_id_by_name(&workflow, "process");
+        workflow.nodes.get_mut(&process_id).unwrap().error_policy =
+            Some(ErrorPolicy::Retry { max_attempts: 1 });
+        assert_eq!(
+            workflow.error_policy_for(&process_id),
+            ErrorPolicy::Retry { max_attempts: 1 }
+        );
+
+        // Once its retries are exhausted the node fails the workflow
+        let instance_id = executor.execute_workflow(Arc::new(workflow)).await.unwrap();
+        wait_for_status(&executor, &instance_id, InstanceStatus::Failed).await;
+        assert_eq!(calls.load(std::sync::atomic::Ordering::SeqCst), 2);
+
+        executor.shutdown(Duration::from_secs(1)).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_node_events_follow_each_transition() {
+        let (executor, _) = create_flaky_executor(0).await;
+        let mut events = executor.subscribe_node_events();
+        let workflow = create_test_workflow();
+        let instance_id = executor.execute_workflow(workflow.clone()).await.unwrap();
+        wait_for_status(&executor, &instance_id, InstanceStatus::Completed).await;
+
+        let mut transitions: HashMap<NodeId, Vec<NodeStatus>> = HashMap::new();
+        while let Ok(event) = events.try_recv() {
+            assert_eq!(event.instance_id, instance_id);
+            transitions
+                .entry(event.node_id)
+                .or_default()
+                .push(event.status);
+        }
+        for name in ["start", "process", "end"] {
+            assert_eq!(
+                transitions[&node_id_by_name(&workflow, name)],
+                vec![NodeStatus::Running, NodeStatus::Completed],
+                "transitions of {}",
+                name
+            );
+        }
+        executor.shutdown(Duration::from_secs(1)).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_failure_policy_decides_what_runs_downstream() {
+        use crate::model::{EdgeId, FailurePolicy};
+
+        // start -> process -> end, and start -> side
+        let run = |policy: FailurePolicy, expected: InstanceStatus| async move {
+            let (executor, _) = create_flaky_executor(u32::MAX).await;
+            executor
+                .register_node_handler(
+                    "side",
+                    Arc::new(|ctx| {
+                        Box::pin(async move {
+                            let node_id = ctx.current_node_id.clone().unwrap();
+                            Ok(NodeResult::success(node_id, serde_json::json!({})))
+                        })
+                    }),
+                )
+                .await;
+            let mut workflow = (*create_test_workflow()).clone();
+            let side = Node::new(NodeId::new(), "side".to_string());
+            let side_id = side.id.clone();
+            workflow.add_node(side).unwrap();
+            let start_id = node_id_by_name(&workflow, "start");
+            workflow
+                .add_edge(Edge::new(EdgeId::new(), start_id, side_id.clone()))
+                .unwrap();
+            let process_id = node_id_by_name(&workflow, "process");
+            workflow.nodes.get_mut(&process_id).unwrap().on_failure = Some(policy);
+
+            let instance_id = executor
+                .execute_workflow(Arc::new(workflow.clone()))
+                .await
+                .unwrap();
+            wait_for_status(&executor, &instance_id, expected).await;
+            let state = executor
+                .state_manager
+                .get_instance(&instance_id)
+                .await
+                .unwrap();
+            let state = state.read().await;
+            assert_eq!(state.node_status[&process_id], NodeStatus::Failed);
+            let end_status = state.node_status[&node_id_by_name(&workflow, "end")];
+            let side_status = state.node_status.get(&side_id).copied();
+            drop(state);
+            executor.shutdown(Duration::from_secs(1)).await.unwrap();
+            (end_status, side_status)
+        };
+
+        // Best-effort: the failure is recorded, but downstream still runs
+        let (end, side) = run(FailurePolicy::Continue, InstanceStatus::Completed).await;
+        assert_eq!(end, NodeStatus::Completed);
+        assert_eq!(side, Some(NodeStatus::Completed));
+
+        // Only the failed branch is skipped; the others finish
+        let (end, side) = run(FailurePolicy::SkipDownstream, InstanceStatus::Completed).await;
+        assert_eq!(end, NodeStatus::Skipped);
+        assert_eq!(side, Some(NodeStatus::Completed));
+
+        // Critical: the whole instance fails
+        let (end, _) = run(FailurePolicy::Abort, InstanceStatus::Failed).await;
+        assert_eq!(end, NodeStatus::Skipped);
+    }
+
+    #[tokio::test]
+    async fn test_switch_node_runs_only_selected_branch() {
+        use crate::model::{EdgeId, SwitchBranch};
+
+        let executor =
+            create_checkpointing_executor_with(Duration::ZERO, ExecutorConfig::default()).await;
+        let calls = Arc::new(std::sync::Mutex::new(Vec::new()));
+        for name in ["route", "eu", "us"] {
+            let calls = calls.clone();
+            executor
+                .register_node_handler(
+                    name,
+                    Arc::new(move |ctx| {
+                        calls.lock().unwrap().push(name);
+                        Box::pin(async move {
+                            let node_id = ctx.current_node_id.clone().unwrap();
+                            Ok(NodeResult::success(
+                                node_id,
+                                serde_json::json!({"order": {"region": "eu"}}),
+                            ))
+                        })
+                    }),
+                )
+                .await;
+        }
+        executor.start().await.unwrap();
+
+        // route switches on its output between eu and us
+        let mut workflow = WorkflowDefinition::new(WorkflowId::new(), "Switch".to_string());
+        let eu = Node::new(NodeId::new(), "eu".to_string());
+        let us = Node::new(NodeId::new(), "us".to_string());
+        let (eu_id, us_id) = (eu.id.clone(), us.id.clone());
+        let route = Node::new(NodeId::new(), "route".to_string()).with_config(
+            SwitchConfig::matching("order.region")
+                .with_branch(SwitchBranch::matching(
+                    eu_id.clone(),
+                    serde_json::json!("eu"),
+                ))
+                .with_branch(SwitchBranch::matching(
+                    us_id.clone(),
+                    serde_json::json!("us"),
+                ))
+                .to_config(),
+        );
+        let route_id = route.id.clone();
+        workflow.add_node(route).unwrap();
+        workflow.add_node(eu).unwrap();
+        workflow.add_node(us).unwrap();
+        for target in [&eu_id, &us_id] {
+            workflow
+                .add_edge(Edge::new(EdgeId::new(), route_id.clone(), target.clone()))
+                .unwrap();
+        }
+
+        let workflow = Arc::new(workflow);
+        let instance_id = executor.execute_workflow(workflow.clone()).await.unwrap();
+        wait_for_status(&executor, &instance_id, InstanceStatus::Completed).await;
+
+        assert_eq!(*calls.lock().unwrap(), vec!["route", "eu"]);
+        let state = executor
+            .state_manager
+            .get_instance(&instance_id)
+            .await
+            .unwrap();
+        let state = state.read().await;
+        assert_eq!(state.node_status[&eu_id], NodeStatus::Completed);
+        assert_eq!(state.node_status[&us_id], NodeStatus::Skipped);
+        assert_eq!(
+            state.skip_reason(&us_id),
+            Some(crate::state::SkipReason::ConditionFalse)
+        );
+        drop(state);
+
+        // The plan marks both branches as conditional
+        let plan = executor.plan(&workflow, None).unwrap();
+        assert_eq!(plan.conditional_nodes().count(), 2);
+
+        executor.shutdown(Duration::from_secs(1)).await.unwrap();
+
+        // The finished instance's switch router is released
+        assert!(executor.switch_routers.lock().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_plan_lists_order_skips_and_checks() {
+        use crate::model::EdgeId;
+        use lion_core::CapabilityId;
+
+        let executor = create_checkpointing_executor(Duration::ZERO).await;
+
+        // start -> charge (plugin call, unconditional) -> refund (conditional) -> notify
+        let capability_id = CapabilityId::new();
+        let start = Node::new(NodeId::new(), "start".to_string())
+            .with_input_schema(serde_json::json!({ "type": "object", "required": ["order"] }));
+        let charge =
+            Node::new(NodeId::new(), "charge".to_string()).with_node_type(NodeType::PluginCall {
+                plugin_id: "payments".to_string(),
+                function: "charge".to_string(),
+            });
+        let refund = Node::new(NodeId::new(), "refund".to_string());
+        let notify = Node::new(NodeId::new(), "notify".to_string());
+        let mut workflow = crate::model::WorkflowBuilder::new("Checkout")
+            .add_node(start.clone())
+            .unwrap()
+            .add_node(charge.clone())
+            .unwrap()
+            .add_node(refund.clone())
+            .unwrap()
+            .add_node(notify.clone())
+            .unwrap()
+            .connect(start.id.clone(), charge.id.clone())
+            .unwrap()
+            .add_edge(
+                Edge::new(EdgeId::new(), charge.id.clone(), refund.id.clone())
+                    .with_json_path("declined"),
+            )
+            .unwrap()
+            .connect(refund.id.clone(), notify.id.clone())
+            .unwrap()
+            .build();
+        workflow
+            .nodes
+            .get_mut(&charge.id)
+            .unwrap()
+            .required_capability = Some(capability_id);
+
+        // Input the entry node rejects is reported before anything is planned
+        let invalid = serde_json::json!({});
+        assert!(matches!(
+            executor.plan(&workflow, Some(&invalid)),
+            Err(ExecutorError::InvalidInput(_))
+        ));
+
+        let input = serde_json::json!({ "order": 7 });
+        let plan = executor.plan(&workflow, Some(&input)).unwrap();
+        let order: Vec<&str> = plan.nodes.iter().map(|node| node.name.as_str()).collect();
+        assert_eq!(order, vec!["start", "charge", "refund", "notify"]);
+        let conditional: Vec<&str> = plan
+            .conditional_nodes()
+            .map(|node| node.name.as_str())
+            .collect();
+        assert_eq!(conditional, vec!["refund", "notify"]);
+        assert_eq!(
+            plan.capability_checks,
+            vec![
+                PlannedCheck::Capability {
+                    node_id: charge.id.clone(),
+                    capability_id,
+                },
+                PlannedCheck::PluginCall {
+                    node_id: charge.id.clone(),
+                    plugin_id: "payments".to_string(),
+                    function: "charge".to_string(),
+                },
+            ]
+        );
+
+        // The plan round-trips through JSON for review, and nothing ran
+        let json = serde_json::to_string(&plan).unwrap();
+        assert_eq!(serde_json::from_str::<ExecutionPlan>(&json).unwrap(), plan);
+        assert_eq!(executor.get_active_execution_count().await, 0);
+    }
+
+    #[tokio::test]
+    async fn test_handler_registry_dispatch_and_fallback() {
+        use crate::engine::handler::FnHandler;
+
+        let executor = create_checkpointing_executor(Duration::ZERO).await;
+        executor.start().await.unwrap();
+        let workflow = create_test_workflow();
+        let tagged = |tag: &'static str| {
+            FnHandler::new(move |_| Ok(serde_json::json!({ "by": tag }))).into_handler()
+        };
+
+        // Nodes nothing would run are rejected before the execution starts
+        let registry = NodeHandlerRegistry::new().with_handler("start", tagged("start"));
+        assert_eq!(
+            registry
+                .missing_handlers(&workflow)
+                .into_iter()
+                .collect::<Vec<_>>(),
+            vec!["end", "process"]
+        );
+        executor.set_handler_registry(registry.clone()).await;
+        assert!(matches!(
+            executor.execute_workflow(workflow.clone()).await,
+            Err(ExecutorError::NoNodeHandler(missing)) if missing == "end, process"
+        ));
+        assert_eq!(executor.get_active_execution_count().await, 0);
+
+        // With a fallback, nodes without their own handler go to it
+        executor
+            .set_handler_registry(registry.with_fallback(tagged("fallback")))
+            .await;
+        let instance_id = executor.execute_workflow(workflow.clone()).await.unwrap();
+        wait_for_status(&executor, &instance_id, InstanceStatus::Completed).await;
+
+        let instance = executor
+            .state_manager
+            .get_instance(&instance_id)
+            .await
+            .unwrap();
+        let state = instance.read().await;
+        let by = |name: &str| state.node_results[&node_id_by_name(&workflow, name)]["by"].clone();
+        assert_eq!(by("start"), "start");
+        assert_eq!(by("process"), "fallback");
+        assert_eq!(by("end"), "fallback");
+        drop(state);
+
+        executor.shutdown(Duration::from_secs(1)).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_cacheable_node_reuses_output() {
+        use crate::engine::cache::NodeResultCache;
+        use crate::model::CachePolicy;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let cache = Arc::new(NodeResultCache::new(Arc::new(MemoryStorage::new())));
+        let executor = create_checkpointing_executor(Duration::ZERO)
+            .await
+            .with_node_cache(cache.clone());
+        executor.start().await.unwrap();
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = calls.clone();
+        executor
+            .register_node_handler(
+                "process",
+                crate::engine::handler::FnHandler::new(move |_| {
+                    Ok(serde_json::json!(counter.fetch_add(1, Ordering::SeqCst)))
+                })
+                .into_handler(),
+            )
+            .await;
+
+        let mut workflow = (*create_test_workflow()).clone();
+        let process_id = node_id_by_name(&workflow, "process");
+        workflow.nodes.get_mut(&process_id).unwrap().cache = Some(CachePolicy::default());
+        let workflow = Arc::new(workflow);
+
+        let run = || async {
+            let instance_id = executor.execute_workflow(workflow.clone()).await.unwrap();
+            wait_for_status(&executor, &instance_id, InstanceStatus::Completed).await;
+            let instance = executor
+                .state_manager
+                .get_instance(&instance_id)
+                .await
+                .unwrap();
+            let output = instance.read().await.node_results[&process_id].clone();
+            output
+        };
+
+        // The second run reuses the first run's output
+        assert_eq!(run().await, serde_json::json!(0));
+        assert_eq!(run().await, serde_json::json!(0));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        // Busting the cache runs the handler again
+        cache
+            .invalidate_node(&workflow.id, &process_id)
+            .await
+            .unwrap();
+        assert_eq!(run().await, serde_json::json!(1));
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+        // Re-registering the node with a new config under the same IDs
+        // misses the outputs cached for the old one
+        let mut reconfigured = (*workflow).clone();
+        reconfigured.nodes.get_mut(&process_id).unwrap().config =
+            serde_json::json!({ "mode": "fast" });
+        let instance_id = executor
+            .execute_workflow(Arc::new(reconfigured))
+            .await
+            .unwrap();
+        wait_for_status(&executor, &instance_id, InstanceStatus::Completed).await;
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+
+        executor.shutdown(Duration::from_secs(1)).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_rollback_compensates_completed_nodes_in_reverse() {
+        use crate::engine::handler::FnHandler;
+
+        let executor = create_checkpointing_executor(Duration::ZERO).await;
+        executor.start().await.unwrap();
+        let undone = Arc::new(std::sync::Mutex::new(Vec::new()));
+        executor
+            .register_node_handler(
+                "end",
+                FnHandler::new(|_| Err(ExecutorError::NodeError("out of stock".to_string())))
+                    .into_handler(),
+            )
+            .await;
+        let log = undone.clone();
+        executor
+            .register_node_handler(
+                "undo_process",
+                Arc::new(move |ctx: ExecutionContext| {
+                    let log = log.clone();
+                    Box::pin(async move {
+                        let node_id = ctx.current_node_id.clone().unwrap();
+                        log.lock().unwrap().push(node_id.clone());
+                        Ok(NodeResult::success(node_id, serde_json::json!({})))
+                    })
+                }),
+            )
+            .await;
+        executor
+            .register_node_handler(
+                "undo_start",
+                FnHandler::new(|_| Err(ExecutorError::NodeError("refund declined".to_string())))
+                    .into_handler(),
+            )
+            .await;
+
+        let mut workflow = (*create_test_workflow()).clone();
+        let start_id = node_id_by_name(&workflow, "start");
+        let process_id = node_id_by_name(&workflow, "process");
+        for (node_id, handler) in [(&start_id, "undo_start"), (&process_id, "undo_process")] {
+            workflow.nodes.get_mut(node_id).unwrap().compensation = Some(handler.to_string());
+        }
+        let workflow = Arc::new(workflow);
+
+        // Rollback is opt-in
+        let instance_id = executor.execute_workflow(workflow.clone()).await.unwrap();
+        wait_for_status(&executor, &instance_id, InstanceStatus::Failed).await;
+        assert_eq!(
+            executor.get_instance_compensations(&instance_id).await,
+            None
+        );
+
+        let options = ExecutionOptions {
+            rollback_on_failure: true,
+            ..Default::default()
+        };
+        let instance_id = executor
+            .execute_workflow_with_options(workflow, &options)
+            .await
+            .unwrap();
+        let mut compensations = None;
+        for _ in 0..100 {
+            compensations = executor.get_instance_compensations(&instance_id).await;
+            if compensations.is_some() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+
+        // Later nodes are undone first, and a failed compensation is reported
+        let compensations = compensations.expect("rollback never finished");
+        let nodes: Vec<&NodeId> = compensations.iter().map(|c| &c.node_id).collect();
+        assert_eq!(nodes, vec![&process_id, &start_id]);
+        assert!(compensations[0].succeeded());
+        assert!(compensations[1]
+            .error
+            .as_deref()
+            .unwrap()
+            .contains("refund declined"));
+        assert_eq!(*undone.lock().unwrap(), vec![process_id]);
+
+        executor.shutdown(Duration::from_secs(1)).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_silent_node_fails_on_heartbeat_timeout() {
+        use std::sync::atomic::{AtomicBool, Ordering};
+
+        let config = ExecutorConfig {
+            heartbeat_timeout: Some(Duration::from_millis(100)),
+            ..Default::default()
+        };
+        let executor = create_checkpointing_executor_with(Duration::ZERO, config).await;
+
+        // A node beating steadily may run longer than the heartbeat timeout
+        executor
+            .register_node_handler(
+                "start",
+                Arc::new(|ctx| {
+                    Box::pin(async move {
+                        for _ in 0..10 {
+                            ctx.heartbeat();
+                            tokio::time::sleep(Duration::from_millis(30)).await;
+                        }
+                        let node_id = ctx.current_node_id.clone().unwrap();
+                        Ok(NodeResult::success(node_id, serde_json::json!({})))
+                    })
+                }),
+            )
+            .await;
+
+        // A node going silent after its first beat is asked to stop
+        let stopped = Arc::new(AtomicBool::new(false));
+        let stopped_clone = stopped.clone();
+        executor
+            .register_node_handler(
+                "process",
+                Arc::new(move |ctx| {
+                    let stopped = stopped_clone.clone();
+                    Box::pin(async move {
+                        ctx.heartbeat();
+                        let watcher = ctx.clone();
+                        tokio::spawn(async move {
+                            watcher.cancelled().await;
+                            stopped.store(watcher.is_cancelled(), Ordering::SeqCst);
+                        });
+                        tokio::time::sleep(Duration::from_secs(30)).await;
+                        let node_id = ctx.current_node_id.clone().unwrap();
+                        Ok(NodeResult::success(node_id, serde_json::json!({})))
+                    })
+                }),
+            )
+            .await;
+        executor.start().await.unwrap();
+
+        let workflow = create_test_workflow();
+        let instance_id = executor.execute_workflow(workflow.clone()).await.unwrap();
+        wait_for_status(&executor, &instance_id, InstanceStatus::Failed).await;
+
+        let state = executor
+            .state_manager
+            .get_instance(&instance_id)
+            .await
+            .unwrap();
+        let state = state.read().await;
+        let (start_id, process_id) = (
+            node_id_by_name(&workflow, "start"),
+            node_id_by_name(&workflow, "process"),
+        );
+        assert_eq!(state.node_status[&start_id], NodeStatus::Completed);
+        assert_eq!(state.node_status[&process_id], NodeStatus::Failed);
+        assert_eq!(
+            state.node_results[&process_id]["reason"],
+            serde_json::json!("heartbeat_timeout")
+        );
+        drop(state);
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(stopped.load(Ordering::SeqCst));
+
+        executor.shutdown(Duration::from_secs(1)).await.unwrap();
+    }
 }
@@ -0,0 +1,388 @@
+use crate::engine::context::{ExecutionContext, NodeResult};
+use crate::engine::executor::{ExecutorError, NodeHandler};
+use crate::model::{NodeType, WorkflowDefinition};
+use async_trait::async_trait;
+use serde_json::Value;
+use std::collections::{BTreeSet, HashMap};
+use std::future::Future;
+use std::sync::Arc;
+
+/// Node handlers keyed by node type, with an optional fallback
+///
+/// A task node's name is its type. Nodes of a type with no handler go to
+/// the fallback handler if one is set.
+#[derive(Clone, Default)]
+pub struct NodeHandlerRegistry {
+    /// Handlers by node type
+    handlers: HashMap<String, NodeHandler>,
+
+    /// Handler for node types without their own
+    fallback: Option<NodeHandler>,
+}
+
+impl NodeHandlerRegistry {
+    /// Create an empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register the handler for a node type, replacing any previous one
+    pub fn register(&mut self, node_type: &str, handler: NodeHandler) {
+        self.handlers.insert(node_type.to_string(), handler);
+    }
+
+    /// Register a handler, returning the registry
+    pub fn with_handler(mut self, node_type: &str, handler: NodeHandler) -> Self {
+        self.register(node_type, handler);
+        self
+    }
+
+    /// Set the handler for node types without their own
+    pub fn set_fallback(&mut self, handler: NodeHandler) {
+        self.fallback = Some(handler);
+    }
+
+    /// Set the fallback handler, returning the registry
+    pub fn with_fallback(mut self, handler: NodeHandler) -> Self {
+        self.set_fallback(handler);
+        self
+    }
+
+    /// Get the handler for a node type, or the fallback
+    pub fn get(&self, node_type: &str) -> Option<NodeHandler> {
+        self.handlers
+            .get(node_type)
+            .or(self.fallback.as_ref())
+            .cloned()
+    }
+
+    /// Get the handler registered for a node type, ignoring the fallback
+    pub fn get_registered(&self, node_type: &str) -> Option<NodeHandler> {
+        self.handlers.get(node_type).cloned()
+    }
+
+    /// Node types of a workflow's task nodes that no handler would run
+    pub fn missing_handlers(&self, definition: &WorkflowDefinition) -> BTreeSet<String> {
+        if self.fallback.is_some() {
+            return BTreeSet::new();
+        }
+        definition
+            .nodes
+            .values()
+            .filter(|node| node.node_type == NodeType::Task)
+            .filter(|node| !self.handlers.contains_key(&node.name))
+            .map(|node| node.name.clone())
+            .collect()
+    }
+}
+
+/// Node handler taking the node's input and returning its output
+///
+/// The executor awaits `handle` on its runtime, so handlers can do I/O
+/// without holding up a worker. Input is passed as for [`FnHandler`].
+#[async_trait]
+pub trait AsyncNodeHandler: Send + Sync {
+    /// Compute the node's output; an error fails the node
+    async fn handle(&self, ctx: &ExecutionContext, input: Value) -> Result<Value, ExecutorError>;
+
+    /// Convert into a handler for `WorkflowExecutor::register_node_handler`
+    fn into_handler(self) -> NodeHandler
+    where
+        Self: Sized + 'static,
+    {
+        let handler = Arc::new(self);
+        Arc::new(move |ctx| {
+            let handler = handler.clone();
+            Box::pin(async move {
+                let (node_id, input) = node_input(&ctx)?;
+                let output = handler.handle(&ctx, input).await?;
+                Ok(NodeResult::success(node_id, output))
+            })
+        })
+    }
+}
+
+/// Adapter registering a synchronous closure as a node handler
+///
+/// The closure receives the node's input, as given by
+/// `ExecutionContext::input`, and returns its output; an error fails the
+/// node. A node without parents gets `null` as input, a node with one parent
+/// gets that parent's output, and a node with several parents gets an object
+/// of their outputs keyed by parent node id. An edge's input path passes
+/// only a field of its source's output. The closure runs
+/// on the executor's worker; closures that block belong in a
+/// [`BlockingFnHandler`].
+pub struct FnHandler<F> {
+    /// Closure computing the node output
+    f: Arc<F>,
+}
+
+impl<F> FnHandler<F>
+where
+    F: Fn(Value) -> Result<Value, ExecutorError> + Send + Sync + 'static,
+{
+    /// Wrap a closure
+    pub fn new(f: F) -> Self {
+        FnHandler { f: Arc::new(f) }
+    }
+
+    /// Convert into a handler for `WorkflowExecutor::register_node_handler`
+    pub fn into_handler(self) -> NodeHandler {
+        self.into()
+    }
+}
+
+impl<F> From<FnHandler<F>> for NodeHandler
+where
+    F: Fn(Value) -> Result<Value, ExecutorError> + Send + Sync + 'static,
+{
+    fn from(handler: FnHandler<F>) -> Self {
+        let f = handler.f;
+        Arc::new(move |ctx| {
+            let f = f.clone();
+            Box::pin(async move {
+                let (node_id, input) = node_input(&ctx)?;
+                Ok(NodeResult::success(node_id, f(input)?))
+            })
+        })
+    }
+}
+
+/// Adapter registering a blocking closure as a node handler
+///
+/// Behaves like [`FnHandler`], but the closure runs on Tokio's blocking
+/// thread pool, so slow synchronous work does not stall other nodes.
+pub struct BlockingFnHandler<F> {
+    /// Closure computing the node output
+    f: Arc<F>,
+}
+
+impl<F> BlockingFnHandler<F>
+where
+    F: Fn(Value) -> Result<Value, ExecutorError> + Send + Sync + 'static,
+{
+    /// Wrap a closure
+    pub fn new(f: F) -> Self {
+        BlockingFnHandler { f: Arc::new(f) }
+    }
+
+    /// Convert into a handler for `WorkflowExecutor::register_node_handler`
+    pub fn into_handler(self) -> NodeHandler {
+        self.into()
+    }
+}
+
+impl<F> From<BlockingFnHandler<F>> for NodeHandler
+where
+    F: Fn(Value) -> Result<Value, ExecutorError> + Send + Sync + 'static,
+{
+    fn from(handler: BlockingFnHandler<F>) -> Self {
+        let f = handler.f;
+        Arc::new(move |ctx| {
+            let f = f.clone();
+            Box::pin(async move {
+                let (node_id, input) = node_input(&ctx)?;
+                let output = tokio::task::spawn_blocking(move || f(input))
+                    .await
+                    .map_err(|e| ExecutorError::NodeError(format!("Handler panicked: {}", e)))??;
+                Ok(NodeResult::success(node_id, output))
+            })
+        })
+    }
+}
+
+/// Adapter registering an async closure as a node handler
+///
+/// Behaves like [`FnHandler`], but the closure returns a future.
+pub struct AsyncFnHandler<F> {
+    /// Closure computing the node output
+    f: Arc<F>,
+}
+
+impl<F, Fut> AsyncFnHandler<F>
+where
+    F: Fn(Value) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Result<Value, ExecutorError>> + Send + 'static,
+{
+    /// Wrap a closure
+    pub fn new(f: F) -> Self {
+        AsyncFnHandler { f: Arc::new(f) }
+    }
+
+    /// Convert into a handler for `WorkflowExecutor::register_node_handler`
+    pub fn into_handler(self) -> NodeHandler {
+        self.into()
+    }
+}
+
+impl<F, Fut> From<AsyncFnHandler<F>> for NodeHandler
+where
+    F: Fn(Value) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Result<Value, ExecutorError>> + Send + 'static,
+{
+    fn from(handler: AsyncFnHandler<F>) -> Self {
+        let f = handler.f;
+        Arc::new(move |ctx| {
+            let f = f.clone();
+            Box::pin(async move {
+                let (node_id, input) = node_input(&ctx)?;
+                Ok(NodeResult::success(node_id, f(input).await?))
+            })
+        })
+    }
+}
+
+/// Collect the current node's id and the input passed to its closure
+pub(crate) fn node_input(
+    ctx: &ExecutionContext,
+) -> Result<(crate::model::NodeId, Value), ExecutorError> {
+    let node_id = ctx
+        .current_node_id
+        .clone()
+        .ok_or_else(|| ExecutorError::Other("No current node".to_string()))?;
+
+    Ok((node_id, ctx.input()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::blocking::BlockingWorkflowEngine;
+    use crate::model::{Edge, EdgeId, Node, NodeId, WorkflowDefinition, WorkflowId};
+    use crate::state::InstanceStatus;
+    use std::collections::HashMap;
+
+    /// A workflow "produce" -> "double", returning both node ids
+    fn pipeline() -> (WorkflowDefinition, NodeId, NodeId) {
+        let mut workflow = WorkflowDefinition::new(WorkflowId::new(), "Pipeline".into());
+        let produce = Node::new(NodeId::new(), "produce".to_string());
+        let produce_id = produce.id.clone();
+        let double = Node::new(NodeId::new(), "double".to_string());
+        let double_id = double.id.clone();
+        workflow.add_node(produce).unwrap();
+        workflow.add_node(double).unwrap();
+        workflow
+            .add_edge(Edge::new(
+                EdgeId::new(),
+                produce_id.clone(),
+                double_id.clone(),
+            ))
+            .unwrap();
+        (workflow, produce_id, double_id)
+    }
+
+    #[test]
+    fn test_fn_handler_runs_closures() {
+        let (workflow, produce_id, double_id) = pipeline();
+
+        let mut handlers: HashMap<String, NodeHandler> = HashMap::new();
+        handlers.insert(
+            "produce".to_string(),
+            FnHandler::new(|input| {
+                assert!(input.is_null());
+                Ok(serde_json::json!({ "n": 21 }))
+            })
+            .into_handler(),
+        );
+        handlers.insert(
+            "double".to_string(),
+            FnHandler::new(|input: Value| {
+                let n = input["n"]
+                    .as_i64()
+                    .ok_or_else(|| ExecutorError::NodeError("missing n".to_string()))?;
+                Ok(serde_json::json!(n * 2))
+            })
+            .into(),
+        );
+
+        let engine = BlockingWorkflowEngine::new().unwrap();
+        let result = engine.run_to_completion(workflow, handlers).unwrap();
+
+        assert!(result.is_success());
+        assert_eq!(
+            result.node_results.get(&produce_id),
+            Some(&serde_json::json!({ "n": 21 }))
+        );
+        assert_eq!(
+            result.node_results.get(&double_id),
+            Some(&serde_json::json!(42))
+        );
+    }
+
+    #[test]
+    fn test_async_fn_handler_errors_fail_the_node() {
+        let (workflow, _, double_id) = pipeline();
+
+        let mut handlers: HashMap<String, NodeHandler> = HashMap::new();
+        handlers.insert(
+            "produce".to_string(),
+            AsyncFnHandler::new(|_| async { Ok(serde_json::json!({ "n": "many" })) })
+                .into_handler(),
+        );
+        handlers.insert(
+            "double".to_string(),
+            AsyncFnHandler::new(|input: Value| async move {
+                tokio::task::yield_now().await;
+                input["n"]
+                    .as_i64()
+                    .map(|n| serde_json::json!(n * 2))
+                    .ok_or_else(|| ExecutorError::NodeError("n is not a number".to_string()))
+            })
+            .into_handler(),
+        );
+
+        let engine = BlockingWorkflowEngine::new().unwrap();
+        let result = engine.run_to_completion(workflow, handlers).unwrap();
+
+        assert_eq!(result.status, InstanceStatus::Failed);
+        assert_eq!(
+            result.node_results.get(&double_id),
+            Some(&serde_json::json!({ "error": "n is not a number" }))
+        );
+    }
+
+    #[test]
+    fn test_async_trait_and_blocking_handlers() {
+        /// Reads a variable from the context and waits before answering
+        struct Lookup;
+
+        #[async_trait]
+        impl AsyncNodeHandler for Lookup {
+            async fn handle(
+                &self,
+                ctx: &ExecutionContext,
+                _input: Value,
+            ) -> Result<Value, ExecutorError> {
+                tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+                Ok(serde_json::json!({
+                    "n": 21,
+                    "node": ctx.current_node_id.as_ref().map(|id| id.to_string()),
+                }))
+            }
+        }
+
+        let (workflow, produce_id, double_id) = pipeline();
+        let mut handlers: HashMap<String, NodeHandler> = HashMap::new();
+        handlers.insert("produce".to_string(), Lookup.into_handler());
+        handlers.insert(
+            "double".to_string(),
+            BlockingFnHandler::new(|input: Value| {
+                std::thread::sleep(std::time::Duration::from_millis(5));
+                Ok(serde_json::json!(
+                    input["n"].as_i64().unwrap_or_default() * 2
+                ))
+            })
+            .into_handler(),
+        );
+
+        let engine = BlockingWorkflowEngine::new().unwrap();
+        let result = engine.run_to_completion(workflow, handlers).unwrap();
+
+        assert!(result.is_success());
+        assert_eq!(
+            result.node_results[&produce_id]["node"],
+            serde_json::json!(produce_id.to_string())
+        );
+        assert_eq!(result.node_results[&double_id], serde_json::json!(42));
+    }
+}
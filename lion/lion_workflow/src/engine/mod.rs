@@ -1,3 +1,9 @@
+pub mod audit;
+pub mod blocking;
+pub mod cache;
 pub mod context;
 pub mod executor;
+pub mod handler;
+pub mod plugin;
 pub mod scheduler;
+pub mod shared;
@@ -0,0 +1,17 @@
+use async_trait::async_trait;
+use serde_json::Value;
+
+/// Calls functions of loaded plugins on behalf of plugin call nodes
+///
+/// The executor checks that the workflow's principal may call the function
+/// before invoking it.
+#[async_trait]
+pub trait PluginInvoker: Send + Sync {
+    /// Call a plugin function with the node's input, returning its output
+    async fn call_plugin(
+        &self,
+        plugin_id: &str,
+        function: &str,
+        input: Value,
+    ) -> Result<Value, String>;
+}
@@ -0,0 +1,256 @@
+use crate::engine::context::ContextError;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+/// Values shared between the parts of an execution, keyed by execution id
+///
+/// A workflow instance and the sagas it drives use the same execution id
+/// (the workflow instance id, passed to sagas as their correlation id), so
+/// values set by a node handler are visible to saga steps and vice versa.
+#[derive(Debug, Default)]
+pub struct SharedContextStore {
+    /// Values by execution id and key
+    values: RwLock<HashMap<String, HashMap<String, serde_json::Value>>>,
+}
+
+impl SharedContextStore {
+    /// Create an empty store
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get a value of an execution
+    pub fn get<T: DeserializeOwned>(
+        &self,
+        execution_id: &str,
+        key: &str,
+    ) -> Result<Option<T>, ContextError> {
+        match self.get_json(execution_id, key) {
+            Some(value) => serde_json::from_value(value)
+                .map(Some)
+                .map_err(|e| ContextError::SerializationError(e.to_string())),
+            None => Ok(None),
+        }
+    }
+
+    /// Set a value of an execution
+    pub fn set<T: Serialize>(
+        &self,
+        execution_id: &str,
+        key: &str,
+        value: &T,
+    ) -> Result<(), ContextError> {
+        let value = serde_json::to_value(value)
+            .map_err(|e| ContextError::SerializationError(e.to_string()))?;
+        self.set_json(execution_id, key, value);
+        Ok(())
+    }
+
+    /// Get the raw JSON value of an execution
+    pub fn get_json(&self, execution_id: &str, key: &str) -> Option<serde_json::Value> {
+        self.values
+            .read()
+            .unwrap()
+            .get(execution_id)
+            .and_then(|values| values.get(key))
+            .cloned()
+    }
+
+    /// Set the raw JSON value of an execution
+    pub fn set_json(&self, execution_id: &str, key: &str, value: serde_json::Value) {
+        self.values
+            .write()
+            .unwrap()
+            .entry(execution_id.to_string())
+            .or_default()
+            .insert(key.to_string(), value);
+    }
+
+    /// Remove a value of an execution, returning it
+    pub fn remove(&self, execution_id: &str, key: &str) -> Option<serde_json::Value> {
+        self.values
+            .write()
+            .unwrap()
+            .get_mut(execution_id)
+            .and_then(|values| values.remove(key))
+    }
+
+    /// Drop every value of an execution
+    pub fn clear_execution(&self, execution_id: &str) {
+        self.values.write().unwrap().remove(execution_id);
+    }
+
+    /// Get a handle to the values of one execution
+    pub fn scope(self: &Arc<Self>, execution_id: &str) -> SharedContext {
+        SharedContext {
+            store: self.clone(),
+            execution_id: execution_id.to_string(),
+        }
+    }
+}
+
+/// Handle to the shared values of a single execution
+#[derive(Debug, Clone)]
+pub struct SharedContext {
+    /// Backing store
+    store: Arc<SharedContextStore>,
+
+    /// Execution the values belong to
+    execution_id: String,
+}
+
+impl SharedContext {
+    /// Id of the execution this handle is scoped to
+    pub fn execution_id(&self) -> &str {
+        &self.execution_id
+    }
+
+    /// Get a value
+    pub fn get<T: DeserializeOwned>(&self, key: &str) -> Result<Option<T>, ContextError> {
+        self.store.get(&self.execution_id, key)
+    }
+
+    /// Set a value
+    pub fn set<T: Serialize>(&self, key: &str, value: &T) -> Result<(), ContextError> {
+        self.store.set(&self.execution_id, key, value)
+    }
+
+    /// Get the raw JSON value
+    pub fn get_json(&self, key: &str) -> Option<serde_json::Value> {
+        self.store.get_json(&self.execution_id, key)
+    }
+
+    /// Set the raw JSON value
+    pub fn set_json(&self, key: &str, value: serde_json::Value) {
+        self.store.set_json(&self.execution_id, key, value)
+    }
+
+    /// Remove a value, returning it
+    pub fn remove(&self, key: &str) -> Option<serde_json::Value> {
+        self.store.remove(&self.execution_id, key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::context::NodeResult;
+    use crate::engine::executor::{ExecutorConfig, WorkflowExecutor};
+    use crate::engine::scheduler::{SchedulerConfig, WorkflowScheduler};
+    use crate::model::{Node, NodeId, WorkflowDefinition, WorkflowId};
+    use crate::patterns::saga::{
+        SagaDefinition, SagaOrchestrator, SagaOrchestratorConfig, SagaStepDefinition, StepStatus,
+    };
+    use crate::state::{InstanceStatus, MemoryStorage, StateMachineManager};
+    use std::time::Duration;
+
+    #[test]
+    fn test_values_are_scoped_by_execution() {
+        let store = Arc::new(SharedContextStore::new());
+        let first = store.scope("exec-1");
+        let second = store.scope("exec-2");
+
+        first.set("count", &3u32).unwrap();
+        assert_eq!(first.get::<u32>("count").unwrap(), Some(3));
+        assert_eq!(second.get::<u32>("count").unwrap(), None);
+
+        // Typed reads fail on a mismatched type
+        assert!(first.get::<String>("count").is_err());
+
+        store.clear_execution("exec-1");
+        assert!(first.get_json("count").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_workflow_node_value_is_visible_to_saga_step() {
+        let store = Arc::new(SharedContextStore::new());
+
+        // A workflow whose only node records an order id
+        let executor = WorkflowExecutor::new(
+            Arc::new(WorkflowScheduler::new(SchedulerConfig::default())),
+            Arc::new(StateMachineManager::<MemoryStorage>::new()),
+            ExecutorConfig {
+                worker_threads: 1,
+                ..Default::default()
+            },
+        )
+        .with_shared_context(store.clone());
+        executor
+            .register_node_handler(
+                "place_order",
+                Arc::new(|ctx| {
+                    Box::pin(async move {
+                        let shared = ctx.shared_context().unwrap();
+                        shared.set("order_id", &"order-42").unwrap();
+                        let node_id = ctx.current_node_id.clone().unwrap();
+                        Ok(NodeResult::success(node_id, serde_json::json!({})))
+                    })
+                }),
+            )
+            .await;
+        executor.start().await.unwrap();
+
+        let mut workflow = WorkflowDefinition::new(WorkflowId::new(), "Order".to_string());
+        workflow
+            .add_node(Node::new(NodeId::new(), "place_order".to_string()))
+            .unwrap();
+        let instance_id = executor.execute_workflow(Arc::new(workflow)).await.unwrap();
+        for _ in 0..100 {
+            if executor.get_instance_status(&instance_id).await == Some(InstanceStatus::Completed) {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        assert_eq!(
+            executor.get_instance_status(&instance_id).await,
+            Some(InstanceStatus::Completed)
+        );
+
+        // A saga correlated with the workflow instance reads it back
+        let orchestrator =
+            SagaOrchestrator::new(SagaOrchestratorConfig::default()).with_shared_context(store);
+        orchestrator
+            .register_step_handler(
+                "billing",
+                "charge",
+                Arc::new(|step| {
+                    let order_id = step
+                        .shared_context
+                        .as_ref()
+                        .and_then(|shared| shared.get::<String>("order_id").ok().flatten());
+                    Box::new(Box::pin(async move {
+                        Ok(serde_json::json!({ "charged": order_id }))
+                    }))
+                }),
+            )
+            .await;
+
+        let mut saga_def = SagaDefinition::new("billing-saga", "Billing");
+        saga_def
+            .add_step(SagaStepDefinition::new(
+                "charge",
+                "Charge",
+                "billing",
+                "charge",
+                serde_json::json!({}),
+            ))
+            .unwrap();
+        let saga_id = orchestrator
+            .create_correlated_saga(saga_def, &instance_id)
+            .await
+            .unwrap();
+        orchestrator.start_saga(&saga_id).await.unwrap();
+
+        let saga = orchestrator.get_saga(&saga_id).await.unwrap();
+        let saga = saga.read().await;
+        assert_eq!(saga.steps["charge"].status, StepStatus::Completed);
+        assert_eq!(
+            saga.steps["charge"].result,
+            Some(serde_json::json!({ "charged": "order-42" }))
+        );
+
+        executor.shutdown(Duration::from_secs(1)).await.unwrap();
+    }
+}
@@ -16,7 +16,7 @@
 //! # Getting Started
 //!
 //! ```rust,no_run
-//! use lion_workflow::{WorkflowDefinition, Node, Edge, NodeId, WorkflowBuilder, EdgeId};
+//! use lion_workflow::WorkflowBuilder;
 //! use lion_workflow::{WorkflowExecutor, ExecutorConfig};
 //! use lion_workflow::engine::executor::NodeHandler;
 //! use lion_workflow::state::{StateMachineManager, CheckpointManager, MemoryStorage};
@@ -24,20 +24,12 @@
 //! use std::sync::Arc;
 //!
 //! // Define a simple workflow
-//! let mut builder = WorkflowBuilder::new("Example Workflow");
-//! let node1 = Node::new(NodeId::new(), "Start".to_string());
-//! let node1_id = node1.id.clone(); // Clone ID before moving the node
-//! let node2 = Node::new(NodeId::new(), "Process".to_string());
-//! let node2_id = node2.id.clone(); // Clone ID before moving the node
-//! let node3 = Node::new(NodeId::new(), "End".to_string());
-//! let node3_id = node3.id.clone(); // Clone ID before moving the node
-//!
-//! let workflow = builder
-//!     .add_node(node1).unwrap()
-//!     .add_node(node2).unwrap()
-//!     .add_node(node3).unwrap()
-//!     .add_edge(Edge::new(EdgeId::new(), node1_id.clone(), node2_id.clone())).unwrap()
-//!     .add_edge(Edge::new(EdgeId::new(), node2_id.clone(), node3_id.clone())).unwrap()
+//! let workflow = WorkflowBuilder::new("Example Workflow")
+//!     .node("Start").unwrap()
+//!     .node("Process").unwrap()
+//!     .node("End").unwrap()
+//!     .add_edge_by_name("Start", "Process").unwrap()
+//!     .add_edge_by_name("Process", "End").unwrap()
 //!     .build();
 //!
 //! // Create execution components
@@ -73,18 +65,24 @@ pub mod utils;
 
 // Re-export important types
 pub use engine::{
-    context::ExecutionContext, context::NodeResult, executor::ExecutorConfig,
-    executor::WorkflowExecutor, scheduler::SchedulerConfig, scheduler::SchedulingPolicy,
-    scheduler::TaskStatus,
+    audit::ExecutionAuditLog, blocking::BlockingWorkflowEngine, blocking::ExecutionResult,
+    cache::NodeResultCache, context::ExecutionContext, context::NodeResult,
+    executor::AdmissionPolicy, executor::ExecutionPlan, executor::ExecutorConfig,
+    executor::PlannedCheck, executor::PlannedNode, executor::ResourceEstimate,
+    executor::WorkflowExecutor, handler::AsyncFnHandler, handler::AsyncNodeHandler,
+    handler::BlockingFnHandler, handler::FnHandler, handler::NodeHandlerRegistry,
+    plugin::PluginInvoker, scheduler::SchedulerConfig, scheduler::SchedulingPolicy,
+    scheduler::TaskStatus, shared::SharedContext, shared::SharedContextStore,
 };
 pub use model::{
-    Edge, EdgeId, Node, NodeId, NodeStatus, WorkflowBuilder, WorkflowDefinition, WorkflowError,
-    WorkflowId,
+    DotOptions, Edge, EdgeId, FailurePolicy, IdGenerator, Node, NodeId, NodeStatus, NodeType,
+    SequentialIdGenerator, WorkflowBuilder, WorkflowDefinition, WorkflowError, WorkflowId,
 };
 pub use patterns::event::{Event, EventBroker};
 pub use state::{
-    CheckpointManager, FileStorage, MemoryStorage, StateMachineManager, StorageBackend,
-    WorkflowState,
+    CheckpointManager, ExecutionResourceUsage, FailureReason, FileStorage, InstanceStatus,
+    MemoryStorage, NodeStatusEvent, NodeTimelineEntry, QuotaKind, SkipReason, StateMachineManager,
+    StorageBackend, WorkflowState,
 };
 
 /// Error types from across the workflow engine
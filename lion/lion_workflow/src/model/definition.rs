@@ -1,11 +1,16 @@
 use crate::model::edge::{Edge, EdgeId};
-use crate::model::node::{Node, NodeId, NodeStatus};
+use crate::model::id_gen::{next_id, RandomIdGenerator, SequentialIdGenerator, SharedIdGenerator};
+use crate::model::node::{FailurePolicy, Node, NodeId, NodeStatus};
 use crate::utils::serialization::{deserialize_id_map, serialize_id_map};
 use lion_core::error::Error as CoreError;
 use lion_core::id::Id;
+use lion_core::types::workflow::{ErrorPolicy, Workflow as CoreWorkflow};
 use lion_core::CapabilityId;
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::Path;
+use std::sync::Arc;
 use thiserror::Error;
 
 /// Unique identifier for workflows
@@ -29,6 +34,15 @@ pub enum WorkflowError {
     #[error("Duplicate edge ID: {0}")]
     DuplicateEdge(EdgeId),
 
+    #[error("No node named '{0}'")]
+    NodeNameNotFound(String),
+
+    #[error("More than one node named '{0}'")]
+    DuplicateNodeName(String),
+
+    #[error("No node to chain from; add a start node first")]
+    NoPreviousNode,
+
     #[error("Invalid edge: source node {0} not found")]
     InvalidEdgeSource(NodeId),
 
@@ -44,6 +58,21 @@ pub enum WorkflowError {
     #[error("Workflow validation error: {0}")]
     ValidationError(String),
 
+    #[error("I/O error: {0}")]
+    IoError(String),
+
+    #[error("Unsupported workflow file format: {0}")]
+    UnsupportedFormat(String),
+
+    /// A workflow file could not be parsed; `line` and `column` are 1-based, or 0 if unknown
+    #[error("Failed to parse {path}: {message}")]
+    ParseError {
+        path: String,
+        line: usize,
+        column: usize,
+        message: String,
+    },
+
     #[error("Core error: {0}")]
     CoreError(#[from] CoreError),
 }
@@ -85,9 +114,11 @@ pub struct WorkflowDefinition {
     pub name: String,
 
     /// Optional description
+    #[serde(default)]
     pub description: Option<String>,
 
     /// Version of this workflow
+    #[serde(default)]
     pub version: Version,
 
     /// Nodes in this workflow (adjacency list representation)
@@ -99,25 +130,49 @@ pub struct WorkflowDefinition {
 
     /// Edges in this workflow
     #[serde(
+        default,
         serialize_with = "serialize_id_map",
         deserialize_with = "deserialize_id_map"
     )]
     pub edges: HashMap<EdgeId, Edge>,
 
     /// Map of node IDs with no incoming edges (start nodes)
+    #[serde(default)]
     pub start_nodes: HashSet<NodeId>,
 
     /// Map of node IDs with no outgoing edges (end nodes)
+    #[serde(default)]
     pub end_nodes: HashSet<NodeId>,
 
     /// Creation timestamp
+    #[serde(default = "chrono::Utc::now")]
     pub created_at: chrono::DateTime<chrono::Utc>,
 
     /// Last updated timestamp
+    #[serde(default = "chrono::Utc::now")]
     pub updated_at: chrono::DateTime<chrono::Utc>,
 
     /// Capability required to execute this workflow
+    #[serde(default)]
     pub required_capability: Option<CapabilityId>,
+
+    /// Error policy of nodes that do not set their own
+    #[serde(default = "default_error_policy")]
+    pub default_error_policy: ErrorPolicy,
+
+    /// Failure policy of nodes that do not set their own
+    #[serde(default)]
+    pub default_failure_policy: FailurePolicy,
+
+    /// Name of the workflow engine running this workflow, if not the
+    /// built-in executor
+    #[serde(default)]
+    pub engine: Option<String>,
+}
+
+/// Error policy of workflows that do not set one: fail on the first error
+fn default_error_policy() -> ErrorPolicy {
+    ErrorPolicy::Fail
 }
 
 // Implement Hash for WorkflowDefinition to only hash the ID field
@@ -144,6 +199,9 @@ impl WorkflowDefinition {
             created_at: now,
             updated_at: now,
             required_capability: None,
+            default_error_policy: default_error_policy(),
+            default_failure_policy: FailurePolicy::default(),
+            engine: None,
         }
     }
 
@@ -281,6 +339,22 @@ impl WorkflowDefinition {
         self.nodes.get(node_id)
     }
 
+    /// Get the error policy of a node: its own, or else the workflow default
+    pub fn error_policy_for(&self, node_id: &NodeId) -> ErrorPolicy {
+        self.nodes
+            .get(node_id)
+            .and_then(|node| node.error_policy.clone())
+            .unwrap_or_else(|| self.default_error_policy.clone())
+    }
+
+    /// Get the failure policy of a node: its own, or else the workflow default
+    pub fn failure_policy_for(&self, node_id: &NodeId) -> FailurePolicy {
+        self.nodes
+            .get(node_id)
+            .and_then(|node| node.on_failure)
+            .unwrap_or(self.default_failure_policy)
+    }
+
     /// Get a mutable reference to a node by its ID
     pub fn get_node_mut(&mut self, node_id: &NodeId) -> Option<&mut Node> {
         self.nodes.get_mut(node_id)
@@ -479,6 +553,91 @@ impl WorkflowDefinition {
         }
     }
 
+    /// Group the workflow nodes into topological levels
+    ///
+    /// A node's level is the length of the longest dependency chain leading
+    /// to it, so start nodes are at level 0 and every node appears in a later
+    /// level than all of its dependencies. Nodes within a level can run in
+    /// parallel.
+    pub fn get_topological_levels(&self) -> Result<Vec<Vec<NodeId>>, WorkflowError> {
+        let order = self.get_topological_order()?;
+
+        let mut node_level: HashMap<NodeId, usize> = HashMap::new();
+        let mut levels: Vec<Vec<NodeId>> = Vec::new();
+        for node_id in order {
+            let level = self
+                .get_incoming_edges(&node_id)?
+                .iter()
+                .filter_map(|edge| node_level.get(&edge.source))
+                .map(|level| level + 1)
+                .max()
+                .unwrap_or(0);
+
+            if levels.len() <= level {
+                levels.resize_with(level + 1, Vec::new);
+            }
+            levels[level].push(node_id.clone());
+            node_level.insert(node_id, level);
+        }
+
+        Ok(levels)
+    }
+
+    /// Find the chain of nodes that determines the minimum total execution time
+    ///
+    /// Each node weighs its duration in `durations`, or nothing if it is missing.
+    /// With no durations at all every node weighs the same, so the longest chain
+    /// by edge count is returned. Returns an empty path if the graph has a cycle.
+    pub fn critical_path(&self, durations: &HashMap<NodeId, std::time::Duration>) -> Vec<NodeId> {
+        let order = match self.get_topological_order() {
+            Ok(order) => order,
+            Err(_) => return Vec::new(),
+        };
+        let weight = |node_id: &NodeId| {
+            if durations.is_empty() {
+                std::time::Duration::from_secs(1)
+            } else {
+                durations.get(node_id).copied().unwrap_or_default()
+            }
+        };
+
+        // Longest finish time of a chain ending at each node, and its predecessor on that chain
+        let mut finish: HashMap<&NodeId, std::time::Duration> = HashMap::new();
+        let mut previous: HashMap<&NodeId, &NodeId> = HashMap::new();
+        for node_id in &order {
+            let best = self
+                .edges
+                .values()
+                .filter(|edge| &edge.target == node_id)
+                .filter_map(|edge| finish.get(&edge.source).map(|time| (*time, &edge.source)))
+                .max_by(|a, b| a.0.cmp(&b.0).then_with(|| b.1.uuid().cmp(&a.1.uuid())));
+            let start = match best {
+                Some((time, source)) => {
+                    previous.insert(node_id, source);
+                    time
+                }
+                None => std::time::Duration::ZERO,
+            };
+            finish.insert(node_id, start + weight(node_id));
+        }
+
+        // Walk back from the node that finishes last
+        let mut current = match finish
+            .iter()
+            .max_by(|a, b| a.1.cmp(b.1).then_with(|| b.0.uuid().cmp(&a.0.uuid())))
+        {
+            Some((node_id, _)) => *node_id,
+            None => return Vec::new(),
+        };
+        let mut path = vec![current.clone()];
+        while let Some(source) = previous.get(current) {
+            path.push((*source).clone());
+            current = source;
+        }
+        path.reverse();
+        path
+    }
+
     /// Validate that all edges respect capability boundaries
     fn validate_edge_capabilities(&self, edge: &Edge) -> Result<(), WorkflowError> {
         // If no capabilities are involved, no validation needed
@@ -515,6 +674,24 @@ impl WorkflowDefinition {
         self
     }
 
+    /// Set the error policy of nodes that do not set their own
+    pub fn with_default_error_policy(mut self, error_policy: ErrorPolicy) -> Self {
+        self.default_error_policy = error_policy;
+        self
+    }
+
+    /// Set the failure policy of nodes that do not set their own
+    pub fn with_default_failure_policy(mut self, policy: FailurePolicy) -> Self {
+        self.default_failure_policy = policy;
+        self
+    }
+
+    /// Set the name of the workflow engine running this workflow
+    pub fn with_engine(mut self, engine: &str) -> Self {
+        self.engine = Some(engine.to_string());
+        self
+    }
+
     /// Set the description for this workflow
     pub fn with_description(mut self, description: &str) -> Self {
         self.description = Some(description.to_string());
@@ -538,6 +715,78 @@ impl WorkflowDefinition {
         }
     }
 
+    /// Compute a stable fingerprint of this workflow's structure
+    ///
+    /// The fingerprint is a SHA-256 hex digest over the normalized topology:
+    /// the serialized nodes, the edges between them and the workflow-level
+    /// settings. Node and edge IDs, the workflow ID, name, description,
+    /// version and timestamps do not contribute, so two definitions built
+    /// the same way produce the same fingerprint, while any other change
+    /// produces a new one.
+    pub fn fingerprint(&self) -> String {
+        use sha2::{Digest, Sha256};
+
+        let mut nodes: Vec<String> = self
+            .nodes
+            .values()
+            .map(|node| node.content().to_string())
+            .collect();
+        nodes.sort();
+
+        // Describe each edge by the content of its endpoints
+        let mut edges: Vec<String> = self
+            .edges
+            .values()
+            .map(|edge| {
+                let mut content = serde_json::to_value(edge).unwrap_or_default();
+                if let Some(fields) = content.as_object_mut() {
+                    fields.remove("id");
+                    for (endpoint, id) in [("source", &edge.source), ("target", &edge.target)] {
+                        let node = self.nodes.get(id).map(Node::content);
+                        fields.insert(endpoint.to_string(), node.unwrap_or_default());
+                    }
+                }
+                content.to_string()
+            })
+            .collect();
+        edges.sort();
+
+        // Workflow-level settings, without identity, metadata or topology
+        let mut settings = serde_json::to_value(self).unwrap_or_default();
+        if let Some(fields) = settings.as_object_mut() {
+            for field in [
+                "id",
+                "name",
+                "description",
+                "version",
+                "nodes",
+                "edges",
+                "start_nodes",
+                "end_nodes",
+                "created_at",
+                "updated_at",
+            ] {
+                fields.remove(field);
+            }
+        }
+
+        let mut hasher = Sha256::new();
+        hasher.update(b"workflow:");
+        hasher.update(settings.to_string().as_bytes());
+        hasher.update(b"\n");
+        for node in &nodes {
+            hasher.update(b"node:");
+            hasher.update(node.as_bytes());
+            hasher.update(b"\n");
+        }
+        for edge in &edges {
+            hasher.update(b"edge:");
+            hasher.update(edge.as_bytes());
+            hasher.update(b"\n");
+        }
+        format!("{:x}", hasher.finalize())
+    }
+
     /// Serialize this workflow to JSON
     pub fn to_json(&self) -> Result<String, WorkflowError> {
         serde_json::to_string(self).map_err(|e| WorkflowError::SerializationError(e.to_string()))
@@ -547,22 +796,312 @@ impl WorkflowDefinition {
     pub fn from_json(json: &str) -> Result<Self, WorkflowError> {
         serde_json::from_str(json).map_err(|e| WorkflowError::SerializationError(e.to_string()))
     }
+
+    /// Load and validate a workflow from a JSON or YAML file
+    ///
+    /// The format is picked from the file extension (`.json`, `.yaml` or `.yml`). Besides the
+    /// native layout, the simpler `lion_core` workflow layout (a `nodes` list where each node
+    /// names its `dependencies`) is accepted and converted.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, WorkflowError> {
+        let path = path.as_ref();
+        let format = FileFormat::from_path(path)?;
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| WorkflowError::IoError(format!("{}: {}", path.display(), e)))?;
+
+        let mut definition = if format.is_core_shape(path, &text)? {
+            let workflow: CoreWorkflow = format.parse(path, &text)?;
+            Self::from_core_workflow(&workflow)?
+        } else {
+            format.parse(path, &text)?
+        };
+
+        definition.rebuild_indexes();
+        definition.validate()?;
+        Ok(definition)
+    }
+
+    /// Convert a `lion_core` workflow, turning each node dependency into an edge
+    ///
+    /// Node IDs are preserved; the core node type and error policy end up in the node config,
+    /// and the error policy also becomes the node's own.
+    pub fn from_core_workflow(workflow: &CoreWorkflow) -> Result<Self, WorkflowError> {
+        workflow
+            .validate()
+            .map_err(WorkflowError::ValidationError)?;
+
+        let mut definition = WorkflowDefinition::new(
+            WorkflowId::from_uuid(workflow.id.uuid()),
+            workflow.name.clone(),
+        );
+        if !workflow.description.is_empty() {
+            definition.description = Some(workflow.description.clone());
+        }
+
+        for core_node in &workflow.nodes {
+            let config = serde_json::json!({
+                "node_type": core_node.node_type,
+                "error_policy": core_node.error_policy,
+            });
+            let node = Node::new(
+                NodeId::from_uuid(core_node.id.uuid()),
+                core_node.name.clone(),
+            )
+            .with_config(config)
+            .with_error_policy(core_node.error_policy.clone());
+            definition.add_node(node).map_err(|e| {
+                WorkflowError::ValidationError(format!("node '{}': {}", core_node.name, e))
+            })?;
+        }
+
+        for core_node in &workflow.nodes {
+            for dependency in &core_node.dependencies {
+                let edge = Edge::new(
+                    EdgeId::new(),
+                    NodeId::from_uuid(dependency.uuid()),
+                    NodeId::from_uuid(core_node.id.uuid()),
+                );
+                definition.add_edge(edge).map_err(|e| {
+                    WorkflowError::ValidationError(format!(
+                        "dependency of node '{}' on {}: {}",
+                        core_node.name, dependency, e
+                    ))
+                })?;
+            }
+        }
+
+        Ok(definition)
+    }
+
+    /// Check the structural integrity of this workflow
+    ///
+    /// Errors name the offending node or edge so hand-written definitions are easy to fix.
+    pub fn validate(&self) -> Result<(), WorkflowError> {
+        if self.nodes.is_empty() {
+            return Err(WorkflowError::ValidationError(
+                "workflow has no nodes".to_string(),
+            ));
+        }
+
+        for (node_id, node) in &self.nodes {
+            if *node_id != node.id {
+                return Err(WorkflowError::ValidationError(format!(
+                    "node '{}' ({}) is stored under key {}",
+                    node.name, node.id, node_id
+                )));
+            }
+        }
+
+        for (edge_id, edge) in &self.edges {
+            if *edge_id != edge.id {
+                return Err(WorkflowError::ValidationError(format!(
+                    "edge {} is stored under key {}",
+                    edge.id, edge_id
+                )));
+            }
+            if !self.nodes.contains_key(&edge.source) {
+                return Err(WorkflowError::ValidationError(format!(
+                    "edge {} references unknown source node {}",
+                    edge.id, edge.source
+                )));
+            }
+            if !self.nodes.contains_key(&edge.target) {
+                return Err(WorkflowError::ValidationError(format!(
+                    "edge {} references unknown target node {}",
+                    edge.id, edge.target
+                )));
+            }
+            self.validate_edge_capabilities(edge)
+                .map_err(|e| WorkflowError::ValidationError(format!("edge {}: {}", edge.id, e)))?;
+        }
+
+        if self.has_cycle() {
+            // Whatever Kahn's algorithm cannot order is on, or downstream of, a cycle
+            let mut in_degree: HashMap<&NodeId, usize> =
+                self.nodes.keys().map(|id| (id, 0)).collect();
+            for edge in self.edges.values() {
+                *in_degree.entry(&edge.target).or_default() += 1;
+            }
+            let mut queue: VecDeque<&NodeId> = in_degree
+                .iter()
+                .filter(|(_, degree)| **degree == 0)
+                .map(|(id, _)| *id)
+                .collect();
+            while let Some(node_id) = queue.pop_front() {
+                in_degree.remove(node_id);
+                for edge in self.edges.values().filter(|e| &e.source == node_id) {
+                    if let Some(degree) = in_degree.get_mut(&edge.target) {
+                        *degree -= 1;
+                        if *degree == 0 {
+                            queue.push_back(&edge.target);
+                        }
+                    }
+                }
+            }
+            let mut names: Vec<&str> = in_degree
+                .keys()
+                .filter_map(|id| self.nodes.get(*id).map(|n| n.name.as_str()))
+                .collect();
+            names.sort_unstable();
+            return Err(WorkflowError::ValidationError(format!(
+                "cycle detected involving nodes: {}",
+                names.join(", ")
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Recompute the adjacency sets, in-degrees and start/end nodes from the edge map
+    fn rebuild_indexes(&mut self) {
+        self.start_nodes = self.nodes.keys().cloned().collect();
+        self.end_nodes = self.nodes.keys().cloned().collect();
+        for node in self.nodes.values_mut() {
+            node.outgoing_edges.clear();
+            node.incoming_edges.clear();
+            node.in_degree = 0;
+        }
+
+        for edge in self.edges.values() {
+            if let Some(source) = self.nodes.get_mut(&edge.source) {
+                source.add_outgoing_edge(edge.id.clone());
+                self.end_nodes.remove(&edge.source);
+            }
+            if let Some(target) = self.nodes.get_mut(&edge.target) {
+                target.add_incoming_edge(edge.id.clone());
+                self.start_nodes.remove(&edge.target);
+            }
+        }
+    }
+}
+
+/// On-disk formats understood by `WorkflowDefinition::from_file`
+#[derive(Debug, Clone, Copy)]
+enum FileFormat {
+    Json,
+    Yaml,
+}
+
+impl FileFormat {
+    fn from_path(path: &Path) -> Result<Self, WorkflowError> {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("json") => Ok(FileFormat::Json),
+            Some(ext) if ext.eq_ignore_ascii_case("yaml") || ext.eq_ignore_ascii_case("yml") => {
+                Ok(FileFormat::Yaml)
+            }
+            _ => Err(WorkflowError::UnsupportedFormat(format!(
+                "{} (expected .json, .yaml or .yml)",
+                path.display()
+            ))),
+        }
+    }
+
+    /// The `lion_core` layout keeps its nodes in a list rather than a map
+    fn is_core_shape(self, path: &Path, text: &str) -> Result<bool, WorkflowError> {
+        Ok(match self {
+            FileFormat::Json => self
+                .parse::<serde_json::Value>(path, text)?
+                .get("nodes")
+                .is_some_and(|nodes| nodes.is_array()),
+            FileFormat::Yaml => self
+                .parse::<serde_yaml::Value>(path, text)?
+                .get("nodes")
+                .is_some_and(|nodes| nodes.is_sequence()),
+        })
+    }
+
+    fn parse<T: DeserializeOwned>(self, path: &Path, text: &str) -> Result<T, WorkflowError> {
+        match self {
+            FileFormat::Json => serde_json::from_str(text).map_err(|e| WorkflowError::ParseError {
+                path: path.display().to_string(),
+                line: e.line(),
+                column: e.column(),
+                message: e.to_string(),
+            }),
+            FileFormat::Yaml => serde_yaml::from_str(text).map_err(|e| {
+                let (line, column) = e
+                    .location()
+                    .map_or((0, 0), |loc| (loc.line(), loc.column()));
+                WorkflowError::ParseError {
+                    path: path.display().to_string(),
+                    line,
+                    column,
+                    message: e.to_string(),
+                }
+            }),
+        }
+    }
 }
 
 /// Builder for workflow definitions
 pub struct WorkflowBuilder {
     definition: WorkflowDefinition,
+
+    /// Source of the workflow, node and edge ids the builder creates
+    id_generator: SharedIdGenerator,
+
+    /// Whether named nodes and connections get ids derived from their names
+    deterministic_ids: bool,
+
+    /// Most recently added node, which `then` chains from
+    last_node: Option<NodeId>,
 }
 
 impl WorkflowBuilder {
-    /// Create a new workflow builder
+    /// Create a new workflow builder using random UUIDs
     pub fn new(name: &str) -> Self {
-        let id = WorkflowId::new();
+        Self::with_id_generator(name, Arc::new(RandomIdGenerator))
+    }
+
+    /// Create a new workflow builder taking its ids from a generator
+    pub fn with_id_generator(name: &str, id_generator: SharedIdGenerator) -> Self {
+        let id = next_id(id_generator.as_ref());
+        WorkflowBuilder {
+            definition: WorkflowDefinition::new(id, name.to_string()),
+            id_generator,
+            deterministic_ids: false,
+            last_node: None,
+        }
+    }
+
+    /// Create a new workflow builder deriving its ids from names
+    ///
+    /// The workflow id comes from the workflow name, nodes made with
+    /// `new_node` take `NodeId::from_name(workflow_name, node_name)`, and
+    /// edges made with `connect` take an id derived from their endpoints, so
+    /// building the same workflow twice gives identical ids. Node names must
+    /// then be unique within the workflow. Ids generated without a name
+    /// count up from an epoch derived from the workflow name.
+    pub fn with_deterministic_ids(name: &str) -> Self {
+        let id = WorkflowId::from_name("workflows", name);
+        let (epoch, _) = id.uuid().as_u64_pair();
         WorkflowBuilder {
             definition: WorkflowDefinition::new(id, name.to_string()),
+            id_generator: Arc::new(SequentialIdGenerator::with_epoch(epoch)),
+            deterministic_ids: true,
+            last_node: None,
         }
     }
 
+    /// Generate a node id
+    pub fn next_node_id(&self) -> NodeId {
+        next_id(self.id_generator.as_ref())
+    }
+
+    /// Generate an edge id
+    pub fn next_edge_id(&self) -> EdgeId {
+        next_id(self.id_generator.as_ref())
+    }
+
+    /// Create a node with a generated id, to be added with `add_node`
+    pub fn new_node(&self, name: &str) -> Node {
+        let id = if self.deterministic_ids {
+            NodeId::from_name(&self.definition.name, name)
+        } else {
+            self.next_node_id()
+        };
+        Node::new(id, name.to_string())
+    }
+
     /// Set the description for this workflow
     pub fn description(mut self, description: &str) -> Self {
         self.definition.description = Some(description.to_string());
@@ -585,24 +1124,165 @@ impl WorkflowBuilder {
         self
     }
 
+    /// Set the error policy of nodes that do not set their own
+    pub fn default_error_policy(mut self, error_policy: ErrorPolicy) -> Self {
+        self.definition.default_error_policy = error_policy;
+        self
+    }
+
+    /// Set the failure policy of nodes that do not set their own
+    pub fn default_failure_policy(mut self, policy: FailurePolicy) -> Self {
+        self.definition.default_failure_policy = policy;
+        self
+    }
+
+    /// Set the name of the workflow engine running this workflow
+    pub fn engine(mut self, engine: &str) -> Self {
+        self.definition.engine = Some(engine.to_string());
+        self
+    }
+
     /// Add a node to this workflow
     pub fn add_node(mut self, node: Node) -> Result<Self, WorkflowError> {
+        let node_id = node.id.clone();
         self.definition.add_node(node)?;
+        self.last_node = Some(node_id);
         Ok(self)
     }
 
+    /// Add the first node of a chain built with `then`
+    pub fn start(self, node: Node) -> Result<Self, WorkflowError> {
+        self.add_node(node)
+    }
+
+    /// Add a node with an edge from the most recently added node
+    ///
+    /// Fails if no node has been added yet.
+    pub fn then(self, node: Node) -> Result<Self, WorkflowError> {
+        let previous = self
+            .last_node
+            .clone()
+            .ok_or(WorkflowError::NoPreviousNode)?;
+        let node_id = node.id.clone();
+        self.add_node(node)?.connect(previous, node_id)
+    }
+
+    /// Add nodes that each depend on the most recently added node
+    ///
+    /// The returned group's `join` adds the node they all lead into. Fails
+    /// if no node has been added yet.
+    pub fn parallel(mut self, nodes: Vec<Node>) -> Result<ParallelGroup, WorkflowError> {
+        let fork = self
+            .last_node
+            .clone()
+            .ok_or(WorkflowError::NoPreviousNode)?;
+        let mut branches = Vec::with_capacity(nodes.len());
+        for node in nodes {
+            let node_id = node.id.clone();
+            self = self
+                .add_node(node)?
+                .connect(fork.clone(), node_id.clone())?;
+            branches.push(node_id);
+        }
+        Ok(ParallelGroup {
+            builder: self,
+            fork,
+            branches,
+        })
+    }
+
     /// Add an edge to this workflow
     pub fn add_edge(mut self, edge: Edge) -> Result<Self, WorkflowError> {
         self.definition.add_edge(edge)?;
         Ok(self)
     }
 
+    /// Add a node with a generated id, to be referred to by its name
+    ///
+    /// Fails if a node with the same name was already added.
+    pub fn node(self, name: &str) -> Result<Self, WorkflowError> {
+        if self.definition.nodes.values().any(|node| node.name == name) {
+            return Err(WorkflowError::DuplicateNodeName(name.to_string()));
+        }
+        let node = self.new_node(name);
+        self.add_node(node)
+    }
+
+    /// Get the id of the node with a name
+    ///
+    /// Fails if no node, or more than one, has the name.
+    pub fn node_id(&self, name: &str) -> Result<NodeId, WorkflowError> {
+        let mut matches = self
+            .definition
+            .nodes
+            .values()
+            .filter(|node| node.name == name);
+        match (matches.next(), matches.next()) {
+            (Some(node), None) => Ok(node.id.clone()),
+            (Some(_), Some(_)) => Err(WorkflowError::DuplicateNodeName(name.to_string())),
+            (None, _) => Err(WorkflowError::NodeNameNotFound(name.to_string())),
+        }
+    }
+
+    /// Add an edge with a generated id between two nodes named by their names
+    pub fn add_edge_by_name(self, source: &str, target: &str) -> Result<Self, WorkflowError> {
+        let source = self.node_id(source)?;
+        let target = self.node_id(target)?;
+        self.connect(source, target)
+    }
+
+    /// Add an edge with a generated id between two nodes
+    pub fn connect(self, source: NodeId, target: NodeId) -> Result<Self, WorkflowError> {
+        let id = if self.deterministic_ids {
+            EdgeId::from_name(&self.definition.name, &format!("{}->{}", source, target))
+        } else {
+            self.next_edge_id()
+        };
+        self.add_edge(Edge::new(id, source, target))
+    }
+
     /// Build the workflow definition
     pub fn build(self) -> WorkflowDefinition {
         self.definition
     }
 }
 
+/// Nodes forked by `WorkflowBuilder::parallel`, waiting to be joined
+pub struct ParallelGroup {
+    /// Builder the nodes were added to
+    builder: WorkflowBuilder,
+
+    /// Node the branches depend on
+    fork: NodeId,
+
+    /// Nodes added in parallel
+    branches: Vec<NodeId>,
+}
+
+impl ParallelGroup {
+    /// Ids of the nodes added in parallel
+    pub fn branches(&self) -> &[NodeId] {
+        &self.branches
+    }
+
+    /// Add a node depending on every branch, which `then` chains from
+    ///
+    /// With no branches, the node depends on the fork node instead.
+    pub fn join(self, node: Node) -> Result<WorkflowBuilder, WorkflowError> {
+        let node_id = node.id.clone();
+        let sources = if self.branches.is_empty() {
+            vec![self.fork]
+        } else {
+            self.branches
+        };
+        let mut builder = self.builder.add_node(node)?;
+        for source in sources {
+            builder = builder.connect(source, node_id.clone())?;
+        }
+        Ok(builder)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -742,6 +1422,14 @@ mod tests {
         let idx3 = order.iter().position(|id| *id == node3_id).unwrap();
         assert!(idx2 > 0 && idx2 < 3);
         assert!(idx3 > 0 && idx3 < 3);
+
+        // Nodes 2 and 3 form the middle level
+        let levels = workflow.get_topological_levels().unwrap();
+        assert_eq!(levels.len(), 3);
+        assert_eq!(levels[0], vec![node1_id]);
+        assert_eq!(levels[1].len(), 2);
+        assert!(levels[1].contains(&node2_id) && levels[1].contains(&node3_id));
+        assert_eq!(levels[2], vec![node4_id]);
     }
 
     #[test]
@@ -809,4 +1497,454 @@ mod tests {
         assert_eq!(workflow.nodes.len(), 2);
         assert_eq!(workflow.edges.len(), 1);
     }
+
+    #[test]
+    fn test_fingerprint() {
+        // Build the same workflow twice, with different random IDs
+        let build = || {
+            let a = Node::new(NodeId::new(), "fetch".to_string())
+                .with_config(serde_json::json!({"url": "http://example.com"}));
+            let b = Node::new(NodeId::new(), "parse".to_string());
+            let (a_id, b_id) = (a.id.clone(), b.id.clone());
+            WorkflowBuilder::new("Pipeline")
+                .add_node(a)
+                .unwrap()
+                .add_node(b)
+                .unwrap()
+                .add_edge(Edge::new(EdgeId::new(), a_id, b_id))
+                .unwrap()
+                .build()
+        };
+
+        let first = build();
+        let second = build();
+        assert_ne!(first.id, second.id);
+        assert_eq!(first.fingerprint(), second.fingerprint());
+        assert_eq!(first.fingerprint().len(), 64);
+
+        // Changing a node's config changes the fingerprint
+        let mut modified = build();
+        let node_id = modified
+            .nodes
+            .values()
+            .find(|n| n.name == "fetch")
+            .map(|n| n.id.clone())
+            .unwrap();
+        modified.get_node_mut(&node_id).unwrap().config =
+            serde_json::json!({"url": "http://example.org"});
+        assert_ne!(first.fingerprint(), modified.fingerprint());
+
+        // So does changing how a node runs, an edge's input or a
+        // workflow-level setting
+        let mut retyped = build();
+        let fetch = retyped
+            .nodes
+            .values_mut()
+            .find(|n| n.name == "fetch")
+            .unwrap();
+        fetch.node_type = crate::model::NodeType::PluginCall {
+            plugin_id: "http".to_string(),
+            function: "get".to_string(),
+        };
+        assert_ne!(first.fingerprint(), retyped.fingerprint());
+        let mut rerouted = build();
+        for edge in rerouted.edges.values_mut() {
+            edge.input_path = Some("$.body".to_string());
+        }
+        assert_ne!(first.fingerprint(), rerouted.fingerprint());
+        let engined = build().with_engine("parallel");
+        assert_ne!(first.fingerprint(), engined.fingerprint());
+
+        // Reversing the edge direction changes the fingerprint
+        let mut reversed = WorkflowDefinition::new(WorkflowId::new(), "Pipeline".to_string());
+        let a = Node::new(NodeId::new(), "fetch".to_string())
+            .with_config(serde_json::json!({"url": "http://example.com"}));
+        let b = Node::new(NodeId::new(), "parse".to_string());
+        let (a_id, b_id) = (a.id.clone(), b.id.clone());
+        reversed.add_node(a).unwrap();
+        reversed.add_node(b).unwrap();
+        reversed
+            .add_edge(Edge::new(EdgeId::new(), b_id, a_id))
+            .unwrap();
+        assert_ne!(first.fingerprint(), reversed.fingerprint());
+    }
+
+    fn two_node_workflow() -> (WorkflowDefinition, NodeId, NodeId) {
+        let node1 = Node::new(NodeId::new(), "Fetch".to_string());
+        let node2 = Node::new(NodeId::new(), "Store".to_string());
+        let node1_id = node1.id.clone();
+        let node2_id = node2.id.clone();
+        let workflow = WorkflowBuilder::new("File Workflow")
+            .add_node(node1)
+            .unwrap()
+            .add_node(node2)
+            .unwrap()
+            .add_edge(Edge::new(EdgeId::new(), node1_id.clone(), node2_id.clone()))
+            .unwrap()
+            .build();
+        (workflow, node1_id, node2_id)
+    }
+
+    #[test]
+    fn test_from_file_json_and_yaml() {
+        let (workflow, node1_id, node2_id) = two_node_workflow();
+        let dir = tempfile::tempdir().unwrap();
+
+        let json_path = dir.path().join("workflow.json");
+        std::fs::write(&json_path, workflow.to_json().unwrap()).unwrap();
+        let yaml_path = dir.path().join("workflow.yml");
+        std::fs::write(&yaml_path, serde_yaml::to_string(&workflow).unwrap()).unwrap();
+
+        for path in [json_path, yaml_path] {
+            let loaded = WorkflowDefinition::from_file(&path).unwrap();
+            assert_eq!(loaded.id, workflow.id);
+            assert_eq!(loaded.nodes.len(), 2);
+            assert_eq!(loaded.edges.len(), 1);
+            assert_eq!(loaded.start_nodes, HashSet::from([node1_id.clone()]));
+            assert_eq!(loaded.end_nodes, HashSet::from([node2_id.clone()]));
+            // In-degrees are not serialized and must be rebuilt on load
+            assert_eq!(loaded.get_node(&node2_id).unwrap().in_degree, 1);
+        }
+    }
+
+    #[test]
+    fn test_from_file_errors() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let txt_path = dir.path().join("workflow.txt");
+        std::fs::write(&txt_path, "{}").unwrap();
+        assert!(matches!(
+            WorkflowDefinition::from_file(&txt_path),
+            Err(WorkflowError::UnsupportedFormat(_))
+        ));
+
+        let yaml_path = dir.path().join("broken.yaml");
+        std::fs::write(&yaml_path, "name: Broken\nnodes:\n  - [unclosed\n").unwrap();
+        match WorkflowDefinition::from_file(&yaml_path) {
+            Err(WorkflowError::ParseError { line, .. }) => assert!(line >= 3),
+            other => panic!("expected parse error, got {:?}", other),
+        }
+
+        let json_path = dir.path().join("broken.json");
+        std::fs::write(&json_path, "{\n  \"name\": \"Broken\",\n  \"id\": 42\n}").unwrap();
+        match WorkflowDefinition::from_file(&json_path) {
+            Err(WorkflowError::ParseError { line, column, .. }) => {
+                assert_eq!(line, 3);
+                assert!(column > 0);
+            }
+            other => panic!("expected parse error, got {:?}", other),
+        }
+
+        // An edge pointing at a node that is not in the file is reported by edge ID
+        let (mut workflow, _, node2_id) = two_node_workflow();
+        workflow.nodes.remove(&node2_id);
+        let edge_id = workflow.edges.keys().next().unwrap().clone();
+        let dangling_path = dir.path().join("dangling.json");
+        std::fs::write(&dangling_path, workflow.to_json().unwrap()).unwrap();
+        let err = WorkflowDefinition::from_file(&dangling_path).unwrap_err();
+        assert!(matches!(err, WorkflowError::ValidationError(_)));
+        assert!(err.to_string().contains(&edge_id.to_string()));
+    }
+
+    #[test]
+    fn test_from_file_core_workflow_shape() {
+        use lion_core::types::workflow::WorkflowNode;
+
+        let mut core = CoreWorkflow::new("Core Workflow", "Loaded from YAML");
+        let fetch = WorkflowNode::new_plugin_call("fetch", "http", "get");
+        let mut store = WorkflowNode::new_plugin_call("store", "db", "put");
+        store.add_dependency(fetch.id);
+        let fetch_id = NodeId::from_uuid(fetch.id.uuid());
+        let store_id = NodeId::from_uuid(store.id.uuid());
+        core.add_node(fetch);
+        core.add_node(store);
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("core.yaml");
+        std::fs::write(&path, serde_yaml::to_string(&core).unwrap()).unwrap();
+
+        let loaded = WorkflowDefinition::from_file(&path).unwrap();
+        assert_eq!(loaded.name, "Core Workflow");
+        assert_eq!(loaded.description.as_deref(), Some("Loaded from YAML"));
+        assert_eq!(loaded.nodes.len(), 2);
+        assert_eq!(loaded.edges.len(), 1);
+        assert_eq!(loaded.start_nodes, HashSet::from([fetch_id]));
+        assert_eq!(loaded.get_node(&store_id).unwrap().in_degree, 1);
+        assert!(loaded.get_node(&store_id).unwrap().config["node_type"].is_object());
+    }
+
+    #[test]
+    fn test_critical_path_diamond() {
+        use std::time::Duration;
+
+        // start -> {fast, slow} -> end
+        let start = Node::new(NodeId::new(), "start".to_string());
+        let fast = Node::new(NodeId::new(), "fast".to_string());
+        let slow = Node::new(NodeId::new(), "slow".to_string());
+        let end = Node::new(NodeId::new(), "end".to_string());
+        let (start_id, fast_id, slow_id, end_id) = (
+            start.id.clone(),
+            fast.id.clone(),
+            slow.id.clone(),
+            end.id.clone(),
+        );
+        let workflow = WorkflowBuilder::new("Diamond")
+            .add_node(start)
+            .unwrap()
+            .add_node(fast)
+            .unwrap()
+            .add_node(slow)
+            .unwrap()
+            .add_node(end)
+            .unwrap()
+            .add_edge(Edge::new(EdgeId::new(), start_id.clone(), fast_id.clone()))
+            .unwrap()
+            .add_edge(Edge::new(EdgeId::new(), start_id.clone(), slow_id.clone()))
+            .unwrap()
+            .add_edge(Edge::new(EdgeId::new(), fast_id.clone(), end_id.clone()))
+            .unwrap()
+            .add_edge(Edge::new(EdgeId::new(), slow_id.clone(), end_id.clone()))
+            .unwrap()
+            .build();
+
+        let durations = HashMap::from([
+            (start_id.clone(), Duration::from_millis(10)),
+            (fast_id.clone(), Duration::from_millis(5)),
+            (slow_id.clone(), Duration::from_millis(500)),
+            (end_id.clone(), Duration::from_millis(10)),
+        ]);
+        assert_eq!(
+            workflow.critical_path(&durations),
+            vec![start_id.clone(), slow_id, end_id.clone()]
+        );
+
+        // Making the other branch slower moves the critical path
+        let mut durations = durations;
+        durations.insert(fast_id.clone(), Duration::from_secs(1));
+        assert_eq!(
+            workflow.critical_path(&durations),
+            vec![start_id.clone(), fast_id, end_id.clone()]
+        );
+
+        // Without durations both branches have the same edge count
+        let path = workflow.critical_path(&HashMap::new());
+        assert_eq!(path.len(), 3);
+        assert_eq!(path.first(), Some(&start_id));
+        assert_eq!(path.last(), Some(&end_id));
+    }
+
+    #[test]
+    fn test_builder_uses_id_generator() {
+        use crate::model::id_gen::SequentialIdGenerator;
+        use uuid::Uuid;
+
+        let builder = WorkflowBuilder::with_id_generator(
+            "Ordered",
+            Arc::new(SequentialIdGenerator::with_epoch(1)),
+        );
+        let first = builder.new_node("first");
+        let first_id = first.id.clone();
+        let second = builder.new_node("second");
+        let second_id = second.id.clone();
+        let workflow = builder
+            .add_node(first)
+            .unwrap()
+            .add_node(second)
+            .unwrap()
+            .connect(first_id.clone(), second_id.clone())
+            .unwrap()
+            .build();
+
+        // The workflow, the nodes and the edge take consecutive ids
+        assert_eq!(workflow.id.uuid(), Uuid::from_u64_pair(1, 0));
+        assert_eq!(first_id.uuid(), Uuid::from_u64_pair(1, 1));
+        assert_eq!(second_id.uuid(), Uuid::from_u64_pair(1, 2));
+        let edge_id = workflow.edges.keys().next().unwrap();
+        assert_eq!(edge_id.uuid(), Uuid::from_u64_pair(1, 3));
+        assert!(first_id.uuid() < second_id.uuid());
+    }
+
+    #[test]
+    fn test_deterministic_ids_are_reproducible() {
+        let build = || {
+            let builder = WorkflowBuilder::with_deterministic_ids("Checkout");
+            let validate = builder.new_node("validate");
+            let charge = builder.new_node("charge");
+            let (validate_id, charge_id) = (validate.id.clone(), charge.id.clone());
+            builder
+                .add_node(validate)
+                .unwrap()
+                .add_node(charge)
+                .unwrap()
+                .connect(validate_id, charge_id)
+                .unwrap()
+                .build()
+        };
+        let first = build();
+        let second = build();
+
+        assert_eq!(first.id, second.id);
+        assert_eq!(
+            first.nodes.keys().collect::<HashSet<_>>(),
+            second.nodes.keys().collect::<HashSet<_>>()
+        );
+        assert_eq!(
+            first.edges.keys().collect::<Vec<_>>(),
+            second.edges.keys().collect::<Vec<_>>()
+        );
+        assert!(first
+            .nodes
+            .contains_key(&NodeId::from_name("Checkout", "validate")));
+
+        // Another workflow's node of the same name gets another id
+        let other = WorkflowBuilder::with_deterministic_ids("Refund").new_node("validate");
+        assert_ne!(other.id, NodeId::from_name("Checkout", "validate"));
+    }
+
+    #[test]
+    fn test_add_edge_by_name() {
+        let workflow = WorkflowBuilder::new("Named")
+            .node("start")
+            .unwrap()
+            .node("process")
+            .unwrap()
+            .node("end")
+            .unwrap()
+            .add_edge_by_name("start", "process")
+            .unwrap()
+            .add_edge_by_name("process", "end")
+            .unwrap()
+            .build();
+
+        let names: Vec<&str> = workflow
+            .get_topological_order()
+            .unwrap()
+            .iter()
+            .map(|id| workflow.nodes[id].name.as_str())
+            .collect();
+        assert_eq!(names, vec!["start", "process", "end"]);
+
+        // Unknown and repeated names are rejected
+        let builder = WorkflowBuilder::new("Named").node("start").unwrap();
+        assert!(matches!(
+            builder.node("start"),
+            Err(WorkflowError::DuplicateNodeName(name)) if name == "start"
+        ));
+        let builder = WorkflowBuilder::new("Named").node("start").unwrap();
+        assert!(matches!(
+            builder.add_edge_by_name("start", "missing"),
+            Err(WorkflowError::NodeNameNotFound(name)) if name == "missing"
+        ));
+
+        // Names shared by nodes added by id are ambiguous
+        let builder = WorkflowBuilder::new("Named");
+        let first = builder.new_node("task");
+        let second = builder.new_node("task");
+        let builder = builder
+            .add_node(first)
+            .unwrap()
+            .add_node(second)
+            .unwrap()
+            .node("end")
+            .unwrap();
+        assert!(matches!(
+            builder.add_edge_by_name("task", "end"),
+            Err(WorkflowError::DuplicateNodeName(_))
+        ));
+    }
+
+    #[test]
+    fn test_then_chains_nodes() {
+        let builder = WorkflowBuilder::new("Pipeline");
+        let (fetch, parse, store) = (
+            builder.new_node("fetch"),
+            builder.new_node("parse"),
+            builder.new_node("store"),
+        );
+        let audit = builder.new_node("audit");
+        let (fetch_id, audit_id) = (fetch.id.clone(), audit.id.clone());
+        let workflow = builder
+            .start(fetch)
+            .unwrap()
+            .then(parse)
+            .unwrap()
+            .then(store)
+            .unwrap()
+            // Branches are still added explicitly
+            .add_node(audit)
+            .unwrap()
+            .connect(fetch_id.clone(), audit_id)
+            .unwrap()
+            .build();
+
+        let names: Vec<&str> = workflow
+            .get_topological_levels()
+            .unwrap()
+            .iter()
+            .flatten()
+            .map(|id| workflow.nodes[id].name.as_str())
+            .filter(|name| *name != "audit")
+            .collect();
+        assert_eq!(names, vec!["fetch", "parse", "store"]);
+        assert_eq!(workflow.edges.len(), 3);
+        assert_eq!(workflow.get_child_nodes(&fetch_id).unwrap().len(), 2);
+
+        let builder = WorkflowBuilder::new("Pipeline");
+        let node = builder.new_node("orphan");
+        assert!(matches!(
+            builder.then(node),
+            Err(WorkflowError::NoPreviousNode)
+        ));
+    }
+
+    #[test]
+    fn test_parallel_join_matches_manual_edges() {
+        let edge_set = |workflow: &WorkflowDefinition| {
+            workflow
+                .edges
+                .values()
+                .map(|edge| (edge.source.clone(), edge.target.clone()))
+                .collect::<HashSet<_>>()
+        };
+
+        let builder = WorkflowBuilder::new("Fork");
+        let nodes: Vec<Node> = ["split", "a", "b", "c", "merge", "report"]
+            .iter()
+            .map(|name| builder.new_node(name))
+            .collect();
+        let ids: Vec<NodeId> = nodes.iter().map(|node| node.id.clone()).collect();
+
+        let forked = builder
+            .start(nodes[0].clone())
+            .unwrap()
+            .parallel(nodes[1..4].to_vec())
+            .unwrap();
+        assert_eq!(forked.branches(), &ids[1..4]);
+        let workflow = forked
+            .join(nodes[4].clone())
+            .unwrap()
+            .then(nodes[5].clone())
+            .unwrap()
+            .build();
+        workflow.validate().unwrap();
+
+        let mut manual = WorkflowBuilder::new("Fork");
+        for node in &nodes {
+            manual = manual.add_node(node.clone()).unwrap();
+        }
+        for branch in &ids[1..4] {
+            manual = manual
+                .connect(ids[0].clone(), branch.clone())
+                .unwrap()
+                .connect(branch.clone(), ids[4].clone())
+                .unwrap();
+        }
+        let manual = manual
+            .connect(ids[4].clone(), ids[5].clone())
+            .unwrap()
+            .build();
+
+        assert_eq!(edge_set(&workflow), edge_set(&manual));
+        assert_eq!(workflow.get_topological_levels().unwrap().len(), 4);
+    }
 }
@@ -7,9 +7,10 @@ use serde::{Deserialize, Serialize};
 pub type EdgeId = Id<Edge>;
 
 /// Types of conditions that can be applied to an edge
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Hash, Default)]
 pub enum ConditionType {
     /// No condition (always passes)
+    #[default]
     None,
     /// JSON path condition on the source node's output
     JsonPath(String),
@@ -37,12 +38,20 @@ pub struct Edge {
     pub target: NodeId,
 
     /// Optional condition that must be satisfied for this edge to be traversed
+    #[serde(default)]
     pub condition: ConditionType,
 
     /// Capability required to traverse this edge (cross-component boundaries)
+    #[serde(default)]
     pub required_capability: Option<CapabilityId>,
 
+    /// Path of the field of the source's output passed to the target (e.g.
+    /// "order.items[0]"); the whole output if unset
+    #[serde(default)]
+    pub input_path: Option<String>,
+
     /// Custom metadata for this edge
+    #[serde(default)]
     pub metadata: serde_json::Value,
 }
 
@@ -55,6 +64,7 @@ impl Edge {
             target,
             condition: ConditionType::None,
             required_capability: None,
+            input_path: None,
             metadata: serde_json::Value::Null,
         }
     }
@@ -86,6 +96,12 @@ impl Edge {
         self
     }
 
+    /// Pass only a field of the source's output to the target
+    pub fn with_input_path(mut self, path: &str) -> Self {
+        self.input_path = Some(path.to_string());
+        self
+    }
+
     /// Set the required capability for traversing this edge
     pub fn with_capability(mut self, capability_id: CapabilityId) -> Self {
         self.required_capability = Some(capability_id);
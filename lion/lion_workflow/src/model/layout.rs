@@ -0,0 +1,305 @@
+use crate::model::definition::{WorkflowDefinition, WorkflowError};
+use crate::model::edge::EdgeId;
+use crate::model::node::NodeId;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Options for laying out a workflow as a layered diagram
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LayoutOptions {
+    /// Distance between consecutive layers, along the x axis
+    pub layer_spacing: f64,
+
+    /// Distance between consecutive nodes of a layer, along the y axis
+    pub node_spacing: f64,
+
+    /// Number of down-and-up barycenter sweeps reducing edge crossings
+    pub sweeps: usize,
+}
+
+impl Default for LayoutOptions {
+    fn default() -> Self {
+        Self {
+            layer_spacing: 200.0,
+            node_spacing: 100.0,
+            sweeps: 4,
+        }
+    }
+}
+
+/// Position of a node in a workflow layout
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NodePosition {
+    /// Node placed
+    pub node_id: NodeId,
+
+    /// Horizontal position, set by the layer
+    pub x: f64,
+
+    /// Vertical position, set by the order within the layer
+    pub y: f64,
+
+    /// Layer of the node: the length of the longest path reaching it
+    pub layer: usize,
+}
+
+/// Route of an edge in a workflow layout
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EdgeRoute {
+    /// Edge routed
+    pub edge_id: EdgeId,
+
+    /// Points the edge passes through, from its source to its target
+    ///
+    /// An edge spanning several layers bends once in each layer it crosses,
+    /// at a slot kept free of nodes.
+    pub points: Vec<(f64, f64)>,
+}
+
+/// Layered layout of a workflow, left to right
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WorkflowLayout {
+    /// Position of every node
+    pub nodes: Vec<NodePosition>,
+
+    /// Route of every edge
+    pub edges: Vec<EdgeRoute>,
+}
+
+/// Something occupying a slot of a layer
+#[derive(Debug, Clone)]
+enum Slot {
+    /// A node of the workflow
+    Node(NodeId),
+
+    /// Where an edge crosses a layer between its source and target
+    Bend,
+}
+
+impl WorkflowDefinition {
+    /// Lay out this workflow as a layered diagram with default options
+    pub fn layout(&self) -> Result<WorkflowLayout, WorkflowError> {
+        self.layout_with(&LayoutOptions::default())
+    }
+
+    /// Lay out this workflow as a layered diagram
+    ///
+    /// Nodes are layered by longest path, so every edge points to a later
+    /// layer, then ordered within their layer by the barycenter of their
+    /// neighbors to reduce crossings. The result only depends on the
+    /// definition, so the same workflow is always drawn the same way.
+    pub fn layout_with(&self, options: &LayoutOptions) -> Result<WorkflowLayout, WorkflowError> {
+        let mut layers: Vec<Vec<usize>> = Vec::new();
+        let mut slots: Vec<Slot> = Vec::new();
+        let mut node_slot: HashMap<NodeId, usize> = HashMap::new();
+        let mut node_layer: HashMap<NodeId, usize> = HashMap::new();
+        for (layer, mut node_ids) in self.get_topological_levels()?.into_iter().enumerate() {
+            // Start from a stable order
+            node_ids.sort_by(|a, b| {
+                let name = |id: &NodeId| self.nodes.get(id).map(|node| node.name.clone());
+                name(a).cmp(&name(b)).then_with(|| a.uuid().cmp(&b.uuid()))
+            });
+            let mut row = Vec::with_capacity(node_ids.len());
+            for node_id in node_ids {
+                node_slot.insert(node_id.clone(), slots.len());
+                node_layer.insert(node_id.clone(), layer);
+                row.push(slots.len());
+                slots.push(Slot::Node(node_id));
+            }
+            layers.push(row);
+        }
+
+        // Split each edge into one link per layer it spans
+        let mut edges: Vec<_> = self.edges.values().collect();
+        edges.sort_by_key(|edge| edge.id.uuid());
+        let mut upper: Vec<Vec<usize>> = vec![Vec::new(); slots.len()];
+        let mut lower: Vec<Vec<usize>> = vec![Vec::new(); slots.len()];
+        let mut edge_slots: Vec<(EdgeId, Vec<usize>)> = Vec::with_capacity(edges.len());
+        for edge in edges {
+            let (Some(&source), Some(&target)) =
+                (node_slot.get(&edge.source), node_slot.get(&edge.target))
+            else {
+                continue;
+            };
+            let mut chain = vec![source];
+            let crossed = node_layer[&edge.source] + 1..node_layer[&edge.target];
+            for row in &mut layers[crossed] {
+                let bend = slots.len();
+                slots.push(Slot::Bend);
+                upper.push(Vec::new());
+                lower.push(Vec::new());
+                row.push(bend);
+                chain.push(bend);
+            }
+            chain.push(target);
+            for link in chain.windows(2) {
+                lower[link[0]].push(link[1]);
+                upper[link[1]].push(link[0]);
+            }
+            edge_slots.push((edge.id.clone(), chain));
+        }
+
+        // Barycenter sweeps, down then up
+        let mut position: Vec<f64> = vec![0.0; slots.len()];
+        let place = |row: &[usize], position: &mut [f64]| {
+            for (index, &slot) in row.iter().enumerate() {
+                position[slot] = index as f64;
+            }
+        };
+        for row in &layers {
+            place(row, &mut position);
+        }
+        let reorder = |row: &mut Vec<usize>, neighbors: &[Vec<usize>], position: &[f64]| {
+            let key = |slot: usize| {
+                let adjacent = &neighbors[slot];
+                if adjacent.is_empty() {
+                    position[slot]
+                } else {
+                    adjacent.iter().map(|&n| position[n]).sum::<f64>() / adjacent.len() as f64
+                }
+            };
+            row.sort_by(|&a, &b| key(a).total_cmp(&key(b)));
+        };
+        for _ in 0..options.sweeps {
+            for row in layers.iter_mut().skip(1) {
+                reorder(row, &upper, &position);
+                place(row, &mut position);
+            }
+            for row in layers.iter_mut().rev().skip(1) {
+                reorder(row, &lower, &position);
+                place(row, &mut position);
+            }
+        }
+
+        let coordinates = |slot: usize, layer: usize| {
+            (
+                layer as f64 * options.layer_spacing,
+                position[slot] * options.node_spacing,
+            )
+        };
+        let mut slot_layer = vec![0; slots.len()];
+        for (layer, row) in layers.iter().enumerate() {
+            for &slot in row {
+                slot_layer[slot] = layer;
+            }
+        }
+
+        let mut nodes = Vec::with_capacity(self.nodes.len());
+        for (layer, row) in layers.iter().enumerate() {
+            for &slot in row {
+                if let Slot::Node(node_id) = &slots[slot] {
+                    let (x, y) = coordinates(slot, layer);
+                    nodes.push(NodePosition {
+                        node_id: node_id.clone(),
+                        x,
+                        y,
+                        layer,
+                    });
+                }
+            }
+        }
+        let edges = edge_slots
+            .into_iter()
+            .map(|(edge_id, chain)| EdgeRoute {
+                edge_id,
+                points: chain
+                    .into_iter()
+                    .map(|slot| coordinates(slot, slot_layer[slot]))
+                    .collect(),
+            })
+            .collect();
+
+        Ok(WorkflowLayout { nodes, edges })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{Edge, Node};
+    use std::collections::HashSet;
+
+    /// Count the pairs of links between two layers that cross
+    fn crossings(layout: &WorkflowLayout) -> usize {
+        let links: Vec<((f64, f64), (f64, f64))> = layout
+            .edges
+            .iter()
+            .flat_map(|route| route.points.windows(2).map(|pair| (pair[0], pair[1])))
+            .collect();
+        let mut count = 0;
+        for (i, a) in links.iter().enumerate() {
+            for b in &links[i + 1..] {
+                let same_layers = a.0 .0 == b.0 .0 && a.1 .0 == b.1 .0;
+                if same_layers && (a.0 .1 - b.0 .1) * (a.1 .1 - b.1 .1) < 0.0 {
+                    count += 1;
+                }
+            }
+        }
+        count
+    }
+
+    #[test]
+    fn test_layout_layers_without_overlap() {
+        // Two crossed chains a1 -> b2 -> c1 and a2 -> b1 -> c2, fanning into
+        // d; e skips ahead from the first layer to d
+        let mut workflow =
+            WorkflowDefinition::new(crate::model::WorkflowId::new(), "Layout".into());
+        let mut ids = HashMap::new();
+        for name in ["a1", "a2", "b1", "b2", "c1", "c2", "d", "e"] {
+            let node = Node::new(NodeId::new(), name.to_string());
+            ids.insert(name, node.id.clone());
+            workflow.add_node(node).unwrap();
+        }
+        let mut long_edge = None;
+        for (source, target) in [
+            ("a1", "b2"),
+            ("a2", "b1"),
+            ("b2", "c1"),
+            ("b1", "c2"),
+            ("c1", "d"),
+            ("c2", "d"),
+            ("e", "d"),
+        ] {
+            let edge = Edge::new(EdgeId::new(), ids[source].clone(), ids[target].clone());
+            if source == "e" {
+                long_edge = Some(edge.id.clone());
+            }
+            workflow.add_edge(edge).unwrap();
+        }
+
+        let layout = workflow.layout().unwrap();
+        assert_eq!(layout.nodes.len(), 8);
+        let by_id: HashMap<&NodeId, &NodePosition> =
+            layout.nodes.iter().map(|p| (&p.node_id, p)).collect();
+
+        // Each layer has its own column, and no two nodes share a spot
+        for position in &layout.nodes {
+            assert_eq!(position.x, position.layer as f64 * 200.0);
+        }
+        let spots: HashSet<(u64, u64)> = layout
+            .nodes
+            .iter()
+            .map(|p| (p.x.to_bits(), p.y.to_bits()))
+            .collect();
+        assert_eq!(spots.len(), 8);
+
+        // Every edge points to a later layer
+        for edge in workflow.edges.values() {
+            assert!(by_id[&edge.source].layer < by_id[&edge.target].layer);
+        }
+        assert_eq!(by_id[&ids["d"]].layer, 3);
+
+        // The long edge bends in each layer it crosses
+        let route = layout
+            .edges
+            .iter()
+            .find(|route| Some(&route.edge_id) == long_edge.as_ref())
+            .unwrap();
+        assert_eq!(route.points.len(), 4);
+        assert_eq!(route.points[1].0, 200.0);
+
+        // The sweeps untangle the crossed chains
+        assert_eq!(crossings(&layout), 0);
+        assert_eq!(workflow.layout().unwrap(), layout);
+    }
+}
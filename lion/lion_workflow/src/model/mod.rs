@@ -1,7 +1,19 @@
 pub mod definition;
 pub mod edge;
+pub mod id_gen;
+pub mod layout;
 pub mod node;
+pub mod render;
+pub mod switch;
 
-pub use definition::{Version, WorkflowBuilder, WorkflowDefinition, WorkflowError, WorkflowId};
+pub use definition::{
+    ParallelGroup, Version, WorkflowBuilder, WorkflowDefinition, WorkflowError, WorkflowId,
+};
 pub use edge::{ConditionType, Edge, EdgeId};
-pub use node::{AtomicNode, Node, NodeId, NodeStatus, Priority};
+pub use id_gen::{IdGenerator, RandomIdGenerator, SequentialIdGenerator, SharedIdGenerator};
+pub use layout::{EdgeRoute, LayoutOptions, NodePosition, WorkflowLayout};
+pub use node::{
+    AtomicNode, CachePolicy, FailurePolicy, Node, NodeId, NodeStatus, NodeType, Priority,
+};
+pub use render::DotOptions;
+pub use switch::{SwitchBranch, SwitchConfig, SwitchError, SwitchMode, SwitchRouter};
@@ -1,5 +1,6 @@
 use crate::model::edge::EdgeId;
 use lion_core::id::Id;
+use lion_core::types::ErrorPolicy;
 use lion_core::CapabilityId;
 use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
@@ -54,6 +55,71 @@ pub enum Priority {
     Critical = 3,
 }
 
+/// How a node is executed
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, Default)]
+pub enum NodeType {
+    /// Run by the node handler registered under the node's name
+    #[default]
+    Task,
+
+    /// Wait for an external system to report the result through
+    /// `WorkflowExecutor::complete_external_task`
+    ExternalTask {
+        /// How long to wait for the callback, in milliseconds (the
+        /// executor's default timeout if `None`)
+        timeout_ms: Option<u64>,
+    },
+
+    /// Run a saga registered with the executor's saga orchestrator to
+    /// completion; the node fails unless the saga commits
+    Saga {
+        /// Id of the registered saga definition
+        saga_id: String,
+    },
+
+    /// Call a function of a loaded plugin through the executor's plugin
+    /// invoker, with the node's input as argument
+    PluginCall {
+        /// Id of the plugin to call
+        plugin_id: String,
+
+        /// Name of the function to call
+        function: String,
+    },
+}
+
+/// How the results of a cacheable node are reused
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Default)]
+pub struct CachePolicy {
+    /// How long a result is reused, in milliseconds (forever if `None`)
+    #[serde(default)]
+    pub ttl_ms: Option<u64>,
+}
+
+impl CachePolicy {
+    /// Reuse results for at most `ttl`
+    pub fn with_ttl(ttl: std::time::Duration) -> Self {
+        CachePolicy {
+            ttl_ms: Some(ttl.as_millis() as u64),
+        }
+    }
+}
+
+/// What happens downstream of a node that failed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum FailurePolicy {
+    /// Skip the node's descendants and fail the whole workflow
+    #[default]
+    Abort,
+
+    /// Keep the node failed but run its successors as if it had completed
+    Continue,
+
+    /// Skip the node's descendants and let the rest of the workflow finish
+    SkipDownstream,
+}
+
 /// A node in the workflow graph
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct Node {
@@ -71,23 +137,61 @@ pub struct Node {
     #[serde(skip)]
     pub in_degree: usize,
 
+    /// How this node is executed
+    #[serde(default)]
+    pub node_type: NodeType,
+
     /// IDs of outgoing edges (to child nodes)
+    #[serde(default)]
     pub outgoing_edges: HashSet<EdgeId>,
 
     /// IDs of incoming edges (from parent nodes)
+    #[serde(default)]
     pub incoming_edges: HashSet<EdgeId>,
 
     /// Capability required to execute this node
+    #[serde(default)]
     pub required_capability: Option<CapabilityId>,
 
     /// Execution priority of this node
+    #[serde(default)]
     pub priority: Priority,
 
     /// Optional deadline for node execution
+    #[serde(default)]
     pub deadline: Option<chrono::DateTime<chrono::Utc>>,
 
     /// Type-specific configuration for this node
+    #[serde(default)]
     pub config: serde_json::Value,
+
+    /// JSON Schema the workflow input must satisfy when this is an entry node
+    #[serde(default)]
+    pub input_schema: Option<serde_json::Value>,
+
+    /// How a failure of this node is handled; the workflow default if unset
+    #[serde(default)]
+    pub error_policy: Option<ErrorPolicy>,
+
+    /// Whether the executor may reuse an earlier result for the same input;
+    /// only for idempotent task nodes
+    #[serde(default)]
+    pub cache: Option<CachePolicy>,
+
+    /// Node type whose handler undoes this node's effects when a failed
+    /// execution is rolled back
+    #[serde(default)]
+    pub compensation: Option<String>,
+
+    /// What happens downstream if this node fails, overriding the workflow
+    /// default
+    #[serde(default)]
+    pub on_failure: Option<FailurePolicy>,
+
+    /// Relative cost of running this node, weighting it in progress reports
+    /// (1 if `None`)
+    #[serde(default)]
+    pub estimated_cost: Option<u32>,
 }
 
 impl std::hash::Hash for Node {
@@ -98,10 +202,16 @@ impl std::hash::Hash for Node {
         self.name.hash(state);
         self.status.hash(state);
         self.in_degree.hash(state);
+        self.node_type.hash(state);
         self.required_capability.hash(state);
         self.priority.hash(state);
+        self.cache.hash(state);
+        self.compensation.hash(state);
+        self.on_failure.hash(state);
+        self.estimated_cost.hash(state);
         // Skip deadline as chrono::DateTime doesn't implement Hash
         // Skip config as serde_json::Value doesn't implement Hash
+        // Skip error_policy as ErrorPolicy doesn't implement Hash
     }
 }
 
@@ -113,12 +223,19 @@ impl Node {
             name,
             status: NodeStatus::Pending,
             in_degree: 0,
+            node_type: NodeType::Task,
             outgoing_edges: HashSet::new(),
             incoming_edges: HashSet::new(),
             required_capability: None,
             priority: Priority::Normal,
             deadline: None,
             config: serde_json::Value::Null,
+            input_schema: None,
+            error_policy: None,
+            cache: None,
+            compensation: None,
+            on_failure: None,
+            estimated_cost: None,
         }
     }
 
@@ -151,6 +268,12 @@ impl Node {
         self.status = NodeStatus::Failed;
     }
 
+    /// Set how this node is executed
+    pub fn with_node_type(mut self, node_type: NodeType) -> Self {
+        self.node_type = node_type;
+        self
+    }
+
     /// Add a required capability to this node
     pub fn with_capability(mut self, capability_id: CapabilityId) -> Self {
         self.required_capability = Some(capability_id);
@@ -175,6 +298,42 @@ impl Node {
         self
     }
 
+    /// Set the JSON Schema the workflow input must satisfy
+    pub fn with_input_schema(mut self, input_schema: serde_json::Value) -> Self {
+        self.input_schema = Some(input_schema);
+        self
+    }
+
+    /// Override the workflow's default error policy for this node
+    pub fn with_error_policy(mut self, error_policy: ErrorPolicy) -> Self {
+        self.error_policy = Some(error_policy);
+        self
+    }
+
+    /// Let the executor reuse this node's results for the same input
+    pub fn cacheable(mut self, policy: CachePolicy) -> Self {
+        self.cache = Some(policy);
+        self
+    }
+
+    /// Set the node type whose handler undoes this node on rollback
+    pub fn with_compensation(mut self, node_type: &str) -> Self {
+        self.compensation = Some(node_type.to_string());
+        self
+    }
+
+    /// Override the workflow's default failure policy for this node
+    pub fn with_failure_policy(mut self, policy: FailurePolicy) -> Self {
+        self.on_failure = Some(policy);
+        self
+    }
+
+    /// Set the relative cost of running this node, for progress reports
+    pub fn with_estimated_cost(mut self, cost: u32) -> Self {
+        self.estimated_cost = Some(cost);
+        self
+    }
+
     /// Increment the in-degree counter for this node
     pub fn increment_in_degree(&mut self) {
         self.in_degree += 1;
@@ -217,6 +376,30 @@ impl Node {
         }
         false
     }
+
+    /// Compute a stable fingerprint of this node's content
+    ///
+    /// The fingerprint is a SHA-256 hex digest over the serialized node
+    /// without its ID and edge IDs; runtime state is not serialized. Every
+    /// other field contributes, so nodes that would run differently have
+    /// different fingerprints.
+    pub fn fingerprint(&self) -> String {
+        use sha2::{Digest, Sha256};
+
+        let content = self.content().to_string();
+        format!("{:x}", Sha256::digest(content.as_bytes()))
+    }
+
+    /// The serialized node without its ID and edge IDs
+    pub(crate) fn content(&self) -> serde_json::Value {
+        let mut content = serde_json::to_value(self).unwrap_or_default();
+        if let Some(fields) = content.as_object_mut() {
+            for field in ["id", "outgoing_edges", "incoming_edges"] {
+                fields.remove(field);
+            }
+        }
+        content
+    }
 }
 
 /// Thread-safe in-degree counter for concurrent workflow execution
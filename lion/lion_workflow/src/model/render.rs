@@ -0,0 +1,368 @@
+use crate::model::definition::WorkflowDefinition;
+use crate::model::edge::{ConditionType, Edge};
+use crate::model::node::{Node, NodeId, NodeStatus};
+use crate::state::machine::WorkflowState;
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
+
+/// Options for rendering a workflow as a Graphviz DOT graph
+#[derive(Debug, Clone, Default)]
+pub struct DotOptions {
+    /// Status of each node in a running instance, used to color the nodes
+    pub node_status: Option<HashMap<NodeId, NodeStatus>>,
+
+    /// Whether to draw the critical path in bold
+    pub highlight_critical_path: bool,
+}
+
+impl DotOptions {
+    /// Create options for a plain rendering of the definition
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Color nodes by their status in the given instance
+    pub fn with_state(mut self, state: &WorkflowState) -> Self {
+        self.node_status = Some(state.node_status.clone());
+        self
+    }
+
+    /// Highlight the critical path
+    pub fn with_critical_path(mut self) -> Self {
+        self.highlight_critical_path = true;
+        self
+    }
+}
+
+/// Fill color used for a node status
+fn status_color(status: NodeStatus) -> &'static str {
+    match status {
+        NodeStatus::Pending => "white",
+        NodeStatus::Ready => "lightblue",
+        NodeStatus::Running => "gold",
+        NodeStatus::Completed => "palegreen",
+        NodeStatus::Failed => "salmon",
+        NodeStatus::Skipped => "lightgray",
+        NodeStatus::Cancelled => "orange",
+    }
+}
+
+/// Short label describing an edge condition, if any
+fn condition_label(condition: &ConditionType) -> Option<String> {
+    match condition {
+        ConditionType::None => None,
+        ConditionType::JsonPath(path) => Some(path.clone()),
+        ConditionType::Expression(expr) => Some(expr.clone()),
+        ConditionType::Custom { plugin_id, .. } => Some(format!("custom: {}", plugin_id)),
+    }
+}
+
+/// Escape a string for use inside a double-quoted DOT identifier
+fn escape_dot(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// Mermaid class applied to nodes with the given status, if any
+fn mermaid_class(status: NodeStatus) -> Option<&'static str> {
+    match status {
+        NodeStatus::Running => Some("running"),
+        NodeStatus::Completed => Some("completed"),
+        NodeStatus::Failed => Some("failed"),
+        _ => None,
+    }
+}
+
+/// Mermaid identifier for a node
+///
+/// UUIDs may start with a digit and contain dashes, so they are prefixed and stripped.
+fn mermaid_id(node_id: &NodeId) -> String {
+    format!("n{}", node_id.uuid().simple())
+}
+
+/// Escape text for use inside a quoted Mermaid label
+fn escape_mermaid(value: &str) -> String {
+    value.replace('"', "#quot;").replace('\n', "<br/>")
+}
+
+impl WorkflowDefinition {
+    /// Render this workflow as a Graphviz DOT graph
+    pub fn to_dot(&self) -> String {
+        self.to_dot_with(&DotOptions::default())
+    }
+
+    /// Render this workflow as a Graphviz DOT graph with the given options
+    ///
+    /// Nodes are labeled by name and edges point from a dependency to its dependent.
+    pub fn to_dot_with(&self, options: &DotOptions) -> String {
+        let critical: Vec<NodeId> = if options.highlight_critical_path {
+            self.critical_path(&HashMap::new())
+        } else {
+            Vec::new()
+        };
+        let critical_nodes: HashSet<&NodeId> = critical.iter().collect();
+        let critical_edges: HashSet<(&NodeId, &NodeId)> = critical
+            .windows(2)
+            .map(|pair| (&pair[0], &pair[1]))
+            .collect();
+
+        let mut out = String::new();
+        let _ = writeln!(out, "digraph \"{}\" {{", escape_dot(&self.name));
+        let _ = writeln!(out, "  rankdir=LR;");
+        let _ = writeln!(out, "  node [shape=box, style=rounded];");
+
+        // Sort for stable output
+        let mut nodes: Vec<&Node> = self.nodes.values().collect();
+        nodes.sort_by(|a, b| {
+            a.name
+                .cmp(&b.name)
+                .then_with(|| a.id.uuid().cmp(&b.id.uuid()))
+        });
+
+        for node in nodes {
+            let mut attrs = vec![format!("label=\"{}\"", escape_dot(&node.name))];
+            if let Some(statuses) = &options.node_status {
+                let status = statuses.get(&node.id).copied().unwrap_or(node.status);
+                attrs.push("style=\"rounded,filled\"".to_string());
+                attrs.push(format!("fillcolor=\"{}\"", status_color(status)));
+                attrs.push(format!("tooltip=\"{}\"", status));
+            }
+            if critical_nodes.contains(&node.id) {
+                attrs.push("color=\"red\"".to_string());
+                attrs.push("penwidth=2".to_string());
+            }
+            let _ = writeln!(out, "  \"{}\" [{}];", node.id, attrs.join(", "));
+        }
+
+        let mut edges: Vec<&Edge> = self.edges.values().collect();
+        edges.sort_by_key(|edge| (edge.source.uuid(), edge.target.uuid(), edge.id.uuid()));
+
+        for edge in edges {
+            let mut attrs = Vec::new();
+            if let Some(label) = condition_label(&edge.condition) {
+                attrs.push(format!("label=\"{}\"", escape_dot(&label)));
+            }
+            if critical_edges.contains(&(&edge.source, &edge.target)) {
+                attrs.push("color=\"red\"".to_string());
+                attrs.push("penwidth=2".to_string());
+            }
+            if attrs.is_empty() {
+                let _ = writeln!(out, "  \"{}\" -> \"{}\";", edge.source, edge.target);
+            } else {
+                let _ = writeln!(
+                    out,
+                    "  \"{}\" -> \"{}\" [{}];",
+                    edge.source,
+                    edge.target,
+                    attrs.join(", ")
+                );
+            }
+        }
+
+        out.push_str("}\n");
+        out
+    }
+
+    /// Render this workflow as a Mermaid `flowchart TD` diagram
+    pub fn to_mermaid(&self) -> String {
+        self.render_mermaid(None)
+    }
+
+    /// Render this workflow as a Mermaid diagram, styling nodes by their status in an instance
+    ///
+    /// Running, completed and failed nodes get the `running`, `completed` and `failed` classes.
+    pub fn to_mermaid_with_state(&self, state: &WorkflowState) -> String {
+        self.render_mermaid(Some(&state.node_status))
+    }
+
+    fn render_mermaid(&self, node_status: Option<&HashMap<NodeId, NodeStatus>>) -> String {
+        let mut out = String::from("flowchart TD\n");
+
+        // Sort for stable output
+        let mut nodes: Vec<&Node> = self.nodes.values().collect();
+        nodes.sort_by(|a, b| {
+            a.name
+                .cmp(&b.name)
+                .then_with(|| a.id.uuid().cmp(&b.id.uuid()))
+        });
+
+        for node in &nodes {
+            let _ = writeln!(
+                out,
+                "    {}[\"{}\"]",
+                mermaid_id(&node.id),
+                escape_mermaid(&node.name)
+            );
+        }
+
+        let mut edges: Vec<&Edge> = self.edges.values().collect();
+        edges.sort_by_key(|edge| (edge.source.uuid(), edge.target.uuid(), edge.id.uuid()));
+
+        for edge in edges {
+            match condition_label(&edge.condition) {
+                Some(label) => {
+                    let _ = writeln!(
+                        out,
+                        "    {} -->|\"{}\"| {}",
+                        mermaid_id(&edge.source),
+                        escape_mermaid(&label),
+                        mermaid_id(&edge.target)
+                    );
+                }
+                None => {
+                    let _ = writeln!(
+                        out,
+                        "    {} --> {}",
+                        mermaid_id(&edge.source),
+                        mermaid_id(&edge.target)
+                    );
+                }
+            }
+        }
+
+        if let Some(statuses) = node_status {
+            out.push_str("    classDef running fill:#fff3cd,stroke:#d39e00\n");
+            out.push_str("    classDef completed fill:#d4edda,stroke:#28a745\n");
+            out.push_str("    classDef failed fill:#f8d7da,stroke:#dc3545\n");
+
+            for node in &nodes {
+                let status = statuses.get(&node.id).copied().unwrap_or(node.status);
+                if let Some(class) = mermaid_class(status) {
+                    let _ = writeln!(out, "    class {} {}", mermaid_id(&node.id), class);
+                }
+            }
+        }
+
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::definition::WorkflowBuilder;
+    use crate::model::edge::EdgeId;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_to_dot() {
+        let a = Node::new(NodeId::new(), "Fetch".to_string());
+        let b = Node::new(NodeId::new(), "Parse \"raw\"".to_string());
+        let c = Node::new(NodeId::new(), "Store".to_string());
+        let d = Node::new(NodeId::new(), "Notify".to_string());
+        let (a_id, b_id, c_id, d_id) = (a.id.clone(), b.id.clone(), c.id.clone(), d.id.clone());
+
+        let workflow = WorkflowBuilder::new("Pipeline")
+            .add_node(a)
+            .unwrap()
+            .add_node(b)
+            .unwrap()
+            .add_node(c)
+            .unwrap()
+            .add_node(d)
+            .unwrap()
+            .add_edge(Edge::new(EdgeId::new(), a_id.clone(), b_id.clone()))
+            .unwrap()
+            .add_edge(Edge::new(EdgeId::new(), b_id.clone(), c_id.clone()))
+            .unwrap()
+            .add_edge(
+                Edge::new(EdgeId::new(), a_id.clone(), d_id.clone()).with_json_path("$.notify"),
+            )
+            .unwrap()
+            .build();
+
+        let dot = workflow.to_dot();
+        assert!(dot.starts_with("digraph \"Pipeline\" {"));
+        for (id, name) in [
+            (&a_id, "Fetch"),
+            (&b_id, "Parse \\\"raw\\\""),
+            (&c_id, "Store"),
+            (&d_id, "Notify"),
+        ] {
+            assert!(dot.contains(&format!("\"{}\" [label=\"{}\"]", id, name)));
+        }
+        assert!(dot.contains(&format!("\"{}\" -> \"{}\";", a_id, b_id)));
+        assert!(dot.contains(&format!("\"{}\" -> \"{}\";", b_id, c_id)));
+        assert!(dot.contains(&format!(
+            "\"{}\" -> \"{}\" [label=\"$.notify\"];",
+            a_id, d_id
+        )));
+        assert!(!dot.contains("fillcolor"));
+        assert!(!dot.contains("penwidth"));
+
+        // Running instance colors and the critical path
+        let mut state = WorkflowState::new(Arc::new(workflow.clone()));
+        state.set_node_running(&a_id).unwrap();
+        state
+            .set_node_completed(&a_id, serde_json::json!({}))
+            .unwrap();
+        let dot = workflow.to_dot_with(&DotOptions::new().with_state(&state).with_critical_path());
+        assert!(dot.contains(&format!(
+            "\"{}\" [label=\"Fetch\", style=\"rounded,filled\", fillcolor=\"palegreen\", tooltip=\"Completed\", color=\"red\", penwidth=2];",
+            a_id
+        )));
+        assert!(dot.contains(&format!(
+            "\"{}\" [label=\"Notify\", style=\"rounded,filled\", fillcolor=\"white\", tooltip=\"Pending\"];",
+            d_id
+        )));
+        assert!(dot.contains(&format!(
+            "\"{}\" -> \"{}\" [color=\"red\", penwidth=2];",
+            b_id, c_id
+        )));
+    }
+
+    #[test]
+    fn test_to_mermaid() {
+        let a = Node::new(NodeId::new(), "Fetch".to_string());
+        let b = Node::new(NodeId::new(), "Say \"hi\"".to_string());
+        let c = Node::new(NodeId::new(), "Store".to_string());
+        let (a_id, b_id, c_id) = (a.id.clone(), b.id.clone(), c.id.clone());
+
+        let workflow = WorkflowBuilder::new("Pipeline")
+            .add_node(a)
+            .unwrap()
+            .add_node(b)
+            .unwrap()
+            .add_node(c)
+            .unwrap()
+            .add_edge(Edge::new(EdgeId::new(), a_id.clone(), b_id.clone()))
+            .unwrap()
+            .add_edge(Edge::new(EdgeId::new(), a_id.clone(), c_id.clone()).with_expression("x > 1"))
+            .unwrap()
+            .build();
+
+        let (a_key, b_key, c_key) = (mermaid_id(&a_id), mermaid_id(&b_id), mermaid_id(&c_id));
+        for key in [&a_key, &b_key, &c_key] {
+            assert!(key.chars().all(|c| c.is_ascii_alphanumeric()));
+            assert!(key.starts_with('n'));
+        }
+
+        let mermaid = workflow.to_mermaid();
+        assert!(mermaid.starts_with("flowchart TD\n"));
+        assert!(mermaid.contains(&format!("    {}[\"Fetch\"]", a_key)));
+        assert!(mermaid.contains(&format!("    {}[\"Say #quot;hi#quot;\"]", b_key)));
+        assert!(mermaid.contains(&format!("    {} --> {}", a_key, b_key)));
+        assert!(mermaid.contains(&format!("    {} -->|\"x > 1\"| {}", a_key, c_key)));
+        assert!(!mermaid.contains("classDef"));
+
+        // A running instance gets per-status classes
+        let mut state = WorkflowState::new(Arc::new(workflow.clone()));
+        state.set_node_running(&a_id).unwrap();
+        state
+            .set_node_completed(&a_id, serde_json::json!({}))
+            .unwrap();
+        state.set_node_running(&b_id).unwrap();
+        state.set_node_running(&c_id).unwrap();
+        state
+            .set_node_failed(&c_id, serde_json::json!("boom"))
+            .unwrap();
+
+        let mermaid = workflow.to_mermaid_with_state(&state);
+        assert!(mermaid.contains("classDef completed"));
+        assert!(mermaid.contains(&format!("    class {} completed", a_key)));
+        assert!(mermaid.contains(&format!("    class {} running", b_key)));
+        assert!(mermaid.contains(&format!("    class {} failed", c_key)));
+    }
+}
@@ -0,0 +1,274 @@
+use crate::model::node::NodeId;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// Error types for switch routing
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum SwitchError {
+    #[error("Switch has no branches")]
+    NoBranches,
+
+    #[error("Total branch weight must be greater than zero")]
+    ZeroWeight,
+
+    #[error("No branch matched value at '{0}'")]
+    NoMatch(String),
+}
+
+/// How a switch node selects its branch
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SwitchMode {
+    /// Select the branch whose value equals the input value at the given dotted path
+    Match {
+        /// Dotted path into the input (e.g. "order.region")
+        path: String,
+    },
+
+    /// Select a branch at random, proportionally to the branch weights
+    WeightedRandom {
+        /// Optional seed for reproducible selection
+        seed: Option<u64>,
+    },
+}
+
+/// A branch of a switch node
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SwitchBranch {
+    /// Node to route to when this branch is selected
+    pub target: NodeId,
+
+    /// Value to match in `Match` mode
+    pub value: Option<serde_json::Value>,
+
+    /// Relative weight in `WeightedRandom` mode
+    pub weight: u32,
+}
+
+impl SwitchBranch {
+    /// Create a branch that matches an exact value
+    pub fn matching(target: NodeId, value: serde_json::Value) -> Self {
+        SwitchBranch {
+            target,
+            value: Some(value),
+            weight: 1,
+        }
+    }
+
+    /// Create a branch with a routing weight
+    pub fn weighted(target: NodeId, weight: u32) -> Self {
+        SwitchBranch {
+            target,
+            value: None,
+            weight,
+        }
+    }
+}
+
+/// Configuration of a switch node, stored in the node's config
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SwitchConfig {
+    /// Selection mode
+    pub mode: SwitchMode,
+
+    /// Candidate branches
+    pub branches: Vec<SwitchBranch>,
+
+    /// Branch taken when nothing matches in `Match` mode
+    pub default: Option<NodeId>,
+}
+
+impl SwitchConfig {
+    /// Create an exact-match switch on the value at `path`
+    pub fn matching(path: &str) -> Self {
+        SwitchConfig {
+            mode: SwitchMode::Match {
+                path: path.to_string(),
+            },
+            branches: Vec::new(),
+            default: None,
+        }
+    }
+
+    /// Create a weighted random switch
+    pub fn weighted_random(seed: Option<u64>) -> Self {
+        SwitchConfig {
+            mode: SwitchMode::WeightedRandom { seed },
+            branches: Vec::new(),
+            default: None,
+        }
+    }
+
+    /// Add a branch
+    pub fn with_branch(mut self, branch: SwitchBranch) -> Self {
+        self.branches.push(branch);
+        self
+    }
+
+    /// Set the default branch
+    pub fn with_default(mut self, target: NodeId) -> Self {
+        self.default = Some(target);
+        self
+    }
+
+    /// Convert this switch into a node config value
+    pub fn to_config(&self) -> serde_json::Value {
+        serde_json::json!({ "switch": self })
+    }
+
+    /// Read a switch from a node config value
+    pub fn from_config(config: &serde_json::Value) -> Option<Self> {
+        config
+            .get("switch")
+            .and_then(|switch| serde_json::from_value(switch.clone()).ok())
+    }
+}
+
+/// Stateful router that evaluates a switch configuration
+pub struct SwitchRouter {
+    /// Switch configuration
+    config: SwitchConfig,
+
+    /// Random source for weighted selection
+    rng: StdRng,
+}
+
+impl SwitchRouter {
+    /// Create a router for a switch configuration
+    ///
+    /// Weighted random switches with a seed produce the same sequence of
+    /// selections every time; without a seed the router is seeded from entropy.
+    pub fn new(config: SwitchConfig) -> Result<Self, SwitchError> {
+        if config.branches.is_empty() && config.default.is_none() {
+            return Err(SwitchError::NoBranches);
+        }
+
+        let rng = match &config.mode {
+            SwitchMode::WeightedRandom { seed } => {
+                if config.branches.iter().map(|b| b.weight as u64).sum::<u64>() == 0 {
+                    return Err(SwitchError::ZeroWeight);
+                }
+                match seed {
+                    Some(seed) => StdRng::seed_from_u64(*seed),
+                    None => StdRng::from_entropy(),
+                }
+            }
+            SwitchMode::Match { .. } => StdRng::seed_from_u64(0),
+        };
+
+        Ok(SwitchRouter { config, rng })
+    }
+
+    /// Get the switch configuration
+    pub fn config(&self) -> &SwitchConfig {
+        &self.config
+    }
+
+    /// Select the branch target for an input
+    pub fn route(&mut self, input: &serde_json::Value) -> Result<NodeId, SwitchError> {
+        match &self.config.mode {
+            SwitchMode::Match { path } => {
+                let pointer = format!("/{}", path.replace('.', "/"));
+                let value = input.pointer(&pointer);
+
+                self.config
+                    .branches
+                    .iter()
+                    .find(|branch| value.is_some() && branch.value.as_ref() == value)
+                    .map(|branch| branch.target.clone())
+                    .or_else(|| self.config.default.clone())
+                    .ok_or_else(|| SwitchError::NoMatch(path.clone()))
+            }
+            SwitchMode::WeightedRandom { .. } => {
+                let total: u64 = self.config.branches.iter().map(|b| b.weight as u64).sum();
+                let mut pick = self.rng.gen_range(0..total);
+
+                for branch in &self.config.branches {
+                    let weight = branch.weight as u64;
+                    if pick < weight {
+                        return Ok(branch.target.clone());
+                    }
+                    pick -= weight;
+                }
+
+                // Unreachable as long as the weights sum to `total`
+                Err(SwitchError::ZeroWeight)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_match_switch() {
+        let eu = NodeId::new();
+        let us = NodeId::new();
+        let other = NodeId::new();
+
+        let config = SwitchConfig::matching("order.region")
+            .with_branch(SwitchBranch::matching(eu.clone(), serde_json::json!("eu")))
+            .with_branch(SwitchBranch::matching(us.clone(), serde_json::json!("us")))
+            .with_default(other.clone());
+        let mut router = SwitchRouter::new(config).unwrap();
+
+        let input = serde_json::json!({"order": {"region": "us"}});
+        assert_eq!(router.route(&input).unwrap(), us);
+
+        let input = serde_json::json!({"order": {"region": "apac"}});
+        assert_eq!(router.route(&input).unwrap(), other);
+    }
+
+    #[test]
+    fn test_weighted_random_switch_distribution() {
+        let a = NodeId::new();
+        let b = NodeId::new();
+        let c = NodeId::new();
+
+        let config = SwitchConfig::weighted_random(Some(42))
+            .with_branch(SwitchBranch::weighted(a.clone(), 70))
+            .with_branch(SwitchBranch::weighted(b.clone(), 20))
+            .with_branch(SwitchBranch::weighted(c.clone(), 10));
+
+        // Route many times with a fixed seed
+        let mut router = SwitchRouter::new(config.clone()).unwrap();
+        let runs = 10_000;
+        let mut counts = std::collections::HashMap::new();
+        let mut picks = Vec::with_capacity(runs);
+        for _ in 0..runs {
+            let target = router.route(&serde_json::Value::Null).unwrap();
+            *counts.entry(target.clone()).or_insert(0usize) += 1;
+            picks.push(target);
+        }
+
+        // Distribution follows the weights
+        let share = |id: &NodeId| counts.get(id).copied().unwrap_or(0) as f64 / runs as f64;
+        assert!((share(&a) - 0.70).abs() < 0.02, "a: {}", share(&a));
+        assert!((share(&b) - 0.20).abs() < 0.02, "b: {}", share(&b));
+        assert!((share(&c) - 0.10).abs() < 0.02, "c: {}", share(&c));
+
+        // The same seed reproduces the same selections
+        let mut replay = SwitchRouter::new(config).unwrap();
+        for expected in picks.iter().take(100) {
+            assert_eq!(&replay.route(&serde_json::Value::Null).unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn test_switch_config_round_trip() {
+        let config = SwitchConfig::weighted_random(Some(7))
+            .with_branch(SwitchBranch::weighted(NodeId::new(), 1));
+        let restored = SwitchConfig::from_config(&config.to_config()).unwrap();
+        assert_eq!(restored, config);
+
+        let invalid = SwitchConfig::weighted_random(None)
+            .with_branch(SwitchBranch::weighted(NodeId::new(), 0));
+        assert_eq!(
+            SwitchRouter::new(invalid).err(),
+            Some(SwitchError::ZeroWeight)
+        );
+    }
+}
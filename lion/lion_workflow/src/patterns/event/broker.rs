@@ -1,15 +1,18 @@
 use crate::patterns::event::retry::RetryManager;
 use crate::patterns::event::store::EventStore;
-use crate::patterns::event::subscription::{EventSubscription, SubscriptionManager};
+use crate::patterns::event::subscription::{
+    EventSubscription, SubscriptionHandle, SubscriptionManager,
+};
 use crate::patterns::event::types::{
     DeliverySemantic, Event, EventAck, EventBrokerConfig, EventError, EventStatus,
+    SubscriberLagPolicy,
 };
 
 use log;
 use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
-use std::time::Duration;
-use tokio::sync::{mpsc, RwLock};
+use std::time::{Duration, Instant};
+use tokio::sync::{mpsc, oneshot, RwLock};
 use tokio::time::timeout;
 
 /// Event broker for managing event distribution
@@ -26,11 +29,20 @@ pub struct EventBroker {
     /// Processed event IDs (for deduplication)
     processed_events: RwLock<HashSet<String>>,
 
+    /// Producer-assigned event IDs and when they were first published
+    published_ids: RwLock<HashMap<String, Instant>>,
+
+    /// Next member to receive an event, by event type and consumer group
+    group_cursors: RwLock<HashMap<(String, String), usize>>,
+
     /// Event store (for persistence and replay)
     event_store: Option<Arc<dyn EventStore>>,
 
     /// Retry manager
     retry_manager: Arc<RetryManager>,
+
+    /// Callers awaiting the response to an event, by the event's ID
+    pending_responses: Arc<RwLock<HashMap<String, oneshot::Sender<Event>>>>,
 }
 
 impl EventBroker {
@@ -46,8 +58,11 @@ impl EventBroker {
             subscriptions: RwLock::new(HashMap::new()),
             in_flight: RwLock::new(HashMap::new()),
             processed_events: RwLock::new(HashSet::new()),
+            published_ids: RwLock::new(HashMap::new()),
+            group_cursors: RwLock::new(HashMap::new()),
             event_store: None,
             retry_manager,
+            pending_responses: Arc::new(RwLock::new(HashMap::new())),
         }
     }
 
@@ -95,6 +110,52 @@ impl EventBroker {
         Ok((event_rx, ack_tx))
     }
 
+    /// Subscribe to events as a member of a consumer group
+    ///
+    /// Each event is delivered to one member of the group, rotating between
+    /// members, while other groups and ungrouped subscribers each get their
+    /// own copy.
+    pub async fn subscribe_group(
+        &self,
+        event_type: &str,
+        group: &str,
+        subscriber_id: &str,
+        capability: Option<lion_core::CapabilityId>,
+    ) -> Result<(mpsc::Receiver<Event>, mpsc::Sender<EventAck>), EventError> {
+        let config = self.config.read().await;
+        let buffer_size = config.channel_buffer_size;
+
+        let (mut subscription, event_rx, ack_tx) =
+            <Self as SubscriptionManager>::create_subscription(
+                event_type,
+                subscriber_id,
+                capability,
+                buffer_size,
+            );
+        subscription.group = Some(group.to_string());
+
+        let mut subs = self.subscriptions.write().await;
+        subs.entry(event_type.to_string())
+            .or_insert_with(Vec::new)
+            .push(subscription);
+
+        Ok((event_rx, ack_tx))
+    }
+
+    /// Get a handle to pause and resume a subscriber's subscription to an
+    /// event type
+    pub async fn subscription_handle(
+        &self,
+        event_type: &str,
+        subscriber_id: &str,
+    ) -> Option<SubscriptionHandle> {
+        let subs = self.subscriptions.read().await;
+        subs.get(event_type)?
+            .iter()
+            .find(|subscription| subscription.subscriber_id == subscriber_id)
+            .map(|subscription| subscription.handle())
+    }
+
     /// Publish an event
     pub async fn publish(&self, event: Event) -> Result<EventStatus, EventError> {
         // Check if event already processed (for exactly-once)
@@ -120,6 +181,13 @@ impl EventBroker {
                 .map_err(|e| EventError::Other(format!("Failed to store event: {}", e)))?;
         }
 
+        // Hand responses to callers awaiting them
+        if let Some(causation_id) = &event.causation_id {
+            if let Some(waiter) = self.pending_responses.write().await.remove(causation_id) {
+                let _ = waiter.send(event.clone());
+            }
+        }
+
         // Find subscribers
         let subs = self.subscriptions.read().await;
         let subscribers = subs.get(&event.event_type);
@@ -141,8 +209,9 @@ impl EventBroker {
                 in_flight.insert(event.id.clone(), event.clone());
             }
 
-            // Send to subscribers
+            // Send to subscribers; group members are collected and served below
             let mut sent = false;
+            let mut groups: Vec<(&str, Vec<&EventSubscription>)> = Vec::new();
             for subscription in subscribers {
                 // Check capability if required
                 if let Some(req_cap) = &event.required_capability {
@@ -157,8 +226,28 @@ impl EventBroker {
                     }
                 }
 
+                if let Some(group) = &subscription.group {
+                    match groups.iter_mut().find(|(name, _)| name == group) {
+                        Some((_, members)) => members.push(subscription),
+                        None => groups.push((group, vec![subscription])),
+                    }
+                    continue;
+                }
+
                 // Send event
-                if subscription.sender.try_send(event.clone()).is_ok() {
+                let delivered = match config.lag_policy {
+                    SubscriberLagPolicy::Drop => subscription.deliver(event.clone()),
+                    SubscriberLagPolicy::Wait => subscription.deliver_waiting(event.clone()).await,
+                };
+                if !delivered {
+                    subscription.record_dropped();
+                    log::warn!(
+                        "Subscriber {} missed event {}",
+                        subscription.subscriber_id,
+                        event.id
+                    );
+                }
+                if delivered {
                     sent = true;
 
                     // If at-most-once, one subscriber is enough
@@ -168,6 +257,51 @@ impl EventBroker {
                 }
             }
 
+            // Send one copy to each group, rotating between its members
+            if !(sent && config.delivery_semantic == DeliverySemantic::AtMostOnce) {
+                let mut cursors = self.group_cursors.write().await;
+                for (group, members) in groups {
+                    let cursor = cursors
+                        .entry((event.event_type.clone(), group.to_string()))
+                        .or_insert(0);
+                    // Prefer members that are not paused
+                    let start = *cursor % members.len();
+                    let order: Vec<usize> = (0..members.len())
+                        .map(|offset| (start + offset) % members.len())
+                        .collect();
+                    let mut delivered_to = order
+                        .iter()
+                        .copied()
+                        .filter(|&index| !members[index].is_paused())
+                        .find(|&index| members[index].deliver(event.clone()))
+                        .or_else(|| {
+                            order
+                                .iter()
+                                .copied()
+                                .find(|&index| members[index].deliver(event.clone()))
+                        });
+                    // Every member is full; wait on the next in turn or drop
+                    if delivered_to.is_none() {
+                        if config.lag_policy == SubscriberLagPolicy::Wait
+                            && members[start].deliver_waiting(event.clone()).await
+                        {
+                            delivered_to = Some(start);
+                        } else {
+                            members[start].record_dropped();
+                            log::warn!("Consumer group {} missed event {}", group, event.id);
+                        }
+                    }
+
+                    if let Some(index) = delivered_to {
+                        *cursor = index + 1;
+                        sent = true;
+                        if config.delivery_semantic == DeliverySemantic::AtMostOnce {
+                            break;
+                        }
+                    }
+                }
+            }
+
             if sent {
                 // Start ack handler if needed
                 if config.delivery_semantic != DeliverySemantic::AtMostOnce && event.requires_ack {
@@ -194,6 +328,72 @@ impl EventBroker {
         }
     }
 
+    /// Publish an event and wait for the first event it causes
+    ///
+    /// Responses are matched by `causation_id`, as set by
+    /// `Event::create_response`, so concurrent callers each get the response
+    /// to their own event. Responses are still delivered to subscribers as
+    /// usual.
+    pub async fn publish_and_await(
+        &self,
+        event: Event,
+        wait: Duration,
+    ) -> Result<Event, EventError> {
+        let event_id = event.id.clone();
+        let (response_tx, response_rx) = oneshot::channel();
+        self.pending_responses
+            .write()
+            .await
+            .insert(event_id.clone(), response_tx);
+
+        if let Err(e) = self.publish(event).await {
+            self.pending_responses.write().await.remove(&event_id);
+            return Err(e);
+        }
+
+        match timeout(wait, response_rx).await {
+            Ok(response) => response.map_err(|_| EventError::ChannelClosed),
+            Err(_) => {
+                self.pending_responses.write().await.remove(&event_id);
+                Err(EventError::Timeout(format!(
+                    "No response to event {} within {:?}",
+                    event_id, wait
+                )))
+            }
+        }
+    }
+
+    /// Publish an event under a producer-assigned ID
+    ///
+    /// Publishing an ID already seen within the configured dedup window is
+    /// ignored and returns `EventStatus::Duplicate`, so producers can safely
+    /// retry. A publish that fails does not count as seen.
+    pub async fn publish_with_id(
+        &self,
+        id: &str,
+        mut event: Event,
+    ) -> Result<EventStatus, EventError> {
+        let window = self.config.read().await.publish_dedup_window;
+
+        {
+            let mut published = self.published_ids.write().await;
+            let now = Instant::now();
+            published.retain(|_, first_seen| now.duration_since(*first_seen) < window);
+            if published.contains_key(id) {
+                log::debug!("Duplicate publish of event {} ignored", id);
+                return Ok(EventStatus::Duplicate);
+            }
+            published.insert(id.to_string(), now);
+        }
+
+        event.id = id.to_string();
+        let result = self.publish(event).await;
+        if result.is_err() {
+            self.published_ids.write().await.remove(id);
+        }
+        result
+    }
+
     /// Process acknowledgment for an event
     async fn process_acknowledgment(&self, event_id: String) {
         let ack_timeout = {
@@ -482,13 +682,26 @@ impl Clone for EventBroker {
             RwLock::new(guard.clone())
         };
 
+        let published_ids = {
+            let guard = futures::executor::block_on(self.published_ids.read());
+            RwLock::new(guard.clone())
+        };
+
+        let group_cursors = {
+            let guard = futures::executor::block_on(self.group_cursors.read());
+            RwLock::new(guard.clone())
+        };
+
         EventBroker {
             config: Arc::clone(&self.config),
             subscriptions,
             in_flight,
             processed_events,
+            published_ids,
+            group_cursors,
             event_store: self.event_store.clone(),
             retry_manager: self.retry_manager.clone(),
+            pending_responses: Arc::clone(&self.pending_responses),
         }
     }
 }
@@ -580,4 +793,234 @@ mod tests {
         // Verify the retry manager queue is empty
         assert!(broker.retry_manager.is_empty().await);
     }
+
+    #[tokio::test]
+    async fn test_publish_with_id_dedupes() {
+        let config = EventBrokerConfig {
+            publish_dedup_window: Duration::from_millis(100),
+            ..Default::default()
+        };
+        let broker = EventBroker::new(config);
+        let (mut event_rx, _ack_tx) = broker
+            .subscribe("order_placed", "billing", None)
+            .await
+            .unwrap();
+
+        let event = |n: u32| {
+            let mut event = Event::new("order_placed", serde_json::json!({ "attempt": n }));
+            event.requires_ack = false;
+            event
+        };
+
+        // A retried publish with the same id is delivered once
+        let status = broker.publish_with_id("order-1", event(1)).await.unwrap();
+        assert_eq!(status, EventStatus::Sent);
+        let status = broker.publish_with_id("order-1", event(2)).await.unwrap();
+        assert_eq!(status, EventStatus::Duplicate);
+
+        let received = event_rx.recv().await.unwrap();
+        assert_eq!(received.id, "order-1");
+        assert_eq!(received.payload["attempt"], 1);
+        assert!(event_rx.try_recv().is_err());
+
+        // Other ids are unaffected
+        let status = broker.publish_with_id("order-2", event(1)).await.unwrap();
+        assert_eq!(status, EventStatus::Sent);
+        assert_eq!(event_rx.recv().await.unwrap().id, "order-2");
+
+        // Once the window has passed the id can be published again
+        tokio::time::sleep(Duration::from_millis(150)).await;
+        let status = broker.publish_with_id("order-1", event(3)).await.unwrap();
+        assert_eq!(status, EventStatus::Sent);
+        assert_eq!(event_rx.recv().await.unwrap().payload["attempt"], 3);
+    }
+
+    #[tokio::test]
+    async fn test_consumer_groups_share_events() {
+        let broker = EventBroker::new(EventBrokerConfig::default());
+        let (mut first_rx, _first_ack) = broker
+            .subscribe_group("order_placed", "billing", "billing-1", None)
+            .await
+            .unwrap();
+        let (mut second_rx, _second_ack) = broker
+            .subscribe_group("order_placed", "billing", "billing-2", None)
+            .await
+            .unwrap();
+        let (mut shipping_rx, _shipping_ack) = broker
+            .subscribe_group("order_placed", "shipping", "shipping-1", None)
+            .await
+            .unwrap();
+        let (mut audit_rx, _audit_ack) = broker
+            .subscribe("order_placed", "audit", None)
+            .await
+            .unwrap();
+
+        let mut published = HashSet::new();
+        for n in 0..6 {
+            let mut event = Event::new("order_placed", serde_json::json!({ "n": n }));
+            event.requires_ack = false;
+            published.insert(event.id.clone());
+            assert_eq!(broker.publish(event).await.unwrap(), EventStatus::Sent);
+        }
+
+        let drain = |rx: &mut mpsc::Receiver<Event>| {
+            let mut ids = HashSet::new();
+            while let Ok(event) = rx.try_recv() {
+                ids.insert(event.id);
+            }
+            ids
+        };
+        let first = drain(&mut first_rx);
+        let second = drain(&mut second_rx);
+
+        // Group members split the events between them
+        assert_eq!(first.len(), 3);
+        assert_eq!(second.len(), 3);
+        assert!(first.is_disjoint(&second));
+        assert_eq!(&first | &second, published);
+
+        // Every other group and ungrouped subscriber gets a full copy
+        assert_eq!(drain(&mut shipping_rx), published);
+        assert_eq!(drain(&mut audit_rx), published);
+    }
+
+    #[tokio::test]
+    async fn test_paused_subscription_delivers_on_resume() {
+        let config = EventBrokerConfig {
+            channel_buffer_size: 3,
+            ..Default::default()
+        };
+        let broker = EventBroker::new(config);
+        let (mut event_rx, _ack_tx) = broker
+            .subscribe("order_placed", "billing", None)
+            .await
+            .unwrap();
+        let handle = broker
+            .subscription_handle("order_placed", "billing")
+            .await
+            .unwrap();
+
+        let event = |n: u32| {
+            let mut event = Event::new("order_placed", serde_json::json!({ "n": n }));
+            event.requires_ack = false;
+            event
+        };
+
+        // Events are held back while paused, up to the buffer size
+        handle.pause();
+        for n in 0..3 {
+            assert_eq!(broker.publish(event(n)).await.unwrap(), EventStatus::Sent);
+        }
+        assert!(broker.publish(event(3)).await.is_err());
+        assert!(event_rx.try_recv().is_err());
+        assert_eq!(handle.buffered_count(), 3);
+
+        // Resuming delivers them in publication order
+        handle.resume().await.unwrap();
+        assert!(!handle.is_paused());
+        for n in 0..3 {
+            assert_eq!(event_rx.recv().await.unwrap().payload["n"], n);
+        }
+
+        // Later events flow directly again
+        broker.publish(event(4)).await.unwrap();
+        assert_eq!(event_rx.recv().await.unwrap().payload["n"], 4);
+    }
+
+    #[tokio::test]
+    async fn test_publish_and_await_matches_responses() {
+        let config = EventBrokerConfig {
+            delivery_semantic: DeliverySemantic::AtMostOnce,
+            ..Default::default()
+        };
+        let broker = Arc::new(EventBroker::new(config));
+        let (mut requests, _ack_tx) = broker.subscribe("task", "worker", None).await.unwrap();
+
+        // Answer both tasks, in the reverse order they arrived
+        let worker = {
+            let broker = Arc::clone(&broker);
+            tokio::spawn(async move {
+                let first = requests.recv().await.unwrap();
+                let second = requests.recv().await.unwrap();
+                for task in [second, first] {
+                    let response = task.create_response("task_done", task.payload.clone());
+                    broker.publish(response).await.unwrap();
+                }
+            })
+        };
+
+        let wait = Duration::from_secs(5);
+        let (a, b) = tokio::join!(
+            broker.publish_and_await(Event::new("task", serde_json::json!("a")), wait),
+            broker.publish_and_await(Event::new("task", serde_json::json!("b")), wait),
+        );
+        assert_eq!(a.unwrap().payload, serde_json::json!("a"));
+        assert_eq!(b.unwrap().payload, serde_json::json!("b"));
+        worker.await.unwrap();
+
+        // Without a responder the wait times out
+        let result = broker
+            .publish_and_await(
+                Event::new("unanswered", serde_json::json!(null)),
+                Duration::from_millis(20),
+            )
+            .await;
+        assert!(matches!(result, Err(EventError::Timeout(_))));
+        assert!(broker.pending_responses.read().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_lagging_subscriber_drops_are_counted() {
+        let config = EventBrokerConfig {
+            delivery_semantic: DeliverySemantic::AtMostOnce,
+            channel_buffer_size: 2,
+            ..Default::default()
+        };
+        let broker = EventBroker::new(config);
+        let (mut events, _ack_tx) = broker.subscribe("tick", "ui", None).await.unwrap();
+        let handle = broker.subscription_handle("tick", "ui").await.unwrap();
+
+        for n in 0..5 {
+            let _ = broker
+                .publish(Event::new("tick", serde_json::json!(n)))
+                .await;
+        }
+
+        // The subscriber learns it lagged and by how much
+        assert_eq!(handle.dropped_count(), 3);
+        assert_eq!(events.recv().await.unwrap().payload, serde_json::json!(0));
+    }
+
+    #[tokio::test]
+    async fn test_wait_policy_applies_backpressure() {
+        let config = EventBrokerConfig {
+            delivery_semantic: DeliverySemantic::AtMostOnce,
+            channel_buffer_size: 1,
+            lag_policy: SubscriberLagPolicy::Wait,
+            ..Default::default()
+        };
+        let broker = Arc::new(EventBroker::new(config));
+        let (mut events, _ack_tx) = broker.subscribe("tick", "ui", None).await.unwrap();
+        let handle = broker.subscription_handle("tick", "ui").await.unwrap();
+
+        let publisher = {
+            let broker = Arc::clone(&broker);
+            tokio::spawn(async move {
+                for n in 0..5 {
+                    broker
+                        .publish(Event::new("tick", serde_json::json!(n)))
+                        .await
+                        .unwrap();
+                }
+            })
+        };
+
+        // A slow consumer still receives every event, in order
+        for n in 0..5 {
+            tokio::time::sleep(Duration::from_millis(2)).await;
+            assert_eq!(events.recv().await.unwrap().payload, serde_json::json!(n));
+        }
+        publisher.await.unwrap();
+        assert_eq!(handle.dropped_count(), 0);
+    }
 }
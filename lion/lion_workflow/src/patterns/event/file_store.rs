@@ -0,0 +1,304 @@
+use crate::patterns::event::store::{EventStore, InMemoryEventStore};
+use crate::patterns::event::types::Event;
+use async_trait::async_trait;
+use futures::Stream;
+use log::warn;
+use serde::{Deserialize, Serialize};
+use std::fs::{File, OpenOptions};
+use std::io::{BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+
+/// When a file event store forces its writes to disk
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyncPolicy {
+    /// Sync after every write, losing nothing on a crash
+    EveryEvent,
+
+    /// Sync once this many writes are pending
+    Batched(usize),
+
+    /// Sync on the first write after this much time since the last sync
+    Interval(Duration),
+}
+
+/// One line of the log file
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+enum LogRecord {
+    /// An event was stored
+    Store { event: Box<Event> },
+
+    /// An event was deleted
+    Delete { id: String },
+}
+
+/// Open log file and its sync bookkeeping
+struct LogWriter {
+    /// Buffered handle appending to the file
+    file: BufWriter<File>,
+
+    /// Writes since the last sync
+    pending: usize,
+
+    /// When the file was last synced
+    last_sync: Instant,
+}
+
+impl LogWriter {
+    /// Flush buffered writes and force them to disk
+    fn sync(&mut self) -> std::io::Result<()> {
+        self.file.flush()?;
+        self.file.get_ref().sync_data()?;
+        self.pending = 0;
+        self.last_sync = Instant::now();
+        Ok(())
+    }
+}
+
+/// Event store appending every change to a JSON Lines file
+///
+/// Events are served from memory; on open, the file is replayed to rebuild
+/// them, so a restarted broker can `replay_events` from where it stopped.
+/// How often writes are synced to disk is set by a `SyncPolicy`; writes not
+/// yet synced may be lost on a crash, and a record torn by one is dropped
+/// on the next open.
+pub struct FileEventStore {
+    /// Events replayed from and written to the file
+    memory: InMemoryEventStore,
+
+    /// Path of the log file
+    path: PathBuf,
+
+    /// Writer appending to the log file
+    writer: Mutex<LogWriter>,
+
+    /// When writes are synced
+    policy: SyncPolicy,
+}
+
+impl FileEventStore {
+    /// Open a log file, creating it if missing, and replay its events
+    pub async fn open(path: impl AsRef<Path>, policy: SyncPolicy) -> Result<Self, String> {
+        let path = path.as_ref().to_path_buf();
+        let mut file = OpenOptions::new()
+            .create(true)
+            .read(true)
+            .append(true)
+            .open(&path)
+            .map_err(|e| format!("Failed to open event log {}: {}", path.display(), e))?;
+
+        let mut contents = Vec::new();
+        file.read_to_end(&mut contents)
+            .map_err(|e| format!("Failed to read event log {}: {}", path.display(), e))?;
+
+        let memory = InMemoryEventStore::new();
+        let mut valid_len = 0;
+        for line in contents.split_inclusive(|&b| b == b'\n') {
+            let record = match serde_json::from_slice::<LogRecord>(line) {
+                Ok(record) => record,
+                // Only the last record can be torn by a crash mid-write
+                Err(e) if !line.ends_with(b"\n") => {
+                    warn!(
+                        "Dropping torn record at the end of {}: {}",
+                        path.display(),
+                        e
+                    );
+                    break;
+                }
+                Err(e) => {
+                    return Err(format!(
+                        "Corrupt record in event log {}: {}",
+                        path.display(),
+                        e
+                    ))
+                }
+            };
+            match record {
+                LogRecord::Store { event } => memory.store_event(&event).await?,
+                LogRecord::Delete { id } => memory.delete_event(&id).await?,
+            }
+            valid_len += line.len();
+        }
+
+        if valid_len < contents.len() {
+            file.set_len(valid_len as u64)
+                .map_err(|e| format!("Failed to truncate event log {}: {}", path.display(), e))?;
+        }
+
+        Ok(FileEventStore {
+            memory,
+            path,
+            writer: Mutex::new(LogWriter {
+                file: BufWriter::new(file),
+                pending: 0,
+                last_sync: Instant::now(),
+            }),
+            policy,
+        })
+    }
+
+    /// Path of the log file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Force every write so far to disk, whatever the sync policy
+    pub async fn sync(&self) -> Result<(), String> {
+        self.writer
+            .lock()
+            .await
+            .sync()
+            .map_err(|e| format!("Failed to sync event log {}: {}", self.path.display(), e))
+    }
+
+    /// Get the events sharing a correlation ID, in the order they were stored
+    pub async fn replay_for_correlation(&self, correlation_id: &str) -> Vec<Event> {
+        self.memory.replay_for_correlation(correlation_id).await
+    }
+
+    /// Stream stored events from a position, then events as they are stored
+    pub fn stream_since(&self, index: usize) -> impl Stream<Item = Event> + Send + 'static {
+        self.memory.stream_since(index)
+    }
+
+    /// Append a record to the file, syncing if the policy calls for it
+    async fn append(&self, record: &LogRecord) -> Result<(), String> {
+        let mut line = serde_json::to_vec(record)
+            .map_err(|e| format!("Failed to serialize event log record: {}", e))?;
+        line.push(b'\n');
+
+        let mut writer = self.writer.lock().await;
+        let io_error = |e| format!("Failed to write event log {}: {}", self.path.display(), e);
+        writer.file.write_all(&line).map_err(io_error)?;
+        writer.pending += 1;
+
+        let due = match self.policy {
+            SyncPolicy::EveryEvent => true,
+            SyncPolicy::Batched(size) => writer.pending >= size,
+            SyncPolicy::Interval(interval) => writer.last_sync.elapsed() >= interval,
+        };
+        if due {
+            writer.sync().map_err(io_error)?;
+        }
+        Ok(())
+    }
+}
+
+impl Drop for FileEventStore {
+    fn drop(&mut self) {
+        if let Err(e) = self.writer.get_mut().sync() {
+            warn!("Failed to sync event log {}: {}", self.path.display(), e);
+        }
+    }
+}
+
+#[async_trait]
+impl EventStore for FileEventStore {
+    async fn store_event(&self, event: &Event) -> Result<(), String> {
+        // Written to the file first, so memory never holds what disk lacks
+        self.append(&LogRecord::Store {
+            event: Box::new(event.clone()),
+        })
+        .await?;
+        self.memory.store_event(event).await
+    }
+
+    async fn load_event(&self, event_id: &str) -> Result<Event, String> {
+        self.memory.load_event(event_id).await
+    }
+
+    async fn load_all_events(&self) -> Result<Vec<Event>, String> {
+        self.memory.load_all_events().await
+    }
+
+    async fn load_events_by_types(&self, event_types: &[String]) -> Result<Vec<Event>, String> {
+        self.memory.load_events_by_types(event_types).await
+    }
+
+    async fn load_events_by_source(&self, source: &str) -> Result<Vec<Event>, String> {
+        self.memory.load_events_by_source(source).await
+    }
+
+    async fn load_events_by_correlation_id(
+        &self,
+        correlation_id: &str,
+    ) -> Result<Vec<Event>, String> {
+        self.memory
+            .load_events_by_correlation_id(correlation_id)
+            .await
+    }
+
+    async fn delete_event(&self, event_id: &str) -> Result<(), String> {
+        self.append(&LogRecord::Delete {
+            id: event_id.to_string(),
+        })
+        .await?;
+        self.memory.delete_event(event_id).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::patterns::event::{EventBroker, EventBrokerConfig};
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_reopen_replays_events() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("events.jsonl");
+
+        let events: Vec<Event> = (0..4)
+            .map(|n| Event::new("order_placed", serde_json::json!({ "n": n })))
+            .collect();
+        {
+            let store = FileEventStore::open(&path, SyncPolicy::Batched(3))
+                .await
+                .unwrap();
+            for event in &events {
+                store.store_event(event).await.unwrap();
+            }
+            store.delete_event(&events[1].id).await.unwrap();
+        }
+
+        // A crash mid-write leaves a torn record, which is dropped
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(br#"{"op":"store","event":{"id""#).unwrap();
+        drop(file);
+
+        let store = Arc::new(
+            FileEventStore::open(&path, SyncPolicy::EveryEvent)
+                .await
+                .unwrap(),
+        );
+        let ids: Vec<String> = store
+            .load_all_events()
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|e| e.id)
+            .collect();
+        assert_eq!(
+            ids,
+            vec![
+                events[0].id.clone(),
+                events[2].id.clone(),
+                events[3].id.clone()
+            ]
+        );
+
+        // Appends after recovery land on a clean line
+        let later = Event::new("order_shipped", serde_json::json!({}));
+        store.store_event(&later).await.unwrap();
+        let reopened = FileEventStore::open(&path, SyncPolicy::EveryEvent)
+            .await
+            .unwrap();
+        assert_eq!(reopened.load_all_events().await.unwrap().len(), 4);
+
+        // A broker rebuilds its state from the replayed events
+        let broker = EventBroker::new(EventBrokerConfig::default()).with_event_store(store);
+        assert_eq!(broker.replay_events(None).await.unwrap(), 4);
+    }
+}
@@ -1,6 +1,7 @@
 //! Event-driven workflow components
 
 pub mod broker;
+pub mod file_store;
 pub mod retry;
 pub mod store;
 pub mod subscription;
@@ -8,10 +9,13 @@ pub mod types;
 
 // Re-exports
 pub use broker::EventBroker;
+pub use file_store::{FileEventStore, SyncPolicy};
 pub use retry::RetryManager;
 pub use store::{EventStore, InMemoryEventStore};
-pub use subscription::{EventSubscription, SerializableSubscription};
-pub use types::{DeliverySemantic, Event, EventAck, EventError, EventPriority, EventStatus};
+pub use subscription::{EventSubscription, SerializableSubscription, SubscriptionHandle};
+pub use types::{
+    DeliverySemantic, Event, EventAck, EventError, EventPriority, EventStatus, SubscriberLagPolicy,
+};
 
 // Re-export the config to avoid the duplicate export warning
 pub use types::EventBrokerConfig;
@@ -1,7 +1,9 @@
 use crate::patterns::event::types::Event;
 use async_trait::async_trait;
+use futures::Stream;
 use std::collections::HashMap;
-use tokio::sync::RwLock;
+use std::sync::Arc;
+use tokio::sync::{watch, RwLock};
 
 /// Trait for event storage
 #[async_trait]
@@ -31,19 +33,152 @@ pub trait EventStore: Send + Sync + 'static {
     async fn delete_event(&self, event_id: &str) -> Result<(), String>;
 }
 
+/// Events in the order they were first stored
+#[derive(Default)]
+struct EventLog {
+    /// Events by position; deleted events leave a gap so positions are stable
+    records: Vec<Option<Event>>,
+
+    /// Position of each stored event by ID
+    positions: HashMap<String, usize>,
+
+    /// Positions of the events sharing each correlation ID, in order
+    by_correlation: HashMap<String, Vec<usize>>,
+}
+
+impl EventLog {
+    /// Iterate the stored events in order
+    fn events(&self) -> impl Iterator<Item = &Event> {
+        self.records.iter().flatten()
+    }
+
+    /// Store an event, replacing any stored with the same ID in place
+    ///
+    /// Returns whether the event was appended rather than replaced.
+    fn store(&mut self, event: &Event) -> bool {
+        let (position, appended) = match self.positions.get(&event.id) {
+            Some(&position) => (position, false),
+            None => {
+                let position = self.records.len();
+                self.records.push(None);
+                self.positions.insert(event.id.clone(), position);
+                (position, true)
+            }
+        };
+
+        let previous = self.records[position].replace(event.clone());
+        let previous_correlation = previous.and_then(|previous| previous.correlation_id);
+        if previous_correlation != event.correlation_id {
+            if let Some(correlation_id) = previous_correlation {
+                self.unindex(&correlation_id, position);
+            }
+            if let Some(correlation_id) = &event.correlation_id {
+                let positions = self
+                    .by_correlation
+                    .entry(correlation_id.clone())
+                    .or_default();
+                let at = positions.partition_point(|&p| p < position);
+                positions.insert(at, position);
+            }
+        }
+
+        appended
+    }
+
+    /// Delete an event, leaving a gap at its position
+    fn delete(&mut self, event_id: &str) {
+        let Some(position) = self.positions.remove(event_id) else {
+            return;
+        };
+        if let Some(correlation_id) = self.records[position]
+            .take()
+            .and_then(|event| event.correlation_id)
+        {
+            self.unindex(&correlation_id, position);
+        }
+    }
+
+    /// Remove a position from a correlation ID's index
+    fn unindex(&mut self, correlation_id: &str, position: usize) {
+        if let Some(positions) = self.by_correlation.get_mut(correlation_id) {
+            positions.retain(|&p| p != position);
+            if positions.is_empty() {
+                self.by_correlation.remove(correlation_id);
+            }
+        }
+    }
+}
+
 /// In-memory event store implementation
+///
+/// Events are kept in the order they were first stored, and loads return
+/// them in that order.
 pub struct InMemoryEventStore {
     /// Stored events
-    events: RwLock<HashMap<String, Event>>,
+    log: Arc<RwLock<EventLog>>,
+
+    /// Number of positions in the log, for waking streams
+    appended: watch::Sender<usize>,
 }
 
 impl InMemoryEventStore {
     /// Create a new in-memory event store
     pub fn new() -> Self {
         InMemoryEventStore {
-            events: RwLock::new(HashMap::new()),
+            log: Arc::new(RwLock::new(EventLog::default())),
+            appended: watch::Sender::new(0),
         }
     }
+
+    /// Get the events sharing a correlation ID, in the order they were stored
+    ///
+    /// Looked up through an index, so the cost does not grow with the size
+    /// of the store.
+    pub async fn replay_for_correlation(&self, correlation_id: &str) -> Vec<Event> {
+        let log = self.log.read().await;
+        log.by_correlation
+            .get(correlation_id)
+            .into_iter()
+            .flatten()
+            .filter_map(|&position| log.records[position].clone())
+            .collect()
+    }
+
+    /// Stream stored events from a position, then events as they are stored
+    ///
+    /// Position 0 is the first event ever stored; deleted events are
+    /// skipped, and storing an event again with the same ID replaces it in
+    /// place rather than yielding it again. The stream ends once the store
+    /// is dropped.
+    pub fn stream_since(&self, index: usize) -> impl Stream<Item = Event> + Send + 'static {
+        let log = Arc::downgrade(&self.log);
+        let appended = self.appended.subscribe();
+
+        futures::stream::unfold(
+            (log, appended, index),
+            |(log, mut appended, mut next)| async move {
+                loop {
+                    // Mark the current length seen before reading, so an
+                    // append after the read still wakes the stream
+                    appended.borrow_and_update();
+                    let found = {
+                        let records = log.upgrade()?;
+                        let records = records.read().await;
+                        let mut found = None;
+                        while found.is_none() && next < records.records.len() {
+                            found = records.records[next].clone();
+                            next += 1;
+                        }
+                        found
+                    };
+                    if let Some(event) = found {
+                        return Some((event, (log, appended, next)));
+                    }
+                    appended.changed().await.ok()?;
+                }
+            },
+        )
+    }
 }
 
 impl Default for InMemoryEventStore {
@@ -55,28 +190,30 @@ impl Default for InMemoryEventStore {
 #[async_trait]
 impl EventStore for InMemoryEventStore {
     async fn store_event(&self, event: &Event) -> Result<(), String> {
-        let mut events = self.events.write().await;
-        events.insert(event.id.clone(), event.clone());
+        let mut log = self.log.write().await;
+        if log.store(event) {
+            self.appended.send_replace(log.records.len());
+        }
         Ok(())
     }
 
     async fn load_event(&self, event_id: &str) -> Result<Event, String> {
-        let events = self.events.read().await;
-        events
+        let log = self.log.read().await;
+        log.positions
             .get(event_id)
-            .cloned()
+            .and_then(|&position| log.records[position].clone())
             .ok_or_else(|| format!("Event not found: {}", event_id))
     }
 
     async fn load_all_events(&self) -> Result<Vec<Event>, String> {
-        let events = self.events.read().await;
-        Ok(events.values().cloned().collect())
+        let log = self.log.read().await;
+        Ok(log.events().cloned().collect())
     }
 
     async fn load_events_by_types(&self, event_types: &[String]) -> Result<Vec<Event>, String> {
-        let events = self.events.read().await;
-        let filtered: Vec<Event> = events
-            .values()
+        let log = self.log.read().await;
+        let filtered: Vec<Event> = log
+            .events()
             .filter(|e| event_types.contains(&e.event_type))
             .cloned()
             .collect();
@@ -85,9 +222,9 @@ impl EventStore for InMemoryEventStore {
     }
 
     async fn load_events_by_source(&self, source: &str) -> Result<Vec<Event>, String> {
-        let events = self.events.read().await;
-        let filtered: Vec<Event> = events
-            .values()
+        let log = self.log.read().await;
+        let filtered: Vec<Event> = log
+            .events()
             .filter(|e| e.source == source)
             .cloned()
             .collect();
@@ -99,19 +236,11 @@ impl EventStore for InMemoryEventStore {
         &self,
         correlation_id: &str,
     ) -> Result<Vec<Event>, String> {
-        let events = self.events.read().await;
-        let filtered: Vec<Event> = events
-            .values()
-            .filter(|e| e.correlation_id.as_deref() == Some(correlation_id))
-            .cloned()
-            .collect();
-
-        Ok(filtered)
+        Ok(self.replay_for_correlation(correlation_id).await)
     }
 
     async fn delete_event(&self, event_id: &str) -> Result<(), String> {
-        let mut events = self.events.write().await;
-        events.remove(event_id);
+        self.log.write().await.delete(event_id);
         Ok(())
     }
 }
@@ -157,4 +286,84 @@ mod tests {
         assert_eq!(events.len(), 1);
         assert_eq!(events[0].id, event.id);
     }
+
+    #[tokio::test]
+    async fn test_stream_since_tails_new_events() {
+        use futures::StreamExt;
+
+        let store = InMemoryEventStore::new();
+        let first = Event::new("first", serde_json::json!(1));
+        let second = Event::new("second", serde_json::json!(2));
+        store.store_event(&first).await.unwrap();
+        store.store_event(&second).await.unwrap();
+
+        // Starts from the given position, skipping earlier events
+        let mut stream = Box::pin(store.stream_since(1));
+        assert_eq!(stream.next().await.unwrap().id, second.id);
+
+        // Then yields events stored concurrently, in order
+        let store = Arc::new(store);
+        let writer = {
+            let store = Arc::clone(&store);
+            tokio::spawn(async move {
+                for n in 3..6 {
+                    store
+                        .store_event(&Event::new("later", serde_json::json!(n)))
+                        .await
+                        .unwrap();
+                    tokio::task::yield_now().await;
+                }
+            })
+        };
+        for n in 3..6 {
+            assert_eq!(stream.next().await.unwrap().payload, serde_json::json!(n));
+        }
+        writer.await.unwrap();
+
+        // Dropping the store ends the stream
+        drop(store);
+        assert!(stream.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_replay_for_correlation_keeps_order() {
+        let store = InMemoryEventStore::new();
+        let mut expected: HashMap<&str, Vec<String>> = HashMap::new();
+        for n in 0..9 {
+            let correlation_id = ["order-1", "order-2", "order-3"][n % 3];
+            let event =
+                Event::new("step", serde_json::json!(n)).with_correlation_id(correlation_id);
+            expected
+                .entry(correlation_id)
+                .or_default()
+                .push(event.id.clone());
+            store.store_event(&event).await.unwrap();
+        }
+        store
+            .store_event(&Event::new("uncorrelated", serde_json::json!(null)))
+            .await
+            .unwrap();
+
+        let ids = |events: Vec<Event>| events.into_iter().map(|e| e.id).collect::<Vec<_>>();
+        assert_eq!(
+            ids(store.replay_for_correlation("order-2").await),
+            expected["order-2"]
+        );
+
+        // Deleted and re-correlated events leave the old correlation's replay
+        let moved = store.load_event(&expected["order-1"][1]).await.unwrap();
+        store.delete_event(&expected["order-1"][0]).await.unwrap();
+        store
+            .store_event(&moved.with_correlation_id("order-3"))
+            .await
+            .unwrap();
+        assert_eq!(
+            ids(store.replay_for_correlation("order-1").await),
+            expected["order-1"][2..]
+        );
+        let order_3 = ids(store.replay_for_correlation("order-3").await);
+        assert_eq!(order_3.len(), 4);
+        assert_eq!(order_3[1], expected["order-1"][1]);
+        assert!(store.replay_for_correlation("unknown").await.is_empty());
+    }
 }
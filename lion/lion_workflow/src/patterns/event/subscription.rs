@@ -1,8 +1,26 @@
 use crate::patterns::event::types::*;
 use lion_core::CapabilityId;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
 use tokio::sync::mpsc;
 use uuid::Uuid;
 
+/// Pause state shared by a subscription and its handles
+#[derive(Debug)]
+struct PauseState {
+    /// Whether delivery is paused
+    paused: bool,
+
+    /// Events held back while paused, oldest first
+    buffered: VecDeque<Event>,
+
+    /// Maximum number of events held back
+    capacity: usize,
+
+    /// Events that could not be delivered because the subscriber lagged
+    dropped: u64,
+}
+
 /// Event subscription
 #[derive(Debug)]
 pub struct EventSubscription {
@@ -15,6 +33,9 @@ pub struct EventSubscription {
     /// Subscriber ID
     pub subscriber_id: String,
 
+    /// Consumer group; each event goes to one member of the group
+    pub group: Option<String>,
+
     /// Subscription creation time
     pub created_at: chrono::DateTime<chrono::Utc>,
 
@@ -26,6 +47,130 @@ pub struct EventSubscription {
 
     /// Event acknowledgment receiver
     pub ack_receiver: mpsc::Receiver<EventAck>,
+
+    /// Pause state, shared with subscription handles
+    pause: Arc<Mutex<PauseState>>,
+}
+
+impl EventSubscription {
+    /// Whether delivery to this subscription is paused
+    pub fn is_paused(&self) -> bool {
+        self.pause.lock().unwrap().paused
+    }
+
+    /// Get a handle to pause and resume this subscription
+    pub fn handle(&self) -> SubscriptionHandle {
+        SubscriptionHandle {
+            subscription_id: self.id.clone(),
+            sender: self.sender.clone(),
+            pause: self.pause.clone(),
+        }
+    }
+
+    /// Deliver an event, holding it back if the subscription is paused
+    ///
+    /// Returns false if the event could not be delivered or held back.
+    pub(crate) fn deliver(&self, event: Event) -> bool {
+        let mut pause = self.pause.lock().unwrap();
+        if pause.paused {
+            if pause.buffered.len() >= pause.capacity {
+                return false;
+            }
+            pause.buffered.push_back(event);
+            return true;
+        }
+        self.sender.try_send(event).is_ok()
+    }
+
+    /// Deliver an event, waiting for room if the channel is full
+    ///
+    /// Returns false if the event could not be delivered or held back.
+    pub(crate) async fn deliver_waiting(&self, event: Event) -> bool {
+        {
+            let mut pause = self.pause.lock().unwrap();
+            if pause.paused {
+                if pause.buffered.len() >= pause.capacity {
+                    return false;
+                }
+                pause.buffered.push_back(event);
+                return true;
+            }
+        }
+        self.sender.send(event).await.is_ok()
+    }
+
+    /// Count an event this subscription missed
+    pub(crate) fn record_dropped(&self) {
+        self.pause.lock().unwrap().dropped += 1;
+    }
+
+    /// Number of events this subscription missed because it lagged
+    pub fn dropped_count(&self) -> u64 {
+        self.pause.lock().unwrap().dropped
+    }
+}
+
+/// Handle to pause and resume delivery to a subscription
+#[derive(Debug, Clone)]
+pub struct SubscriptionHandle {
+    /// ID of the subscription
+    pub subscription_id: String,
+
+    /// Event sender channel of the subscription
+    sender: mpsc::Sender<Event>,
+
+    /// Pause state of the subscription
+    pause: Arc<Mutex<PauseState>>,
+}
+
+impl SubscriptionHandle {
+    /// Stop delivering events; they are held back until `resume`
+    ///
+    /// Events published while the buffer is full are not delivered to this
+    /// subscription.
+    pub fn pause(&self) {
+        self.pause.lock().unwrap().paused = true;
+    }
+
+    /// Deliver the held-back events in order and resume delivery
+    pub async fn resume(&self) -> Result<(), EventError> {
+        loop {
+            // Stay paused until the buffer is drained so that newly published
+            // events queue up behind the held-back ones
+            let next = {
+                let mut pause = self.pause.lock().unwrap();
+                match pause.buffered.pop_front() {
+                    Some(event) => event,
+                    None => {
+                        pause.paused = false;
+                        return Ok(());
+                    }
+                }
+            };
+            self.sender
+                .send(next)
+                .await
+                .map_err(|_| EventError::ChannelClosed)?;
+        }
+    }
+
+    /// Whether delivery is paused
+    pub fn is_paused(&self) -> bool {
+        self.pause.lock().unwrap().paused
+    }
+
+    /// Number of events held back
+    pub fn buffered_count(&self) -> usize {
+        self.pause.lock().unwrap().buffered.len()
+    }
+
+    /// Number of events the subscription missed because it lagged
+    ///
+    /// A subscriber seeing this grow should refetch the state it tracks
+    /// rather than rely on the events it received.
+    pub fn dropped_count(&self) -> u64 {
+        self.pause.lock().unwrap().dropped
+    }
 }
 
 // Manual implementation of Clone for EventSubscription
@@ -38,10 +183,12 @@ impl Clone for EventSubscription {
             id: self.id.clone(),
             event_type: self.event_type.clone(),
             subscriber_id: self.subscriber_id.clone(),
+            group: self.group.clone(),
             created_at: self.created_at,
             required_capability: self.required_capability,
             sender: self.sender.clone(),
             ack_receiver: ack_rx,
+            pause: self.pause.clone(),
         }
     }
 }
@@ -58,6 +205,10 @@ pub struct SerializableSubscription {
     /// Subscriber ID
     pub subscriber_id: String,
 
+    /// Consumer group, if any
+    #[serde(default)]
+    pub group: Option<String>,
+
     /// Subscription creation time
     pub created_at: chrono::DateTime<chrono::Utc>,
 
@@ -71,6 +222,7 @@ impl From<&EventSubscription> for SerializableSubscription {
             id: sub.id.clone(),
             event_type: sub.event_type.clone(),
             subscriber_id: sub.subscriber_id.clone(),
+            group: sub.group.clone(),
             created_at: sub.created_at,
             required_capability: sub.required_capability,
         }
@@ -99,10 +251,17 @@ pub trait SubscriptionManager {
             id: format!("sub-{}", Uuid::new_v4()),
             event_type: event_type.to_string(),
             subscriber_id: subscriber_id.to_string(),
+            group: None,
             created_at: chrono::Utc::now(),
             required_capability: capability,
             sender: event_tx,
             ack_receiver: ack_rx,
+            pause: Arc::new(Mutex::new(PauseState {
+                paused: false,
+                buffered: VecDeque::new(),
+                capacity: buffer_size,
+                dropped: 0,
+            })),
         };
 
         (subscription, event_rx, ack_tx)
@@ -49,6 +49,17 @@ pub enum DeliverySemantic {
     ExactlyOnce,
 }
 
+/// What a broker does when a subscriber's channel is full
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SubscriberLagPolicy {
+    #[default]
+    /// Skip the subscriber and count the event as dropped for it
+    Drop,
+
+    /// Make the publisher wait until the subscriber has room
+    Wait,
+}
+
 /// Event priority levels
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, Default)]
 pub enum EventPriority {
@@ -79,6 +90,9 @@ pub enum EventStatus {
 
     /// Event was processed (idempotent check)
     Processed,
+
+    /// Event was ignored because its producer-assigned ID was already published
+    Duplicate,
 }
 
 /// Workflow event
@@ -307,6 +321,9 @@ pub struct EventBrokerConfig {
     /// Enable backpressure
     pub enable_backpressure: bool,
 
+    /// What to do when a subscriber falls behind
+    pub lag_policy: SubscriberLagPolicy,
+
     /// Maximum in-flight events
     pub max_in_flight: usize,
 
@@ -315,6 +332,10 @@ pub struct EventBrokerConfig {
 
     /// How long to keep processed event IDs (for deduplication)
     pub processed_event_ttl: Duration,
+
+    /// How long producer-assigned event IDs are remembered to dedupe
+    /// retried publishes
+    pub publish_dedup_window: Duration,
 }
 
 impl Default for EventBrokerConfig {
@@ -327,9 +348,11 @@ impl Default for EventBrokerConfig {
             default_expiration: Some(Duration::from_secs(3600)),
             channel_buffer_size: 1000,
             enable_backpressure: true,
+            lag_policy: SubscriberLagPolicy::Drop,
             max_in_flight: 100,
             track_processed_events: true,
             processed_event_ttl: Duration::from_secs(3600),
+            publish_dedup_window: Duration::from_secs(600),
         }
     }
 }
@@ -3,7 +3,8 @@ pub mod saga;
 
 pub use event::{
     DeliverySemantic, Event, EventAck, EventBroker, EventBrokerConfig, EventError, EventPriority,
-    EventStatus, EventStore, InMemoryEventStore, RetryManager,
+    EventStatus, EventStore, FileEventStore, InMemoryEventStore, RetryManager, SubscriberLagPolicy,
+    SyncPolicy,
 };
 
 // Re-export saga types
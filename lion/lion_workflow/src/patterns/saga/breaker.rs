@@ -0,0 +1,147 @@
+use std::time::{Duration, Instant};
+
+/// Configuration for the circuit breakers guarding saga step handlers
+#[derive(Debug, Clone)]
+pub struct CircuitBreakerConfig {
+    /// Consecutive failures after which the circuit opens
+    pub failure_threshold: u32,
+
+    /// How long an open circuit rejects calls before allowing a trial call
+    pub reset_timeout_ms: u64,
+}
+
+impl Default for CircuitBreakerConfig {
+    fn default() -> Self {
+        CircuitBreakerConfig {
+            failure_threshold: 5,
+            reset_timeout_ms: 30000, // 30 seconds
+        }
+    }
+}
+
+/// State of a circuit breaker
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CircuitState {
+    /// Calls go through
+    Closed,
+
+    /// Calls are rejected without reaching the handler
+    Open,
+
+    /// A single trial call is allowed to decide whether to close again
+    HalfOpen,
+}
+
+/// Circuit breaker for one step handler, shared by every saga calling it
+#[derive(Debug)]
+pub struct CircuitBreaker {
+    /// Current state
+    state: CircuitState,
+
+    /// Failures since the last success
+    consecutive_failures: u32,
+
+    /// When the circuit last opened
+    opened_at: Option<Instant>,
+}
+
+impl CircuitBreaker {
+    /// Create a closed circuit breaker
+    pub fn new() -> Self {
+        CircuitBreaker {
+            state: CircuitState::Closed,
+            consecutive_failures: 0,
+            opened_at: None,
+        }
+    }
+
+    /// Get the current state
+    pub fn state(&self) -> CircuitState {
+        self.state
+    }
+
+    /// Check whether a call may go through, moving an open circuit to
+    /// half-open once its reset timeout has passed
+    pub fn try_acquire(&mut self, config: &CircuitBreakerConfig, now: Instant) -> bool {
+        match self.state {
+            CircuitState::Closed => true,
+            CircuitState::HalfOpen => false,
+            CircuitState::Open => {
+                let reset_timeout = Duration::from_millis(config.reset_timeout_ms);
+                let elapsed = self
+                    .opened_at
+                    .map(|opened_at| now.saturating_duration_since(opened_at))
+                    .unwrap_or(reset_timeout);
+                if elapsed >= reset_timeout {
+                    // Let a single trial call through
+                    self.state = CircuitState::HalfOpen;
+                    true
+                } else {
+                    false
+                }
+            }
+        }
+    }
+
+    /// Record a successful call, closing the circuit
+    pub fn record_success(&mut self) {
+        self.state = CircuitState::Closed;
+        self.consecutive_failures = 0;
+        self.opened_at = None;
+    }
+
+    /// Record a failed call, opening the circuit if the trial call failed or
+    /// too many calls failed in a row
+    pub fn record_failure(&mut self, config: &CircuitBreakerConfig, now: Instant) {
+        self.consecutive_failures += 1;
+
+        if self.state == CircuitState::HalfOpen
+            || self.consecutive_failures >= config.failure_threshold
+        {
+            self.state = CircuitState::Open;
+            self.opened_at = Some(now);
+        }
+    }
+}
+
+impl Default for CircuitBreaker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_breaker_opens_and_recovers() {
+        let config = CircuitBreakerConfig {
+            failure_threshold: 2,
+            reset_timeout_ms: 1000,
+        };
+        let mut breaker = CircuitBreaker::new();
+        let start = Instant::now();
+
+        assert!(breaker.try_acquire(&config, start));
+        breaker.record_failure(&config, start);
+        assert_eq!(breaker.state(), CircuitState::Closed);
+        breaker.record_failure(&config, start);
+        assert_eq!(breaker.state(), CircuitState::Open);
+        assert!(!breaker.try_acquire(&config, start + Duration::from_millis(999)));
+
+        // After the timeout one trial call goes through; its failure reopens
+        let later = start + Duration::from_secs(1);
+        assert!(breaker.try_acquire(&config, later));
+        assert_eq!(breaker.state(), CircuitState::HalfOpen);
+        assert!(!breaker.try_acquire(&config, later));
+        breaker.record_failure(&config, later);
+        assert_eq!(breaker.state(), CircuitState::Open);
+
+        // A successful trial call closes the circuit
+        let later = later + Duration::from_secs(1);
+        assert!(breaker.try_acquire(&config, later));
+        breaker.record_success();
+        assert_eq!(breaker.state(), CircuitState::Closed);
+    }
+}
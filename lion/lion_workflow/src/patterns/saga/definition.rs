@@ -1,6 +1,6 @@
 use crate::patterns::saga::step::SagaStepDefinition;
 use crate::patterns::saga::types::SagaError;
-use crate::patterns::saga::types::SagaStrategy;
+use crate::patterns::saga::types::{CompensationOrder, SagaStrategy};
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
 
@@ -20,6 +20,10 @@ pub struct SagaDefinition {
     #[serde(default)]
     pub strategy: SagaStrategy,
 
+    /// Order completed steps are compensated in
+    #[serde(default)]
+    pub compensation_order: CompensationOrder,
+
     /// Maximum execution time for the entire saga
     pub timeout_ms: u64,
 
@@ -44,6 +48,7 @@ impl SagaDefinition {
             name: name.to_string(),
             steps: Vec::new(),
             strategy: SagaStrategy::Orchestration,
+            compensation_order: CompensationOrder::Reverse,
             timeout_ms: 300000, // 5 minutes
             max_retries: 3,
             retry_delay_ms: 1000, // 1 second
@@ -86,6 +91,12 @@ impl SagaDefinition {
         self
     }
 
+    /// Set the order completed steps are compensated in
+    pub fn with_compensation_order(mut self, order: CompensationOrder) -> Self {
+        self.compensation_order = order;
+        self
+    }
+
     /// Set the saga timeout
     pub fn with_timeout(mut self, timeout_ms: u64) -> Self {
         self.timeout_ms = timeout_ms;
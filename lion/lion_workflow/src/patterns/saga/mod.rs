@@ -1,5 +1,6 @@
 //! Saga orchestration pattern for distributed transactions
 
+pub mod breaker;
 pub mod definition;
 pub mod execution;
 pub mod orchestrator;
@@ -7,14 +8,18 @@ pub mod step;
 pub mod types;
 
 // Re-exports
+pub use breaker::{CircuitBreaker, CircuitBreakerConfig, CircuitState};
 pub use definition::SagaDefinition;
-pub use orchestrator::SagaOrchestrator;
+pub use orchestrator::{Saga, SagaOrchestrator, SagaOutcome, StepOutcome};
 pub use step::SagaStep;
 pub use step::SagaStepDefinition;
 pub use types::{
     AbortTask, CompensationTask, SagaError, SagaStatus, SagaStrategy, StepResult, StepStatus,
 };
-pub use types::{CompensationHandler, SagaOrchestratorConfig, StepHandler};
+pub use types::{
+    CompensationComparator, CompensationHandler, CompensationOrder, SagaOrchestratorConfig,
+    StepHandler,
+};
 
 #[cfg(test)]
 mod tests {
@@ -1,15 +1,17 @@
+use crate::engine::shared::SharedContextStore;
 use crate::patterns::event::EventBroker;
+use crate::patterns::saga::breaker::{CircuitBreaker, CircuitState};
 use crate::patterns::saga::definition::SagaDefinition;
 use crate::patterns::saga::step::SagaStep;
 use crate::patterns::saga::types::{
-    AbortTask, CompensationHandler, CompensationTask, SagaError, SagaOrchestratorConfig,
-    SagaStatus, StepHandler, StepResult, StepStatus,
+    AbortTask, CompensationHandler, CompensationOrder, CompensationTask, SagaError,
+    SagaOrchestratorConfig, SagaStatus, StepHandler, StepResult, StepStatus,
 };
 
 use log;
 use std::collections::{HashMap, VecDeque};
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use tokio::sync::{mpsc, Mutex, RwLock};
 use tokio::time::timeout;
 
@@ -44,6 +46,15 @@ pub struct SagaOrchestrator {
 
     /// Queue for saga aborts
     abort_queue: RwLock<VecDeque<AbortTask>>,
+
+    /// Values shared with the workflows the sagas belong to
+    shared_store: Option<Arc<SharedContextStore>>,
+
+    /// Registered saga definitions by definition id
+    definitions: RwLock<HashMap<String, SagaDefinition>>,
+
+    /// Circuit breakers by step handler, shared by every saga
+    circuit_breakers: Arc<std::sync::Mutex<HashMap<String, CircuitBreaker>>>,
 }
 
 /// Saga instance
@@ -210,21 +221,44 @@ impl Saga {
         ready_steps
     }
 
-    /// Get steps that need compensation
+    /// Get steps that need compensation, in the definition's compensation order
     pub fn get_compensation_steps(&self) -> Vec<String> {
-        // Compensation is in reverse order of execution
-        let mut compensation_steps = Vec::new();
-
-        for step_id in self.execution_order.iter().rev() {
+        // Only compensate completed steps with compensation actions
+        let needs_compensation = |step_id: &String| {
             let step = &self.steps[step_id];
+            step.status == StepStatus::Completed && step.has_compensation()
+        };
 
-            // Only compensate completed steps with compensation actions
-            if step.status == StepStatus::Completed && step.has_compensation() {
-                compensation_steps.push(step_id.clone());
+        match &self.definition.compensation_order {
+            CompensationOrder::Reverse => self
+                .execution_order
+                .iter()
+                .rev()
+                .filter(|step_id| needs_compensation(step_id))
+                .cloned()
+                .collect(),
+            CompensationOrder::Declared => self
+                .definition
+                .steps
+                .iter()
+                .map(|step| &step.id)
+                .filter(|step_id| needs_compensation(step_id))
+                .cloned()
+                .collect(),
+            CompensationOrder::Custom(comparator) => {
+                let mut compensation_steps: Vec<String> = self
+                    .execution_order
+                    .iter()
+                    .rev()
+                    .filter(|step_id| needs_compensation(step_id))
+                    .cloned()
+                    .collect();
+                compensation_steps.sort_by(|a, b| {
+                    comparator(&self.steps[a].definition, &self.steps[b].definition)
+                });
+                compensation_steps
             }
         }
-
-        compensation_steps
     }
 
     /// Check if the saga is complete (all steps in terminal state)
@@ -247,6 +281,112 @@ impl Saga {
 
         (completed_steps / total_steps) * 100.0
     }
+
+    /// Get a detailed report of how the saga and each of its steps fared
+    pub fn outcome(&self) -> SagaOutcome {
+        let steps = self
+            .execution_order
+            .iter()
+            .filter_map(|step_id| self.steps.get(step_id))
+            .map(|step| StepOutcome {
+                step_id: step.definition.id.clone(),
+                name: step.definition.name.clone(),
+                status: step.status,
+                result: step.result.clone(),
+                error: step.error.clone(),
+                retry_count: step.retry_count,
+                started_at: step.start_time,
+                finished_at: step.end_time,
+                compensation_started_at: step.compensation_start_time,
+                compensation_finished_at: step.compensation_end_time,
+            })
+            .collect();
+
+        SagaOutcome {
+            saga_id: self.instance_id.clone(),
+            definition_id: self.definition.id.clone(),
+            status: self.status,
+            error: self.error.clone(),
+            started_at: self.start_time,
+            finished_at: self.end_time,
+            steps,
+        }
+    }
+}
+
+/// How a single saga step fared
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct StepOutcome {
+    /// Step ID
+    pub step_id: String,
+
+    /// Step name
+    pub name: String,
+
+    /// Final status of the step
+    pub status: StepStatus,
+
+    /// Result of the step, if it completed
+    pub result: Option<serde_json::Value>,
+
+    /// Error of the step or of its compensation
+    pub error: Option<String>,
+
+    /// Number of retries
+    pub retry_count: u32,
+
+    /// When the step started
+    pub started_at: Option<chrono::DateTime<chrono::Utc>>,
+
+    /// When the step finished
+    pub finished_at: Option<chrono::DateTime<chrono::Utc>>,
+
+    /// When the step's compensation started
+    pub compensation_started_at: Option<chrono::DateTime<chrono::Utc>>,
+
+    /// When the step's compensation finished
+    pub compensation_finished_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// Detailed report of a saga instance, for audit and debugging
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct SagaOutcome {
+    /// Saga instance ID
+    pub saga_id: String,
+
+    /// ID of the saga definition
+    pub definition_id: String,
+
+    /// Final status of the saga
+    pub status: SagaStatus,
+
+    /// Overall error
+    pub error: Option<String>,
+
+    /// When the saga started
+    pub started_at: Option<chrono::DateTime<chrono::Utc>>,
+
+    /// When the saga finished
+    pub finished_at: Option<chrono::DateTime<chrono::Utc>>,
+
+    /// Steps in execution order
+    pub steps: Vec<StepOutcome>,
+}
+
+impl SagaOutcome {
+    /// Get the outcome of a step
+    pub fn step(&self, step_id: &str) -> Option<&StepOutcome> {
+        self.steps.iter().find(|step| step.step_id == step_id)
+    }
+
+    /// IDs of the steps with the given status, in execution order
+    pub fn steps_with_status(&self, status: StepStatus) -> Vec<&str> {
+        self.steps
+            .iter()
+            .filter(|step| step.status == status)
+            .map(|step| step.step_id.as_str())
+            .collect()
+    }
 }
 
 impl SagaOrchestrator {
@@ -265,6 +405,9 @@ impl SagaOrchestrator {
             _cancel_rx: Mutex::new(rx),
             compensation_queue: RwLock::new(VecDeque::new()),
             abort_queue: RwLock::new(VecDeque::new()),
+            shared_store: None,
+            definitions: RwLock::new(HashMap::new()),
+            circuit_breakers: Arc::new(std::sync::Mutex::new(HashMap::new())),
         }
     }
 
@@ -274,6 +417,24 @@ impl SagaOrchestrator {
         self
     }
 
+    /// Set the store of values shared with workflow executions
+    ///
+    /// Steps see the values of the execution named by their saga's
+    /// correlation id, or of the saga itself if it has none.
+    pub fn with_shared_context(mut self, store: Arc<SharedContextStore>) -> Self {
+        self.shared_store = Some(store);
+        self
+    }
+
+    /// Give a step access to the shared values of its saga's execution
+    fn attach_shared_context(&self, saga: &Saga, mut step: SagaStep) -> SagaStep {
+        if let Some(store) = &self.shared_store {
+            let execution_id = saga.correlation_id.as_ref().unwrap_or(&saga.instance_id);
+            step.shared_context = Some(store.scope(execution_id));
+        }
+        step
+    }
+
     /// Register a step handler
     pub async fn register_step_handler(&self, service: &str, action: &str, handler: StepHandler) {
         let mut handlers = self.step_handlers.write().await;
@@ -464,6 +625,81 @@ impl SagaOrchestrator {
         Ok(instance_id)
     }
 
+    /// Create a new saga instance correlated with another execution, such as
+    /// the workflow instance it runs for
+    pub async fn create_correlated_saga(
+        &self,
+        definition: SagaDefinition,
+        correlation_id: &str,
+    ) -> Result<String, SagaError> {
+        let saga = Saga::new(definition)?.with_correlation_id(correlation_id);
+        let instance_id = saga.instance_id.clone();
+
+        let mut sagas = self.sagas.write().await;
+        sagas.insert(instance_id.clone(), Arc::new(RwLock::new(saga)));
+
+        Ok(instance_id)
+    }
+
+    /// Register a saga definition so it can be run by id
+    pub async fn register_definition(&self, definition: SagaDefinition) -> Result<(), SagaError> {
+        definition.validate()?;
+        self.definitions
+            .write()
+            .await
+            .insert(definition.id.clone(), definition);
+        Ok(())
+    }
+
+    /// Run a registered saga to completion
+    ///
+    /// The saga is correlated with `correlation_id`. If a step fails or the
+    /// saga is aborted, the compensation runs before this returns rather
+    /// than in the background.
+    /// Returns the saga in its final state.
+    pub async fn run_registered_saga(
+        &self,
+        definition_id: &str,
+        correlation_id: &str,
+    ) -> Result<Saga, SagaError> {
+        let definition = self
+            .definitions
+            .read()
+            .await
+            .get(definition_id)
+            .cloned()
+            .ok_or_else(|| {
+                SagaError::DefinitionError(format!(
+                    "No saga definition registered: {}",
+                    definition_id
+                ))
+            })?;
+
+        let saga_id = self
+            .create_correlated_saga(definition, correlation_id)
+            .await?;
+        self.start_saga(&saga_id).await?;
+
+        let saga_lock = self
+            .get_saga(&saga_id)
+            .await
+            .ok_or_else(|| SagaError::NotFound(saga_id.clone()))?;
+        let status = saga_lock.read().await.status;
+        if matches!(
+            status,
+            SagaStatus::Compensating | SagaStatus::Failed | SagaStatus::Aborted
+        ) {
+            self.compensation_queue
+                .write()
+                .await
+                .retain(|task| task.saga_id != saga_id);
+            self.compensate_saga_internal(&saga_id).await?;
+        }
+
+        let saga = saga_lock.read().await.clone();
+        Ok(saga)
+    }
+
     /// Start a saga execution
     pub async fn start_saga(&self, saga_id: &str) -> Result<(), SagaError> {
         let saga_lock = {
@@ -514,8 +750,11 @@ impl SagaOrchestrator {
                 continue;
             }
 
-            // Execute each ready step
+            // Execute each ready step, stopping once the saga is aborted
             for step_id in ready_steps {
+                if saga_lock.read().await.status == SagaStatus::Aborted {
+                    return Ok(());
+                }
                 match self.execute_step(saga_id, &step_id).await {
                     Ok(_) => { /* Step executed successfully */ }
                     Err(e) => {
@@ -550,6 +789,7 @@ impl SagaOrchestrator {
             saga.steps
                 .get(step_id)
                 .cloned()
+                .map(|step| self.attach_shared_context(&saga, step))
                 .ok_or_else(|| SagaError::StepNotFound(step_id.to_string()))?
         };
 
@@ -585,16 +825,51 @@ impl SagaOrchestrator {
             }
         };
 
-        // Execute the step with timeout
-        let timeout_duration = Duration::from_millis(step.definition.timeout_ms);
-        let step_execution = (handler)(&step);
+        // Fail fast if the handler's circuit is open
+        let breaker_key = format!("{}/{}", step.definition.service, step.definition.action);
+        let breaker_config = self.config.read().await.circuit_breaker.clone();
+        let allowed = match &breaker_config {
+            Some(config) => self
+                .circuit_breakers
+                .lock()
+                .unwrap()
+                .entry(breaker_key.clone())
+                .or_default()
+                .try_acquire(config, Instant::now()),
+            None => true,
+        };
 
-        let execution_result = match timeout(timeout_duration, step_execution).await {
-            Ok(result) => result,
-            Err(_) => Err(format!(
-                "Step timed out after {}ms",
-                step.definition.timeout_ms
-            )),
+        let execution_result = if allowed {
+            // Execute the step with timeout
+            let timeout_duration = Duration::from_millis(step.definition.timeout_ms);
+            let step_execution = (handler)(&step);
+
+            let execution_result = match timeout(timeout_duration, step_execution).await {
+                Ok(result) => result,
+                Err(_) => Err(format!(
+                    "Step timed out after {}ms",
+                    step.definition.timeout_ms
+                )),
+            };
+
+            if let Some(config) = &breaker_config {
+                let mut breakers = self.circuit_breakers.lock().unwrap();
+                let breaker = breakers.entry(breaker_key).or_default();
+                match &execution_result {
+                    Ok(_) => breaker.record_success(),
+                    Err(_) => breaker.record_failure(config, Instant::now()),
+                }
+            }
+
+            execution_result
+        } else {
+            log::warn!(
+                "Circuit open for {}, short-circuiting step {} of saga {}",
+                breaker_key,
+                step_id,
+                saga_id
+            );
+            Err(format!("Circuit open for {}", breaker_key))
         };
 
         // Process result
@@ -612,8 +887,8 @@ impl SagaOrchestrator {
                 // Create a copy of the steps for the result
                 let steps_copy = saga.steps.clone();
 
-                // Check if saga is complete
-                if saga.is_complete() {
+                // Check if saga is complete, unless it was aborted meanwhile
+                if saga.is_complete() && saga.status != SagaStatus::Aborted {
                     saga.mark_completed(Some(serde_json::json!({
                         "steps": steps_copy,
                     })));
@@ -671,6 +946,16 @@ impl SagaOrchestrator {
         Ok(result)
     }
 
+    /// Get the circuit state of a step handler, if it has been called with
+    /// circuit breaking enabled
+    pub fn circuit_state(&self, service: &str, action: &str) -> Option<CircuitState> {
+        self.circuit_breakers
+            .lock()
+            .unwrap()
+            .get(&format!("{}/{}", service, action))
+            .map(|breaker| breaker.state())
+    }
+
     /// Compensate a saga (undo completed steps)
     pub async fn compensate_saga(&self, saga_id: &str) -> Result<(), SagaError> {
         // Queue the compensation task
@@ -711,7 +996,7 @@ impl SagaOrchestrator {
             saga_id
         );
 
-        // Execute compensation for each step in reverse order
+        // Execute compensation for each step in compensation order
         let mut compensation_errors = Vec::new();
 
         for step_id in compensation_steps {
@@ -761,6 +1046,7 @@ impl SagaOrchestrator {
             saga.steps
                 .get(step_id)
                 .cloned()
+                .map(|step| self.attach_shared_context(&saga, step))
                 .ok_or_else(|| SagaError::StepNotFound(step_id.to_string()))?
         };
 
@@ -854,6 +1140,13 @@ impl SagaOrchestrator {
         sagas.get(saga_id).cloned()
     }
 
+    /// Get the outcome of a saga instance
+    pub async fn get_saga_outcome(&self, saga_id: &str) -> Option<SagaOutcome> {
+        let saga = self.get_saga(saga_id).await?;
+        let outcome = saga.read().await.outcome();
+        Some(outcome)
+    }
+
     /// Process any pending abort tasks immediately
     async fn process_abort_tasks(&self) -> Result<(), SagaError> {
         if let Some(task) = self.dequeue_abort_task().await {
@@ -900,6 +1193,27 @@ impl SagaOrchestrator {
         Ok(())
     }
 
+    /// Abort the active sagas correlated with `correlation_id`
+    ///
+    /// A saga run by `run_registered_saga` stops after its current step and
+    /// compensates the steps it completed. Returns the ids of the aborted sagas.
+    pub async fn abort_correlated_sagas(&self, correlation_id: &str, reason: &str) -> Vec<String> {
+        let sagas = self.sagas.read().await;
+        let mut aborted = Vec::new();
+
+        for (saga_id, saga_lock) in sagas.iter() {
+            let mut saga = saga_lock.write().await;
+            if saga.correlation_id.as_deref() == Some(correlation_id)
+                && matches!(saga.status, SagaStatus::Created | SagaStatus::Running)
+            {
+                saga.mark_aborted(reason);
+                aborted.push(saga_id.clone());
+            }
+        }
+
+        aborted
+    }
+
     /// Get all sagas
     pub async fn get_all_sagas(&self) -> Vec<Arc<RwLock<Saga>>> {
         let sagas = self.sagas.read().await;
@@ -1024,6 +1338,9 @@ impl Clone for SagaOrchestrator {
 
             // Clone option and Arc fields normally
             event_broker: self.event_broker.clone(),
+            shared_store: self.shared_store.clone(),
+            definitions: RwLock::new(HashMap::new()),
+            circuit_breakers: Arc::clone(&self.circuit_breakers),
 
             // Initialize other fields with default values
             is_running: RwLock::new(false),
@@ -1038,6 +1355,7 @@ impl Clone for SagaOrchestrator {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::patterns::saga::step::SagaStepDefinition;
 
     // Helper function to create a boxed step handler that returns the provided value
     #[allow(dead_code)]
@@ -1079,4 +1397,317 @@ mod tests {
         );
         // Skipping this test to prevent hangs - the test functionality is covered in integration tests
     }
+
+    #[tokio::test]
+    async fn test_outcome_reports_partial_failure_and_compensations() {
+        let orch = SagaOrchestrator::new(SagaOrchestratorConfig::default());
+        orch.register_step_handler(
+            "inventory",
+            "reserve",
+            create_success_handler(serde_json::json!({"held": 2})),
+        )
+        .await;
+        orch.register_step_handler(
+            "shipping",
+            "book",
+            create_success_handler(serde_json::json!({"slot": "am"})),
+        )
+        .await;
+        orch.register_step_handler("billing", "charge", create_failure_handler("Card declined"))
+            .await;
+        orch.register_compensation_handler(
+            "inventory",
+            "release",
+            create_compensation_handler(true),
+        )
+        .await;
+        orch.register_compensation_handler(
+            "shipping",
+            "cancel",
+            create_compensation_handler(false),
+        )
+        .await;
+
+        let mut saga_def = SagaDefinition::new("checkout", "Checkout");
+        saga_def
+            .add_step(
+                SagaStepDefinition::new(
+                    "reserve",
+                    "Reserve",
+                    "inventory",
+                    "reserve",
+                    serde_json::json!({}),
+                )
+                .with_compensation("release", serde_json::json!({})),
+            )
+            .unwrap();
+        saga_def
+            .add_step(
+                SagaStepDefinition::new("book", "Book", "shipping", "book", serde_json::json!({}))
+                    .with_compensation("cancel", serde_json::json!({}))
+                    .with_dependency("reserve"),
+            )
+            .unwrap();
+        saga_def
+            .add_step(
+                SagaStepDefinition::new(
+                    "charge",
+                    "Charge",
+                    "billing",
+                    "charge",
+                    serde_json::json!({}),
+                )
+                .with_dependency("book"),
+            )
+            .unwrap();
+        orch.register_definition(saga_def).await.unwrap();
+
+        let saga = orch
+            .run_registered_saga("checkout", "order-1")
+            .await
+            .unwrap();
+        let outcome = orch.get_saga_outcome(&saga.instance_id).await.unwrap();
+        assert_eq!(outcome, saga.outcome());
+
+        // Booking could not be undone, so the saga failed with errors
+        assert_eq!(outcome.definition_id, "checkout");
+        assert_eq!(outcome.status, SagaStatus::FailedWithErrors);
+        assert!(outcome.started_at.is_some() && outcome.finished_at.is_some());
+        let step_ids: Vec<&str> = outcome.steps.iter().map(|s| s.step_id.as_str()).collect();
+        assert_eq!(step_ids, vec!["reserve", "book", "charge"]);
+
+        let charge = outcome.step("charge").unwrap();
+        assert_eq!(charge.status, StepStatus::Failed);
+        assert_eq!(charge.error.as_deref(), Some("Card declined"));
+        assert!(charge.compensation_started_at.is_none());
+
+        let reserve = outcome.step("reserve").unwrap();
+        assert_eq!(reserve.status, StepStatus::Compensated);
+        assert_eq!(reserve.result, Some(serde_json::json!({"held": 2})));
+        assert!(reserve.finished_at.is_some());
+        assert!(reserve.compensation_finished_at.is_some());
+
+        let book = outcome.step("book").unwrap();
+        assert_eq!(book.status, StepStatus::CompensationFailed);
+        assert_eq!(book.error.as_deref(), Some("Compensation failed"));
+
+        // Compensations ran in reverse order
+        assert!(book.compensation_started_at <= reserve.compensation_started_at);
+        assert_eq!(
+            outcome.steps_with_status(StepStatus::Compensated),
+            vec!["reserve"]
+        );
+    }
+
+    /// Run a failing saga of reserve -> lock -> book -> charge and return the
+    /// steps in the order they were compensated
+    async fn compensation_order_of(order: CompensationOrder) -> Vec<String> {
+        let orch = SagaOrchestrator::new(SagaOrchestratorConfig::default());
+        let compensated = Arc::new(std::sync::Mutex::new(Vec::new()));
+        for step in ["reserve", "lock", "book"] {
+            orch.register_step_handler(step, "run", create_success_handler(serde_json::json!({})))
+                .await;
+            let compensated = compensated.clone();
+            orch.register_compensation_handler(
+                step,
+                "undo",
+                Arc::new(move |step| {
+                    compensated.lock().unwrap().push(step.definition.id.clone());
+                    Box::new(Box::pin(async { Ok(()) }))
+                }),
+            )
+            .await;
+        }
+        orch.register_step_handler("charge", "run", create_failure_handler("Card declined"))
+            .await;
+
+        let mut saga_def =
+            SagaDefinition::new("checkout", "Checkout").with_compensation_order(order);
+        let mut previous: Option<&str> = None;
+        for step in ["reserve", "lock", "book", "charge"] {
+            let mut step_def =
+                SagaStepDefinition::new(step, step, step, "run", serde_json::json!({}));
+            if step != "charge" {
+                step_def = step_def.with_compensation("undo", serde_json::json!({}));
+            }
+            if let Some(dep) = previous {
+                step_def = step_def.with_dependency(dep);
+            }
+            saga_def.add_step(step_def).unwrap();
+            previous = Some(step);
+        }
+        orch.register_definition(saga_def).await.unwrap();
+
+        let saga = orch
+            .run_registered_saga("checkout", "order-1")
+            .await
+            .unwrap();
+        assert_eq!(saga.status, SagaStatus::Compensated);
+
+        let compensated = compensated.lock().unwrap().clone();
+        compensated
+    }
+
+    /// Orchestrator whose "billing/charge" handler counts its calls and fails
+    /// while `failing` is set, with a checkout saga of reserve -> charge
+    async fn breaker_orchestrator(
+        reset_timeout_ms: u64,
+        calls: Arc<std::sync::atomic::AtomicU32>,
+        failing: Arc<std::sync::atomic::AtomicBool>,
+    ) -> SagaOrchestrator {
+        use crate::patterns::saga::breaker::CircuitBreakerConfig;
+        use std::sync::atomic::Ordering;
+
+        let orch = SagaOrchestrator::new(SagaOrchestratorConfig {
+            circuit_breaker: Some(CircuitBreakerConfig {
+                failure_threshold: 2,
+                reset_timeout_ms,
+            }),
+            ..Default::default()
+        });
+        orch.register_step_handler(
+            "inventory",
+            "reserve",
+            create_success_handler(serde_json::json!({})),
+        )
+        .await;
+        orch.register_compensation_handler(
+            "inventory",
+            "release",
+            create_compensation_handler(true),
+        )
+        .await;
+        orch.register_step_handler(
+            "billing",
+            "charge",
+            Arc::new(move |_step| {
+                calls.fetch_add(1, Ordering::SeqCst);
+                let failing = failing.load(Ordering::SeqCst);
+                Box::new(Box::pin(async move {
+                    if failing {
+                        Err("Payment service unavailable".to_string())
+                    } else {
+                        Ok(serde_json::json!({"charged": true}))
+                    }
+                }))
+            }),
+        )
+        .await;
+
+        let mut saga_def = SagaDefinition::new("checkout", "Checkout");
+        saga_def
+            .add_step(
+                SagaStepDefinition::new(
+                    "reserve",
+                    "Reserve",
+                    "inventory",
+                    "reserve",
+                    serde_json::json!({}),
+                )
+                .with_compensation("release", serde_json::json!({})),
+            )
+            .unwrap();
+        saga_def
+            .add_step(
+                SagaStepDefinition::new(
+                    "charge",
+                    "Charge",
+                    "billing",
+                    "charge",
+                    serde_json::json!({}),
+                )
+                .with_dependency("reserve"),
+            )
+            .unwrap();
+        orch.register_definition(saga_def).await.unwrap();
+        orch
+    }
+
+    #[tokio::test]
+    async fn test_circuit_breaker_short_circuits_failing_step() {
+        use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+
+        let calls = Arc::new(AtomicU32::new(0));
+        let failing = Arc::new(AtomicBool::new(true));
+        let orch = breaker_orchestrator(60_000, calls.clone(), failing).await;
+
+        // Two failing runs trip the breaker
+        for order in ["order-1", "order-2"] {
+            let saga = orch.run_registered_saga("checkout", order).await.unwrap();
+            assert_eq!(saga.status, SagaStatus::Compensated);
+        }
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+        assert_eq!(
+            orch.circuit_state("billing", "charge"),
+            Some(CircuitState::Open)
+        );
+
+        // Later sagas fail fast without calling the handler, and compensate
+        for order in ["order-3", "order-4"] {
+            let saga = orch.run_registered_saga("checkout", order).await.unwrap();
+            assert_eq!(saga.status, SagaStatus::Compensated);
+            assert_eq!(saga.steps["reserve"].status, StepStatus::Compensated);
+            assert_eq!(saga.steps["charge"].status, StepStatus::Failed);
+            assert_eq!(
+                saga.steps["charge"].error.as_deref(),
+                Some("Circuit open for billing/charge")
+            );
+        }
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+        assert_eq!(
+            orch.circuit_state("inventory", "reserve"),
+            Some(CircuitState::Closed)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_circuit_breaker_closes_after_successful_trial() {
+        use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+
+        let calls = Arc::new(AtomicU32::new(0));
+        let failing = Arc::new(AtomicBool::new(true));
+        let orch = breaker_orchestrator(50, calls.clone(), failing.clone()).await;
+
+        for order in ["order-1", "order-2"] {
+            orch.run_registered_saga("checkout", order).await.unwrap();
+        }
+        assert_eq!(
+            orch.circuit_state("billing", "charge"),
+            Some(CircuitState::Open)
+        );
+
+        // Once the dependency recovers, the trial call after the reset
+        // timeout closes the circuit
+        failing.store(false, Ordering::SeqCst);
+        tokio::time::sleep(Duration::from_millis(60)).await;
+        let saga = orch
+            .run_registered_saga("checkout", "order-3")
+            .await
+            .unwrap();
+        assert_eq!(saga.status, SagaStatus::Completed);
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+        assert_eq!(
+            orch.circuit_state("billing", "charge"),
+            Some(CircuitState::Closed)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_compensation_order_strategies() {
+        assert_eq!(
+            compensation_order_of(CompensationOrder::Reverse).await,
+            vec!["book", "lock", "reserve"]
+        );
+        assert_eq!(
+            compensation_order_of(CompensationOrder::Declared).await,
+            vec!["reserve", "lock", "book"]
+        );
+
+        // Release the lock only after everything else has been undone
+        let lock_last = CompensationOrder::custom(|a, b| (a.id == "lock").cmp(&(b.id == "lock")));
+        assert_eq!(
+            compensation_order_of(lock_last).await,
+            vec!["book", "reserve", "lock"]
+        );
+    }
 }
@@ -1,3 +1,4 @@
+use crate::engine::shared::SharedContext;
 use crate::model::Priority;
 use crate::patterns::saga::types::StepStatus;
 use serde::{Deserialize, Serialize};
@@ -154,6 +155,11 @@ pub struct SagaStep {
 
     /// Compensation end time
     pub compensation_end_time: Option<chrono::DateTime<chrono::Utc>>,
+
+    /// Values shared with the rest of the execution, set while the step or
+    /// its compensation runs
+    #[serde(skip)]
+    pub shared_context: Option<SharedContext>,
 }
 
 impl SagaStep {
@@ -169,6 +175,7 @@ impl SagaStep {
             end_time: None,
             compensation_start_time: None,
             compensation_end_time: None,
+            shared_context: None,
         }
     }
 
@@ -1,5 +1,7 @@
 use crate::patterns::event::types::EventError;
 use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::fmt;
 use std::future::Future;
 use std::sync::Arc;
 use thiserror::Error;
@@ -105,6 +107,59 @@ pub enum SagaStrategy {
     Choreography,
 }
 
+/// Comparator deciding which of two completed steps is compensated first
+pub type CompensationComparator = Arc<
+    dyn Fn(
+            &crate::patterns::saga::step::SagaStepDefinition,
+            &crate::patterns::saga::step::SagaStepDefinition,
+        ) -> Ordering
+        + Send
+        + Sync,
+>;
+
+/// Order in which completed steps are compensated
+///
+/// A saga with a custom order cannot be serialized.
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub enum CompensationOrder {
+    /// Reverse of the order the steps ran in
+    #[default]
+    Reverse,
+
+    /// Order the steps were declared in the definition
+    Declared,
+
+    /// Sorted by a comparator, ties keeping reverse execution order
+    #[serde(skip)]
+    Custom(CompensationComparator),
+}
+
+impl CompensationOrder {
+    /// Order compensations with a custom comparator
+    pub fn custom<F>(comparator: F) -> Self
+    where
+        F: Fn(
+                &crate::patterns::saga::step::SagaStepDefinition,
+                &crate::patterns::saga::step::SagaStepDefinition,
+            ) -> Ordering
+            + Send
+            + Sync
+            + 'static,
+    {
+        CompensationOrder::Custom(Arc::new(comparator))
+    }
+}
+
+impl fmt::Debug for CompensationOrder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CompensationOrder::Reverse => write!(f, "Reverse"),
+            CompensationOrder::Declared => write!(f, "Declared"),
+            CompensationOrder::Custom(_) => write!(f, "Custom(<comparator>)"),
+        }
+    }
+}
+
 /// Result of a saga step execution
 #[derive(Debug, Clone)]
 pub struct StepResult {
@@ -184,6 +239,9 @@ pub struct SagaOrchestratorConfig {
 
     /// Channel buffer size
     pub channel_buffer_size: usize,
+
+    /// Circuit breaker guarding each step handler, if any
+    pub circuit_breaker: Option<crate::patterns::saga::breaker::CircuitBreakerConfig>,
 }
 
 impl Default for SagaOrchestratorConfig {
@@ -198,6 +256,7 @@ impl Default for SagaOrchestratorConfig {
             default_retries: 3,
             default_retry_delay_ms: 1000, // 1 second
             channel_buffer_size: 1000,
+            circuit_breaker: None,
         }
     }
 }
@@ -1,6 +1,11 @@
-use crate::model::{WorkflowDefinition, WorkflowError, WorkflowId};
-use crate::state::storage::StorageBackend;
+use crate::model::{EdgeId, NodeId, NodeStatus, WorkflowDefinition, WorkflowError, WorkflowId};
+use crate::state::machine::{
+    ConditionResult, ExecutionResourceUsage, FailureReason, NodeTiming, WorkflowState,
+};
+use crate::state::storage::{SerializationFormat, StorageBackend};
+use crate::utils::serialization::{deserialize_id_map, serialize_id_map};
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::PathBuf;
 use std::sync::Arc;
@@ -56,10 +61,254 @@ pub struct CheckpointMetadata {
     /// Checksum (SHA-256) of the checkpoint data
     pub checksum: String,
 
+    /// Encoding of the checkpoint data
+    #[serde(default)]
+    pub format: SerializationFormat,
+
     /// Custom metadata for this checkpoint
     pub custom_metadata: serde_json::Value,
 }
 
+/// Default number of delta checkpoints written between two full state snapshots
+pub const DEFAULT_FULL_SNAPSHOT_INTERVAL: u64 = 10;
+
+/// Changes to a workflow instance state since the previous state checkpoint
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StateDelta {
+    /// Nodes whose status changed
+    #[serde(
+        serialize_with = "serialize_id_map",
+        deserialize_with = "deserialize_id_map"
+    )]
+    pub node_status: HashMap<NodeId, NodeStatus>,
+
+    /// Nodes whose in-degree changed
+    #[serde(
+        serialize_with = "serialize_id_map",
+        deserialize_with = "deserialize_id_map"
+    )]
+    pub node_in_degree: HashMap<NodeId, usize>,
+
+    /// Node results that were added or changed
+    #[serde(
+        serialize_with = "serialize_id_map",
+        deserialize_with = "deserialize_id_map"
+    )]
+    pub node_results: HashMap<NodeId, serde_json::Value>,
+
+    /// Node results that were removed
+    pub removed_results: Vec<NodeId>,
+
+    /// Edge conditions that were added or changed
+    #[serde(
+        serialize_with = "serialize_id_map",
+        deserialize_with = "deserialize_id_map"
+    )]
+    pub edge_conditions: HashMap<EdgeId, ConditionResult>,
+
+    /// Edge conditions that were removed
+    pub removed_conditions: Vec<EdgeId>,
+
+    /// Node timings that were added or changed
+    #[serde(
+        default,
+        serialize_with = "serialize_id_map",
+        deserialize_with = "deserialize_id_map"
+    )]
+    pub node_timings: HashMap<NodeId, NodeTiming>,
+
+    /// Node timings that were removed
+    #[serde(default)]
+    pub removed_timings: Vec<NodeId>,
+
+    /// Complete set of ready nodes (small, so it is always recorded in full)
+    pub ready_nodes: HashSet<NodeId>,
+
+    /// Last updated time
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+
+    /// Whether the workflow is complete
+    pub is_completed: bool,
+
+    /// Whether the workflow has failed
+    pub has_failed: bool,
+
+    /// Whether the workflow is paused
+    #[serde(default)]
+    pub is_paused: bool,
+
+    /// New instance metadata, if it changed
+    pub metadata: Option<serde_json::Value>,
+
+    /// Accumulated resource usage (small, so it is always recorded in full)
+    #[serde(default)]
+    pub resource_usage: Option<ExecutionResourceUsage>,
+
+    /// Why the engine failed the workflow, if it did
+    #[serde(default)]
+    pub failure_reason: Option<FailureReason>,
+}
+
+impl StateDelta {
+    /// Compute the changes needed to turn `previous` into `current`
+    pub fn between(previous: &WorkflowState, current: &WorkflowState) -> Self {
+        let mut delta = StateDelta {
+            ready_nodes: current.ready_nodes.clone(),
+            updated_at: current.updated_at,
+            is_completed: current.is_completed,
+            has_failed: current.has_failed,
+            is_paused: current.is_paused,
+            resource_usage: current.resource_usage,
+            failure_reason: current.failure_reason.clone(),
+            ..Default::default()
+        };
+
+        for (id, status) in &current.node_status {
+            if previous.node_status.get(id) != Some(status) {
+                delta.node_status.insert(id.clone(), *status);
+            }
+        }
+
+        for (id, in_degree) in &current.node_in_degree {
+            if previous.node_in_degree.get(id) != Some(in_degree) {
+                delta.node_in_degree.insert(id.clone(), *in_degree);
+            }
+        }
+
+        for (id, result) in &current.node_results {
+            if previous.node_results.get(id) != Some(result) {
+                delta.node_results.insert(id.clone(), result.clone());
+            }
+        }
+        for id in previous.node_results.keys() {
+            if !current.node_results.contains_key(id) {
+                delta.removed_results.push(id.clone());
+            }
+        }
+
+        for (id, condition) in &current.edge_conditions {
+            if previous.edge_conditions.get(id) != Some(condition) {
+                delta.edge_conditions.insert(id.clone(), *condition);
+            }
+        }
+        for id in previous.edge_conditions.keys() {
+            if !current.edge_conditions.contains_key(id) {
+                delta.removed_conditions.push(id.clone());
+            }
+        }
+
+        for (id, timing) in &current.node_timings {
+            if previous.node_timings.get(id) != Some(timing) {
+                delta.node_timings.insert(id.clone(), *timing);
+            }
+        }
+        for id in previous.node_timings.keys() {
+            if !current.node_timings.contains_key(id) {
+                delta.removed_timings.push(id.clone());
+            }
+        }
+
+        if previous.metadata != current.metadata {
+            delta.metadata = Some(current.metadata.clone());
+        }
+
+        delta
+    }
+
+    /// Apply this delta on top of a state
+    pub fn apply(&self, state: &mut WorkflowState) {
+        state
+            .node_status
+            .extend(self.node_status.iter().map(|(k, v)| (k.clone(), *v)));
+        state
+            .node_in_degree
+            .extend(self.node_in_degree.iter().map(|(k, v)| (k.clone(), *v)));
+        state.node_results.extend(
+            self.node_results
+                .iter()
+                .map(|(k, v)| (k.clone(), v.clone())),
+        );
+        for id in &self.removed_results {
+            state.node_results.remove(id);
+        }
+        state
+            .edge_conditions
+            .extend(self.edge_conditions.iter().map(|(k, v)| (k.clone(), *v)));
+        for id in &self.removed_conditions {
+            state.edge_conditions.remove(id);
+        }
+        state
+            .node_timings
+            .extend(self.node_timings.iter().map(|(k, v)| (k.clone(), *v)));
+        for id in &self.removed_timings {
+            state.node_timings.remove(id);
+        }
+
+        state.ready_nodes = self.ready_nodes.clone();
+        state.updated_at = self.updated_at;
+        state.is_completed = self.is_completed;
+        state.has_failed = self.has_failed;
+        state.is_paused = self.is_paused;
+        state.resource_usage = self.resource_usage;
+        state.failure_reason = self.failure_reason.clone();
+        if let Some(metadata) = &self.metadata {
+            state.metadata = metadata.clone();
+        }
+    }
+}
+
+/// Content of a state checkpoint
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "data", rename_all = "snake_case")]
+pub enum StateCheckpointPayload {
+    /// Complete instance state
+    Full(WorkflowState),
+
+    /// Changes since the previous state checkpoint
+    Delta(StateDelta),
+}
+
+/// A persisted checkpoint of a workflow instance state
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StateCheckpoint {
+    /// Instance this checkpoint belongs to
+    pub instance_id: String,
+
+    /// Sequence number, increasing per instance
+    pub sequence: u64,
+
+    /// Checkpoint schema version
+    pub version: String,
+
+    /// Timestamp when this checkpoint was created
+    pub created_at: chrono::DateTime<chrono::Utc>,
+
+    /// Full snapshot or delta
+    pub payload: StateCheckpointPayload,
+}
+
+impl StateCheckpoint {
+    /// Whether this checkpoint is a full snapshot
+    pub fn is_full(&self) -> bool {
+        matches!(self.payload, StateCheckpointPayload::Full(_))
+    }
+}
+
+/// Tracks the last persisted state of an instance so the next checkpoint can be a delta
+struct DeltaTracker {
+    /// State as of the last checkpoint
+    last_state: WorkflowState,
+
+    /// Sequence number of the last checkpoint
+    sequence: u64,
+
+    /// Number of deltas written since the last full snapshot
+    deltas_since_full: u64,
+}
+
+/// Delta tracker of an instance, locked while the instance is checkpointed
+type TrackerSlot = Arc<Mutex<Option<DeltaTracker>>>;
+
 /// Manager for persisting and restoring workflow state
 #[derive(Clone)]
 pub struct CheckpointManager<S: StorageBackend> {
@@ -74,6 +323,12 @@ pub struct CheckpointManager<S: StorageBackend> {
 
     /// Lock to ensure only one checkpoint operation happens at a time per workflow
     locks: Arc<tokio::sync::Mutex<std::collections::HashMap<WorkflowId, Arc<Mutex<()>>>>>,
+
+    /// Number of delta checkpoints between two full state snapshots
+    full_snapshot_interval: u64,
+
+    /// Last persisted state per unfinished instance, used to compute deltas
+    state_trackers: Arc<Mutex<HashMap<String, TrackerSlot>>>,
 }
 
 impl<S: StorageBackend> CheckpointManager<S> {
@@ -84,8 +339,23 @@ impl<S: StorageBackend> CheckpointManager<S> {
             base_dir: None,
             schema_version: schema_version.to_string(),
             locks: Arc::new(Mutex::new(std::collections::HashMap::new())),
+            full_snapshot_interval: DEFAULT_FULL_SNAPSHOT_INTERVAL,
+            state_trackers: Arc::new(Mutex::new(HashMap::new())),
         }
     }
+
+    /// Set how many delta checkpoints are written between full state snapshots
+    ///
+    /// An interval of 0 disables deltas and always writes full snapshots.
+    pub fn with_full_snapshot_interval(mut self, interval: u64) -> Self {
+        self.full_snapshot_interval = interval;
+        self
+    }
+
+    /// Get the full snapshot interval
+    pub fn full_snapshot_interval(&self) -> u64 {
+        self.full_snapshot_interval
+    }
 }
 
 impl CheckpointManager<crate::state::storage::FileStorage> {
@@ -146,9 +416,11 @@ impl<S: StorageBackend> CheckpointManager<S> {
             uuid::Uuid::new_v4().as_simple()
         );
 
-        // Serialize the workflow
-        let checkpoint_data =
-            serde_json::to_vec(workflow).map_err(CheckpointError::SerializationError)?;
+        // Serialize the workflow in the storage backend's format
+        let format = self.storage.format();
+        let checkpoint_data = format.serialize(workflow).map_err(|e| {
+            CheckpointError::StorageError(format!("Failed to encode checkpoint: {}", e))
+        })?;
 
         // Calculate checksum
         let checksum = calculate_sha256(&checkpoint_data);
@@ -161,6 +433,7 @@ impl<S: StorageBackend> CheckpointManager<S> {
             created_at: chrono::Utc::now(),
             size: checkpoint_data.len(),
             checksum,
+            format,
             custom_metadata: serde_json::Value::Null,
         };
 
@@ -270,9 +543,11 @@ impl<S: StorageBackend> CheckpointManager<S> {
             )));
         }
 
-        // Deserialize the workflow
-        let workflow: WorkflowDefinition = serde_json::from_slice(&checkpoint_data)
-            .map_err(CheckpointError::SerializationError)?;
+        // Deserialize the workflow in the format it was written with
+        let workflow: WorkflowDefinition =
+            metadata.format.deserialize(&checkpoint_data).map_err(|e| {
+                CheckpointError::StorageError(format!("Failed to decode checkpoint: {}", e))
+            })?;
 
         Ok(workflow)
     }
@@ -353,6 +628,317 @@ impl<S: StorageBackend> CheckpointManager<S> {
     }
 }
 
+impl<S: StorageBackend> CheckpointManager<S> {
+    /// Save a checkpoint of a workflow instance state
+    ///
+    /// Only the changes since the previous state checkpoint are written, except
+    /// for the first checkpoint of an instance and every `full_snapshot_interval`
+    /// deltas, when a full snapshot is written instead. Writing a full snapshot
+    /// deletes the older checkpoints of the instance, which it supersedes.
+    pub async fn save_state_checkpoint(
+        &self,
+        state: &WorkflowState,
+    ) -> Result<String, CheckpointError> {
+        let slot = self.tracker_slot(&state.instance_id).await;
+        let mut previous = slot.lock().await;
+
+        let (checkpoint, tracker) = self.next_state_checkpoint(previous.as_ref(), state).await?;
+
+        // Serialize and store atomically
+        let key = state_checkpoint_key(&state.instance_id, checkpoint.sequence);
+        let data = self.encode_state_checkpoint(&checkpoint)?;
+
+        let temp_key = format!("{}.tmp", key);
+        self.storage.store(&temp_key, &data).await.map_err(|e| {
+            CheckpointError::StorageError(format!("Failed to store state checkpoint: {}", e))
+        })?;
+        self.storage.rename(&temp_key, &key).await.map_err(|e| {
+            CheckpointError::StorageError(format!("Failed to finalize state checkpoint: {}", e))
+        })?;
+
+        // Remember what was persisted
+        self.track_persisted_state(&mut previous, tracker).await;
+
+        if checkpoint.is_full() {
+            self.prune_state_checkpoints_before(&state.instance_id, checkpoint.sequence)
+                .await?;
+        }
+
+        Ok(key)
+    }
+
+    /// Save checkpoints of several instance states in a single storage write
+    ///
+    /// Each state is checkpointed as by `save_state_checkpoint`, so the same
+    /// instance may appear more than once and is sequenced in input order.
+    /// Returns the checkpoint keys in input order.
+    pub async fn save_state_checkpoints(
+        &self,
+        states: &[WorkflowState],
+    ) -> Result<Vec<String>, CheckpointError> {
+        // Lock the trackers of the batch's instances, in a fixed order
+        let mut instance_ids: Vec<&str> = states.iter().map(|s| s.instance_id.as_str()).collect();
+        instance_ids.sort_unstable();
+        instance_ids.dedup();
+        let mut trackers = HashMap::with_capacity(instance_ids.len());
+        for instance_id in instance_ids {
+            let slot = self.tracker_slot(instance_id).await;
+            trackers.insert(instance_id, slot.lock_owned().await);
+        }
+
+        // Trackers only take effect once the whole batch is stored
+        let mut staged: HashMap<String, DeltaTracker> = HashMap::new();
+        let mut latest_full: HashMap<String, u64> = HashMap::new();
+        let mut keys = Vec::with_capacity(states.len());
+        let mut entries = Vec::with_capacity(states.len());
+
+        for state in states {
+            let previous = staged
+                .get(&state.instance_id)
+                .or_else(|| trackers.get(state.instance_id.as_str())?.as_ref());
+            let (checkpoint, tracker) = self.next_state_checkpoint(previous, state).await?;
+            if checkpoint.is_full() {
+                latest_full.insert(state.instance_id.clone(), checkpoint.sequence);
+            }
+
+            let key = state_checkpoint_key(&state.instance_id, checkpoint.sequence);
+            entries.push((key.clone(), self.encode_state_checkpoint(&checkpoint)?));
+            keys.push(key);
+            staged.insert(state.instance_id.clone(), tracker);
+        }
+
+        self.storage.store_batch(&entries).await.map_err(|e| {
+            CheckpointError::StorageError(format!("Failed to store state checkpoints: {}", e))
+        })?;
+
+        for (instance_id, tracker) in staged {
+            if let Some(previous) = trackers.get_mut(instance_id.as_str()) {
+                self.track_persisted_state(previous, tracker).await;
+            }
+        }
+
+        for (instance_id, sequence) in latest_full {
+            self.prune_state_checkpoints_before(&instance_id, sequence)
+                .await?;
+        }
+
+        Ok(keys)
+    }
+
+    /// Forget the last persisted state of an instance
+    ///
+    /// Its next state checkpoint is a full snapshot, numbered after those
+    /// already in storage.
+    pub async fn forget_instance_state(&self, instance_id: &str) {
+        self.state_trackers.lock().await.remove(instance_id);
+    }
+
+    /// Get the tracker slot of an instance, creating an empty one
+    async fn tracker_slot(&self, instance_id: &str) -> TrackerSlot {
+        self.state_trackers
+            .lock()
+            .await
+            .entry(instance_id.to_string())
+            .or_default()
+            .clone()
+    }
+
+    /// Keep the tracker of a stored checkpoint in the instance's locked slot,
+    /// or drop the slot once the instance has finished and takes no more
+    /// checkpoints
+    async fn track_persisted_state(&self, slot: &mut Option<DeltaTracker>, tracker: DeltaTracker) {
+        let state = &tracker.last_state;
+        if state.is_completed || state.has_failed {
+            self.state_trackers.lock().await.remove(&state.instance_id);
+            *slot = None;
+        } else {
+            *slot = Some(tracker);
+        }
+    }
+
+    /// Build the next checkpoint of an instance along with the tracker to keep once it is stored
+    async fn next_state_checkpoint(
+        &self,
+        tracker: Option<&DeltaTracker>,
+        state: &WorkflowState,
+    ) -> Result<(StateCheckpoint, DeltaTracker), CheckpointError> {
+        // Decide between a full snapshot and a delta
+        let (sequence, payload, deltas_since_full) = match tracker {
+            Some(tracker)
+                if self.full_snapshot_interval > 0
+                    && tracker.deltas_since_full < self.full_snapshot_interval =>
+            {
+                (
+                    tracker.sequence + 1,
+                    StateCheckpointPayload::Delta(StateDelta::between(&tracker.last_state, state)),
+                    tracker.deltas_since_full + 1,
+                )
+            }
+            Some(tracker) => (
+                tracker.sequence + 1,
+                StateCheckpointPayload::Full(state.clone()),
+                0,
+            ),
+            None => {
+                // Continue numbering after any checkpoints already in storage
+                let sequence = self
+                    .list_state_checkpoint_keys(&state.instance_id)
+                    .await?
+                    .last()
+                    .map(|(sequence, _)| sequence + 1)
+                    .unwrap_or(0);
+                (sequence, StateCheckpointPayload::Full(state.clone()), 0)
+            }
+        };
+
+        let checkpoint = StateCheckpoint {
+            instance_id: state.instance_id.clone(),
+            sequence,
+            version: self.schema_version.clone(),
+            created_at: chrono::Utc::now(),
+            payload,
+        };
+        let tracker = DeltaTracker {
+            last_state: state.clone(),
+            sequence,
+            deltas_since_full,
+        };
+
+        Ok((checkpoint, tracker))
+    }
+
+    /// Delete the state checkpoints of an instance older than the given sequence
+    async fn prune_state_checkpoints_before(
+        &self,
+        instance_id: &str,
+        sequence: u64,
+    ) -> Result<(), CheckpointError> {
+        for (_, key) in self
+            .list_state_checkpoint_keys(instance_id)
+            .await?
+            .into_iter()
+            .take_while(|(older, _)| *older < sequence)
+        {
+            self.storage.delete(&key).await.map_err(|e| {
+                CheckpointError::StorageError(format!("Failed to delete state checkpoint: {}", e))
+            })?;
+        }
+
+        Ok(())
+    }
+
+    /// Encode a state checkpoint in the storage backend's format
+    fn encode_state_checkpoint(
+        &self,
+        checkpoint: &StateCheckpoint,
+    ) -> Result<Vec<u8>, CheckpointError> {
+        self.storage.format().serialize(checkpoint).map_err(|e| {
+            CheckpointError::StorageError(format!("Failed to encode state checkpoint: {}", e))
+        })
+    }
+
+    /// Load a single state checkpoint by key
+    pub async fn load_state_checkpoint(
+        &self,
+        key: &str,
+    ) -> Result<StateCheckpoint, CheckpointError> {
+        let data = self.storage.load(key).await.map_err(|e| {
+            CheckpointError::StorageError(format!("Failed to load state checkpoint: {}", e))
+        })?;
+
+        let checkpoint: StateCheckpoint =
+            self.storage.format().deserialize(&data).map_err(|e| {
+                CheckpointError::StorageError(format!("Failed to decode state checkpoint: {}", e))
+            })?;
+
+        // Check schema version
+        if checkpoint.version != self.schema_version {
+            return Err(CheckpointError::SchemaVersionMismatch {
+                expected: self.schema_version.clone(),
+                found: checkpoint.version,
+            });
+        }
+
+        Ok(checkpoint)
+    }
+
+    /// Reconstruct the latest state of an instance
+    ///
+    /// Loads the most recent full snapshot and applies every later delta in order.
+    /// The returned state has no definition attached.
+    pub async fn load_latest_state(
+        &self,
+        instance_id: &str,
+    ) -> Result<WorkflowState, CheckpointError> {
+        let keys = self.list_state_checkpoint_keys(instance_id).await?;
+
+        // Walk backwards to the most recent full snapshot
+        let mut chain = Vec::new();
+        let mut base = None;
+        for (_, key) in keys.iter().rev() {
+            let checkpoint = self.load_state_checkpoint(key).await?;
+            match checkpoint.payload {
+                StateCheckpointPayload::Full(state) => {
+                    base = Some(state);
+                    break;
+                }
+                StateCheckpointPayload::Delta(delta) => chain.push(delta),
+            }
+        }
+
+        let mut state = match base {
+            Some(state) => state,
+            None => return Err(CheckpointError::NotFound(instance_id.to_string())),
+        };
+
+        // Apply deltas oldest first
+        for delta in chain.iter().rev() {
+            delta.apply(&mut state);
+        }
+
+        Ok(state)
+    }
+
+    /// List the state checkpoints of an instance
+    pub async fn list_state_checkpoints(
+        &self,
+        instance_id: &str,
+    ) -> Result<Vec<StateCheckpoint>, CheckpointError> {
+        let mut result = Vec::new();
+        for (_, key) in self.list_state_checkpoint_keys(instance_id).await? {
+            result.push(self.load_state_checkpoint(&key).await?);
+        }
+        Ok(result)
+    }
+
+    /// List state checkpoint keys of an instance, sorted by sequence number
+    async fn list_state_checkpoint_keys(
+        &self,
+        instance_id: &str,
+    ) -> Result<Vec<(u64, String)>, CheckpointError> {
+        let keys = self.storage.list().await.map_err(|e| {
+            CheckpointError::StorageError(format!("Failed to list checkpoints: {}", e))
+        })?;
+
+        let prefix = format!("{}.state-", instance_id);
+        let mut result: Vec<(u64, String)> = keys
+            .into_iter()
+            .filter_map(|key| {
+                let sequence = key.strip_prefix(&prefix)?.parse::<u64>().ok()?;
+                Some((sequence, key))
+            })
+            .collect();
+        result.sort_by_key(|(sequence, _)| *sequence);
+
+        Ok(result)
+    }
+}
+
+/// Storage key of a state checkpoint
+fn state_checkpoint_key(instance_id: &str, sequence: u64) -> String {
+    format!("{}.state-{:010}", instance_id, sequence)
+}
+
 /// Calculate SHA-256 checksum of data
 fn calculate_sha256(data: &[u8]) -> String {
     use sha2::{Digest, Sha256};
@@ -507,4 +1093,170 @@ mod tests {
             remaining.len()
         );
     }
+
+    #[tokio::test]
+    async fn test_state_checkpoint_deltas() {
+        let storage = crate::state::storage::MemoryStorage::new();
+        let manager = CheckpointManager::new(storage, "1.0.0").with_full_snapshot_interval(2);
+
+        // Create a state for the test workflow
+        let workflow = Arc::new(create_test_workflow());
+        let mut state = WorkflowState::new(workflow.clone());
+        let start_node_id = state.ready_nodes.iter().next().cloned().unwrap();
+
+        // First checkpoint is always a full snapshot
+        manager.save_state_checkpoint(&state).await.unwrap();
+
+        // Subsequent checkpoints are deltas until the interval is reached
+        state.set_node_running(&start_node_id).unwrap();
+        manager.save_state_checkpoint(&state).await.unwrap();
+        state
+            .set_node_completed(&start_node_id, serde_json::json!({"ok": true}))
+            .unwrap();
+        manager.save_state_checkpoint(&state).await.unwrap();
+
+        let checkpoints = manager
+            .list_state_checkpoints(&state.instance_id)
+            .await
+            .unwrap();
+        let kinds: Vec<bool> = checkpoints.iter().map(|c| c.is_full()).collect();
+        assert_eq!(kinds, vec![true, false, false]);
+
+        // A delta only carries the nodes that changed
+        match &checkpoints[1].payload {
+            StateCheckpointPayload::Delta(delta) => {
+                assert_eq!(delta.node_status.len(), 1);
+                assert!(delta.node_results.is_empty());
+            }
+            _ => panic!("Expected a delta checkpoint"),
+        }
+
+        // Reconstructed state matches the live state
+        let loaded = manager.load_latest_state(&state.instance_id).await.unwrap();
+        assert_eq!(loaded.node_status, state.node_status);
+        assert_eq!(loaded.node_in_degree, state.node_in_degree);
+        assert_eq!(loaded.node_results, state.node_results);
+        assert_eq!(loaded.ready_nodes, state.ready_nodes);
+
+        // The next full snapshot supersedes every older checkpoint
+        manager.save_state_checkpoint(&state).await.unwrap();
+        let checkpoints = manager
+            .list_state_checkpoints(&state.instance_id)
+            .await
+            .unwrap();
+        assert_eq!(checkpoints.len(), 1);
+        assert!(checkpoints[0].is_full());
+        assert_eq!(checkpoints[0].sequence, 3);
+
+        let loaded = manager.load_latest_state(&state.instance_id).await.unwrap();
+        assert_eq!(loaded.node_status, state.node_status);
+    }
+
+    #[tokio::test]
+    async fn test_batched_state_checkpoints() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let manager =
+            CheckpointManager::with_file_storage(temp_dir.path().to_path_buf(), "1.0.0").unwrap();
+
+        let workflow = Arc::new(create_test_workflow());
+        let first = WorkflowState::new(workflow.clone());
+        let mut second = WorkflowState::new(workflow.clone());
+        let start_node_id = second.ready_nodes.iter().next().cloned().unwrap();
+        let third = WorkflowState::new(workflow);
+
+        // The same instance twice in one batch yields a snapshot followed by a delta
+        let before = second.clone();
+        second.set_node_running(&start_node_id).unwrap();
+        let keys = manager
+            .save_state_checkpoints(&[first.clone(), before, second.clone(), third.clone()])
+            .await
+            .unwrap();
+        assert_eq!(keys.len(), 4);
+
+        // Every checkpoint in the batch is individually retrievable
+        for (key, state) in keys.iter().zip([&first, &second, &second, &third]) {
+            let checkpoint = manager.load_state_checkpoint(key).await.unwrap();
+            assert_eq!(checkpoint.instance_id, state.instance_id);
+        }
+        assert!(!manager
+            .load_state_checkpoint(&keys[2])
+            .await
+            .unwrap()
+            .is_full());
+
+        let loaded = manager
+            .load_latest_state(&second.instance_id)
+            .await
+            .unwrap();
+        assert_eq!(loaded.node_status, second.node_status);
+
+        // Later single checkpoints continue the batched sequence
+        let key = manager.save_state_checkpoint(&second).await.unwrap();
+        assert_eq!(
+            manager.load_state_checkpoint(&key).await.unwrap().sequence,
+            2
+        );
+    }
+
+    #[tokio::test]
+    async fn test_state_trackers_dropped_when_finished_or_forgotten() {
+        let storage = crate::state::storage::MemoryStorage::new();
+        let manager = CheckpointManager::new(storage, "1.0.0");
+
+        let workflow = Arc::new(create_test_workflow());
+        let mut running = WorkflowState::new(workflow.clone());
+        let mut finished = WorkflowState::new(workflow);
+        manager
+            .save_state_checkpoints(&[running.clone(), finished.clone()])
+            .await
+            .unwrap();
+        assert_eq!(manager.state_trackers.lock().await.len(), 2);
+
+        // A finished instance takes no more checkpoints
+        finished.is_completed = true;
+        manager.save_state_checkpoint(&finished).await.unwrap();
+        assert_eq!(manager.state_trackers.lock().await.len(), 1);
+
+        // A forgotten instance continues with a full snapshot
+        manager.forget_instance_state(&running.instance_id).await;
+        assert!(manager.state_trackers.lock().await.is_empty());
+        let start_node_id = running.ready_nodes.iter().next().cloned().unwrap();
+        running.set_node_running(&start_node_id).unwrap();
+        let key = manager.save_state_checkpoint(&running).await.unwrap();
+        let checkpoint = manager.load_state_checkpoint(&key).await.unwrap();
+        assert!(checkpoint.is_full());
+        assert_eq!(checkpoint.sequence, 1);
+    }
+
+    #[tokio::test]
+    async fn test_checkpoints_with_binary_format() {
+        use crate::state::storage::SerializationFormat;
+
+        let storage = crate::state::storage::MemoryStorage::new()
+            .with_format(SerializationFormat::MessagePack);
+        let manager = CheckpointManager::new(storage, "1.0.0");
+
+        // Definition checkpoints record their format and round-trip
+        let workflow = create_test_workflow();
+        let checkpoint_id = manager.save_checkpoint(&workflow).await.unwrap();
+        let checkpoints = manager.list_checkpoints(&workflow.id).await.unwrap();
+        assert_eq!(checkpoints[0].format, SerializationFormat::MessagePack);
+        let loaded = manager.load_checkpoint(&checkpoint_id).await.unwrap();
+        assert_eq!(loaded.nodes.len(), 2);
+
+        // State checkpoints use the same format
+        let state = WorkflowState::new(Arc::new(workflow));
+        manager.save_state_checkpoint(&state).await.unwrap();
+        let loaded = manager.load_latest_state(&state.instance_id).await.unwrap();
+        assert_eq!(loaded.node_status, state.node_status);
+    }
+
+    #[tokio::test]
+    async fn test_load_latest_state_not_found() {
+        let storage = crate::state::storage::MemoryStorage::new();
+        let manager = CheckpointManager::new(storage, "1.0.0");
+
+        let result = manager.load_latest_state("missing-instance").await;
+        assert!(matches!(result, Err(CheckpointError::NotFound(_))));
+    }
 }
@@ -1,12 +1,13 @@
-use crate::model::{EdgeId, NodeId, NodeStatus, WorkflowDefinition, WorkflowId};
+use crate::model::{EdgeId, FailurePolicy, NodeId, NodeStatus, WorkflowDefinition, WorkflowId};
 use crate::state::checkpoint::{CheckpointError, CheckpointManager};
 use crate::state::storage::StorageBackend;
 use crate::utils::serialization::{deserialize_id_map, serialize_id_map};
+use lion_core::types::workflow::{Principal, ResourceQuota};
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use thiserror::Error;
-use tokio::sync::RwLock;
+use tokio::sync::{broadcast, RwLock};
 
 /// Error types for state machine operations
 #[derive(Error, Debug)]
@@ -49,6 +50,224 @@ pub enum ConditionResult {
     Error,
 }
 
+/// Overall status of a workflow instance
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum InstanceStatus {
+    /// Instance is waiting for an execution slot
+    Queued,
+    /// Instance is executing nodes
+    Running,
+    /// Instance is paused; running nodes finish but no new nodes start
+    Paused,
+    /// All nodes have finished successfully
+    Completed,
+    /// At least one node has failed
+    Failed,
+}
+
+impl std::fmt::Display for InstanceStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InstanceStatus::Queued => write!(f, "Queued"),
+            InstanceStatus::Running => write!(f, "Running"),
+            InstanceStatus::Paused => write!(f, "Paused"),
+            InstanceStatus::Completed => write!(f, "Completed"),
+            InstanceStatus::Failed => write!(f, "Failed"),
+        }
+    }
+}
+
+/// Execution timing of a single node
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NodeTiming {
+    /// When the latest attempt started
+    pub started_at: Option<chrono::DateTime<chrono::Utc>>,
+
+    /// When the node reached a terminal status
+    pub finished_at: Option<chrono::DateTime<chrono::Utc>>,
+
+    /// Number of times the node was started
+    pub attempts: u32,
+}
+
+/// Name of the custom resource metric carrying the fuel a node consumed
+pub const FUEL_METRIC: &str = "fuel_consumed";
+
+/// Resources consumed by all nodes of a workflow instance
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExecutionResourceUsage {
+    /// Total CPU time, in microseconds
+    pub cpu_time_us: u64,
+
+    /// Highest memory usage of any node, in bytes
+    pub peak_memory_bytes: usize,
+
+    /// Total fuel consumed
+    pub fuel_consumed: u64,
+
+    /// Number of node executions that reported usage
+    pub nodes_metered: usize,
+}
+
+impl ExecutionResourceUsage {
+    /// Add the usage reported for one node execution
+    pub fn record(&mut self, usage: &lion_core::types::ResourceUsage) {
+        self.cpu_time_us += usage.cpu_time_us;
+        self.peak_memory_bytes = self
+            .peak_memory_bytes
+            .max(usage.peak_memory_bytes.max(usage.memory_bytes));
+        if let Some(fuel) = usage.custom_metrics.get(FUEL_METRIC) {
+            self.fuel_consumed += *fuel as u64;
+        }
+        self.nodes_metered += 1;
+    }
+}
+
+/// Resource limited by a [`ResourceQuota`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum QuotaKind {
+    /// Total CPU time, in milliseconds
+    CpuTime,
+
+    /// Peak memory, in bytes
+    Memory,
+
+    /// Number of node executions
+    NodeCount,
+}
+
+impl std::fmt::Display for QuotaKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            QuotaKind::CpuTime => write!(f, "cpu_time_ms"),
+            QuotaKind::Memory => write!(f, "memory_bytes"),
+            QuotaKind::NodeCount => write!(f, "node_count"),
+        }
+    }
+}
+
+/// Why a workflow instance failed, when the engine rather than a node failed it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FailureReason {
+    /// A resource quota of the execution was exceeded
+    QuotaExceeded {
+        /// Resource whose limit was crossed
+        quota: QuotaKind,
+
+        /// Configured limit
+        limit: u64,
+
+        /// Amount used (or about to be used) when the breach was detected
+        used: u64,
+    },
+
+    /// The execution was cancelled
+    Cancelled,
+}
+
+impl std::fmt::Display for FailureReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FailureReason::QuotaExceeded { quota, limit, used } => {
+                write!(f, "Quota exceeded: {} used {} of {}", quota, used, limit)
+            }
+            FailureReason::Cancelled => write!(f, "Execution cancelled"),
+        }
+    }
+}
+
+/// Outcome of running a node's compensation during a rollback
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CompensationOutcome {
+    /// Node whose effects were undone
+    pub node_id: NodeId,
+
+    /// Node type of the compensation handler
+    pub handler: String,
+
+    /// Why the compensation failed, if it did
+    pub error: Option<String>,
+}
+
+impl CompensationOutcome {
+    /// Whether the compensation succeeded
+    pub fn succeeded(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+/// Why a node was skipped instead of run
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SkipReason {
+    /// The node failed and its error policy skips it
+    ErrorPolicy,
+
+    /// The condition of an incoming edge evaluated to false
+    ConditionFalse,
+
+    /// A parent node failed
+    ParentFailed,
+
+    /// Every parent was skipped, or a parent was skipped after a failure
+    ParentSkipped,
+}
+
+impl std::fmt::Display for SkipReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SkipReason::ErrorPolicy => write!(f, "error-policy"),
+            SkipReason::ConditionFalse => write!(f, "condition-false"),
+            SkipReason::ParentFailed => write!(f, "parent-failed"),
+            SkipReason::ParentSkipped => write!(f, "parent-skipped"),
+        }
+    }
+}
+
+/// One node's entry in an instance timeline
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NodeTimelineEntry {
+    /// Node this entry describes
+    pub node_id: NodeId,
+
+    /// Node name, if the definition is attached
+    pub node_name: Option<String>,
+
+    /// Current status of the node
+    pub status: NodeStatus,
+
+    /// When the latest attempt started
+    pub started_at: Option<chrono::DateTime<chrono::Utc>>,
+
+    /// When the node reached a terminal status
+    pub finished_at: Option<chrono::DateTime<chrono::Utc>>,
+
+    /// Number of times the node was started
+    pub attempts: u32,
+
+    /// Why the node was skipped, if it was
+    pub skip_reason: Option<SkipReason>,
+}
+
+/// Number of node status events buffered for slow subscribers
+const NODE_EVENT_CAPACITY: usize = 1024;
+
+/// A node of a workflow instance changed status
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NodeStatusEvent {
+    /// Instance the node belongs to
+    pub instance_id: String,
+
+    /// Node that changed status
+    pub node_id: NodeId,
+
+    /// Status the node moved to
+    pub status: NodeStatus,
+
+    /// When the node moved to it
+    pub at: chrono::DateTime<chrono::Utc>,
+}
+
 /// State of a workflow execution instance
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WorkflowState {
@@ -90,6 +309,22 @@ pub struct WorkflowState {
     )]
     pub edge_conditions: HashMap<EdgeId, ConditionResult>,
 
+    /// Start/finish times and attempt counts of nodes that have run
+    #[serde(
+        default,
+        serialize_with = "serialize_id_map",
+        deserialize_with = "deserialize_id_map"
+    )]
+    pub node_timings: HashMap<NodeId, NodeTiming>,
+
+    /// Why each skipped node was skipped
+    #[serde(
+        default,
+        serialize_with = "serialize_id_map",
+        deserialize_with = "deserialize_id_map"
+    )]
+    pub skip_reasons: HashMap<NodeId, SkipReason>,
+
     /// Set of nodes that are currently ready (in-degree = 0, status = Pending)
     pub ready_nodes: HashSet<NodeId>,
 
@@ -105,8 +340,41 @@ pub struct WorkflowState {
     /// Whether this workflow has failed
     pub has_failed: bool,
 
+    /// Whether this workflow is paused (no new nodes are scheduled)
+    #[serde(default)]
+    pub is_paused: bool,
+
+    /// Identity on whose behalf this instance runs
+    #[serde(default)]
+    pub principal: Option<Principal>,
+
+    /// Resources consumed so far (`None` unless collection is enabled)
+    #[serde(default)]
+    pub resource_usage: Option<ExecutionResourceUsage>,
+
+    /// Resource limits of this instance
+    #[serde(default)]
+    pub quota: ResourceQuota,
+
+    /// Why the engine failed this instance, if it did
+    #[serde(default)]
+    pub failure_reason: Option<FailureReason>,
+
+    /// Whether the compensations of completed nodes run if this instance fails
+    #[serde(default)]
+    pub rollback_on_failure: bool,
+
+    /// Outcomes of the compensations run by a rollback, in the order they
+    /// ran (`None` unless a rollback has finished)
+    #[serde(default)]
+    pub compensations: Option<Vec<CompensationOutcome>>,
+
     /// Additional metadata for this workflow instance
     pub metadata: serde_json::Value,
+
+    /// Channel node status changes are published to, if watched
+    #[serde(skip)]
+    node_events: Option<broadcast::Sender<NodeStatusEvent>>,
 }
 
 impl WorkflowState {
@@ -139,21 +407,50 @@ impl WorkflowState {
             node_in_degree,
             node_results: HashMap::new(),
             edge_conditions: HashMap::new(),
+            node_timings: HashMap::new(),
+            skip_reasons: HashMap::new(),
             ready_nodes,
             created_at: now,
             updated_at: now,
             is_completed: false,
             has_failed: false,
+            is_paused: false,
+            principal: None,
+            resource_usage: None,
+            quota: ResourceQuota::default(),
+            failure_reason: None,
+            rollback_on_failure: false,
+            compensations: None,
             metadata: serde_json::Value::Null,
+            node_events: None,
         }
     }
 
+    /// Publish this instance's node status changes to a channel
+    pub fn with_node_events(mut self, sender: broadcast::Sender<NodeStatusEvent>) -> Self {
+        self.node_events = Some(sender);
+        self
+    }
+
     /// Set the definition for this workflow state
     pub fn with_definition(mut self, definition: Arc<WorkflowDefinition>) -> Self {
         self.definition = Some(definition);
         self
     }
 
+    /// Get the overall status of this workflow instance
+    pub fn status(&self) -> InstanceStatus {
+        if self.has_failed {
+            InstanceStatus::Failed
+        } else if self.is_completed {
+            InstanceStatus::Completed
+        } else if self.is_paused {
+            InstanceStatus::Paused
+        } else {
+            InstanceStatus::Running
+        }
+    }
+
     /// Get the current status of a node
     pub fn get_node_status(&self, node_id: &NodeId) -> Option<NodeStatus> {
         self.node_status.get(node_id).copied()
@@ -182,10 +479,14 @@ impl WorkflowState {
         }
 
         // Update status
-        self.node_status
-            .insert(node_id.clone(), NodeStatus::Running);
+        let now = chrono::Utc::now();
+        self.set_node_status(node_id, NodeStatus::Running);
         self.ready_nodes.remove(node_id);
-        self.updated_at = chrono::Utc::now();
+        let timing = self.node_timings.entry(node_id.clone()).or_default();
+        timing.started_at = Some(now);
+        timing.finished_at = None;
+        timing.attempts += 1;
+        self.updated_at = now;
 
         Ok(())
     }
@@ -211,34 +512,143 @@ impl WorkflowState {
         }
 
         // Update status and store result
-        self.node_status
-            .insert(node_id.clone(), NodeStatus::Completed);
+        self.set_node_status(node_id, NodeStatus::Completed);
         self.node_results.insert(node_id.clone(), result);
         self.updated_at = chrono::Utc::now();
+        self.mark_node_finished(node_id);
+
+        Ok(self.release_successors(node_id))
+    }
+
+    /// Set a failed node as skipped so the workflow continues without it
+    ///
+    /// The error is stored as the node's result and its successors become
+    /// ready as if it had completed.
+    pub fn set_node_skipped(
+        &mut self,
+        node_id: &NodeId,
+        error: serde_json::Value,
+    ) -> Result<Vec<NodeId>, StateMachineError> {
+        // Check if node exists
+        if !self.node_status.contains_key(node_id) {
+            return Err(StateMachineError::NodeNotFound(node_id.clone()));
+        }
+
+        let current_status = self.node_status[node_id];
+        if current_status != NodeStatus::Running && current_status != NodeStatus::Ready {
+            return Err(StateMachineError::InvalidTransition(
+                current_status,
+                NodeStatus::Skipped,
+            ));
+        }
+
+        self.node_results.insert(node_id.clone(), error);
+        self.skip_node(node_id, SkipReason::ErrorPolicy);
 
-        // Find outgoing edges to activate next nodes
+        Ok(self.release_successors(node_id))
+    }
+
+    /// Why a node was skipped, if it was
+    pub fn skip_reason(&self, node_id: &NodeId) -> Option<SkipReason> {
+        self.skip_reasons.get(node_id).copied()
+    }
+
+    /// Move a node to a status, publishing the change if watched
+    fn set_node_status(&mut self, node_id: &NodeId, status: NodeStatus) {
+        self.node_status.insert(node_id.clone(), status);
+        if let Some(sender) = &self.node_events {
+            // Nobody may be subscribed
+            let _ = sender.send(NodeStatusEvent {
+                instance_id: self.instance_id.clone(),
+                node_id: node_id.clone(),
+                status,
+                at: chrono::Utc::now(),
+            });
+        }
+    }
+
+    /// Mark a node as skipped for `reason`
+    fn skip_node(&mut self, node_id: &NodeId, reason: SkipReason) {
+        self.set_node_status(node_id, NodeStatus::Skipped);
+        self.skip_reasons.insert(node_id.clone(), reason);
+        self.ready_nodes.remove(node_id);
+        self.updated_at = chrono::Utc::now();
+        self.node_timings
+            .entry(node_id.clone())
+            .or_default()
+            .finished_at = Some(self.updated_at);
+    }
+
+    /// Edges leaving a node, with their targets
+    fn outgoing_edges(&self, node_id: &NodeId) -> Vec<(EdgeId, NodeId)> {
+        let Some(definition) = &self.definition else {
+            return Vec::new();
+        };
+        let Some(node) = definition.nodes.get(node_id) else {
+            return Vec::new();
+        };
+        node.outgoing_edges
+            .iter()
+            .filter_map(|edge_id| definition.edges.get(edge_id))
+            .map(|edge| (edge.id.clone(), edge.target.clone()))
+            .collect()
+    }
+
+    /// Whether every parent of a node was skipped by a condition or cascade
+    fn all_parents_skipped(&self, node_id: &NodeId) -> bool {
+        let Some(definition) = &self.definition else {
+            return false;
+        };
+        let Some(node) = definition.nodes.get(node_id) else {
+            return false;
+        };
+        node.incoming_edges
+            .iter()
+            .filter_map(|edge_id| definition.edges.get(edge_id))
+            .all(|edge| {
+                !matches!(
+                    self.skip_reason(&edge.source),
+                    None | Some(SkipReason::ErrorPolicy)
+                )
+            })
+    }
+
+    /// Decrease the in-degree of a finished node's successors, returning
+    /// those that became ready
+    ///
+    /// The target of an edge whose condition failed is skipped, as is a node
+    /// whose parents were all skipped that way; their successors are
+    /// released in turn.
+    fn release_successors(&mut self, node_id: &NodeId) -> Vec<NodeId> {
         let mut newly_ready = Vec::new();
+        let mut finished = vec![node_id.clone()];
+
+        while let Some(finished_id) = finished.pop() {
+            for (edge_id, target) in self.outgoing_edges(&finished_id) {
+                let pending = self.node_status.get(&target) == Some(&NodeStatus::Pending);
+                if pending && self.edge_conditions.get(&edge_id) == Some(&ConditionResult::Failed) {
+                    self.skip_node(&target, SkipReason::ConditionFalse);
+                    finished.push(target);
+                    continue;
+                }
 
-        if let Some(definition) = &self.definition {
-            if let Some(node) = definition.nodes.get(node_id) {
-                for edge_id in &node.outgoing_edges {
-                    if let Some(edge) = definition.edges.get(edge_id) {
-                        // Decrease in-degree of target node
-                        if let Some(in_degree) = self.node_in_degree.get_mut(&edge.target) {
-                            if *in_degree > 0 {
-                                *in_degree -= 1;
-
-                                // If in-degree is now 0, mark as ready
-                                if *in_degree == 0 {
-                                    if let Some(status) = self.node_status.get(&edge.target) {
-                                        if *status == NodeStatus::Pending {
-                                            self.ready_nodes.insert(edge.target.clone());
-                                            newly_ready.push(edge.target.clone());
-                                        }
-                                    }
-                                }
-                            }
-                        }
+                // Decrease in-degree of target node
+                let Some(in_degree) = self.node_in_degree.get_mut(&target) else {
+                    continue;
+                };
+                if *in_degree == 0 {
+                    continue;
+                }
+                *in_degree -= 1;
+
+                // If in-degree is now 0, mark as ready
+                if *in_degree == 0 && pending {
+                    if self.all_parents_skipped(&target) {
+                        self.skip_node(&target, SkipReason::ParentSkipped);
+                        finished.push(target);
+                    } else {
+                        self.ready_nodes.insert(target.clone());
+                        newly_ready.push(target);
                     }
                 }
             }
@@ -247,7 +657,21 @@ impl WorkflowState {
         // Check if workflow is completed (all nodes completed or failed)
         self.check_workflow_completion();
 
-        Ok(newly_ready)
+        newly_ready
+    }
+
+    /// Skip the pending descendants of a failed node, which can no longer run
+    fn skip_descendants_of_failed(&mut self, node_id: &NodeId) {
+        let mut failed_parents = vec![(node_id.clone(), SkipReason::ParentFailed)];
+
+        while let Some((parent_id, reason)) = failed_parents.pop() {
+            for (_, target) in self.outgoing_edges(&parent_id) {
+                if self.node_status.get(&target) == Some(&NodeStatus::Pending) {
+                    self.skip_node(&target, reason);
+                    failed_parents.push((target, SkipReason::ParentSkipped));
+                }
+            }
+        }
     }
 
     /// Set a node as failed
@@ -255,6 +679,50 @@ impl WorkflowState {
         &mut self,
         node_id: &NodeId,
         error: serde_json::Value,
+    ) -> Result<(), StateMachineError> {
+        self.record_failure(node_id, error)?;
+        self.has_failed = true;
+        self.skip_descendants_of_failed(node_id);
+
+        // Check if workflow is completed
+        self.check_workflow_completion();
+
+        Ok(())
+    }
+
+    /// Set a node as failed, handling the rest of the workflow per `policy`
+    ///
+    /// Returns the successors that became ready, which only
+    /// `FailurePolicy::Continue` releases.
+    pub fn set_node_failed_with_policy(
+        &mut self,
+        node_id: &NodeId,
+        error: serde_json::Value,
+        policy: FailurePolicy,
+    ) -> Result<Vec<NodeId>, StateMachineError> {
+        match policy {
+            FailurePolicy::Abort => {
+                self.set_node_failed(node_id, error)?;
+                Ok(Vec::new())
+            }
+            FailurePolicy::Continue => {
+                self.record_failure(node_id, error)?;
+                Ok(self.release_successors(node_id))
+            }
+            FailurePolicy::SkipDownstream => {
+                self.record_failure(node_id, error)?;
+                self.skip_descendants_of_failed(node_id);
+                self.check_workflow_completion();
+                Ok(Vec::new())
+            }
+        }
+    }
+
+    /// Mark a running or ready node as failed, storing its error as its result
+    fn record_failure(
+        &mut self,
+        node_id: &NodeId,
+        error: serde_json::Value,
     ) -> Result<(), StateMachineError> {
         // Check if node exists
         if !self.node_status.contains_key(node_id) {
@@ -271,14 +739,65 @@ impl WorkflowState {
         }
 
         // Update status and store error
-        self.node_status.insert(node_id.clone(), NodeStatus::Failed);
+        self.set_node_status(node_id, NodeStatus::Failed);
         self.node_results.insert(node_id.clone(), error);
         self.ready_nodes.remove(node_id);
-        self.has_failed = true;
         self.updated_at = chrono::Utc::now();
+        self.mark_node_finished(node_id);
 
-        // Check if workflow is completed
-        self.check_workflow_completion();
+        Ok(())
+    }
+
+    /// Cancel the unfinished nodes among `node_ids` and fail the workflow
+    ///
+    /// Nodes that already reached a terminal status are left untouched. The
+    /// error is stored as the result of each cancelled node. Returns the
+    /// nodes that were cancelled.
+    pub fn cancel_nodes(&mut self, node_ids: &[NodeId], error: serde_json::Value) -> Vec<NodeId> {
+        let mut cancelled = Vec::new();
+        for node_id in node_ids {
+            let unfinished = matches!(
+                self.node_status.get(node_id),
+                Some(NodeStatus::Pending | NodeStatus::Ready | NodeStatus::Running)
+            );
+            if unfinished {
+                self.set_node_status(node_id, NodeStatus::Cancelled);
+                self.node_results.insert(node_id.clone(), error.clone());
+                self.ready_nodes.remove(node_id);
+                self.mark_node_finished(node_id);
+                cancelled.push(node_id.clone());
+            }
+        }
+
+        if !cancelled.is_empty() {
+            self.has_failed = true;
+            self.updated_at = chrono::Utc::now();
+        }
+
+        cancelled
+    }
+
+    /// Return a running node to the ready set
+    ///
+    /// Used when a node's execution is abandoned (e.g. cancelled during
+    /// shutdown) so that it runs again once the instance is resumed.
+    pub fn requeue_node(&mut self, node_id: &NodeId) -> Result<(), StateMachineError> {
+        // Check if node exists
+        if !self.node_status.contains_key(node_id) {
+            return Err(StateMachineError::NodeNotFound(node_id.clone()));
+        }
+
+        let current_status = self.node_status[node_id];
+        if current_status != NodeStatus::Running {
+            return Err(StateMachineError::InvalidTransition(
+                current_status,
+                NodeStatus::Ready,
+            ));
+        }
+
+        self.set_node_status(node_id, NodeStatus::Ready);
+        self.ready_nodes.insert(node_id.clone());
+        self.updated_at = chrono::Utc::now();
 
         Ok(())
     }
@@ -303,6 +822,148 @@ impl WorkflowState {
         Ok(())
     }
 
+    /// Record the finish time of a node that has started
+    fn mark_node_finished(&mut self, node_id: &NodeId) {
+        if let Some(timing) = self.node_timings.get_mut(node_id) {
+            timing.finished_at = Some(chrono::Utc::now());
+        }
+    }
+
+    /// Fraction of the work done, from 0.0 to 1.0
+    ///
+    /// Nodes that completed, failed, were skipped or were cancelled count as
+    /// done, each weighted by its estimated cost. This is a single pass over
+    /// the node statuses.
+    pub fn progress(&self) -> f32 {
+        let Some(definition) = &self.definition else {
+            return if self.is_completed { 1.0 } else { 0.0 };
+        };
+
+        let (mut done, mut total) = (0.0, 0.0);
+        for (node_id, status) in &self.node_status {
+            let weight = definition
+                .nodes
+                .get(node_id)
+                .and_then(|node| node.estimated_cost)
+                .map_or(1.0, f64::from);
+            total += weight;
+            if matches!(
+                status,
+                NodeStatus::Completed
+                    | NodeStatus::Failed
+                    | NodeStatus::Skipped
+                    | NodeStatus::Cancelled
+            ) {
+                done += weight;
+            }
+        }
+
+        if total > 0.0 {
+            (done / total) as f32
+        } else if self.is_completed {
+            1.0
+        } else {
+            0.0
+        }
+    }
+
+    /// Resources consumed by this instance, if collection is enabled
+    pub fn resource_usage(&self) -> Option<ExecutionResourceUsage> {
+        self.resource_usage
+    }
+
+    /// Add the resource usage reported by a node, if collection is enabled
+    pub fn record_resource_usage(&mut self, usage: &lion_core::types::ResourceUsage) {
+        if let Some(total) = &mut self.resource_usage {
+            total.record(usage);
+            self.updated_at = chrono::Utc::now();
+        }
+    }
+
+    /// The first quota this instance has exceeded, if any
+    ///
+    /// `pending_starts` is the number of nodes about to be started; they
+    /// count towards the node quota.
+    pub fn quota_breach(&self, pending_starts: usize) -> Option<FailureReason> {
+        let usage = self.resource_usage.unwrap_or_default();
+        let cpu_time_ms = usage.cpu_time_us / 1000;
+        if let Some(limit) = self.quota.max_cpu_time_ms.filter(|l| cpu_time_ms > *l) {
+            return Some(FailureReason::QuotaExceeded {
+                quota: QuotaKind::CpuTime,
+                limit,
+                used: cpu_time_ms,
+            });
+        }
+        if let Some(limit) = self
+            .quota
+            .max_memory_bytes
+            .filter(|l| usage.peak_memory_bytes > *l)
+        {
+            return Some(FailureReason::QuotaExceeded {
+                quota: QuotaKind::Memory,
+                limit: limit as u64,
+                used: usage.peak_memory_bytes as u64,
+            });
+        }
+        let started: usize = self
+            .node_timings
+            .values()
+            .map(|t| t.attempts as usize)
+            .sum();
+        let nodes = started + pending_starts;
+        if let Some(limit) = self.quota.max_nodes.filter(|l| nodes > *l) {
+            return Some(FailureReason::QuotaExceeded {
+                quota: QuotaKind::NodeCount,
+                limit: limit as u64,
+                used: nodes as u64,
+            });
+        }
+        None
+    }
+
+    /// Fail the instance if it has exceeded a quota
+    ///
+    /// Every unfinished node is cancelled with the breach as its result.
+    /// Returns the breach, if there was one.
+    pub fn enforce_quota(&mut self, pending_starts: usize) -> Option<FailureReason> {
+        let reason = self.quota_breach(pending_starts)?;
+        let node_ids: Vec<NodeId> = self.node_status.keys().cloned().collect();
+        self.cancel_nodes(
+            &node_ids,
+            serde_json::json!({ "error": reason.to_string() }),
+        );
+        self.has_failed = true;
+        self.failure_reason = Some(reason.clone());
+        self.updated_at = chrono::Utc::now();
+        Some(reason)
+    }
+
+    /// Execution timeline of this instance
+    ///
+    /// Contains one entry per node that has started, ordered by the start
+    /// time of its latest attempt.
+    pub fn timeline(&self) -> Vec<NodeTimelineEntry> {
+        let mut entries: Vec<NodeTimelineEntry> = self
+            .node_timings
+            .iter()
+            .map(|(id, timing)| NodeTimelineEntry {
+                node_id: id.clone(),
+                node_name: self
+                    .definition
+                    .as_ref()
+                    .and_then(|d| d.nodes.get(id))
+                    .map(|n| n.name.clone()),
+                status: self.node_status.get(id).copied().unwrap_or_default(),
+                started_at: timing.started_at,
+                finished_at: timing.finished_at,
+                attempts: timing.attempts,
+                skip_reason: self.skip_reason(id),
+            })
+            .collect();
+        entries.sort_by_key(|e| (e.started_at, e.finished_at));
+        entries
+    }
+
     /// Check if all nodes are completed or failed
     fn check_workflow_completion(&mut self) {
         if self.has_failed {
@@ -327,9 +988,16 @@ impl WorkflowState {
         self.updated_at = chrono::Utc::now();
         self.is_completed = false;
         self.has_failed = false;
+        self.is_paused = false;
         self.ready_nodes.clear();
         self.node_results.clear();
         self.edge_conditions.clear();
+        self.node_timings.clear();
+        self.skip_reasons.clear();
+        self.failure_reason = None;
+        if self.resource_usage.is_some() {
+            self.resource_usage = Some(ExecutionResourceUsage::default());
+        }
 
         // Reset node status and in-degree
         if let Some(definition) = &self.definition {
@@ -356,6 +1024,9 @@ pub struct StateMachineManager<S: StorageBackend> {
 
     /// Workflow definitions (cache)
     definitions: Arc<RwLock<HashMap<WorkflowId, Arc<WorkflowDefinition>>>>,
+
+    /// Channel the instances publish node status changes to
+    node_events: broadcast::Sender<NodeStatusEvent>,
 }
 
 impl<S: StorageBackend> StateMachineManager<S> {
@@ -365,6 +1036,7 @@ impl<S: StorageBackend> StateMachineManager<S> {
             states: Arc::new(RwLock::new(HashMap::new())),
             checkpoint_manager: None,
             definitions: Arc::new(RwLock::new(HashMap::new())),
+            node_events: broadcast::channel(NODE_EVENT_CAPACITY).0,
         }
     }
 
@@ -374,9 +1046,23 @@ impl<S: StorageBackend> StateMachineManager<S> {
             states: Arc::new(RwLock::new(HashMap::new())),
             checkpoint_manager: Some(checkpoint_manager),
             definitions: Arc::new(RwLock::new(HashMap::new())),
+            node_events: broadcast::channel(NODE_EVENT_CAPACITY).0,
         }
     }
 
+    /// Whether a checkpoint manager is configured
+    pub fn has_checkpoint_manager(&self) -> bool {
+        self.checkpoint_manager.is_some()
+    }
+
+    /// Subscribe to the node status changes of every instance
+    ///
+    /// Only changes after the call are received. A subscriber falling more
+    /// than 1024 events behind skips the oldest.
+    pub fn subscribe_node_events(&self) -> broadcast::Receiver<NodeStatusEvent> {
+        self.node_events.subscribe()
+    }
+
     /// Create a new workflow instance
     pub async fn create_instance(
         &self,
@@ -389,7 +1075,7 @@ impl<S: StorageBackend> StateMachineManager<S> {
         }
 
         // Create a new state
-        let state = WorkflowState::new(definition);
+        let state = WorkflowState::new(definition).with_node_events(self.node_events.clone());
         let instance_id = state.instance_id.clone();
         let state = Arc::new(RwLock::new(state));
 
@@ -495,6 +1181,77 @@ impl<S: StorageBackend> StateMachineManager<S> {
         }
     }
 
+    /// Checkpoint the execution state of a workflow instance
+    ///
+    /// Writes a delta against the previous state checkpoint, or a full
+    /// snapshot when the checkpoint manager's interval calls for one.
+    pub async fn checkpoint_instance_state(
+        &self,
+        instance_id: &str,
+    ) -> Result<String, StateMachineError> {
+        // Get the instance
+        let state_lock = match self.get_instance(instance_id).await {
+            Some(state) => state,
+            None => {
+                return Err(StateMachineError::Other(format!(
+                    "Instance not found: {}",
+                    instance_id
+                )))
+            }
+        };
+
+        let manager = match &self.checkpoint_manager {
+            Some(manager) => manager,
+            None => {
+                return Err(StateMachineError::Other(
+                    "Checkpoint manager not configured".to_string(),
+                ))
+            }
+        };
+
+        // Snapshot the state without holding the lock during I/O
+        let snapshot = state_lock.read().await.clone();
+        let checkpoint_id = manager.save_state_checkpoint(&snapshot).await?;
+        Ok(checkpoint_id)
+    }
+
+    /// Restore a workflow instance from its state checkpoints
+    ///
+    /// The latest full snapshot is loaded and all later deltas are applied on
+    /// top of it. The restored instance is registered with this manager.
+    pub async fn restore_instance(
+        &self,
+        instance_id: &str,
+    ) -> Result<Arc<RwLock<WorkflowState>>, StateMachineError> {
+        let manager = match &self.checkpoint_manager {
+            Some(manager) => manager,
+            None => {
+                return Err(StateMachineError::Other(
+                    "Checkpoint manager not configured".to_string(),
+                ))
+            }
+        };
+
+        // Rebuild the state and attach its definition, starting its next
+        // checkpoint from a full snapshot
+        let state = manager.load_latest_state(instance_id).await?;
+        manager.forget_instance_state(instance_id).await;
+        let definition = self.load_definition(&state.workflow_id).await?;
+        let state = Arc::new(RwLock::new(
+            state
+                .with_definition(definition)
+                .with_node_events(self.node_events.clone()),
+        ));
+
+        // Register the state
+        {
+            let mut states = self.states.write().await;
+            states.insert(instance_id.to_string(), state.clone());
+        }
+
+        Ok(state)
+    }
+
     /// Get nodes that are ready to execute
     pub async fn get_ready_nodes(
         &self,
@@ -556,9 +1313,10 @@ impl<S: StorageBackend> StateMachineManager<S> {
 
         // If workflow completed, create a final checkpoint
         if state.is_completed && !state.has_failed {
+            let final_state = state.clone();
             drop(state); // Release write lock before checkpoint
-            if let Some(_manager) = &self.checkpoint_manager {
-                // TODO: Save the final state
+            if let Some(manager) = &self.checkpoint_manager {
+                manager.save_state_checkpoint(&final_state).await?;
             }
         }
 
@@ -586,6 +1344,162 @@ impl<S: StorageBackend> StateMachineManager<S> {
         state.set_node_failed(node_id, error)
     }
 
+    /// Mark a node as failed under a failure policy, returning the successors
+    /// that became ready
+    pub async fn set_node_failed_with_policy(
+        &self,
+        instance_id: &str,
+        node_id: &NodeId,
+        error: serde_json::Value,
+        policy: FailurePolicy,
+    ) -> Result<Vec<NodeId>, StateMachineError> {
+        let state_lock = match self.get_instance(instance_id).await {
+            Some(state) => state,
+            None => {
+                return Err(StateMachineError::Other(format!(
+                    "Instance not found: {}",
+                    instance_id
+                )))
+            }
+        };
+
+        let mut state = state_lock.write().await;
+        let newly_ready = state.set_node_failed_with_policy(node_id, error, policy)?;
+
+        // If workflow completed, create a final checkpoint
+        if state.is_completed && !state.has_failed {
+            let final_state = state.clone();
+            drop(state); // Release write lock before checkpoint
+            if let Some(manager) = &self.checkpoint_manager {
+                manager.save_state_checkpoint(&final_state).await?;
+            }
+        }
+
+        Ok(newly_ready)
+    }
+
+    /// Pause or resume a workflow instance
+    pub async fn set_instance_paused(
+        &self,
+        instance_id: &str,
+        paused: bool,
+    ) -> Result<(), StateMachineError> {
+        let state_lock = match self.get_instance(instance_id).await {
+            Some(state) => state,
+            None => {
+                return Err(StateMachineError::Other(format!(
+                    "Instance not found: {}",
+                    instance_id
+                )))
+            }
+        };
+
+        let mut state = state_lock.write().await;
+        if state.is_completed {
+            return Err(StateMachineError::Other(format!(
+                "Instance already finished: {}",
+                instance_id
+            )));
+        }
+
+        state.is_paused = paused;
+        state.updated_at = chrono::Utc::now();
+        Ok(())
+    }
+
+    /// Fail an instance that has exceeded a quota, returning the breach
+    pub async fn enforce_quota(
+        &self,
+        instance_id: &str,
+        pending_starts: usize,
+    ) -> Result<Option<FailureReason>, StateMachineError> {
+        let state_lock = match self.get_instance(instance_id).await {
+            Some(state) => state,
+            None => {
+                return Err(StateMachineError::Other(format!(
+                    "Instance not found: {}",
+                    instance_id
+                )))
+            }
+        };
+
+        let mut state = state_lock.write().await;
+        Ok(state.enforce_quota(pending_starts))
+    }
+
+    /// Cancel the unfinished nodes among `node_ids` and fail the workflow
+    pub async fn cancel_nodes(
+        &self,
+        instance_id: &str,
+        node_ids: &[NodeId],
+        error: serde_json::Value,
+    ) -> Result<Vec<NodeId>, StateMachineError> {
+        let state_lock = match self.get_instance(instance_id).await {
+            Some(state) => state,
+            None => {
+                return Err(StateMachineError::Other(format!(
+                    "Instance not found: {}",
+                    instance_id
+                )))
+            }
+        };
+
+        let mut state = state_lock.write().await;
+        Ok(state.cancel_nodes(node_ids, error))
+    }
+
+    /// Return a running node to the ready set
+    pub async fn requeue_node(
+        &self,
+        instance_id: &str,
+        node_id: &NodeId,
+    ) -> Result<(), StateMachineError> {
+        let state_lock = match self.get_instance(instance_id).await {
+            Some(state) => state,
+            None => {
+                return Err(StateMachineError::Other(format!(
+                    "Instance not found: {}",
+                    instance_id
+                )))
+            }
+        };
+
+        let mut state = state_lock.write().await;
+        state.requeue_node(node_id)
+    }
+
+    /// Mark a failed node as skipped, returning the successors that became ready
+    pub async fn set_node_skipped(
+        &self,
+        instance_id: &str,
+        node_id: &NodeId,
+        error: serde_json::Value,
+    ) -> Result<Vec<NodeId>, StateMachineError> {
+        let state_lock = match self.get_instance(instance_id).await {
+            Some(state) => state,
+            None => {
+                return Err(StateMachineError::Other(format!(
+                    "Instance not found: {}",
+                    instance_id
+                )))
+            }
+        };
+
+        let mut state = state_lock.write().await;
+        let newly_ready = state.set_node_skipped(node_id, error)?;
+
+        // If workflow completed, create a final checkpoint
+        if state.is_completed && !state.has_failed {
+            let final_state = state.clone();
+            drop(state); // Release write lock before checkpoint
+            if let Some(manager) = &self.checkpoint_manager {
+                manager.save_state_checkpoint(&final_state).await?;
+            }
+        }
+
+        Ok(newly_ready)
+    }
+
     /// Schedule next nodes for execution in a workflow instance
     pub async fn schedule_next_nodes(
         &self,
@@ -855,4 +1769,269 @@ mod tests {
         assert_eq!(loaded_definition.nodes.len(), 3);
         assert_eq!(loaded_definition.edges.len(), 2);
     }
+
+    #[tokio::test]
+    async fn test_restore_instance_from_deltas() {
+        let storage = MemoryStorage::new();
+        let checkpoint_manager =
+            CheckpointManager::new(storage, "1.0.0").with_full_snapshot_interval(1);
+        let manager = StateMachineManager::with_checkpoint_manager(checkpoint_manager);
+
+        let workflow = create_test_workflow();
+        let node_id = |name: &str| {
+            workflow
+                .nodes
+                .iter()
+                .find(|(_, node)| node.name == name)
+                .map(|(id, _)| id.clone())
+                .unwrap()
+        };
+        let start_node_id = node_id("Start");
+        let middle_node_id = node_id("Middle");
+
+        // Create instance and take an initial full snapshot
+        let instance = manager.create_instance(workflow.clone()).await.unwrap();
+        let instance_id = instance.read().await.instance_id.clone();
+        manager
+            .checkpoint_instance_state(&instance_id)
+            .await
+            .unwrap();
+
+        // Complete the start node and checkpoint a delta
+        manager
+            .set_node_running(&instance_id, &start_node_id)
+            .await
+            .unwrap();
+        manager
+            .set_node_completed(&instance_id, &start_node_id, serde_json::json!({"v": 1}))
+            .await
+            .unwrap();
+        manager
+            .checkpoint_instance_state(&instance_id)
+            .await
+            .unwrap();
+
+        // Run the middle node, which forces a full snapshot, then another delta
+        manager
+            .set_node_running(&instance_id, &middle_node_id)
+            .await
+            .unwrap();
+        manager
+            .checkpoint_instance_state(&instance_id)
+            .await
+            .unwrap();
+        manager
+            .set_node_completed(&instance_id, &middle_node_id, serde_json::json!({"v": 2}))
+            .await
+            .unwrap();
+        manager
+            .checkpoint_instance_state(&instance_id)
+            .await
+            .unwrap();
+
+        // The second full snapshot superseded the first snapshot and its delta
+        let kinds: Vec<bool> = manager
+            .checkpoint_manager
+            .as_ref()
+            .unwrap()
+            .list_state_checkpoints(&instance_id)
+            .await
+            .unwrap()
+            .iter()
+            .map(|c| c.is_full())
+            .collect();
+        assert_eq!(kinds, vec![true, false]);
+
+        // Restore the instance, replacing the live one
+        let live = instance.read().await.clone();
+        let restored = manager.restore_instance(&instance_id).await.unwrap();
+        assert!(!Arc::ptr_eq(&restored, &instance));
+
+        // The restored state matches the live state
+        let restored = restored.read().await;
+        assert!(restored.definition.is_some());
+        assert_eq!(restored.node_status, live.node_status);
+        assert_eq!(restored.node_in_degree, live.node_in_degree);
+        assert_eq!(restored.node_results, live.node_results);
+        assert_eq!(restored.ready_nodes, live.ready_nodes);
+        assert_eq!(restored.timeline(), live.timeline());
+        assert_eq!(
+            restored.get_node_status(&middle_node_id),
+            Some(NodeStatus::Completed)
+        );
+    }
+
+    #[test]
+    fn test_skip_reasons() {
+        // "a" branches to "b" (whose edge condition fails) and "d"; "b"
+        // feeds "c", and both branches join at "j"
+        let mut workflow = WorkflowDefinition::new(WorkflowId::new(), "Branches".to_string());
+        let mut ids = HashMap::new();
+        for name in ["a", "b", "c", "d", "j"] {
+            let node = Node::new(NodeId::new(), name.to_string());
+            ids.insert(name, node.id.clone());
+            workflow.add_node(node).unwrap();
+        }
+        let mut edge = |source: &str, target: &str| {
+            let edge = Edge::new(EdgeId::new(), ids[source].clone(), ids[target].clone());
+            let edge_id = edge.id.clone();
+            workflow.add_edge(edge).unwrap();
+            edge_id
+        };
+        let a_to_b = edge("a", "b");
+        edge("a", "d");
+        edge("b", "c");
+        edge("b", "j");
+        edge("d", "j");
+        let mut state = WorkflowState::new(Arc::new(workflow));
+
+        state
+            .set_edge_condition(&a_to_b, ConditionResult::Failed)
+            .unwrap();
+        state.set_node_running(&ids["a"]).unwrap();
+        let ready = state
+            .set_node_completed(&ids["a"], serde_json::json!(null))
+            .unwrap();
+        assert_eq!(ready, vec![ids["d"].clone()]);
+        assert_eq!(
+            state.skip_reason(&ids["b"]),
+            Some(SkipReason::ConditionFalse)
+        );
+        assert_eq!(
+            state.skip_reason(&ids["c"]),
+            Some(SkipReason::ParentSkipped)
+        );
+
+        // The join still runs once its other parent completes
+        assert_eq!(state.get_node_status(&ids["j"]), Some(NodeStatus::Pending));
+        state.set_node_running(&ids["d"]).unwrap();
+        let ready = state
+            .set_node_completed(&ids["d"], serde_json::json!(null))
+            .unwrap();
+        assert_eq!(ready, vec![ids["j"].clone()]);
+        assert_eq!(state.skip_reason(&ids["j"]), None);
+
+        let skipped: Vec<_> = state
+            .timeline()
+            .into_iter()
+            .filter_map(|entry| entry.skip_reason.map(|reason| (entry.node_id, reason)))
+            .collect();
+        assert_eq!(skipped.len(), 2);
+
+        // Descendants of a failed node are skipped
+        let workflow = create_test_workflow();
+        let node_id = |name: &str| {
+            workflow
+                .nodes
+                .iter()
+                .find(|(_, node)| node.name == name)
+                .map(|(id, _)| id.clone())
+                .unwrap()
+        };
+        let mut state = WorkflowState::new(workflow.clone());
+        state.set_node_running(&node_id("Start")).unwrap();
+        state
+            .set_node_failed(&node_id("Start"), serde_json::json!({"error": "boom"}))
+            .unwrap();
+        assert_eq!(
+            state.skip_reason(&node_id("Middle")),
+            Some(SkipReason::ParentFailed)
+        );
+        assert_eq!(
+            state.skip_reason(&node_id("End")),
+            Some(SkipReason::ParentSkipped)
+        );
+
+        // A node skipped by its error policy lets its successors run
+        let mut state = WorkflowState::new(workflow.clone());
+        state.set_node_running(&node_id("Start")).unwrap();
+        let ready = state
+            .set_node_skipped(&node_id("Start"), serde_json::json!({"error": "boom"}))
+            .unwrap();
+        assert_eq!(ready, vec![node_id("Middle")]);
+        assert_eq!(
+            state.skip_reason(&node_id("Start")),
+            Some(SkipReason::ErrorPolicy)
+        );
+        assert_eq!(SkipReason::ParentFailed.to_string(), "parent-failed");
+    }
+
+    #[test]
+    fn test_timeline_records_attempts_in_order() {
+        let workflow = create_test_workflow();
+        let mut state = WorkflowState::new(workflow.clone());
+        let node_id = |name: &str| {
+            workflow
+                .nodes
+                .iter()
+                .find(|(_, node)| node.name == name)
+                .map(|(id, _)| id.clone())
+                .unwrap()
+        };
+        let start = node_id("Start");
+        let middle = node_id("Middle");
+
+        // Start runs twice before completing
+        state.set_node_running(&start).unwrap();
+        state.requeue_node(&start).unwrap();
+        state.set_node_running(&start).unwrap();
+        state
+            .set_node_completed(&start, serde_json::json!(1))
+            .unwrap();
+        state.set_node_running(&middle).unwrap();
+
+        let timeline = state.timeline();
+        assert_eq!(timeline.len(), 2);
+        assert_eq!(timeline[0].node_id, start);
+        assert_eq!(timeline[0].node_name.as_deref(), Some("Start"));
+        assert_eq!(timeline[0].status, NodeStatus::Completed);
+        assert_eq!(timeline[0].attempts, 2);
+        assert!(timeline[0].finished_at >= timeline[0].started_at);
+        assert_eq!(timeline[1].node_id, middle);
+        assert_eq!(timeline[1].status, NodeStatus::Running);
+        assert!(timeline[1].finished_at.is_none());
+        assert!(timeline[1].started_at >= timeline[0].finished_at);
+
+        state.reset();
+        assert!(state.timeline().is_empty());
+    }
+
+    #[test]
+    fn test_progress_weighs_nodes_by_cost() {
+        let mut workflow = (*create_test_workflow()).clone();
+        let node_id = |workflow: &WorkflowDefinition, name: &str| {
+            workflow
+                .nodes
+                .iter()
+                .find(|(_, node)| node.name == name)
+                .map(|(id, _)| id.clone())
+                .unwrap()
+        };
+        let start = node_id(&workflow, "Start");
+        let middle = node_id(&workflow, "Middle");
+        let end = node_id(&workflow, "End");
+        workflow.nodes.get_mut(&middle).unwrap().estimated_cost = Some(2);
+        let mut state = WorkflowState::new(Arc::new(workflow));
+        assert_eq!(state.progress(), 0.0);
+
+        // Start weighs 1 of 4, then Middle 2 more
+        state.set_node_running(&start).unwrap();
+        state
+            .set_node_completed(&start, serde_json::json!(1))
+            .unwrap();
+        assert_eq!(state.progress(), 0.25);
+        state.set_node_running(&middle).unwrap();
+        assert_eq!(state.progress(), 0.25);
+        state
+            .set_node_completed(&middle, serde_json::json!(2))
+            .unwrap();
+        assert_eq!(state.progress(), 0.75);
+
+        state.set_node_running(&end).unwrap();
+        state
+            .set_node_completed(&end, serde_json::json!(3))
+            .unwrap();
+        assert!(state.is_completed);
+        assert_eq!(state.progress(), 1.0);
+    }
 }
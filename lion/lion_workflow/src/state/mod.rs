@@ -2,6 +2,13 @@ pub mod checkpoint;
 pub mod machine;
 pub mod storage;
 
-pub use checkpoint::{CheckpointError, CheckpointManager, CheckpointMetadata};
-pub use machine::{ConditionResult, StateMachineError, StateMachineManager, WorkflowState};
-pub use storage::{FileStorage, MemoryStorage, StorageBackend, StorageError};
+pub use checkpoint::{
+    CheckpointError, CheckpointManager, CheckpointMetadata, StateCheckpoint,
+    StateCheckpointPayload, StateDelta,
+};
+pub use machine::{
+    CompensationOutcome, ConditionResult, ExecutionResourceUsage, FailureReason, InstanceStatus,
+    NodeStatusEvent, NodeTimelineEntry, NodeTiming, QuotaKind, SkipReason, StateMachineError,
+    StateMachineManager, WorkflowState, FUEL_METRIC,
+};
+pub use storage::{FileStorage, MemoryStorage, SerializationFormat, StorageBackend, StorageError};
@@ -1,4 +1,6 @@
 use async_trait::async_trait;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
 use std::fs;
 use std::io;
 use std::path::PathBuf;
@@ -26,12 +28,72 @@ pub enum StorageError {
     Other(String),
 }
 
+/// Encoding used for values written to a storage backend
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum SerializationFormat {
+    /// JSON (human-readable)
+    #[default]
+    Json,
+
+    /// MessagePack (compact binary)
+    MessagePack,
+
+    /// CBOR (compact binary)
+    Cbor,
+}
+
+impl SerializationFormat {
+    /// Encode a value in this format
+    pub fn serialize<T: Serialize + ?Sized>(&self, value: &T) -> Result<Vec<u8>, StorageError> {
+        match self {
+            SerializationFormat::Json => serde_json::to_vec(value)
+                .map_err(|e| StorageError::SerializationError(e.to_string())),
+            SerializationFormat::MessagePack => rmp_serde::to_vec_named(value)
+                .map_err(|e| StorageError::SerializationError(e.to_string())),
+            SerializationFormat::Cbor => {
+                let mut data = Vec::new();
+                ciborium::into_writer(value, &mut data)
+                    .map_err(|e| StorageError::SerializationError(e.to_string()))?;
+                Ok(data)
+            }
+        }
+    }
+
+    /// Decode a value encoded in this format
+    pub fn deserialize<T: DeserializeOwned>(&self, data: &[u8]) -> Result<T, StorageError> {
+        match self {
+            SerializationFormat::Json => serde_json::from_slice(data)
+                .map_err(|e| StorageError::SerializationError(e.to_string())),
+            SerializationFormat::MessagePack => rmp_serde::from_slice(data)
+                .map_err(|e| StorageError::SerializationError(e.to_string())),
+            SerializationFormat::Cbor => ciborium::from_reader(data)
+                .map_err(|e| StorageError::SerializationError(e.to_string())),
+        }
+    }
+}
+
 /// Trait for storage backends
 #[async_trait]
 pub trait StorageBackend: Send + Sync + 'static {
+    /// Format used to encode values written to this backend
+    fn format(&self) -> SerializationFormat {
+        SerializationFormat::Json
+    }
+
     /// Store data with the given key
     async fn store(&self, key: &str, data: &[u8]) -> Result<(), StorageError>;
 
+    /// Store several key/value pairs in one operation
+    ///
+    /// The default implementation stores each entry in turn; backends that can
+    /// coalesce writes should override it.
+    async fn store_batch(&self, entries: &[(String, Vec<u8>)]) -> Result<(), StorageError> {
+        for (key, data) in entries {
+            self.store(key, data).await?;
+        }
+        Ok(())
+    }
+
     /// Load data with the given key
     async fn load(&self, key: &str) -> Result<Vec<u8>, StorageError>;
 
@@ -52,12 +114,24 @@ pub trait StorageBackend: Send + Sync + 'static {
 pub struct FileStorage {
     /// Base directory for storage
     base_dir: PathBuf,
+
+    /// Encoding for stored values
+    format: SerializationFormat,
 }
 
 impl FileStorage {
     /// Create a new file storage backend
     pub fn new(base_dir: PathBuf) -> Self {
-        FileStorage { base_dir }
+        FileStorage {
+            base_dir,
+            format: SerializationFormat::default(),
+        }
+    }
+
+    /// Set the encoding for stored values
+    pub fn with_format(mut self, format: SerializationFormat) -> Self {
+        self.format = format;
+        self
     }
 
     /// Get the full path for a key
@@ -68,6 +142,10 @@ impl FileStorage {
 
 #[async_trait]
 impl StorageBackend for FileStorage {
+    fn format(&self) -> SerializationFormat {
+        self.format
+    }
+
     async fn store(&self, key: &str, data: &[u8]) -> Result<(), StorageError> {
         let path = self.get_path(key);
 
@@ -90,6 +168,30 @@ impl StorageBackend for FileStorage {
         Ok(())
     }
 
+    async fn store_batch(&self, entries: &[(String, Vec<u8>)]) -> Result<(), StorageError> {
+        // Write and sync every temporary file concurrently
+        let written = futures::future::try_join_all(entries.iter().map(|(key, data)| async move {
+            let path = self.get_path(key);
+            if let Some(parent) = path.parent() {
+                tokio::fs::create_dir_all(parent).await?;
+            }
+            // Keys may differ only in their extension, so append rather than replace it
+            let temp_path = self.get_path(&format!("{}.tmp", key));
+            let mut file = tokio::fs::File::create(&temp_path).await?;
+            tokio::io::AsyncWriteExt::write_all(&mut file, data).await?;
+            file.sync_all().await?;
+            Ok::<_, io::Error>((temp_path, path))
+        }))
+        .await?;
+
+        // Only publish the batch once all of it is on disk
+        for (temp_path, path) in written {
+            tokio::fs::rename(&temp_path, &path).await?;
+        }
+
+        Ok(())
+    }
+
     async fn load(&self, key: &str) -> Result<Vec<u8>, StorageError> {
         let path = self.get_path(key);
 
@@ -164,6 +266,9 @@ impl StorageBackend for FileStorage {
 pub struct MemoryStorage {
     /// Data store
     data: tokio::sync::RwLock<std::collections::HashMap<String, Vec<u8>>>,
+
+    /// Encoding for stored values
+    format: SerializationFormat,
 }
 
 impl MemoryStorage {
@@ -171,8 +276,15 @@ impl MemoryStorage {
     pub fn new() -> Self {
         MemoryStorage {
             data: tokio::sync::RwLock::new(std::collections::HashMap::new()),
+            format: SerializationFormat::default(),
         }
     }
+
+    /// Set the encoding for stored values
+    pub fn with_format(mut self, format: SerializationFormat) -> Self {
+        self.format = format;
+        self
+    }
 }
 
 impl Default for MemoryStorage {
@@ -183,12 +295,22 @@ impl Default for MemoryStorage {
 
 #[async_trait]
 impl StorageBackend for MemoryStorage {
+    fn format(&self) -> SerializationFormat {
+        self.format
+    }
+
     async fn store(&self, key: &str, data: &[u8]) -> Result<(), StorageError> {
         let mut map = self.data.write().await;
         map.insert(key.to_string(), data.to_vec());
         Ok(())
     }
 
+    async fn store_batch(&self, entries: &[(String, Vec<u8>)]) -> Result<(), StorageError> {
+        let mut map = self.data.write().await;
+        map.extend(entries.iter().cloned());
+        Ok(())
+    }
+
     async fn load(&self, key: &str) -> Result<Vec<u8>, StorageError> {
         let map = self.data.read().await;
 
@@ -226,6 +348,231 @@ impl StorageBackend for MemoryStorage {
     }
 }
 
+/// Access bookkeeping for keys held in memory by a `SpilloverStorage`
+#[derive(Default)]
+struct SpilloverIndex {
+    /// Size and last access tick of each in-memory key
+    resident: std::collections::HashMap<String, (usize, u64)>,
+
+    /// Total bytes held in memory
+    memory_bytes: usize,
+
+    /// Monotonic access counter
+    tick: u64,
+}
+
+impl SpilloverIndex {
+    /// Record an access to an in-memory key
+    fn touch(&mut self, key: &str, size: usize) {
+        self.tick += 1;
+        if let Some((old_size, _)) = self.resident.insert(key.to_string(), (size, self.tick)) {
+            self.memory_bytes -= old_size;
+        }
+        self.memory_bytes += size;
+    }
+
+    /// Forget an in-memory key
+    fn remove(&mut self, key: &str) -> bool {
+        match self.resident.remove(key) {
+            Some((size, _)) => {
+                self.memory_bytes -= size;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Least recently used in-memory key
+    fn least_recently_used(&self) -> Option<String> {
+        self.resident
+            .iter()
+            .min_by_key(|(_, (_, tick))| *tick)
+            .map(|(key, _)| key.clone())
+    }
+}
+
+/// Storage backend that keeps recently used data in memory and spills to disk
+///
+/// Values are written to an in-memory store until the memory budget is
+/// exceeded, at which point the least recently used values are moved to
+/// file storage. Spilled values are reloaded into memory on access.
+pub struct SpilloverStorage {
+    /// Fast in-memory tier
+    memory: MemoryStorage,
+
+    /// Disk tier for evicted values
+    disk: FileStorage,
+
+    /// Maximum number of bytes kept in memory
+    memory_budget: usize,
+
+    /// LRU bookkeeping for the memory tier
+    index: tokio::sync::Mutex<SpilloverIndex>,
+}
+
+impl SpilloverStorage {
+    /// Create a new spillover storage with the given memory budget in bytes
+    pub fn new(memory: MemoryStorage, disk: FileStorage, memory_budget: usize) -> Self {
+        SpilloverStorage {
+            memory,
+            disk,
+            memory_budget,
+            index: tokio::sync::Mutex::new(SpilloverIndex::default()),
+        }
+    }
+
+    /// Create a spillover storage backed by a fresh memory store and a directory on disk
+    pub fn with_directory(base_dir: PathBuf, memory_budget: usize) -> Self {
+        Self::new(
+            MemoryStorage::new(),
+            FileStorage::new(base_dir),
+            memory_budget,
+        )
+    }
+
+    /// Get the memory budget in bytes
+    pub fn memory_budget(&self) -> usize {
+        self.memory_budget
+    }
+
+    /// Get the number of bytes currently held in memory
+    pub async fn memory_usage(&self) -> usize {
+        self.index.lock().await.memory_bytes
+    }
+
+    /// Check whether a key is currently held in memory
+    pub async fn is_in_memory(&self, key: &str) -> bool {
+        self.index.lock().await.resident.contains_key(key)
+    }
+
+    /// Put a value in the memory tier and spill other values until within budget
+    async fn admit(
+        &self,
+        index: &mut SpilloverIndex,
+        key: &str,
+        data: &[u8],
+    ) -> Result<(), StorageError> {
+        // Values larger than the whole budget go straight to disk
+        if data.len() > self.memory_budget {
+            if index.remove(key) {
+                self.memory.delete(key).await?;
+            }
+            return self.disk.store(key, data).await;
+        }
+
+        self.memory.store(key, data).await?;
+        index.touch(key, data.len());
+
+        // Evict least recently used values to disk
+        while index.memory_bytes > self.memory_budget {
+            let victim = match index.least_recently_used() {
+                Some(victim) => victim,
+                None => break,
+            };
+            let victim_data = self.memory.load(&victim).await?;
+            self.disk.store(&victim, &victim_data).await?;
+            self.memory.delete(&victim).await?;
+            index.remove(&victim);
+            log::debug!("Spilled {} ({} bytes) to disk", victim, victim_data.len());
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl StorageBackend for SpilloverStorage {
+    fn format(&self) -> SerializationFormat {
+        self.memory.format()
+    }
+
+    async fn store(&self, key: &str, data: &[u8]) -> Result<(), StorageError> {
+        let mut index = self.index.lock().await;
+
+        // Drop any stale copy on disk
+        self.disk.delete(key).await?;
+
+        self.admit(&mut index, key, data).await
+    }
+
+    async fn store_batch(&self, entries: &[(String, Vec<u8>)]) -> Result<(), StorageError> {
+        let mut index = self.index.lock().await;
+
+        for (key, data) in entries {
+            self.disk.delete(key).await?;
+            self.admit(&mut index, key, data).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn load(&self, key: &str) -> Result<Vec<u8>, StorageError> {
+        let mut index = self.index.lock().await;
+
+        // Serve from memory if resident
+        if index.resident.contains_key(key) {
+            let data = self.memory.load(key).await?;
+            index.touch(key, data.len());
+            return Ok(data);
+        }
+
+        // Otherwise reload from disk and promote back into memory
+        let data = self.disk.load(key).await?;
+        if data.len() <= self.memory_budget {
+            self.admit(&mut index, key, &data).await?;
+            self.disk.delete(key).await?;
+        }
+
+        Ok(data)
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), StorageError> {
+        let mut index = self.index.lock().await;
+        if index.remove(key) {
+            self.memory.delete(key).await?;
+        }
+        self.disk.delete(key).await
+    }
+
+    async fn exists(&self, key: &str) -> Result<bool, StorageError> {
+        let index = self.index.lock().await;
+        if index.resident.contains_key(key) {
+            return Ok(true);
+        }
+        self.disk.exists(key).await
+    }
+
+    async fn list(&self) -> Result<Vec<String>, StorageError> {
+        let index = self.index.lock().await;
+        let mut keys: Vec<String> = index.resident.keys().cloned().collect();
+
+        // The disk directory only exists once something has been spilled
+        match self.disk.list().await {
+            Ok(disk_keys) => keys.extend(disk_keys),
+            Err(StorageError::IoError(e)) if e.kind() == io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e),
+        }
+
+        keys.sort();
+        keys.dedup();
+        Ok(keys)
+    }
+
+    async fn rename(&self, old_key: &str, new_key: &str) -> Result<(), StorageError> {
+        let mut index = self.index.lock().await;
+
+        if let Some((size, _)) = index.resident.get(old_key).copied() {
+            self.memory.rename(old_key, new_key).await?;
+            index.remove(old_key);
+            index.remove(new_key);
+            index.touch(new_key, size);
+            self.disk.delete(new_key).await
+        } else {
+            self.disk.rename(old_key, new_key).await
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -328,4 +675,137 @@ mod tests {
         let loaded = storage.load(new_key).await.unwrap();
         assert_eq!(loaded, data);
     }
+
+    #[tokio::test]
+    async fn test_store_batch() {
+        let temp_dir = TempDir::new().unwrap();
+        let backends: Vec<Box<dyn StorageBackend>> = vec![
+            Box::new(FileStorage::new(temp_dir.path().join("file"))),
+            Box::new(MemoryStorage::new()),
+            Box::new(SpilloverStorage::with_directory(
+                temp_dir.path().join("spill"),
+                16,
+            )),
+        ];
+        let entries: Vec<(String, Vec<u8>)> = (0..5)
+            .map(|i| (format!("batch-{}", i), format!("value {}", i).into_bytes()))
+            .collect();
+
+        for storage in backends {
+            storage.store_batch(&entries).await.unwrap();
+
+            for (key, data) in &entries {
+                assert_eq!(&storage.load(key).await.unwrap(), data);
+            }
+            let mut keys = storage.list().await.unwrap();
+            keys.sort();
+            assert_eq!(
+                keys,
+                entries
+                    .iter()
+                    .map(|(key, _)| key.clone())
+                    .collect::<Vec<_>>()
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_serialization_formats_round_trip() {
+        use crate::model::{Edge, EdgeId, Node, NodeId, WorkflowDefinition, WorkflowId};
+        use crate::state::machine::WorkflowState;
+        use std::sync::Arc;
+
+        // Build a workflow state with nested node results
+        let mut workflow = WorkflowDefinition::new(WorkflowId::new(), "Formats".to_string());
+        let node1 = Node::new(NodeId::new(), "first".to_string());
+        let node2 = Node::new(NodeId::new(), "second".to_string());
+        let (node1_id, node2_id) = (node1.id.clone(), node2.id.clone());
+        workflow.add_node(node1).unwrap();
+        workflow.add_node(node2).unwrap();
+        workflow
+            .add_edge(Edge::new(EdgeId::new(), node1_id.clone(), node2_id))
+            .unwrap();
+
+        let mut state = WorkflowState::new(Arc::new(workflow));
+        state.set_node_running(&node1_id).unwrap();
+        state
+            .set_node_completed(
+                &node1_id,
+                serde_json::json!({
+                    "items": [1, 2, {"nested": {"deep": [true, null, "x"]}}],
+                    "count": 3,
+                    "ratio": 0.5
+                }),
+            )
+            .unwrap();
+        state.metadata = serde_json::json!({"owner": {"team": "core"}});
+
+        for format in [
+            SerializationFormat::Json,
+            SerializationFormat::MessagePack,
+            SerializationFormat::Cbor,
+        ] {
+            let storage = MemoryStorage::new().with_format(format);
+            assert_eq!(storage.format(), format);
+
+            // Encode, store, load and decode
+            let data = storage.format().serialize(&state).unwrap();
+            storage.store("state", &data).await.unwrap();
+            let loaded = storage.load("state").await.unwrap();
+            let decoded: WorkflowState = storage.format().deserialize(&loaded).unwrap();
+
+            assert_eq!(decoded.instance_id, state.instance_id, "{:?}", format);
+            assert_eq!(decoded.node_status, state.node_status, "{:?}", format);
+            assert_eq!(decoded.node_in_degree, state.node_in_degree, "{:?}", format);
+            assert_eq!(decoded.node_results, state.node_results, "{:?}", format);
+            assert_eq!(decoded.ready_nodes, state.ready_nodes, "{:?}", format);
+            assert_eq!(decoded.metadata, state.metadata, "{:?}", format);
+            assert_eq!(decoded.created_at, state.created_at, "{:?}", format);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_spillover_storage() {
+        let temp_dir = TempDir::new().unwrap();
+        let storage = SpilloverStorage::with_directory(temp_dir.path().to_path_buf(), 16);
+
+        // Fill the memory budget
+        storage.store("a", b"aaaaaaaa").await.unwrap();
+        storage.store("b", b"bbbbbbbb").await.unwrap();
+        assert_eq!(storage.memory_usage().await, 16);
+
+        // Touch "a" so that "b" becomes the least recently used
+        assert_eq!(storage.load("a").await.unwrap(), b"aaaaaaaa");
+
+        // Exceeding the budget spills "b" to disk
+        storage.store("c", b"cccccccc").await.unwrap();
+        assert!(storage.is_in_memory("a").await);
+        assert!(!storage.is_in_memory("b").await);
+        assert!(storage.is_in_memory("c").await);
+        assert!(temp_dir.path().join("b").exists());
+        assert!(storage.memory_usage().await <= storage.memory_budget());
+
+        // All keys are still visible
+        assert!(storage.exists("b").await.unwrap());
+        let keys = storage.list().await.unwrap();
+        assert_eq!(keys, vec!["a", "b", "c"]);
+
+        // Loading "b" transparently reloads it and spills another key
+        assert_eq!(storage.load("b").await.unwrap(), b"bbbbbbbb");
+        assert!(storage.is_in_memory("b").await);
+        assert!(!temp_dir.path().join("b").exists());
+        assert!(!storage.is_in_memory("a").await);
+        assert_eq!(storage.load("a").await.unwrap(), b"aaaaaaaa");
+
+        // Values larger than the budget go straight to disk
+        storage.store("big", &[7u8; 32]).await.unwrap();
+        assert!(!storage.is_in_memory("big").await);
+        assert_eq!(storage.load("big").await.unwrap(), vec![7u8; 32]);
+
+        // Delete removes from both tiers
+        storage.delete("b").await.unwrap();
+        storage.delete("big").await.unwrap();
+        assert!(!storage.exists("b").await.unwrap());
+        assert!(!storage.exists("big").await.unwrap());
+    }
 }
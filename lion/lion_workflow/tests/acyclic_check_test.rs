@@ -1,9 +1,10 @@
 use chrono::Utc;
 use lion_core::id::Id;
+use lion_core::types::ErrorPolicy;
 use lion_workflow::model::definition::{Version, WorkflowDefinition};
 use lion_workflow::model::edge::{Edge, EdgeId};
 use lion_workflow::model::node::NodeId;
-use lion_workflow::model::node::{Node, NodeStatus, Priority};
+use lion_workflow::model::node::{FailurePolicy, Node, NodeStatus, NodeType, Priority};
 use std::collections::{HashMap, HashSet};
 
 /// Test function to check if a workflow is acyclic
@@ -68,12 +69,19 @@ fn create_acyclic_workflow() -> WorkflowDefinition {
         name: "Node 1".to_string(),
         status: NodeStatus::Pending,
         in_degree: 0,
+        node_type: NodeType::Task,
         outgoing_edges: HashSet::new(),
         incoming_edges: HashSet::new(),
         required_capability: None,
         priority: Priority::Normal,
         deadline: None,
         config: serde_json::Value::Null,
+        input_schema: None,
+        error_policy: None,
+        cache: None,
+        compensation: None,
+        on_failure: None,
+        estimated_cost: None,
     };
 
     let node2_id = NodeId::new();
@@ -82,12 +90,19 @@ fn create_acyclic_workflow() -> WorkflowDefinition {
         name: "Node 2".to_string(),
         status: NodeStatus::Pending,
         in_degree: 0,
+        node_type: NodeType::Task,
         outgoing_edges: HashSet::new(),
         incoming_edges: HashSet::new(),
         required_capability: None,
         priority: Priority::Normal,
         deadline: None,
         config: serde_json::Value::Null,
+        input_schema: None,
+        error_policy: None,
+        cache: None,
+        compensation: None,
+        on_failure: None,
+        estimated_cost: None,
     };
 
     let node3_id = NodeId::new();
@@ -96,12 +111,19 @@ fn create_acyclic_workflow() -> WorkflowDefinition {
         name: "Node 3".to_string(),
         status: NodeStatus::Pending,
         in_degree: 0,
+        node_type: NodeType::Task,
         outgoing_edges: HashSet::new(),
         incoming_edges: HashSet::new(),
         required_capability: None,
         priority: Priority::Normal,
         deadline: None,
         config: serde_json::Value::Null,
+        input_schema: None,
+        error_policy: None,
+        cache: None,
+        compensation: None,
+        on_failure: None,
+        estimated_cost: None,
     };
 
     // Create edges for a DAG: 1 -> 2 -> 3
@@ -138,6 +160,9 @@ fn create_acyclic_workflow() -> WorkflowDefinition {
         created_at: Utc::now(),
         updated_at: Utc::now(),
         required_capability: None,
+        default_error_policy: ErrorPolicy::Fail,
+        default_failure_policy: FailurePolicy::Abort,
+        engine: None,
     }
 }
 
@@ -154,12 +179,19 @@ fn create_cyclic_workflow() -> WorkflowDefinition {
         name: "Node 1".to_string(),
         status: NodeStatus::Pending,
         in_degree: 0,
+        node_type: NodeType::Task,
         outgoing_edges: HashSet::new(),
         incoming_edges: HashSet::new(),
         required_capability: None,
         priority: Priority::Normal,
         deadline: None,
         config: serde_json::Value::Null,
+        input_schema: None,
+        error_policy: None,
+        cache: None,
+        compensation: None,
+        on_failure: None,
+        estimated_cost: None,
     };
 
     let node2_id = NodeId::new();
@@ -168,12 +200,19 @@ fn create_cyclic_workflow() -> WorkflowDefinition {
         name: "Node 2".to_string(),
         status: NodeStatus::Pending,
         in_degree: 0,
+        node_type: NodeType::Task,
         outgoing_edges: HashSet::new(),
         incoming_edges: HashSet::new(),
         required_capability: None,
         priority: Priority::Normal,
         deadline: None,
         config: serde_json::Value::Null,
+        input_schema: None,
+        error_policy: None,
+        cache: None,
+        compensation: None,
+        on_failure: None,
+        estimated_cost: None,
     };
 
     let node3_id = NodeId::new();
@@ -182,12 +221,19 @@ fn create_cyclic_workflow() -> WorkflowDefinition {
         name: "Node 3".to_string(),
         status: NodeStatus::Pending,
         in_degree: 0,
+        node_type: NodeType::Task,
         outgoing_edges: HashSet::new(),
         incoming_edges: HashSet::new(),
         required_capability: None,
         priority: Priority::Normal,
         deadline: None,
         config: serde_json::Value::Null,
+        input_schema: None,
+        error_policy: None,
+        cache: None,
+        compensation: None,
+        on_failure: None,
+        estimated_cost: None,
     };
 
     // Create edges for a cycle: 1 -> 2 -> 3 -> 1
@@ -225,6 +271,9 @@ fn create_cyclic_workflow() -> WorkflowDefinition {
         created_at: Utc::now(),
         updated_at: Utc::now(),
         required_capability: None,
+        default_error_policy: ErrorPolicy::Fail,
+        default_failure_policy: FailurePolicy::Abort,
+        engine: None,
     }
 }
 
@@ -1,6 +1,8 @@
+use lion_core::id::WorkflowId;
+use lion_runtime::workflow::WorkflowManager;
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
-use tauri::Window;
+use tauri::{State, Window};
 use uuid::Uuid;
 
 /// Structure for creating a log entry
@@ -35,6 +37,31 @@ pub struct PluginCallRequest {
     args: Option<String>,
 }
 
+/// Position of a node in a workflow diagram
+#[derive(Debug, Serialize, Deserialize)]
+pub struct NodeLayout {
+    node_id: String,
+    x: f64,
+    y: f64,
+    layer: usize,
+}
+
+/// Hint for drawing an edge of a workflow diagram
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EdgeHint {
+    edge_id: String,
+    source: String,
+    target: String,
+    points: Vec<(f64, f64)>,
+}
+
+/// Layout of a workflow diagram
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WorkflowLayoutResponse {
+    nodes: Vec<NodeLayout>,
+    edges: Vec<EdgeHint>,
+}
+
 /// Simple ping command to test if bridge is working
 #[tauri::command]
 pub fn ping() -> String {
@@ -188,6 +215,103 @@ pub async fn call_plugin_integrated(
     Ok(result)
 }
 
+/// Lay out a registered workflow as a layered diagram
+#[tauri::command]
+pub async fn compute_workflow_layout(
+    workflow_id: String,
+) -> Result<WorkflowLayoutResponse, String> {
+    let definition = lion_cli::interfaces::workflow::get_workflow_definition(&workflow_id)
+        .map_err(|e| format!("Failed to get workflow: {}", e))?;
+    let layout = definition
+        .layout()
+        .map_err(|e| format!("Failed to lay out workflow: {}", e))?;
+
+    let nodes = layout
+        .nodes
+        .into_iter()
+        .map(|position| NodeLayout {
+            node_id: position.node_id.to_string(),
+            x: position.x,
+            y: position.y,
+            layer: position.layer,
+        })
+        .collect();
+
+    let edges = layout
+        .edges
+        .into_iter()
+        .filter_map(|route| {
+            let edge = definition.edges.get(&route.edge_id)?;
+            Some(EdgeHint {
+                edge_id: route.edge_id.to_string(),
+                source: edge.source.to_string(),
+                target: edge.target.to_string(),
+                points: route.points,
+            })
+        })
+        .collect();
+
+    Ok(WorkflowLayoutResponse { nodes, edges })
+}
+
+/// Workflow manager owned by the app, through which workflow executions
+/// are cancelled
+pub struct WorkflowState {
+    manager: Option<WorkflowManager>,
+}
+
+impl WorkflowState {
+    /// Create the workflow manager, leaving it unavailable if it fails to
+    /// start
+    pub fn new() -> Self {
+        let manager = match lion_ui::state::create_workflow_manager() {
+            Ok(manager) => Some(manager),
+            Err(e) => {
+                eprintln!("Failed to create workflow manager: {}", e);
+                None
+            }
+        };
+        Self { manager }
+    }
+}
+
+/// Cancel a running or paused workflow execution
+///
+/// Executions are identified by the ID of the workflow they run, as the
+/// runtime keeps one execution per workflow. Fails if the execution has
+/// already finished.
+#[tauri::command]
+pub async fn cancel_workflow_execution(
+    window: Window,
+    workflows: State<'_, WorkflowState>,
+    execution_id: String,
+) -> Result<String, String> {
+    let manager = workflows
+        .manager
+        .as_ref()
+        .ok_or_else(|| "Workflow manager is not available".to_string())?;
+    let workflow_id = Uuid::parse_str(&execution_id)
+        .map(WorkflowId::from_uuid)
+        .map_err(|e| format!("Invalid workflow execution ID: {}", e))?;
+
+    // The manager refuses executions that have already finished
+    manager
+        .cancel_workflow(workflow_id)
+        .await
+        .map_err(|e| format!("Failed to cancel workflow execution: {}", e))?;
+
+    let _ = window.emit_to(
+        &window.label(),
+        "workflow-execution-cancelled",
+        serde_json::json!({
+            "execution_id": execution_id,
+            "status": "CANCELLED"
+        }),
+    );
+
+    Ok("CANCELLED".to_string())
+}
+
 /// Get recent logs from the system
 #[tauri::command]
 pub async fn get_recent_logs() -> Result<Vec<serde_json::Value>, String> {
@@ -63,6 +63,7 @@ fn main() {
 
     tauri::Builder::default()
         .tray_icon(tray_icon.build().unwrap())
+        .manage(bridge::WorkflowState::new())
         .on_window_event(|event| match event.event() {
             WindowEvent::CloseRequested { api, .. } => {
                 if event.window().label() == "main" {
@@ -87,6 +88,8 @@ fn main() {
             bridge::load_plugin_integrated,
             bridge::list_plugins_integrated,
             bridge::call_plugin_integrated,
+            bridge::compute_workflow_layout,
+            bridge::cancel_workflow_execution,
             bridge::get_recent_logs
         ])
         .run(tauri::generate_context!("tauri.conf.json"))
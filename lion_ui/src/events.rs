@@ -1,14 +1,17 @@
 use axum::{
-    extract::State,
+    extract::{Json, Path as AxumPath, State},
+    http::StatusCode,
     response::sse::{Event, Sse},
 };
-use futures::{stream::Stream, StreamExt};
+use futures::{future, stream::Stream, StreamExt};
 use std::{convert::Infallible, sync::Arc};
 use tokio_stream::wrappers::BroadcastStream;
 use tracing::error;
+use uuid::Uuid;
 
 use crate::logs::LogEntry;
 use crate::state::AppState;
+use crate::workflows::{error_body, workflow_manager};
 
 /// Server-Sent Events handler for streaming logs in real-time
 pub async fn sse_handler(
@@ -39,3 +42,58 @@ pub async fn sse_handler(
 
     Sse::new(stream)
 }
+
+/// Server-Sent Events handler for streaming the node status changes of one
+/// execution
+///
+/// The path ID is the execution ID returned when the workflow was started.
+/// The stream ends once the workflow is started again, as the runtime keeps
+/// one execution per workflow.
+pub async fn workflow_events_handler(
+    State(state): State<Arc<AppState>>,
+    AxumPath(execution_id): AxumPath<Uuid>,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, (StatusCode, Json<serde_json::Value>)>
+{
+    let manager = workflow_manager(&state)?;
+
+    // Subscribe before looking the execution up, so no event is missed
+    let rx = manager.subscribe_node_events();
+    let workflow_id = state
+        .executions
+        .read()
+        .await
+        .get(&execution_id)
+        .copied()
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                error_body(format!("Execution {} not found", execution_id)),
+            )
+        })?;
+
+    // Keep the events of the requested execution while it is current
+    let stream = BroadcastStream::new(rx)
+        .take_while(move |_| {
+            let state = state.clone();
+            async move { state.executions.read().await.get(&execution_id) == Some(&workflow_id) }
+        })
+        .filter_map(move |msg| {
+            let event = match msg {
+                Ok(event) if event.workflow_id != workflow_id => None,
+                Ok(event) => match serde_json::to_string(&event) {
+                    Ok(json) => Some(Ok(Event::default().data(json))),
+                    Err(e) => {
+                        error!("Failed to serialize node event: {}", e);
+                        Some(Ok(Event::default().comment("Error serializing node event")))
+                    }
+                },
+                Err(e) => {
+                    error!("Error receiving from broadcast: {}", e);
+                    Some(Ok(Event::default().comment("Error receiving node event")))
+                }
+            };
+            future::ready(event)
+        });
+
+    Ok(Sse::new(stream))
+}
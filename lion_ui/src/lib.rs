@@ -9,3 +9,4 @@ pub mod plugins;
 pub mod state;
 pub mod utils;
 pub mod wasm;
+pub mod workflows;
@@ -22,12 +22,14 @@ mod plugins;
 mod state;
 mod utils;
 mod wasm;
+mod workflows;
 
 use agents::*;
-use events::sse_handler;
+use events::{sse_handler, workflow_events_handler};
 use logs::{search_logs_handler, LogEntry};
 use plugins::*;
 use state::AppState;
+use workflows::*;
 
 #[tokio::main]
 async fn main() {
@@ -76,6 +78,10 @@ async fn main() {
         .route("/api/wasm/plugins", post(wasm::load_wasm_plugin).get(wasm::list_wasm_plugins))
         .route("/api/wasm/plugins/:plugin_id", get(wasm::get_wasm_plugin_info))
         .route("/api/wasm/plugins/:plugin_id/invoke", post(wasm::invoke_wasm_plugin_function))
+        .route("/api/workflows", post(register_workflow_handler))
+        .route("/api/workflows/:workflow_id/execute", post(execute_workflow_handler))
+        .route("/api/executions/:execution_id", get(get_execution_handler))
+        .route("/api/executions/:execution_id/events", get(workflow_events_handler))
         // Serve static files from the frontend directory
         .nest_service("/assets", ServeDir::new("lion_ui/frontend/dist/assets"))
         .layer(
@@ -1,8 +1,14 @@
 use crate::logs::LogEntry;
+use lion_core::id::WorkflowId;
+use lion_runtime::capabilities::manager::CapabilityManager;
+use lion_runtime::plugin::manager::PluginManager;
+use lion_runtime::system::config::RuntimeConfig;
+use lion_runtime::workflow::WorkflowManager;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::{broadcast, RwLock};
+use tracing::error;
 use uuid::Uuid;
 use wasmtime::{Instance, Memory, Module};
 
@@ -46,6 +52,12 @@ pub struct AppState {
 
     /// WebAssembly engine
     pub wasm_engine: Option<wasmtime::Engine>,
+
+    /// Workflow manager running registered workflows
+    pub workflows: Option<WorkflowManager>,
+
+    /// Started executions, mapped to the workflow they run
+    pub executions: RwLock<HashMap<Uuid, WorkflowId>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -69,6 +81,14 @@ impl AppState {
 
         let wasm_engine = wasmtime::Engine::new(&config).ok();
 
+        let workflows = match create_workflow_manager() {
+            Ok(manager) => Some(manager),
+            Err(e) => {
+                error!("Failed to create workflow manager: {}", e);
+                None
+            }
+        };
+
         Self {
             logs_tx,
             log_buffer,
@@ -76,6 +96,8 @@ impl AppState {
             plugins: RwLock::new(HashMap::new()),
             plugins_wasm: RwLock::new(HashMap::new()),
             wasm_engine,
+            workflows,
+            executions: RwLock::new(HashMap::new()),
         }
     }
 
@@ -85,3 +107,14 @@ impl AppState {
         let _ = self.logs_tx.send(entry.clone());
     }
 }
+
+/// Create a workflow manager with the default runtime configuration
+pub fn create_workflow_manager() -> anyhow::Result<WorkflowManager> {
+    let config = RuntimeConfig::default();
+    let capability_manager = Arc::new(CapabilityManager::new()?);
+    let plugin_manager = Arc::new(PluginManager::new(
+        config.clone(),
+        capability_manager.clone(),
+    )?);
+    WorkflowManager::new(config, capability_manager, plugin_manager)
+}
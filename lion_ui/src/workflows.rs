@@ -0,0 +1,172 @@
+use axum::{
+    extract::{Json, Path as AxumPath, State},
+    http::StatusCode,
+};
+use lion_core::id::WorkflowId;
+use lion_core::types::workflow::ExecutionStatus;
+use lion_runtime::workflow::manager::WorkflowManagerError;
+use lion_runtime::workflow::WorkflowManager;
+use lion_workflow::model::WorkflowDefinition;
+use std::sync::Arc;
+use tracing::{error, info};
+use uuid::Uuid;
+
+use crate::logs::{LogEntry, LogLevel};
+use crate::state::AppState;
+
+/// Error response body
+pub(crate) fn error_body(message: impl std::fmt::Display) -> Json<serde_json::Value> {
+    Json(serde_json::json!({ "error": message.to_string() }))
+}
+
+/// Get the workflow manager, or a 503 response if it failed to start
+pub(crate) fn workflow_manager(
+    state: &AppState,
+) -> Result<&WorkflowManager, (StatusCode, Json<serde_json::Value>)> {
+    state.workflows.as_ref().ok_or((
+        StatusCode::SERVICE_UNAVAILABLE,
+        error_body("Workflow manager is not available"),
+    ))
+}
+
+/// Map a workflow manager error to a response status
+fn error_status(e: &anyhow::Error) -> StatusCode {
+    match e.downcast_ref::<WorkflowManagerError>() {
+        Some(WorkflowManagerError::NotFound(_)) => StatusCode::NOT_FOUND,
+        Some(WorkflowManagerError::AlreadyExists(_)) => StatusCode::CONFLICT,
+        Some(WorkflowManagerError::InvalidDefinition(_))
+        | Some(WorkflowManagerError::UnknownEngine(_))
+        | Some(WorkflowManagerError::PluginNotFound(_)) => StatusCode::BAD_REQUEST,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+/// Handler for registering a workflow definition
+///
+/// The body is a JSON `WorkflowDefinition`. Definitions that fail to parse
+/// or validate are rejected with 400.
+pub async fn register_workflow_handler(
+    State(state): State<Arc<AppState>>,
+    body: String,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let manager = match workflow_manager(&state) {
+        Ok(manager) => manager,
+        Err(response) => return response,
+    };
+
+    let definition = match WorkflowDefinition::from_json(&body)
+        .and_then(|definition| definition.validate().map(|_| definition))
+    {
+        Ok(definition) => definition,
+        Err(e) => return (StatusCode::BAD_REQUEST, error_body(e)),
+    };
+    let name = definition.name.clone();
+
+    match manager.register_workflow(definition).await {
+        Ok(workflow_id) => {
+            state
+                .log(LogEntry::new(
+                    LogLevel::Info,
+                    format!("Workflow '{}' registered", name),
+                    "workflow",
+                ))
+                .await;
+            info!("Workflow '{}' registered with ID {}", name, workflow_id);
+
+            (
+                StatusCode::CREATED,
+                Json(serde_json::json!({ "workflow_id": workflow_id.uuid() })),
+            )
+        }
+        Err(e) => {
+            error!("Failed to register workflow '{}': {}", name, e);
+            (error_status(&e), error_body(e))
+        }
+    }
+}
+
+/// Handler for starting a workflow
+///
+/// The optional JSON body is the workflow input. The runtime keeps one
+/// execution per workflow, so starting a workflow again forgets the
+/// execution IDs of its earlier runs.
+pub async fn execute_workflow_handler(
+    State(state): State<Arc<AppState>>,
+    AxumPath(workflow_id): AxumPath<Uuid>,
+    input: Option<Json<serde_json::Value>>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let manager = match workflow_manager(&state) {
+        Ok(manager) => manager,
+        Err(response) => return response,
+    };
+
+    let workflow_id = WorkflowId::from_uuid(workflow_id);
+    let input = input.map_or_else(|| serde_json::json!({}), |Json(input)| input);
+
+    if let Err(e) = manager.start_workflow(workflow_id, input).await {
+        error!("Failed to start workflow {}: {}", workflow_id, e);
+        return (error_status(&e), error_body(e));
+    }
+
+    let execution_id = Uuid::new_v4();
+    {
+        let mut executions = state.executions.write().await;
+        executions.retain(|_, id| *id != workflow_id);
+        executions.insert(execution_id, workflow_id);
+    }
+    info!(
+        "Workflow {} started as execution {}",
+        workflow_id, execution_id
+    );
+
+    (
+        StatusCode::ACCEPTED,
+        Json(serde_json::json!({
+            "execution_id": execution_id,
+            "workflow_id": workflow_id.uuid(),
+        })),
+    )
+}
+
+/// Handler for getting the status of an execution
+///
+/// The result is included once the execution has completed.
+pub async fn get_execution_handler(
+    State(state): State<Arc<AppState>>,
+    AxumPath(execution_id): AxumPath<Uuid>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let manager = match workflow_manager(&state) {
+        Ok(manager) => manager,
+        Err(response) => return response,
+    };
+
+    let workflow_id = match state.executions.read().await.get(&execution_id) {
+        Some(workflow_id) => *workflow_id,
+        None => {
+            return (
+                StatusCode::NOT_FOUND,
+                error_body(format!("Execution {} not found", execution_id)),
+            )
+        }
+    };
+
+    let status = match manager.get_workflow_status(&workflow_id).await {
+        Ok(status) => status,
+        Err(e) => return (error_status(&e), error_body(e)),
+    };
+    let result = if status == ExecutionStatus::Completed {
+        manager.get_workflow_results(&workflow_id).await.ok()
+    } else {
+        None
+    };
+
+    (
+        StatusCode::OK,
+        Json(serde_json::json!({
+            "execution_id": execution_id,
+            "workflow_id": workflow_id.uuid(),
+            "status": status,
+            "result": result,
+        })),
+    )
+}
@@ -0,0 +1,134 @@
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+    routing::{get, post},
+    Router,
+};
+use std::sync::Arc;
+use tokio::sync::{broadcast, RwLock};
+use tower::ServiceExt;
+use uuid::Uuid;
+
+extern crate lion_ui;
+use lion_ui::state::AppState;
+use lion_ui::workflows::{
+    execute_workflow_handler, get_execution_handler, register_workflow_handler,
+};
+use lion_workflow::model::{Node, NodeId, WorkflowDefinition, WorkflowId};
+
+/// Build the workflow routes the server exposes
+fn app() -> Router {
+    let (logs_tx, _) = broadcast::channel(100);
+    let log_buffer = Arc::new(RwLock::new(Vec::with_capacity(100)));
+    let app_state = Arc::new(AppState::new(logs_tx, log_buffer));
+
+    Router::new()
+        .route("/api/workflows", post(register_workflow_handler))
+        .route(
+            "/api/workflows/:workflow_id/execute",
+            post(execute_workflow_handler),
+        )
+        .route("/api/executions/:execution_id", get(get_execution_handler))
+        .with_state(app_state)
+}
+
+/// Send a request and return the status and JSON body of the response
+async fn send(
+    app: &Router,
+    method: &str,
+    uri: &str,
+    body: Body,
+) -> (StatusCode, serde_json::Value) {
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method(method)
+                .uri(uri)
+                .header("content-type", "application/json")
+                .body(body)
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    let status = response.status();
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, serde_json::from_slice(&bytes).unwrap())
+}
+
+fn definition_json() -> String {
+    let mut definition = WorkflowDefinition::new(WorkflowId::new(), "Api".to_string());
+    definition
+        .add_node(Node::new(NodeId::new(), "step".to_string()))
+        .unwrap();
+    definition.to_json().unwrap()
+}
+
+#[tokio::test]
+async fn test_register_rejects_invalid_definition() {
+    let app = app();
+
+    // Not a workflow definition at all
+    let (status, body) = send(&app, "POST", "/api/workflows", Body::from("{\"name\": 1}")).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(body["error"].is_string());
+
+    // A parsable definition that fails validation
+    let empty = WorkflowDefinition::new(WorkflowId::new(), "Empty".to_string());
+    let (status, _) = send(
+        &app,
+        "POST",
+        "/api/workflows",
+        Body::from(empty.to_json().unwrap()),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_execute_unknown_workflow_is_not_found() {
+    let app = app();
+
+    let uri = format!("/api/workflows/{}/execute", Uuid::new_v4());
+    let (status, _) = send(&app, "POST", &uri, Body::empty()).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let uri = format!("/api/executions/{}", Uuid::new_v4());
+    let (status, _) = send(&app, "GET", &uri, Body::empty()).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_register_execute_and_get_execution() {
+    let app = app();
+
+    let definition = definition_json();
+    let (status, body) = send(
+        &app,
+        "POST",
+        "/api/workflows",
+        Body::from(definition.clone()),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    let workflow_id = body["workflow_id"].as_str().unwrap().to_string();
+
+    // Registering the same definition again conflicts
+    let (status, _) = send(&app, "POST", "/api/workflows", Body::from(definition)).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+
+    let uri = format!("/api/workflows/{}/execute", workflow_id);
+    let (status, body) = send(&app, "POST", &uri, Body::from("{\"n\": 1}")).await;
+    assert_eq!(status, StatusCode::ACCEPTED);
+    let execution_id = body["execution_id"].as_str().unwrap().to_string();
+
+    let uri = format!("/api/executions/{}", execution_id);
+    let (status, body) = send(&app, "GET", &uri, Body::empty()).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["workflow_id"], workflow_id.as_str());
+    assert_eq!(body["status"], "Running");
+    assert!(body["result"].is_null());
+}
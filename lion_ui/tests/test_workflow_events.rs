@@ -0,0 +1,130 @@
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+    routing::{get, post},
+    Router,
+};
+use futures::StreamExt;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{broadcast, RwLock};
+use tower::ServiceExt;
+use uuid::Uuid;
+
+extern crate lion_ui;
+use lion_ui::events::workflow_events_handler;
+use lion_ui::state::AppState;
+use lion_ui::workflows::{execute_workflow_handler, register_workflow_handler};
+use lion_workflow::model::{Node, NodeId, WorkflowDefinition, WorkflowId};
+
+/// Send a request and return the response
+async fn send(app: &Router, method: &str, uri: &str, body: Body) -> axum::response::Response {
+    app.clone()
+        .oneshot(
+            Request::builder()
+                .method(method)
+                .uri(uri)
+                .header("content-type", "application/json")
+                .body(body)
+                .unwrap(),
+        )
+        .await
+        .unwrap()
+}
+
+/// Send a request and return the JSON body of the response
+async fn send_json(app: &Router, method: &str, uri: &str, body: Body) -> serde_json::Value {
+    let response = send(app, method, uri, body).await;
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    serde_json::from_slice(&bytes).unwrap()
+}
+
+#[tokio::test]
+async fn test_workflow_events_stream_one_execution() {
+    let (logs_tx, _) = broadcast::channel(100);
+    let log_buffer = Arc::new(RwLock::new(Vec::with_capacity(100)));
+    let app_state = Arc::new(AppState::new(logs_tx, log_buffer));
+    let app = Router::new()
+        .route("/api/workflows", post(register_workflow_handler))
+        .route(
+            "/api/workflows/:workflow_id/execute",
+            post(execute_workflow_handler),
+        )
+        .route(
+            "/api/executions/:execution_id/events",
+            get(workflow_events_handler),
+        )
+        .with_state(app_state.clone());
+
+    // Register and start two workflows, watching the first
+    let mut started = Vec::new();
+    for name in ["Watched", "Other"] {
+        let mut definition = WorkflowDefinition::new(WorkflowId::new(), name.to_string());
+        let node = Node::new(NodeId::new(), "step".to_string());
+        let node_id = lion_core::id::NodeId::from_uuid(node.id.uuid());
+        definition.add_node(node).unwrap();
+
+        let body = send_json(
+            &app,
+            "POST",
+            "/api/workflows",
+            Body::from(definition.to_json().unwrap()),
+        )
+        .await;
+        let workflow_id = body["workflow_id"].as_str().unwrap().to_string();
+
+        let uri = format!("/api/workflows/{}/execute", workflow_id);
+        let body = send_json(&app, "POST", &uri, Body::empty()).await;
+        let execution_id = body["execution_id"].as_str().unwrap().to_string();
+        started.push((workflow_id, node_id, execution_id));
+    }
+    let (watched, watched_node, execution_id) = &started[0];
+    let (other, other_node, _) = &started[1];
+
+    let uri = format!("/api/executions/{}/events", execution_id);
+    let response = send(&app, "GET", &uri, Body::empty()).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // Complete the steps once the stream is open
+    let manager = app_state.workflows.as_ref().unwrap();
+    for (workflow_id, node_id) in [(other, other_node), (watched, watched_node)] {
+        let workflow_id = lion_core::id::WorkflowId::from_uuid(workflow_id.parse().unwrap());
+        manager
+            .complete_node(&workflow_id, node_id, serde_json::json!({}))
+            .await
+            .unwrap();
+    }
+
+    // Read events until the watched step completes
+    let mut body = response.into_body().into_data_stream();
+    let mut events = String::new();
+    while !events.contains("Completed") {
+        let chunk = tokio::time::timeout(Duration::from_secs(5), body.next())
+            .await
+            .expect("no node event received")
+            .unwrap()
+            .unwrap();
+        events.push_str(std::str::from_utf8(&chunk).unwrap());
+    }
+
+    assert!(events.contains(watched.as_str()));
+    assert!(!events.contains(other.as_str()));
+}
+
+#[tokio::test]
+async fn test_workflow_events_unknown_execution_is_not_found() {
+    let (logs_tx, _) = broadcast::channel(100);
+    let log_buffer = Arc::new(RwLock::new(Vec::with_capacity(100)));
+    let app = Router::new()
+        .route(
+            "/api/executions/:execution_id/events",
+            get(workflow_events_handler),
+        )
+        .with_state(Arc::new(AppState::new(logs_tx, log_buffer)));
+
+    let uri = format!("/api/executions/{}/events", Uuid::new_v4());
+    let response = send(&app, "GET", &uri, Body::empty()).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}